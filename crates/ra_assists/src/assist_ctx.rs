@@ -1,16 +1,17 @@
 //! This module defines `AssistCtx` -- the API surface that is exposed to assists.
-use either::Either;
-use hir::{db::HirDatabase, InFile, SourceAnalyzer, SourceBinder};
-use ra_db::FileRange;
+use std::iter::successors;
+
+use hir::{db::HirDatabase, InFile, SourceAnalyzer, SourceBinder, Type};
+use ra_db::{Edition, FileRange};
 use ra_fmt::{leading_indent, reindent};
 use ra_syntax::{
     algo::{self, find_covering_element, find_node_at_offset},
-    AstNode, SourceFile, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextUnit,
-    TokenAtOffset,
+    ast, AstNode, SourceFile, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange,
+    TextUnit, TokenAtOffset, T,
 };
 use ra_text_edit::TextEditBuilder;
 
-use crate::{AssistAction, AssistId, AssistLabel, ResolvedAssist};
+use crate::{ActionData, AssistAction, AssistId, AssistLabel, ResolvedAssist};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Assist {
@@ -93,7 +94,9 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
                 f(&mut edit);
                 edit.build()
             };
-            Assist::Resolved { assist: ResolvedAssist { label, action_data: Either::Left(action) } }
+            Assist::Resolved {
+                assist: ResolvedAssist { label, action_data: ActionData::Single(action) },
+            }
         } else {
             Assist::Unresolved { label }
         };
@@ -101,12 +104,35 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
         Some(assist)
     }
 
-    #[allow(dead_code)] // will be used for auto import assist with multiple actions
+    /// Adds an assist with several alternative actions, of which the user
+    /// picks one.
     pub(crate) fn add_assist_group(
         self,
         id: AssistId,
         label: impl Into<String>,
         f: impl FnOnce() -> Vec<ActionBuilder>,
+    ) -> Option<Assist> {
+        self.add_assist_with_actions(id, label, f, ActionData::Group)
+    }
+
+    /// Adds an assist consisting of several steps. Unlike the actions of a
+    /// group, all of the steps are applied, in order, and each one is computed
+    /// against the text produced by the previous ones.
+    pub(crate) fn add_assist_sequence(
+        self,
+        id: AssistId,
+        label: impl Into<String>,
+        f: impl FnOnce() -> Vec<ActionBuilder>,
+    ) -> Option<Assist> {
+        self.add_assist_with_actions(id, label, f, ActionData::Sequence)
+    }
+
+    fn add_assist_with_actions(
+        self,
+        id: AssistId,
+        label: impl Into<String>,
+        f: impl FnOnce() -> Vec<ActionBuilder>,
+        action_data: fn(Vec<AssistAction>) -> ActionData,
     ) -> Option<Assist> {
         let label = AssistLabel { label: label.into(), id };
         let assist = if self.should_compute_edit {
@@ -116,7 +142,7 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
             Assist::Resolved {
                 assist: ResolvedAssist {
                     label,
-                    action_data: Either::Right(
+                    action_data: action_data(
                         actions.into_iter().map(ActionBuilder::build).collect(),
                     ),
                 },
@@ -142,6 +168,39 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
     pub(crate) fn covering_element(&self) -> SyntaxElement {
         find_covering_element(self.source_file.syntax(), self.frange.range)
     }
+    /// Returns the edition of the crate containing the file, for assists
+    /// which only apply to some editions. If the file belongs to several
+    /// crates, the first one is used.
+    #[allow(dead_code)]
+    pub(crate) fn edition(&self) -> Edition {
+        let crate_graph = self.db.crate_graph();
+        match self.db.relevant_crates(self.frange.file_id).first() {
+            Some(&krate) => crate_graph.edition(krate),
+            None => Edition::Edition2018,
+        }
+    }
+    /// Whether the file has syntax errors in or touching `range`. Assists which
+    /// restructure code should decline then, as the tree there doesn't reflect
+    /// what the user wrote.
+    #[allow(dead_code)]
+    pub(crate) fn has_syntax_errors_in(&self, range: TextRange) -> bool {
+        self.source_file
+            .syntax()
+            .descendants_with_tokens()
+            .filter(|it| it.kind() == SyntaxKind::ERROR)
+            .any(|it| it.text_range().intersection(&range).is_some())
+    }
+    /// Returns the type of the innermost expression at the cursor, if it is
+    /// fully known.
+    pub(crate) fn type_of_expr_at_cursor(&self) -> Option<Type> {
+        let expr = self.find_node_at_offset::<ast::Expr>()?;
+        let offset = self.frange.range.start();
+        let ty = self.source_analyzer(expr.syntax(), Some(offset)).type_of(self.db, &expr)?;
+        if ty.contains_unknown() {
+            return None;
+        }
+        Some(ty)
+    }
     pub(crate) fn source_binder(&self) -> SourceBinder<'a, DB> {
         SourceBinder::new(self.db)
     }
@@ -157,6 +216,47 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
     pub(crate) fn covering_node_for_range(&self, range: TextRange) -> SyntaxElement {
         find_covering_element(self.source_file.syntax(), range)
     }
+    /// Returns the block which the cursor is at the end of, with only trivia
+    /// before its closing brace, for assists adding code at the end of a
+    /// scope.
+    #[allow(dead_code)]
+    pub(crate) fn at_end_of_block(&self) -> Option<ast::BlockExpr> {
+        block_ending_at(self.source_file.syntax(), self.frange.range.start())
+    }
+    /// Whether there is only trivia after the cursor.
+    #[allow(dead_code)]
+    pub(crate) fn at_end_of_file(&self) -> bool {
+        is_end_of_file(self.source_file.syntax(), self.frange.range.start())
+    }
+}
+
+/// Returns the first token after `offset` that isn't trivia, if `offset` isn't
+/// in the middle of a token.
+fn next_non_trivia(root: &SyntaxNode, offset: TextUnit) -> Option<Option<SyntaxToken>> {
+    let token = match root.token_at_offset(offset).right_biased() {
+        Some(it) => it,
+        None => return Some(None),
+    };
+    if !token.kind().is_trivia() && token.text_range().start() != offset {
+        return None;
+    }
+    Some(successors(Some(token), |it| it.next_token()).find(|it| !it.kind().is_trivia()))
+}
+
+fn block_ending_at(root: &SyntaxNode, offset: TextUnit) -> Option<ast::BlockExpr> {
+    let r_curly = next_non_trivia(root, offset)??;
+    if r_curly.kind() != T!['}'] {
+        return None;
+    }
+    let block = ast::Block::cast(r_curly.parent())?;
+    ast::BlockExpr::cast(block.syntax().parent()?)
+}
+
+fn is_end_of_file(root: &SyntaxNode, offset: TextUnit) -> bool {
+    match next_non_trivia(root, offset) {
+        Some(next) => next.is_none(),
+        None => false,
+    }
 }
 
 #[derive(Default)]
@@ -168,7 +268,6 @@ pub(crate) struct ActionBuilder {
 }
 
 impl ActionBuilder {
-    #[allow(dead_code)]
     /// Adds a custom label to the action, if it needs to be different from the assist label
     pub(crate) fn label(&mut self, label: impl Into<String>) {
         self.label = Some(label.into())
@@ -194,6 +293,21 @@ impl ActionBuilder {
         self.replace(node.text_range(), replace_with)
     }
 
+    /// Replaces `old` with `new`, parenthesizing `new` if its precedence is
+    /// too low for the place of `old`.
+    pub(crate) fn replace_expr(
+        &mut self,
+        old: &ast::Expr,
+        new: impl Into<String>,
+        new_precedence: ExprPrecedence,
+    ) {
+        let mut new = new.into();
+        if new_precedence < ExprPrecedence::required_at(old) {
+            new = format!("({})", new);
+        }
+        self.replace(old.syntax().text_range(), new)
+    }
+
     /// Remove specified `range` of text.
     #[allow(unused)]
     pub(crate) fn delete(&mut self, range: TextRange) {
@@ -236,3 +350,252 @@ impl ActionBuilder {
         }
     }
 }
+
+/// How tightly an expression binds, from the loosest to the tightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ExprPrecedence {
+    /// Closures, `return` and `break`.
+    Jump,
+    /// Blocks, `if`, `match` and loops, which end an expression statement
+    /// they start.
+    Block,
+    Assign,
+    Range,
+    Or,
+    And,
+    Compare,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Sum,
+    Product,
+    Cast,
+    /// Unary operators, references and `box`.
+    Prefix,
+    /// Calls, field accesses, indexing, `?` and `.await`.
+    Postfix,
+    Atom,
+}
+
+impl ExprPrecedence {
+    pub(crate) fn of(expr: &ast::Expr) -> ExprPrecedence {
+        match expr {
+            ast::Expr::LambdaExpr(_) | ast::Expr::ReturnExpr(_) | ast::Expr::BreakExpr(_) => {
+                ExprPrecedence::Jump
+            }
+            ast::Expr::BlockExpr(_)
+            | ast::Expr::TryBlockExpr(_)
+            | ast::Expr::IfExpr(_)
+            | ast::Expr::MatchExpr(_)
+            | ast::Expr::LoopExpr(_)
+            | ast::Expr::ForExpr(_)
+            | ast::Expr::WhileExpr(_) => ExprPrecedence::Block,
+            ast::Expr::BinExpr(it) => it.op_kind().map_or(ExprPrecedence::Jump, Self::of_bin_op),
+            ast::Expr::RangeExpr(_) => ExprPrecedence::Range,
+            ast::Expr::CastExpr(_) => ExprPrecedence::Cast,
+            ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => {
+                ExprPrecedence::Prefix
+            }
+            ast::Expr::CallExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::AwaitExpr(_)
+            | ast::Expr::TryExpr(_) => ExprPrecedence::Postfix,
+            _ => ExprPrecedence::Atom,
+        }
+    }
+
+    /// Returns the text of `expr`, parenthesized if it binds looser than
+    /// required, like for a method call receiver with `Postfix`.
+    pub(crate) fn parenthesize(self, expr: &ast::Expr) -> String {
+        if ExprPrecedence::of(expr) < self {
+            format!("({})", expr.syntax())
+        } else {
+            expr.syntax().to_string()
+        }
+    }
+
+    fn of_bin_op(op: ast::BinOp) -> ExprPrecedence {
+        use ast::BinOp::*;
+        match op {
+            BooleanOr => ExprPrecedence::Or,
+            BooleanAnd => ExprPrecedence::And,
+            EqualityTest | NegatedEqualityTest | LesserEqualTest | GreaterEqualTest
+            | LesserTest | GreaterTest => ExprPrecedence::Compare,
+            BitwiseOr => ExprPrecedence::BitOr,
+            BitwiseXor => ExprPrecedence::BitXor,
+            BitwiseAnd => ExprPrecedence::BitAnd,
+            LeftShift | RightShift => ExprPrecedence::Shift,
+            Addition | Subtraction => ExprPrecedence::Sum,
+            Multiplication | Division | Remainder => ExprPrecedence::Product,
+            Assignment | AddAssign | DivAssign | MulAssign | RemAssign | ShrAssign | ShlAssign
+            | SubAssign | BitOrAssign | BitAndAssign | BitXorAssign => ExprPrecedence::Assign,
+        }
+    }
+
+    /// The next tighter precedence.
+    fn tighter(self) -> ExprPrecedence {
+        match self {
+            ExprPrecedence::Jump => ExprPrecedence::Block,
+            ExprPrecedence::Block => ExprPrecedence::Assign,
+            ExprPrecedence::Assign => ExprPrecedence::Range,
+            ExprPrecedence::Range => ExprPrecedence::Or,
+            ExprPrecedence::Or => ExprPrecedence::And,
+            ExprPrecedence::And => ExprPrecedence::Compare,
+            ExprPrecedence::Compare => ExprPrecedence::BitOr,
+            ExprPrecedence::BitOr => ExprPrecedence::BitXor,
+            ExprPrecedence::BitXor => ExprPrecedence::BitAnd,
+            ExprPrecedence::BitAnd => ExprPrecedence::Shift,
+            ExprPrecedence::Shift => ExprPrecedence::Sum,
+            ExprPrecedence::Sum => ExprPrecedence::Product,
+            ExprPrecedence::Product => ExprPrecedence::Cast,
+            ExprPrecedence::Cast => ExprPrecedence::Prefix,
+            ExprPrecedence::Prefix => ExprPrecedence::Postfix,
+            ExprPrecedence::Postfix | ExprPrecedence::Atom => ExprPrecedence::Atom,
+        }
+    }
+
+    /// The loosest precedence an expression can have in place of `expr`
+    /// without parentheses.
+    pub(crate) fn required_at(expr: &ast::Expr) -> ExprPrecedence {
+        let node = expr.syntax();
+        let is_node = |it: Option<ast::Expr>| it.map_or(false, |it| it.syntax() == node);
+        let parent = match node.parent().and_then(ast::Expr::cast) {
+            Some(it) => it,
+            None => return ExprPrecedence::Jump,
+        };
+        match parent {
+            ast::Expr::MethodCallExpr(it) if is_node(it.expr()) => ExprPrecedence::Postfix,
+            ast::Expr::FieldExpr(it) if is_node(it.expr()) => ExprPrecedence::Postfix,
+            ast::Expr::CallExpr(it) if is_node(it.expr()) => ExprPrecedence::Postfix,
+            ast::Expr::IndexExpr(it) if is_node(it.base()) => ExprPrecedence::Postfix,
+            ast::Expr::AwaitExpr(_) | ast::Expr::TryExpr(_) => ExprPrecedence::Postfix,
+            ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => {
+                ExprPrecedence::Prefix
+            }
+            ast::Expr::CastExpr(_) => ExprPrecedence::Cast,
+            ast::Expr::RangeExpr(_) => ExprPrecedence::Range.tighter(),
+            ast::Expr::BinExpr(it) => {
+                let precedence = match it.op_kind() {
+                    Some(it) => Self::of_bin_op(it),
+                    None => return ExprPrecedence::Atom,
+                };
+                let is_lhs = is_node(it.lhs());
+                match precedence {
+                    // Assignments associate to the right.
+                    ExprPrecedence::Assign if !is_lhs => precedence,
+                    // Comparisons don't associate at all.
+                    ExprPrecedence::Compare => precedence.tighter(),
+                    _ if is_lhs => precedence,
+                    _ => precedence.tighter(),
+                }
+            }
+            _ => ExprPrecedence::Jump,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::{ast, AstNode, SourceFile};
+    use test_utils::extract_offset;
+
+    use super::{block_ending_at, is_end_of_file, ActionBuilder, ExprPrecedence};
+
+    fn check_replace_expr(
+        before: &str,
+        old: &str,
+        new: &str,
+        precedence: ExprPrecedence,
+        after: &str,
+    ) {
+        let file = SourceFile::parse(before).tree();
+        let old = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::Expr::cast)
+            .find(|it| it.syntax().text() == old)
+            .expect("expected an expression");
+        let mut builder = ActionBuilder::default();
+        builder.replace_expr(&old, new, precedence);
+        assert_eq!(builder.build().edit.apply(before), after);
+    }
+
+    #[test]
+    fn replace_expr_in_bin_expr() {
+        let sum = ExprPrecedence::Sum;
+        check_replace_expr("fn f() { a * x }", "x", "b + c", sum, "fn f() { a * (b + c) }");
+        check_replace_expr("fn f() { a - x }", "x", "b - c", sum, "fn f() { a - (b - c) }");
+        check_replace_expr("fn f() { x - a }", "x", "b - c", sum, "fn f() { b - c - a }");
+        check_replace_expr("fn f() { a < x }", "x", "b + c", sum, "fn f() { a < b + c }");
+        let compare = ExprPrecedence::Compare;
+        check_replace_expr("fn f() { x == a }", "x", "b < c", compare, "fn f() { (b < c) == a }");
+        let assign = ExprPrecedence::Assign;
+        check_replace_expr("fn f() { a = x }", "x", "b = c", assign, "fn f() { a = b = c }");
+        check_replace_expr("fn f() { x = a }", "x", "b = c", assign, "fn f() { (b = c) = a }");
+    }
+
+    #[test]
+    fn replace_expr_in_method_call() {
+        let postfix = ExprPrecedence::Postfix;
+        check_replace_expr("fn f() { x.g() }", "x", "a.b", postfix, "fn f() { a.b.g() }");
+        check_replace_expr(
+            "fn f() { x.g() }",
+            "x",
+            "-a",
+            ExprPrecedence::Prefix,
+            "fn f() { (-a).g() }",
+        );
+        check_replace_expr(
+            "fn f() { x.g(); }",
+            "x",
+            "if a { b } else { c }",
+            ExprPrecedence::Block,
+            "fn f() { (if a { b } else { c }).g(); }",
+        );
+        check_replace_expr(
+            "fn f() { a.g(x) }",
+            "x",
+            "b + c",
+            ExprPrecedence::Sum,
+            "fn f() { a.g(b + c) }",
+        );
+        check_replace_expr(
+            "fn f() { let y = x; }",
+            "x",
+            "|| 1",
+            ExprPrecedence::Jump,
+            "fn f() { let y = || 1; }",
+        );
+    }
+
+    #[test]
+    fn block_ending_at_offset() {
+        let check = |text: &str, block: Option<&str>| {
+            let (offset, text) = extract_offset(text);
+            let file = SourceFile::parse(&text).tree();
+            let actual = block_ending_at(file.syntax(), offset);
+            assert_eq!(actual.map(|it| it.syntax().to_string()).as_deref(), block);
+        };
+        check("fn f() { if c { a(); <|>\n    }\n}", Some("{ a(); \n    }"));
+        check("fn f() {\n    a(); // done\n<|>}", Some("{\n    a(); // done\n}"));
+        check("fn f() { a(); <|>b(); }", None);
+        check("fn f() { a()<|>; }", None);
+        check("struct S {<|>}", None);
+    }
+
+    #[test]
+    fn end_of_file_at_offset() {
+        let check = |text: &str, expected: bool| {
+            let (offset, text) = extract_offset(text);
+            let file = SourceFile::parse(&text).tree();
+            assert_eq!(is_end_of_file(file.syntax(), offset), expected);
+        };
+        check("fn f() {}<|>", true);
+        check("fn f() {}\n<|>\n// trailing\n", true);
+        check("fn f() {<|>}", false);
+        check("fn f() {}\nst<|>ruct S;", false);
+    }
+}
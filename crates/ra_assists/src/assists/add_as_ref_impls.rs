@@ -0,0 +1,184 @@
+use format_buf::format;
+use hir::{db::HirDatabase, InFile};
+use join_to_string::join;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner},
+    SmolStr,
+};
+
+use crate::{assist_ctx::ActionBuilder, utils::path_name, Assist, AssistCtx, AssistId};
+
+// Assist: add_as_ref_impls
+//
+// Adds `AsRef` and `AsMut` impls forwarding to the only field of a newtype.
+//
+// ```
+// struct Name(String);<|>
+// ```
+// ->
+// ```
+// struct Name(String);
+//
+// impl AsRef<String> for Name {
+//     fn as_ref(&self) -> &String {
+//         &self.0
+//     }
+// }
+// ```
+pub(crate) fn add_as_ref_impls(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    // Inside the field list, field-level assists are more relevant.
+    let field_list_range = match strukt.kind() {
+        ast::StructKind::Tuple(it) => it.syntax().text_range(),
+        ast::StructKind::Record(it) => it.syntax().text_range(),
+        ast::StructKind::Unit => return None,
+    };
+    let offset = ctx.frange.range.start();
+    if field_list_range.start() < offset && offset < field_list_range.end() {
+        return None;
+    }
+    let (field, field_ty) = match strukt.kind() {
+        ast::StructKind::Tuple(fields) => {
+            let mut fields = fields.fields();
+            let field_ty = fields.next()?.type_ref()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            (SmolStr::from("0"), field_ty)
+        }
+        ast::StructKind::Record(fields) => {
+            let mut fields = fields.fields();
+            let field = fields.next()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            (field.name()?.text().clone(), field.ascribed_type()?)
+        }
+        ast::StructKind::Unit => return None,
+    };
+    let def = ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), strukt.clone()))?;
+    if def.fields(ctx.db).len() != 1 {
+        return None;
+    }
+
+    let implemented = strukt
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| it.target_type().and_then(|it| path_name(&it)).as_ref() == Some(name.text()))
+        .filter_map(|it| path_name(&it.target_trait()?))
+        .collect::<Vec<_>>();
+    let missing = [("AsRef", "as_ref", ""), ("AsMut", "as_mut", "mut ")]
+        .iter()
+        .filter(|(trait_name, _, _)| !implemented.iter().any(|it| it.as_str() == *trait_name))
+        .cloned()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return None;
+    }
+
+    ctx.add_assist_group(AssistId("add_as_ref_impls"), "Implement AsRef/AsMut", || {
+        missing
+            .into_iter()
+            .map(|(trait_name, method, mut_kw)| {
+                let mut edit = ActionBuilder::default();
+                edit.label(format!("Implement {}<{}>", trait_name, field_ty.syntax()));
+                edit.target(strukt.syntax().text_range());
+
+                let mut buf = String::new();
+                buf.push_str("\n\nimpl");
+                if let Some(type_params) = strukt.type_param_list() {
+                    format!(buf, "{}", type_params.syntax());
+                }
+                format!(buf, " {}<{}> for {}", trait_name, field_ty.syntax(), name.text());
+                if let Some(type_params) = strukt.type_param_list() {
+                    let lifetime_params = type_params
+                        .lifetime_params()
+                        .filter_map(|it| it.lifetime_token())
+                        .map(|it| it.text().clone());
+                    let type_params = type_params
+                        .type_params()
+                        .filter_map(|it| it.name())
+                        .map(|it| it.text().clone());
+                    join(lifetime_params.chain(type_params))
+                        .surround_with("<", ">")
+                        .to_buf(&mut buf);
+                }
+                format!(
+                    buf,
+                    " {{\n    fn {}(&{}self) -> &{}{} {{\n        &{}self.{}\n    }}\n}}",
+                    method,
+                    mut_kw,
+                    mut_kw,
+                    field_ty.syntax(),
+                    mut_kw,
+                    field
+                );
+                edit.insert(strukt.syntax().text_range().end(), buf);
+                edit
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist_group, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_as_ref_impls_for_tuple_struct() {
+        check_assist_group(
+            add_as_ref_impls,
+            "struct Meters(u32);<|>",
+            &[
+                "struct Meters(u32);\n\nimpl AsRef<u32> for Meters {\n    fn as_ref(&self) -> &u32 {\n        &self.0\n    }\n}",
+                "struct Meters(u32);\n\nimpl AsMut<u32> for Meters {\n    fn as_mut(&mut self) -> &mut u32 {\n        &mut self.0\n    }\n}",
+            ],
+        );
+    }
+
+    #[test]
+    fn add_as_ref_impls_for_generic_record_struct() {
+        check_assist_group(
+            add_as_ref_impls,
+            "struct Wrapper<|><T> { items: Vec<T> }",
+            &[
+                "struct Wrapper<T> { items: Vec<T> }\n\nimpl<T> AsRef<Vec<T>> for Wrapper<T> {\n    fn as_ref(&self) -> &Vec<T> {\n        &self.items\n    }\n}",
+                "struct Wrapper<T> { items: Vec<T> }\n\nimpl<T> AsMut<Vec<T>> for Wrapper<T> {\n    fn as_mut(&mut self) -> &mut Vec<T> {\n        &mut self.items\n    }\n}",
+            ],
+        );
+    }
+
+    #[test]
+    fn add_as_ref_impls_skips_existing_impls() {
+        check_assist_group(
+            add_as_ref_impls,
+            "struct Meters(u32);<|>\nimpl AsRef<u32> for Meters {}",
+            &["struct Meters(u32);\n\nimpl AsMut<u32> for Meters {\n    fn as_mut(&mut self) -> &mut u32 {\n        &mut self.0\n    }\n}\nimpl AsRef<u32> for Meters {}"],
+        );
+        check_assist_not_applicable(
+            add_as_ref_impls,
+            "struct Meters(u32);<|>\nimpl AsRef<u32> for Meters {}\nimpl AsMut<u32> for Meters {}",
+        );
+    }
+
+    #[test]
+    fn add_as_ref_impls_target() {
+        check_assist_target(add_as_ref_impls, "struct <|>Meters(u32);", "struct Meters(u32);");
+    }
+
+    #[test]
+    fn add_as_ref_impls_not_applicable_in_field_list() {
+        check_assist_not_applicable(add_as_ref_impls, "struct Meters(<|>u32);");
+    }
+
+    #[test]
+    fn add_as_ref_impls_not_applicable_for_multiple_fields() {
+        check_assist_not_applicable(add_as_ref_impls, "struct <|>Point(u32, u32);");
+        check_assist_not_applicable(add_as_ref_impls, "struct Unit;<|>");
+    }
+}
@@ -0,0 +1,147 @@
+use either::Either;
+use hir::{db::HirDatabase, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode},
+    TextUnit,
+};
+
+use crate::{utils::has_side_effects, Assist, AssistCtx, AssistId};
+
+// Assist: add_bounds_check
+//
+// Adds the scaffold of a `debug_assert!` checking the bounds of the offset of
+// a raw pointer, before the statement computing it.
+//
+// ```
+// unsafe fn get(ptr: *const u8, i: usize) -> u8 {
+//     *ptr.<|>add(i)
+// }
+// ```
+// ->
+// ```
+// unsafe fn get(ptr: *const u8, i: usize) -> u8 {
+//     debug_assert!(i <= len, "`ptr` offset out of bounds");
+//     *ptr.add(i)
+// }
+// ```
+pub(crate) fn add_bounds_check(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let method = call.name_ref()?;
+    if method.text() != "add" && method.text() != "offset" {
+        return None;
+    }
+    let receiver = call.expr()?;
+    let mut args = call.arg_list()?.args();
+    let count = match (args.next(), args.next()) {
+        (Some(it), None) => it,
+        _ => return None,
+    };
+    if has_side_effects(count.syntax()) {
+        return None;
+    }
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    let receiver_ty = analyzer.type_of(db, &receiver)?.display(db).to_string();
+    if !receiver_ty.starts_with("*const ") && !receiver_ty.starts_with("*mut ") {
+        return None;
+    }
+
+    // The statement of the innermost block, where the assertion goes.
+    let anchor = call
+        .syntax()
+        .ancestors()
+        .find(|it| it.parent().map_or(false, |it| ast::Block::can_cast(it.kind())))?;
+    // Bindings of the statement itself, like match arms, aren't in scope yet.
+    for path_expr in count.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match analyzer.resolve_path(db, &path_expr.path()?) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let range = match local.source(db).value {
+            Either::Left(it) => it.syntax().text_range(),
+            Either::Right(it) => it.syntax().text_range(),
+        };
+        if range.is_subrange(&anchor.text_range()) {
+            return None;
+        }
+    }
+
+    ctx.add_assist(AssistId("add_bounds_check"), "Add debug_assert bounds check", |edit| {
+        let indent = leading_indent(&anchor).unwrap_or_default();
+        let offset = anchor.text_range().start();
+        let prefix = format!("debug_assert!({} <= ", count.syntax());
+        edit.target(call.syntax().text_range());
+        edit.insert(
+            offset,
+            format!(
+                "{}len, \"`{}` offset out of bounds\");\n{}",
+                prefix,
+                receiver.syntax(),
+                indent
+            ),
+        );
+        edit.set_cursor(offset + TextUnit::of_str(&prefix));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_bounds_check_in_loop() {
+        check_assist(
+            add_bounds_check,
+            r#"
+fn fill(buf: *mut u32, n: isize) {
+    for i in 0..n {
+        unsafe {
+            let p = buf.<|>offset(i * 2);
+            *p = 0;
+        }
+    }
+}
+"#,
+            r#"
+fn fill(buf: *mut u32, n: isize) {
+    for i in 0..n {
+        unsafe {
+            debug_assert!(i * 2 <= <|>len, "`buf` offset out of bounds");
+            let p = buf.offset(i * 2);
+            *p = 0;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_bounds_check_target() {
+        check_assist_target(
+            add_bounds_check,
+            "unsafe fn f(p: *const u8) -> u8 { *p.<|>add(1) }",
+            "p.add(1)",
+        );
+    }
+
+    #[test]
+    fn add_bounds_check_not_applicable() {
+        // Not a raw pointer.
+        check_assist_not_applicable(
+            add_bounds_check,
+            "struct S;\nimpl S { fn add(&self, n: usize) {} }\nfn f(s: S) { s.<|>add(1); }",
+        );
+        // The offset is computed in the statement.
+        check_assist_not_applicable(
+            add_bounds_check,
+            "unsafe fn f(p: *const u8, o: Option<usize>) -> *const u8 { match o { Some(n) => p.<|>add(n), None => p } }",
+        );
+        check_assist_not_applicable(
+            add_bounds_check,
+            "unsafe fn f(p: *const u8, mut n: usize) { let q = p.<|>add({ n += 1; n }); }",
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use format_buf::format;
+use hir::{db::HirDatabase, AssocItem, Crate, HasSource, ImplBlock, InFile, PathResolution, Trait};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_default_implementation
+//
+// Gives a required trait method a default body, so that impls can omit it.
+// If all impls share the same body, it is used as the default.
+//
+// ```
+// trait Shape {
+//     fn name(&self) -> String<|>;
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn name(&self) -> String {
+//         unimplemented!()
+//     }
+// }
+// ```
+pub(crate) fn add_default_implementation(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if fn_def.body().is_some() {
+        return None;
+    }
+    let semi = fn_def.syntax().last_token().filter(|it| it.kind() == T![;])?;
+    let trait_def = fn_def.syntax().ancestors().nth(2).and_then(ast::TraitDef::cast)?;
+
+    let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+    let body = match common_body(&ctx, &trait_def, &fn_def) {
+        Some(it) => it,
+        None => format!("{{\n{}    unimplemented!()\n{}}}", indent, indent),
+    };
+
+    ctx.add_assist(AssistId("add_default_implementation"), "Add default implementation", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        let mut buf = String::new();
+        format!(buf, " {}", body);
+        edit.replace(semi.text_range(), buf);
+        let body_start = semi.text_range().start() + TextUnit::of_char(' ');
+        match body.find("unimplemented!()") {
+            Some(offset) => edit.set_cursor(body_start + TextUnit::from_usize(offset)),
+            None => edit.set_cursor(body_start),
+        }
+    })
+}
+
+/// Returns the body shared by all the impls of the method, if there are
+/// several and they are in the current file.
+fn common_body(
+    ctx: &AssistCtx<impl HirDatabase>,
+    trait_def: &ast::TraitDef,
+    fn_def: &ast::FnDef,
+) -> Option<String> {
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let name = fn_def.name()?;
+    let params = param_names(fn_def)?;
+    let trait_ = ctx.source_binder().to_def(InFile::new(file_id, trait_def.clone()))?;
+
+    let mut bodies = Vec::new();
+    for krate in Crate::all(db) {
+        for impl_block in ImplBlock::for_trait(db, krate, trait_) {
+            let source = impl_block.source(db);
+            if source.file_id != file_id {
+                return None;
+            }
+            let method = source.value.item_list()?.impl_items().find_map(|it| match it {
+                ast::ImplItem::FnDef(it)
+                    if it.name().map_or(false, |it| it.text() == name.text()) =>
+                {
+                    Some(it)
+                }
+                _ => None,
+            })?;
+            // The body can only be reused if it refers to the parameters by
+            // the same names.
+            if param_names(&method)? != params {
+                return None;
+            }
+            let body = method.body()?;
+            // Fields and other methods of the implementing type aren't
+            // available in the trait.
+            if !refers_to_trait_items_only(ctx, trait_, impl_block, &body) {
+                return None;
+            }
+            bodies.push(body.syntax().to_string());
+        }
+    }
+    // A single impl is not a reason to make its behavior the default.
+    if bodies.len() < 2 {
+        return None;
+    }
+    let normalize = |body: &str| body.split_whitespace().collect::<Vec<_>>();
+    let (first, rest) = bodies.split_first()?;
+    if rest.iter().all(|it| normalize(it) == normalize(first)) {
+        Some(first.clone())
+    } else {
+        None
+    }
+}
+
+fn refers_to_trait_items_only(
+    ctx: &AssistCtx<impl HirDatabase>,
+    trait_: Trait,
+    impl_block: ImplBlock,
+    body: &ast::BlockExpr,
+) -> bool {
+    let db = ctx.db;
+    let items = trait_.items(db).into_iter().chain(impl_block.items(db)).collect::<Vec<_>>();
+    body.syntax().descendants().all(|node| {
+        if ast::FieldExpr::can_cast(node.kind()) || ast::MacroCall::can_cast(node.kind()) {
+            return false;
+        }
+        if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            let analyzer = ctx.source_analyzer(call.syntax(), None);
+            return match analyzer.resolve_method_call(&call) {
+                Some(it) => items.contains(&AssocItem::Function(it)),
+                None => false,
+            };
+        }
+        let path = match ast::Path::cast(node) {
+            // Only the full path is resolved, not its qualifiers.
+            Some(it) if it.syntax().parent().and_then(ast::Path::cast).is_none() => it,
+            _ => return true,
+        };
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        match analyzer.resolve_path(db, &path) {
+            Some(PathResolution::Local(_)) => true,
+            Some(PathResolution::AssocItem(it)) => items.contains(&it),
+            _ => false,
+        }
+    })
+}
+
+fn param_names(fn_def: &ast::FnDef) -> Option<Vec<String>> {
+    let params = fn_def.param_list()?.params().map(|it| it.pat().map(|it| it.syntax().to_string()));
+    params.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_unimplemented_default_implementation() {
+        check_assist(
+            add_default_implementation,
+            r#"
+trait Shape {
+    fn area(&self) -> f64;
+    fn <|>name(&self) -> String;
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> f64 { 1.0 }
+    fn name(&self) -> String { "circle".to_string() }
+}
+"#,
+            r#"
+trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String {
+        <|>unimplemented!()
+    }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> f64 { 1.0 }
+    fn name(&self) -> String { "circle".to_string() }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_common_default_implementation() {
+        check_assist(
+            add_default_implementation,
+            r#"
+trait Shape {
+    fn <|>scale(&self, by: u32) -> u32;
+}
+struct Circle;
+struct Square;
+impl Shape for Circle {
+    fn scale(&self, by: u32) -> u32 {
+        by * 2
+    }
+}
+impl Shape for Square {
+    fn scale(&self, by: u32) -> u32 { by * 2 }
+}
+"#,
+            r#"
+trait Shape {
+    fn scale(&self, by: u32) -> u32 <|>{
+        by * 2
+    }
+}
+struct Circle;
+struct Square;
+impl Shape for Circle {
+    fn scale(&self, by: u32) -> u32 {
+        by * 2
+    }
+}
+impl Shape for Square {
+    fn scale(&self, by: u32) -> u32 { by * 2 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_default_implementation_ignores_bodies_using_the_impl() {
+        check_assist(
+            add_default_implementation,
+            r#"
+trait Named {
+    fn <|>name(&self) -> String;
+}
+struct A { name: String }
+struct B { name: String }
+impl Named for A {
+    fn name(&self) -> String { self.name.clone() }
+}
+impl Named for B {
+    fn name(&self) -> String { self.name.clone() }
+}
+"#,
+            r#"
+trait Named {
+    fn name(&self) -> String {
+        <|>unimplemented!()
+    }
+}
+struct A { name: String }
+struct B { name: String }
+impl Named for A {
+    fn name(&self) -> String { self.name.clone() }
+}
+impl Named for B {
+    fn name(&self) -> String { self.name.clone() }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_default_implementation_target() {
+        check_assist_target(add_default_implementation, "trait T { fn <|>f(); }", "fn f();");
+    }
+
+    #[test]
+    fn add_default_implementation_not_applicable() {
+        check_assist_not_applicable(add_default_implementation, "trait T { fn <|>f() {} }");
+        check_assist_not_applicable(add_default_implementation, "extern \"C\" { fn <|>f(); }");
+    }
+}
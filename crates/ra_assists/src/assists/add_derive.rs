@@ -1,11 +1,10 @@
 use hir::db::HirDatabase;
 use ra_syntax::{
     ast::{self, AstNode, AttrsOwner},
-    SyntaxKind::{COMMENT, WHITESPACE},
     TextUnit,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{utils::attr_insertion_offset, Assist, AssistCtx, AssistId};
 
 // Assist: add_derive
 //
@@ -27,7 +26,7 @@ use crate::{Assist, AssistCtx, AssistId};
 // ```
 pub(crate) fn add_derive(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
-    let node_start = derive_insertion_offset(&nominal)?;
+    let node_start = attr_insertion_offset(nominal.syntax())?;
     ctx.add_assist(AssistId("add_derive"), "Add `#[derive]`", |edit| {
         let derive_attr = nominal
             .attrs()
@@ -47,15 +46,6 @@ pub(crate) fn add_derive(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     })
 }
 
-// Insert `derive` after doc comments.
-fn derive_insertion_offset(nominal: &ast::NominalDef) -> Option<TextUnit> {
-    let non_ws_child = nominal
-        .syntax()
-        .children_with_tokens()
-        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?;
-    Some(non_ws_child.text_range().start())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
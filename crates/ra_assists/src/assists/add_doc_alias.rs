@@ -0,0 +1,146 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind::WHITESPACE,
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_doc_alias
+//
+// Adds a `#[doc(alias = "...")]` attribute to an item, so that searching the
+// documentation for another name, like an old one, finds it.
+//
+// ```
+// /// Counts the elements.
+// pub fn <|>len(&self) -> usize {
+//     self.len
+// }
+// ```
+// ->
+// ```
+// /// Counts the elements.
+// #[doc(alias = "")]
+// pub fn len(&self) -> usize {
+//     self.len
+// }
+// ```
+pub(crate) fn add_doc_alias(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let item = ctx.find_node_at_offset::<ast::ModuleItem>()?;
+    let name = match &item {
+        ast::ModuleItem::StructDef(it) => it.name(),
+        ast::ModuleItem::UnionDef(it) => it.name(),
+        ast::ModuleItem::EnumDef(it) => it.name(),
+        ast::ModuleItem::FnDef(it) => it.name(),
+        ast::ModuleItem::TraitDef(it) => it.name(),
+        ast::ModuleItem::TypeAliasDef(it) => it.name(),
+        ast::ModuleItem::ConstDef(it) => it.name(),
+        ast::ModuleItem::StaticDef(it) => it.name(),
+        ast::ModuleItem::Module(it) => it.name(),
+        _ => None,
+    }?;
+    // Offered on the head of the item, not in its body.
+    if ctx.frange.range.start() > name.syntax().text_range().end() {
+        return None;
+    }
+    let attrs: Vec<ast::Attr> = match &item {
+        ast::ModuleItem::StructDef(it) => it.attrs().collect(),
+        ast::ModuleItem::UnionDef(it) => it.attrs().collect(),
+        ast::ModuleItem::EnumDef(it) => it.attrs().collect(),
+        ast::ModuleItem::FnDef(it) => it.attrs().collect(),
+        ast::ModuleItem::TraitDef(it) => it.attrs().collect(),
+        ast::ModuleItem::TypeAliasDef(it) => it.attrs().collect(),
+        ast::ModuleItem::ConstDef(it) => it.attrs().collect(),
+        ast::ModuleItem::StaticDef(it) => it.attrs().collect(),
+        ast::ModuleItem::Module(it) => it.attrs().collect(),
+        _ => return None,
+    };
+    // An alias still to be filled in.
+    let has_empty_alias = attrs.iter().any(|it| {
+        it.syntax().text().to_string().split_whitespace().collect::<String>()
+            == "#[doc(alias=\"\")]"
+    });
+    if has_empty_alias {
+        return None;
+    }
+
+    // After the doc comments, but before the other attributes.
+    let elements = item.syntax().children_with_tokens().collect::<Vec<_>>();
+    let last_doc = elements.iter().rposition(|it| {
+        it.as_token()
+            .and_then(|it| ast::Comment::cast(it.clone()))
+            .map_or(false, |it| it.kind().doc.is_some())
+    });
+    let start = match last_doc {
+        Some(idx) => {
+            elements[idx + 1..].iter().find(|it| it.kind() != WHITESPACE)?.text_range().start()
+        }
+        None => item.syntax().text_range().start(),
+    };
+    let indent = leading_indent(item.syntax()).unwrap_or_default();
+
+    ctx.add_assist(AssistId("add_doc_alias"), "Add #[doc(alias = \"...\")]", |edit| {
+        edit.target(item.syntax().text_range());
+        edit.insert(start, format!("#[doc(alias = \"\")]\n{}", indent));
+        edit.set_cursor(start + TextUnit::of_str("#[doc(alias = \""));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_doc_alias_before_attributes() {
+        check_assist(
+            add_doc_alias,
+            r#"
+impl Buffer {
+    /// The number of bytes.
+    ///
+    /// Used to be `size`.
+    #[inline]
+    pub fn len<|>(&self) -> usize {
+        self.len
+    }
+}
+"#,
+            r#"
+impl Buffer {
+    /// The number of bytes.
+    ///
+    /// Used to be `size`.
+    #[doc(alias = "<|>")]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_doc_alias_without_docs() {
+        check_assist(
+            add_doc_alias,
+            "mod m {\n    #[derive(Clone)]\n    struct <|>S;\n}",
+            "mod m {\n    #[doc(alias = \"<|>\")]\n    #[derive(Clone)]\n    struct S;\n}",
+        );
+    }
+
+    #[test]
+    fn add_doc_alias_target() {
+        check_assist_target(add_doc_alias, "const <|>N: u8 = 1;", "const N: u8 = 1;");
+    }
+
+    #[test]
+    fn add_doc_alias_not_applicable() {
+        check_assist_not_applicable(add_doc_alias, "#[doc(alias = \"\")]\nfn <|>f() {}");
+        check_assist_not_applicable(add_doc_alias, "fn f() { <|>g(); }");
+        check_assist_not_applicable(add_doc_alias, "impl <|>S {}");
+    }
+}
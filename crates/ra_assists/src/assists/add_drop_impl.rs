@@ -0,0 +1,111 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use join_to_string::join;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{
+    utils::{adt_fields, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: add_drop_impl
+//
+// Adds a stub impl of `Drop` for a struct or an enum.
+//
+// ```
+// struct Guard<|> {
+//     id: u32,
+// }
+// ```
+// ->
+// ```
+// struct Guard {
+//     id: u32,
+// }
+//
+// impl Drop for Guard {
+//     fn drop(&mut self) {
+//         unimplemented!()
+//     }
+// }
+// ```
+pub(crate) fn add_drop_impl(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?;
+    let (adt, _) = adt_fields(&ctx, &nominal)?;
+    // A `Copy` type can't have a destructor.
+    let ty = adt.ty(ctx.db);
+    if implements_trait(ctx.db, &ty, "Drop") || implements_trait(ctx.db, &ty, "Copy") {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_drop_impl"), "Implement Drop", |edit| {
+        edit.target(nominal.syntax().text_range());
+        let start_offset = nominal.syntax().text_range().end();
+        let type_params = nominal.type_param_list();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = &type_params {
+            format!(buf, "{}", type_params.syntax());
+        }
+        format!(buf, " Drop for {}", name.text());
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text().clone());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        // The impl of `Drop` has to have the same bounds as the type.
+        if let Some(where_clause) = nominal.where_clause() {
+            format!(buf, " {}", where_clause.syntax());
+        }
+        buf.push_str(" {\n    fn drop(&mut self) {\n        ");
+        edit.set_cursor(start_offset + TextUnit::of_str(&buf));
+        buf.push_str("unimplemented!()\n    }\n}");
+        edit.insert(start_offset, buf);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_drop_impl_for_struct() {
+        check_assist(
+            add_drop_impl,
+            "struct Foo {<|>}",
+            "struct Foo {}\n\nimpl Drop for Foo {\n    fn drop(&mut self) {\n        <|>unimplemented!()\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn add_drop_impl_with_bounds() {
+        check_assist(
+            add_drop_impl,
+            "enum <|>E<'a, T: Clone> where T: Eq { A(&'a T) }",
+            "enum E<'a, T: Clone> where T: Eq { A(&'a T) }\n\nimpl<'a, T: Clone> Drop for E<'a, T> where T: Eq {\n    fn drop(&mut self) {\n        <|>unimplemented!()\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn add_drop_impl_target() {
+        check_assist_target(add_drop_impl, "struct <|>Foo(u8);", "struct Foo(u8);");
+    }
+
+    #[test]
+    fn add_drop_impl_not_applicable() {
+        check_assist_not_applicable(
+            add_drop_impl,
+            "trait Drop {}\nstruct <|>Foo;\nimpl Drop for Foo { fn drop(&mut self) {} }",
+        );
+        check_assist_not_applicable(add_drop_impl, "#[derive(Clone, Copy)]\nstruct <|>Foo;");
+    }
+}
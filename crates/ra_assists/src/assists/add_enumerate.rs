@@ -0,0 +1,146 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner},
+    TextUnit,
+};
+
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: add_enumerate
+//
+// Adds an index to the loop pattern by enumerating the iterated elements.
+//
+// ```
+// fn print(names: &[String]) {
+//     for <|>name in &names {
+//         println!("{}", name);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn print(names: &[String]) {
+//     for (i, name) in names.iter().enumerate() {
+//         println!("{}", name);
+//     }
+// }
+// ```
+pub(crate) fn add_enumerate(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let for_expr = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let body = for_expr.loop_body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let pat = for_expr.pat()?;
+    if let ast::Pat::TuplePat(_) = pat {
+        return None;
+    }
+    let iterable = for_expr.iterable()?;
+    let enumerated = enumerate(&iterable)?;
+
+    // The index can't shadow a name which the body already uses.
+    let used_names = body
+        .syntax()
+        .descendants()
+        .filter_map(|it| match ast::NameRef::cast(it.clone()) {
+            Some(it) => Some(it.text().clone()),
+            None => ast::Name::cast(it).map(|it| it.text().clone()),
+        })
+        .collect::<Vec<_>>();
+    let index = ["i", "idx", "index"]
+        .iter()
+        .find(|&&it| used_names.iter().all(|name| name.as_str() != it))?;
+
+    ctx.add_assist(AssistId("add_enumerate"), "Add index with enumerate", |edit| {
+        let pat_range = pat.syntax().text_range();
+        edit.target(for_expr.syntax().text_range());
+        edit.replace(pat_range, format!("({}, {})", index, pat.syntax()));
+        edit.replace(iterable.syntax().text_range(), enumerated);
+        edit.set_cursor(pat_range.start() + TextUnit::of_char('('));
+    })
+}
+
+fn enumerate(iterable: &ast::Expr) -> Option<String> {
+    let receiver = ExprPrecedence::Postfix.parenthesize(iterable);
+    let res = match iterable {
+        ast::Expr::RefExpr(it) => {
+            let method = if it.is_mut() { "iter_mut" } else { "iter" };
+            let referent = ExprPrecedence::Postfix.parenthesize(&it.expr()?);
+            format!("{}.{}().enumerate()", referent, method)
+        }
+        ast::Expr::MethodCallExpr(it) => {
+            if it.name_ref()?.text().as_str() == "enumerate" {
+                return None;
+            }
+            // Method calls in a loop header usually already produce an
+            // iterator.
+            format!("{}.enumerate()", iterable.syntax())
+        }
+        ast::Expr::RangeExpr(_) => format!("{}.enumerate()", receiver),
+        _ => format!("{}.into_iter().enumerate()", receiver),
+    };
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_enumerate_to_borrowed_iterable() {
+        check_assist(
+            add_enumerate,
+            "fn f() { for <|>x in &v { g(x); } }",
+            "fn f() { for (<|>i, x) in v.iter().enumerate() { g(x); } }",
+        );
+        check_assist(
+            add_enumerate,
+            "fn f() { for x in &mut <|>self.v { g(x); } }",
+            "fn f() { for (<|>i, x) in self.v.iter_mut().enumerate() { g(x); } }",
+        );
+    }
+
+    #[test]
+    fn add_enumerate_to_other_iterables() {
+        check_assist(
+            add_enumerate,
+            "fn f() { for <|>Point { x, y } in points.iter().rev() {} }",
+            "fn f() { for (<|>i, Point { x, y }) in points.iter().rev().enumerate() {} }",
+        );
+        check_assist(
+            add_enumerate,
+            "fn f() { for <|>x in 1..n {} }",
+            "fn f() { for (<|>i, x) in (1..n).enumerate() {} }",
+        );
+        check_assist(
+            add_enumerate,
+            "fn f() { for <|>x in v {} }",
+            "fn f() { for (<|>i, x) in v.into_iter().enumerate() {} }",
+        );
+    }
+
+    #[test]
+    fn add_enumerate_avoids_used_names() {
+        check_assist(
+            add_enumerate,
+            "fn f() { for <|>x in &v { let i = x; } }",
+            "fn f() { for (<|>idx, x) in v.iter().enumerate() { let i = x; } }",
+        );
+    }
+
+    #[test]
+    fn add_enumerate_target() {
+        check_assist_target(add_enumerate, "fn f() { for <|>x in &v {} }", "for x in &v {}");
+    }
+
+    #[test]
+    fn add_enumerate_not_applicable() {
+        check_assist_not_applicable(add_enumerate, "fn f() { for <|>(a, b) in v {} }");
+        check_assist_not_applicable(
+            add_enumerate,
+            "fn f() { for <|>x in v.iter().enumerate() {} }",
+        );
+        check_assist_not_applicable(add_enumerate, "fn f() { for x in &v { <|>g(x); } }");
+    }
+}
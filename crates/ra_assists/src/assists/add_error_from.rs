@@ -0,0 +1,186 @@
+use hir::{db::HirDatabase, InFile};
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, TypeAscriptionOwner},
+    SyntaxNode,
+};
+
+use crate::{utils::implements_trait, Assist, AssistCtx, AssistId};
+
+// Assist: add_error_from
+//
+// Adds `#[from]` to the field of a `thiserror` error variant wrapping another
+// error, so that it converts with `?`.
+//
+// ```
+// struct IoError;
+//
+// #[derive(Debug, Error)]
+// enum Error {
+//     #[error("io error")]
+//     <|>Io(IoError),
+// }
+// ```
+// ->
+// ```
+// struct IoError;
+//
+// #[derive(Debug, Error)]
+// enum Error {
+//     #[error("io error")]
+//     Io(#[from] IoError),
+// }
+// ```
+pub(crate) fn add_error_from(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
+    let enum_def = variant.parent_enum();
+    if !derives_error(&enum_def) {
+        return None;
+    }
+    let (field, type_ref) = single_field(&variant)?;
+    if has_from(&field) {
+        return None;
+    }
+    // A second `#[from]` of the same type would conflict.
+    let variants = enum_def.variant_list()?.variants();
+    let conflicts = variants.filter(|it| *it != variant).filter_map(|it| single_field(&it)).any(
+        |(field, other)| has_from(&field) && other.syntax().text() == type_ref.syntax().text(),
+    );
+    if conflicts {
+        return None;
+    }
+
+    let file_id = ctx.frange.file_id.into();
+    let def = ctx.source_binder().to_def(InFile::new(file_id, variant.clone()))?;
+    let ty = def.fields(ctx.db).into_iter().next()?.ty(ctx.db);
+    let is_error = implements_trait(ctx.db, &ty, "Error")
+        || type_ref.syntax().text().to_string().ends_with("Error");
+    if !is_error {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_error_from"), "Add #[from]", |edit| {
+        let offset = field.text_range().start();
+        edit.target(variant.syntax().text_range());
+        edit.insert(offset, "#[from] ");
+        edit.set_cursor(offset);
+    })
+}
+
+fn derives_error(enum_def: &ast::EnumDef) -> bool {
+    enum_def.attrs().filter_map(|it| it.as_simple_call()).filter(|(name, _)| name == "derive").any(
+        |(_, tt)| {
+            tt.syntax().children_with_tokens().any(|it| match it.into_token() {
+                Some(token) => token.text() == "Error",
+                None => false,
+            })
+        },
+    )
+}
+
+/// Returns the only field of the variant, with its type.
+fn single_field(variant: &ast::EnumVariant) -> Option<(SyntaxNode, ast::TypeRef)> {
+    match variant.kind() {
+        ast::StructKind::Tuple(list) => {
+            let mut fields = list.fields();
+            match (fields.next()?, fields.next()) {
+                (it, None) => Some((it.syntax().clone(), it.type_ref()?)),
+                _ => None,
+            }
+        }
+        ast::StructKind::Record(list) => {
+            let mut fields = list.fields();
+            match (fields.next()?, fields.next()) {
+                (it, None) => Some((it.syntax().clone(), it.ascribed_type()?)),
+                _ => None,
+            }
+        }
+        ast::StructKind::Unit => None,
+    }
+}
+
+fn has_from(field: &SyntaxNode) -> bool {
+    field
+        .children()
+        .filter_map(ast::Attr::cast)
+        .any(|it| it.as_simple_atom().map_or(false, |it| it == "from"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_error_from_tuple_field() {
+        check_assist(
+            add_error_from,
+            r#"
+#[derive(thiserror::Error, Debug)]
+enum E {
+    #[error("parse")]
+    Parse(<|>ParseError),
+}
+"#,
+            r#"
+#[derive(thiserror::Error, Debug)]
+enum E {
+    #[error("parse")]
+    Parse(<|>#[from] ParseError),
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_error_from_record_field() {
+        check_assist(
+            add_error_from,
+            r#"
+trait Error {}
+struct Io;
+impl Error for Io {}
+#[derive(Error)]
+enum E {
+    <|>Io { #[source] source: Io },
+}
+"#,
+            r#"
+trait Error {}
+struct Io;
+impl Error for Io {}
+#[derive(Error)]
+enum E {
+    Io { <|>#[from] #[source] source: Io },
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_error_from_target() {
+        check_assist_target(
+            add_error_from,
+            "#[derive(Error)]\nenum E { <|>A(AError), B }",
+            "A(AError)",
+        );
+    }
+
+    #[test]
+    fn add_error_from_not_applicable() {
+        check_assist_not_applicable(add_error_from, "#[derive(Debug)]\nenum E { <|>A(AError) }");
+        check_assist_not_applicable(
+            add_error_from,
+            "#[derive(Error)]\nenum E { <|>A(#[from] AError) }",
+        );
+        check_assist_not_applicable(
+            add_error_from,
+            "#[derive(Error)]\nenum E { <|>A(AError, u32) }",
+        );
+        check_assist_not_applicable(add_error_from, "#[derive(Error)]\nenum E { <|>A(u32) }");
+        // `From<AError>` is already implemented by another variant.
+        check_assist_not_applicable(
+            add_error_from,
+            "#[derive(Error)]\nenum E { #[error] B(#[from] AError), <|>A(AError) }",
+        );
+    }
+}
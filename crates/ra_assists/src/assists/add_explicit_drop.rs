@@ -0,0 +1,107 @@
+use hir::{db::HirDatabase, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextUnit,
+};
+
+use crate::{utils::is_copy_type, Assist, AssistCtx, AssistId};
+
+// Assist: add_explicit_drop
+//
+// Drops a local variable where it is named on its own, to release it before
+// the end of its scope.
+//
+// ```
+// struct Guard;
+//
+// fn main() {
+//     let guard = Guard;
+//     guard<|>;
+//     work();
+// }
+// ```
+// ->
+// ```
+// struct Guard;
+//
+// fn main() {
+//     let guard = Guard;
+//     drop(guard);
+//     work();
+// }
+// ```
+pub(crate) fn add_explicit_drop(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let path_expr = ctx.find_node_at_offset::<ast::PathExpr>()?;
+    let stmt = ast::ExprStmt::cast(path_expr.syntax().parent()?)?;
+    let path = path_expr.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+    let local = match analyzer.resolve_path(ctx.db, &path)? {
+        PathResolution::Local(it) => it,
+        _ => return None,
+    };
+    // Dropping a copy doesn't release anything.
+    let ty = ctx.type_of_expr_at_cursor()?;
+    if local.is_self(ctx.db) || is_copy_type(ctx.db, &ty) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_explicit_drop"), "Drop explicitly here", |edit| {
+        let text = format!("drop({});", path_expr.syntax());
+        let range = stmt.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, text.clone());
+        edit.set_cursor(range.start() + TextUnit::of_str(&text));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_explicit_drop_in_nested_block() {
+        check_assist(
+            add_explicit_drop,
+            r#"
+struct Lock;
+fn f(lock: Lock) {
+    if true {
+        <|>lock;
+    }
+}
+"#,
+            r#"
+struct Lock;
+fn f(lock: Lock) {
+    if true {
+        drop(lock);<|>
+    }
+}
+"#,
+        );
+        check_assist(
+            add_explicit_drop,
+            "struct Buf;\nfn f(v: Buf) { v<|>; g(); }",
+            "struct Buf;\nfn f(v: Buf) { drop(v);<|> g(); }",
+        );
+    }
+
+    #[test]
+    fn add_explicit_drop_target() {
+        check_assist_target(add_explicit_drop, "struct S;\nfn f(s: S) { s<|>; }", "s;");
+    }
+
+    #[test]
+    fn add_explicit_drop_not_applicable() {
+        check_assist_not_applicable(add_explicit_drop, "fn f(x: u32) { x<|>; }");
+        check_assist_not_applicable(add_explicit_drop, "struct S;\nfn f(s: &S) { s<|>; }");
+        check_assist_not_applicable(add_explicit_drop, "struct S;\nfn f(s: S) { g(s<|>); }");
+        // The type isn't known.
+        check_assist_not_applicable(add_explicit_drop, "fn f(s: String) { s<|>; }");
+        check_assist_not_applicable(add_explicit_drop, "const C: u8 = 0; fn f() { C<|>; }");
+    }
+}
@@ -0,0 +1,111 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use join_to_string::join;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{
+    utils::{adt_fields, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: add_from_str_impl
+//
+// Adds a stub impl of `FromStr` for a struct or an enum, to parse it from a
+// string.
+//
+// ```
+// struct Guard<|> {
+//     id: u32,
+// }
+// ```
+// ->
+// ```
+// struct Guard {
+//     id: u32,
+// }
+//
+// impl std::str::FromStr for Guard {
+//     type Err = ();
+//
+//     fn from_str(s: &str) -> Result<Self, Self::Err> {
+//         unimplemented!()
+//     }
+// }
+// ```
+pub(crate) fn add_from_str_impl(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?;
+    let (adt, _) = adt_fields(&ctx, &nominal)?;
+    if implements_trait(ctx.db, &adt.ty(ctx.db), "FromStr") {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_from_str_impl"), "Implement FromStr", |edit| {
+        edit.target(nominal.syntax().text_range());
+        let start_offset = nominal.syntax().text_range().end();
+        let type_params = nominal.type_param_list();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = &type_params {
+            format!(buf, "{}", type_params.syntax());
+        }
+        format!(buf, " std::str::FromStr for {}", name.text());
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text().clone());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        if let Some(where_clause) = nominal.where_clause() {
+            format!(buf, " {}", where_clause.syntax());
+        }
+        buf.push_str(" {\n    type Err = ");
+        edit.set_cursor(start_offset + TextUnit::of_str(&buf));
+        buf.push_str("();\n\n    fn from_str(s: &str) -> Result<Self, Self::Err> {\n");
+        buf.push_str("        unimplemented!()\n    }\n}");
+        edit.insert(start_offset, buf);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_from_str_impl_for_enum() {
+        check_assist(
+            add_from_str_impl,
+            "enum <|>Level { Low, High }",
+            "enum Level { Low, High }\n\nimpl std::str::FromStr for Level {\n    type Err = <|>();\n\n    fn from_str(s: &str) -> Result<Self, Self::Err> {\n        unimplemented!()\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn add_from_str_impl_with_bounds() {
+        check_assist(
+            add_from_str_impl,
+            "struct <|>Id<T>(T) where T: Copy;",
+            "struct Id<T>(T) where T: Copy;\n\nimpl<T> std::str::FromStr for Id<T> where T: Copy {\n    type Err = <|>();\n\n    fn from_str(s: &str) -> Result<Self, Self::Err> {\n        unimplemented!()\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn add_from_str_impl_target() {
+        check_assist_target(add_from_str_impl, "struct <|>Foo(u8);", "struct Foo(u8);");
+    }
+
+    #[test]
+    fn add_from_str_impl_not_applicable() {
+        check_assist_not_applicable(
+            add_from_str_impl,
+            "trait FromStr {}\nstruct <|>Foo;\nimpl FromStr for Foo {}",
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use join_to_string::join;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{
+    utils::{adt_fields, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: add_into_iterator_impl
+//
+// Implements `IntoIterator` for a wrapper of a collection by delegating to
+// the collection.
+//
+// ```
+// struct Vec<T>(T);
+//
+// struct Ids<|>(Vec<u32>);
+// ```
+// ->
+// ```
+// struct Vec<T>(T);
+//
+// struct Ids(Vec<u32>);
+//
+// impl IntoIterator for Ids {
+//     type Item = u32;
+//     type IntoIter = std::vec::IntoIter<u32>;
+//
+//     fn into_iter(self) -> Self::IntoIter {
+//         self.0.into_iter()
+//     }
+// }
+// ```
+pub(crate) fn add_into_iterator_impl(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let (adt, fields) = adt_fields(&ctx, &nominal)?;
+    let field = match fields.as_slice() {
+        [it] => it,
+        _ => return None,
+    };
+    if implements_trait(ctx.db, &adt.ty(ctx.db), "IntoIterator") {
+        return None;
+    }
+    let field_ty = field.ty(ctx.db).display(ctx.db).to_string();
+    if field_ty.contains("{unknown}") {
+        return None;
+    }
+    let (collection, args) = split_type_args(&field_ty)?;
+    // Sets and maps can have a hasher as the last argument.
+    let (module, item_args) = match (collection, args.len()) {
+        ("Vec", 1) => ("std::vec", 1),
+        ("VecDeque", 1) => ("std::collections::vec_deque", 1),
+        ("LinkedList", 1) => ("std::collections::linked_list", 1),
+        ("BinaryHeap", 1) => ("std::collections::binary_heap", 1),
+        ("HashSet", 1) | ("HashSet", 2) => ("std::collections::hash_set", 1),
+        ("BTreeSet", 1) => ("std::collections::btree_set", 1),
+        ("HashMap", 2) | ("HashMap", 3) => ("std::collections::hash_map", 2),
+        ("BTreeMap", 2) => ("std::collections::btree_map", 2),
+        _ => return None,
+    };
+    let into_iter_args = args[..item_args].join(", ");
+
+    ctx.add_assist(AssistId("add_into_iterator_impl"), "Implement IntoIterator", |edit| {
+        edit.target(strukt.syntax().text_range());
+        let start_offset = strukt.syntax().text_range().end();
+        let type_params = strukt.type_param_list();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = &type_params {
+            format!(buf, "{}", type_params.syntax());
+        }
+        format!(buf, " IntoIterator for {}", name.text());
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text().clone());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        if let Some(where_clause) = strukt.where_clause() {
+            format!(buf, " {}", where_clause.syntax());
+        }
+        // Maps iterate over the pairs of keys and values.
+        if item_args == 1 {
+            format!(buf, " {{\n    type Item = {};", into_iter_args);
+        } else {
+            format!(buf, " {{\n    type Item = ({});", into_iter_args);
+        }
+        format!(buf, "\n    type IntoIter = {}::IntoIter<{}>;\n\n    ", module, into_iter_args);
+        edit.set_cursor(start_offset + TextUnit::of_str(&buf));
+        format!(
+            buf,
+            "fn into_iter(self) -> Self::IntoIter {{\n        self.{}.into_iter()\n    }}\n}}",
+            field.name(ctx.db)
+        );
+        edit.insert(start_offset, buf);
+    })
+}
+
+/// Splits `HashMap<K, Vec<V>>` into `HashMap` and `["K", "Vec<V>"]`.
+fn split_type_args(ty: &str) -> Option<(&str, Vec<&str>)> {
+    let start = ty.find('<')?;
+    if !ty.ends_with('>') {
+        return None;
+    }
+    let inner = &ty[start + 1..ty.len() - 1];
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut arg_start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(inner[arg_start..i].trim());
+                arg_start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    args.push(inner[arg_start..].trim());
+    Some((&ty[..start], args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_into_iterator_impl_for_generic_struct() {
+        check_assist(
+            add_into_iterator_impl,
+            r#"
+struct HashMap<K, V>(K, V);
+struct Index<T: Eq> {
+    entries<|>: HashMap<T, (u8, bool)>,
+}
+"#,
+            r#"
+struct HashMap<K, V>(K, V);
+struct Index<T: Eq> {
+    entries: HashMap<T, (u8, bool)>,
+}
+
+impl<T: Eq> IntoIterator for Index<T> {
+    type Item = (T, (u8, bool));
+    type IntoIter = std::collections::hash_map::IntoIter<T, (u8, bool)>;
+
+    <|>fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_into_iterator_impl_target() {
+        check_assist_target(
+            add_into_iterator_impl,
+            "struct Vec<T>(T);\nstruct Ids<|>(Vec<u32>);",
+            "struct Ids(Vec<u32>);",
+        );
+    }
+
+    #[test]
+    fn add_into_iterator_impl_not_applicable() {
+        // Not a collection.
+        check_assist_not_applicable(
+            add_into_iterator_impl,
+            "struct Option<T>(T);\nstruct Ids<|>(Option<u32>);",
+        );
+        // More than one field.
+        check_assist_not_applicable(
+            add_into_iterator_impl,
+            "struct Vec<T>(T);\nstruct Ids<|>(Vec<u32>, u8);",
+        );
+        // Already implemented.
+        check_assist_not_applicable(
+            add_into_iterator_impl,
+            r#"
+struct Vec<T>(T);
+struct Ids<|>(Vec<u32>);
+impl IntoIterator for Ids {}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_nested_type_args() {
+        assert_eq!(
+            split_type_args("HashMap<K, Vec<(u8, V)>>"),
+            Some(("HashMap", vec!["K", "Vec<(u8, V)>"]))
+        );
+        assert_eq!(split_type_args("u32"), None);
+    }
+}
@@ -0,0 +1,227 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_partial_cmp
+//
+// Implements `partial_cmp` by comparing the fields in declaration order, the
+// same way `#[derive(PartialOrd)]` does. For enums only the variants are
+// compared.
+//
+// ```
+// struct Version { major: u32, minor: u32 }
+//
+// impl PartialOrd for Version {<|>}
+// ```
+// ->
+// ```
+// struct Version { major: u32, minor: u32 }
+//
+// impl PartialOrd for Version {
+//     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+//         match self.major.partial_cmp(&other.major) {
+//             Some(std::cmp::Ordering::Equal) => {}
+//             ord => return ord,
+//         }
+//         self.minor.partial_cmp(&other.minor)
+//     }
+// }
+// ```
+pub(crate) fn add_partial_cmp(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    let trait_name = match impl_block.target_trait()? {
+        ast::TypeRef::PathType(it) => it.path()?.segment()?.name_ref()?,
+        _ => return None,
+    };
+    if trait_name.text().as_str() != "PartialOrd" {
+        return None;
+    }
+    let item_list = impl_block.item_list()?;
+    let is_implemented = item_list.impl_items().any(|it| match it {
+        ast::ImplItem::FnDef(it) => {
+            it.name().map_or(false, |it| it.text().as_str() == "partial_cmp")
+        }
+        _ => false,
+    });
+    if is_implemented {
+        return None;
+    }
+    let target_path = match impl_block.target_type()? {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(impl_block.syntax(), None);
+    let body = match analyzer.resolve_path(db, &target_path)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Struct(strukt))) => {
+            let fields = strukt.fields(db).iter().map(|it| it.name(db).to_string()).collect();
+            compare_fields(fields)
+        }
+        PathResolution::Def(ModuleDef::Adt(Adt::Enum(enum_))) => {
+            let name = enum_.name(db).to_string();
+            let variants = enum_.variants(db).iter().map(|it| it.name(db).to_string()).collect();
+            compare_variants(&name, variants)
+        }
+        _ => return None,
+    };
+    let l_curly = item_list.syntax().first_token().filter(|it| it.kind() == T!['{'])?;
+
+    ctx.add_assist(AssistId("add_partial_cmp"), "Generate partial_cmp from fields", |edit| {
+        edit.target(impl_block.syntax().text_range());
+        let mut buf = String::new();
+        buf.push_str("\n    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {\n");
+        buf.push_str(&body);
+        buf.push_str("    }");
+        if item_list.impl_items().next().is_none() && !item_list.syntax().text().contains_char('\n')
+        {
+            buf.push('\n');
+        }
+        edit.insert(l_curly.text_range().end(), buf);
+        edit.set_cursor(l_curly.text_range().end() + TextUnit::of_str("\n    "));
+    })
+}
+
+fn compare_fields(fields: Vec<String>) -> String {
+    let mut buf = String::new();
+    let (last, rest) = match fields.split_last() {
+        Some(it) => it,
+        None => return "        Some(std::cmp::Ordering::Equal)\n".to_string(),
+    };
+    for field in rest {
+        format!(
+            buf,
+            "        match self.{0}.partial_cmp(&other.{0}) {{\n            Some(std::cmp::Ordering::Equal) => {{}}\n            ord => return ord,\n        }}\n",
+            field
+        );
+    }
+    format!(buf, "        self.{0}.partial_cmp(&other.{0})\n", last);
+    buf
+}
+
+fn compare_variants(name: &str, variants: Vec<String>) -> String {
+    let mut buf = String::new();
+    buf.push_str("        let discriminant = |it: &Self| match it {\n");
+    for (idx, variant) in variants.iter().enumerate() {
+        format!(buf, "            {}::{} {{ .. }} => {},\n", name, variant, idx);
+    }
+    buf.push_str("        };\n");
+    buf.push_str("        discriminant(self).partial_cmp(&discriminant(other))\n");
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_partial_cmp_for_record_struct() {
+        check_assist(
+            add_partial_cmp,
+            r#"
+struct Version { major: u32, minor: u32, patch: u32 }
+
+impl PartialOrd for Version {<|>}
+"#,
+            r#"
+struct Version { major: u32, minor: u32, patch: u32 }
+
+impl PartialOrd for Version {
+    <|>fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.major.partial_cmp(&other.major) {
+            Some(std::cmp::Ordering::Equal) => {}
+            ord => return ord,
+        }
+        match self.minor.partial_cmp(&other.minor) {
+            Some(std::cmp::Ordering::Equal) => {}
+            ord => return ord,
+        }
+        self.patch.partial_cmp(&other.patch)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_partial_cmp_for_tuple_struct() {
+        check_assist(
+            add_partial_cmp,
+            r#"
+struct Meters(f64);
+
+impl PartialOrd for Meters {
+<|>}
+"#,
+            r#"
+struct Meters(f64);
+
+impl PartialOrd for Meters {
+    <|>fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_partial_cmp_for_enum() {
+        check_assist(
+            add_partial_cmp,
+            r#"
+enum Level { Low, Custom(u8), High { boost: bool } }
+
+impl PartialOrd for Level {<|>}
+"#,
+            r#"
+enum Level { Low, Custom(u8), High { boost: bool } }
+
+impl PartialOrd for Level {
+    <|>fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let discriminant = |it: &Self| match it {
+            Level::Low { .. } => 0,
+            Level::Custom { .. } => 1,
+            Level::High { .. } => 2,
+        };
+        discriminant(self).partial_cmp(&discriminant(other))
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_partial_cmp_target() {
+        check_assist_target(
+            add_partial_cmp,
+            "struct S;\nimpl PartialOrd for S {<|>}",
+            "impl PartialOrd for S {}",
+        );
+    }
+
+    #[test]
+    fn add_partial_cmp_not_applicable_if_implemented() {
+        check_assist_not_applicable(
+            add_partial_cmp,
+            r#"
+struct S;
+impl PartialOrd for S {<|>
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { None }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_partial_cmp_not_applicable_for_other_traits() {
+        check_assist_not_applicable(add_partial_cmp, "struct S;\nimpl Ord for S {<|>}");
+        check_assist_not_applicable(add_partial_cmp, "struct S;\nimpl S {<|>}");
+    }
+}
@@ -0,0 +1,268 @@
+use hir::{db::HirDatabase, Adt, InFile, ModuleDef, PathResolution, VariantDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeParamsOwner},
+    SyntaxNode, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_phantom_data
+//
+// Adds a `PhantomData` field for the type parameters a struct doesn't use,
+// and to the places where it is constructed.
+//
+// ```
+// struct Id<T> { <|>raw: u64 }
+//
+// fn id<T>(raw: u64) -> Id<T> {
+//     Id { raw }
+// }
+// ```
+// ->
+// ```
+// struct Id<T> { raw: u64, _marker: std::marker::PhantomData<T> }
+//
+// fn id<T>(raw: u64) -> Id<T> {
+//     Id { raw, _marker: std::marker::PhantomData }
+// }
+// ```
+pub(crate) fn add_phantom_data(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let field_types: Vec<_> = match strukt.kind() {
+        ast::StructKind::Record(it) => it.fields().filter_map(|it| it.ascribed_type()).collect(),
+        ast::StructKind::Tuple(it) => it.fields().filter_map(|it| it.type_ref()).collect(),
+        ast::StructKind::Unit => return None,
+    };
+    let unused = strukt
+        .type_param_list()?
+        .type_params()
+        .filter_map(|it| it.name())
+        .filter(|name| !is_used(&field_types, name.text()))
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let marker_ty = match unused.as_slice() {
+        [] => return None,
+        [it] => format!("std::marker::PhantomData<{}>", it),
+        _ => format!("std::marker::PhantomData<({})>", unused.join(", ")),
+    };
+
+    let file_id = ctx.frange.file_id.into();
+    let def = ctx.source_binder().to_def(InFile::new(file_id, strukt.clone()))?;
+    let file = strukt.syntax().ancestors().last()?;
+    let mut edits = Vec::new();
+    match strukt.kind() {
+        ast::StructKind::Record(fields) => {
+            if fields.fields().any(|it| it.name().map_or(false, |it| it.text() == "_marker")) {
+                return None;
+            }
+            edits.push(append(
+                fields.syntax(),
+                fields.fields().last().map(|it| it.syntax().clone()),
+                &format!("_marker: {}", marker_ty),
+            )?);
+            for record_lit in file.descendants().filter_map(ast::RecordLit::cast) {
+                let analyzer = ctx.source_analyzer(record_lit.syntax(), None);
+                if analyzer.resolve_record_literal(&record_lit) != Some(VariantDef::Struct(def)) {
+                    continue;
+                }
+                let field_list = record_lit.record_field_list()?;
+                if field_list.spread().is_some() {
+                    continue;
+                }
+                edits.push(append(
+                    field_list.syntax(),
+                    field_list.fields().last().map(|it| it.syntax().clone()),
+                    "_marker: std::marker::PhantomData",
+                )?);
+            }
+            // Patterns listing every field would have to list the new one.
+            for record_pat in file.descendants().filter_map(ast::RecordPat::cast) {
+                let analyzer = ctx.source_analyzer(record_pat.syntax(), None);
+                if analyzer.resolve_record_pattern(&record_pat) != Some(VariantDef::Struct(def)) {
+                    continue;
+                }
+                let field_pats = record_pat.record_field_pat_list()?;
+                if !field_pats.syntax().children_with_tokens().any(|it| it.kind() == T![..]) {
+                    return None;
+                }
+            }
+        }
+        ast::StructKind::Tuple(fields) => {
+            edits.push(append(
+                fields.syntax(),
+                fields.fields().last().map(|it| it.syntax().clone()),
+                &marker_ty,
+            )?);
+            let resolves_to_struct = |node: &SyntaxNode, path: &ast::Path| {
+                let analyzer = ctx.source_analyzer(node, None);
+                match analyzer.resolve_path(ctx.db, path) {
+                    Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(it)))) => it == def,
+                    Some(PathResolution::SelfType(it)) => {
+                        it.target_ty(ctx.db).as_adt() == Some(Adt::Struct(def))
+                    }
+                    _ => false,
+                }
+            };
+            for call in file.descendants().filter_map(ast::CallExpr::cast) {
+                let path = match call.expr() {
+                    Some(ast::Expr::PathExpr(it)) => it.path(),
+                    _ => None,
+                };
+                if !path.map_or(false, |it| resolves_to_struct(call.syntax(), &it)) {
+                    continue;
+                }
+                let arg_list = call.arg_list()?;
+                edits.push(append(
+                    arg_list.syntax(),
+                    arg_list.args().last().map(|it| it.syntax().clone()),
+                    "std::marker::PhantomData",
+                )?);
+            }
+            for pat in file.descendants().filter_map(ast::TupleStructPat::cast) {
+                if !pat.path().map_or(false, |it| resolves_to_struct(pat.syntax(), &it)) {
+                    continue;
+                }
+                if !pat.args().any(|it| ast::DotDotPat::can_cast(it.syntax().kind())) {
+                    return None;
+                }
+            }
+        }
+        ast::StructKind::Unit => return None,
+    }
+
+    ctx.add_assist(AssistId("add_phantom_data"), "Add PhantomData field", |edit| {
+        edit.target(strukt.syntax().text_range());
+        for (offset, text) in edits {
+            edit.insert(offset, text);
+        }
+    })
+}
+
+/// Whether the type parameter is named in one of the types.
+fn is_used(types: &[ast::TypeRef], name: &str) -> bool {
+    types.iter().flat_map(|it| it.syntax().descendants()).filter_map(ast::Path::cast).any(|it| {
+        it.qualifier().is_none()
+            && it.segment().and_then(|it| it.name_ref()).map_or(false, |it| it.text() == name)
+    })
+}
+
+/// The insertion of `item` at the end of a list of fields or arguments,
+/// keeping a layout of one per line.
+fn append(list: &SyntaxNode, last: Option<SyntaxNode>, item: &str) -> Option<(TextUnit, String)> {
+    let last = match last {
+        Some(it) => it,
+        None => {
+            let open = list.first_token()?;
+            let text =
+                if open.kind() == T!['{'] { format!(" {} ", item) } else { item.to_string() };
+            return Some((open.text_range().end(), text));
+        }
+    };
+    let mut buf = String::from(",");
+    match leading_indent(&last) {
+        Some(indent) if list.text().contains_char('\n') => {
+            buf.push('\n');
+            buf.push_str(&indent);
+        }
+        _ => buf.push(' '),
+    }
+    buf.push_str(item);
+    Some((last.text_range().end(), buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_phantom_data_record() {
+        check_assist(
+            add_phantom_data,
+            r#"
+struct <|>Handle<'a, T, U: Clone, V> {
+    name: &'a str,
+    value: Vec<V>,
+}
+
+impl<'a, T, U: Clone, V> Handle<'a, T, U, V> {
+    fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            value: Vec::new(),
+        }
+    }
+    fn with(&self, name: &'a str) -> Self {
+        Handle { name, ..self.clone() }
+    }
+}
+"#,
+            r#"
+struct <|>Handle<'a, T, U: Clone, V> {
+    name: &'a str,
+    value: Vec<V>,
+    _marker: std::marker::PhantomData<(T, U)>,
+}
+
+impl<'a, T, U: Clone, V> Handle<'a, T, U, V> {
+    fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            value: Vec::new(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+    fn with(&self, name: &'a str) -> Self {
+        Handle { name, ..self.clone() }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_phantom_data_tuple() {
+        check_assist(
+            add_phantom_data,
+            r#"
+struct Key<T><|>(u32);
+struct Empty<T>();
+fn f() -> Key<u8> {
+    let Key(..) = Key(1);
+    Key(2)
+}
+"#,
+            r#"
+struct Key<T><|>(u32, std::marker::PhantomData<T>);
+struct Empty<T>();
+fn f() -> Key<u8> {
+    let Key(..) = Key(1, std::marker::PhantomData);
+    Key(2, std::marker::PhantomData)
+}
+"#,
+        );
+        check_assist(
+            add_phantom_data,
+            "struct Empty<T><|>(); fn f() { Empty(); }",
+            "struct Empty<T><|>(std::marker::PhantomData<T>); fn f() { Empty(std::marker::PhantomData); }",
+        );
+    }
+
+    #[test]
+    fn add_phantom_data_target() {
+        check_assist_target(add_phantom_data, "struct S<T> { <|>x: u8 }", "struct S<T> { x: u8 }");
+    }
+
+    #[test]
+    fn add_phantom_data_not_applicable() {
+        check_assist_not_applicable(add_phantom_data, "struct S<T> { <|>x: Option<T> }");
+        check_assist_not_applicable(add_phantom_data, "struct S<T: Iterator> { <|>x: T::Item }");
+        check_assist_not_applicable(add_phantom_data, "struct S<T>;<|>");
+        // The pattern would miss the new field.
+        check_assist_not_applicable(
+            add_phantom_data,
+            "struct S<T> { <|>x: u8 }\nfn f<T>(s: S<T>) { let S { x } = s; }",
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use hir::{db::HirDatabase, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_tracing_span
+//
+// Enters a `tracing` span named after the function for the duration of its
+// body.
+//
+// ```
+// mod tracing {}
+//
+// fn <|>handle(request: Request) {
+//     respond(request);
+// }
+// ```
+// ->
+// ```
+// mod tracing {}
+//
+// fn handle(request: Request) {
+//     let _span = tracing::info_span!("handle").entered();
+//     respond(request);
+// }
+// ```
+pub(crate) fn add_tracing_span(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let name = fn_def.name()?;
+    let block = fn_def.body()?.block()?;
+    if ctx.frange.range.start() >= block.syntax().text_range().start() {
+        return None;
+    }
+    let is_instrumented = fn_def.attrs().any(|it| {
+        it.path().map_or(false, |it| it.syntax().text().to_string().ends_with("instrument"))
+    });
+    let has_span = block
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .filter_map(|it| it.path()?.segment()?.name_ref())
+        .any(|it| it.text().ends_with("span"));
+    if is_instrumented || has_span {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(fn_def.syntax(), None);
+    let tracing = make::path_from_name_ref(make::name_ref("tracing"));
+    match analyzer.resolve_path(ctx.db, &tracing) {
+        Some(PathResolution::Def(ModuleDef::Module(_))) => (),
+        _ => return None,
+    }
+    let l_curly = block.syntax().first_token().filter(|it| it.kind() == T!['{'])?;
+
+    ctx.add_assist(AssistId("add_tracing_span"), "Add tracing span (scaffold)", |edit| {
+        let indent = " ".repeat(IndentLevel::from_node(fn_def.syntax()).0 as usize * 4);
+        let span =
+            format!("\n{}    let _span = tracing::info_span!(\"{}\").entered();", indent, name);
+        let start = l_curly.text_range().end();
+        edit.target(fn_def.syntax().text_range());
+        match l_curly.next_token() {
+            Some(ws) if ws.kind() == WHITESPACE && ws.text().contains('\n') => {
+                edit.insert(start, span);
+            }
+            // The body isn't on lines of its own.
+            next => {
+                let next = next.filter(|it| it.kind() == WHITESPACE);
+                let end = next.map_or(start, |it| it.text_range().end());
+                let is_empty = end == block.syntax().text_range().end() - TextUnit::of_char('}');
+                let rest_indent = if is_empty { indent } else { format!("{}    ", indent) };
+                edit.replace(TextRange::from_to(start, end), format!("{}\n{}", span, rest_indent));
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_in_fixture, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn add_tracing_span_with_dependency() {
+        check_assist_in_fixture(
+            add_tracing_span,
+            r#"
+//- /main.rs crate:main deps:tracing
+struct S;
+impl S {
+    pub fn <|>run(&self) {
+        self.step();
+    }
+}
+//- /tracing.rs crate:tracing
+pub struct Span;
+"#,
+            r#"struct S;
+impl S {
+    pub fn <|>run(&self) {
+        let _span = tracing::info_span!("run").entered();
+        self.step();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_tracing_span_to_single_line_body() {
+        check_assist(
+            add_tracing_span,
+            "mod tracing {}\nfn <|>f() { g(); }",
+            "mod tracing {}\nfn <|>f() {\n    let _span = tracing::info_span!(\"f\").entered();\n    g(); }",
+        );
+        check_assist(
+            add_tracing_span,
+            "mod tracing {}\nfn <|>f() {}",
+            "mod tracing {}\nfn <|>f() {\n    let _span = tracing::info_span!(\"f\").entered();\n}",
+        );
+    }
+
+    #[test]
+    fn add_tracing_span_target() {
+        check_assist_target(add_tracing_span, "mod tracing {}\nfn <|>f() {}", "fn f() {}");
+    }
+
+    #[test]
+    fn add_tracing_span_not_applicable() {
+        // No tracing.
+        check_assist_not_applicable(add_tracing_span, "fn <|>f() {\n    g();\n}");
+        for before in &[
+            "#[tracing::instrument]\nfn <|>f() {\n    g();\n}",
+            "fn <|>f() {\n    let span = debug_span!(\"f\");\n    g();\n}",
+            "fn f() {\n    <|>g();\n}",
+        ] {
+            check_assist_not_applicable(add_tracing_span, &format!("mod tracing {{}}\n{}", before));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use hir::{db::HirDatabase, Adt};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner},
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
+    SyntaxNode,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_track_caller
+//
+// Adds `#[track_caller]` to a function which can panic, so that the panic
+// message points to the caller.
+//
+// ```
+// /// Returns the first byte.
+// fn <|>first(bytes: &[u8]) -> u8 {
+//     assert!(!bytes.is_empty());
+//     bytes[0]
+// }
+// ```
+// ->
+// ```
+// /// Returns the first byte.
+// #[track_caller]
+// fn first(bytes: &[u8]) -> u8 {
+//     assert!(!bytes.is_empty());
+//     bytes[0]
+// }
+// ```
+pub(crate) fn add_track_caller(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    if fn_def.has_atom_attr("track_caller") {
+        return None;
+    }
+    if !can_panic(&ctx, &fn_def) {
+        return None;
+    }
+
+    // The attribute goes after the doc comments and other attributes.
+    let start = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| match it.kind() {
+            ATTR | COMMENT | WHITESPACE => false,
+            _ => true,
+        })?
+        .text_range()
+        .start();
+    let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+
+    ctx.add_assist(AssistId("add_track_caller"), "Add #[track_caller]", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        edit.insert(start, format!("#[track_caller]\n{}", indent));
+    })
+}
+
+/// Whether the body of the function itself panics on a failed assertion or an
+/// `unwrap` or `expect`.
+fn can_panic(ctx: &AssistCtx<impl HirDatabase>, fn_def: &ast::FnDef) -> bool {
+    let analyzer = ctx.source_analyzer(fn_def.syntax(), None);
+    let is_own = |node: &SyntaxNode| {
+        node.ancestors()
+            .find(|it| ast::FnDef::can_cast(it.kind()) || ast::LambdaExpr::can_cast(it.kind()))
+            .map_or(false, |it| &it == fn_def.syntax())
+    };
+    fn_def.syntax().descendants().filter(|it| is_own(it)).any(|node| {
+        if let Some(macro_call) = ast::MacroCall::cast(node.clone()) {
+            let name = macro_call.path().map(|it| it.syntax().to_string()).unwrap_or_default();
+            return match name.as_str() {
+                "panic" | "assert" | "assert_eq" | "assert_ne" | "unreachable" | "todo"
+                | "unimplemented" => true,
+                _ => false,
+            };
+        }
+        let call = match ast::MethodCallExpr::cast(node) {
+            Some(it) => it,
+            None => return false,
+        };
+        match call.name_ref() {
+            Some(it) if it.text().as_str() == "unwrap" || it.text().as_str() == "expect" => (),
+            _ => return false,
+        }
+        // Only the methods of `Option` and `Result` are known to panic.
+        let receiver_ty = call.expr().and_then(|it| analyzer.type_of(ctx.db, &it));
+        match receiver_ty.as_ref().and_then(|it| it.as_adt()) {
+            Some(Adt::Enum(it)) => {
+                let name = it.name(ctx.db).to_string();
+                name == "Option" || name == "Result"
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_track_caller_after_attributes() {
+        check_assist(
+            add_track_caller,
+            r#"
+enum Option<T> { Some(T), None }
+impl S {
+    /// Docs.
+    #[inline]
+    pub fn <|>get(&self, x: Option<u32>) -> u32 {
+        x.unwrap()
+    }
+}
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+impl S {
+    /// Docs.
+    #[inline]
+    #[track_caller]
+    pub fn <|>get(&self, x: Option<u32>) -> u32 {
+        x.unwrap()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_track_caller_target() {
+        check_assist_target(add_track_caller, "fn <|>f() { panic!() }", "fn f() { panic!() }");
+    }
+
+    #[test]
+    fn add_track_caller_not_applicable() {
+        // Already there.
+        check_assist_not_applicable(add_track_caller, "#[track_caller]\nfn <|>f() { panic!() }");
+        // Panicking in a closure.
+        check_assist_not_applicable(add_track_caller, "fn <|>f() { let g = || panic!(); }");
+        // Some other `unwrap`.
+        check_assist_not_applicable(
+            add_track_caller,
+            "struct S; impl S { fn unwrap(self) {} }\nfn <|>f(s: S) { s.unwrap() }",
+        );
+    }
+}
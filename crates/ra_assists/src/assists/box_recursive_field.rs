@@ -0,0 +1,449 @@
+use hir::{db::HirDatabase, InFile, VariantDef};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::IDENT,
+    TextRange,
+};
+
+use crate::{
+    utils::{field_at_offset, FieldRef, FieldUsages},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: box_recursive_field
+//
+// Wraps the type of a field which contains its own type in `Box`, updating
+// the places where the field is initialized and read.
+//
+// ```
+// struct Node {
+//     value: u32,
+//     next: <|>Node,
+// }
+//
+// fn make(value: u32, next: Node) -> Node {
+//     Node { value, next }
+// }
+// ```
+// ->
+// ```
+// struct Node {
+//     value: u32,
+//     next: Box<Node>,
+// }
+//
+// fn make(value: u32, next: Node) -> Node {
+//     Node { value, next: Box::new(next) }
+// }
+// ```
+pub(crate) fn box_recursive_field(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (field, type_ref, field_syntax) = field_at_offset(&ctx)?;
+
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let (type_name, variant): (_, VariantDef) =
+        match field_syntax.ancestors().find_map(ast::EnumVariant::cast) {
+            Some(variant) => {
+                let enum_def = variant.syntax().ancestors().find_map(ast::EnumDef::cast)?;
+                (enum_def.name()?, sb.to_def(InFile::new(file_id, variant))?.into())
+            }
+            None => {
+                let strukt = field_syntax.ancestors().find_map(ast::StructDef::cast)?;
+                (strukt.name()?, sb.to_def(InFile::new(file_id, strukt))?.into())
+            }
+        };
+    if !is_type(&type_ref, type_name.text().as_str()) {
+        return None;
+    }
+
+    let usages = FieldUsages::find(&ctx, &field, &field_syntax, variant)?;
+
+    ctx.add_assist(AssistId("box_recursive_field"), "Box recursive field", |edit| {
+        let range = type_ref.syntax().text_range();
+        edit.target(field_syntax.text_range());
+        edit.replace(range, format!("Box<{}>", type_ref.syntax()));
+        for expr in usages.initializers {
+            edit.replace(expr.syntax().text_range(), format!("Box::new({})", expr.syntax()));
+        }
+        for name_ref in usages.shorthands {
+            let name = name_ref.text();
+            edit.insert(name_ref.syntax().text_range().end(), format!(": Box::new({})", name));
+        }
+        for access in usages.reads {
+            edit.insert(access.syntax().text_range().start(), "*");
+        }
+        edit.set_cursor(range.start());
+    })
+}
+
+// Assist: box_recursive_variant
+//
+// Wraps the fields of an enum variant which contain the enum itself in `Box`,
+// updating the constructions of the variant and the bindings of the fields.
+//
+// ```
+// enum Expr {
+//     Lit(i64),
+//     <|>Neg(Expr),
+// }
+//
+// fn eval(expr: Expr) -> i64 {
+//     match expr {
+//         Expr::Lit(n) => n,
+//         Expr::Neg(inner) => -eval(inner),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Expr {
+//     Lit(i64),
+//     Neg(Box<Expr>),
+// }
+//
+// fn eval(expr: Expr) -> i64 {
+//     match expr {
+//         Expr::Lit(n) => n,
+//         Expr::Neg(inner) => -eval(*inner),
+//     }
+// }
+// ```
+pub(crate) fn box_recursive_variant(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
+    let enum_name = variant.syntax().ancestors().find_map(ast::EnumDef::cast)?.name()?;
+    let fields = match variant.kind() {
+        ast::StructKind::Record(it) => it
+            .fields()
+            .map(|it| {
+                let name = FieldRef::Named(it.name()?.text().to_string());
+                Some((name, it.ascribed_type()?, it.syntax().clone()))
+            })
+            .collect::<Option<Vec<_>>>()?,
+        ast::StructKind::Tuple(it) => it
+            .fields()
+            .enumerate()
+            .map(|(idx, it)| Some((FieldRef::Positional(idx), it.type_ref()?, it.syntax().clone())))
+            .collect::<Option<Vec<_>>>()?,
+        ast::StructKind::Unit => return None,
+    };
+    let fields = fields
+        .into_iter()
+        .filter(|(_, type_ref, _)| is_type(type_ref, enum_name.text().as_str()))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let file_id = ctx.frange.file_id.into();
+    let def: VariantDef = ctx.source_binder().to_def(InFile::new(file_id, variant.clone()))?.into();
+
+    let mut replacements = Vec::new();
+    let mut usages = Vec::new();
+    let mut derefs = Vec::new();
+    for (field, type_ref, field_syntax) in fields.iter() {
+        let range = type_ref.syntax().text_range();
+        replacements.push((range, format!("Box<{}>", type_ref.syntax())));
+        let field_usages = FieldUsages::find_with_patterns(&ctx, field, field_syntax, def)?;
+        for pat in field_usages.patterns.iter() {
+            derefs.extend(boxed_binding_derefs(&ctx, pat)?);
+        }
+        usages.push(field_usages);
+    }
+    // A box moved out of the variant can be moved into it again.
+    let mut is_moved_back = |range: TextRange| match derefs.iter().position(|it| *it == range) {
+        Some(idx) => {
+            derefs.remove(idx);
+            true
+        }
+        None => false,
+    };
+    let mut inserts = Vec::new();
+    for usages in usages {
+        for expr in usages.initializers {
+            let range = expr.syntax().text_range();
+            if !is_moved_back(range) {
+                replacements.push((range, format!("Box::new({})", expr.syntax())));
+            }
+        }
+        for name_ref in usages.shorthands {
+            let range = name_ref.syntax().text_range();
+            if !is_moved_back(range) {
+                inserts.push((range.end(), format!(": Box::new({})", name_ref.text())));
+            }
+        }
+    }
+    for range in derefs {
+        // Only paths can be dereferenced, not shorthand fields.
+        let is_path = ctx
+            .covering_node_for_range(range)
+            .ancestors()
+            .find_map(ast::PathExpr::cast)
+            .map_or(false, |it| it.syntax().text_range() == range);
+        if !is_path {
+            return None;
+        }
+        inserts.push((range.start(), "*".to_string()));
+    }
+    let is_nested = replacements.iter().any(|(outer, _)| {
+        replacements.iter().any(|(it, _)| it != outer && it.is_subrange(outer))
+            || inserts.iter().any(|(it, _)| outer.start() <= *it && *it <= outer.end())
+    });
+    if is_nested {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("box_recursive_variant"), "Box recursive variant", |edit| {
+        edit.target(variant.syntax().text_range());
+        for (range, text) in replacements {
+            edit.replace(range, text);
+        }
+        for (offset, text) in inserts {
+            edit.insert(offset, text);
+        }
+    })
+}
+
+/// The usages of a binding of a field which is boxed now, where the box has to
+/// be dereferenced.
+fn boxed_binding_derefs(
+    ctx: &AssistCtx<impl HirDatabase>,
+    pat: &ast::Pat,
+) -> Option<Vec<TextRange>> {
+    let bind_pat = match pat {
+        ast::Pat::PlaceholderPat(_) => return Some(Vec::new()),
+        ast::Pat::BindPat(it) if it.pat().is_none() => it,
+        _ => return None,
+    };
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+    let by_ref = bind_pat.is_ref()
+        || analyzer.type_of_pat(db, pat)?.display(db).to_string().starts_with('&');
+    // Uses inside of macro calls aren't found.
+    let name = bind_pat.name()?.text().clone();
+    let scope = bind_pat.syntax().ancestors().find(|it| ast::FnDef::can_cast(it.kind()))?;
+    let in_macro = scope
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .filter_map(|it| it.token_tree())
+        .flat_map(|it| it.syntax().descendants_with_tokens())
+        .any(|it| it.kind() == IDENT && it.as_token().map_or(false, |it| *it.text() == name));
+    if in_macro {
+        return None;
+    }
+
+    let mut derefs = Vec::new();
+    for reference in analyzer.find_all_refs(bind_pat) {
+        let path_expr = ctx
+            .covering_node_for_range(reference.range)
+            .ancestors()
+            .find_map(ast::PathExpr::cast)
+            .filter(|it| it.syntax().text_range() == reference.range);
+        let parent = path_expr.as_ref().and_then(|it| it.syntax().parent());
+        let is_receiver = match (&path_expr, parent.clone().and_then(ast::Expr::cast)) {
+            (Some(path_expr), Some(ast::Expr::FieldExpr(it))) => {
+                it.expr().map_or(false, |it| it.syntax() == path_expr.syntax())
+            }
+            (Some(path_expr), Some(ast::Expr::MethodCallExpr(it))) => {
+                it.expr().map_or(false, |it| it.syntax() == path_expr.syntax())
+            }
+            _ => false,
+        };
+        if is_receiver {
+            continue;
+        }
+        if by_ref {
+            // References to the box are coerced to references to its content
+            // when passed to a function.
+            if parent.map_or(false, |it| ast::ArgList::can_cast(it.kind())) {
+                continue;
+            }
+            return None;
+        }
+        derefs.push(reference.range);
+    }
+    Some(derefs)
+}
+
+fn is_type(type_ref: &ast::TypeRef, name: &str) -> bool {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path(),
+        _ => None,
+    };
+    let segment = match path {
+        Some(path) if path.qualifier().is_none() => path.segment(),
+        _ => None,
+    };
+    match segment.and_then(|it| it.name_ref()) {
+        Some(name_ref) => name_ref.text().as_str() == name || name_ref.text().as_str() == "Self",
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn box_recursive_record_field() {
+        check_assist(
+            box_recursive_field,
+            r#"
+struct Node { value: u32, next: <|>Node }
+fn make(n: Node) -> Node { Node { value: 1, next: n } }
+fn get(n: Node) -> Node { n.next }
+fn value(n: &Node) -> u32 { n.next.value }
+"#,
+            r#"
+struct Node { value: u32, next: <|>Box<Node> }
+fn make(n: Node) -> Node { Node { value: 1, next: Box::new(n) } }
+fn get(n: Node) -> Node { *n.next }
+fn value(n: &Node) -> u32 { n.next.value }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_field_shorthand() {
+        check_assist(
+            box_recursive_field,
+            r#"
+struct Node { value: u32, next: Se<|>lf }
+fn make(value: u32, next: Node) -> Node { Node { value, next } }
+"#,
+            r#"
+struct Node { value: u32, next: <|>Box<Self> }
+fn make(value: u32, next: Node) -> Node { Node { value, next: Box::new(next) } }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_enum_variant_field() {
+        check_assist(
+            box_recursive_field,
+            r#"
+enum Expr { Lit(u32), Neg(<|>Expr), Add(Expr, Expr) }
+fn neg(e: Expr) -> Expr { Expr::Neg(e) }
+fn add(a: Expr, b: Expr) -> Expr { Expr::Add(a, b) }
+"#,
+            r#"
+enum Expr { Lit(u32), Neg(<|>Box<Expr>), Add(Expr, Expr) }
+fn neg(e: Expr) -> Expr { Expr::Neg(Box::new(e)) }
+fn add(a: Expr, b: Expr) -> Expr { Expr::Add(a, b) }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_field_target() {
+        check_assist_target(box_recursive_field, "struct Node { next: <|>Node }", "next: Node");
+    }
+
+    #[test]
+    fn box_recursive_field_not_applicable_with_patterns() {
+        check_assist_not_applicable(
+            box_recursive_field,
+            r#"
+struct Node { value: u32, next: <|>Node }
+fn f(n: Node) { let Node { next, .. } = n; }
+"#,
+        );
+        check_assist_not_applicable(
+            box_recursive_field,
+            r#"
+enum Expr { Lit(u32), Neg(<|>Expr) }
+fn f(e: Expr) { if let Expr::Neg(inner) = e {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_variant_with_bindings() {
+        check_assist(
+            box_recursive_variant,
+            r#"
+enum Expr { Lit(i64), <|>Add(Expr, Expr), Neg(Expr) }
+fn eval(e: Expr) -> i64 {
+    match e {
+        Expr::Lit(n) => n,
+        Expr::Add(a, _) => eval(a),
+        Expr::Add(.., b) => b.eval(),
+        Expr::Neg(e) => -eval(e),
+    }
+}
+fn swap(e: Expr) -> Expr { match e { Expr::Add(a, b) => Expr::Add(b, a), e => e } }
+fn two() -> Expr { Expr::Add(Expr::Lit(1), Expr::Lit(1)) }
+"#,
+            r#"
+enum Expr { Lit(i64), <|>Add(Box<Expr>, Box<Expr>), Neg(Expr) }
+fn eval(e: Expr) -> i64 {
+    match e {
+        Expr::Lit(n) => n,
+        Expr::Add(a, _) => eval(*a),
+        Expr::Add(.., b) => b.eval(),
+        Expr::Neg(e) => -eval(e),
+    }
+}
+fn swap(e: Expr) -> Expr { match e { Expr::Add(a, b) => Expr::Add(b, a), e => e } }
+fn two() -> Expr { Expr::Add(Box::new(Expr::Lit(1)), Box::new(Expr::Lit(1))) }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_record_variant_by_reference() {
+        check_assist(
+            box_recursive_variant,
+            r#"
+enum Tree { Leaf, No<|>de { left: Tree, right: Self, value: u32 } }
+fn sum(t: &Tree) -> u32 {
+    match t {
+        Tree::Leaf => 0,
+        Tree::Node { left, right: r, value } => sum(left) + r.sum() + value,
+    }
+}
+fn leaf(value: u32) -> Tree { Tree::Node { left: Tree::Leaf, right: Tree::Leaf, value } }
+"#,
+            r#"
+enum Tree { Leaf, No<|>de { left: Box<Tree>, right: Box<Self>, value: u32 } }
+fn sum(t: &Tree) -> u32 {
+    match t {
+        Tree::Leaf => 0,
+        Tree::Node { left, right: r, value } => sum(left) + r.sum() + value,
+    }
+}
+fn leaf(value: u32) -> Tree { Tree::Node { left: Box::new(Tree::Leaf), right: Box::new(Tree::Leaf), value } }
+"#,
+        );
+    }
+
+    #[test]
+    fn box_recursive_variant_target() {
+        check_assist_target(box_recursive_variant, "enum E { A, <|>B(E) }", "B(E)");
+    }
+
+    #[test]
+    fn box_recursive_variant_not_applicable() {
+        check_assist_not_applicable(box_recursive_variant, "enum E { <|>A(u32), B }");
+        // Nested patterns.
+        check_assist_not_applicable(
+            box_recursive_variant,
+            "enum E { A(u32), <|>B(E) }\nfn f(e: E) { if let E::B(E::A(n)) = e {} }",
+        );
+        // A reference to the box isn't one to its content.
+        check_assist_not_applicable(
+            box_recursive_variant,
+            "enum E { A(u32), <|>B(E) }\nfn f(e: &E, o: &E) -> bool { match e { E::B(it) => it == o, _ => false } }",
+        );
+        check_assist_not_applicable(
+            box_recursive_variant,
+            "enum E { A(u32), <|>B(E) }\nfn f(e: E) { if let E::B(it) = e { dbg!(it); } }",
+        );
+    }
+
+    #[test]
+    fn box_recursive_field_not_applicable_for_other_types() {
+        check_assist_not_applicable(box_recursive_field, "struct A { b: <|>B }");
+        check_assist_not_applicable(box_recursive_field, "struct Node { next: <|>Box<Node> }");
+    }
+}
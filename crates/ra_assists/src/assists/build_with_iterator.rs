@@ -0,0 +1,268 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::{BREAK_EXPR, CONTINUE_EXPR, FOR_EXPR, LOOP_EXPR, RETURN_EXPR, WHILE_EXPR},
+    SyntaxNode, TextRange,
+};
+
+use crate::{
+    utils::{is_mutated_after, single_expr},
+    Assist, AssistCtx, AssistId,
+};
+
+const MAX_PUSHES: usize = 4;
+
+// Assist: build_with_iterator
+//
+// Replaces a vector built by a few pushes, some of them conditional, with a
+// chain of iterators.
+//
+// ```
+// struct Vec<T>(T);
+// impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+//
+// fn args(verbose: bool) -> Vec<&'static str> {
+//     let mut <|>args = Vec::new();
+//     args.push("run");
+//     if verbose {
+//         args.push("-v");
+//     }
+//     args
+// }
+// ```
+// ->
+// ```
+// struct Vec<T>(T);
+// impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+//
+// fn args(verbose: bool) -> Vec<&'static str> {
+//     let args = std::iter::once("run")
+//         .chain(if verbose { Some("-v") } else { None })
+//         .collect::<Vec<_>>();
+//     args
+// }
+// ```
+pub(crate) fn build_with_iterator(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let vec = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) if it.is_mutable() && !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let init = match let_stmt.initializer()? {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    if init.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    match analyzer.type_of(ctx.db, &init.clone().into()).and_then(|it| it.as_adt()) {
+        Some(Adt::Struct(it)) if it.name(ctx.db).to_string() == "Vec" => (),
+        _ => return None,
+    }
+    let refs = analyzer.find_all_refs(&vec).into_iter().map(|it| it.range).collect::<Vec<_>>();
+
+    let mut pushes = Vec::new();
+    let mut end = let_stmt.syntax().text_range().end();
+    let mut next = let_stmt.syntax().next_sibling();
+    while let Some(stmt) = next.clone().and_then(ast::ExprStmt::cast) {
+        let push = match Push::from_expr(&stmt.expr()?, &refs) {
+            Some(it) => it,
+            None => break,
+        };
+        if pushes.len() == MAX_PUSHES {
+            return None;
+        }
+        pushes.push(push);
+        end = stmt.syntax().text_range().end();
+        next = stmt.syntax().next_sibling();
+    }
+    if !pushes.iter().any(|it| it.cond.is_some()) {
+        return None;
+    }
+    // The vector may only be used for pushing until it is complete.
+    let range = TextRange::from_to(let_stmt.syntax().text_range().start(), end);
+    let push_count = refs.iter().filter(|it| it.is_subrange(&range)).count();
+    if push_count != pushes.len() {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("build_with_iterator"), "Build with iterator", |edit| {
+        let indent = leading_indent(let_stmt.syntax()).unwrap_or_default();
+        let mut items = pushes.iter().map(Push::to_iterator);
+        let mut buf = String::new();
+        let name = vec.name().map(|it| it.text().to_string()).unwrap_or_default();
+        let block = let_stmt.syntax().parent().expect("statements are in a block");
+        let mut_kw = if is_mutated_after(&block, &refs, range) { "mut " } else { "" };
+        format!(buf, "let {}{}", mut_kw, name);
+        if let Some(ty) = let_stmt.ascribed_type() {
+            format!(buf, ": {}", ty.syntax());
+        }
+        match &pushes[0].cond {
+            None => format!(buf, " = {}", items.next().unwrap()),
+            Some(_) => buf.push_str(" = std::iter::empty()"),
+        }
+        for item in items {
+            format!(buf, "\n{}    .chain({})", indent, item);
+        }
+        match let_stmt.ascribed_type() {
+            Some(_) => format!(buf, "\n{}    .collect();", indent),
+            None => format!(buf, "\n{}    .collect::<Vec<_>>();", indent),
+        }
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// A `vec.push(value);`, possibly inside an `if` without `else`.
+struct Push {
+    cond: Option<ast::Expr>,
+    value: ast::Expr,
+}
+
+impl Push {
+    fn from_expr(expr: &ast::Expr, refs: &[TextRange]) -> Option<Push> {
+        let (cond, push) = match expr {
+            ast::Expr::IfExpr(it) => {
+                let cond = it.condition()?;
+                if cond.pat().is_some() || it.else_branch().is_some() {
+                    return None;
+                }
+                (Some(cond.expr()?), single_expr(&it.then_branch()?)?)
+            }
+            _ => (None, expr.clone()),
+        };
+        let call = match push {
+            ast::Expr::MethodCallExpr(it) => it,
+            _ => return None,
+        };
+        if call.name_ref()?.text().as_str() != "push" {
+            return None;
+        }
+        match call.expr()? {
+            ast::Expr::PathExpr(it) if refs.contains(&it.syntax().text_range()) => (),
+            _ => return None,
+        }
+        let mut args = call.arg_list()?.args();
+        let value = match (args.next()?, args.next()) {
+            (it, None) => it,
+            _ => return None,
+        };
+        if cond.iter().any(|it| has_control_flow(it.syntax())) || has_control_flow(value.syntax()) {
+            return None;
+        }
+        Some(Push { cond, value })
+    }
+
+    fn to_iterator(&self) -> String {
+        match &self.cond {
+            Some(cond) => {
+                format!("if {} {{ Some({}) }} else {{ None }}", cond.syntax(), self.value.syntax())
+            }
+            None => format!("std::iter::once({})", self.value.syntax()),
+        }
+    }
+}
+
+/// Whether the node contains loops or jumps, which can't be moved into the
+/// chain as they are.
+fn has_control_flow(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| match it.kind() {
+        FOR_EXPR | WHILE_EXPR | LOOP_EXPR | BREAK_EXPR | CONTINUE_EXPR | RETURN_EXPR => true,
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const VEC: &str = r#"
+struct Vec<T>(T);
+impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+"#;
+
+    #[test]
+    fn build_with_iterator_only_conditional() {
+        check_assist(
+            build_with_iterator,
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+fn f(a: bool, b: u32) {
+    let mut v: Vec<u32> = Vec::new()<|>;
+    if a {
+        v.push(1);
+    }
+    if b > 2 {
+        v.push(b * 2)
+    }
+    g();
+    v.push(3);
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+fn f(a: bool, b: u32) {
+    <|>let mut v: Vec<u32> = std::iter::empty()
+        .chain(if a { Some(1) } else { None })
+        .chain(if b > 2 { Some(b * 2) } else { None })
+        .collect();
+    g();
+    v.push(3);
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn build_with_iterator_target() {
+        check_assist_target(
+            build_with_iterator,
+            &format!(
+                "{}{}",
+                VEC, "fn f(a: bool) { let mut <|>v = Vec::new(); if a { v.push(1); } v }"
+            ),
+            "let mut v = Vec::new(); if a { v.push(1); }",
+        );
+    }
+
+    #[test]
+    fn build_with_iterator_not_applicable() {
+        // Not a `Vec`.
+        check_assist_not_applicable(
+            build_with_iterator,
+            "struct S; fn f(a: bool) { let mut <|>v = S::new(); if a { v.push(1); } }",
+        );
+        // Unconditional pushes only.
+        check_assist_not_applicable(
+            build_with_iterator,
+            &format!("{}{}", VEC, "fn f() { let mut <|>v = Vec::new(); v.push(1); v.push(2); }"),
+        );
+        // Pushing depends on the vector.
+        check_assist_not_applicable(
+            build_with_iterator,
+            &format!(
+                "{}{}",
+                VEC, "fn f(a: bool) { let mut <|>v = Vec::new(); if a { v.push(v.len()); } }"
+            ),
+        );
+        // Early return in the pushed value.
+        check_assist_not_applicable(
+            build_with_iterator,
+            &format!(
+                "{}{}",
+                VEC, "fn f(a: bool) { let mut <|>v = Vec::new(); if a { v.push(return); } }"
+            ),
+        );
+    }
+}
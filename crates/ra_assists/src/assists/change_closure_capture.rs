@@ -0,0 +1,130 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::WHITESPACE,
+    SyntaxToken, TextRange, T,
+};
+
+use crate::{utils::outer_locals, Assist, AssistCtx, AssistId};
+
+// Assist: change_closure_capture
+//
+// Makes a closure take ownership of what it captures, or stops it from doing
+// so when it doesn't capture anything.
+//
+// ```
+// fn main() {
+//     let name = String::new();
+//     spawn(<|>|| greet(&name));
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let name = String::new();
+//     spawn(move || greet(&name));
+// }
+// ```
+pub(crate) fn change_closure_capture(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = ctx.find_node_at_offset::<ast::LambdaExpr>()?;
+    let param_list = lambda.param_list()?;
+    let body = lambda.body()?;
+    // Only offered on the head of the closure, not in its body.
+    if ctx.frange.range.start() > body.syntax().text_range().start() {
+        return None;
+    }
+    let move_kw = lambda
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == T![move]);
+    match move_kw {
+        Some(move_kw) => remove_move(ctx, &lambda, &body, move_kw),
+        None => ctx.add_assist(AssistId("change_closure_capture"), "Add move to closure", |edit| {
+            let offset = param_list.syntax().text_range().start();
+            edit.target(lambda.syntax().text_range());
+            edit.insert(offset, "move ");
+            edit.set_cursor(offset);
+        }),
+    }
+}
+
+fn remove_move(
+    ctx: AssistCtx<impl HirDatabase>,
+    lambda: &ast::LambdaExpr,
+    body: &ast::Expr,
+    move_kw: SyntaxToken,
+) -> Option<Assist> {
+    // Without captures, borrowing and moving are the same. Locals used in
+    // macros aren't seen, so those are left alone.
+    let captures = outer_locals(&ctx, &[body.syntax().clone()], lambda.syntax().text_range())?;
+    if !captures.is_empty()
+        || body.syntax().descendants().any(|it| ast::MacroCall::can_cast(it.kind()))
+    {
+        return None;
+    }
+    let end = match move_kw.next_token() {
+        Some(it) if it.kind() == WHITESPACE => it.text_range().end(),
+        _ => move_kw.text_range().end(),
+    };
+
+    ctx.add_assist(AssistId("change_closure_capture"), "Remove move", |edit| {
+        let range = TextRange::from_to(move_kw.text_range().start(), end);
+        edit.target(lambda.syntax().text_range());
+        edit.delete(range);
+        edit.set_cursor(range.start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_move_to_closure() {
+        check_assist(
+            change_closure_capture,
+            "fn f() { let x = 1; g(<|>|a: u8| a + x); }",
+            "fn f() { let x = 1; g(<|>move |a: u8| a + x); }",
+        );
+    }
+
+    #[test]
+    fn add_move_to_async_closure() {
+        check_assist(
+            change_closure_capture,
+            "fn f() { let x = 1; g(async <|>|| x); }",
+            "fn f() { let x = 1; g(async <|>move || x); }",
+        );
+    }
+
+    #[test]
+    fn remove_move_from_closure() {
+        check_assist(
+            change_closure_capture,
+            "fn f() { g(<|>move |a: u8| { let b = a; b + 1 }); }",
+            "fn f() { g(<|>|a: u8| { let b = a; b + 1 }); }",
+        );
+    }
+
+    #[test]
+    fn change_closure_capture_target() {
+        check_assist_target(change_closure_capture, "fn f() { g(<|>|a| a); }", "|a| a");
+    }
+
+    #[test]
+    fn change_closure_capture_not_applicable() {
+        // In the body.
+        check_assist_not_applicable(change_closure_capture, "fn f() { g(|a| a + <|>1); }");
+        // Removing `move` would borrow the captures.
+        check_assist_not_applicable(
+            change_closure_capture,
+            "fn f() { let x = 1; g(<|>move || x); }",
+        );
+        check_assist_not_applicable(
+            change_closure_capture,
+            "fn f() { let x = 1; g(<|>move || println!(\"{}\", x)); }",
+        );
+    }
+}
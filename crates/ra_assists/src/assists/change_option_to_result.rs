@@ -0,0 +1,138 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextUnit,
+};
+
+use crate::{
+    utils::{
+        constructor_call, own_descendants, return_positions, single_type_arg, wrap_in_conversion,
+    },
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: change_option_to_result
+//
+// Changes the return type of a function from `Option<T>` to `Result<T, ()>`,
+// converting `Some` and `None` in return positions and adjusting `?` usages.
+// The cursor is left on the error type.
+//
+// ```
+// fn parse(s: &str) -> Option<|><u32> {
+//     if s.is_empty() {
+//         return None;
+//     }
+//     Some(92)
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Result<u32, ()> {
+//     if s.is_empty() {
+//         return Err(());
+//     }
+//     Ok(92)
+// }
+// ```
+pub(crate) fn change_option_to_result(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let type_ref = ret_type.type_ref()?;
+    let inner = single_type_arg(&type_ref, "Option")?;
+    let fn_def = ast::FnDef::cast(ret_type.syntax().parent()?)?;
+    let body = fn_def.body()?;
+
+    let positions = return_positions(&body);
+    let try_exprs = own_descendants(body.syntax()).filter_map(ast::TryExpr::cast);
+    let try_operands = try_exprs.filter_map(|it| it.expr()).collect::<Vec<_>>();
+
+    ctx.add_assist(AssistId("change_option_to_result"), "Change to Result", |edit| {
+        let range = type_ref.syntax().text_range();
+        edit.target(range);
+        let new_type = format!("Result<{}, ()>", inner.syntax());
+        let error_offset = TextUnit::of_str(&new_type) - TextUnit::of_str("()>");
+        edit.replace(range, new_type);
+        for expr in &positions {
+            match constructor_call(expr) {
+                Some((callee, "Some")) => edit.replace(callee.syntax().text_range(), "Ok"),
+                Some((_, "None")) => edit.replace(expr.syntax().text_range(), "Err(())"),
+                _ => wrap_in_conversion(edit, expr, ".ok_or(())"),
+            }
+        }
+        for operand in &try_operands {
+            edit.insert(operand.syntax().text_range().end(), ".ok_or(())");
+        }
+        edit.set_cursor(range.start() + error_offset);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn change_option_to_result_with_early_return_and_try() {
+        check_assist(
+            change_option_to_result,
+            r#"
+fn f(x: u32) -> Opt<|>ion<u32> {
+    if x == 0 {
+        return None;
+    }
+    let y = lookup(x)?;
+    Some(y + 1)
+}
+"#,
+            r#"
+fn f(x: u32) -> Result<u32, <|>()> {
+    if x == 0 {
+        return Err(());
+    }
+    let y = lookup(x).ok_or(())?;
+    Ok(y + 1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn change_option_to_result_converts_other_values() {
+        check_assist(
+            change_option_to_result,
+            "fn f(x: u32) -> Option<|><u32> { match x { 0 => None, 1 => lookup(x), _ => a.or(b) } }",
+            "fn f(x: u32) -> Result<u32, <|>()> { match x { 0 => Err(()), 1 => lookup(x).ok_or(()), _ => a.or(b).ok_or(()) } }",
+        );
+        check_assist(
+            change_option_to_result,
+            "fn f(x: u32) -> Option<|><u32> { if x > 0 { Some(x) } else { *cached } }",
+            "fn f(x: u32) -> Result<u32, <|>()> { if x > 0 { Ok(x) } else { (*cached).ok_or(()) } }",
+        );
+    }
+
+    #[test]
+    fn change_option_to_result_ignores_closures() {
+        check_assist(
+            change_option_to_result,
+            "fn f() -> <|>Option<u8> { let g = || { return None; }; g() }",
+            "fn f() -> Result<u8, <|>()> { let g = || { return None; }; g().ok_or(()) }",
+        );
+    }
+
+    #[test]
+    fn change_option_to_result_target() {
+        check_assist_target(
+            change_option_to_result,
+            "fn f() -> <|>Option<u8> { None }",
+            "Option<u8>",
+        );
+    }
+
+    #[test]
+    fn change_option_to_result_not_applicable_for_other_types() {
+        check_assist_not_applicable(
+            change_option_to_result,
+            "fn f() -> <|>Result<u8, ()> { Ok(1) }",
+        );
+        check_assist_not_applicable(change_option_to_result, "fn f() -> <|>u8 { 1 }");
+    }
+}
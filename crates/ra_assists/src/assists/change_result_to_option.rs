@@ -0,0 +1,192 @@
+use either::Either;
+use hir::{db::HirDatabase, PathResolution};
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{
+    utils::{constructor_call, own_descendants, return_positions, wrap_in_conversion},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: change_result_to_option
+//
+// Changes the return type of a function from `Result<T, E>` to `Option<T>`,
+// converting `Ok` and `Err` in return positions and adjusting `?` usages. The
+// error information is lost.
+//
+// ```
+// fn parse(s: &str) -> Result<|><u32, ParseError> {
+//     if s.is_empty() {
+//         return Err(ParseError::Empty);
+//     }
+//     Ok(92)
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Option<u32> {
+//     if s.is_empty() {
+//         return None;
+//     }
+//     Some(92)
+// }
+// ```
+pub(crate) fn change_result_to_option(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let type_ref = ret_type.type_ref()?;
+    let ok_type = ok_type(&type_ref)?;
+    let fn_def = ast::FnDef::cast(ret_type.syntax().parent()?)?;
+    let body = fn_def.body()?;
+
+    let positions = return_positions(&body);
+    // Dropping an error which is also used elsewhere, for example logged,
+    // would hide that the error is still computed for a reason.
+    for expr in &positions {
+        if let Some((_, "Err")) = constructor_call(expr) {
+            if is_used_elsewhere(&ctx, expr) {
+                return None;
+            }
+        }
+    }
+    let try_exprs = own_descendants(body.syntax()).filter_map(ast::TryExpr::cast);
+    let try_operands = try_exprs.filter_map(|it| it.expr()).collect::<Vec<_>>();
+
+    ctx.add_assist(
+        AssistId("change_result_to_option"),
+        "Change to Option (drops error information)",
+        |edit| {
+            let range = type_ref.syntax().text_range();
+            edit.target(range);
+            edit.replace(range, format!("Option<{}>", ok_type.syntax()));
+            for expr in &positions {
+                match constructor_call(expr) {
+                    Some((callee, "Ok")) => edit.replace(callee.syntax().text_range(), "Some"),
+                    Some((_, "Err")) => edit.replace(expr.syntax().text_range(), "None"),
+                    _ => wrap_in_conversion(edit, expr, ".ok()"),
+                }
+            }
+            for operand in &try_operands {
+                edit.insert(operand.syntax().text_range().end(), ".ok()");
+            }
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+/// For `Result<T, E>` and aliases like `io::Result<T>`, returns `T`.
+fn ok_type(type_ref: &ast::TypeRef) -> Option<ast::TypeRef> {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text().as_str() != "Result" {
+        return None;
+    }
+    let args = segment.type_arg_list()?.type_args().collect::<Vec<_>>();
+    if args.is_empty() || args.len() > 2 {
+        return None;
+    }
+    args[0].type_ref()
+}
+
+/// Whether the argument of `Err(e)` is a local which is referenced elsewhere.
+fn is_used_elsewhere(ctx: &AssistCtx<impl HirDatabase>, err_call: &ast::Expr) -> bool {
+    let arg = match err_call {
+        ast::Expr::CallExpr(it) => it.arg_list().and_then(|it| it.args().next()),
+        _ => None,
+    };
+    let path = match arg {
+        Some(ast::Expr::PathExpr(it)) => it.path(),
+        _ => None,
+    };
+    let path = match path {
+        Some(it) => it,
+        None => return false,
+    };
+    let analyzer = ctx.source_analyzer(path.syntax(), None);
+    let local = match analyzer.resolve_path(ctx.db, &path) {
+        Some(PathResolution::Local(it)) => it,
+        _ => return false,
+    };
+    match local.source(ctx.db).value {
+        Either::Left(bind_pat) => analyzer.find_all_refs(&bind_pat).len() > 1,
+        Either::Right(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn change_result_to_option_with_early_return_and_try() {
+        check_assist(
+            change_result_to_option,
+            r#"
+fn f(x: u32) -> Res<|>ult<u32, String> {
+    if x == 0 {
+        return Err(format!("zero"));
+    }
+    let y = parse(x)?;
+    Ok(y + 1)
+}
+"#,
+            r#"
+fn f(x: u32) -> <|>Option<u32> {
+    if x == 0 {
+        return None;
+    }
+    let y = parse(x).ok()?;
+    Some(y + 1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn change_result_to_option_converts_other_values() {
+        check_assist(
+            change_result_to_option,
+            "fn f(x: u32) -> <|>io::Result<u32> { match x { 0 => Ok(1), _ => read(x) } }",
+            "fn f(x: u32) -> <|>Option<u32> { match x { 0 => Some(1), _ => read(x).ok() } }",
+        );
+    }
+
+    #[test]
+    fn change_result_to_option_drops_unused_errors() {
+        check_assist(
+            change_result_to_option,
+            "fn f() -> <|>Result<u8, E> { match g() { Ok(x) => Ok(x), Err(e) => Err(e) } }",
+            "fn f() -> <|>Option<u8> { match g() { Ok(x) => Some(x), Err(e) => None } }",
+        );
+    }
+
+    #[test]
+    fn change_result_to_option_target() {
+        check_assist_target(
+            change_result_to_option,
+            "fn f() -> <|>Result<u8, ()> { Ok(1) }",
+            "Result<u8, ()>",
+        );
+    }
+
+    #[test]
+    fn change_result_to_option_not_applicable_when_error_is_used() {
+        check_assist_not_applicable(
+            change_result_to_option,
+            r#"
+fn f() -> <|>Result<u8, E> {
+    let e = make_error();
+    log(&e);
+    Err(e)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn change_result_to_option_not_applicable_for_other_types() {
+        check_assist_not_applicable(change_result_to_option, "fn f() -> <|>Option<u8> { None }");
+    }
+}
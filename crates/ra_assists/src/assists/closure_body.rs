@@ -0,0 +1,179 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    SyntaxKind::COMMENT,
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: simplify_closure_body
+//
+// Removes the block around the body of a closure which is a single
+// expression.
+//
+// ```
+// fn main() {
+//     let double = <|>|x: u32| {
+//         x * 2
+//     };
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let double = |x: u32| x * 2;
+// }
+// ```
+pub(crate) fn simplify_closure_body(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = lambda_at_head(&ctx)?;
+    // The return type requires a block.
+    if lambda.ret_type().is_some() {
+        return None;
+    }
+    let block_expr = match lambda.body()? {
+        ast::Expr::BlockExpr(it) => it,
+        _ => return None,
+    };
+    let block = block_expr.block()?;
+    // Neither `unsafe` nor `async` blocks.
+    if block_expr.syntax().first_child_or_token()?.as_node() != Some(block.syntax()) {
+        return None;
+    }
+    let expr = block.expr()?;
+    if block.statements().next().is_some()
+        || block.syntax().descendants_with_tokens().any(|it| it.kind() == COMMENT)
+    {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("simplify_closure_body"), "Simplify closure body", |edit| {
+        let expr = IndentLevel(1).decrease_indent(expr);
+        edit.target(lambda.syntax().text_range());
+        edit.replace(block_expr.syntax().text_range(), expr.syntax().to_string());
+    })
+}
+
+// Assist: add_block_to_closure
+//
+// Wraps the body of a closure in a block, to add statements to it.
+//
+// ```
+// fn main() {
+//     let double = <|>|x: u32| x * 2;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let double = |x: u32| {
+//         x * 2
+//     };
+// }
+// ```
+pub(crate) fn add_block_to_closure(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let lambda = lambda_at_head(&ctx)?;
+    let body = lambda.body()?;
+    if let ast::Expr::BlockExpr(_) = body {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_block_to_closure"), "Add block to closure", |edit| {
+        let level = IndentLevel::from_node(lambda.syntax());
+        let indent = " ".repeat(level.0 as usize * 4);
+        let body_range = body.syntax().text_range();
+        let body = IndentLevel(1).increase_indent(body);
+        edit.target(lambda.syntax().text_range());
+        edit.replace(body_range, format!("{{\n{}    {}\n{}}}", indent, body.syntax(), indent));
+        edit.set_cursor(body_range.start() + TextUnit::of_str(&format!("{{\n{}    ", indent)));
+    })
+}
+
+/// The closure under the cursor, when it's not in its body.
+fn lambda_at_head(ctx: &AssistCtx<impl HirDatabase>) -> Option<ast::LambdaExpr> {
+    let lambda = ctx.find_node_at_offset::<ast::LambdaExpr>()?;
+    if ctx.frange.range.start() > lambda.body()?.syntax().text_range().start() {
+        return None;
+    }
+    Some(lambda)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn simplify_closure_body_in_call() {
+        check_assist(
+            simplify_closure_body,
+            r#"
+fn f(items: &[u8]) {
+    items.iter().map(<|>|it| {
+        g(
+            *it,
+        )
+    });
+}
+"#,
+            r#"
+fn f(items: &[u8]) {
+    items.iter().map(<|>|it| g(
+        *it,
+    ));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn simplify_closure_body_target() {
+        check_assist_target(simplify_closure_body, "fn f() { g(<|>|| { 1 }); }", "|| { 1 }");
+    }
+
+    #[test]
+    fn simplify_closure_body_not_applicable() {
+        // Statements.
+        check_assist_not_applicable(simplify_closure_body, "fn f() { g(<|>|| { h(); 1 }); }");
+        check_assist_not_applicable(simplify_closure_body, "fn f() { g(<|>|| { // one\n 1 }); }");
+        // A return type.
+        check_assist_not_applicable(simplify_closure_body, "fn f() { g(<|>|| -> u8 { 1 }); }");
+        check_assist_not_applicable(simplify_closure_body, "fn f() { g(<|>|| unsafe { h() }); }");
+        // In the body.
+        check_assist_not_applicable(simplify_closure_body, "fn f() { g(|| { <|>1 }); }");
+    }
+
+    #[test]
+    fn add_block_to_closure_in_call() {
+        check_assist(
+            add_block_to_closure,
+            r#"
+fn f(items: &[u8]) {
+    items.iter().for_each(|it<|>| g(
+        *it,
+    ));
+}
+"#,
+            r#"
+fn f(items: &[u8]) {
+    items.iter().for_each(|it| {
+        <|>g(
+            *it,
+        )
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_block_to_closure_target() {
+        check_assist_target(add_block_to_closure, "fn f() { g(<|>|| 1); }", "|| 1");
+    }
+
+    #[test]
+    fn add_block_to_closure_not_applicable() {
+        check_assist_not_applicable(add_block_to_closure, "fn f() { g(<|>|| { 1 }); }");
+        check_assist_not_applicable(add_block_to_closure, "fn f() { g(|| 1 + <|>2); }");
+    }
+}
@@ -0,0 +1,167 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: collapse_map_projections
+//
+// Collapses consecutive calls to `map` which only access fields into one.
+//
+// ```
+// fn main() {
+//     let city = user.map(|u| u.address).<|>map(|a| a.city);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let city = user.map(|u| u.address.city);
+// }
+// ```
+pub(crate) fn collapse_map_projections(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    projection(&call)?;
+
+    // The run of projections around the call, from the first one on.
+    let mut first = call.clone();
+    while let Some(ast::Expr::MethodCallExpr(receiver)) = first.expr() {
+        if projection(&receiver).is_none() {
+            break;
+        }
+        first = receiver;
+    }
+    let mut last = call;
+    while let Some(parent) = last.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        let is_receiver = parent.expr().map_or(false, |it| it.syntax() == last.syntax());
+        if !is_receiver || projection(&parent).is_none() {
+            break;
+        }
+        last = parent;
+    }
+    if first == last {
+        return None;
+    }
+
+    let (_, first_body) = projection(&first)?;
+    let mut fields = String::new();
+    let mut next = last.clone();
+    while next != first {
+        let (param, body) = projection(&next)?;
+        // Everything after the parameter, like `.a.b` for `|x| x.a.b`.
+        let body_text = body.syntax().to_string();
+        fields.insert_str(0, &body_text[param.len()..]);
+        next = match next.expr()? {
+            ast::Expr::MethodCallExpr(it) => it,
+            _ => return None,
+        };
+    }
+
+    ctx.add_assist(AssistId("collapse_map_projections"), "Collapse map projections", |edit| {
+        let rest =
+            TextRange::from_to(first.syntax().text_range().end(), last.syntax().text_range().end());
+        edit.target(last.syntax().text_range());
+        edit.insert(first_body.syntax().text_range().end(), fields);
+        edit.delete(rest);
+        edit.set_cursor(first.syntax().text_range().start());
+    })
+}
+
+/// Matches `.map(|x| x.a.b)`, returning the name of the parameter and the
+/// body of the closure.
+fn projection(call: &ast::MethodCallExpr) -> Option<(String, ast::FieldExpr)> {
+    if call.name_ref()?.text().as_str() != "map" || call.type_arg_list().is_some() {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let lambda = match (args.next()?, args.next()) {
+        (ast::Expr::LambdaExpr(it), None) => it,
+        _ => return None,
+    };
+    if lambda.ret_type().is_some() {
+        return None;
+    }
+    let mut params = lambda.param_list()?.params();
+    let param = match (params.next()?.pat()?, params.next()) {
+        (ast::Pat::BindPat(it), None) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => {
+            it.name()?.text().to_string()
+        }
+        _ => return None,
+    };
+    let body = match lambda.body()? {
+        ast::Expr::FieldExpr(it) => it,
+        _ => return None,
+    };
+    let mut base = body.expr()?;
+    while let ast::Expr::FieldExpr(it) = base {
+        base = it.expr()?;
+    }
+    match base {
+        ast::Expr::PathExpr(it) if it.syntax().text() == param.as_str() => Some((param, body)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn collapse_two_map_projections() {
+        check_assist(
+            collapse_map_projections,
+            "fn f() { let x = a.map(|p: P| p.b).m<|>ap(|q| q.c.d); }",
+            "fn f() { let x = <|>a.map(|p: P| p.b.c.d); }",
+        );
+    }
+
+    #[test]
+    fn collapse_chain_of_map_projections() {
+        check_assist(
+            collapse_map_projections,
+            r#"
+fn f() {
+    a.iter()
+        .map(|x| g(x))
+        .map(|x| x.b)
+        .map(|x| <|>x.c)
+        .map(|x| x.0)
+        .count();
+}
+"#,
+            r#"
+fn f() {
+    <|>a.iter()
+        .map(|x| g(x))
+        .map(|x| x.b.c.0)
+        .count();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn collapse_map_projections_target() {
+        check_assist_target(
+            collapse_map_projections,
+            "fn f() { a.map(|p| p.b).<|>map(|q| q.c); }",
+            "a.map(|p| p.b).map(|q| q.c)",
+        );
+    }
+
+    #[test]
+    fn collapse_map_projections_not_applicable() {
+        check_assist_not_applicable(collapse_map_projections, "fn f() { a.<|>map(|p| p.b); }");
+        check_assist_not_applicable(
+            collapse_map_projections,
+            "fn f() { a.map(|p| p.b).<|>map(|q| q.c()); }",
+        );
+        check_assist_not_applicable(
+            collapse_map_projections,
+            "fn f() { a.map(|p| p.b).<|>map(|q| r.c); }",
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use hir::{db::HirDatabase, Adt};
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{
+    utils::{has_side_effects, AccumulatorLoop},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: collect_into_map
+//
+// Converts a `for` loop which only inserts into a new map into a `collect`.
+//
+// ```
+// fn main() {
+//     let mut ages = HashMap::new();
+//     <|>for (name, age) in people {
+//         ages.insert(name, age);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let ages: HashMap<_, _> = people.into_iter().collect();
+// }
+// ```
+pub(crate) fn collect_into_map(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let acc_loop = AccumulatorLoop::find(&ctx)?;
+    let map_name = empty_map_name(&ctx, &acc_loop.init)?;
+    let insert = match acc_loop.body_expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if insert.name_ref()?.text().as_str() != "insert" || !acc_loop.is_acc(&insert.expr()?) {
+        return None;
+    }
+    let mut args = insert.arg_list()?.args();
+    let (key, value) = match (args.next()?, args.next()?, args.next()) {
+        (key, value, None) => (key, value),
+        _ => return None,
+    };
+    if has_side_effects(key.syntax()) || has_side_effects(value.syntax()) {
+        return None;
+    }
+    // The map may only be touched by the insertion itself.
+    let loop_range = acc_loop.for_expr.syntax().text_range();
+    if acc_loop.refs.iter().filter(|it| it.is_subrange(&loop_range)).count() != 1 {
+        return None;
+    }
+
+    let pat = acc_loop.for_expr.pat()?;
+    let mut chain = acc_loop.iterator()?;
+    if !is_same_pair(&pat, &key, &value) {
+        chain.push_str(&format!(".map(|{}| ({}, {}))", pat.syntax(), key.syntax(), value.syntax()));
+    }
+    chain.push_str(".collect()");
+    let ty = format!("{}<_, _>", map_name);
+
+    ctx.add_assist(AssistId("collect_into_map"), "Collect into map", |edit| {
+        edit.target(acc_loop.range);
+        edit.replace(acc_loop.range, acc_loop.let_with_typed_value(Some(&ty), chain));
+        edit.set_cursor(acc_loop.range.start());
+    })
+}
+
+/// Returns the name of the map type if `init` creates an empty `HashMap` or
+/// `BTreeMap`, like `HashMap::new()`.
+fn empty_map_name(ctx: &AssistCtx<impl HirDatabase>, init: &ast::Expr) -> Option<String> {
+    let call = match init {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    if call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let ty = ctx.source_analyzer(call.syntax(), None).type_of(ctx.db, init)?;
+    let name = match ty.as_adt()? {
+        Adt::Struct(it) => it.name(ctx.db).to_string(),
+        _ => return None,
+    };
+    match name.as_str() {
+        "HashMap" | "BTreeMap" => Some(name),
+        _ => None,
+    }
+}
+
+/// Whether the loop pattern is `(key, value)`, so the items can be collected
+/// as they are.
+fn is_same_pair(pat: &ast::Pat, key: &ast::Expr, value: &ast::Expr) -> bool {
+    let tuple_pat = match pat {
+        ast::Pat::TuplePat(it) => it,
+        _ => return false,
+    };
+    let is_binding = |pat: Option<ast::Pat>, expr: &ast::Expr| match (pat, expr) {
+        (Some(ast::Pat::BindPat(pat)), ast::Expr::PathExpr(expr)) => {
+            !pat.is_mutable() && !pat.is_ref() && pat.syntax().text() == expr.syntax().text()
+        }
+        _ => false,
+    };
+    let mut args = tuple_pat.args();
+    is_binding(args.next(), key) && is_binding(args.next(), value) && args.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const MAPS: &str = r#"
+struct HashMap<K, V> { k: K, v: V }
+impl<K, V> HashMap<K, V> { fn new() -> Self { loop {} } }
+struct BTreeMap<K, V> { k: K, v: V }
+impl<K, V> BTreeMap<K, V> { fn new() -> Self { loop {} } }
+struct Vec<T> { t: T }
+impl<T> Vec<T> { fn new() -> Self { loop {} } }
+"#;
+
+    #[test]
+    fn collect_pairs_into_map() {
+        check_assist(
+            collect_into_map,
+            &format!(
+                "{}{}",
+                MAPS,
+                r#"
+fn f(pairs: Vec<(u8, u8)>) {
+    let mut m = HashMap::new();
+    <|>for (k, v) in pairs {
+        m.insert(k, v);
+    }
+    g(&m);
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                MAPS,
+                r#"
+fn f(pairs: Vec<(u8, u8)>) {
+    <|>let m: HashMap<_, _> = pairs.into_iter().collect();
+    g(&m);
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn collect_mapped_items_into_map() {
+        check_assist(
+            collect_into_map,
+            &format!(
+                "{}{}",
+                MAPS,
+                r#"
+fn f(items: &[u8]) {
+    let mut m: BTreeMap<u8, usize> = BTreeMap::new();
+    <|>for item in items { m.insert(*item, 1) }
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                MAPS,
+                r#"
+fn f(items: &[u8]) {
+    <|>let m: BTreeMap<u8, usize> = items.into_iter().map(|item| (*item, 1)).collect();
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn collect_into_map_target() {
+        check_assist_target(
+            collect_into_map,
+            &format!(
+                "{}{}",
+                MAPS,
+                "fn f() {\n    let mut m = HashMap::new();\n    <|>for (k, v) in xs { m.insert(k, v); }\n}"
+            ),
+            "let mut m = HashMap::new();\n    for (k, v) in xs { m.insert(k, v); }",
+        );
+    }
+
+    #[test]
+    fn collect_into_map_not_applicable() {
+        let check = |body: &str| {
+            check_assist_not_applicable(
+                collect_into_map,
+                &format!("{}fn f() {{\n{}\n}}", MAPS, body),
+            )
+        };
+        // Conditional insert.
+        check("let mut m = HashMap::new();\n<|>for (k, v) in xs { if k > 0 { m.insert(k, v); } }");
+        // The map is read in the loop.
+        check("let mut m = HashMap::new();\n<|>for (k, v) in xs { m.insert(k, m.len()); }");
+        // Not a map.
+        check("let mut m = Vec::new();\n<|>for (k, v) in xs { m.insert(k, v); }");
+        // Not created empty.
+        check("let mut m = make_map();\n<|>for (k, v) in xs { m.insert(k, v); }");
+    }
+}
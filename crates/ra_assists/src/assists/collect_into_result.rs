@@ -0,0 +1,233 @@
+use hir::{db::HirDatabase, Adt, HirDisplay, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{utils::result_error_type, Assist, AssistCtx, AssistId};
+
+// Assist: collect_into_result
+//
+// Replaces unwrapping each item before collecting with collecting into a
+// `Result` and returning the first error.
+//
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+//
+// fn parse_all(xs: Vec<Result<u32, String>>) -> Result<Vec<u32>, String> {
+//     let values = xs.into_iter().<|>map(|x| x.unwrap()).collect::<Vec<_>>();
+//     Result::Ok(values)
+// }
+// ```
+// ->
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+//
+// fn parse_all(xs: Vec<Result<u32, String>>) -> Result<Vec<u32>, String> {
+//     let values = xs.into_iter().collect::<Result<Vec<_>, _>>()?;
+//     Result::Ok(values)
+// }
+// ```
+pub(crate) fn collect_into_result(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let map_call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let map_name = map_call.name_ref()?;
+    if map_name.text().as_str() != "map" {
+        return None;
+    }
+    let mut args = map_call.arg_list()?.args();
+    let unwrap = match (args.next()?, args.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    // The items are only known to be unwrapped if they are collected right away.
+    let collect_call = ast::MethodCallExpr::cast(map_call.syntax().parent()?)?;
+    if collect_call.name_ref()?.text().as_str() != "collect"
+        || collect_call.arg_list()?.args().next().is_some()
+    {
+        return None;
+    }
+
+    // Closures have a return type of their own.
+    let fn_def = map_call.syntax().ancestors().find_map(|it| {
+        if ast::LambdaExpr::can_cast(it.kind()) {
+            Some(None)
+        } else {
+            ast::FnDef::cast(it).map(Some)
+        }
+    })??;
+    let error_type = result_error_type(&fn_def.ret_type()?.type_ref()?)?;
+
+    let analyzer = ctx.source_analyzer(map_call.syntax(), None);
+    let item_error_type = match &unwrap {
+        ast::Expr::LambdaExpr(it) => {
+            let item = unwrapped_param(it)?;
+            match analyzer.type_of(ctx.db, &item) {
+                Some(ty) if !ty.contains_unknown() => {
+                    match ty.as_adt() {
+                        Some(Adt::Enum(it)) if it.name(ctx.db).to_string() == "Result" => (),
+                        _ => return None,
+                    }
+                    let ty = ty.display(ctx.db).to_string();
+                    ty.rsplit(", ").next().map(|it| it.trim_end_matches('>').to_string())
+                }
+                _ => None,
+            }
+        }
+        ast::Expr::PathExpr(it) => {
+            let path = it.path()?;
+            if path.segment()?.name_ref()?.text().as_str() != "unwrap" {
+                return None;
+            }
+            match analyzer.resolve_path(ctx.db, &path.qualifier()?)? {
+                PathResolution::Def(ModuleDef::Adt(Adt::Enum(it)))
+                    if it.name(ctx.db).to_string() == "Result" => {}
+                _ => return None,
+            }
+            None
+        }
+        _ => return None,
+    };
+    // The error is converted with `From` by `?`, which only leaves a few cases
+    // we can be sure about.
+    let error_text = error_type.syntax().text().to_string();
+    if let Some(item_error_type) = item_error_type {
+        if !error_text.starts_with("Box<dyn") && error_text != item_error_type {
+            return None;
+        }
+    }
+
+    let collection = match collect_call.type_arg_list() {
+        Some(it) => {
+            let mut type_args = it.type_args();
+            match (type_args.next()?, type_args.next()) {
+                (it, None) => it.syntax().to_string(),
+                _ => return None,
+            }
+        }
+        None if has_ascribed_type(&collect_call) => "_".to_string(),
+        None => "Vec<_>".to_string(),
+    };
+
+    ctx.add_assist(AssistId("collect_into_result"), "Collect into Result", |edit| {
+        let range = TextRange::from_to(
+            map_name.syntax().text_range().start(),
+            collect_call.syntax().text_range().end(),
+        );
+        edit.target(range);
+        edit.replace(range, format!("collect::<Result<{}, _>>()?", collection));
+        edit.set_cursor(range.start());
+    })
+}
+
+/// For `|x| x.unwrap()`, returns the `x` which is unwrapped.
+fn unwrapped_param(lambda: &ast::LambdaExpr) -> Option<ast::Expr> {
+    let mut params = lambda.param_list()?.params();
+    let param = match (params.next()?.pat()?, params.next()) {
+        (ast::Pat::BindPat(it), None) => it,
+        _ => return None,
+    };
+    let call = match lambda.body()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if call.name_ref()?.text().as_str() != "unwrap" || call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let receiver = call.expr()?;
+    if receiver.syntax().text() != param.syntax().text() {
+        return None;
+    }
+    Some(receiver)
+}
+
+/// Whether the collected value is assigned to a variable of a known type.
+fn has_ascribed_type(call: &ast::MethodCallExpr) -> bool {
+    call.syntax()
+        .parent()
+        .and_then(ast::LetStmt::cast)
+        .map_or(false, |it| it.ascribed_type().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn collect_into_result_with_path() {
+        check_assist(
+            collect_into_result,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn f() -> Result<(), Error> {
+    let v: Vec<u32> = xs.iter().cloned().map(Result::unwrap<|>).collect();
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn f() -> Result<(), Error> {
+    let v: Vec<u32> = xs.iter().cloned().<|>collect::<Result<_, _>>()?;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn collect_into_result_with_closure() {
+        check_assist(
+            collect_into_result,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S;
+fn f(xs: Vec<Result<u8, S>>) -> Result<(), S> {
+    g(xs.into_iter().<|>map(|it: Result<u8, S>| it.unwrap()).collect());
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S;
+fn f(xs: Vec<Result<u8, S>>) -> Result<(), S> {
+    g(xs.into_iter().<|>collect::<Result<Vec<_>, _>>()?);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn collect_into_result_target() {
+        check_assist_target(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nfn f() -> Result<(), E> { it.<|>map(Result::unwrap).collect::<Vec<_>>() }",
+            "map(Result::unwrap).collect::<Vec<_>>()",
+        );
+    }
+
+    #[test]
+    fn collect_into_result_not_applicable() {
+        // The function can't return the error.
+        check_assist_not_applicable(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nfn f() -> u32 { it.<|>map(Result::unwrap).collect() }",
+        );
+        // Not collected.
+        check_assist_not_applicable(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nfn f() -> Result<(), E> { it.<|>map(Result::unwrap).sum() }",
+        );
+        // An error which can't be converted.
+        check_assist_not_applicable(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nstruct A; struct B;\nfn f() -> Result<(), B> { it.<|>map(|x: Result<u8, A>| x.unwrap()).collect() }",
+        );
+        // Not unwrapping the item.
+        check_assist_not_applicable(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nfn f() -> Result<(), E> { it.<|>map(|x| y.unwrap()).collect() }",
+        );
+        // Inside a closure.
+        check_assist_not_applicable(
+            collect_into_result,
+            "enum Result<T, E> { Ok(T), Err(E) }\nfn f() -> Result<(), E> { let g = || it.<|>map(Result::unwrap).collect(); }",
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: move_collect_type_to_binding
+//
+// Moves the type of a `collect` turbofish to the variable it is assigned to.
+//
+// ```
+// fn main() {
+//     let <|>squares = (1..4).map(|it| it * it).collect::<Vec<u32>>();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let squares: Vec<u32> = (1..4).map(|it| it * it).collect();
+// }
+// ```
+pub(crate) fn move_collect_type_to_binding(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let pat = let_stmt.pat()?;
+    let call = collect_call(&let_stmt)?;
+    let type_args = call.type_arg_list()?;
+    let mut args = type_args.type_args();
+    let ty = match (args.next(), args.next()) {
+        (Some(it), None) => it.type_ref()?,
+        _ => return None,
+    };
+    if type_args.lifetime_args().next().is_some() || type_args.assoc_type_args().next().is_some() {
+        return None;
+    }
+
+    ctx.add_assist(
+        AssistId("move_collect_type_to_binding"),
+        "Move collect type to binding",
+        |edit| {
+            edit.target(let_stmt.syntax().text_range());
+            edit.insert(pat.syntax().text_range().end(), format!(": {}", ty.syntax()));
+            edit.delete(type_args.syntax().text_range());
+        },
+    )
+}
+
+// Assist: move_binding_type_to_collect
+//
+// Moves the type of a variable assigned the result of `collect` to a
+// turbofish on the call.
+//
+// ```
+// fn main() {
+//     let <|>squares: Vec<u32> = (1..4).map(|it| it * it).collect();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let squares = (1..4).map(|it| it * it).collect::<Vec<u32>>();
+// }
+// ```
+pub(crate) fn move_binding_type_to_collect(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let ty = let_stmt.ascribed_type()?;
+    let pat = let_stmt.pat()?;
+    let call = collect_call(&let_stmt)?;
+    if call.type_arg_list().is_some() {
+        return None;
+    }
+    let name_ref = call.name_ref()?;
+
+    ctx.add_assist(
+        AssistId("move_binding_type_to_collect"),
+        "Move binding type to collect",
+        |edit| {
+            edit.target(let_stmt.syntax().text_range());
+            edit.delete(TextRange::from_to(
+                pat.syntax().text_range().end(),
+                ty.syntax().text_range().end(),
+            ));
+            edit.insert(name_ref.syntax().text_range().end(), format!("::<{}>", ty.syntax()));
+        },
+    )
+}
+
+/// The `collect()` call initializing the variable.
+fn collect_call(let_stmt: &ast::LetStmt) -> Option<ast::MethodCallExpr> {
+    let call = match let_stmt.initializer()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if call.name_ref()?.text() != "collect" || call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn move_collect_type_to_binding_with_pattern() {
+        check_assist(
+            move_collect_type_to_binding,
+            "fn f(s: &str) { let mut <|>words = s.split(' ').collect::<Vec<&str>>(); }",
+            "fn f(s: &str) { let mut <|>words: Vec<&str> = s.split(' ').collect(); }",
+        );
+    }
+
+    #[test]
+    fn move_collect_type_to_binding_target() {
+        check_assist_target(
+            move_collect_type_to_binding,
+            "fn f() { <|>let v = it.collect::<Vec<_>>(); }",
+            "let v = it.collect::<Vec<_>>();",
+        );
+    }
+
+    #[test]
+    fn move_collect_type_to_binding_not_applicable() {
+        check_assist_not_applicable(
+            move_collect_type_to_binding,
+            "fn f() { <|>let v: Vec<_> = it.collect(); }",
+        );
+        check_assist_not_applicable(
+            move_collect_type_to_binding,
+            "fn f() { <|>let v = it.collect::<Vec<_>>().len(); }",
+        );
+    }
+
+    #[test]
+    fn move_binding_type_to_collect_with_pattern() {
+        check_assist(
+            move_binding_type_to_collect,
+            "fn f(s: &str) { let mut <|>words : Vec<&str> = s.split(' ').collect(); }",
+            "fn f(s: &str) { let mut <|>words = s.split(' ').collect::<Vec<&str>>(); }",
+        );
+    }
+
+    #[test]
+    fn move_binding_type_to_collect_target() {
+        check_assist_target(
+            move_binding_type_to_collect,
+            "fn f() { <|>let v: Vec<_> = it.collect(); }",
+            "let v: Vec<_> = it.collect();",
+        );
+    }
+
+    #[test]
+    fn move_binding_type_to_collect_not_applicable() {
+        check_assist_not_applicable(
+            move_binding_type_to_collect,
+            "fn f() { <|>let v = it.collect::<Vec<_>>(); }",
+        );
+        check_assist_not_applicable(
+            move_binding_type_to_collect,
+            "fn f() { <|>let v: usize = it.count(); }",
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use hir::db::HirDatabase;
+use ra_fmt::extract_trivial_expression;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner},
+    T,
+};
+
+use crate::{utils::is_copy_type, Assist, AssistCtx, AssistId};
+
+// Assist: combine_if_let
+//
+// Combines two nested `if let` expressions into a single one, matching on a tuple.
+//
+// ```
+// fn main(a: &Option<u32>, b: &Option<u32>) {
+//     <|>if let Some(x) = a {
+//         if let Some(y) = b {
+//             foo(x, y);
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main(a: &Option<u32>, b: &Option<u32>) {
+//     if let (Some(x), Some(y)) = (a, b) {
+//         foo(x, y);
+//     }
+// }
+// ```
+pub(crate) fn combine_if_let(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_keyword = ctx.find_token_at_offset(T![if])?;
+    let outer = ast::IfExpr::cast(if_keyword.parent())?;
+    if outer.else_branch().is_some() {
+        return None;
+    }
+    let outer_cond = outer.condition()?;
+    let outer_pat = outer_cond.pat()?;
+    let outer_expr = outer_cond.expr()?;
+
+    // The outer block must consist of the inner `if let` only.
+    let inner = match extract_trivial_expression(&outer.then_branch()?)? {
+        ast::Expr::IfExpr(it) => it,
+        _ => return None,
+    };
+    if inner.else_branch().is_some() {
+        return None;
+    }
+    let inner_cond = inner.condition()?;
+    let inner_pat = inner_cond.pat()?;
+    let inner_expr = inner_cond.expr()?;
+    let inner_block = inner.then_branch()?;
+
+    // Both scrutinees are evaluated up front, so the inner one can't refer to
+    // anything bound by the outer pattern.
+    let outer_bindings = outer_pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    if inner_expr
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .any(|it| outer_bindings.contains(it.text()))
+    {
+        return None;
+    }
+
+    // The inner scrutinee is now evaluated even if the outer pattern doesn't
+    // match, and places are moved into the tuple rather than matched in place.
+    let is_copied = |expr: &ast::Expr| {
+        let analyzer = ctx.source_analyzer(expr.syntax(), None);
+        analyzer.type_of(ctx.db, expr).map_or(false, |ty| is_copy_type(ctx.db, &ty))
+    };
+    if !is_place(&inner_expr) || !is_copied(&inner_expr) {
+        return None;
+    }
+    if is_place(&outer_expr) && !is_copied(&outer_expr) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("combine_if_let"), "Combine if-let", |edit| {
+        let body = IndentLevel(1).decrease_indent(inner_block);
+        let combined = format!(
+            "if let ({}, {}) = ({}, {}) {}",
+            outer_pat.syntax(),
+            inner_pat.syntax(),
+            outer_expr.syntax(),
+            inner_expr.syntax(),
+            body.syntax()
+        );
+        edit.target(outer.syntax().text_range());
+        edit.replace(outer.syntax().text_range(), combined);
+        edit.set_cursor(outer.syntax().text_range().start());
+    })
+}
+
+/// Whether the expression is a variable or one of its fields, which can be
+/// evaluated without side effects.
+fn is_place(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_place(&it)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn combine_nested_if_let() {
+        check_assist(
+            combine_if_let,
+            r#"
+            #[derive(Clone, Copy)]
+            enum Status { Ready(u8), Busy }
+            struct Job { status: Status }
+            fn f(job: Job) {
+                <|>if let Some(x) = load() {
+                    if let Status::Ready(y) = job.status {
+                        foo(x, y);
+                        bar();
+                    }
+                }
+            }
+            "#,
+            r#"
+            #[derive(Clone, Copy)]
+            enum Status { Ready(u8), Busy }
+            struct Job { status: Status }
+            fn f(job: Job) {
+                <|>if let (Some(x), Status::Ready(y)) = (load(), job.status) {
+                    foo(x, y);
+                    bar();
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn combine_if_let_target() {
+        check_assist_target(
+            combine_if_let,
+            "fn f(a: &A, b: &B) { <|>if let Some(x) = a { if let Some(y) = b { foo(x, y) } } }",
+            "if let Some(x) = a { if let Some(y) = b { foo(x, y) } }",
+        );
+    }
+
+    #[test]
+    fn combine_if_let_not_applicable_with_else() {
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f() { <|>if let Some(x) = a { if let Some(y) = b { foo(x, y) } } else { bar() } }",
+        );
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f() { <|>if let Some(x) = a { if let Some(y) = b { foo(x, y) } else { bar() } } }",
+        );
+    }
+
+    #[test]
+    fn combine_if_let_not_applicable_with_extra_statements() {
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f() { <|>if let Some(x) = a { baz(); if let Some(y) = b { foo(x, y) } } }",
+        );
+    }
+
+    #[test]
+    fn combine_if_let_not_applicable_when_inner_uses_outer_binding() {
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f() { <|>if let Some(x) = a { if let Some(y) = x.get() { foo(y) } } }",
+        );
+    }
+
+    #[test]
+    fn combine_if_let_not_applicable_when_inner_is_moved_or_computed() {
+        check_assist_not_applicable(
+            combine_if_let,
+            "struct B;\nfn f(a: &A, b: B) { <|>if let Some(x) = a { if let B = b { foo(x) } } }",
+        );
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f(a: &A) { <|>if let Some(x) = a { if let Some(y) = g() { foo(x, y) } } }",
+        );
+    }
+
+    #[test]
+    fn combine_if_let_not_applicable_for_plain_if() {
+        check_assist_not_applicable(
+            combine_if_let,
+            "fn f() { <|>if a { if let Some(y) = b { foo(y) } } }",
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{utils::diverges, Assist, AssistCtx, AssistId};
+
+// Assist: combine_into_let_else
+//
+// Combines a local with a `match` on it that either unwraps it or diverges into
+// a `let ... else`.
+//
+// ```
+// fn parse(s: &str) -> Result<u32, Error> {
+//     let digits = find_digits(s);
+//     let <|>n = match digits {
+//         Some(n) => n,
+//         None => return Err(Error::Empty),
+//     };
+//     Ok(n)
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Result<u32, Error> {
+//     let Some(n) = find_digits(s) else { return Err(Error::Empty); };
+//     Ok(n)
+// }
+// ```
+pub(crate) fn combine_into_let_else(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let binding = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) if it.pat().is_none() => it,
+        _ => return None,
+    };
+    let match_expr = match let_stmt.initializer()? {
+        ast::Expr::MatchExpr(it) => it,
+        _ => return None,
+    };
+    let scrutinee = match match_expr.expr()? {
+        ast::Expr::PathExpr(it) => it,
+        _ => return None,
+    };
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    // The matched local has to be declared just before, and used only here.
+    let prev_let = ast::LetStmt::cast(let_stmt.syntax().prev_sibling()?)?;
+    let local = match prev_let.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    if prev_let.ascribed_type().is_some() {
+        return None;
+    }
+    let init = prev_let.initializer()?;
+    // The `else` would be ambiguous after a `}` or a lazy boolean operator.
+    let is_lazy_bool = match &init {
+        ast::Expr::BinExpr(it) => match it.op_kind() {
+            Some(ast::BinOp::BooleanAnd) | Some(ast::BinOp::BooleanOr) => true,
+            _ => false,
+        },
+        _ => false,
+    };
+    if is_lazy_bool || init.syntax().text().to_string().ends_with('}') {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    let refs = analyzer.find_all_refs(&local);
+    if refs.len() != 1 || refs[0].range != scrutinee.syntax().text_range() {
+        return None;
+    }
+
+    let mut arms = match_expr.match_arm_list()?.arms();
+    let (first, second) = (arms.next()?, arms.next()?);
+    if arms.next().is_some() {
+        return None;
+    }
+    let (pat, value, else_arm) = match value_arm(&first) {
+        Some((pat, value)) => (pat, value, second),
+        None => {
+            let (pat, value) = value_arm(&second)?;
+            (pat, value, first)
+        }
+    };
+    let else_expr = else_arm.expr()?;
+    if else_arm.guard().is_some() || !diverges(&ctx, &else_expr) {
+        return None;
+    }
+    // Nothing bound by the other pattern survives in the `else` block.
+    let else_range = else_expr.syntax().text_range();
+    for pat in else_arm.pats() {
+        for bind_pat in pat.syntax().descendants().filter_map(ast::BindPat::cast) {
+            if analyzer.find_all_refs(&bind_pat).iter().any(|it| it.range.is_subrange(&else_range))
+            {
+                return None;
+            }
+        }
+    }
+
+    ctx.add_assist(AssistId("combine_into_let_else"), "Combine into let-else", |edit| {
+        // The value is bound under the name of the outer `let`.
+        let pat_text = pat.syntax().to_string();
+        let pat_start = pat.syntax().text_range().start();
+        let value_range = value.syntax().text_range();
+        let start = (value_range.start() - pat_start).to_usize();
+        let end = (value_range.end() - pat_start).to_usize();
+        let mut buf = String::new();
+        format!(
+            buf,
+            "let {}{}{} = {} else ",
+            &pat_text[..start],
+            binding.syntax(),
+            &pat_text[end..],
+            init.syntax(),
+        );
+        match &else_expr {
+            ast::Expr::BlockExpr(it) => {
+                buf.push_str(&IndentLevel(1).decrease_indent(it.clone()).syntax().to_string())
+            }
+            it => format!(buf, "{{ {}; }}", it.syntax()),
+        }
+        buf.push(';');
+        let range = TextRange::from_to(
+            prev_let.syntax().text_range().start(),
+            let_stmt.syntax().text_range().end(),
+        );
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Matches an arm like `Some(x) => x`, returning the pattern and the binding of
+/// the value.
+fn value_arm(arm: &ast::MatchArm) -> Option<(ast::Pat, ast::BindPat)> {
+    if arm.guard().is_some() {
+        return None;
+    }
+    let mut pats = arm.pats();
+    let pat = pats.next()?;
+    if pats.next().is_some() {
+        return None;
+    }
+    let name = match arm.expr()? {
+        ast::Expr::PathExpr(it) => {
+            let path = it.path()?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            path.segment()?.name_ref()?.text().clone()
+        }
+        _ => return None,
+    };
+    let value = pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .find(|it| it.name().map_or(false, |it| *it.text() == name))?;
+    // A bare binding matches anything, so there is nothing to `else`.
+    if value.is_ref() || value.pat().is_some() || value.syntax() == pat.syntax() {
+        return None;
+    }
+    Some((pat, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn combine_into_let_else_with_expr() {
+        check_assist(
+            combine_into_let_else,
+            r#"
+fn f() -> Result<u8, E> {
+    let opt = compute();
+    let <|>x = match opt {
+        Some(x) => x,
+        None => return Err(E),
+    };
+    Ok(x)
+}
+"#,
+            r#"
+fn f() -> Result<u8, E> {
+    <|>let Some(x) = compute() else { return Err(E); };
+    Ok(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn combine_into_let_else_with_block() {
+        check_assist(
+            combine_into_let_else,
+            r#"
+fn f() {
+    loop {
+        let res = next();
+        let mut <|>item = match res {
+            Err(_) => {
+                log();
+                continue;
+            }
+            Ok(Pair(_, value)) => value,
+        };
+        item.update();
+    }
+}
+"#,
+            r#"
+fn f() {
+    loop {
+        <|>let Ok(Pair(_, mut item)) = next() else {
+            log();
+            continue;
+        };
+        item.update();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn combine_into_let_else_target() {
+        check_assist_target(
+            combine_into_let_else,
+            "fn f() { let a = g(); let <|>b = match a { Some(b) => b, None => return }; h(b); }",
+            "let a = g(); let b = match a { Some(b) => b, None => return };",
+        );
+    }
+
+    #[test]
+    fn combine_into_let_else_not_applicable() {
+        // The local is used again.
+        check_assist_not_applicable(
+            combine_into_let_else,
+            "fn f() { let a = g(); let <|>b = match a { Some(b) => b, None => return }; h(a); }",
+        );
+        // The other arm doesn't diverge.
+        check_assist_not_applicable(
+            combine_into_let_else,
+            "fn f() { let a = g(); let <|>b = match a { Some(b) => b, None => 0 }; }",
+        );
+        // The other arm uses its binding.
+        check_assist_not_applicable(
+            combine_into_let_else,
+            "fn f() -> R { let a = g(); let <|>b = match a { Ok(b) => b, Err(e) => return Err(e) }; }",
+        );
+        // The `else` would be ambiguous after the initializer.
+        check_assist_not_applicable(
+            combine_into_let_else,
+            "fn f(c: bool) { let a = if c { g() } else { h() }; let <|>b = match a { Some(b) => b, None => return }; }",
+        );
+        // Not declared just before.
+        check_assist_not_applicable(
+            combine_into_let_else,
+            "fn f() { let a = g(); h(); let <|>b = match a { Some(b) => b, None => return }; }",
+        );
+    }
+}
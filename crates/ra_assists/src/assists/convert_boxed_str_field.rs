@@ -0,0 +1,226 @@
+use hir::{db::HirDatabase, InFile, VariantDef};
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextRange, TextUnit,
+};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{field_at_offset, single_type_arg, FieldUsages},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_boxed_str_field
+//
+// Changes the type of a `Box<str>` field to `String`, or the other way around
+// to save the capacity, updating the places where it is initialized.
+//
+// ```
+// struct Person {
+//     name: <|>Box<str>,
+// }
+//
+// fn person(name: Box<str>) -> Person {
+//     Person { name }
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// fn person(name: Box<str>) -> Person {
+//     Person { name: name.to_string() }
+// }
+// ```
+pub(crate) fn convert_boxed_str_field(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (field, type_ref, field_syntax) = field_at_offset(&ctx)?;
+    let to_string = match single_type_arg(&type_ref, "Box") {
+        Some(it) if it.syntax().text() == "str" => true,
+        Some(_) => return None,
+        None if type_ref.syntax().text() == "String" => false,
+        None => return None,
+    };
+
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let variant: VariantDef = match field_syntax.ancestors().find_map(ast::EnumVariant::cast) {
+        Some(it) => sb.to_def(InFile::new(file_id, it))?.into(),
+        None => {
+            let strukt = field_syntax.ancestors().find_map(ast::StructDef::cast)?;
+            sb.to_def(InFile::new(file_id, strukt))?.into()
+        }
+    };
+    let usages = FieldUsages::find(&ctx, &field, &field_syntax, variant)?;
+
+    let (new_type, conversion, read_conversion) = if to_string {
+        ("String", ".to_string()", ".into_boxed_str()")
+    } else {
+        ("Box<str>", ".into_boxed_str()", ".into_string()")
+    };
+    let convert = |expr: &ast::Expr| match expr {
+        // The target of `into` is inferred from the field.
+        ast::Expr::MethodCallExpr(it) if is_method(it, "into") => expr.syntax().to_string(),
+        ast::Expr::MethodCallExpr(it) if to_string && is_method(it, "into_boxed_str") => {
+            it.expr().map_or_else(|| expr.syntax().to_string(), |it| it.syntax().to_string())
+        }
+        _ => format!("{}{}", ExprPrecedence::Postfix.parenthesize(expr), conversion),
+    };
+
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    edits.push((type_ref.syntax().text_range(), new_type.to_string()));
+    for expr in &usages.initializers {
+        edits.push((expr.syntax().text_range(), convert(expr)));
+    }
+    for name_ref in &usages.shorthands {
+        let value = format!(": {}{}", name_ref.text(), conversion);
+        edits.push((empty_at(name_ref.syntax().text_range().end()), value));
+    }
+    for access in &usages.reads {
+        let parent = access.syntax().parent();
+        if let Some(ref_expr) = parent.clone().and_then(ast::RefExpr::cast) {
+            if ref_expr.is_mut() {
+                return None;
+            }
+            continue;
+        }
+        let assignment = parent.and_then(ast::BinExpr::cast).filter(|it| {
+            it.op_kind() == Some(ast::BinOp::Assignment)
+                && it.lhs().map_or(false, |lhs| lhs.syntax() == access.syntax())
+        });
+        match assignment.and_then(|it| it.rhs()) {
+            Some(rhs) => edits.push((rhs.syntax().text_range(), convert(&rhs))),
+            None => {
+                edits.push((empty_at(access.syntax().text_range().end()), read_conversion.into()))
+            }
+        }
+    }
+    // A `Box<str>` only has the methods of `str`.
+    if !to_string {
+        for access in &usages.receivers {
+            let call = access.syntax().parent().and_then(ast::MethodCallExpr::cast);
+            let name = match call.and_then(|it| it.name_ref()) {
+                Some(it) => it.text().clone(),
+                None => continue,
+            };
+            if STRING_METHODS.contains(&name.as_str()) {
+                return None;
+            }
+        }
+    }
+
+    ctx.add_assist(AssistId("convert_boxed_str_field"), format!("Use {}", new_type), |edit| {
+        edit.target(field_syntax.text_range());
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(type_ref.syntax().text_range().start());
+    })
+}
+
+const STRING_METHODS: &[&str] = &[
+    "as_mut_str",
+    "capacity",
+    "clear",
+    "drain",
+    "extend",
+    "insert",
+    "insert_str",
+    "into_bytes",
+    "into_boxed_str",
+    "pop",
+    "push",
+    "push_str",
+    "remove",
+    "reserve",
+    "retain",
+    "shrink_to_fit",
+    "split_off",
+    "truncate",
+];
+
+fn is_method(call: &ast::MethodCallExpr, name: &str) -> bool {
+    call.name_ref().map_or(false, |it| it.text().as_str() == name)
+        && call.arg_list().map_or(false, |it| it.args().next().is_none())
+}
+
+fn empty_at(offset: TextUnit) -> TextRange {
+    TextRange::offset_len(offset, 0.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_boxed_str_field_to_string() {
+        check_assist(
+            convert_boxed_str_field,
+            r#"
+struct Person { name: <|>Box<str>, age: u32 }
+impl Person {
+    fn new(name: Box<str>) -> Self { Person { name, age: 0 } }
+    fn from_str(s: &str) -> Self { Person { name: s.into(), age: 0 } }
+    fn from_string(s: String) -> Self { Person { name: s.into_boxed_str(), age: 0 } }
+    fn into_name(self) -> Box<str> { self.name }
+    fn name(&self) -> &str { &self.name }
+    fn rename(&mut self, name: Box<str>) { self.name = name; }
+}
+"#,
+            r#"
+struct Person { name: <|>String, age: u32 }
+impl Person {
+    fn new(name: Box<str>) -> Self { Person { name: name.to_string(), age: 0 } }
+    fn from_str(s: &str) -> Self { Person { name: s.into(), age: 0 } }
+    fn from_string(s: String) -> Self { Person { name: s, age: 0 } }
+    fn into_name(self) -> Box<str> { self.name.into_boxed_str() }
+    fn name(&self) -> &str { &self.name }
+    fn rename(&mut self, name: Box<str>) { self.name = name.to_string(); }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_string_field_to_boxed_str() {
+        check_assist(
+            convert_boxed_str_field,
+            r#"
+struct Name(<|>String);
+fn name(s: &str) -> Name { Name(s.to_string()) }
+fn len(n: &Name) -> usize { n.0.len() }
+"#,
+            r#"
+struct Name(<|>Box<str>);
+fn name(s: &str) -> Name { Name(s.to_string().into_boxed_str()) }
+fn len(n: &Name) -> usize { n.0.len() }
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_boxed_str_field_target() {
+        check_assist_target(
+            convert_boxed_str_field,
+            "struct S { name: <|>String }",
+            "name: String",
+        );
+    }
+
+    #[test]
+    fn convert_boxed_str_field_not_applicable() {
+        check_assist_not_applicable(convert_boxed_str_field, "struct S { name: <|>Box<u8> }");
+        // `push_str` doesn't exist on `Box<str>`.
+        check_assist_not_applicable(
+            convert_boxed_str_field,
+            "struct S { name: <|>String }\nfn f(s: &mut S) { s.name.push_str(\"!\"); }",
+        );
+        // The field is bound by a pattern.
+        check_assist_not_applicable(
+            convert_boxed_str_field,
+            "struct S { name: <|>String }\nfn f(s: S) { let S { name } = s; }",
+        );
+    }
+}
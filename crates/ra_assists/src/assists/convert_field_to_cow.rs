@@ -0,0 +1,276 @@
+use hir::{db::HirDatabase, InFile, VariantDef};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    TextRange, TextUnit,
+};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{field_at_offset, single_type_arg, FieldUsages},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_field_to_cow
+//
+// Changes the type of a `String` or `Vec<T>` field to `Cow`, so that it can
+// also hold borrowed data. A lifetime parameter is added to the struct unless
+// it already has one, so the struct may then only be named in impl headers,
+// where the lifetime can be elided.
+//
+// ```
+// struct Person {
+//     name: <|>String,
+// }
+//
+// impl Person {
+//     fn new(name: String) -> Self {
+//         Person { name }
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Person<'a> {
+//     name: std::borrow::Cow<'a, str>,
+// }
+//
+// impl Person<'_> {
+//     fn new(name: String) -> Self {
+//         Person { name: name.into() }
+//     }
+// }
+// ```
+pub(crate) fn convert_field_to_cow(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (field, type_ref, field_syntax) = field_at_offset(&ctx)?;
+    let borrowed = borrowed_type(&type_ref)?;
+    let strukt = ast::StructDef::cast(field_syntax.parent()?.parent()?)?;
+    let struct_name = strukt.name()?;
+
+    let lifetimes = match strukt.type_param_list() {
+        Some(it) => it.lifetime_params().collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    // With several lifetimes, we can't tell which one the data should live for.
+    let (lifetime, is_new_lifetime) = match lifetimes.as_slice() {
+        [] => ("'a".to_string(), true),
+        [it] => (it.lifetime_token()?.text().to_string(), false),
+        _ => return None,
+    };
+
+    let variant: VariantDef =
+        ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), strukt.clone()))?.into();
+    let usages = FieldUsages::find(&ctx, &field, &field_syntax, variant)?;
+
+    let root = field_syntax.ancestors().last()?;
+    let cow = if root.descendants().filter_map(ast::UseItem::cast).any(|it| imports_cow(&it)) {
+        "Cow"
+    } else {
+        "std::borrow::Cow"
+    };
+
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    if is_new_lifetime {
+        // Other files may name the struct too.
+        if strukt.visibility().is_some() {
+            return None;
+        }
+        match strukt.type_param_list().and_then(|it| it.syntax().first_token()) {
+            Some(l_angle) => edits.push((empty_at(l_angle.text_range().end()), "'a, ".to_string())),
+            None => edits.push((empty_at(struct_name.syntax().text_range().end()), "<'a>".into())),
+        }
+        // Impl headers can elide the new lifetime, but elsewhere it would have
+        // to be chosen.
+        for path_type in root.descendants().filter_map(ast::PathType::cast) {
+            let segment = match path_type.path().and_then(|it| it.segment()) {
+                Some(it) => it,
+                None => continue,
+            };
+            if segment
+                .name_ref()
+                .map_or(true, |it| it.text().as_str() != struct_name.text().as_str())
+            {
+                continue;
+            }
+            let is_impl_target = path_type
+                .syntax()
+                .parent()
+                .and_then(ast::ImplBlock::cast)
+                .and_then(|it| it.target_type())
+                .map_or(false, |it| it.syntax() == path_type.syntax());
+            if !is_impl_target {
+                return None;
+            }
+            match segment.type_arg_list().and_then(|it| it.syntax().first_token()) {
+                Some(l_angle) => edits.push((empty_at(l_angle.text_range().end()), "'_, ".into())),
+                None => edits.push((empty_at(segment.syntax().text_range().end()), "<'_>".into())),
+            }
+        }
+    }
+    let new_type = format!("{}<{}, {}>", cow, lifetime, borrowed);
+    edits.push((type_ref.syntax().text_range(), new_type));
+    for expr in &usages.initializers {
+        edits.push((
+            expr.syntax().text_range(),
+            format!("{}.into()", ExprPrecedence::Postfix.parenthesize(expr)),
+        ));
+    }
+    for name_ref in &usages.shorthands {
+        let conversion = format!(": {}.into()", name_ref.text());
+        edits.push((empty_at(name_ref.syntax().text_range().end()), conversion));
+    }
+    for access in &usages.reads {
+        let parent = access.syntax().parent();
+        if parent.as_ref().map_or(false, |it| ast::RefExpr::can_cast(it.kind())) {
+            continue;
+        }
+        let assignment = parent.and_then(ast::BinExpr::cast).filter(|it| {
+            it.op_kind() == Some(ast::BinOp::Assignment)
+                && it.lhs().map_or(false, |lhs| lhs.syntax() == access.syntax())
+        });
+        match assignment.and_then(|it| it.rhs()) {
+            Some(rhs) => {
+                let conversion = format!("{}.into()", ExprPrecedence::Postfix.parenthesize(&rhs));
+                edits.push((rhs.syntax().text_range(), conversion))
+            }
+            None => {
+                edits.push((empty_at(access.syntax().text_range().end()), ".into_owned()".into()))
+            }
+        }
+    }
+
+    // Place the cursor on the lifetime, accounting for the edits before it.
+    let type_start = type_ref.syntax().text_range().start();
+    let mut cursor = type_start + TextUnit::of_str(cow) + TextUnit::of_char('<');
+    for (range, text) in &edits {
+        if range.end() <= type_start {
+            cursor = cursor + TextUnit::of_str(text) - range.len();
+        }
+    }
+
+    ctx.add_assist(AssistId("convert_field_to_cow"), "Use Cow", |edit| {
+        edit.target(field_syntax.text_range());
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+/// For `String` and `Vec<T>`, returns `str` and `[T]` respectively.
+fn borrowed_type(type_ref: &ast::TypeRef) -> Option<String> {
+    if let Some(item) = single_type_arg(type_ref, "Vec") {
+        return Some(format!("[{}]", item.syntax()));
+    }
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text().as_str() == "String" && segment.type_arg_list().is_none() {
+        Some("str".to_string())
+    } else {
+        None
+    }
+}
+
+fn imports_cow(use_item: &ast::UseItem) -> bool {
+    let text = use_item.syntax().text().to_string();
+    text.contains("borrow::Cow") || (text.contains("borrow::{") && text.contains("Cow"))
+}
+
+fn empty_at(offset: TextUnit) -> TextRange {
+    TextRange::offset_len(offset, 0.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_string_field_to_cow() {
+        check_assist(
+            convert_field_to_cow,
+            r#"
+struct Person { name: <|>String, age: u32 }
+impl Person {
+    fn new(name: String) -> Self { Person { name, age: 0 } }
+    fn into_name(self) -> String { self.name }
+    fn name(&self) -> &str { &self.name }
+    fn rename(&mut self, name: String) { self.name = name; }
+}
+"#,
+            r#"
+struct Person<'a> { name: std::borrow::Cow<<|>'a, str>, age: u32 }
+impl Person<'_> {
+    fn new(name: String) -> Self { Person { name: name.into(), age: 0 } }
+    fn into_name(self) -> String { self.name.into_owned() }
+    fn name(&self) -> &str { &self.name }
+    fn rename(&mut self, name: String) { self.name = name.into(); }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_vec_field_to_cow_with_existing_lifetime() {
+        check_assist(
+            convert_field_to_cow,
+            r#"
+struct Buf<'s, T> { label: &'s str, items: Vec<|><T> }
+fn make<'s, T>(label: &'s str, items: Vec<T>) -> Buf<'s, T> { Buf { label, items: items } }
+"#,
+            r#"
+struct Buf<'s, T> { label: &'s str, items: std::borrow::Cow<<|>'s, [T]> }
+fn make<'s, T>(label: &'s str, items: Vec<T>) -> Buf<'s, T> { Buf { label, items: items.into() } }
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_tuple_field_to_imported_cow() {
+        check_assist(
+            convert_field_to_cow,
+            r#"
+use std::borrow::Cow;
+struct Name<T>(<|>String, T);
+impl<T> Name<T> {}
+impl Name<u8> { fn new(s: String) -> Self { Name(s, 0) } }
+"#,
+            r#"
+use std::borrow::Cow;
+struct Name<'a, T>(Cow<<|>'a, str>, T);
+impl<T> Name<'_, T> {}
+impl Name<'_, u8> { fn new(s: String) -> Self { Name(s.into(), 0) } }
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_field_to_cow_target() {
+        check_assist_target(convert_field_to_cow, "struct S { name: <|>String }", "name: String");
+    }
+
+    #[test]
+    fn convert_field_to_cow_not_applicable_with_several_lifetimes() {
+        check_assist_not_applicable(
+            convert_field_to_cow,
+            "struct S<'a, 'b> { a: &'a str, b: &'b str, name: <|>String }",
+        );
+    }
+
+    #[test]
+    fn convert_field_to_cow_not_applicable_when_struct_is_named() {
+        check_assist_not_applicable(
+            convert_field_to_cow,
+            "struct S { name: <|>String }\nfn s(name: String) -> S { S { name } }",
+        );
+        check_assist_not_applicable(convert_field_to_cow, "pub struct S { name: <|>String }");
+    }
+
+    #[test]
+    fn convert_field_to_cow_not_applicable_for_other_types() {
+        check_assist_not_applicable(convert_field_to_cow, "struct S { size: <|>u32 }");
+        check_assist_not_applicable(convert_field_to_cow, "enum E { V { name: <|>String } }");
+    }
+}
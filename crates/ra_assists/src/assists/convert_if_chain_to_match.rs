@@ -0,0 +1,355 @@
+use format_buf::format;
+use hir::{db::HirDatabase, HirDisplay};
+use ra_fmt::extract_trivial_expression;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxNode,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_if_chain_to_match
+//
+// Replaces a chain of `if`s comparing the same value against constants with a
+// `match` on range and literal patterns.
+//
+// ```
+// fn grade(score: u32) -> char {
+//     <|>if score < 50 {
+//         'F'
+//     } else if score >= 50 && score < 80 {
+//         'B'
+//     } else if score == 100 {
+//         'S'
+//     } else {
+//         'A'
+//     }
+// }
+// ```
+// ->
+// ```
+// fn grade(score: u32) -> char {
+//     match score {
+//         0..=49 => 'F',
+//         50..=79 => 'B',
+//         100 => 'S',
+//         _ => 'A',
+//     }
+// }
+// ```
+pub(crate) fn convert_if_chain_to_match(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
+    if ctx.frange.range.start() >= if_expr.then_branch()?.syntax().text_range().start() {
+        return None;
+    }
+    let if_expr = if_expr
+        .syntax()
+        .ancestors()
+        .take_while(|it| ast::IfExpr::can_cast(it.kind()))
+        .last()
+        .and_then(ast::IfExpr::cast)?;
+
+    let mut subject = None;
+    let mut arms = Vec::new();
+    let mut current = if_expr.clone();
+    let else_block = loop {
+        let cond = current.condition()?;
+        if cond.pat().is_some() {
+            return None;
+        }
+        let values = alternatives(&cond.expr()?, &mut subject)?;
+        arms.push((values, current.then_branch()?));
+        match current.else_branch() {
+            Some(ast::ElseBranch::IfExpr(it)) => current = it,
+            Some(ast::ElseBranch::Block(it)) => break Some(it),
+            None => break None,
+        }
+    };
+    if arms.len() < 2 {
+        return None;
+    }
+    let subject = subject?;
+    match subject {
+        ast::Expr::PathExpr(_) | ast::Expr::FieldExpr(_) => (),
+        _ => return None,
+    }
+
+    // Open ranges are bounded by the limits of the type.
+    let ty = ctx
+        .source_analyzer(subject.syntax(), None)
+        .type_of(ctx.db, &subject)
+        .map(|it| it.display(ctx.db).to_string());
+    let ty = ty.as_ref().map(String::as_str);
+    let mut patterns = Vec::new();
+    for (values, _) in &arms {
+        let alternatives = values.iter().map(|it| it.to_pattern(ty)).collect::<Option<Vec<_>>>()?;
+        patterns.push(alternatives.join(" | "));
+    }
+
+    ctx.add_assist(AssistId("convert_if_chain_to_match"), "Convert to match", |edit| {
+        let mut buf = String::new();
+        format!(buf, "match {} {{\n", subject.syntax());
+        for (pattern, (_, block)) in patterns.iter().zip(&arms) {
+            format!(buf, "    {} => {}\n", pattern, format_arm(block));
+        }
+        match &else_block {
+            Some(block) => format!(buf, "    _ => {}\n", format_arm(block)),
+            None => buf.push_str("    _ => {}\n"),
+        }
+        buf.push_str("}");
+        edit.target(if_expr.syntax().text_range());
+        edit.replace_node_and_indent(if_expr.syntax(), buf);
+        edit.set_cursor(if_expr.syntax().text_range().start())
+    })
+}
+
+fn format_arm(block: &ast::BlockExpr) -> String {
+    match extract_trivial_expression(block) {
+        Some(e) if !e.syntax().text().contains_char('\n') => format!("{},", e.syntax().text()),
+        _ => block.syntax().text().to_string(),
+    }
+}
+
+/// A constant, with its value if it is an integer.
+struct Constant {
+    text: String,
+    value: Option<i128>,
+}
+
+impl Constant {
+    fn offset(&self, by: i128) -> Option<Constant> {
+        let value = self.value? + by;
+        Some(Constant { text: value.to_string(), value: Some(value) })
+    }
+}
+
+/// The values matched by a comparison, with inclusive bounds.
+enum Values {
+    Exact(Constant),
+    Range { start: Option<Constant>, end: Option<Constant> },
+}
+
+impl Values {
+    fn to_pattern(&self, ty: Option<&str>) -> Option<String> {
+        let (start, end) = match self {
+            Values::Exact(it) => return Some(it.text.clone()),
+            Values::Range { start, end } => (start, end),
+        };
+        let start = match start {
+            Some(it) => it.text.clone(),
+            None => type_limits(ty?)?.0,
+        };
+        let end = match end {
+            Some(it) => it.text.clone(),
+            None => type_limits(ty?)?.1,
+        };
+        if start == end {
+            return Some(start);
+        }
+        Some(format!("{}..={}", start, end))
+    }
+}
+
+fn type_limits(ty: &str) -> Option<(String, String)> {
+    let res = match ty {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+            ("0".to_string(), format!("std::{}::MAX", ty))
+        }
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
+            (format!("std::{}::MIN", ty), format!("std::{}::MAX", ty))
+        }
+        "char" => ("'\\0'".to_string(), "std::char::MAX".to_string()),
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// Returns the values of the condition, joined with `||`.
+fn alternatives(cond: &ast::Expr, subject: &mut Option<ast::Expr>) -> Option<Vec<Values>> {
+    match cond {
+        ast::Expr::ParenExpr(it) => alternatives(&it.expr()?, subject),
+        ast::Expr::BinExpr(it) if it.op_kind()? == ast::BinOp::BooleanOr => {
+            let mut res = alternatives(&it.lhs()?, subject)?;
+            res.extend(alternatives(&it.rhs()?, subject)?);
+            Some(res)
+        }
+        ast::Expr::BinExpr(it) if it.op_kind()? == ast::BinOp::BooleanAnd => {
+            let lhs = comparison(&it.lhs()?, subject)?;
+            let rhs = comparison(&it.rhs()?, subject)?;
+            let res = match (lhs, rhs) {
+                (
+                    Values::Range { start: Some(start), end: None },
+                    Values::Range { start: None, end: Some(end) },
+                )
+                | (
+                    Values::Range { start: None, end: Some(end) },
+                    Values::Range { start: Some(start), end: None },
+                ) => Values::Range { start: Some(start), end: Some(end) },
+                _ => return None,
+            };
+            Some(vec![res])
+        }
+        _ => Some(vec![comparison(cond, subject)?]),
+    }
+}
+
+fn comparison(cond: &ast::Expr, subject: &mut Option<ast::Expr>) -> Option<Values> {
+    let bin_expr = match cond {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    let (lhs, rhs) = (bin_expr.lhs()?, bin_expr.rhs()?);
+    let (operand, value, flipped) = match constant(&rhs) {
+        Some(it) => (lhs, it, false),
+        None => (rhs, constant(&lhs)?, true),
+    };
+    match subject {
+        Some(it) if !is_same(it.syntax(), operand.syntax()) => return None,
+        Some(_) => (),
+        None => *subject = Some(operand),
+    }
+
+    use ast::BinOp::*;
+    let op = match (bin_expr.op_kind()?, flipped) {
+        (LesserTest, true) => GreaterTest,
+        (LesserEqualTest, true) => GreaterEqualTest,
+        (GreaterTest, true) => LesserTest,
+        (GreaterEqualTest, true) => LesserEqualTest,
+        (op, _) => op,
+    };
+    let res = match op {
+        EqualityTest => Values::Exact(value),
+        LesserTest => Values::Range { start: None, end: Some(value.offset(-1)?) },
+        LesserEqualTest => Values::Range { start: None, end: Some(value) },
+        GreaterTest => Values::Range { start: Some(value.offset(1)?), end: None },
+        GreaterEqualTest => Values::Range { start: Some(value), end: None },
+        _ => return None,
+    };
+    Some(res)
+}
+
+fn constant(expr: &ast::Expr) -> Option<Constant> {
+    match expr {
+        ast::Expr::Literal(it) => match it.kind() {
+            ast::LiteralKind::Char => Some(Constant { text: it.syntax().to_string(), value: None }),
+            ast::LiteralKind::IntNumber { suffix } => {
+                let text = it.syntax().to_string();
+                let digits = &text[..text.len() - suffix.map_or(0, |it| it.len())];
+                let value = digits.replace('_', "").parse::<i128>().ok();
+                Some(Constant { text, value })
+            }
+            _ => None,
+        },
+        ast::Expr::PrefixExpr(it) if it.op_kind()? == ast::PrefixOp::Neg => {
+            let value = constant(&it.expr()?)?.value?;
+            Some(Constant { text: it.syntax().to_string(), value: Some(-value) })
+        }
+        _ => None,
+    }
+}
+
+fn is_same(lhs: &SyntaxNode, rhs: &SyntaxNode) -> bool {
+    let normalize =
+        |node: &SyntaxNode| node.text().to_string().split_whitespace().collect::<String>();
+    normalize(lhs) == normalize(rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_if_chain_with_open_ranges() {
+        check_assist(
+            convert_if_chain_to_match,
+            r#"
+fn f(x: i32) -> i32 {
+    <|>if x < 0 {
+        -1
+    } else if 10 < x {
+        1
+    } else if x == 5 {
+        let y = 2;
+        y
+    } else {
+        0
+    }
+}
+"#,
+            r#"
+fn f(x: i32) -> i32 {
+    <|>match x {
+        std::i32::MIN..=-1 => -1,
+        11..=std::i32::MAX => 1,
+        5 => {
+            let y = 2;
+            y
+        }
+        _ => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_with_alternatives() {
+        check_assist(
+            convert_if_chain_to_match,
+            r#"
+fn f(c: char) {
+    if c == 'a' || c == 'b' {
+        g()
+    } else if<|> c <= 'z' && c >= 'x' {
+        h()
+    }
+}
+"#,
+            r#"
+fn f(c: char) {
+    <|>match c {
+        'a' | 'b' => g(),
+        'x'..='z' => h(),
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_to_match_target() {
+        check_assist_target(
+            convert_if_chain_to_match,
+            "fn f(x: u8) { <|>if x == 1 {} else if x == 2 {} }",
+            "if x == 1 {} else if x == 2 {}",
+        );
+    }
+
+    #[test]
+    fn convert_if_chain_to_match_not_applicable() {
+        // Different values.
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f(x: u8, y: u8) { <|>if x == 1 {} else if y == 2 {} }",
+        );
+        // Not a constant.
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f(x: u8, y: u8) { <|>if x == y {} else if x == 2 {} }",
+        );
+        // Not a pattern.
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f(x: u8) { <|>if x != 1 {} else if x == 2 {} }",
+        );
+        // No chain.
+        check_assist_not_applicable(convert_if_chain_to_match, "fn f(x: u8) { <|>if x == 1 {} }");
+        // An open range over an unknown type.
+        check_assist_not_applicable(
+            convert_if_chain_to_match,
+            "fn f(x: Foo) { <|>if x < 1 {} else if x == 2 {} }",
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode},
+    T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_match_to_if_chain
+//
+// Replaces a `match` on literal and range patterns with a chain of `if`s
+// comparing the value.
+//
+// ```
+// fn size(n: u32) -> &'static str {
+//     <|>match n {
+//         0 => "none",
+//         1..=9 => "small",
+//         _ => "large",
+//     }
+// }
+// ```
+// ->
+// ```
+// fn size(n: u32) -> &'static str {
+//     if n == 0 {
+//         "none"
+//     } else if n >= 1 && n <= 9 {
+//         "small"
+//     } else {
+//         "large"
+//     }
+// }
+// ```
+pub(crate) fn convert_match_to_if_chain(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+    if ctx.frange.range.start() >= arm_list.syntax().text_range().start() {
+        return None;
+    }
+    // The value is compared once per arm, so it has to be a place.
+    let subject = match match_expr.expr()? {
+        it @ ast::Expr::PathExpr(_) | it @ ast::Expr::FieldExpr(_) => it,
+        _ => return None,
+    };
+    let subject = subject.syntax().to_string();
+
+    let arms = arm_list.arms().collect::<Vec<_>>();
+    let (last, arms) = arms.split_last()?;
+    if arms.is_empty() {
+        return None;
+    }
+    let mut branches = Vec::new();
+    for arm in arms {
+        let alternatives =
+            arm.pats().map(|it| condition(&it, &subject)).collect::<Option<Vec<_>>>()?;
+        let mut cond = match alternatives.len() {
+            0 => return None,
+            1 => alternatives.join(""),
+            _ => alternatives.join(" || "),
+        };
+        if let Some(guard) = arm.guard() {
+            if alternatives.len() > 1 {
+                cond = format!("({})", cond);
+            }
+            format!(cond, " && {}", guard.expr()?.syntax());
+        }
+        branches.push((cond, body(arm)?));
+    }
+    // A match is exhaustive, so the last arm is what remains.
+    if last.guard().is_some() {
+        return None;
+    }
+    for pat in last.pats() {
+        match pat {
+            ast::Pat::PlaceholderPat(_) => (),
+            it => {
+                condition(&it, &subject)?;
+            }
+        }
+    }
+    let else_body = body(last)?;
+
+    ctx.add_assist(AssistId("convert_match_to_if_chain"), "Convert to if/else", |edit| {
+        let mut buf = String::new();
+        for (i, (cond, body)) in branches.iter().enumerate() {
+            if i > 0 {
+                buf.push_str(" else ");
+            }
+            format!(buf, "if {} {}", cond, block(body));
+        }
+        format!(buf, " else {}", block(&else_body));
+        edit.target(match_expr.syntax().text_range());
+        edit.replace_node_and_indent(match_expr.syntax(), buf);
+        edit.set_cursor(match_expr.syntax().text_range().start())
+    })
+}
+
+/// Returns the comparison equivalent to a literal or range pattern.
+fn condition(pat: &ast::Pat, subject: &str) -> Option<String> {
+    let res = match pat {
+        ast::Pat::LiteralPat(it) => format!("{} == {}", subject, it.syntax()),
+        ast::Pat::RangePat(it) => {
+            let mut bounds = it.syntax().children().filter_map(ast::Pat::cast);
+            let (start, end) = (bounds.next()?, bounds.next()?);
+            for bound in &[&start, &end] {
+                match bound {
+                    ast::Pat::LiteralPat(_) | ast::Pat::PathPat(_) => (),
+                    _ => return None,
+                }
+            }
+            let is_inclusive = it
+                .syntax()
+                .children_with_tokens()
+                .any(|it| it.kind() == T![..=] || it.kind() == T![...]);
+            let end_op = if is_inclusive { "<=" } else { "<" };
+            format!("{} >= {} && {} {} {}", subject, start.syntax(), subject, end_op, end.syntax())
+        }
+        _ => return None,
+    };
+    Some(res)
+}
+
+fn block(body: &str) -> String {
+    if body.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n}}", body)
+    }
+}
+
+/// Returns the body of the arm as the indented contents of a block.
+fn body(arm: &ast::MatchArm) -> Option<String> {
+    let expr = arm.expr()?;
+    let arm_indent = leading_indent(arm.syntax()).unwrap_or_default();
+    let (text, indent) = match &expr {
+        ast::Expr::BlockExpr(it) => {
+            let text = it.syntax().to_string();
+            let inner = text.trim_start_matches('{').trim_end_matches('}').trim().to_string();
+            (inner, format!("{}    ", arm_indent))
+        }
+        _ => (expr.syntax().to_string(), arm_indent.to_string()),
+    };
+    let mut res = String::new();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            res.push('\n');
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line = if i > 0 && line.starts_with(&indent) { &line[indent.len()..] } else { line };
+        format!(res, "    {}", line);
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_match_to_if_chain_with_blocks() {
+        check_assist(
+            convert_match_to_if_chain,
+            r#"
+fn f(c: char) {
+    if true {
+        <|>match c {
+            'a' | 'b' if flag => {
+                let x = 1;
+                g(x);
+            }
+            'x'...'z' => h(),
+            '0'..'9' => h()
+                .unwrap(),
+            _ => {}
+        }
+    }
+}
+"#,
+            r#"
+fn f(c: char) {
+    if true {
+        <|>if (c == 'a' || c == 'b') && flag {
+            let x = 1;
+            g(x);
+        } else if c >= 'x' && c <= 'z' {
+            h()
+        } else if c >= '0' && c < '9' {
+            h()
+                .unwrap()
+        } else {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_match_to_if_chain_without_wildcard() {
+        check_assist(
+            convert_match_to_if_chain,
+            "fn f(x: u8) -> u8 { <|>match x { 0 => 1, 1..=255 => 0 } }",
+            "fn f(x: u8) -> u8 { <|>if x == 0 {\n    1\n} else {\n    0\n} }",
+        );
+    }
+
+    #[test]
+    fn convert_match_to_if_chain_target() {
+        check_assist_target(
+            convert_match_to_if_chain,
+            "fn f(x: u8) { <|>match x { 1 => (), _ => () } }",
+            "match x { 1 => (), _ => () }",
+        );
+    }
+
+    #[test]
+    fn convert_match_to_if_chain_not_applicable() {
+        check_assist_not_applicable(
+            convert_match_to_if_chain,
+            "fn f(x: u8) { <|>match x { 1 => (), y => () } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_if_chain,
+            "fn f(x: E) { <|>match x { E::A => (), _ => () } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_if_chain,
+            "fn f() { <|>match g() { 1 => (), _ => () } }",
+        );
+        check_assist_not_applicable(
+            convert_match_to_if_chain,
+            "fn f(x: u8) { <|>match x { _ => () } }",
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{
+    utils::{has_side_effects, is_copy_type, AccumulatorLoop},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_to_any_or_find
+//
+// Converts a `for` loop which searches for an item into a call to `any` or
+// `find`.
+//
+// ```
+// fn main() {
+//     let mut has_admin = false;
+//     <|>for user in users {
+//         if user.is_admin() {
+//             has_admin = true;
+//             break;
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let has_admin = users.into_iter().any(|user| user.is_admin());
+// }
+// ```
+pub(crate) fn convert_to_any_or_find(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let acc_loop = AccumulatorLoop::find(&ctx)?;
+    let if_expr = match acc_loop.body_expr()? {
+        ast::Expr::IfExpr(it) => it,
+        _ => return None,
+    };
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond = cond.expr()?;
+    if has_side_effects(cond.syntax()) {
+        return None;
+    }
+    // The accumulator is set, and the loop possibly left.
+    let block = if_expr.then_branch()?.block()?;
+    let mut exprs = Vec::new();
+    for stmt in block.statements() {
+        match stmt {
+            ast::Stmt::ExprStmt(it) => exprs.push(it.expr()?),
+            ast::Stmt::LetStmt(_) => return None,
+        }
+    }
+    exprs.extend(block.expr());
+    let (assign, breaks) = match exprs.as_slice() {
+        [ast::Expr::BinExpr(assign)] => (assign.clone(), false),
+        [ast::Expr::BinExpr(assign), ast::Expr::BreakExpr(it)] => {
+            // A labeled `break` may leave an outer loop too.
+            if it.syntax().text() != "break" {
+                return None;
+            }
+            (assign.clone(), true)
+        }
+        _ => return None,
+    };
+    if assign.op_kind()? != ast::BinOp::Assignment || !acc_loop.is_acc(&assign.lhs()?) {
+        return None;
+    }
+    let loop_range = acc_loop.for_expr.syntax().text_range();
+    if acc_loop.refs.iter().filter(|it| it.is_subrange(&loop_range)).count() != 1 {
+        return None;
+    }
+
+    let pat = acc_loop.for_expr.pat()?;
+    let init = acc_loop.init.syntax().text();
+    let value = assign.rhs()?;
+    let mut chain = acc_loop.iterator()?;
+    let label = if init == "false" && value.syntax().text() == "true" {
+        chain.push_str(&format!(".any(|{}| {})", pat.syntax(), cond.syntax()));
+        "Use any()"
+    } else if init == "None" && breaks {
+        // The item itself is captured.
+        let captured = match value {
+            ast::Expr::CallExpr(call) => {
+                let mut args = call.arg_list()?.args();
+                match (call.expr()?, args.next(), args.next()) {
+                    (ast::Expr::PathExpr(path), Some(arg), None)
+                        if path.syntax().text() == "Some" =>
+                    {
+                        arg
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        let is_item = match (&pat, &captured) {
+            (ast::Pat::BindPat(bind_pat), ast::Expr::PathExpr(path)) => {
+                bind_pat.pat().is_none()
+                    && !bind_pat.is_mutable()
+                    && !bind_pat.is_ref()
+                    && path.syntax().text() == pat.syntax().text()
+            }
+            _ => false,
+        };
+        if !is_item {
+            return None;
+        }
+        // `find` passes items by reference, destructure the reference if the
+        // item can be copied out of it.
+        let db = ctx.db;
+        let analyzer = ctx.source_analyzer(acc_loop.for_expr.syntax(), None);
+        let param = match analyzer.type_of_pat(db, &pat) {
+            Some(item_ty) if is_copy_type(db, &item_ty) => {
+                format!("&{}", pat.syntax())
+            }
+            _ => pat.syntax().to_string(),
+        };
+        chain.push_str(&format!(".find(|{}| {})", param, cond.syntax()));
+        "Use find()"
+    } else {
+        return None;
+    };
+
+    ctx.add_assist(AssistId("convert_to_any_or_find"), label, |edit| {
+        edit.target(acc_loop.range);
+        edit.replace(acc_loop.range, acc_loop.let_with_value(chain));
+        edit.set_cursor(acc_loop.range.start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_to_any_without_break() {
+        check_assist(
+            convert_to_any_or_find,
+            r#"
+fn f(xs: &[u32]) -> bool {
+    let mut found = false;
+    <|>for x in xs {
+        if *x > 10 { found = true }
+    }
+    found
+}
+"#,
+            r#"
+fn f(xs: &[u32]) -> bool {
+    <|>let found = xs.into_iter().any(|x| *x > 10);
+    found
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_find() {
+        check_assist(
+            convert_to_any_or_find,
+            r#"
+fn f(ids: Vec<u32>) {
+    let mut first = None;
+    <|>for id in &ids {
+        if id % 2 == 0 {
+            first = Some(id);
+            break;
+        }
+    }
+}
+"#,
+            r#"
+fn f(ids: Vec<u32>) {
+    <|>let first = ids.iter().find(|&id| id % 2 == 0);
+}
+"#,
+        );
+        check_assist(
+            convert_to_any_or_find,
+            "fn f() {\n    let mut first = None;\n    <|>for n in 0..10u32 {\n        if n > 3 {\n            first = Some(n);\n            break\n        }\n    }\n}",
+            "fn f() {\n    <|>let first = (0..10u32).find(|&n| n > 3);\n}",
+        );
+    }
+
+    #[test]
+    fn convert_to_any_or_find_target() {
+        check_assist_target(
+            convert_to_any_or_find,
+            "fn f(xs: &[u32]) { let mut b = false; <|>for x in xs { if *x == 0 { b = true; } } }",
+            "let mut b = false; for x in xs { if *x == 0 { b = true; } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_any_or_find_not_applicable() {
+        for before in &[
+            // Other side effects.
+            "fn f(xs: &[u32]) { let mut b = false; <|>for x in xs { if *x == 0 { b = true; log(x); } } }",
+            "fn f(xs: &[u32]) { let mut b = false; <|>for x in xs { if check(x)? { b = true; } } }",
+            // The last match.
+            "fn f(xs: &[u32]) { let mut b = None; <|>for x in xs { if *x == 0 { b = Some(x); } } }",
+            // Not the item.
+            "fn f(xs: &[u32]) { let mut b = None; <|>for x in xs { if *x == 0 { b = Some(*x); break; } } }",
+            "fn f(xs: &[u32]) { let mut b = true; <|>for x in xs { if *x == 0 { b = false; break; } } }",
+            "fn f(xs: &[u32]) { let mut b = false; 'l: <|>for x in xs { if *x == 0 { b = true; break 'l; } } }",
+        ] {
+            check_assist_not_applicable(convert_to_any_or_find, before);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::ast::{self, AstNode, TypeAscriptionOwner};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{has_side_effects, is_copy_type, single_expr, AccumulatorLoop},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_to_count_or_sum
+//
+// Converts a `for` loop which only counts or sums up items into an iterator
+// chain.
+//
+// ```
+// fn main() {
+//     let mut total: u32 = 0;
+//     <|>for item in items {
+//         if item.is_valid() {
+//             total += item.size;
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let total: u32 = items.into_iter().filter(|item| item.is_valid()).map(|item| item.size).sum();
+// }
+// ```
+pub(crate) fn convert_to_count_or_sum(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let acc_loop = AccumulatorLoop::find(&ctx)?;
+    let (guard, update) = match acc_loop.body_expr()? {
+        ast::Expr::IfExpr(if_expr) => {
+            if if_expr.else_branch().is_some() {
+                return None;
+            }
+            let cond = if_expr.condition()?;
+            if cond.pat().is_some() {
+                return None;
+            }
+            (Some(cond.expr()?), single_expr(&if_expr.then_branch()?)?)
+        }
+        it => (None, it),
+    };
+    let update = match update {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    if update.op_kind()? != ast::BinOp::AddAssign || !acc_loop.is_acc(&update.lhs()?) {
+        return None;
+    }
+    let rhs = update.rhs()?;
+    if has_side_effects(rhs.syntax())
+        || guard.as_ref().map_or(false, |it| has_side_effects(it.syntax()))
+    {
+        return None;
+    }
+    // The accumulator may only be touched by the update itself.
+    let loop_range = acc_loop.for_expr.syntax().text_range();
+    if acc_loop.refs.iter().filter(|it| it.is_subrange(&loop_range)).count() != 1 {
+        return None;
+    }
+
+    let db = ctx.db;
+    let pat = acc_loop.for_expr.pat()?;
+    let analyzer = ctx.source_analyzer(acc_loop.for_expr.syntax(), None);
+    let acc_ty = analyzer.type_of_pat(db, &acc_loop.acc.clone().into())?;
+    if acc_ty.contains_unknown() {
+        return None;
+    }
+    let acc_ty = acc_ty.display(db).to_string();
+    // `filter` passes items by reference, destructure the reference if the
+    // item can be copied out of it.
+    let filter_param = match (&pat, analyzer.type_of_pat(db, &pat)) {
+        (ast::Pat::BindPat(_), Some(item_ty)) if is_copy_type(db, &item_ty) => {
+            format!("&{}", pat.syntax())
+        }
+        _ => pat.syntax().to_string(),
+    };
+    let is_count = match &rhs {
+        ast::Expr::Literal(it) => it.syntax().text() == "1",
+        _ => false,
+    };
+    let is_zero_init = match &acc_loop.init {
+        ast::Expr::Literal(it) => is_zero(&it.syntax().text().to_string()),
+        _ => false,
+    };
+    let is_typed = acc_loop.let_stmt.ascribed_type().is_some() && is_zero_init;
+
+    let mut chain = acc_loop.iterator()?;
+    if let Some(guard) = &guard {
+        chain.push_str(&format!(".filter(|{}| {})", filter_param, guard.syntax()));
+    }
+    let label = if is_count {
+        chain.push_str(".count()");
+        if acc_ty != "usize" {
+            chain = format!("{} as {}", chain, acc_ty);
+        }
+        "Use count()"
+    } else {
+        let is_item = match &rhs {
+            ast::Expr::PathExpr(it) => it.syntax().to_string() == pat.syntax().to_string(),
+            _ => false,
+        };
+        if !is_item {
+            chain.push_str(&format!(".map(|{}| {})", pat.syntax(), rhs.syntax()));
+        }
+        if is_typed {
+            chain.push_str(".sum()");
+        } else {
+            chain.push_str(&format!(".sum::<{}>()", acc_ty));
+        }
+        "Use sum()"
+    };
+    let value = if is_zero_init {
+        chain
+    } else {
+        format!("{} + {}", ExprPrecedence::Sum.parenthesize(&acc_loop.init), chain)
+    };
+
+    ctx.add_assist(AssistId("convert_to_count_or_sum"), label, |edit| {
+        edit.target(acc_loop.range);
+        edit.replace(acc_loop.range, acc_loop.let_with_value(value));
+        edit.set_cursor(acc_loop.range.start());
+    })
+}
+
+fn is_zero(literal: &str) -> bool {
+    let digits = literal.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '_');
+    !digits.is_empty() && digits.chars().all(|c| c == '0' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_guarded_increment_to_count() {
+        check_assist(
+            convert_to_count_or_sum,
+            r#"
+fn f() {
+    let mut count: usize = 0;
+    <|>for x in xs {
+        if x.is_empty() {
+            count += 1;
+        }
+    }
+}
+"#,
+            r#"
+fn f() {
+    <|>let count: usize = xs.into_iter().filter(|x| x.is_empty()).count();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_increment_to_count_with_cast() {
+        check_assist(
+            convert_to_count_or_sum,
+            r#"
+fn f() {
+    let mut count = 0u32;
+    <|>for _ in &xs { count += 1 }
+}
+"#,
+            r#"
+fn f() {
+    <|>let count = xs.iter().count() as u32;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_mapped_sum() {
+        check_assist(
+            convert_to_count_or_sum,
+            r#"
+fn f() {
+    let mut total = 0u64;
+    <|>for item in items {
+        total += item.size;
+    }
+    total
+}
+"#,
+            r#"
+fn f() {
+    <|>let total = items.into_iter().map(|item| item.size).sum::<u64>();
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_sum_with_initial_value() {
+        check_assist(
+            convert_to_count_or_sum,
+            r#"
+fn f() {
+    let mut s: i64 = 10;
+    <|>for x in xs { s += x; }
+}
+"#,
+            r#"
+fn f() {
+    <|>let s: i64 = 10 + xs.into_iter().sum::<i64>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_count_or_sum_target() {
+        check_assist_target(
+            convert_to_count_or_sum,
+            "fn f() { let mut s = 0u8; <|>for x in xs { s += x; } }",
+            "let mut s = 0u8; for x in xs { s += x; }",
+        );
+    }
+
+    #[test]
+    fn convert_to_count_or_sum_not_applicable_for_other_updates() {
+        check_assist_not_applicable(
+            convert_to_count_or_sum,
+            "fn f() { let mut s = 0u8; <|>for x in xs { s *= x; } }",
+        );
+        check_assist_not_applicable(
+            convert_to_count_or_sum,
+            "fn f() { let mut s = 0u8; <|>for x in xs { s += x; foo(); } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_count_or_sum_not_applicable_if_guard_uses_accumulator() {
+        check_assist_not_applicable(
+            convert_to_count_or_sum,
+            "fn f() { let mut s = 0u8; <|>for x in xs { if s < x { s += 1 } } }",
+        );
+    }
+}
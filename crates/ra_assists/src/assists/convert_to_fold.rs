@@ -0,0 +1,175 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode, NameOwner};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{has_side_effects, AccumulatorLoop},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_to_fold
+//
+// Converts a `for` loop which only updates an accumulator into a `fold`.
+//
+// ```
+// fn main() {
+//     let mut sum = 0;
+//     <|>for x in &xs {
+//         sum = sum + x;
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let sum = xs.iter().fold(0, |sum, x| sum + x);
+// }
+// ```
+pub(crate) fn convert_to_fold(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let acc_loop = AccumulatorLoop::find(&ctx)?;
+    let assign = match acc_loop.body_expr()? {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    let op = assign.op_kind()?;
+    if !op.is_assignment() || !acc_loop.is_acc(&assign.lhs()?) {
+        return None;
+    }
+    let rhs = assign.rhs()?;
+    if has_side_effects(rhs.syntax()) {
+        return None;
+    }
+    let acc_name = acc_loop.acc.name()?;
+    let pat = acc_loop.for_expr.pat()?;
+    let iterator = acc_loop.iterator()?;
+
+    ctx.add_assist(AssistId("convert_to_fold"), "Convert to fold", |edit| {
+        let value = if op == ast::BinOp::Assignment {
+            rhs.syntax().to_string()
+        } else {
+            let op_token = assign.op_token().expect("assignment always has an operator");
+            let op_text = op_token.text().trim_end_matches('=');
+            // Unary operators bind tighter than any of the binary ones.
+            let rhs = ExprPrecedence::Prefix.parenthesize(&rhs);
+            format!("{} {} {}", acc_name.syntax(), op_text, rhs)
+        };
+        let fold = format!(
+            "{}.fold({}, |{}, {}| {})",
+            iterator,
+            acc_loop.init.syntax(),
+            acc_name.syntax(),
+            pat.syntax(),
+            value
+        );
+        edit.target(acc_loop.range);
+        edit.replace(acc_loop.range, acc_loop.let_with_value(fold));
+        edit.set_cursor(acc_loop.range.start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_assignment_to_fold() {
+        check_assist(
+            convert_to_fold,
+            r#"
+fn f() {
+    let mut acc = 1;
+    <|>for x in xs {
+        acc = combine(acc, x);
+    }
+    acc
+}
+"#,
+            r#"
+fn f() {
+    <|>let acc = xs.into_iter().fold(1, |acc, x| combine(acc, x));
+    acc
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_compound_assignment_to_fold() {
+        check_assist(
+            convert_to_fold,
+            r#"
+fn f() {
+    let mut product: u64 = 1;
+    <|>for i in 1..n { product *= i + 1 }
+    product
+}
+"#,
+            r#"
+fn f() {
+    <|>let product: u64 = (1..n).fold(1, |product, i| product * (i + 1));
+    product
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_fold_keeps_mut_if_modified_later() {
+        check_assist(
+            convert_to_fold,
+            r#"
+fn f() {
+    let mut acc = 0;
+    <|>for x in &mut xs.items {
+        acc += x.len();
+    }
+    acc += 1;
+}
+"#,
+            r#"
+fn f() {
+    <|>let mut acc = xs.items.iter_mut().fold(0, |acc, x| acc + x.len());
+    acc += 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_fold_target() {
+        check_assist_target(
+            convert_to_fold,
+            "fn f() { let mut s = 0; <|>for x in xs { s += x; } }",
+            "let mut s = 0; for x in xs { s += x; }",
+        );
+    }
+
+    #[test]
+    fn convert_to_fold_not_applicable_with_side_effects() {
+        check_assist_not_applicable(
+            convert_to_fold,
+            "fn f() { let mut s = 0; <|>for x in xs { s += x; println!(\"{}\", x); } }",
+        );
+        check_assist_not_applicable(
+            convert_to_fold,
+            "fn f() { let mut s = 0; <|>for x in xs { s += x?; } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_fold_not_applicable_for_other_variable() {
+        check_assist_not_applicable(
+            convert_to_fold,
+            "fn f() { let mut s = 0; let mut t = 0; <|>for x in xs { s += x; } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_fold_not_applicable_for_multiple_accumulators() {
+        check_assist_not_applicable(
+            convert_to_fold,
+            "fn f() { let mut s = 0; <|>for x in xs { s += x; t += x; } }",
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::ast::{self, AstNode, NameOwner};
+
+use crate::{
+    utils::{has_side_effects, single_expr, AccumulatorLoop},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_to_min_max
+//
+// Converts a `for` loop which keeps track of the smallest or largest item into
+// a call to `min`/`max`.
+//
+// ```
+// fn main() {
+//     let mut smallest: i32 = i32::MAX;
+//     <|>for x in xs {
+//         if x < smallest {
+//             smallest = x;
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let smallest: i32 = xs.into_iter().min().unwrap_or(i32::MAX);
+// }
+// ```
+pub(crate) fn convert_to_min_max(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let acc_loop = AccumulatorLoop::find(&ctx)?;
+    let if_expr = match acc_loop.body_expr()? {
+        ast::Expr::IfExpr(it) => it,
+        _ => return None,
+    };
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cmp = match cond.expr()? {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    let assign = match single_expr(&if_expr.then_branch()?)? {
+        ast::Expr::BinExpr(it) => it,
+        _ => return None,
+    };
+    if has_side_effects(cmp.syntax()) {
+        return None;
+    }
+
+    // The loop body must be `if <cmp> { acc = item }`.
+    let item = match acc_loop.for_expr.pat()? {
+        ast::Pat::BindPat(it) => it.name()?.text().to_string(),
+        _ => return None,
+    };
+    let acc = acc_loop.acc.name()?.text().to_string();
+    if assign.op_kind()? != ast::BinOp::Assignment
+        || !acc_loop.is_acc(&assign.lhs()?)
+        || assign.rhs()?.syntax().to_string() != item
+    {
+        return None;
+    }
+    let loop_range = acc_loop.for_expr.syntax().text_range();
+    if acc_loop.refs.iter().filter(|it| it.is_subrange(&loop_range)).count() != 2 {
+        return None;
+    }
+
+    // Normalize the comparison to `<item key> <op> <acc key>`.
+    let (lhs, rhs) = (cmp.lhs()?, cmp.rhs()?);
+    let (lhs_text, rhs_text) = (lhs.syntax().to_string(), rhs.syntax().to_string());
+    let (key, acc_side, item_is_lhs) = match (key_of(&lhs_text, &item), key_of(&rhs_text, &acc)) {
+        (Some(item_key), Some(acc_key)) if item_key == acc_key => (item_key, rhs, true),
+        _ => match (key_of(&lhs_text, &acc), key_of(&rhs_text, &item)) {
+            (Some(acc_key), Some(item_key)) if item_key == acc_key => (item_key, lhs, false),
+            _ => return None,
+        },
+    };
+    let (is_less, is_strict) = match cmp.op_kind()? {
+        ast::BinOp::LesserTest => (item_is_lhs, true),
+        ast::BinOp::LesserEqualTest => (item_is_lhs, false),
+        ast::BinOp::GreaterTest => (!item_is_lhs, true),
+        ast::BinOp::GreaterEqualTest => (!item_is_lhs, false),
+        _ => return None,
+    };
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(acc_loop.for_expr.syntax(), None);
+    let compared_ty = analyzer.type_of(db, &acc_side)?;
+    if compared_ty.contains_unknown() {
+        return None;
+    }
+    let compared_ty = compared_ty.display(db).to_string();
+    // Floats are only `PartialOrd`.
+    if compared_ty == "f32" || compared_ty == "f64" {
+        return None;
+    }
+    // `min` keeps the first of equal items, `max` the last one. It doesn't
+    // matter for plain primitives, but otherwise the loop has to agree.
+    let ties_matter = !key.is_empty() || !is_primitive(&compared_ty);
+    if ties_matter && is_strict != is_less {
+        return None;
+    }
+
+    let method = match (is_less, key.is_empty()) {
+        (true, true) => "min".to_string(),
+        (false, true) => "max".to_string(),
+        (true, false) => format!("min_by_key(|{}| {}{})", item, item, key),
+        (false, false) => format!("max_by_key(|{}| {}{})", item, item, key),
+    };
+    let iterator = acc_loop.iterator()?;
+    let init = acc_loop.init.syntax().to_string();
+    let sentinel = if is_less { "::MAX" } else { "::MIN" };
+    let value = if key.is_empty() && init.ends_with(sentinel) {
+        format!("{}.{}().unwrap_or({})", iterator, method_name(&method), init)
+    } else {
+        format!("std::iter::once({}).chain({}).{}.unwrap()", init, iterator, with_call(&method))
+    };
+    let label = format!("Use {}()", method_name(&method));
+
+    ctx.add_assist(AssistId("convert_to_min_max"), label, |edit| {
+        edit.target(acc_loop.range);
+        edit.replace(acc_loop.range, acc_loop.let_with_value(value));
+        edit.set_cursor(acc_loop.range.start());
+    })
+}
+
+/// For `name` or `name.rest`, returns `""` or `.rest` respectively.
+fn key_of<'a>(expr: &'a str, name: &str) -> Option<&'a str> {
+    if !expr.starts_with(name) {
+        return None;
+    }
+    let rest = &expr[name.len()..];
+    if rest.is_empty() || rest.starts_with('.') {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+fn method_name(method: &str) -> &str {
+    method.split('(').next().unwrap_or(method)
+}
+
+fn with_call(method: &str) -> String {
+    if method.contains('(') {
+        method.to_string()
+    } else {
+        format!("{}()", method)
+    }
+}
+
+fn is_primitive(ty: &str) -> bool {
+    match ty {
+        "bool" | "char" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "u128" | "usize" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_to_min_with_sentinel() {
+        check_assist(
+            convert_to_min_max,
+            r#"
+fn f() {
+    let mut lo: u32 = u32::MAX;
+    <|>for x in xs {
+        if lo > x {
+            lo = x;
+        }
+    }
+}
+"#,
+            r#"
+fn f() {
+    <|>let lo: u32 = xs.into_iter().min().unwrap_or(u32::MAX);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_max_with_initial_item() {
+        check_assist(
+            convert_to_min_max,
+            r#"
+fn f(first: u8) {
+    let mut hi = first;
+    <|>for x in rest { if x > hi { hi = x } }
+}
+"#,
+            r#"
+fn f(first: u8) {
+    <|>let hi = std::iter::once(first).chain(rest.into_iter()).max().unwrap();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_max_by_key() {
+        check_assist(
+            convert_to_min_max,
+            r#"
+struct Item { size: u32 }
+
+fn f(first: Item) {
+    let mut largest = first;
+    <|>for it in items {
+        if it.size >= largest.size {
+            largest = it;
+        }
+    }
+}
+"#,
+            r#"
+struct Item { size: u32 }
+
+fn f(first: Item) {
+    <|>let largest = std::iter::once(first).chain(items.into_iter()).max_by_key(|it| it.size).unwrap();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_min_max_target() {
+        check_assist_target(
+            convert_to_min_max,
+            "fn f() { let mut m = 0u8; <|>for x in xs { if x > m { m = x } } }",
+            "let mut m = 0u8; for x in xs { if x > m { m = x } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_min_max_not_applicable_for_different_ties() {
+        check_assist_not_applicable(
+            convert_to_min_max,
+            r#"
+struct Item { size: u32 }
+
+fn f(first: Item) {
+    let mut largest = first;
+    <|>for it in items {
+        if it.size > largest.size {
+            largest = it;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_min_max_not_applicable_for_floats() {
+        check_assist_not_applicable(
+            convert_to_min_max,
+            "fn f() { let mut m = 0f64; <|>for x in xs { if x > m { m = x } } }",
+        );
+    }
+
+    #[test]
+    fn convert_to_min_max_not_applicable_for_other_assignments() {
+        check_assist_not_applicable(
+            convert_to_min_max,
+            "fn f() { let mut m = 0u8; <|>for x in xs { if x > m { m = x + 1 } } }",
+        );
+    }
+}
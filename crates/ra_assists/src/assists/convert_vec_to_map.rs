@@ -0,0 +1,397 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, AttrsOwner, NameOwner, TypeAscriptionOwner},
+    SmolStr, SyntaxNode, TextRange,
+};
+
+use super::add_import::auto_import_text_edit;
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_vec_to_map
+//
+// Converts a local vector of key-value pairs, which is only used for lookups,
+// into a `HashMap` or, if the keys are not hashable, a `BTreeMap`.
+//
+// ```
+// fn main() {
+//     let mut <|>ages = Vec::new();
+//     ages.push((1u32, 20u8));
+//     let known = ages.iter().any(|(id, _)| *id == 1);
+// }
+// ```
+// ->
+// ```
+// use std::collections::HashMap;
+//
+// fn main() {
+//     let mut ages = HashMap::new();
+//     ages.insert(1u32, 20u8);
+//     let known = ages.contains_key(&1);
+// }
+// ```
+pub(crate) fn convert_vec_to_map(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let bind_pat = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    let init = let_stmt.initializer()?;
+    if ctx.frange.range.start() >= init.syntax().text_range().start() || !is_new_vec(&init) {
+        return None;
+    }
+
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    // All uses have to be replaceable by their map equivalent. Iteration, for
+    // example, depends on the insertion order.
+    let mut edits = Vec::new();
+    let mut pushed_keys = Vec::new();
+    for reference in analyzer.find_all_refs(&bind_pat) {
+        let usage = ctx.covering_node_for_range(reference.range);
+        let path_expr = usage.ancestors().find_map(ast::PathExpr::cast)?;
+        pushed_keys.extend(pushed_key(&path_expr));
+        edits.push(rewrite_usage(&path_expr, &reference.name)?);
+    }
+
+    let ascribed = match let_stmt.ascribed_type() {
+        Some(it) => Some((it.clone(), pair_types(&it.syntax().to_string())?)),
+        None => None,
+    };
+    let key = match &ascribed {
+        Some((_, (key, _))) => key.clone(),
+        None => {
+            let key = pushed_keys.first()?;
+            let ty = ctx.source_analyzer(key.syntax(), None).type_of(ctx.db, key)?;
+            if ty.contains_unknown() {
+                return None;
+            }
+            ty.display(ctx.db).to_string()
+        }
+    };
+    let root = let_stmt.syntax().ancestors().last()?;
+    let map = map_for_key(&key, &root)?;
+
+    let label = format!("Convert to {}", map);
+    ctx.add_assist(AssistId("convert_vec_to_map"), label, |edit| {
+        edit.target(let_stmt.syntax().text_range());
+        if let Some((type_ref, (key, value))) = ascribed {
+            edit.replace(type_ref.syntax().text_range(), format!("{}<{}, {}>", map, key, value));
+        }
+        edit.replace(init.syntax().text_range(), format!("{}::new()", map));
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        let target = [SmolStr::new("std"), SmolStr::new("collections"), SmolStr::new(map)];
+        auto_import_text_edit(
+            let_stmt.syntax(),
+            let_stmt.syntax(),
+            &target,
+            edit.text_edit_builder(),
+        );
+    })
+}
+
+fn is_new_vec(expr: &ast::Expr) -> bool {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return false,
+    };
+    let has_args = call.arg_list().map_or(true, |it| it.args().next().is_some());
+    match call.expr() {
+        Some(ast::Expr::PathExpr(it)) => !has_args && it.syntax().text() == "Vec::new",
+        _ => false,
+    }
+}
+
+/// For `Vec<(K, V)>`, returns `K` and `V`.
+fn pair_types(ty: &str) -> Option<(String, String)> {
+    if !ty.starts_with("Vec<(") || !ty.ends_with(")>") {
+        return None;
+    }
+    let inner = &ty["Vec<(".len()..ty.len() - ")>".len()];
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => commas.push(idx),
+            _ => (),
+        }
+    }
+    match commas.as_slice() {
+        [idx] => Some((inner[..*idx].trim().to_string(), inner[idx + 1..].trim().to_string())),
+        _ => None,
+    }
+}
+
+/// Picks `HashMap` if the key is known to be hashable, `BTreeMap` if it is
+/// known to be ordered.
+fn map_for_key(key: &str, root: &SyntaxNode) -> Option<&'static str> {
+    let key = key.trim_start_matches('&').trim_start_matches("'static ");
+    match key {
+        "f32" | "f64" => return None,
+        "bool" | "char" | "str" | "String" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+        | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => return Some("HashMap"),
+        _ => (),
+    }
+    let derives = root
+        .descendants()
+        .filter_map(|it| {
+            let attrs = if let Some(it) = ast::StructDef::cast(it.clone()) {
+                if it.name()?.text().as_str() != key {
+                    return None;
+                }
+                it.attrs()
+            } else {
+                let it = ast::EnumDef::cast(it)?;
+                if it.name()?.text().as_str() != key {
+                    return None;
+                }
+                it.attrs()
+            };
+            Some(attrs.map(|it| it.syntax().text().to_string()).collect::<Vec<_>>())
+        })
+        .next()?;
+    let derives = |name: &str| {
+        derives.iter().any(|it| {
+            it.contains("derive")
+                && it.split(|c: char| !c.is_alphanumeric()).any(|word| word == name)
+        })
+    };
+    if derives("Hash") && derives("Eq") {
+        Some("HashMap")
+    } else if derives("Ord") {
+        Some("BTreeMap")
+    } else {
+        None
+    }
+}
+
+/// For `v.push((k, x))`, returns `k`.
+fn pushed_key(path_expr: &ast::PathExpr) -> Option<ast::Expr> {
+    let call = ast::MethodCallExpr::cast(path_expr.syntax().parent()?)?;
+    if call.name_ref()?.text().as_str() != "push" {
+        return None;
+    }
+    match call.arg_list()?.args().next()? {
+        ast::Expr::TupleExpr(it) => it.exprs().next(),
+        _ => None,
+    }
+}
+
+/// Rewrites a use of the vector, if it has a map equivalent.
+fn rewrite_usage(path_expr: &ast::PathExpr, name: &str) -> Option<(TextRange, String)> {
+    let call = ast::MethodCallExpr::cast(path_expr.syntax().parent()?)?;
+    let method = call.name_ref()?;
+    match method.text().as_str() {
+        "len" | "is_empty" | "clear" => {
+            Some((method.syntax().text_range(), method.text().to_string()))
+        }
+        "push" => {
+            let arg_list = call.arg_list()?;
+            let pair = match arg_list.args().next()? {
+                ast::Expr::TupleExpr(it) => it,
+                _ => return None,
+            };
+            let elements = pair.exprs().map(|it| it.syntax().to_string()).collect::<Vec<_>>();
+            if elements.len() != 2 {
+                return None;
+            }
+            let range = TextRange::from_to(
+                method.syntax().text_range().start(),
+                arg_list.syntax().text_range().end(),
+            );
+            Some((range, format!("insert({}, {})", elements[0], elements[1])))
+        }
+        "iter" => rewrite_lookup(&call, name),
+        _ => None,
+    }
+}
+
+/// Rewrites `v.iter().any(|(k, _)| *k == key)` to `v.contains_key(&key)` and
+/// `v.iter().find(|(k, _)| *k == key).map(|(_, v)| v)` to `v.get(&key)`.
+fn rewrite_lookup(iter_call: &ast::MethodCallExpr, name: &str) -> Option<(TextRange, String)> {
+    let search = ast::MethodCallExpr::cast(iter_call.syntax().parent()?)?;
+    let (key_pat, body) = closure_arg(&search)?;
+    let key_name = match key_pat.0 {
+        ast::Pat::BindPat(it) => it.name()?.text().to_string(),
+        _ => return None,
+    };
+    if !is_placeholder(&key_pat.1) {
+        return None;
+    }
+    let cmp = match body {
+        ast::Expr::BinExpr(it) if it.op_kind() == Some(ast::BinOp::EqualityTest) => it,
+        _ => return None,
+    };
+    let is_key = |expr: &ast::Expr| {
+        let text = expr.syntax().text().to_string();
+        text == key_name || text.trim_start_matches('*') == key_name
+    };
+    let (lhs, rhs) = (cmp.lhs()?, cmp.rhs()?);
+    let searched = match (is_key(&lhs), is_key(&rhs)) {
+        (true, false) => rhs,
+        (false, true) => lhs,
+        _ => return None,
+    };
+    let uses_key = searched
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .any(|it| it.text().as_str() == key_name);
+    if uses_key {
+        return None;
+    }
+    let searched = match &searched {
+        ast::Expr::RefExpr(_) => searched.syntax().to_string(),
+        _ => format!("&{}", searched.syntax()),
+    };
+
+    match search.name_ref()?.text().as_str() {
+        "any" => {
+            Some((search.syntax().text_range(), format!("{}.contains_key({})", name, searched)))
+        }
+        "find" => {
+            let map_call = ast::MethodCallExpr::cast(search.syntax().parent()?)?;
+            if map_call.name_ref()?.text().as_str() != "map" {
+                return None;
+            }
+            let (value_pat, body) = closure_arg(&map_call)?;
+            let value_name = match value_pat.1 {
+                ast::Pat::BindPat(it) => it.name()?.text().to_string(),
+                _ => return None,
+            };
+            if !is_placeholder(&value_pat.0) || body.syntax().text() != value_name.as_str() {
+                return None;
+            }
+            Some((map_call.syntax().text_range(), format!("{}.get({})", name, searched)))
+        }
+        _ => None,
+    }
+}
+
+/// For a call with a single `|(a, b)| body` or `|&(a, b)| body` closure
+/// argument, returns the patterns of the pair and the body.
+fn closure_arg(call: &ast::MethodCallExpr) -> Option<((ast::Pat, ast::Pat), ast::Expr)> {
+    let mut args = call.arg_list()?.args();
+    let closure = match args.next()? {
+        ast::Expr::LambdaExpr(it) => it,
+        _ => return None,
+    };
+    if args.next().is_some() {
+        return None;
+    }
+    let mut params = closure.param_list()?.params();
+    let pat = params.next()?.pat()?;
+    if params.next().is_some() {
+        return None;
+    }
+    let pat = match pat {
+        ast::Pat::RefPat(it) => it.pat()?,
+        it => it,
+    };
+    let tuple = match pat {
+        ast::Pat::TuplePat(it) => it,
+        _ => return None,
+    };
+    let mut elements = tuple.args();
+    let pair = (elements.next()?, elements.next()?);
+    if elements.next().is_some() {
+        return None;
+    }
+    Some((pair, closure.body()?))
+}
+
+fn is_placeholder(pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::PlaceholderPat(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_vec_to_hash_map() {
+        check_assist(
+            convert_vec_to_map,
+            r#"
+fn main() {
+    let mut <|>ages = Vec::new();
+    ages.push((1u32, 20u8));
+    if !ages.is_empty() {
+        let known = ages.iter().any(|(id, _)| *id == 1);
+    }
+}
+"#,
+            r#"
+use std::collections::HashMap;
+
+fn main() {
+    let mut <|>ages = HashMap::new();
+    ages.insert(1u32, 20u8);
+    if !ages.is_empty() {
+        let known = ages.contains_key(&1);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_vec_to_btree_map() {
+        check_assist(
+            convert_vec_to_map,
+            r#"
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Id(u32);
+
+fn main() {
+    let mut <|>names: Vec<(Id, u8)> = Vec::new();
+    names.push((Id(1), 2));
+    let found = names.iter().find(|&(id, _)| id == &Id(1)).map(|(_, n)| n);
+}
+"#,
+            r#"
+use std::collections::BTreeMap;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Id(u32);
+
+fn main() {
+    let mut <|>names: BTreeMap<Id, u8> = BTreeMap::new();
+    names.insert(Id(1), 2);
+    let found = names.get(&Id(1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_vec_to_map_target() {
+        check_assist_target(
+            convert_vec_to_map,
+            "fn f() { let mut <|>v = Vec::new(); v.push((1u8, 2u8)); }",
+            "let mut v = Vec::new();",
+        );
+    }
+
+    #[test]
+    fn convert_vec_to_map_not_applicable_when_iterated() {
+        check_assist_not_applicable(
+            convert_vec_to_map,
+            "fn f() { let mut <|>v = Vec::new(); v.push((1u8, 2u8)); for it in v {} }",
+        );
+    }
+
+    #[test]
+    fn convert_vec_to_map_not_applicable_for_float_keys() {
+        check_assist_not_applicable(
+            convert_vec_to_map,
+            "fn f() { let mut <|>v = Vec::new(); v.push((1f32, 2u8)); }",
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use format_buf::format;
+use hir::{db::HirDatabase, InFile};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{utils::implements_trait, Assist, AssistCtx, AssistId};
+
+// Assist: default_from_new
+//
+// Implements `Default` by calling an argument-free `new` function.
+//
+// ```
+// struct Registry { count: u32 }
+//
+// impl Registry {
+//     pub fn <|>new() -> Self {
+//         Registry { count: 0 }
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Registry { count: u32 }
+//
+// impl Registry {
+//     pub fn new() -> Self {
+//         Registry { count: 0 }
+//     }
+// }
+//
+// impl Default for Registry {
+//     fn default() -> Self {
+//         Self::new()
+//     }
+// }
+// ```
+pub(crate) fn default_from_new(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if fn_def.name()?.text().as_str() != "new" || fn_def.type_param_list().is_some() {
+        return None;
+    }
+    let impl_block = fn_def.syntax().ancestors().find_map(ast::ImplBlock::cast)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    let target_type = impl_block.target_type()?;
+    match fn_def.ret_type()?.type_ref()? {
+        ast::TypeRef::PathType(it)
+            if it.syntax().text() == "Self"
+                || it.syntax().text() == target_type.syntax().text() => {}
+        _ => return None,
+    }
+
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let function = sb.to_def(InFile::new(file_id, fn_def.clone()))?;
+    if function.has_self_param(ctx.db) || !function.params(ctx.db).is_empty() {
+        return None;
+    }
+    let impl_def = sb.to_def(InFile::new(file_id, impl_block.clone()))?;
+    if implements_trait(ctx.db, &impl_def.target_ty(ctx.db), "Default") {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("default_from_new"), "Generate Default from new", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        let offset = impl_block.syntax().text_range().end();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = impl_block.type_param_list() {
+            format!(buf, "{}", type_params.syntax());
+        }
+        format!(buf, " Default for {}", target_type.syntax());
+        if let Some(where_clause) = impl_block.where_clause() {
+            format!(buf, " {}", where_clause.syntax());
+        }
+        buf.push_str(" {\n    fn default() -> Self {\n        Self::new()\n    }\n}");
+        edit.insert(offset, buf);
+        edit.set_cursor(offset + TextUnit::of_str("\n\n"));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn default_from_new_generic() {
+        check_assist(
+            default_from_new,
+            r#"
+struct Stack<'a, T> { items: &'a [T] }
+impl<'a, T> Stack<'a, T> where T: Copy {
+    fn <|>new() -> Stack<'a, T> { Stack { items: &[] } }
+}
+"#,
+            r#"
+struct Stack<'a, T> { items: &'a [T] }
+impl<'a, T> Stack<'a, T> where T: Copy {
+    fn new() -> Stack<'a, T> { Stack { items: &[] } }
+}
+
+<|>impl<'a, T> Default for Stack<'a, T> where T: Copy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn default_from_new_target() {
+        check_assist_target(
+            default_from_new,
+            "struct S;\nimpl S {\n    fn <|>new() -> Self { S }\n}",
+            "fn new() -> Self { S }",
+        );
+    }
+
+    #[test]
+    fn default_from_new_not_applicable() {
+        check_assist_not_applicable(
+            default_from_new,
+            "struct S(u8);\nimpl S { fn <|>new(x: u8) -> Self { S(x) } }",
+        );
+        check_assist_not_applicable(
+            default_from_new,
+            "struct S;\nimpl S { fn <|>new() -> Option<Self> { None } }",
+        );
+        check_assist_not_applicable(
+            default_from_new,
+            "struct S;\nimpl S { fn <|>new() -> Self { S } }\nimpl Default for S { fn default() -> Self { S } }",
+        );
+        check_assist_not_applicable(
+            default_from_new,
+            "struct S;\ntrait T { fn new() -> Self; }\nimpl T for S { fn <|>new() -> Self { S } }",
+        );
+    }
+}
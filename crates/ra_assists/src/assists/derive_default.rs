@@ -0,0 +1,192 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt, HirDisplay, Type};
+use join_to_string::join;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{
+    utils::{adt_fields, derive_edit, implements_trait, is_scalar},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: derive_default
+//
+// Derives `Default` for a struct whose fields all have a default value, or
+// else adds an impl of `Default` to fill in.
+//
+// ```
+// #[derive(Debug)]
+// struct <|>Options {
+//     verbose: bool,
+//     retries: u32,
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, Default)]
+// struct Options {
+//     verbose: bool,
+//     retries: u32,
+// }
+// ```
+pub(crate) fn derive_default(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let db = ctx.db;
+    let (adt, fields) = adt_fields(&ctx, &nominal)?;
+    if implements_trait(db, &adt.ty(db), "Default") {
+        return None;
+    }
+    let has_defaults = fields.iter().map(|it| has_default(db, &it.ty(db))).collect::<Vec<_>>();
+
+    if has_defaults.iter().all(|it| *it) {
+        let (offset, text) = derive_edit(&nominal, "Default")?;
+        return ctx.add_assist(AssistId("derive_default"), "Derive Default", |edit| {
+            edit.target(strukt.syntax().text_range());
+            edit.insert(offset, text);
+        });
+    }
+
+    // Fields without a default are left for the user to fill in.
+    let value =
+        |has_default: &bool| if *has_default { "Default::default()" } else { "unimplemented!()" };
+    let mut value_text = String::new();
+    match strukt.kind() {
+        ast::StructKind::Record(field_list) => {
+            let names = field_list.fields().filter_map(|it| it.name()).collect::<Vec<_>>();
+            if names.len() != has_defaults.len() {
+                return None;
+            }
+            value_text.push_str("Self {");
+            join(
+                names
+                    .iter()
+                    .zip(&has_defaults)
+                    .map(|(name, it)| format!("{}: {}", name.text(), value(it))),
+            )
+            .separator(", ")
+            .surround_with(" ", " ")
+            .to_buf(&mut value_text);
+            value_text.push('}');
+        }
+        ast::StructKind::Tuple(_) => {
+            value_text.push_str("Self");
+            join(has_defaults.iter().map(value))
+                .separator(", ")
+                .surround_with("(", ")")
+                .to_buf(&mut value_text);
+        }
+        ast::StructKind::Unit => return None,
+    }
+    let name = strukt.name()?;
+
+    ctx.add_assist(AssistId("derive_default"), "Implement Default", |edit| {
+        edit.target(strukt.syntax().text_range());
+        let start_offset = strukt.syntax().text_range().end();
+        let type_params = strukt.type_param_list();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = &type_params {
+            format!(buf, "{}", type_params.syntax());
+        }
+        format!(buf, " Default for {}", name.text());
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text().clone());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        if let Some(where_clause) = strukt.where_clause() {
+            format!(buf, " {}", where_clause.syntax());
+        }
+        buf.push_str(" {\n    fn default() -> Self {\n        ");
+        let body_start = start_offset + TextUnit::of_str(&buf);
+        match value_text.find("unimplemented!()") {
+            Some(offset) => edit.set_cursor(body_start + TextUnit::from_usize(offset)),
+            None => edit.set_cursor(body_start),
+        }
+        format!(buf, "{}\n    }}\n}}", value_text);
+        edit.insert(start_offset, buf);
+    })
+}
+
+/// Whether the type is known to implement `Default`.
+fn has_default(db: &impl HirDatabase, ty: &Type) -> bool {
+    if let Some(adt) = ty.as_adt() {
+        return match adt {
+            Adt::Union(_) => false,
+            _ => implements_trait(db, ty, "Default"),
+        };
+    }
+    let tuple_fields = ty.tuple_fields(db);
+    if !tuple_fields.is_empty() {
+        return tuple_fields.iter().all(|it| has_default(db, it));
+    }
+    let text = ty.display(db).to_string();
+    match text.as_str() {
+        "()" | "&str" => true,
+        it if it.starts_with('&') => it.starts_with("&[") && it.ends_with(']'),
+        it => is_scalar(it),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn derive_default_with_default_fields() {
+        check_assist(
+            derive_default,
+            r#"
+#[derive(Default)]
+struct Inner;
+/// Docs.
+#[derive(Clone)]
+struct <|>Foo { a: Inner, b: (u8, bool), c: &'static str }
+"#,
+            r#"
+#[derive(Default)]
+struct Inner;
+/// Docs.
+#[derive(Clone, Default)]
+struct <|>Foo { a: Inner, b: (u8, bool), c: &'static str }
+"#,
+        );
+    }
+
+    #[test]
+    fn implement_default_for_record_struct() {
+        check_assist(
+            derive_default,
+            "struct Inner;\nstruct <|>Foo { a: Inner, b: u8 }",
+            "struct Inner;\nstruct Foo { a: Inner, b: u8 }\n\nimpl Default for Foo {\n    fn default() -> Self {\n        Self { a: <|>unimplemented!(), b: Default::default() }\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn implement_default_for_tuple_struct() {
+        check_assist(
+            derive_default,
+            "struct Inner;\nstruct <|>Foo<'a, T>(&'a T, u8);",
+            "struct Inner;\nstruct Foo<'a, T>(&'a T, u8);\n\nimpl<'a, T> Default for Foo<'a, T> {\n    fn default() -> Self {\n        Self(<|>unimplemented!(), Default::default())\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn derive_default_target() {
+        check_assist_target(derive_default, "struct <|>Foo { a: u8 }", "struct Foo { a: u8 }");
+    }
+
+    #[test]
+    fn derive_default_not_applicable() {
+        check_assist_not_applicable(derive_default, "#[derive(Default)]\nstruct <|>Foo { a: u8 }");
+        check_assist_not_applicable(derive_default, "enum <|>E { A, B }");
+    }
+}
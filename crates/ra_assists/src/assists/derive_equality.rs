@@ -0,0 +1,165 @@
+use hir::{db::HirDatabase, HirDisplay, Type};
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{
+    utils::{adt_fields, derive_edit, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: derive_equality
+//
+// Derives `PartialEq` and `Eq` for a struct or an enum, leaving out `Eq` if a
+// field is a float.
+//
+// ```
+// #[derive(Debug)]
+// struct <|>Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, PartialEq, Eq)]
+// struct Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+pub(crate) fn derive_equality(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let db = ctx.db;
+    let (adt, fields) = adt_fields(&ctx, &nominal)?;
+    let mut not_eq = None;
+    for field in fields {
+        // Every field has to be `PartialEq` for the derive to work.
+        if !equality(db, &field.ty(db))? && not_eq.is_none() {
+            not_eq = Some(field.name(db));
+        }
+    }
+
+    let ty = adt.ty(db);
+    let mut traits = Vec::new();
+    if !implements_trait(db, &ty, "PartialEq") {
+        traits.push("PartialEq");
+    }
+    if not_eq.is_none() && !implements_trait(db, &ty, "Eq") {
+        traits.push("Eq");
+    }
+    if traits.is_empty() {
+        return None;
+    }
+    let (offset, text) = derive_edit(&nominal, &traits.join(", "))?;
+    let label = match &not_eq {
+        Some(field) => format!("Derive PartialEq (not Eq, as `{}` is a float)", field),
+        None => "Derive equality".to_string(),
+    };
+
+    ctx.add_assist(AssistId("derive_equality"), label, |edit| {
+        edit.target(nominal.syntax().text_range());
+        edit.insert(offset, text);
+    })
+}
+
+/// Returns whether a `PartialEq` type is also `Eq`, or `None` if it isn't known
+/// to be `PartialEq` at all.
+fn equality(db: &impl HirDatabase, ty: &Type) -> Option<bool> {
+    if ty.as_adt().is_some() {
+        if !implements_trait(db, ty, "PartialEq") {
+            return None;
+        }
+        return Some(implements_trait(db, ty, "Eq"));
+    }
+    let tuple_fields = ty.tuple_fields(db);
+    if !tuple_fields.is_empty() {
+        let mut is_eq = true;
+        for field in tuple_fields {
+            is_eq &= equality(db, &field)?;
+        }
+        return Some(is_eq);
+    }
+    builtin_equality(&ty.display(db).to_string())
+}
+
+fn builtin_equality(ty: &str) -> Option<bool> {
+    if ty.starts_with("&mut ") {
+        return builtin_equality(&ty["&mut ".len()..]);
+    }
+    if ty.starts_with('&') {
+        return builtin_equality(&ty[1..]);
+    }
+    if ty.starts_with('[') && ty.ends_with(";_]") {
+        return builtin_equality(&ty[1..ty.len() - 3]);
+    }
+    if ty.starts_with('[') && ty.ends_with(']') {
+        return builtin_equality(&ty[1..ty.len() - 1]);
+    }
+    match ty {
+        "f32" | "f64" => Some(false),
+        "bool" | "char" | "str" | "()" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8"
+        | "u16" | "u32" | "u64" | "u128" | "usize" => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn derive_equality_for_enum() {
+        check_assist(
+            derive_equality,
+            r#"
+#[derive(PartialEq, Eq)]
+struct Id(u32);
+
+#[derive(Clone)]
+enum <|>Key { Id(Id), Name(&'static str, [u8; 2]), None }
+"#,
+            r#"
+#[derive(PartialEq, Eq)]
+struct Id(u32);
+
+#[derive(Clone, PartialEq, Eq)]
+enum <|>Key { Id(Id), Name(&'static str, [u8; 2]), None }
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_equality_without_eq_for_floats() {
+        check_assist(
+            derive_equality,
+            "struct <|>P { id: (u8, u8), x: f64 }",
+            "#[derive(PartialEq)]\nstruct <|>P { id: (u8, u8), x: f64 }",
+        );
+    }
+
+    #[test]
+    fn derive_equality_adds_missing_eq() {
+        check_assist(
+            derive_equality,
+            "#[derive(PartialEq)]\nstruct <|>P { id: u8 }",
+            "#[derive(PartialEq, Eq)]\nstruct <|>P { id: u8 }",
+        );
+    }
+
+    #[test]
+    fn derive_equality_target() {
+        check_assist_target(derive_equality, "struct <|>Unit;", "struct Unit;");
+    }
+
+    #[test]
+    fn derive_equality_not_applicable() {
+        // Already derived.
+        check_assist_not_applicable(
+            derive_equality,
+            "#[derive(PartialEq, Eq)]\nstruct <|>P { id: u8 }",
+        );
+        check_assist_not_applicable(derive_equality, "#[derive(PartialEq)]\nstruct <|>P(f32);");
+        // A field which can't be compared.
+        check_assist_not_applicable(derive_equality, "struct Name;\nstruct <|>P { name: Name }");
+    }
+}
@@ -0,0 +1,328 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, ArgListOwner, AstNode, NameOwner};
+
+use crate::{
+    utils::{adt_fields, derive_edit, derived_struct, implements_trait, path_name, removal_range},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: derive_ord
+//
+// Replaces an impl of `Ord` which compares the fields in the order they are
+// declared with a derive.
+//
+// ```
+// #[derive(PartialEq, Eq)]
+// struct Version { major: u32, minor: u32 }
+//
+// impl <|>Ord for Version {
+//     fn cmp(&self, other: &Self) -> Ordering {
+//         self.major.cmp(&other.major).then(self.minor.cmp(&other.minor))
+//     }
+// }
+//
+// impl PartialOrd for Version {
+//     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+//         Some(self.cmp(other))
+//     }
+// }
+// ```
+// ->
+// ```
+// #[derive(PartialEq, Eq, PartialOrd, Ord)]
+// struct Version { major: u32, minor: u32 }
+// ```
+pub(crate) fn derive_ord(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    let item_list = impl_block.item_list()?;
+    if ctx.frange.range.start() >= item_list.syntax().text_range().start() {
+        return None;
+    }
+    if path_name(&impl_block.target_trait()?)?.as_str() != "Ord" {
+        return None;
+    }
+    let strukt = derived_struct(&impl_block)?;
+    let (fn_def, expr) = single_method(&impl_block, "cmp")?;
+    let other = match fn_def.param_list()?.params().next()?.pat()? {
+        ast::Pat::BindPat(it) => it.name()?.text().to_string(),
+        _ => return None,
+    };
+    let mut compared = Vec::new();
+    compared_fields(&expr, &other, &mut compared)?;
+    let declared = match strukt.kind() {
+        ast::StructKind::Record(it) => {
+            it.fields().map(|it| Some(it.name()?.text().to_string())).collect::<Option<Vec<_>>>()?
+        }
+        ast::StructKind::Tuple(it) => (0..it.fields().count()).map(|it| it.to_string()).collect(),
+        ast::StructKind::Unit => return None,
+    };
+    if compared != declared {
+        return None;
+    }
+
+    // A `PartialOrd` impl delegating to `Ord` goes too, any other conflicts.
+    let name = strukt.name()?.text().clone();
+    let partial_ord = impl_block
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| {
+            it.target_trait().and_then(|it| path_name(&it)).map_or(false, |it| it == "PartialOrd")
+        })
+        .find(|it| it.target_type().and_then(|it| path_name(&it)).map_or(false, |it| it == name));
+    if let Some(partial_ord) = &partial_ord {
+        if !delegates_to_cmp(partial_ord) {
+            return None;
+        }
+    }
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let (adt, _) = adt_fields(&ctx, &nominal)?;
+    let ty = adt.ty(ctx.db);
+    let mut traits = Vec::new();
+    for name in &["PartialEq", "Eq", "PartialOrd"] {
+        if !implements_trait(ctx.db, &ty, name) || (*name == "PartialOrd" && partial_ord.is_some())
+        {
+            traits.push(*name);
+        }
+    }
+    traits.push("Ord");
+    let (offset, text) = derive_edit(&nominal, &traits.join(", "))?;
+
+    ctx.add_assist(AssistId("derive_ord"), "Replace with derive", |edit| {
+        edit.target(impl_block.syntax().text_range());
+        edit.insert(offset, text);
+        edit.delete(removal_range(&impl_block));
+        if let Some(partial_ord) = &partial_ord {
+            edit.delete(removal_range(partial_ord));
+        }
+        edit.set_cursor(strukt.syntax().text_range().start());
+    })
+}
+
+/// Returns the only method of the impl, with the expression it consists of.
+fn single_method(impl_block: &ast::ImplBlock, name: &str) -> Option<(ast::FnDef, ast::Expr)> {
+    let mut items = impl_block.item_list()?.impl_items();
+    let fn_def = match (items.next()?, items.next()) {
+        (ast::ImplItem::FnDef(it), None) => it,
+        _ => return None,
+    };
+    if fn_def.name()?.text().as_str() != name {
+        return None;
+    }
+    let block = fn_def.body()?.block()?;
+    if block.statements().next().is_some() {
+        return None;
+    }
+    let expr = block.expr()?;
+    Some((fn_def, expr))
+}
+
+/// Collects the fields compared by `expr`, which chains comparisons of
+/// `self` to `other` with `then` or `then_with`, or compares tuples of fields.
+fn compared_fields(expr: &ast::Expr, other: &str, acc: &mut Vec<String>) -> Option<()> {
+    let call = match expr {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let mut args = call.arg_list()?.args();
+    let arg = match (args.next()?, args.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    let receiver = call.expr()?;
+    match call.name_ref()?.text().as_str() {
+        "then" => {
+            compared_fields(&receiver, other, acc)?;
+            compared_fields(&arg, other, acc)
+        }
+        "then_with" => {
+            let body = match arg {
+                ast::Expr::LambdaExpr(it) if it.param_list()?.params().next().is_none() => {
+                    it.body()?
+                }
+                _ => return None,
+            };
+            compared_fields(&receiver, other, acc)?;
+            compared_fields(&body, other, acc)
+        }
+        "cmp" => {
+            let arg = match arg {
+                ast::Expr::RefExpr(it) if !it.is_mut() => it.expr()?,
+                _ => return None,
+            };
+            match (receiver, arg) {
+                (ast::Expr::TupleExpr(lhs), ast::Expr::TupleExpr(rhs)) => {
+                    let (lhs, rhs) =
+                        (lhs.exprs().collect::<Vec<_>>(), rhs.exprs().collect::<Vec<_>>());
+                    if lhs.len() != rhs.len() {
+                        return None;
+                    }
+                    for (lhs, rhs) in lhs.iter().zip(&rhs) {
+                        acc.push(field_pair(&strip_ref(lhs)?, &strip_ref(rhs)?, other)?);
+                    }
+                    Some(())
+                }
+                (lhs, rhs) => {
+                    acc.push(field_pair(&lhs, &rhs, other)?);
+                    Some(())
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+/// For `self.a` and `other.a` returns `a`.
+fn field_pair(lhs: &ast::Expr, rhs: &ast::Expr, other: &str) -> Option<String> {
+    let field = |expr: &ast::Expr, base: &str| match expr {
+        ast::Expr::FieldExpr(it) if it.expr()?.syntax().text() == base => {
+            match it.field_access()? {
+                ast::FieldKind::Name(it) => Some(it.text().to_string()),
+                ast::FieldKind::Index(it) => Some(it.text().to_string()),
+            }
+        }
+        _ => None,
+    };
+    let name = field(lhs, "self")?;
+    if field(rhs, other)? != name {
+        return None;
+    }
+    Some(name)
+}
+
+fn strip_ref(expr: &ast::Expr) -> Option<ast::Expr> {
+    match expr {
+        ast::Expr::RefExpr(it) if !it.is_mut() => it.expr(),
+        _ => Some(expr.clone()),
+    }
+}
+
+/// Whether the `PartialOrd` impl returns `Some(self.cmp(other))`.
+fn delegates_to_cmp(impl_block: &ast::ImplBlock) -> bool {
+    let is_delegation = || {
+        let (fn_def, expr) = single_method(impl_block, "partial_cmp")?;
+        let other = fn_def.param_list()?.params().next()?.pat()?;
+        let call = match expr {
+            ast::Expr::CallExpr(it) => it,
+            _ => return None,
+        };
+        match call.expr()? {
+            ast::Expr::PathExpr(it) if it.syntax().text() == "Some" => (),
+            _ => return None,
+        }
+        let arg = call.arg_list()?.args().next()?;
+        let expected = format!("self.cmp({})", other.syntax());
+        Some(arg.syntax().text() == expected.as_str())
+    };
+    is_delegation().unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn derive_ord_with_then_with() {
+        check_assist(
+            derive_ord,
+            r#"
+struct Version { major: u32, minor: u32, patch: u32 }
+
+impl <|>Ord for Version {
+    fn cmp(&self, o: &Version) -> Ordering {
+        self.major
+            .cmp(&o.major)
+            .then_with(|| self.minor.cmp(&o.minor))
+            .then(self.patch.cmp(&o.patch))
+    }
+}
+"#,
+            r#"
+<|>#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Version { major: u32, minor: u32, patch: u32 }
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_ord_tuple_comparison() {
+        check_assist(
+            derive_ord,
+            r#"
+#[derive(PartialEq, Eq)]
+struct P(u8, u8);
+
+impl PartialOrd for P {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for P<|> {
+    fn cmp(&self, other: &Self) -> Ordering { (&self.0, &self.1).cmp(&(&other.0, &other.1)) }
+}
+"#,
+            r#"
+<|>#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct P(u8, u8);
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_ord_target() {
+        check_assist_target(
+            derive_ord,
+            "struct S { a: u8 }\nimpl <|>Ord for S { fn cmp(&self, other: &S) -> Ordering { self.a.cmp(&other.a) } }",
+            "impl Ord for S { fn cmp(&self, other: &S) -> Ordering { self.a.cmp(&other.a) } }",
+        );
+    }
+
+    #[test]
+    fn derive_ord_not_applicable() {
+        // Not the declaration order.
+        check_assist_not_applicable(
+            derive_ord,
+            r#"
+struct S { a: u8, b: u8 }
+impl <|>Ord for S {
+    fn cmp(&self, other: &Self) -> Ordering { self.b.cmp(&other.b).then(self.a.cmp(&other.a)) }
+}
+"#,
+        );
+        // Reversed.
+        check_assist_not_applicable(
+            derive_ord,
+            r#"
+struct S { a: u8 }
+impl <|>Ord for S {
+    fn cmp(&self, other: &Self) -> Ordering { other.a.cmp(&self.a) }
+}
+"#,
+        );
+        // Not every field.
+        check_assist_not_applicable(
+            derive_ord,
+            r#"
+struct S { a: u8, b: u8 }
+impl <|>Ord for S {
+    fn cmp(&self, other: &Self) -> Ordering { self.a.cmp(&other.a) }
+}
+"#,
+        );
+        // `PartialOrd` disagrees.
+        check_assist_not_applicable(
+            derive_ord,
+            r#"
+struct S { a: u8 }
+impl <|>Ord for S {
+    fn cmp(&self, other: &Self) -> Ordering { self.a.cmp(&other.a) }
+}
+impl PartialOrd for S {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { None }
+}
+"#,
+        );
+    }
+}
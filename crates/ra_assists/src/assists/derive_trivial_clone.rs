@@ -0,0 +1,234 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, ArgListOwner, AstNode, NameOwner};
+
+use crate::{
+    utils::{derive_edit, derived_struct, path_name, removal_range},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: derive_trivial_clone
+//
+// Replaces an impl of `Clone` which clones every field with a derive.
+//
+// ```
+// struct Point { x: u32, y: u32 }
+//
+// impl <|>Clone for Point {
+//     fn clone(&self) -> Self {
+//         Point { x: self.x.clone(), y: self.y.clone() }
+//     }
+// }
+// ```
+// ->
+// ```
+// #[derive(Clone)]
+// struct Point { x: u32, y: u32 }
+// ```
+pub(crate) fn derive_trivial_clone(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    let item_list = impl_block.item_list()?;
+    if ctx.frange.range.start() >= item_list.syntax().text_range().start() {
+        return None;
+    }
+    if path_name(&impl_block.target_trait()?)?.as_str() != "Clone" {
+        return None;
+    }
+    let strukt = derived_struct(&impl_block)?;
+
+    let mut items = item_list.impl_items();
+    let fn_def = match (items.next()?, items.next()) {
+        (ast::ImplItem::FnDef(it), None) => it,
+        _ => return None,
+    };
+    if fn_def.name()?.text().as_str() != "clone" {
+        return None;
+    }
+    let block = fn_def.body()?.block()?;
+    if block.statements().next().is_some() || !is_trivial_clone(&strukt, &block.expr()?) {
+        return None;
+    }
+
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let (offset, text) = derive_edit(&nominal, "Clone")?;
+
+    ctx.add_assist(AssistId("derive_trivial_clone"), "Replace with derive", |edit| {
+        edit.target(impl_block.syntax().text_range());
+        edit.insert(offset, text);
+        edit.delete(removal_range(&impl_block));
+        edit.set_cursor(strukt.syntax().text_range().start());
+    })
+}
+
+/// Whether `expr` builds the struct from a clone of each of its fields.
+fn is_trivial_clone(strukt: &ast::StructDef, expr: &ast::Expr) -> bool {
+    let name = match strukt.name() {
+        Some(it) => it.text().clone(),
+        None => return false,
+    };
+    let is_struct_path = |path: Option<ast::Path>| {
+        path.map_or(false, |it| it.syntax().text() == "Self" || it.syntax().text() == name.as_str())
+    };
+    match (strukt.kind(), expr) {
+        (ast::StructKind::Record(fields), ast::Expr::RecordLit(record_lit)) => {
+            if !is_struct_path(record_lit.path()) {
+                return false;
+            }
+            let field_list = match record_lit.record_field_list() {
+                Some(it) => it,
+                None => return false,
+            };
+            let names = fields
+                .fields()
+                .map(|it| Some(it.name()?.text().to_string()))
+                .collect::<Option<Vec<_>>>();
+            let cloned = field_list
+                .fields()
+                .map(|field| {
+                    let name = field.name_ref()?.text().to_string();
+                    if cloned_field(&field.expr()?)? == name {
+                        Some(name)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Option<Vec<_>>>();
+            match (names, cloned) {
+                (Some(mut names), Some(mut cloned)) => {
+                    names.sort();
+                    cloned.sort();
+                    field_list.spread().is_none() && names == cloned
+                }
+                _ => false,
+            }
+        }
+        (ast::StructKind::Tuple(fields), ast::Expr::CallExpr(call)) => {
+            let is_constructor = match call.expr() {
+                Some(ast::Expr::PathExpr(it)) => is_struct_path(it.path()),
+                _ => false,
+            };
+            let args = match call.arg_list() {
+                Some(it) => it.args(),
+                None => return false,
+            };
+            let indices = (0..fields.fields().count()).map(|it| Some(it.to_string()));
+            is_constructor && indices.eq(args.map(|it| cloned_field(&it)))
+        }
+        (ast::StructKind::Unit, ast::Expr::PathExpr(it)) => is_struct_path(it.path()),
+        _ => false,
+    }
+}
+
+/// Returns the name of the field cloned by `self.field.clone()` or
+/// `Clone::clone(&self.field)`.
+fn cloned_field(expr: &ast::Expr) -> Option<String> {
+    let field_expr = match expr {
+        ast::Expr::MethodCallExpr(call) => {
+            if call.name_ref()?.text().as_str() != "clone"
+                || call.arg_list()?.args().next().is_some()
+            {
+                return None;
+            }
+            match call.expr()? {
+                ast::Expr::FieldExpr(it) => it,
+                _ => return None,
+            }
+        }
+        ast::Expr::CallExpr(call) => {
+            match call.expr()? {
+                ast::Expr::PathExpr(it) if it.syntax().text() == "Clone::clone" => (),
+                _ => return None,
+            }
+            let mut args = call.arg_list()?.args();
+            match (args.next()?, args.next()) {
+                (ast::Expr::RefExpr(it), None) if !it.is_mut() => match it.expr()? {
+                    ast::Expr::FieldExpr(it) => it,
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    if field_expr.expr()?.syntax().text() != "self" {
+        return None;
+    }
+    match field_expr.field_access()? {
+        ast::FieldKind::Name(it) => Some(it.text().to_string()),
+        ast::FieldKind::Index(it) => Some(it.text().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn derive_trivial_clone_tuple_struct() {
+        check_assist(
+            derive_trivial_clone,
+            r#"
+/// Docs.
+#[derive(Debug)]
+struct Pair(u32, String);
+
+impl <|>Clone for Pair {
+    fn clone(&self) -> Pair {
+        Self(self.0.clone(), Clone::clone(&self.1))
+    }
+}
+
+fn main() {}
+"#,
+            r#"
+<|>/// Docs.
+#[derive(Debug, Clone)]
+struct Pair(u32, String);
+
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_trivial_clone_unit_struct() {
+        check_assist(
+            derive_trivial_clone,
+            "struct Unit;\nimpl Clone for <|>Unit { fn clone(&self) -> Self { Unit } }",
+            "<|>#[derive(Clone)]\nstruct Unit;",
+        );
+    }
+
+    #[test]
+    fn derive_trivial_clone_target() {
+        check_assist_target(
+            derive_trivial_clone,
+            "struct Unit;\nimpl Clone for <|>Unit { fn clone(&self) -> Self { Unit } }",
+            "impl Clone for Unit { fn clone(&self) -> Self { Unit } }",
+        );
+    }
+
+    #[test]
+    fn derive_trivial_clone_not_applicable() {
+        // A field is missing.
+        check_assist_not_applicable(
+            derive_trivial_clone,
+            "struct P { x: u32, y: u32 }\nimpl <|>Clone for P { fn clone(&self) -> P { P { x: self.x.clone(), y: 0 } } }",
+        );
+        // Fields are swapped.
+        check_assist_not_applicable(
+            derive_trivial_clone,
+            "struct P { x: u32, y: u32 }\nimpl <|>Clone for P { fn clone(&self) -> P { P { x: self.y.clone(), y: self.x.clone() } } }",
+        );
+        // Custom logic.
+        check_assist_not_applicable(
+            derive_trivial_clone,
+            "struct P { x: u32 }\nimpl <|>Clone for P { fn clone(&self) -> P { log(); P { x: self.x.clone() } } }",
+        );
+        // Generic.
+        check_assist_not_applicable(
+            derive_trivial_clone,
+            "struct P<T> { x: T }\nimpl<T: Copy> <|>Clone for P<T> { fn clone(&self) -> P<T> { P { x: self.x.clone() } } }",
+        );
+    }
+}
@@ -9,8 +9,9 @@ use ra_syntax::{
     SyntaxNode,
 };
 
+use super::invert_if::invert_boolean_expression;
 use crate::{
-    assist_ctx::{Assist, AssistCtx},
+    assist_ctx::{Assist, AssistCtx, ExprPrecedence},
     AssistId,
 };
 
@@ -84,7 +85,16 @@ pub(crate) fn convert_to_guarded_return(ctx: AssistCtx<impl HirDatabase>) -> Opt
 
     let early_expression: ast::Expr = match parent_container.kind() {
         WHILE_EXPR | LOOP_EXPR => make::expr_continue(),
-        FN_DEF => make::expr_return(),
+        FN_DEF => {
+            // A bare `return` only works in functions returning `()`.
+            let ret_type = ast::FnDef::cast(parent_container)?.ret_type();
+            match ret_type.and_then(|it| it.type_ref()) {
+                Some(ast::TypeRef::TupleType(it)) if it.fields().next().is_none() => (),
+                Some(_) => return None,
+                None => (),
+            }
+            make::expr_return()
+        }
         _ => return None,
     };
 
@@ -101,8 +111,15 @@ pub(crate) fn convert_to_guarded_return(ctx: AssistCtx<impl HirDatabase>) -> Opt
             None => {
                 // If.
                 let early_expression = &(early_expression.syntax().to_string() + ";");
+                let new_cond = invert_boolean_expression(&cond_expr).unwrap_or_else(|| {
+                    if ExprPrecedence::of(&cond_expr) < ExprPrecedence::Prefix {
+                        make::expr_not(make::expr_paren(cond_expr.clone()))
+                    } else {
+                        make::expr_not(cond_expr.clone())
+                    }
+                });
                 let new_expr = if_indent_level
-                    .increase_indent(make::if_expression(&cond_expr, early_expression));
+                    .increase_indent(make::if_expression(&new_cond, early_expression));
                 replace(new_expr.syntax(), &then_block, &parent_block, &if_expr)
             }
             Some((path, bound_ident)) => {
@@ -498,4 +515,58 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn convert_inverts_condition() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+            fn main() {
+                if<|> n != 0 {
+                    foo();
+                }
+            }
+            "#,
+            r#"
+            fn main() {
+                if<|> n == 0 {
+                    return;
+                }
+                foo();
+            }
+            "#,
+        );
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+            fn main() -> () {
+                if<|> a && b {
+                    foo();
+                }
+            }
+            "#,
+            r#"
+            fn main() -> () {
+                if<|> !(a && b) {
+                    return;
+                }
+                foo();
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn ignore_fn_returning_value() {
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            r#"
+            fn main() -> u32 {
+                if<|> true {
+                    foo();
+                }
+            }
+            "#,
+        );
+    }
 }
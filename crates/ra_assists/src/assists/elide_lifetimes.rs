@@ -0,0 +1,276 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, TypeParamsOwner},
+    SmolStr, SyntaxElement, SyntaxKind,
+    SyntaxKind::{
+        DYN_TRAIT_TYPE, FN_POINTER_TYPE, FOR_TYPE, IMPL_TRAIT_TYPE, LIFETIME, LIFETIME_ARG,
+        LIFETIME_PARAM, PARAM_LIST, REFERENCE_TYPE, SELF_PARAM, TYPE_BOUND, WHITESPACE,
+    },
+    SyntaxNode, SyntaxToken, TextRange, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: elide_lifetimes
+//
+// Removes explicit lifetimes from a function signature if the elision rules
+// would infer exactly the same ones.
+//
+// ```
+// fn first_word<'a><|>(s: &'a str) -> &'a str {
+//     s
+// }
+// ```
+// ->
+// ```
+// fn first_word(s: &str) -> &str {
+//     s
+// }
+// ```
+pub(crate) fn elide_lifetimes(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if let Some(body) = fn_def.body() {
+        if ctx.frange.range.start() > body.syntax().text_range().start() {
+            return None;
+        }
+    }
+    let type_params = fn_def.type_param_list()?;
+    let mut declared = Vec::new();
+    for param in type_params.lifetime_params() {
+        // `'a: 'b` bounds can't be expressed with elided lifetimes.
+        if param.syntax().children_with_tokens().any(|it| it.kind() == T![:]) {
+            return None;
+        }
+        declared.push(param.lifetime_token()?.text().clone());
+    }
+    if declared.is_empty() {
+        return None;
+    }
+
+    let param_list = fn_def.param_list()?;
+    let ret_type = fn_def.ret_type();
+    let in_signature = |token: &SyntaxToken| {
+        let range = token.text_range();
+        range.is_subrange(&param_list.syntax().text_range())
+            || ret_type.as_ref().map_or(false, |it| range.is_subrange(&it.syntax().text_range()))
+            || (token.parent().kind() == LIFETIME_PARAM
+                && token.parent().parent().as_ref() == Some(type_params.syntax()))
+    };
+    // Lifetimes used in bounds, the where clause or the body have to stay named.
+    let used_elsewhere = fn_def
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .any(|it| it.kind() == LIFETIME && declared.contains(it.text()) && !in_signature(&it));
+    if used_elsewhere {
+        return None;
+    }
+
+    // Every elided input lifetime becomes a distinct one, so a named lifetime
+    // may only be used once among the parameters.
+    let inputs = lifetime_positions(param_list.syntax(), &declared)?;
+    if declared.iter().any(|name| inputs.iter().filter(|it| it.as_ref() == Some(name)).count() > 1)
+    {
+        return None;
+    }
+    let outputs = match &ret_type {
+        Some(ret_type) => lifetime_positions(ret_type.syntax(), &declared)?
+            .into_iter()
+            .flatten()
+            .filter(|it| declared.contains(it))
+            .collect(),
+        None => Vec::new(),
+    };
+    if !outputs.is_empty() {
+        let self_lifetime = param_list
+            .self_param()
+            .filter(|it| it.kind() != ast::SelfParamKind::Owned)
+            .map(|it| lifetime_of(it.syntax()));
+        let source = match self_lifetime {
+            Some(it) => it,
+            None if inputs.len() == 1 => inputs[0].clone(),
+            None => return None,
+        }?;
+        if outputs.iter().any(|it| *it != source) {
+            return None;
+        }
+    }
+
+    ctx.add_assist(AssistId("elide_lifetimes"), "Elide lifetimes", |edit| {
+        edit.target(type_params.syntax().text_range());
+
+        let only_lifetimes = type_params.syntax().children().all(|it| it.kind() == LIFETIME_PARAM);
+        if only_lifetimes {
+            edit.delete(type_params.syntax().text_range());
+        } else {
+            for param in type_params.lifetime_params() {
+                edit.delete(with_trailing(param.syntax().clone().into(), &[T![,], WHITESPACE]));
+            }
+        }
+
+        let signature = Some(param_list.syntax().clone())
+            .into_iter()
+            .chain(ret_type.as_ref().map(|it| it.syntax().clone()));
+        for node in signature {
+            let lifetimes = node
+                .descendants_with_tokens()
+                .filter_map(|it| it.into_token())
+                .filter(|it| it.kind() == LIFETIME && declared.contains(it.text()));
+            for lifetime in lifetimes {
+                if lifetime.parent().kind() == LIFETIME_ARG {
+                    edit.replace(lifetime.text_range(), "'_");
+                } else {
+                    edit.delete(with_trailing(lifetime.into(), &[WHITESPACE]));
+                }
+            }
+        }
+    })
+}
+
+/// Lists the lifetimes of all reference-like positions below `node`, using
+/// `None` for positions where the lifetime is already elided.
+///
+/// Lifetimes under `impl`/`dyn` bounds, `fn` pointers and `for<>` binders
+/// follow different rules, so the declared ones are not allowed there.
+fn lifetime_positions(node: &SyntaxNode, declared: &[SmolStr]) -> Option<Vec<Option<SmolStr>>> {
+    let is_nested = |element: &SyntaxElement| {
+        let parent = match element {
+            SyntaxElement::Node(it) => it.parent(),
+            SyntaxElement::Token(it) => Some(it.parent()),
+        };
+        parent
+            .into_iter()
+            .flat_map(|it| it.ancestors())
+            .take_while(|it| it != node)
+            .any(|it| is_binder(it.kind()))
+    };
+
+    let mut res = Vec::new();
+    for element in node.descendants_with_tokens() {
+        match &element {
+            SyntaxElement::Token(token) if token.kind() == LIFETIME => {
+                if is_nested(&element) {
+                    if declared.contains(token.text()) {
+                        return None;
+                    }
+                    continue;
+                }
+                res.push(Some(token.text().clone()));
+            }
+            SyntaxElement::Node(it) if it.kind() == REFERENCE_TYPE || it.kind() == SELF_PARAM => {
+                let is_ref = it.children_with_tokens().any(|it| it.kind() == T![&]);
+                let is_elided = it.children_with_tokens().all(|it| it.kind() != LIFETIME);
+                if is_ref && is_elided && !is_nested(&element) {
+                    res.push(None);
+                }
+            }
+            _ => (),
+        }
+    }
+    Some(res)
+}
+
+fn is_binder(kind: SyntaxKind) -> bool {
+    match kind {
+        TYPE_BOUND | FN_POINTER_TYPE | FOR_TYPE | IMPL_TRAIT_TYPE | DYN_TRAIT_TYPE | PARAM_LIST => {
+            true
+        }
+        _ => false,
+    }
+}
+
+fn lifetime_of(node: &SyntaxNode) -> Option<SmolStr> {
+    node.children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == LIFETIME)
+        .map(|it| it.text().clone())
+}
+
+fn with_trailing(element: SyntaxElement, kinds: &[SyntaxKind]) -> TextRange {
+    let mut end = element.text_range().end();
+    let mut next = match &element {
+        SyntaxElement::Node(it) => it.next_sibling_or_token(),
+        SyntaxElement::Token(it) => it.next_sibling_or_token(),
+    };
+    while let Some(it) = next {
+        if !kinds.contains(&it.kind()) {
+            break;
+        }
+        end = it.text_range().end();
+        next = match &it {
+            SyntaxElement::Node(it) => it.next_sibling_or_token(),
+            SyntaxElement::Token(it) => it.next_sibling_or_token(),
+        };
+    }
+    TextRange::from_to(element.text_range().start(), end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn elide_single_input_lifetime() {
+        check_assist(
+            elide_lifetimes,
+            "fn f<|><'a>(x: &'a mut u32, y: u32) -> &'a u32 {}",
+            "fn f<|>(x: &mut u32, y: u32) -> &u32 {}",
+        );
+    }
+
+    #[test]
+    fn elide_self_lifetime() {
+        check_assist(
+            elide_lifetimes,
+            "impl S { fn f<|><'a, 'b>(&'a self, x: &'b str) -> &'a str {} }",
+            "impl S { fn f<|>(&self, x: &str) -> &str {} }",
+        );
+    }
+
+    #[test]
+    fn elide_keeps_type_params() {
+        check_assist(
+            elide_lifetimes,
+            "fn f<|><'a, T: Clone>(x: Wrapper<'a, T>) -> T {}",
+            "fn f<|><T: Clone>(x: Wrapper<'_, T>) -> T {}",
+        );
+    }
+
+    #[test]
+    fn elide_lifetimes_target() {
+        check_assist_target(elide_lifetimes, "fn f<|><'a>(x: &'a u32) {}", "<'a>");
+    }
+
+    #[test]
+    fn elide_not_applicable_for_ambiguous_output() {
+        check_assist_not_applicable(
+            elide_lifetimes,
+            "fn f<|><'a>(x: &'a u32, y: &u32) -> &'a u32 {}",
+        );
+        check_assist_not_applicable(
+            elide_lifetimes,
+            "impl S { fn f<|><'a>(&self, x: &'a u32) -> &'a u32 {} }",
+        );
+    }
+
+    #[test]
+    fn elide_not_applicable_for_shared_lifetime() {
+        check_assist_not_applicable(elide_lifetimes, "fn f<|><'a>(x: &'a u32, y: &'a u32) {}");
+    }
+
+    #[test]
+    fn elide_not_applicable_for_bounds() {
+        check_assist_not_applicable(
+            elide_lifetimes,
+            "fn f<|><'a, 'b: 'a>(x: &'a u32, y: &'b u32) {}",
+        );
+        check_assist_not_applicable(elide_lifetimes, "fn f<|><'a, T: 'a>(x: &'a T) {}");
+        check_assist_not_applicable(elide_lifetimes, "fn f<|><'a>(x: Box<dyn Tr + 'a>) {}");
+    }
+
+    #[test]
+    fn elide_not_applicable_in_body() {
+        check_assist_not_applicable(elide_lifetimes, "fn f<'a>(x: &'a u32) { <|>foo() }");
+    }
+}
@@ -0,0 +1,123 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{ast, AstNode, SyntaxElement, T};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: expand_matches_macro
+//
+// Replaces a `matches!` macro with the `match` it stands for, to add more
+// arms to it.
+//
+// ```
+// fn is_vowel(c: char) -> bool {
+//     <|>matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+// }
+// ```
+// ->
+// ```
+// fn is_vowel(c: char) -> bool {
+//     match c {
+//         'a' | 'e' | 'i' | 'o' | 'u' => true,
+//         _ => false,
+//     }
+// }
+// ```
+pub(crate) fn expand_matches_macro(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    if macro_call.path()?.syntax().text() != "matches" {
+        return None;
+    }
+    let token_tree = macro_call.token_tree()?;
+    // Splits the arguments on the separators outside of nested delimiters.
+    let elements = token_tree.syntax().children_with_tokens().collect::<Vec<_>>();
+    let inner = elements.get(1..elements.len().checked_sub(1)?)?;
+    let comma = inner.iter().position(|it| it.kind() == T![,])?;
+    let guard = inner[comma..].iter().position(|it| it.kind() == T![if]).map(|it| it + comma);
+    let mut pat_end = guard.unwrap_or(inner.len());
+    if guard.is_none()
+        && inner[..pat_end].iter().rev().find(|it| !it.kind().is_trivia())?.kind() == T![,]
+    {
+        // A trailing comma.
+        pat_end = inner[..pat_end].iter().rposition(|it| it.kind() == T![,])?;
+    }
+    let scrutinee = text_of(&inner[..comma]);
+    let pat = text_of(&inner[comma + 1..pat_end]);
+    if scrutinee.is_empty() || pat.is_empty() {
+        return None;
+    }
+    let guard = guard.map(|it| text_of(&inner[it + 1..]));
+
+    ctx.add_assist(AssistId("expand_matches_macro"), "Expand to match", |edit| {
+        let indent = leading_indent(macro_call.syntax()).unwrap_or_default();
+        let mut buf = String::new();
+        format!(buf, "match {} {{\n{}    {}", scrutinee, indent, pat);
+        if let Some(guard) = guard {
+            format!(buf, " if {}", guard.trim_end_matches(',').trim_end());
+        }
+        format!(buf, " => true,\n{}    _ => false,\n{}}}", indent, indent);
+        let range = macro_call.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn text_of(elements: &[SyntaxElement]) -> String {
+    elements.iter().map(|it| it.to_string()).collect::<String>().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn expand_matches_macro_in_block() {
+        check_assist(
+            expand_matches_macro,
+            r#"
+fn f() {
+    if c {
+        let b = <|>matches!(x.kind(), Kind::A(_, _) | Kind::B,);
+    }
+}
+"#,
+            r#"
+fn f() {
+    if c {
+        let b = <|>match x.kind() {
+            Kind::A(_, _) | Kind::B => true,
+            _ => false,
+        };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn expand_matches_macro_with_guard() {
+        check_assist(
+            expand_matches_macro,
+            "fn f() -> bool { !<|>matches!(x, Some(n) if n > 0) }",
+            "fn f() -> bool { !<|>match x {\n    Some(n) if n > 0 => true,\n    _ => false,\n} }",
+        );
+    }
+
+    #[test]
+    fn expand_matches_macro_target() {
+        check_assist_target(
+            expand_matches_macro,
+            "fn f() { <|>matches!(x, None); }",
+            "matches!(x, None)",
+        );
+    }
+
+    #[test]
+    fn expand_matches_macro_not_applicable() {
+        check_assist_not_applicable(expand_matches_macro, "fn f() { <|>assert!(x, y); }");
+        check_assist_not_applicable(expand_matches_macro, "fn f() { <|>matches!(x); }");
+    }
+}
@@ -0,0 +1,198 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt};
+use ra_syntax::ast::{self, AstNode, NameOwner};
+
+use crate::{
+    utils::{build_pat, resolve_enum_def, single_expr},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: expand_nested_match
+//
+// Replaces the `todo!()` or `unimplemented!()` of a match arm with a match
+// over the enum value bound by the arm.
+//
+// ```
+// enum Shape { Circle, Square }
+// enum Item { Shape(Shape), Empty }
+//
+// fn draw(item: Item) {
+//     match item {
+//         Item::Shape(shape) => <|>unimplemented!(),
+//         Item::Empty => (),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Shape { Circle, Square }
+// enum Item { Shape(Shape), Empty }
+//
+// fn draw(item: Item) {
+//     match item {
+//         Item::Shape(shape) => match shape {
+//             Shape::Circle => unimplemented!(),
+//             Shape::Square => unimplemented!(),
+//         },
+//         Item::Empty => (),
+//     }
+// }
+// ```
+pub(crate) fn expand_nested_match(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let macro_name = macro_call.path()?.syntax().text();
+    if macro_name != "todo" && macro_name != "unimplemented" {
+        return None;
+    }
+    let arm = macro_call.syntax().ancestors().find_map(ast::MatchArm::cast)?;
+    let body = match arm.expr()? {
+        ast::Expr::BlockExpr(it) => single_expr(&it)?,
+        it => it,
+    };
+    if body.syntax() != macro_call.syntax() {
+        return None;
+    }
+    let match_expr = arm.syntax().ancestors().find_map(ast::MatchExpr::cast)?;
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(arm.syntax(), None);
+    resolve_enum_def(db, &analyzer, &match_expr.expr()?)?;
+    // With several enum bindings, we can't tell which one is meant.
+    let mut bindings = arm
+        .pats()
+        .flat_map(|it| it.syntax().descendants().collect::<Vec<_>>())
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|binding| {
+            let ty = analyzer.type_of_pat(db, &binding.clone().into())?;
+            let enum_def = ty.autoderef(db).find_map(|ty| match ty.as_adt() {
+                Some(Adt::Enum(e)) => Some(e),
+                _ => None,
+            })?;
+            Some((binding, enum_def))
+        });
+    let (binding, enum_def) = match (bindings.next()?, bindings.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    let name = binding.name()?;
+    let module = analyzer.module()?;
+    let pats = enum_def
+        .variants(db)
+        .into_iter()
+        .map(|variant| build_pat(db, module, variant))
+        .collect::<Option<Vec<_>>>()?;
+    if pats.is_empty() {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("expand_nested_match"), "Expand into nested match", |edit| {
+        let mut buf = String::new();
+        format!(buf, "match {} {{\n", name.syntax());
+        for pat in pats {
+            format!(buf, "    {} => unimplemented!(),\n", pat.syntax());
+        }
+        buf.push_str("}");
+        edit.target(macro_call.syntax().text_range());
+        edit.replace_node_and_indent(macro_call.syntax(), buf);
+        edit.set_cursor(macro_call.syntax().text_range().start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn expand_nested_match_in_block() {
+        check_assist(
+            expand_nested_match,
+            r#"
+enum Inner { A(u32), B { x: u8 }, C }
+enum Outer { X(Inner, u8), Y }
+fn f(o: &Outer) {
+    match o {
+        Outer::X(inner, n) => {
+            todo!()<|>
+        }
+        Outer::Y => (),
+    }
+}
+"#,
+            r#"
+enum Inner { A(u32), B { x: u8 }, C }
+enum Outer { X(Inner, u8), Y }
+fn f(o: &Outer) {
+    match o {
+        Outer::X(inner, n) => {
+            <|>match inner {
+                Inner::A(_) => unimplemented!(),
+                Inner::B { x } => unimplemented!(),
+                Inner::C => unimplemented!(),
+            }
+        }
+        Outer::Y => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn expand_nested_match_unimplemented() {
+        check_assist(
+            expand_nested_match,
+            r#"
+enum Inner { A, B }
+enum Outer { X(Inner), Y }
+fn f(o: Outer) {
+    match o {
+        Outer::X(inner) => <|>unimplemented!(),
+        Outer::Y => (),
+    }
+}
+"#,
+            r#"
+enum Inner { A, B }
+enum Outer { X(Inner), Y }
+fn f(o: Outer) {
+    match o {
+        Outer::X(inner) => <|>match inner {
+            Inner::A => unimplemented!(),
+            Inner::B => unimplemented!(),
+        },
+        Outer::Y => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn expand_nested_match_target() {
+        check_assist_target(
+            expand_nested_match,
+            "enum I { A }\nenum O { X(I) }\nfn f(o: O) { match o { O::X(i) => <|>todo!() } }",
+            "todo!()",
+        );
+    }
+
+    #[test]
+    fn expand_nested_match_not_applicable() {
+        // Not an enum.
+        check_assist_not_applicable(
+            expand_nested_match,
+            "enum O { X(u32) }\nfn f(o: O) { match o { O::X(i) => <|>todo!() } }",
+        );
+        // More than the `todo!()`.
+        check_assist_not_applicable(
+            expand_nested_match,
+            "enum I { A }\nenum O { X(I) }\nfn f(o: O) { match o { O::X(i) => { g(); <|>todo!() } } }",
+        );
+        // Two enum bindings.
+        check_assist_not_applicable(
+            expand_nested_match,
+            "enum I { A }\nenum O { X(I, I) }\nfn f(o: O) { match o { O::X(i, j) => <|>todo!() } }",
+        );
+    }
+}
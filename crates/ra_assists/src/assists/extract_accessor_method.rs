@@ -0,0 +1,314 @@
+use format_buf::format;
+use hir::{db::HirDatabase, HirDisplay, InFile};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    TextUnit, T,
+};
+
+use crate::{utils::is_copy_type, Assist, AssistCtx, AssistId};
+
+// Assist: extract_accessor_method
+//
+// Replaces a chain of field accesses on `self` with a call to a new method
+// returning a reference to the last field.
+//
+// ```
+// struct Theme;
+// struct Config { theme: Theme }
+// struct App { config: Config }
+//
+// impl App {
+//     fn greet(&self) {
+//         draw(&self.config.<|>theme);
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Theme;
+// struct Config { theme: Theme }
+// struct App { config: Config }
+//
+// impl App {
+//     fn greet(&self) {
+//         draw(self.theme());
+//     }
+//
+//     fn theme(&self) -> &Theme {
+//         &self.config.theme
+//     }
+// }
+// ```
+pub(crate) fn extract_accessor_method(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let chain = ctx.find_node_at_offset::<ast::FieldExpr>()?;
+    let name = chain.name_ref()?.text().clone();
+    // Only chains of at least two fields, starting at `self`.
+    let mut base = chain.expr()?;
+    let mut depth = 1;
+    while let ast::Expr::FieldExpr(it) = base {
+        base = it.expr()?;
+        depth += 1;
+    }
+    match &base {
+        ast::Expr::PathExpr(it) if it.syntax().text() == "self" && depth >= 2 => (),
+        _ => return None,
+    }
+    // The cursor is on the last field, so the chain is the innermost
+    // expression there.
+    let db = ctx.db;
+    let ty = ctx.type_of_expr_at_cursor()?;
+    let ty_text = ty.display(db).to_string();
+
+    // The accessor returns a reference, which has to fit where the field was.
+    let parent = chain.syntax().parent()?;
+    let (range, call) = match ast::Expr::cast(parent.clone()) {
+        Some(ast::Expr::RefExpr(it)) if !it.is_mut() => (it.syntax().text_range(), ""),
+        Some(ast::Expr::FieldExpr(_)) | Some(ast::Expr::MethodCallExpr(_)) => {
+            (chain.syntax().text_range(), "")
+        }
+        _ if is_copy_type(db, &ty) => (chain.syntax().text_range(), "*"),
+        _ => return None,
+    };
+    let call = format!("{}self.{}()", call, name);
+
+    let fn_def = chain.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let impl_block = ast::ImplBlock::cast(fn_def.syntax().parent()?.parent()?)?;
+    let target = inherent_impl(&ctx, &impl_block)?;
+    let has_method = |it: &ast::ImplBlock| {
+        it.item_list().map_or(false, |it| {
+            it.impl_items().any(|it| match it {
+                ast::ImplItem::FnDef(it) => it.name().map_or(false, |it| *it.text() == name),
+                _ => false,
+            })
+        })
+    };
+    if target.as_ref().map_or(false, has_method) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("extract_accessor_method"), "Extract accessor method", |edit| {
+        let method = |indent: &str| {
+            let mut buf = String::new();
+            format!(
+                buf,
+                "{}fn {}(&self) -> &{} {{\n{}    &{}\n{}}}",
+                indent,
+                name,
+                ty_text,
+                indent,
+                chain.syntax(),
+                indent
+            );
+            buf
+        };
+        let (offset, text) = match &target {
+            Some(target) => {
+                let last_item = target.item_list().and_then(|it| it.impl_items().last());
+                match (*target == impl_block, last_item) {
+                    (true, _) => {
+                        let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+                        (fn_def.syntax().text_range().end(), format!("\n\n{}", method(&indent)))
+                    }
+                    (false, Some(item)) => {
+                        let indent = leading_indent(item.syntax()).unwrap_or_default();
+                        (item.syntax().text_range().end(), format!("\n\n{}", method(&indent)))
+                    }
+                    (false, None) => {
+                        let l_curly = target
+                            .syntax()
+                            .descendants_with_tokens()
+                            .find(|it| it.kind() == T!['{'])
+                            .map_or(target.syntax().text_range().end(), |it| it.text_range().end());
+                        (l_curly, format!("\n{}\n", method("    ")))
+                    }
+                }
+            }
+            None => {
+                let mut buf = String::new();
+                buf.push_str("\n\nimpl");
+                if let Some(type_params) = impl_block.type_param_list() {
+                    format!(buf, "{}", type_params.syntax());
+                }
+                if let Some(target_type) = impl_block.target_type() {
+                    format!(buf, " {}", target_type.syntax());
+                }
+                if let Some(where_clause) = impl_block.where_clause() {
+                    format!(buf, " {}", where_clause.syntax());
+                }
+                format!(buf, " {{\n{}\n}}", method("    "));
+                (impl_block.syntax().text_range().end(), buf)
+            }
+        };
+
+        // The cursor goes to the name of the new method, so it can be renamed.
+        let mut cursor = offset + TextUnit::from_usize(text.find("fn ").unwrap_or(0) + 3);
+        if offset >= range.end() {
+            cursor = cursor + TextUnit::of_str(&call) - range.len();
+        }
+        edit.target(chain.syntax().text_range());
+        edit.replace(range, call);
+        edit.insert(offset, text);
+        edit.set_cursor(cursor);
+    })
+}
+
+/// Returns the inherent impl of the type of `impl_block` in the same file, or
+/// `Some(None)` if there is none.
+fn inherent_impl(
+    ctx: &AssistCtx<impl HirDatabase>,
+    impl_block: &ast::ImplBlock,
+) -> Option<Option<ast::ImplBlock>> {
+    if impl_block.target_trait().is_none() {
+        return Some(Some(impl_block.clone()));
+    }
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let target_ty = sb.to_def(InFile::new(file_id, impl_block.clone()))?.target_ty(db);
+    let res = impl_block
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| it.target_trait().is_none())
+        .find(|it| {
+            sb.to_def(InFile::new(file_id, it.clone()))
+                .map_or(false, |it| it.target_ty(db) == target_ty)
+        });
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_accessor_method_in_same_impl() {
+        check_assist(
+            extract_accessor_method,
+            r#"
+struct C { n: u32 }
+struct B { c: C }
+struct S { b: B }
+impl S {
+    fn f(&self) -> u32 {
+        self.b.<|>c.n
+    }
+}
+"#,
+            r#"
+struct C { n: u32 }
+struct B { c: C }
+struct S { b: B }
+impl S {
+    fn f(&self) -> u32 {
+        self.c().n
+    }
+
+    fn <|>c(&self) -> &C {
+        &self.b.c
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_accessor_method_from_trait_impl() {
+        check_assist(
+            extract_accessor_method,
+            r#"
+trait T { fn f(&self); }
+struct C;
+struct B { c: C }
+struct S<X> { b: B, x: X }
+impl<X> T for S<X> {
+    fn f(&self) {
+        g(&self.b.<|>c);
+    }
+}
+"#,
+            r#"
+trait T { fn f(&self); }
+struct C;
+struct B { c: C }
+struct S<X> { b: B, x: X }
+impl<X> T for S<X> {
+    fn f(&self) {
+        g(self.c());
+    }
+}
+
+impl<X> S<X> {
+    fn <|>c(&self) -> &C {
+        &self.b.c
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_accessor_method_into_other_impl() {
+        check_assist(
+            extract_accessor_method,
+            r#"
+trait T { fn f(&self); }
+struct B { n: u32 }
+struct S { b: B }
+impl S {}
+impl T for S {
+    fn f(&self) {
+        g(self.b.<|>n);
+    }
+}
+"#,
+            r#"
+trait T { fn f(&self); }
+struct B { n: u32 }
+struct S { b: B }
+impl S {
+    fn <|>n(&self) -> &u32 {
+        &self.b.n
+    }
+}
+impl T for S {
+    fn f(&self) {
+        g(*self.n());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_accessor_method_target() {
+        check_assist_target(
+            extract_accessor_method,
+            "struct B { n: u8 }\nstruct S { b: B }\nimpl S { fn f(&self) { self.b.<|>n.g(); } }",
+            "self.b.n",
+        );
+    }
+
+    #[test]
+    fn extract_accessor_method_not_applicable() {
+        // A single field.
+        check_assist_not_applicable(
+            extract_accessor_method,
+            "struct S { n: u8 }\nimpl S { fn f(&self) { g(&self.<|>n); } }",
+        );
+        // The field is moved.
+        check_assist_not_applicable(
+            extract_accessor_method,
+            "struct C;\nstruct B { c: C }\nstruct S { b: B }\nimpl S { fn f(self) { g(self.b.<|>c); } }",
+        );
+        // The method exists.
+        check_assist_not_applicable(
+            extract_accessor_method,
+            "struct B { n: u8 }\nstruct S { b: B }\nimpl S { fn n(&self) {} fn f(&self) { self.b.<|>n.g(); } }",
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::iter::successors;
+
+use format_buf::format;
+use hir::{db::HirDatabase, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, make, AstNode, AttrsOwner},
+    SyntaxKind::{CHAR, FLOAT_NUMBER, IDENT, INT_NUMBER, STRING},
+    SyntaxToken, TextRange, TextUnit, T,
+};
+
+use crate::{
+    utils::{is_copy_type, is_test_module},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: extract_assertion_helper
+//
+// Extracts an assertion repeated in tests with different values into a
+// helper function taking the values.
+//
+// ```
+// #[test]
+// fn parses_numbers() {
+//     <|>assert_eq!(parse("1"), Some(1));
+//     assert_eq!(parse("42"), Some(42));
+// }
+// ```
+// ->
+// ```
+// #[test]
+// fn parses_numbers() {
+//     assert_helper("1", 1);
+//     assert_helper("42", 42);
+// }
+//
+// fn assert_helper(arg1: &str, arg2: i32) {
+//     assert_eq!(parse(arg1), Some(arg2));
+// }
+// ```
+pub(crate) fn extract_assertion_helper(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let macro_name = macro_call.path()?.syntax().to_string();
+    if !macro_name.starts_with("assert") {
+        return None;
+    }
+    let fn_def = macro_call.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let module = fn_def.syntax().ancestors().find_map(ast::Module::cast);
+    if !fn_def.has_atom_attr("test") && !module.as_ref().map_or(false, is_test_module) {
+        return None;
+    }
+    let scope = match &module {
+        Some(it) => it.item_list()?.syntax().clone(),
+        None => fn_def.syntax().ancestors().last()?,
+    };
+
+    let tokens = tt_tokens(&macro_call)?;
+    let mut occurrences = Vec::new();
+    for other in scope.descendants().filter_map(ast::MacroCall::cast) {
+        if other.path().map_or(true, |it| it.syntax().text() != macro_name.as_str()) {
+            continue;
+        }
+        let other_tokens = match tt_tokens(&other) {
+            Some(it) if it.len() == tokens.len() => it,
+            _ => continue,
+        };
+        let differs = tokens.iter().zip(&other_tokens).all(|(a, b)| {
+            a.text() == b.text() || (a.kind() == b.kind() && is_value(a) && is_value(b))
+        });
+        if differs {
+            occurrences.push((other, other_tokens));
+        }
+    }
+    if occurrences.len() < 2 {
+        return None;
+    }
+    // The positions with different values, grouped by the values taken.
+    let mut params: Vec<(Vec<usize>, Vec<String>)> = Vec::new();
+    for i in 0..tokens.len() {
+        let values = occurrences.iter().map(|(_, it)| it[i].text().to_string()).collect::<Vec<_>>();
+        if values.iter().all(|it| *it == values[0]) {
+            continue;
+        }
+        match params.iter_mut().find(|(_, it)| *it == values) {
+            Some((positions, _)) => positions.push(i),
+            None => params.push((vec![i], values)),
+        }
+    }
+
+    let db = ctx.db;
+    let mut param_types = Vec::new();
+    for (positions, _) in &params {
+        let mut ty = None;
+        for (call, tokens) in &occurrences {
+            let token = &tokens[positions[0]];
+            let analyzer = ctx.source_analyzer(call.syntax(), Some(token.text_range().start()));
+            let token_ty = match token.kind() {
+                IDENT => {
+                    let path = make::path_from_name_ref(make::name_ref(token.text()));
+                    let local = match analyzer.resolve_path(db, &path)? {
+                        PathResolution::Local(it) => it,
+                        _ => return None,
+                    };
+                    let local_ty = local.ty(db);
+                    // Moved into the helper, so it mustn't be used afterwards.
+                    if !is_copy_type(db, &local_ty) {
+                        let pat = local.source(db).value.left()?;
+                        let end = call.syntax().text_range().end();
+                        if analyzer.find_all_refs(&pat).iter().any(|it| it.range.start() >= end) {
+                            return None;
+                        }
+                    }
+                    local_ty.display(db).to_string()
+                }
+                STRING => "&str".to_string(),
+                CHAR => "char".to_string(),
+                _ => literal_type(token.text()),
+            };
+            if token_ty.contains("{unknown}") || ty.as_ref().map_or(false, |it| *it != token_ty) {
+                return None;
+            }
+            ty = Some(token_ty);
+        }
+        param_types.push(ty?);
+    }
+
+    let target = fn_def.syntax().clone();
+    ctx.add_assist(AssistId("extract_assertion_helper"), "Extract assertion helper", |edit| {
+        let indent = leading_indent(&target).unwrap_or_default();
+        let mut buf = String::new();
+        format!(buf, "\n\n{}fn assert_helper(", indent);
+        for (i, ty) in param_types.iter().enumerate() {
+            if i > 0 {
+                buf.push_str(", ");
+            }
+            format!(buf, "arg{}: {}", i + 1, ty);
+        }
+        format!(buf, ") {{\n{}    {}!", indent, macro_name);
+        buf.push_str(&substituted(&macro_call, &tokens, &params));
+        if !buf.ends_with(';') {
+            buf.push(';');
+        }
+        format!(buf, "\n{}}}", indent);
+        let offset = target.text_range().end();
+
+        edit.target(macro_call.syntax().text_range());
+        // The cursor goes on the name of the helper, after the replacements
+        // before it.
+        let mut cursor = offset + TextUnit::from_usize(buf.find("assert_helper").unwrap_or(0));
+        for (call, tokens) in &occurrences {
+            let mut args = String::new();
+            for (i, (positions, _)) in params.iter().enumerate() {
+                if i > 0 {
+                    args.push_str(", ");
+                }
+                args.push_str(tokens[positions[0]].text());
+            }
+            let semi = if call.syntax().text().to_string().ends_with(';') { ";" } else { "" };
+            let range = call.syntax().text_range();
+            let text = format!("assert_helper({}){}", args, semi);
+            if range.end() <= offset {
+                cursor = cursor + TextUnit::of_str(&text) - range.len();
+            }
+            edit.replace(range, text);
+        }
+        edit.insert(offset, buf);
+        edit.set_cursor(cursor);
+    })
+}
+
+/// The tokens of the arguments of the macro, without trivia.
+fn tt_tokens(macro_call: &ast::MacroCall) -> Option<Vec<SyntaxToken>> {
+    let tt = macro_call.token_tree()?;
+    let tokens = tt
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .collect();
+    Some(tokens)
+}
+
+/// Whether the token can be a different value in another assertion: a
+/// literal, or a variable which isn't the name of a field, method or macro.
+fn is_value(token: &SyntaxToken) -> bool {
+    match token.kind() {
+        INT_NUMBER | FLOAT_NUMBER | STRING | CHAR => true,
+        IDENT => {
+            let prev = successors(token.prev_token(), |it| it.prev_token())
+                .find(|it| !it.kind().is_trivia());
+            let next = successors(token.next_token(), |it| it.next_token())
+                .find(|it| !it.kind().is_trivia());
+            !prev.map_or(false, |it| it.kind() == T![.] || it.kind() == T![::])
+                && !next.map_or(false, |it| {
+                    it.kind() == T!['('] || it.kind() == T![!] || it.kind() == T![::]
+                })
+        }
+        _ => false,
+    }
+}
+
+fn literal_type(text: &str) -> String {
+    let suffixes = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        "f32", "f64",
+    ];
+    if let Some(suffix) = suffixes.iter().find(|it| text.ends_with(*it)) {
+        return suffix.to_string();
+    }
+    let is_float = text.contains('.') || (!text.starts_with("0x") && text.contains('e'));
+    if is_float { "f64" } else { "i32" }.to_string()
+}
+
+/// The text of the macro's token tree with the values replaced by the
+/// parameters.
+fn substituted(
+    macro_call: &ast::MacroCall,
+    tokens: &[SyntaxToken],
+    params: &[(Vec<usize>, Vec<String>)],
+) -> String {
+    let tt = match macro_call.token_tree() {
+        Some(it) => it,
+        None => return String::new(),
+    };
+    let start = tt.syntax().text_range().start();
+    let mut text = tt.syntax().to_string();
+    let mut replacements = params
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (positions, _))| {
+            positions.iter().map(move |&it| (tokens[it].text_range(), format!("arg{}", i + 1)))
+        })
+        .collect::<Vec<(TextRange, String)>>();
+    replacements.sort_by_key(|(range, _)| range.start());
+    for (range, name) in replacements.into_iter().rev() {
+        let range = (range.start() - start).to_usize()..(range.end() - start).to_usize();
+        text.replace_range(range, &name);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_assertion_helper_in_test_module() {
+        check_assist(
+            extract_assertion_helper,
+            r#"
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits() {
+        let input = "a,b";
+        <|>assert!(split(input, ',').len() == 2);
+    }
+
+    #[test]
+    fn splits_nothing() {
+        let empty = "";
+        assert!(split(empty, ';').len() == 0);
+        assert!(split(empty, ';').is_empty());
+    }
+}
+"#,
+            r#"
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits() {
+        let input = "a,b";
+        assert_helper(input, ',', 2);
+    }
+
+    fn <|>assert_helper(arg1: &str, arg2: char, arg3: i32) {
+        assert!(split(arg1, arg2).len() == arg3);
+    }
+
+    #[test]
+    fn splits_nothing() {
+        let empty = "";
+        assert_helper(empty, ';', 0);
+        assert!(split(empty, ';').is_empty());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_assertion_helper_target() {
+        check_assist_target(
+            extract_assertion_helper,
+            "#[test]\nfn f() {\n    <|>assert_eq!(g(1u8), 2);\n    assert_eq!(g(3u8), 4);\n}",
+            "assert_eq!(g(1u8), 2)",
+        );
+    }
+
+    #[test]
+    fn extract_assertion_helper_not_applicable() {
+        // Not repeated.
+        check_assist_not_applicable(
+            extract_assertion_helper,
+            "#[test]\nfn f() {\n    <|>assert_eq!(g(1), 2);\n    assert_ne!(g(3), 4);\n}",
+        );
+        // Different methods.
+        check_assist_not_applicable(
+            extract_assertion_helper,
+            "#[test]\nfn f(v: Vec<u8>) {\n    <|>assert_eq!(v.len(), 2);\n    assert_eq!(v.capacity(), 2);\n}",
+        );
+        // Not in a test.
+        check_assist_not_applicable(
+            extract_assertion_helper,
+            "fn f() {\n    <|>assert_eq!(g(1), 2);\n    assert_eq!(g(3), 4);\n}",
+        );
+    }
+}
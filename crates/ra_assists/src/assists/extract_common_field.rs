@@ -0,0 +1,413 @@
+use hir::{db::HirDatabase, InFile, ModuleDef, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange, TextUnit, T,
+};
+
+use crate::{
+    utils::{field_name, is_local_module},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: extract_common_field
+//
+// Moves a field which every variant of an enum has into a struct wrapping the
+// enum, and updates the construction and matching sites in the file.
+//
+// ```
+// enum Token<|> {
+//     Ident { span: u32, name: u32 },
+//     Comma { span: u32 },
+// }
+//
+// fn span(token: &Token) -> u32 {
+//     match token {
+//         Token::Ident { span, .. } => *span,
+//         Token::Comma { span } => *span,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum TokenKind {
+//     Ident { name: u32 },
+//     Comma,
+// }
+//
+// struct Token {
+//     span: u32,
+//     kind: TokenKind,
+// }
+//
+// fn span(token: &Token) -> u32 {
+//     match token {
+//         Token { span, kind: TokenKind::Ident { .. } } => *span,
+//         Token { span, kind: TokenKind::Comma } => *span,
+//     }
+// }
+// ```
+pub(crate) fn extract_common_field(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let variant_list = enum_def.variant_list()?;
+    if ctx.frange.range.start() > variant_list.syntax().text_range().start() {
+        return None;
+    }
+    if enum_def.type_param_list().is_some() {
+        return None;
+    }
+    let name = enum_def.name()?;
+    let variants = variant_list.variants().collect::<Vec<_>>();
+    if variants.len() < 2 {
+        return None;
+    }
+    let mut field_lists = Vec::new();
+    for variant in &variants {
+        match variant.kind() {
+            ast::StructKind::Record(it) => field_lists.push(it),
+            _ => return None,
+        }
+    }
+    let common = field_lists[0].fields().find(|field| {
+        field_lists.iter().all(|list| list.fields().any(|it| is_same_field(&it, field)))
+    })?;
+    let field = common.name()?.text().to_string();
+    let field_ty = common.ascribed_type()?;
+    if field == "kind" {
+        return None;
+    }
+    let kind_name = format!("{}Kind", name.text());
+    let root = enum_def.syntax().ancestors().last()?;
+    if root.descendants().filter_map(ast::Name::cast).any(|it| it.text().as_str() == kind_name) {
+        return None;
+    }
+    let variant_names =
+        variants.iter().filter_map(|it| Some(it.name()?.text().to_string())).collect::<Vec<_>>();
+    let mentions_variant = |path: &ast::Path| {
+        let qualifier = match path.qualifier().and_then(|it| it.segment()) {
+            Some(it) => it.syntax().text().to_string(),
+            None => return false,
+        };
+        let segment = match path.segment() {
+            Some(it) => it.syntax().text().to_string(),
+            None => return false,
+        };
+        (qualifier == "Self" || qualifier == name.text().as_str())
+            && variant_names.contains(&segment)
+    };
+
+    let file_id = ctx.frange.file_id.into();
+    let def = ctx.source_binder().to_def(InFile::new(file_id, enum_def.clone()))?;
+    // Uses in other files couldn't be updated.
+    if enum_def.visibility().is_some() || !is_local_module(&ctx, def.module(ctx.db)) {
+        return None;
+    }
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    for path in root.descendants().filter_map(ast::Path::cast) {
+        // Imports of the variants would have to be rewritten too.
+        if let Some(use_tree) = path.syntax().parent().and_then(ast::UseTree::cast) {
+            let imports_all = use_tree.use_tree_list().is_some()
+                || use_tree.syntax().children_with_tokens().any(|it| it.kind() == T![*]);
+            let last = path.segment().map(|it| it.syntax().text().to_string());
+            if mentions_variant(&path)
+                || (imports_all && last.as_deref() == Some(name.text().as_str()))
+            {
+                return None;
+            }
+            continue;
+        }
+        if path.syntax().parent().map_or(false, |it| ast::Path::can_cast(it.kind())) {
+            continue;
+        }
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        let is_variant = match analyzer.resolve_path(ctx.db, &path) {
+            Some(PathResolution::Def(ModuleDef::EnumVariant(it))) => it.parent_enum(ctx.db) == def,
+            Some(_) => false,
+            // `Self::Variant` isn't resolved.
+            None => mentions_variant(&path) && in_impl_of(&path, name.text()),
+        };
+        if !is_variant {
+            if mentions_variant(&path) {
+                return None;
+            }
+            continue;
+        }
+        // A variant used through an import can't be qualified with the kind.
+        let qualifier = path.qualifier()?;
+        let (wrapper, kind) = if qualifier.syntax().text() == "Self" {
+            ("Self".to_string(), kind_name.clone())
+        } else {
+            (qualifier.syntax().to_string(), format!("{}Kind", qualifier.syntax()))
+        };
+        let kind_path = format!("{}::{}", kind, path.segment()?.syntax());
+        let parent = path.syntax().parent()?;
+        let text = if let Some(record_lit) = ast::RecordLit::cast(parent.clone()) {
+            rewrite_literal(&record_lit, &field, &wrapper, &kind_path)?
+        } else if let Some(record_pat) = ast::RecordPat::cast(parent.clone()) {
+            rewrite_pattern(&record_pat, &field, &wrapper, &kind_path)?
+        } else {
+            return None;
+        };
+        edits.push((parent.text_range(), text));
+    }
+    let is_nested = edits
+        .iter()
+        .any(|(range, _)| edits.iter().any(|(it, _)| it != range && it.is_subrange(range)));
+    if is_nested {
+        return None;
+    }
+
+    edits.push((name.syntax().text_range(), kind_name.clone()));
+    for list in &field_lists {
+        let field = list.fields().find(|it| is_same_field(it, &common))?;
+        edits.push((removal_range(&field)?, String::new()));
+    }
+    let indent = leading_indent(enum_def.syntax()).unwrap_or_default();
+    let attrs = enum_def
+        .attrs()
+        .filter(|it| it.simple_name().as_deref() == Some("derive"))
+        .map(|it| format!("{}\n{}", it.syntax(), indent))
+        .collect::<String>();
+    let prefix = format!("\n\n{}{}struct ", indent, attrs);
+    let strukt = format!(
+        "{}{} {{\n{}    {}: {},\n{}    kind: {},\n{}}}",
+        prefix,
+        name.text(),
+        indent,
+        field,
+        field_ty.syntax(),
+        indent,
+        kind_name,
+        indent
+    );
+    // The name of the struct, after the other edits.
+    let enum_end = enum_def.syntax().text_range().end();
+    let cursor = edits
+        .iter()
+        .filter(|(range, _)| range.end() <= enum_end)
+        .fold(enum_end.to_usize() + prefix.len(), |acc, (range, text)| {
+            acc + text.len() - range.len().to_usize()
+        });
+
+    ctx.add_assist(AssistId("extract_common_field"), "Extract common field", |edit| {
+        edit.target(enum_def.syntax().text_range());
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.insert(enum_end, strukt);
+        edit.set_cursor(TextUnit::from_usize(cursor));
+    })
+}
+
+fn is_same_field(field: &ast::RecordFieldDef, other: &ast::RecordFieldDef) -> bool {
+    let text = |field: &ast::RecordFieldDef| {
+        let name = field.name()?.text().to_string();
+        let ty = field.ascribed_type()?.syntax().text().to_string();
+        Some((name, ty.split_whitespace().collect::<String>()))
+    };
+    match (text(field), text(other)) {
+        (Some(it), Some(other)) => it == other,
+        _ => false,
+    }
+}
+
+fn in_impl_of(path: &ast::Path, name: &str) -> bool {
+    path.syntax()
+        .ancestors()
+        .find_map(ast::ImplBlock::cast)
+        .and_then(|it| it.target_type())
+        .map_or(false, |it| it.syntax().text() == name)
+}
+
+/// The range removing the field with its separator, or the whole field list
+/// for the only field, which leaves a unit variant.
+fn removal_range(field: &ast::RecordFieldDef) -> Option<TextRange> {
+    let list = field.syntax().parent()?;
+    let fields = list.children().collect::<Vec<_>>();
+    let idx = fields.iter().position(|it| it == field.syntax())?;
+    let range = field.syntax().text_range();
+    let range = if let Some(next) = fields.get(idx + 1) {
+        TextRange::from_to(range.start(), next.text_range().start())
+    } else if idx > 0 {
+        TextRange::from_to(fields[idx - 1].text_range().end(), range.end())
+    } else {
+        let start = list
+            .prev_sibling_or_token()
+            .filter(|it| it.kind() == WHITESPACE)
+            .map_or(list.text_range().start(), |it| it.text_range().start());
+        TextRange::from_to(start, list.text_range().end())
+    };
+    Some(range)
+}
+
+fn rewrite_literal(
+    record_lit: &ast::RecordLit,
+    field: &str,
+    wrapper: &str,
+    kind_path: &str,
+) -> Option<String> {
+    let field_list = record_lit.record_field_list()?;
+    if field_list.spread().is_some() {
+        return None;
+    }
+    let mut common = None;
+    let mut rest = Vec::new();
+    for it in field_list.fields() {
+        if *it.name_ref()?.text() == *field {
+            common = Some(it.syntax().to_string());
+        } else {
+            rest.push(it.syntax().to_string());
+        }
+    }
+    Some(format!("{} {{ {}, kind: {} }}", wrapper, common?, kind_text(kind_path, &rest)))
+}
+
+fn rewrite_pattern(
+    record_pat: &ast::RecordPat,
+    field: &str,
+    wrapper: &str,
+    kind_path: &str,
+) -> Option<String> {
+    let field_list = record_pat.record_field_pat_list()?;
+    let has_rest = field_list.syntax().children_with_tokens().any(|it| it.kind() == T![..]);
+    let mut common = None;
+    let mut rest = Vec::new();
+    for it in field_list.syntax().children() {
+        if field_name(&it)? == field {
+            common = Some(it.to_string());
+        } else {
+            rest.push(it.to_string());
+        }
+    }
+    if has_rest {
+        rest.push("..".to_string());
+    }
+    let kind = kind_text(kind_path, &rest);
+    match common {
+        Some(common) => Some(format!("{} {{ {}, kind: {} }}", wrapper, common, kind)),
+        None if has_rest => Some(format!("{} {{ kind: {}, .. }}", wrapper, kind)),
+        None => None,
+    }
+}
+
+fn kind_text(kind_path: &str, fields: &[String]) -> String {
+    if fields.is_empty() {
+        kind_path.to_string()
+    } else {
+        format!("{} {{ {} }}", kind_path, fields.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn extract_common_field_with_constructors() {
+        check_assist(
+            extract_common_field,
+            r#"
+#[derive(Debug)]
+enum <|>Expr {
+    Lit { value: i64, span: (u32, u32) },
+    Neg { span: (u32,u32), inner: u32 },
+}
+
+impl Expr {
+    fn lit(value: i64) -> Expr {
+        Expr::Lit { value, span: (0, 0) }
+    }
+    fn is_lit(&self) -> bool {
+        match self {
+            Self::Lit { .. } => true,
+            Self::Neg { inner: 0, span } => false,
+            _ => false,
+        }
+    }
+}
+"#,
+            r#"
+#[derive(Debug)]
+enum ExprKind {
+    Lit { value: i64 },
+    Neg { inner: u32 },
+}
+
+#[derive(Debug)]
+struct <|>Expr {
+    span: (u32, u32),
+    kind: ExprKind,
+}
+
+impl Expr {
+    fn lit(value: i64) -> Expr {
+        Expr { span: (0, 0), kind: ExprKind::Lit { value } }
+    }
+    fn is_lit(&self) -> bool {
+        match self {
+            Self { kind: ExprKind::Lit { .. }, .. } => true,
+            Self { span, kind: ExprKind::Neg { inner: 0 } } => false,
+            _ => false,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_common_field_target() {
+        check_assist_target(
+            extract_common_field,
+            "enum E<|> { A { x: u8 }, B { x: u8 } }",
+            "enum E { A { x: u8 }, B { x: u8 } }",
+        );
+    }
+
+    #[test]
+    fn extract_common_field_not_applicable() {
+        // Not in every variant.
+        check_assist_not_applicable(extract_common_field, "enum E<|> { A { x: u8 }, B { y: u8 } }");
+        check_assist_not_applicable(extract_common_field, "enum E<|> { A { x: u8 }, B(u8) }");
+        // Of different types.
+        check_assist_not_applicable(
+            extract_common_field,
+            "enum E<|> { A { x: u8 }, B { x: u16 } }",
+        );
+        // Imported variants.
+        check_assist_not_applicable(
+            extract_common_field,
+            "use E::*;\nenum E<|> { A { x: u8 }, B { x: u8 } }",
+        );
+        // A pattern which doesn't ignore the rest.
+        check_assist_not_applicable(
+            extract_common_field,
+            "enum E<|> { A { x: u8 }, B { x: u8 } }\nfn f(e: E) { if let E::A {} = e {} }",
+        );
+        // Visible in other files.
+        check_assist_not_applicable(
+            extract_common_field,
+            "pub enum E<|> { A { x: u8 }, B { x: u8 } }",
+        );
+    }
+
+    #[test]
+    fn extract_common_field_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            extract_common_field,
+            r#"
+//- /main.rs
+mod tests;
+enum E<|> { A { x: u8 }, B { x: u8 } }
+
+//- /tests.rs
+fn f() -> super::E { super::E::A { x: 0 } }
+"#,
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: extract_if_condition
+//
+// Extracts the condition of an `if` into a named boolean.
+//
+// ```
+// fn main() {
+//     if x > 0 &&<|> y < 10 {
+//         foo();
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let is_valid = x > 0 && y < 10;
+//     if is_valid {
+//         foo();
+//     }
+// }
+// ```
+pub(crate) fn extract_if_condition(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr = ctx.find_node_at_offset::<ast::IfExpr>()?;
+    let cond = if_expr.condition()?;
+    // `if let` bindings are only in scope inside the `if`, so there's nothing
+    // to extract.
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond_expr = cond.expr()?;
+    let cond_range = cond_expr.syntax().text_range();
+    if !cond_range.contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+    match &cond_expr {
+        ast::Expr::PathExpr(_) | ast::Expr::Literal(_) => return None,
+        _ => (),
+    }
+
+    // The `let` goes right before the statement holding the `if`. `else if`
+    // conditions are evaluated lazily, so hoisting them would change semantics.
+    let parent = if_expr.syntax().parent()?;
+    let anchor = if ast::ExprStmt::can_cast(parent.kind()) || ast::LetStmt::can_cast(parent.kind())
+    {
+        parent
+    } else if ast::Block::can_cast(parent.kind()) {
+        if_expr.syntax().clone()
+    } else {
+        return None;
+    };
+    let indent = leading_indent(&anchor)?;
+    // The new variable must not shadow or be shadowed by anything used in the
+    // function.
+    let scope = if_expr.syntax().ancestors().find(|it| ast::FnDef::can_cast(it.kind()))?;
+    let is_taken = |name: &str| {
+        scope.descendants().any(|it| match ast::Name::cast(it.clone()) {
+            Some(it) => it.text().as_str() == name,
+            None => ast::NameRef::cast(it).map_or(false, |it| it.text().as_str() == name),
+        })
+    };
+    let name = (1..)
+        .map(|idx| if idx == 1 { "is_valid".to_string() } else { format!("is_valid{}", idx) })
+        .find(|it| !is_taken(it))?;
+
+    ctx.add_assist(AssistId("extract_if_condition"), "Extract condition", |edit| {
+        let anchor_start = anchor.text_range().start();
+        edit.target(cond_range);
+        edit.insert(anchor_start, format!("let {} = {};\n{}", name, cond_expr.syntax(), indent));
+        edit.replace(cond_range, name.clone());
+        edit.set_cursor(anchor_start + TextUnit::of_str("let "));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_if_condition_in_statement() {
+        check_assist(
+            extract_if_condition,
+            r#"
+            fn f() {
+                foo();
+                if a.is_empty() ||<|> b {
+                    bar();
+                }
+            }
+            "#,
+            r#"
+            fn f() {
+                foo();
+                let <|>is_valid = a.is_empty() || b;
+                if is_valid {
+                    bar();
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_in_let_initializer() {
+        check_assist(
+            extract_if_condition,
+            r#"
+            fn f() {
+                let x = if <|>a && b { 1 } else { 2 };
+            }
+            "#,
+            r#"
+            fn f() {
+                let <|>is_valid = a && b;
+                let x = if is_valid { 1 } else { 2 };
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_in_tail_expr() {
+        check_assist(
+            extract_if_condition,
+            r#"
+            fn f() -> i32 {
+                if <|>!a { 1 } else { 2 }
+            }
+            "#,
+            r#"
+            fn f() -> i32 {
+                let <|>is_valid = !a;
+                if is_valid { 1 } else { 2 }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_with_taken_name() {
+        check_assist(
+            extract_if_condition,
+            r#"
+            fn f(is_valid: bool) {
+                if <|>a && b {
+                    g(is_valid);
+                }
+            }
+            "#,
+            r#"
+            fn f(is_valid: bool) {
+                let <|>is_valid2 = a && b;
+                if is_valid2 {
+                    g(is_valid);
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_target() {
+        check_assist_target(extract_if_condition, "fn f() {\n    if a <|>== b {}\n}", "a == b");
+    }
+
+    #[test]
+    fn extract_if_condition_not_applicable_for_if_let() {
+        check_assist_not_applicable(
+            extract_if_condition,
+            "fn f() {\n    if let Some(x) = <|>a.b() {}\n}",
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_not_applicable_for_simple_condition() {
+        check_assist_not_applicable(extract_if_condition, "fn f() {\n    if <|>flag {}\n}");
+    }
+
+    #[test]
+    fn extract_if_condition_not_applicable_for_else_if() {
+        check_assist_not_applicable(
+            extract_if_condition,
+            "fn f() {\n    if a {} else if <|>b && c {}\n}",
+        );
+    }
+
+    #[test]
+    fn extract_if_condition_not_applicable_outside_condition() {
+        check_assist_not_applicable(
+            extract_if_condition,
+            "fn f() {\n    if a && b { <|>foo() }\n}",
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use either::Either;
+use format_buf::format;
+use hir::{db::HirDatabase, HirDisplay};
+use ra_fmt::{leading_indent, reindent};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner},
+    Direction,
+    SyntaxKind::{
+        BREAK_EXPR, CONTINUE_EXPR, FOR_EXPR, IDENT, LAMBDA_EXPR, LOOP_EXPR, MACRO_CALL,
+        RETURN_EXPR, TRY_EXPR, WHILE_EXPR,
+    },
+    TextUnit, T,
+};
+
+use crate::{
+    utils::{outer_locals, params_and_args},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: extract_match_arm
+//
+// Moves the body of a match arm into a new function, passing the bindings
+// of the arm and the locals it uses as parameters.
+//
+// ```
+// enum Shape { Circle(u32), Square(u32) }
+//
+// fn area(shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Circle(r) => <|>{
+//             let d = 2 * r;
+//             d * d * 3 / 4
+//         }
+//         Shape::Square(a) => a * a,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Shape { Circle(u32), Square(u32) }
+//
+// fn area(shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Circle(r) => fun_name(r),
+//         Shape::Square(a) => a * a,
+//     }
+// }
+//
+// fn fun_name(r: u32) -> u32 {
+//     let d = 2 * r;
+//     d * d * 3 / 4
+// }
+// ```
+pub(crate) fn extract_match_arm(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let arm = ctx.find_node_at_offset::<ast::MatchArm>()?;
+    let body = arm.expr()?;
+    let body_range = body.syntax().text_range();
+    if !ctx.frange.range.is_subrange(&body_range) {
+        return None;
+    }
+    let fn_def = arm.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    // Generic, async and unsafe functions would need the same treatment for
+    // the new function, which is not supported yet.
+    if fn_def.type_param_list().is_some()
+        || fn_def.is_async()
+        || fn_def.syntax().children_with_tokens().any(|it| it.kind() == T![unsafe])
+    {
+        return None;
+    }
+
+    // `return` and `?` would exit the new function instead of the original
+    // one, and `break` and `continue` need their loop.
+    let exits_early = body.syntax().descendants().any(|node| {
+        let stops_at = |kinds: &[_]| {
+            node.ancestors()
+                .take_while(|it| it != body.syntax())
+                .any(|it| it.kind() == LAMBDA_EXPR || kinds.contains(&it.kind()))
+        };
+        match node.kind() {
+            RETURN_EXPR | TRY_EXPR => !stops_at(&[]),
+            BREAK_EXPR | CONTINUE_EXPR => !stops_at(&[LOOP_EXPR, WHILE_EXPR, FOR_EXPR]),
+            _ => false,
+        }
+    });
+    if exits_early {
+        return None;
+    }
+
+    let db = ctx.db;
+    let locals = outer_locals(&ctx, &[body.syntax().clone()], body_range)?;
+    // Mutable locals from outside of the arm would be moved into the new
+    // function, so the changes would be lost.
+    for local in &locals {
+        let is_bound_by_arm = match local.source(db).value {
+            Either::Left(bind_pat) => {
+                let range = bind_pat.syntax().text_range();
+                arm.pats().any(|it| range.is_subrange(&it.syntax().text_range()))
+            }
+            Either::Right(_) => false,
+        };
+        if local.is_mut(db) && !is_bound_by_arm {
+            return None;
+        }
+    }
+
+    // Uses of locals inside macro calls are not resolved, so we can't tell
+    // which ones have to be passed along.
+    let names_before = fn_def
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| it.syntax().text_range().end() <= body_range.start())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    let uses_local_in_macro = body
+        .syntax()
+        .descendants()
+        .filter(|it| it.kind() == MACRO_CALL)
+        .flat_map(|it| it.descendants_with_tokens())
+        .filter_map(|it| it.into_token())
+        .any(|it| it.kind() == IDENT && names_before.contains(it.text()));
+    if uses_local_in_macro {
+        return None;
+    }
+
+    let (params, args) = params_and_args(db, locals)?;
+
+    let analyzer = ctx.source_analyzer(body.syntax(), None);
+    let ret_ty = analyzer.type_of(db, &body)?;
+    let ret_ty_text = ret_ty.display(db).to_string();
+    if ret_ty.contains_unknown() || ret_ty_text.contains('|') {
+        return None;
+    }
+
+    // Block-like bodies don't need a comma, a call does.
+    let is_block_like = match body {
+        ast::Expr::BlockExpr(_)
+        | ast::Expr::IfExpr(_)
+        | ast::Expr::MatchExpr(_)
+        | ast::Expr::LoopExpr(_)
+        | ast::Expr::WhileExpr(_)
+        | ast::Expr::ForExpr(_) => true,
+        _ => false,
+    };
+    let has_comma = arm
+        .syntax()
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(|it| !it.kind().is_trivia())
+        .map_or(true, |it| it.kind() == T![,] || it.kind() == T!['}']);
+    let needs_comma = is_block_like && !has_comma;
+
+    let is_method = fn_def.syntax().parent().and_then(|it| it.parent()).map_or(false, |it| {
+        ast::ImplBlock::can_cast(it.kind()) || ast::TraitDef::can_cast(it.kind())
+    });
+    let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+    let arm_level = IndentLevel::from_node(arm.syntax());
+    let body_text = match &body {
+        ast::Expr::BlockExpr(it) => arm_level.decrease_indent(it.clone()).syntax().to_string(),
+        _ => {
+            let expr = arm_level.decrease_indent(body.clone()).syntax().to_string();
+            format!("{{\n    {}\n}}", reindent(&expr, "    "))
+        }
+    };
+
+    ctx.add_assist(AssistId("extract_match_arm"), "Extract arm into function", |edit| {
+        let mut call = String::new();
+        if is_method {
+            call.push_str("Self::");
+        }
+        format!(call, "fun_name({})", args.join(", "));
+        if needs_comma {
+            call.push(',');
+        }
+
+        let mut buf = String::new();
+        format!(buf, "\n\n{}fn ", indent);
+        let name_offset = TextUnit::of_str(&buf);
+        format!(buf, "fun_name({})", params.join(", "));
+        if ret_ty_text != "()" {
+            format!(buf, " -> {}", ret_ty_text);
+        }
+        format!(buf, " {}", reindent(&body_text, &indent));
+
+        let fn_end = fn_def.syntax().text_range().end();
+        edit.target(body_range);
+        edit.replace(body_range, call.clone());
+        edit.insert(fn_end, buf);
+        edit.set_cursor(fn_end - body_range.len() + TextUnit::of_str(&call) + name_offset);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_match_arm_with_block() {
+        check_assist(
+            extract_match_arm,
+            r#"
+enum E { A(u8, u8), B }
+struct S;
+impl S {
+    fn f(&self, e: E, k: u8) {
+        match e {
+            E::A(mut x, y) => {<|>
+                x += y * k;
+                if x > 0 {
+                    g(x);
+                }
+            }
+            E::B => (),
+        }
+    }
+}
+"#,
+            r#"
+enum E { A(u8, u8), B }
+struct S;
+impl S {
+    fn f(&self, e: E, k: u8) {
+        match e {
+            E::A(mut x, y) => Self::fun_name(x, y, k),
+            E::B => (),
+        }
+    }
+
+    fn <|>fun_name(mut x: u8, y: u8, k: u8) {
+        x += y * k;
+        if x > 0 {
+            g(x);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_match_arm_with_expr() {
+        check_assist(
+            extract_match_arm,
+            r#"
+enum E { A(u32), B }
+fn f(e: E) -> u32 {
+    match e {
+        E::A(n) => <|>n
+            * 2,
+        E::B => 0,
+    }
+}
+"#,
+            r#"
+enum E { A(u32), B }
+fn f(e: E) -> u32 {
+    match e {
+        E::A(n) => fun_name(n),
+        E::B => 0,
+    }
+}
+
+fn <|>fun_name(n: u32) -> u32 {
+    n
+        * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_match_arm_adds_comma() {
+        check_assist(
+            extract_match_arm,
+            "fn f(b: bool) { match b { true => {<|> g(); } false => () } }",
+            "fn f(b: bool) { match b { true => fun_name(), false => () } }\n\nfn <|>fun_name() { g(); }",
+        );
+    }
+
+    #[test]
+    fn extract_match_arm_target() {
+        check_assist_target(
+            extract_match_arm,
+            "fn f(b: bool) { match b { true => <|>g(1), false => () } }",
+            "g(1)",
+        );
+    }
+
+    #[test]
+    fn extract_match_arm_not_applicable() {
+        // On the pattern.
+        check_assist_not_applicable(
+            extract_match_arm,
+            "fn f(b: bool) { match b { <|>true => g(1), false => () } }",
+        );
+        // Changing a mutable local.
+        check_assist_not_applicable(
+            extract_match_arm,
+            "fn f(b: bool) { let mut n = 0; match b { true => <|>{ n += 1; } false => () } }",
+        );
+        // Returning from the function.
+        check_assist_not_applicable(
+            extract_match_arm,
+            "fn f(b: bool) -> u8 { match b { true => <|>{ return 1; } false => 0 } }",
+        );
+        // Breaking out of an outer loop.
+        check_assist_not_applicable(
+            extract_match_arm,
+            "fn f(b: bool) { loop { match b { true => <|>{ break; } false => () } } }",
+        );
+    }
+}
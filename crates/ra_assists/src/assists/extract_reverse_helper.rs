@@ -0,0 +1,238 @@
+use format_buf::format;
+use hir::{db::HirDatabase, HirDisplay};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    SyntaxKind::{ITEM_LIST, MODULE, SOURCE_FILE},
+    SyntaxNode,
+};
+
+use crate::{
+    assist_ctx::{ActionBuilder, ExprPrecedence},
+    utils::anchor_stmt,
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: extract_reverse_helper
+//
+// Gives a name to the reversal of a string, either with a `reverse` helper
+// function or with a `reversed` variable.
+//
+// ```
+// fn main() {
+//     let same = name == name.chars().rev()<|>.collect::<String>();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let same = name == reverse(&name);
+// }
+//
+// fn reverse(s: &str) -> String {
+//     s.chars().rev().collect()
+// }
+// ```
+pub(crate) fn extract_reverse_helper(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (collect, string) = ctx
+        .covering_element()
+        .ancestors()
+        .filter_map(ast::MethodCallExpr::cast)
+        .find_map(|it| string_reversal(&ctx, it))?;
+    let item = collect.syntax().ancestors().find(is_module_item)?;
+    let helper = item.parent()?.children().filter_map(ast::FnDef::cast).find(|it| {
+        it.name().map_or(false, |it| it.text().as_str() == "reverse")
+            && it.param_list().map_or(false, |it| it.params().count() == 1)
+    });
+    // The helper itself.
+    if helper.as_ref().map_or(false, |it| *it.syntax() == item) {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(string.syntax(), None);
+    let is_str = analyzer
+        .type_of(ctx.db, &string)
+        .map_or(false, |it| it.display(ctx.db).to_string() == "&str");
+    let arg = if is_str {
+        string.syntax().to_string()
+    } else {
+        format!("&{}", ExprPrecedence::Prefix.parenthesize(&string))
+    };
+    // A reversal which is the whole statement isn't worth naming.
+    let anchor = anchor_stmt(ast::Expr::from(collect.clone())).filter(|(anchor, wrap_in_block)| {
+        !wrap_in_block
+            && ast::ExprStmt::cast(anchor.clone())
+                .and_then(|it| it.expr())
+                .map_or(true, |it| it.syntax() != collect.syntax())
+    });
+    let range = collect.syntax().text_range();
+
+    ctx.add_assist_group(AssistId("extract_reverse_helper"), "Name string reversal", || {
+        let mut actions = Vec::new();
+
+        let mut edit = ActionBuilder::default();
+        edit.target(range);
+        edit.replace(range, format!("reverse({})", arg));
+        if helper.is_some() {
+            edit.label("Use reverse helper");
+        } else {
+            edit.label("Extract into reverse helper");
+            let indent = leading_indent(&item).unwrap_or_default();
+            let mut buf = String::new();
+            format!(buf, "\n\n{}fn reverse(s: &str) -> String {{\n", indent);
+            format!(buf, "{}    s.chars().rev().collect()\n{}}}", indent, indent);
+            edit.insert(item.text_range().end(), buf);
+        }
+        actions.push(edit);
+
+        if let Some((anchor, _)) = &anchor {
+            let indent = leading_indent(anchor).unwrap_or_default();
+            let mut edit = ActionBuilder::default();
+            edit.label("Introduce `reversed` variable");
+            edit.target(range);
+            edit.insert(
+                anchor.text_range().start(),
+                format!("let reversed = {};\n{}", collect.syntax(), indent),
+            );
+            edit.replace(range, "reversed");
+            actions.push(edit);
+        }
+        actions
+    })
+}
+
+/// Matches `string.chars().rev().collect::<String>()`, returning the `collect`
+/// call and the string.
+fn string_reversal(
+    ctx: &AssistCtx<impl HirDatabase>,
+    collect: ast::MethodCallExpr,
+) -> Option<(ast::MethodCallExpr, ast::Expr)> {
+    let receiver = |call: &ast::MethodCallExpr, name: &str| {
+        if call.name_ref()?.text().as_str() != name || call.arg_list()?.args().next().is_some() {
+            return None;
+        }
+        call.expr()
+    };
+    let rev = match receiver(&collect, "collect")? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let chars = match receiver(&rev, "rev")? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let string = receiver(&chars, "chars")?;
+    let collects_string = match collect.type_arg_list() {
+        Some(it) => it.syntax().text() == "::<String>",
+        None => {
+            let analyzer = ctx.source_analyzer(collect.syntax(), None);
+            let ty = analyzer.type_of(ctx.db, &ast::Expr::from(collect.clone()));
+            ty.map_or(false, |it| it.display(ctx.db).to_string() == "String")
+        }
+    };
+    if !collects_string {
+        return None;
+    }
+    Some((collect, string))
+}
+
+fn is_module_item(node: &SyntaxNode) -> bool {
+    match node.parent() {
+        Some(parent) if parent.kind() == SOURCE_FILE => true,
+        Some(parent) if parent.kind() == ITEM_LIST => {
+            parent.parent().map_or(false, |it| it.kind() == MODULE)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist_group, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_reverse_helper_in_module() {
+        check_assist_group(
+            extract_reverse_helper,
+            r#"
+mod m {
+    fn f(s: &str) {
+        if s.chars().rev().<|>collect::<String>() == s {}
+    }
+}
+"#,
+            &[
+                r#"
+mod m {
+    fn f(s: &str) {
+        if reverse(s) == s {}
+    }
+
+    fn reverse(s: &str) -> String {
+        s.chars().rev().collect()
+    }
+}
+"#,
+                r#"
+mod m {
+    fn f(s: &str) {
+        let reversed = s.chars().rev().collect::<String>();
+        if reversed == s {}
+    }
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn extract_reverse_helper_uses_existing_helper() {
+        check_assist_group(
+            extract_reverse_helper,
+            r#"
+fn reverse(s: &str) -> String { s.chars().rev().collect() }
+fn f(x: u8) -> String {
+    <|>x.to_string().chars().rev().collect::<String>()
+}
+"#,
+            &[
+                r#"
+fn reverse(s: &str) -> String { s.chars().rev().collect() }
+fn f(x: u8) -> String {
+    reverse(&x.to_string())
+}
+"#,
+                r#"
+fn reverse(s: &str) -> String { s.chars().rev().collect() }
+fn f(x: u8) -> String {
+    let reversed = x.to_string().chars().rev().collect::<String>();
+    reversed
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn extract_reverse_helper_target() {
+        check_assist_target(
+            extract_reverse_helper,
+            "fn f(s: &str) { let r = s.chars().rev().collect::<<|>String>(); }",
+            "s.chars().rev().collect::<String>()",
+        );
+    }
+
+    #[test]
+    fn extract_reverse_helper_not_applicable() {
+        // Not collected into a string.
+        check_assist_not_applicable(
+            extract_reverse_helper,
+            "fn f(s: &str) { let r = s.chars().rev().collect::<Vec<_>>()<|>; }",
+        );
+        // Not reversed.
+        check_assist_not_applicable(
+            extract_reverse_helper,
+            "fn f(s: &str) { let r = s.chars().collect::<String>()<|>; }",
+        );
+    }
+}
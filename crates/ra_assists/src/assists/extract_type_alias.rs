@@ -0,0 +1,221 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    SyntaxKind::{
+        IMPL_TRAIT_TYPE, LIFETIME, PATH, PATH_SEGMENT, PLACEHOLDER_TYPE, SOURCE_FILE, TYPE_ARG,
+        TYPE_ARG_LIST,
+    },
+    SyntaxNode, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: extract_type_alias
+//
+// Introduces a type alias for a complex type repeated in the module, and
+// replaces its occurrences.
+//
+// ```
+// struct Index {
+//     by_name: HashMap<String, Vec<(u32, String)>>,
+// }
+//
+// fn build(entries: &[String]) -> HashMap<<|>String, Vec<(u32, String)>> {
+//     todo!()
+// }
+// ```
+// ->
+// ```
+// type Alias = HashMap<String, Vec<(u32, String)>>;
+//
+// struct Index {
+//     by_name: Alias,
+// }
+//
+// fn build(entries: &[String]) -> Alias {
+//     todo!()
+// }
+// ```
+pub(crate) fn extract_type_alias(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let type_ref = if ctx.frange.range.is_empty() {
+        // The whole type, even if the cursor is on one of its arguments.
+        ctx.find_node_at_offset::<ast::TypeRef>()?
+            .syntax()
+            .ancestors()
+            .take_while(|it| match it.kind() {
+                PATH | PATH_SEGMENT | TYPE_ARG | TYPE_ARG_LIST => true,
+                kind => ast::TypeRef::can_cast(kind),
+            })
+            .filter_map(ast::TypeRef::cast)
+            .last()?
+    } else {
+        ctx.covering_element().ancestors().find_map(ast::TypeRef::cast)?
+    };
+    if !is_complex(&type_ref) || !can_be_aliased(&type_ref) {
+        return None;
+    }
+    let module = enclosing_module(type_ref.syntax())?;
+    let text = tokens(type_ref.syntax());
+    let occurrences = module
+        .descendants()
+        .filter_map(ast::TypeRef::cast)
+        .filter(|it| enclosing_module(it.syntax()).as_ref() == Some(&module))
+        .filter(|it| tokens(it.syntax()) == text)
+        .collect::<Vec<_>>();
+    if occurrences.len() < 2 {
+        return None;
+    }
+    // Generic parameters aren't in scope of the alias.
+    if occurrences.iter().any(uses_type_params) {
+        return None;
+    }
+    let items = match ast::Module::cast(module.clone()) {
+        Some(it) => it.item_list()?.syntax().clone(),
+        None => module.clone(),
+    };
+    let anchor =
+        occurrences[0].syntax().ancestors().find(|it| it.parent().as_ref() == Some(&items))?;
+
+    let existing_names = module
+        .descendants()
+        .filter_map(ast::Name::cast)
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let name = std::iter::once("Alias".to_string())
+        .chain((1..).map(|it| format!("Alias{}", it)))
+        .find(|it| !existing_names.contains(it))?;
+
+    ctx.add_assist(AssistId("extract_type_alias"), "Extract type alias", |edit| {
+        let indent = leading_indent(&anchor).unwrap_or_default();
+        let offset = anchor.text_range().start();
+        edit.target(type_ref.syntax().text_range());
+        edit.insert(offset, format!("type {} = {};\n\n{}", name, type_ref.syntax(), indent));
+        edit.set_cursor(offset + TextUnit::of_str("type "));
+        for occurrence in &occurrences {
+            edit.replace(occurrence.syntax().text_range(), name.clone());
+        }
+    })
+}
+
+/// Whether the type is worth naming: it has generic arguments, or is a tuple,
+/// an array or a function pointer.
+fn is_complex(type_ref: &ast::TypeRef) -> bool {
+    match type_ref {
+        ast::TypeRef::TupleType(_)
+        | ast::TypeRef::ArrayType(_)
+        | ast::TypeRef::FnPointerType(_) => true,
+        _ => type_ref.syntax().descendants().any(|it| it.kind() == TYPE_ARG_LIST),
+    }
+}
+
+fn can_be_aliased(type_ref: &ast::TypeRef) -> bool {
+    !type_ref.syntax().descendants_with_tokens().any(|it| match it.kind() {
+        IMPL_TRAIT_TYPE | PLACEHOLDER_TYPE | T![Self] => true,
+        LIFETIME => it.as_token().map_or(false, |it| it.text() != "'static"),
+        _ => false,
+    })
+}
+
+fn uses_type_params(type_ref: &ast::TypeRef) -> bool {
+    let type_params = type_ref
+        .syntax()
+        .ancestors()
+        .filter_map(|it| it.children().find_map(ast::TypeParamList::cast))
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    type_ref
+        .syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|it| it.qualifier().is_none())
+        .filter_map(|it| it.segment()?.name_ref())
+        .any(|it| type_params.contains(it.text()))
+}
+
+fn enclosing_module(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors().find(|it| ast::Module::can_cast(it.kind()) || it.kind() == SOURCE_FILE)
+}
+
+fn tokens(node: &SyntaxNode) -> Vec<String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_range, check_assist_target,
+    };
+
+    #[test]
+    fn extract_type_alias_in_module() {
+        check_assist(
+            extract_type_alias,
+            r#"
+fn outside(x: Option<(u8, u8)>) {}
+mod geometry {
+    struct Segment {
+        ends: Option<(u8,u8)>,
+    }
+
+    impl Segment {
+        fn ends(&self) -> Option<(u8, u8)><|> {
+            self.ends
+        }
+    }
+}
+"#,
+            r#"
+fn outside(x: Option<(u8, u8)>) {}
+mod geometry {
+    type <|>Alias = Option<(u8, u8)>;
+
+    struct Segment {
+        ends: Alias,
+    }
+
+    impl Segment {
+        fn ends(&self) -> Alias {
+            self.ends
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_for_selected_argument() {
+        check_assist_range(
+            extract_type_alias,
+            "struct Alias;\nfn f(a: Vec<<|>[u8; 4]<|>>, b: [u8; 4]) {}",
+            "struct Alias;\ntype <|>Alias1 = [u8; 4];\n\nfn f(a: Vec<Alias1>, b: Alias1) {}",
+        );
+    }
+
+    #[test]
+    fn extract_type_alias_target() {
+        check_assist_target(extract_type_alias, "fn f(a: Vec<u8<|>>) -> Vec<u8> { a }", "Vec<u8>");
+    }
+
+    #[test]
+    fn extract_type_alias_not_applicable() {
+        // Not repeated.
+        check_assist_not_applicable(extract_type_alias, "fn f(a: Vec<u8<|>>) -> Vec<u16> { a }");
+        // Not complex.
+        check_assist_not_applicable(extract_type_alias, "fn f(a: String<|>) -> String { a }");
+        // Uses a generic parameter.
+        check_assist_not_applicable(extract_type_alias, "fn f<T>(a: Vec<T<|>>) -> Vec<T> { a }");
+        check_assist_not_applicable(
+            extract_type_alias,
+            "fn f<'a>(a: &'a Vec<u8<|>>) -> &'a Vec<u8> { a }",
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use either::Either;
+use hir::{db::HirDatabase, EnumVariant, ModuleDef, PathResolution, VariantDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner},
+    SyntaxKind::{ITEM_LIST, SOURCE_FILE},
+    TextRange, T,
+};
+
+use crate::{utils::resolve_enum_def, Assist, AssistCtx, AssistId};
+
+// Assist: extract_visitor_trait
+//
+// Generates the scaffold of a visitor trait with a method for each variant of
+// the enum matched on, and a function dispatching to it. The bodies of the
+// arms which only use their bindings become the default methods.
+//
+// ```
+// enum Shape { Circle(u32), Square { side: u32 } }
+//
+// fn area(shape: Shape) -> u32 {
+//     <|>match shape {
+//         Shape::Circle(r) => 3 * r * r,
+//         Shape::Square { side } => side * side,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Shape { Circle(u32), Square { side: u32 } }
+//
+// fn area(shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Circle(r) => 3 * r * r,
+//         Shape::Square { side } => side * side,
+//     }
+// }
+//
+// trait ShapeVisitor {
+//     fn visit_circle(&mut self, r: u32) -> u32 {
+//         3 * r * r
+//     }
+//
+//     fn visit_square(&mut self, side: u32) -> u32 {
+//         side * side
+//     }
+// }
+//
+// fn walk_shape(visitor: &mut impl ShapeVisitor, shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Circle(r) => visitor.visit_circle(r),
+//         Shape::Square { side } => visitor.visit_square(side),
+//     }
+// }
+// ```
+pub(crate) fn extract_visitor_trait(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+    // Offered on the `match` itself rather than in the arms.
+    if ctx.frange.range.start() > arm_list.syntax().text_range().start() {
+        return None;
+    }
+    let scrutinee = match_expr.expr()?;
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(match_expr.syntax(), None);
+    let enum_def = resolve_enum_def(db, &analyzer, &scrutinee)?;
+    let scrutinee_ty = analyzer.type_of(db, &scrutinee)?.display(db).to_string();
+    let ret_ty =
+        analyzer.type_of(db, &ast::Expr::from(match_expr.clone()))?.display(db).to_string();
+    if scrutinee_ty.contains("{unknown}") || ret_ty.contains("{unknown}") {
+        return None;
+    }
+
+    // Every variant has to have exactly one arm of its own.
+    let variants = enum_def.variants(db);
+    let mut arms = Vec::new();
+    for arm in arm_list.arms() {
+        let mut pats = arm.pats();
+        let pat = pats.next()?;
+        if pats.next().is_some() || arm.guard().is_some() {
+            return None;
+        }
+        let variant = arm_variant(&ctx, &pat)?;
+        if arms.iter().any(|(it, _, _, _)| *it == variant) {
+            return None;
+        }
+        let mut params = Vec::new();
+        for bind_pat in pat.syntax().descendants().filter_map(ast::BindPat::cast) {
+            let ty = analyzer.type_of_pat(db, &ast::Pat::from(bind_pat.clone()))?;
+            let ty = ty.display(db).to_string();
+            if ty.contains("{unknown}") {
+                return None;
+            }
+            params.push((bind_pat.name()?.text().to_string(), ty));
+        }
+        arms.push((variant, pat, params, arm));
+    }
+    if arms.len() != variants.len() {
+        return None;
+    }
+
+    let enum_name = enum_def.name(db).to_string();
+    let snake_enum_name = to_snake_case(&enum_name);
+    // The item in the module, not the method in an impl.
+    let anchor = match_expr.syntax().ancestors().find(|it| match it.parent() {
+        Some(parent) if parent.kind() == ITEM_LIST => {
+            parent.parent().map_or(false, |it| ast::Module::can_cast(it.kind()))
+        }
+        Some(parent) => parent.kind() == SOURCE_FILE,
+        None => false,
+    })?;
+    let ret = if ret_ty == "()" { String::new() } else { format!(" -> {}", ret_ty) };
+
+    ctx.add_assist(AssistId("extract_visitor_trait"), "Extract visitor trait (scaffold)", |edit| {
+        let mut buf = String::new();
+        buf.push_str(&format!("\n\ntrait {}Visitor {{", enum_name));
+        for (i, (variant, _, params, arm)) in arms.iter().enumerate() {
+            if i > 0 {
+                buf.push('\n');
+            }
+            let params =
+                params.iter().map(|(name, ty)| format!(", {}: {}", name, ty)).collect::<String>();
+            buf.push_str(&format!(
+                "\n    fn visit_{}(&mut self{}){} {}",
+                to_snake_case(&variant.name(db).to_string()),
+                params,
+                ret,
+                method_body(&ctx, arm)
+                    .unwrap_or_else(|| "{\n        unimplemented!()\n    }".to_string())
+            ));
+        }
+        buf.push_str(&format!(
+            "\n}}\n\nfn walk_{}(visitor: &mut impl {}Visitor, {}: {}){} {{\n    match {} {{",
+            snake_enum_name, enum_name, snake_enum_name, scrutinee_ty, ret, snake_enum_name
+        ));
+        for (variant, pat, params, _) in &arms {
+            let args = params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+            buf.push_str(&format!(
+                "\n        {} => visitor.visit_{}({}),",
+                pat.syntax(),
+                to_snake_case(&variant.name(db).to_string()),
+                args.join(", ")
+            ));
+        }
+        buf.push_str("\n    }\n}");
+        let indent = leading_indent(&anchor).unwrap_or_default();
+        let buf =
+            buf.replace('\n', &format!("\n{}", indent)).replace(&format!("{}\n", indent), "\n");
+
+        edit.target(match_expr.syntax().text_range());
+        edit.insert(anchor.text_range().end(), buf);
+    })
+}
+
+fn arm_variant(ctx: &AssistCtx<impl HirDatabase>, pat: &ast::Pat) -> Option<EnumVariant> {
+    let analyzer = ctx.source_analyzer(pat.syntax(), None);
+    let path = match pat {
+        ast::Pat::PathPat(it) => it.path()?,
+        ast::Pat::TupleStructPat(it) => it.path()?,
+        ast::Pat::RecordPat(it) => {
+            return match analyzer.resolve_record_pattern(it)? {
+                VariantDef::EnumVariant(it) => Some(it),
+                _ => None,
+            };
+        }
+        _ => return None,
+    };
+    match analyzer.resolve_path(ctx.db, &path)? {
+        PathResolution::Def(ModuleDef::EnumVariant(it)) => Some(it),
+        _ => None,
+    }
+}
+
+/// The body of the arm as the body of a method, if it only uses the bindings
+/// of the arm and doesn't return or break out of the function.
+fn method_body(ctx: &AssistCtx<impl HirDatabase>, arm: &ast::MatchArm) -> Option<String> {
+    let expr = arm.expr()?;
+    let arm_range = arm.syntax().text_range();
+    for node in expr.syntax().descendants() {
+        let escapes = ast::ReturnExpr::can_cast(node.kind())
+            || ast::TryExpr::can_cast(node.kind())
+            || ast::BreakExpr::can_cast(node.kind())
+            || ast::ContinueExpr::can_cast(node.kind())
+            || ast::AwaitExpr::can_cast(node.kind())
+            || ast::MacroCall::can_cast(node.kind());
+        if escapes {
+            return None;
+        }
+        if let Some(path_expr) = ast::PathExpr::cast(node) {
+            let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+            if let Some(PathResolution::Local(local)) =
+                analyzer.resolve_path(ctx.db, &path_expr.path()?)
+            {
+                let range = match local.source(ctx.db).value {
+                    Either::Left(it) => it.syntax().text_range(),
+                    Either::Right(_) => return None,
+                };
+                if !is_subrange(arm_range, range) {
+                    return None;
+                }
+            }
+        }
+    }
+    if expr.syntax().descendants_with_tokens().any(|it| it.kind() == T![self]) {
+        return None;
+    }
+
+    let body = match expr {
+        ast::Expr::BlockExpr(block) => {
+            let level = IndentLevel::from_node(arm.syntax());
+            IndentLevel(1).increase_indent(level.decrease_indent(block)).syntax().to_string()
+        }
+        expr => format!("{{\n        {}\n    }}", expr.syntax()),
+    };
+    Some(body)
+}
+
+fn is_subrange(range: TextRange, subrange: TextRange) -> bool {
+    range.start() <= subrange.start() && subrange.end() <= range.end()
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut res = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                res.push('_');
+            }
+            res.extend(c.to_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_visitor_trait_with_stubs() {
+        check_assist(
+            extract_visitor_trait,
+            r#"
+enum Event { Click { x: u32, y: u32 }, Key(char), Quit }
+struct App;
+impl App {
+    fn press(&mut self, c: char) {}
+    fn handle(&mut self, event: &Event) {
+        <|>match event {
+            Event::Click { x, y: _ } => {
+                let doubled = x * 2;
+                log(doubled);
+            }
+            Event::Key(c) => self.press(*c),
+            Event::Quit => {}
+        }
+    }
+}
+"#,
+            r#"
+enum Event { Click { x: u32, y: u32 }, Key(char), Quit }
+struct App;
+impl App {
+    fn press(&mut self, c: char) {}
+    fn handle(&mut self, event: &Event) {
+        <|>match event {
+            Event::Click { x, y: _ } => {
+                let doubled = x * 2;
+                log(doubled);
+            }
+            Event::Key(c) => self.press(*c),
+            Event::Quit => {}
+        }
+    }
+}
+
+trait EventVisitor {
+    fn visit_click(&mut self, x: &u32) {
+        let doubled = x * 2;
+        log(doubled);
+    }
+
+    fn visit_key(&mut self, c: &char) {
+        unimplemented!()
+    }
+
+    fn visit_quit(&mut self) {}
+}
+
+fn walk_event(visitor: &mut impl EventVisitor, event: &Event) {
+    match event {
+        Event::Click { x, y: _ } => visitor.visit_click(x),
+        Event::Key(c) => visitor.visit_key(c),
+        Event::Quit => visitor.visit_quit(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_visitor_trait_target() {
+        check_assist_target(
+            extract_visitor_trait,
+            "enum E { A, B }\nfn f(e: E) { <|>match e { E::A => (), E::B => () } }",
+            "match e { E::A => (), E::B => () }",
+        );
+    }
+
+    #[test]
+    fn extract_visitor_trait_not_applicable() {
+        // Not every variant has an arm of its own.
+        check_assist_not_applicable(
+            extract_visitor_trait,
+            "enum E { A, B, C }\nfn f(e: E) { <|>match e { E::A => (), _ => () } }",
+        );
+        check_assist_not_applicable(
+            extract_visitor_trait,
+            "enum E { A, B }\nfn f(e: E) { <|>match e { E::A | E::B => () } }",
+        );
+        check_assist_not_applicable(
+            extract_visitor_trait,
+            "enum E { A(u8), B }\nfn f(e: E) { <|>match e { E::A(x) if x > 0 => (), E::A(_) => (), E::B => () } }",
+        );
+        // In the arms.
+        check_assist_not_applicable(
+            extract_visitor_trait,
+            "enum E { A, B }\nfn f(e: E) { match e { E::A => <|>(), E::B => () } }",
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use hir::{db::HirDatabase, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::COMMENT,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_field_shorthand
+//
+// Uses the field init shorthand for the fields of a struct literal which are
+// initialized with a variable of the same name.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn point(x: i32, y: i32) -> Point {
+//     Point { x: x, <|>y: y }
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn point(x: i32, y: i32) -> Point {
+//     Point { x, y }
+// }
+// ```
+pub(crate) fn use_field_shorthand(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let record_lit = ctx.find_node_at_offset::<ast::RecordLit>()?;
+    let field_list = record_lit.record_field_list()?;
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(record_lit.syntax(), None);
+    let fields = field_list
+        .fields()
+        .filter(|field| {
+            let (name_ref, path) = match (field.name_ref(), field.expr()) {
+                (Some(name_ref), Some(ast::Expr::PathExpr(it))) => match it.path() {
+                    Some(path) => (name_ref, path),
+                    None => return false,
+                },
+                _ => return false,
+            };
+            let is_same_name = path.qualifier().is_none()
+                && path.syntax().text() == name_ref.syntax().text()
+                && !field.syntax().descendants_with_tokens().any(|it| it.kind() == COMMENT);
+            // Only variables, anything else might be shadowed differently.
+            is_same_name
+                && match analyzer.resolve_path(db, &path) {
+                    Some(PathResolution::Local(_)) => true,
+                    _ => false,
+                }
+        })
+        .collect::<Vec<_>>();
+    let first = fields.first()?.syntax().text_range().start();
+
+    ctx.add_assist(AssistId("use_field_shorthand"), "Use field shorthand", |edit| {
+        edit.target(field_list.syntax().text_range());
+        for field in fields {
+            if let Some(name_ref) = field.name_ref() {
+                edit.replace(field.syntax().text_range(), name_ref.syntax().text().to_string());
+            }
+        }
+        edit.set_cursor(first);
+    })
+}
+
+// Assist: expand_field_shorthand
+//
+// Spells out the values of the fields of a struct literal which use the field
+// init shorthand.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn point(x: i32, y: i32) -> Point {
+//     Point { x, <|>y }
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn point(x: i32, y: i32) -> Point {
+//     Point { x: x, y: y }
+// }
+// ```
+pub(crate) fn expand_field_shorthand(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let record_lit = ctx.find_node_at_offset::<ast::RecordLit>()?;
+    let field_list = record_lit.record_field_list()?;
+    let names = field_list
+        .fields()
+        .filter(|it| it.expr().is_none())
+        .filter_map(|it| it.name_ref())
+        .collect::<Vec<_>>();
+    let first = names.first()?.syntax().text_range().start();
+
+    ctx.add_assist(AssistId("expand_field_shorthand"), "Expand shorthand", |edit| {
+        edit.target(field_list.syntax().text_range());
+        for name_ref in names {
+            edit.insert(name_ref.syntax().text_range().end(), format!(": {}", name_ref.syntax()));
+        }
+        edit.set_cursor(first);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_field_shorthand_for_matching_fields() {
+        check_assist(
+            use_field_shorthand,
+            r#"
+struct S { a: u8, b: u8, c: u8, d: u8 }
+const D: u8 = 0;
+fn f(a: u8, c: u8) -> S {
+    let b = a;
+    S {
+        a: <|>a,
+        b: c,
+        c: c,
+        d: D,
+    }
+}
+"#,
+            r#"
+struct S { a: u8, b: u8, c: u8, d: u8 }
+const D: u8 = 0;
+fn f(a: u8, c: u8) -> S {
+    let b = a;
+    S {
+        <|>a,
+        b: c,
+        c,
+        d: D,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_field_shorthand_target() {
+        check_assist_target(
+            use_field_shorthand,
+            "struct S { a: u8 }\nfn f(a: u8) { S { a: <|>a }; }",
+            "{ a: a }",
+        );
+    }
+
+    #[test]
+    fn use_field_shorthand_not_applicable() {
+        check_assist_not_applicable(
+            use_field_shorthand,
+            "struct S { a: u8 }\nfn f(a: u8) { S { <|>a }; }",
+        );
+        check_assist_not_applicable(
+            use_field_shorthand,
+            "struct S { a: u8 }\nconst a: u8 = 0;\nfn f() { S { <|>a: a }; }",
+        );
+        check_assist_not_applicable(
+            use_field_shorthand,
+            "struct S { a: u8 }\nfn f(a: u8) { S { <|>a: a /* same */ }; }",
+        );
+    }
+
+    #[test]
+    fn expand_field_shorthand_for_shorthand_fields() {
+        check_assist(
+            expand_field_shorthand,
+            "struct S { a: u8, b: u8, c: u8 }\nfn f(a: u8, c: u8) { S { a, b: 1, <|>c }; }",
+            "struct S { a: u8, b: u8, c: u8 }\nfn f(a: u8, c: u8) { S { <|>a: a, b: 1, c: c }; }",
+        );
+    }
+
+    #[test]
+    fn expand_field_shorthand_target() {
+        check_assist_target(
+            expand_field_shorthand,
+            "struct S { a: u8 }\nfn f(a: u8) { S { <|>a }; }",
+            "{ a }",
+        );
+    }
+
+    #[test]
+    fn expand_field_shorthand_not_applicable() {
+        check_assist_not_applicable(
+            expand_field_shorthand,
+            "struct S { a: u8 }\nfn f(a: u8) { S { <|>a: a }; }",
+        );
+    }
+}
@@ -2,10 +2,13 @@
 
 use std::iter;
 
-use hir::{db::HirDatabase, Adt, HasSource};
-use ra_syntax::ast::{self, edit::IndentLevel, make, AstNode, NameOwner};
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, edit::IndentLevel, make, AstNode};
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{
+    utils::{build_pat, resolve_enum_def},
+    Assist, AssistCtx, AssistId,
+};
 
 // Assist: fill_match_arms
 //
@@ -81,44 +84,6 @@ fn is_trivial(arm: &ast::MatchArm) -> bool {
     })
 }
 
-fn resolve_enum_def(
-    db: &impl HirDatabase,
-    analyzer: &hir::SourceAnalyzer,
-    expr: &ast::Expr,
-) -> Option<hir::Enum> {
-    let expr_ty = analyzer.type_of(db, &expr)?;
-
-    let result = expr_ty.autoderef(db).find_map(|ty| match ty.as_adt() {
-        Some(Adt::Enum(e)) => Some(e),
-        _ => None,
-    });
-    result
-}
-
-fn build_pat(
-    db: &impl HirDatabase,
-    module: hir::Module,
-    var: hir::EnumVariant,
-) -> Option<ast::Pat> {
-    let path = crate::ast_transform::path_to_ast(module.find_use_path(db, var.into())?);
-
-    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
-    let pat: ast::Pat = match var.source(db).value.kind() {
-        ast::StructKind::Tuple(field_list) => {
-            let pats =
-                iter::repeat(make::placeholder_pat().into()).take(field_list.fields().count());
-            make::tuple_struct_pat(path, pats).into()
-        }
-        ast::StructKind::Record(field_list) => {
-            let pats = field_list.fields().map(|f| make::bind_pat(f.name().unwrap()).into());
-            make::record_pat(path, pats).into()
-        }
-        ast::StructKind::Unit => make::path_pat(path),
-    };
-
-    Some(pat)
-}
-
 #[cfg(test)]
 mod tests {
     use crate::helpers::{check_assist, check_assist_target};
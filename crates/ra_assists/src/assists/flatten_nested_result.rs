@@ -0,0 +1,158 @@
+use hir::{db::HirDatabase, Adt, Crate, HasSource, ImplBlock, Type};
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: flatten_nested_result
+//
+// Flattens an expression of type `Result<Result<T, E>, E>` into a
+// `Result<T, E>`.
+//
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// struct Error;
+//
+// fn parse(text: &str) -> Result<Result<u32, Error>, Error> { todo!() }
+//
+// fn main() {
+//     let n = <|>parse("1");
+// }
+// ```
+// ->
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// struct Error;
+//
+// fn parse(text: &str) -> Result<Result<u32, Error>, Error> { todo!() }
+//
+// fn main() {
+//     let n = parse("1").and_then(|it| it);
+// }
+// ```
+pub(crate) fn flatten_nested_result(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let db = ctx.db;
+    let (expr, outer_err, inner_err, krate) =
+        ctx.covering_element().ancestors().filter_map(ast::Expr::cast).find_map(|expr| {
+            let analyzer = ctx.source_analyzer(expr.syntax(), None);
+            let ty = analyzer.type_of(db, &expr)?;
+            let (inner, outer_err) = result_types(db, &ty)?;
+            let (_, inner_err) = result_types(db, &inner)?;
+            Some((expr, outer_err, inner_err, analyzer.module()?.krate()))
+        })?;
+    let outer_name = outer_err.display(db).to_string();
+    let inner_name = inner_err.display(db).to_string();
+    if outer_name.contains("{unknown}") || inner_name.contains("{unknown}") {
+        return None;
+    }
+    // The inner error is converted if it isn't the same.
+    let flattened = if outer_name == inner_name {
+        "and_then(|it| it)"
+    } else if has_from_impl(db, krate, &outer_err, &inner_name) {
+        "and_then(|it| it.map_err(Into::into))"
+    } else {
+        return None;
+    };
+
+    ctx.add_assist(AssistId("flatten_nested_result"), "Flatten nested Result", |edit| {
+        edit.target(expr.syntax().text_range());
+        let receiver = ExprPrecedence::Postfix.parenthesize(&expr);
+        edit.replace_expr(&expr, format!("{}.{}", receiver, flattened), ExprPrecedence::Postfix);
+        edit.set_cursor(expr.syntax().text_range().start());
+    })
+}
+
+/// For `Result<T, E>`, returns `T` and `E`.
+fn result_types(db: &impl HirDatabase, ty: &Type) -> Option<(Type, Type)> {
+    let enum_ = match ty.as_adt()? {
+        Adt::Enum(it) if it.name(db).to_string() == "Result" => it,
+        _ => return None,
+    };
+    let mut field_ty = |name: &str| {
+        let variant = enum_.variants(db).into_iter().find(|it| it.name(db).to_string() == name)?;
+        ty.variant_fields(db, variant.into()).into_iter().next().map(|(_, ty)| ty)
+    };
+    Some((field_ty("Ok")?, field_ty("Err")?))
+}
+
+/// Whether the crate has an `impl From<source> for target`.
+fn has_from_impl(db: &impl HirDatabase, krate: Crate, target: &Type, source: &str) -> bool {
+    let target_name = target.display(db).to_string();
+    ImplBlock::all_in_crate(db, krate).into_iter().any(|impl_block| {
+        if impl_block.target_ty(db).display(db).to_string() != target_name {
+            return false;
+        }
+        let trait_ref = match impl_block.source(db).value.target_trait() {
+            Some(it) => it.syntax().text().to_string().split_whitespace().collect::<String>(),
+            None => return false,
+        };
+        match trait_ref.rfind("From<") {
+            Some(idx) if trait_ref.ends_with('>') => {
+                let arg = &trait_ref[idx + "From<".len()..trait_ref.len() - 1];
+                arg.rsplit("::").next() == Some(source)
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const RESULT: &str = "enum Result<T, E> { Ok(T), Err(E) }\nstruct IoError;\nstruct AppError;\n";
+
+    #[test]
+    fn flatten_nested_result_with_conversion() {
+        check_assist(
+            flatten_nested_result,
+            &format!(
+                "{}{}",
+                RESULT,
+                "impl From<IoError> for AppError {}\nfn f(r: Result<Result<u8, IoError>, AppError>) { let x = r<|>; }"
+            ),
+            &format!(
+                "{}{}",
+                RESULT,
+                "impl From<IoError> for AppError {}\nfn f(r: Result<Result<u8, IoError>, AppError>) { let x = <|>r.and_then(|it| it.map_err(Into::into)); }"
+            ),
+        );
+    }
+
+    #[test]
+    fn flatten_nested_result_same_error() {
+        check_assist(
+            flatten_nested_result,
+            &format!("{}{}", RESULT, "fn f(r: &Result<Result<u8, ()>, ()>) { let x = *<|>r; }"),
+            &format!(
+                "{}{}",
+                RESULT,
+                "fn f(r: &Result<Result<u8, ()>, ()>) { let x = <|>(*r).and_then(|it| it); }"
+            ),
+        );
+    }
+
+    #[test]
+    fn flatten_nested_result_target() {
+        check_assist_target(
+            flatten_nested_result,
+            &format!("{}{}", RESULT, "fn f(g: fn() -> Result<Result<u8, ()>, ()>) { g(<|>); }"),
+            "g()",
+        );
+    }
+
+    #[test]
+    fn flatten_nested_result_not_applicable() {
+        check_assist_not_applicable(
+            flatten_nested_result,
+            &format!("{}{}", RESULT, "fn f(r: Result<u8, IoError>) { let x = r<|>; }"),
+        );
+        check_assist_not_applicable(
+            flatten_nested_result,
+            &format!(
+                "{}{}",
+                RESULT, "fn f(r: Result<Result<u8, IoError>, AppError>) { let x = r<|>; }"
+            ),
+        );
+    }
+}
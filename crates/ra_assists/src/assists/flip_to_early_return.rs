@@ -0,0 +1,172 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::{extract_trivial_expression, leading_indent};
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{utils::diverges, Assist, AssistCtx, AssistId};
+
+// Assist: flip_to_early_return
+//
+// Replaces an `if let` whose `else` branch diverges with a `let` of the
+// matched value, followed by the body of the `if let`.
+//
+// ```
+// fn first(s: Option<&str>) -> Option<char> {
+//     <|>if let Some(s) = s {
+//         let c = s.chars().next();
+//         c
+//     } else {
+//         return None
+//     }
+// }
+// ```
+// ->
+// ```
+// fn first(s: Option<&str>) -> Option<char> {
+//     let s = match s {
+//         Some(it) => it,
+//         _ => return None,
+//     };
+//     let c = s.chars().next();
+//     c
+// }
+// ```
+pub(crate) fn flip_to_early_return(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
+    let then_block = if_expr.then_branch()?;
+    if ctx.frange.range.start() >= then_block.syntax().text_range().start() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    let pat = match cond.pat()? {
+        ast::Pat::TupleStructPat(it) => it,
+        _ => return None,
+    };
+    let mut args = pat.args();
+    let binding = match (args.next()?, args.next()) {
+        (ast::Pat::BindPat(it), None) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => it,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    // The body can only move up if nothing follows the `if let`.
+    let parent_block = ast::Block::cast(if_expr.syntax().parent()?)?;
+    if parent_block.expr()?.syntax() != if_expr.syntax() {
+        return None;
+    }
+    if !diverges(&ctx, &ast::Expr::from(else_block.clone())) {
+        return None;
+    }
+
+    let indent = leading_indent(if_expr.syntax()).unwrap_or_default();
+    let sad_arm = match extract_trivial_expression(&else_block) {
+        Some(it) if !it.syntax().text().contains_char('\n') => format!("{},", it.syntax()),
+        _ => change_indent(&else_block.syntax().to_string(), |line| format!("    {}", line)),
+    };
+    let then_text = then_block.syntax().to_string();
+    let body = then_text.trim_start_matches('{').trim_end_matches('}').trim();
+    let body = change_indent(body, |line| {
+        line.get(4..).filter(|_| line.starts_with("    ")).unwrap_or(line).to_string()
+    });
+
+    let mut buf = String::new();
+    format!(buf, "let {} = match {} {{\n", binding.syntax(), cond.expr()?.syntax());
+    format!(buf, "{}    {}(it) => it,\n", indent, pat.path()?.syntax());
+    format!(buf, "{}    _ => {}\n", indent, sad_arm);
+    format!(buf, "{}}};", indent);
+    if !body.is_empty() {
+        format!(buf, "\n{}{}", indent, body);
+    }
+
+    ctx.add_assist(AssistId("flip_to_early_return"), "Flip to early return", |edit| {
+        edit.target(if_expr.syntax().text_range());
+        edit.replace(if_expr.syntax().text_range(), buf);
+        edit.set_cursor(if_expr.syntax().text_range().start());
+    })
+}
+
+/// Applies `f` to all but the first line.
+fn change_indent(text: &str, f: impl Fn(&str) -> String) -> String {
+    let mut lines = text.lines();
+    let mut res = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        res.push('\n');
+        if !line.trim().is_empty() {
+            res.push_str(&f(line));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn flip_to_early_return_with_block() {
+        check_assist(
+            flip_to_early_return,
+            r#"
+fn f() {
+    for x in xs {
+        if let Ok(mut y) <|>= parse(x) {
+            y += 1;
+
+            use_it(y);
+        } else {
+            log(x);
+            continue;
+        }
+    }
+}
+"#,
+            r#"
+fn f() {
+    for x in xs {
+        <|>let mut y = match parse(x) {
+            Ok(it) => it,
+            _ => {
+                log(x);
+                continue;
+            }
+        };
+        y += 1;
+
+        use_it(y);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn flip_to_early_return_target() {
+        check_assist_target(
+            flip_to_early_return,
+            "fn f() -> u8 { <|>if let Some(x) = y { x } else { return 0 } }",
+            "if let Some(x) = y { x } else { return 0 }",
+        );
+    }
+
+    #[test]
+    fn flip_to_early_return_not_applicable() {
+        // The else branch completes.
+        check_assist_not_applicable(
+            flip_to_early_return,
+            "fn f() -> u8 { <|>if let Some(x) = y { x } else { 0 } }",
+        );
+        // Code follows.
+        check_assist_not_applicable(
+            flip_to_early_return,
+            "fn f() { <|>if let Some(x) = y { g(x); } else { return } h(); }",
+        );
+        // Several bindings.
+        check_assist_not_applicable(
+            flip_to_early_return,
+            "fn f() { <|>if let S(a, b) = y { g(a); } else { return } }",
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner},
+    SyntaxKind::WHITESPACE,
+    SyntaxNode, TextUnit,
+};
+
+use crate::{utils::attr_insertion_offset, Assist, AssistCtx, AssistId};
+
+// Assist: gate_behind_feature
+//
+// Adds a `#[cfg(feature = "")]` attribute to the item, field or variant under
+// the cursor.
+//
+// ```
+// /// Serializes the config.
+// pub fn <|>to_json(config: &Config) -> String { todo!() }
+// ```
+// ->
+// ```
+// /// Serializes the config.
+// #[cfg(feature = "")]
+// pub fn to_json(config: &Config) -> String { todo!() }
+// ```
+pub(crate) fn gate_behind_feature(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (node, attrs) = gated_node(&ctx)?;
+    // Only offered outside of the body of the item.
+    let covering = ctx.covering_element();
+    let in_body = covering
+        .ancestors()
+        .take_while(|it| it != &node)
+        .any(|it| ast::Block::can_cast(it.kind()) || ast::ItemList::can_cast(it.kind()));
+    if in_body {
+        return None;
+    }
+    if attrs.iter().any(|it| it.simple_name().map_or(false, |it| it == "cfg")) {
+        return None;
+    }
+    let offset = attr_insertion_offset(&node)?;
+
+    ctx.add_assist(AssistId("gate_behind_feature"), "Gate behind feature", |edit| {
+        // Fields of tuple structs usually share a line.
+        let starts_line = node
+            .first_token()
+            .and_then(|it| it.prev_token())
+            .map_or(true, |it| it.kind() == WHITESPACE && it.text().contains('\n'));
+        let separator = if starts_line {
+            format!("\n{}", leading_indent(&node).unwrap_or_default())
+        } else {
+            " ".to_string()
+        };
+        let attr = "#[cfg(feature = \"";
+        edit.target(node.text_range());
+        edit.insert(offset, format!("{}\")]{}", attr, separator));
+        edit.set_cursor(offset + TextUnit::of_str(attr));
+    })
+}
+
+/// Returns the innermost field, variant or item at the cursor, with its
+/// attributes.
+fn gated_node(ctx: &AssistCtx<impl HirDatabase>) -> Option<(SyntaxNode, Vec<ast::Attr>)> {
+    if let Some(it) = ctx.find_node_at_offset::<ast::RecordFieldDef>() {
+        return Some((it.syntax().clone(), it.attrs().collect()));
+    }
+    if let Some(it) = ctx.find_node_at_offset::<ast::TupleFieldDef>() {
+        return Some((it.syntax().clone(), it.attrs().collect()));
+    }
+    if let Some(it) = ctx.find_node_at_offset::<ast::EnumVariant>() {
+        return Some((it.syntax().clone(), it.attrs().collect()));
+    }
+    let item = ctx.find_node_at_offset::<ast::ModuleItem>()?;
+    Some((item.syntax().clone(), item.attrs().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn gate_behind_feature_after_doc_comment() {
+        check_assist(
+            gate_behind_feature,
+            r#"
+mod m {
+    /// Docs.
+    #[derive(Debug)]
+    struct <|>S;
+}
+"#,
+            r#"
+mod m {
+    /// Docs.
+    #[cfg(feature = "<|>")]
+    #[derive(Debug)]
+    struct S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn gate_behind_feature_fields_and_variants() {
+        check_assist(
+            gate_behind_feature,
+            "struct S {\n    /// Docs.\n    a: <|>u32,\n}",
+            "struct S {\n    /// Docs.\n    #[cfg(feature = \"<|>\")]\n    a: u32,\n}",
+        );
+        check_assist(
+            gate_behind_feature,
+            "struct S(u8, <|>u32);",
+            "struct S(u8, #[cfg(feature = \"<|>\")] u32);",
+        );
+        check_assist(
+            gate_behind_feature,
+            "enum E {\n    A,\n    <|>B(u8),\n}",
+            "enum E {\n    A,\n    #[cfg(feature = \"<|>\")]\n    B(u8),\n}",
+        );
+    }
+
+    #[test]
+    fn gate_behind_feature_target() {
+        check_assist_target(gate_behind_feature, "fn <|>f() {}\nfn g() {}", "fn f() {}");
+    }
+
+    #[test]
+    fn gate_behind_feature_not_applicable() {
+        check_assist_not_applicable(gate_behind_feature, "#[cfg(test)]\nfn <|>f() {}");
+        check_assist_not_applicable(gate_behind_feature, "fn f() { <|>g(); }");
+    }
+}
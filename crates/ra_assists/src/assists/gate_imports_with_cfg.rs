@@ -0,0 +1,124 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, ModuleItemOwner},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: gate_imports_with_cfg
+//
+// Puts the selected `use` items behind a `#[cfg]` attribute, to import items
+// which only exist with some feature.
+//
+// ```
+// <|>use std::fs::File;
+// use std::io::Read;<|>
+// ```
+// ->
+// ```
+// #[cfg(feature = "")]
+// use std::fs::File;
+// #[cfg(feature = "")]
+// use std::io::Read;
+// ```
+pub(crate) fn gate_imports_with_cfg(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let items = ctx.covering_element().ancestors().find_map(|it| {
+        if let Some(file) = ast::SourceFile::cast(it.clone()) {
+            return Some(file.items().collect::<Vec<_>>());
+        }
+        let list = ast::ItemList::cast(it)?;
+        ast::Module::cast(list.syntax().parent()?)?;
+        Some(list.items().collect::<Vec<_>>())
+    })?;
+    let mut use_items = Vec::new();
+    for item in items {
+        if item.syntax().text_range().intersection(&selection).is_none() {
+            continue;
+        }
+        match item {
+            ast::ModuleItem::UseItem(it) => use_items.push(it),
+            _ => return None,
+        }
+    }
+    if use_items.is_empty() {
+        return None;
+    }
+    let is_gated =
+        |it: &ast::UseItem| it.attrs().any(|it| it.simple_name().map_or(false, |it| it == "cfg"));
+    if use_items.iter().any(is_gated) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("gate_imports_with_cfg"), "Gate imports with cfg", |edit| {
+        edit.target(selection);
+        for use_item in &use_items {
+            let indent = leading_indent(use_item.syntax()).unwrap_or_default();
+            let text = format!("#[cfg(feature = \"\")]\n{}", indent);
+            edit.insert(use_item.syntax().text_range().start(), text);
+        }
+        let first = use_items[0].syntax().text_range().start();
+        edit.set_cursor(first + TextUnit::of_str("#[cfg(feature = \""));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist_range, check_assist_range_not_applicable, check_assist_range_target,
+    };
+
+    #[test]
+    fn gate_imports_with_cfg_in_file() {
+        check_assist_range(
+            gate_imports_with_cfg,
+            r#"
+use foo::A;
+<|>use foo::B;
+#[allow(unused)]
+use bar::{C, D};<|>
+
+fn f() {}
+"#,
+            r#"
+use foo::A;
+#[cfg(feature = "<|>")]
+use foo::B;
+#[cfg(feature = "")]
+#[allow(unused)]
+use bar::{C, D};
+
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn gate_imports_with_cfg_in_module() {
+        check_assist_range(
+            gate_imports_with_cfg,
+            "mod m {\n    use a::<|>B;\n    use c::D<|>;\n}",
+            "mod m {\n    #[cfg(feature = \"<|>\")]\n    use a::B;\n    #[cfg(feature = \"\")]\n    use c::D;\n}",
+        );
+    }
+
+    #[test]
+    fn gate_imports_with_cfg_target() {
+        check_assist_range_target(gate_imports_with_cfg, "<|>use a::B;<|>\nuse c::D;", "use a::B;");
+    }
+
+    #[test]
+    fn gate_imports_with_cfg_not_applicable() {
+        check_assist_range_not_applicable(gate_imports_with_cfg, "<|>use a::B;\nfn f() {}<|>");
+        check_assist_range_not_applicable(
+            gate_imports_with_cfg,
+            "<|>#[cfg(test)]\nuse a::B;\nuse c::D;<|>",
+        );
+    }
+}
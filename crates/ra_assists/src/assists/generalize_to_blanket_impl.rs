@@ -0,0 +1,195 @@
+use hir::{db::HirDatabase, ImplBlock, InFile, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, TypeParamsOwner},
+    SyntaxKind::{IDENT, WHITESPACE},
+    SyntaxNode, TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: generalize_to_blanket_impl
+//
+// Replaces identical impls of a trait for several types with a single impl
+// for all the types satisfying a bound.
+//
+// ```
+// trait Describe { fn describe(&self) -> String; }
+// struct Cat;
+// struct Dog;
+//
+// impl <|>Describe for Cat {
+//     fn describe(&self) -> String { self.to_string() }
+// }
+//
+// impl Describe for Dog {
+//     fn describe(&self) -> String { self.to_string() }
+// }
+// ```
+// ->
+// ```
+// trait Describe { fn describe(&self) -> String; }
+// struct Cat;
+// struct Dog;
+//
+// impl<T: Sized> Describe for T {
+//     fn describe(&self) -> String { self.to_string() }
+// }
+// ```
+pub(crate) fn generalize_to_blanket_impl(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    let item_list = impl_block.item_list()?;
+    if ctx.frange.range.start() >= item_list.syntax().text_range().start() {
+        return None;
+    }
+    let trait_ref = impl_block.target_trait()?;
+    let trait_path = match &trait_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let target_type = impl_block.target_type()?;
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(impl_block.syntax(), None);
+    let trait_ = match analyzer.resolve_path(db, &trait_path)? {
+        PathResolution::Def(ModuleDef::Trait(it)) => it,
+        _ => return None,
+    };
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let krate = sb.to_def(InFile::new(file_id, impl_block.clone()))?.krate(db);
+    // Only the crate of the trait may implement it for all types.
+    if trait_.module(db).krate() != krate {
+        return None;
+    }
+
+    // All the impls of the trait are in this file, and are the same.
+    let trait_impls = ImplBlock::for_trait(db, krate, trait_);
+    let impls = impl_block
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| {
+            sb.to_def(InFile::new(file_id, it.clone()))
+                .map_or(false, |it| trait_impls.contains(&it))
+        })
+        .collect::<Vec<_>>();
+    if impls.len() < 2 || impls.len() != trait_impls.len() {
+        return None;
+    }
+    let trait_tokens = tokens(trait_ref.syntax());
+    let item_tokens = tokens(item_list.syntax());
+    let is_same = |it: &ast::ImplBlock| {
+        it.type_param_list().is_none()
+            && it.where_clause().is_none()
+            && it.attrs().next().is_none()
+            && !it.is_negative()
+            && it.target_trait().map_or(false, |it| tokens(it.syntax()) == trait_tokens)
+            && it.item_list().map_or(false, |it| tokens(it.syntax()) == item_tokens)
+    };
+    if !impls.iter().all(is_same) {
+        return None;
+    }
+    // The type parameter doesn't shadow anything used in the impl.
+    let uses_t = trait_ref
+        .syntax()
+        .descendants_with_tokens()
+        .chain(item_list.syntax().descendants_with_tokens())
+        .any(|it| it.kind() == IDENT && it.as_token().map_or(false, |it| it.text() == "T"));
+    if uses_t {
+        return None;
+    }
+    let impl_kw = impl_block.syntax().children_with_tokens().find(|it| it.kind() == T![impl])?;
+    let header_range =
+        TextRange::from_to(impl_kw.text_range().start(), target_type.syntax().text_range().end());
+
+    ctx.add_assist(AssistId("generalize_to_blanket_impl"), "Generalize to blanket impl", |edit| {
+        edit.target(impl_block.syntax().text_range());
+        edit.replace(header_range, format!("impl<T: Sized> {} for T", trait_ref.syntax()));
+        // The cursor goes to the bound, to be replaced.
+        let mut cursor = header_range.start() + TextUnit::of_str("impl<T: ");
+        for other in impls.iter().filter(|it| **it != impl_block) {
+            let mut range = other.syntax().text_range();
+            if let Some(ws) =
+                other.syntax().prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+            {
+                range = TextRange::from_to(ws.text_range().start(), range.end());
+            }
+            if range.end() <= header_range.start() {
+                cursor = cursor - range.len();
+            }
+            edit.delete(range);
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+/// The tokens of the node, without whitespace.
+fn tokens(node: &SyntaxNode) -> Vec<String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() != WHITESPACE)
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn generalize_to_blanket_impl_after_other_impls() {
+        check_assist(
+            generalize_to_blanket_impl,
+            r#"
+trait Id<K> { fn id(&self) -> K; }
+struct A;
+struct B;
+struct C;
+impl Id<u32> for A {
+    fn id(&self) -> u32 {
+        self.key()
+    }
+}
+impl Id<u32> for B { fn id(&self) -> u32 { self.key() } }
+impl Id<u32> <|>for C {
+    fn id(&self) -> u32 { self.key() }
+}
+"#,
+            r#"
+trait Id<K> { fn id(&self) -> K; }
+struct A;
+struct B;
+struct C;
+impl<T: <|>Sized> Id<u32> for T {
+    fn id(&self) -> u32 { self.key() }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generalize_to_blanket_impl_target() {
+        check_assist_target(
+            generalize_to_blanket_impl,
+            "trait Tr {}\nstruct A;\nstruct B;\nimpl <|>Tr for A {}\nimpl Tr for B {}",
+            "impl Tr for A {}",
+        );
+    }
+
+    #[test]
+    fn generalize_to_blanket_impl_not_applicable() {
+        for before in &[
+            // A single impl.
+            "trait Tr {}\nstruct A;\nimpl <|>Tr for A {}",
+            // Different bodies.
+            "trait Tr { fn f(); }\nstruct A;\nstruct B;\nimpl <|>Tr for A { fn f() {} }\nimpl Tr for B { fn f() { g() } }",
+            "trait Tr {}\nstruct A;\nstruct B;\nimpl <|>Tr for A {}\nimpl<U> Tr for Vec<U> {}",
+            "trait Tr {}\nstruct A;\nstruct B;\nimpl <|>Tr for A {}\n#[cfg(test)]\nimpl Tr for B {}",
+            "trait Tr { type T; }\nstruct A;\nstruct B;\nimpl <|>Tr for A { type T = (); }\nimpl Tr for B { type T = (); }",
+        ] {
+            check_assist_not_applicable(generalize_to_blanket_impl, before);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use hir::{db::HirDatabase, Adt, FieldSource, HasSource, InFile};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxKind::COMMENT,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: generate_chained_setters
+//
+// Adds a setter returning `&mut Self` to the impl for each field of the
+// struct, so that it can be configured with chained calls.
+//
+// ```
+// pub struct Request { timeout: u64, retries: u8 }
+//
+// impl<|> Request {
+//     pub fn send(&self) {}
+// }
+// ```
+// ->
+// ```
+// pub struct Request { timeout: u64, retries: u8 }
+//
+// impl Request {
+//     pub fn send(&self) {}
+//
+//     pub fn timeout(&mut self, timeout: u64) -> &mut Self {
+//         self.timeout = timeout;
+//         self
+//     }
+//
+//     pub fn retries(&mut self, retries: u8) -> &mut Self {
+//         self.retries = retries;
+//         self
+//     }
+// }
+// ```
+pub(crate) fn generate_chained_setters(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    let item_list = impl_block.item_list()?;
+    if impl_block.target_trait().is_some()
+        || ctx.frange.range.start() >= item_list.syntax().text_range().start()
+    {
+        return None;
+    }
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let target_ty = sb.to_def(InFile::new(file_id, impl_block.clone()))?.target_ty(db);
+    let strukt = match target_ty.as_adt()? {
+        Adt::Struct(it) => it,
+        _ => return None,
+    };
+
+    // Methods of any inherent impl of the struct in the file.
+    let methods = impl_block
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| it.target_trait().is_none())
+        .filter(|it| {
+            sb.to_def(InFile::new(file_id, it.clone()))
+                .map_or(false, |it| it.target_ty(db) == target_ty)
+        })
+        .filter_map(|it| it.item_list())
+        .flat_map(|it| it.impl_items())
+        .filter_map(|it| match it {
+            ast::ImplItem::FnDef(it) => Some(it.name()?.text().clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut setters = Vec::new();
+    for field in strukt.fields(db) {
+        let field = match field.source(db).value {
+            FieldSource::Named(it) => it,
+            FieldSource::Pos(_) => return None,
+        };
+        let name = field.name()?.text().clone();
+        if !methods.contains(&name) {
+            setters.push((name, field.ascribed_type()?));
+        }
+    }
+    if setters.is_empty() {
+        return None;
+    }
+    let vis = strukt
+        .source(db)
+        .value
+        .visibility()
+        .map_or(String::new(), |it| format!("{} ", it.syntax()));
+    let last_item = item_list.impl_items().last();
+    let has_comments = item_list.syntax().descendants_with_tokens().any(|it| it.kind() == COMMENT);
+    if last_item.is_none() && has_comments {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("generate_chained_setters"), "Generate chained setters", |edit| {
+        let impl_indent = leading_indent(impl_block.syntax()).unwrap_or_default();
+        let indent = match &last_item {
+            Some(it) => leading_indent(it.syntax()).unwrap_or_default(),
+            None => format!("{}    ", impl_indent),
+        };
+        let setters = setters
+            .iter()
+            .map(|(name, ty)| {
+                format!(
+                    "{}{}fn {}(&mut self, {}: {}) -> &mut Self {{\n{}    self.{} = {};\n{}    self\n{}}}",
+                    indent,
+                    vis,
+                    name,
+                    name,
+                    ty.syntax(),
+                    indent,
+                    name,
+                    name,
+                    indent,
+                    indent
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        edit.target(impl_block.syntax().text_range());
+        match last_item {
+            Some(it) => edit.insert(it.syntax().text_range().end(), format!("\n\n{}", setters)),
+            None => edit.replace(
+                item_list.syntax().text_range(),
+                format!("{{\n{}\n{}}}", setters, impl_indent),
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn generate_chained_setters_for_missing_fields() {
+        check_assist(
+            generate_chained_setters,
+            r#"
+struct Config { name: String, value: Option<u8>, verbose: bool }
+
+mod m {
+    impl <|>super::Config {}
+}
+
+impl Config {
+    fn verbose(&mut self, verbose: bool) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+}
+"#,
+            r#"
+struct Config { name: String, value: Option<u8>, verbose: bool }
+
+mod m {
+    impl <|>super::Config {
+        fn name(&mut self, name: String) -> &mut Self {
+            self.name = name;
+            self
+        }
+
+        fn value(&mut self, value: Option<u8>) -> &mut Self {
+            self.value = value;
+            self
+        }
+    }
+}
+
+impl Config {
+    fn verbose(&mut self, verbose: bool) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_chained_setters_target() {
+        check_assist_target(
+            generate_chained_setters,
+            "struct S { n: u32 }\nimpl <|>S {}",
+            "impl S {}",
+        );
+    }
+
+    #[test]
+    fn generate_chained_setters_not_applicable() {
+        check_assist_not_applicable(
+            generate_chained_setters,
+            "struct S { n: u32 }\nimpl <|>S { fn n(&mut self, n: u32) -> &mut Self { self.n = n; self } }",
+        );
+        check_assist_not_applicable(generate_chained_setters, "struct S(u32);\nimpl <|>S {}");
+        check_assist_not_applicable(
+            generate_chained_setters,
+            "struct S { n: u32 }\ntrait T {}\nimpl T for <|>S {}",
+        );
+    }
+}
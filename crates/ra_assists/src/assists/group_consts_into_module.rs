@@ -0,0 +1,270 @@
+use std::iter::successors;
+
+use hir::{db::HirDatabase, InFile, ModuleDef, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, VisibilityOwner},
+    SyntaxKind::{IDENT, STRING},
+    TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: group_consts_into_module
+//
+// Moves the selected constants into a new module, qualifying their uses.
+//
+// ```
+// <|>const MAX_RETRIES: u32 = 3;
+// const BACKOFF_MS: u64 = 500;<|>
+//
+// fn backoff(attempt: u32) -> u64 {
+//     if attempt < MAX_RETRIES { BACKOFF_MS } else { 0 }
+// }
+// ```
+// ->
+// ```
+// mod constants {
+//     pub(super) const MAX_RETRIES: u32 = 3;
+//     pub(super) const BACKOFF_MS: u64 = 500;
+// }
+//
+// fn backoff(attempt: u32) -> u64 {
+//     if attempt < constants::MAX_RETRIES { constants::BACKOFF_MS } else { 0 }
+// }
+// ```
+pub(crate) fn group_consts_into_module(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let selection = ctx.frange.range;
+    if selection.start() == selection.end() {
+        return None;
+    }
+    let parent = ctx.covering_element().ancestors().find(|it| {
+        ast::SourceFile::can_cast(it.kind())
+            || (ast::ItemList::can_cast(it.kind())
+                && it.parent().map_or(false, |it| ast::Module::can_cast(it.kind())))
+    })?;
+    let consts = parent
+        .children()
+        .filter(|it| {
+            let range = it.text_range();
+            range.start() < selection.end() && selection.start() < range.end()
+        })
+        .map(|it| ast::ConstDef::cast(it).filter(|it| it.visibility().is_none()))
+        .collect::<Option<Vec<_>>>()?;
+    if consts.len() < 2 {
+        return None;
+    }
+    let names =
+        consts.iter().map(|it| Some(it.name()?.text().to_string())).collect::<Option<Vec<_>>>()?;
+    let is_taken = parent
+        .children()
+        .filter_map(|it| it.children().find_map(ast::Name::cast))
+        .any(|it| it.text() == "constants");
+    if is_taken {
+        return None;
+    }
+    let range = TextRange::from_to(
+        consts.first()?.syntax().text_range().start(),
+        consts.last()?.syntax().text_range().end(),
+    );
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    let mut sb = ctx.source_binder();
+    let defs = consts
+        .iter()
+        .map(|it| sb.to_def(InFile::new(file_id.into(), it.clone())))
+        .collect::<Option<Vec<_>>>()?;
+    // Private constants can only be used in the module and its descendants,
+    // which must all be in this file.
+    let module = defs[0].module(db);
+    let mut modules = vec![module];
+    while let Some(it) = modules.pop() {
+        if it.definition_source(db).file_id != file_id.into() {
+            return None;
+        }
+        modules.extend(it.children(db));
+    }
+
+    let root = parent.ancestors().last()?;
+    // Uses which can't be resolved, or qualified, reliably.
+    let has_opaque_uses = root.descendants_with_tokens().any(|it| match it.into_token() {
+        Some(token) if token.kind() == IDENT && names.contains(&token.text().to_string()) => {
+            token.parent().ancestors().any(|it| {
+                ast::MacroCall::can_cast(it.kind())
+                    || ast::UseItem::can_cast(it.kind())
+                    || ast::BindPat::can_cast(it.kind())
+            })
+        }
+        _ => false,
+    });
+    if has_opaque_uses {
+        return None;
+    }
+    let has_multiline_strings = root.descendants_with_tokens().any(|it| {
+        it.kind() == STRING && it.text_range().is_subrange(&range) && it.to_string().contains('\n')
+    });
+    if has_multiline_strings {
+        return None;
+    }
+
+    let mut qualified = Vec::new();
+    let mut is_used_outside = vec![false; defs.len()];
+    for path in root.descendants().filter_map(ast::Path::cast) {
+        // Only whole paths, not their qualifiers.
+        if path.syntax().parent().map_or(false, |it| ast::Path::can_cast(it.kind())) {
+            continue;
+        }
+        let is_moved = path.syntax().text_range().is_subrange(&range);
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        let resolution = analyzer.resolve_path(db, &path);
+        let idx = match resolution {
+            Some(PathResolution::Def(ModuleDef::Const(it))) => {
+                defs.iter().position(|def| *def == it)
+            }
+            _ => None,
+        };
+        if is_moved {
+            // Within the new module, only the other moved constants and
+            // builtin types keep resolving the same way.
+            match (path.qualifier(), &resolution) {
+                (None, Some(PathResolution::Def(ModuleDef::BuiltinType(_)))) => (),
+                (None, _) if idx.is_some() => (),
+                (Some(_), _) if is_absolute(&path) => (),
+                _ => return None,
+            }
+            continue;
+        }
+        let idx = match idx {
+            Some(it) => it,
+            None => continue,
+        };
+        if path.qualifier().is_none() && analyzer.module()? != module {
+            return None;
+        }
+        is_used_outside[idx] = true;
+        qualified.push(path.segment()?.syntax().text_range().start());
+    }
+
+    let text = root.text().to_string();
+    let mut body = String::new();
+    let mut last = range.start();
+    for (const_def, is_used_outside) in consts.iter().zip(is_used_outside) {
+        if !is_used_outside {
+            continue;
+        }
+        let const_kw = const_def
+            .syntax()
+            .children_with_tokens()
+            .find(|it| it.kind() == T![const])?
+            .text_range()
+            .start();
+        body.push_str(&text[last.to_usize()..const_kw.to_usize()]);
+        body.push_str("pub(super) ");
+        last = const_kw;
+    }
+    body.push_str(&text[last.to_usize()..range.end().to_usize()]);
+
+    ctx.add_assist(AssistId("group_consts_into_module"), "Group into module", |edit| {
+        let indent = leading_indent(consts[0].syntax()).unwrap_or_default();
+        let body = body
+            .split('\n')
+            .map(|it| if it.trim().is_empty() { String::new() } else { format!("    {}", it) })
+            .collect::<Vec<_>>()
+            .join("\n");
+        edit.target(range);
+        for offset in &qualified {
+            edit.insert(*offset, "constants::");
+        }
+        edit.replace(range, format!("mod constants {{\n{}{}\n{}}}", indent, body, indent));
+        // The cursor goes to the name of the module, to be renamed.
+        let shift =
+            qualified.iter().filter(|it| **it < range.start()).count() * "constants::".len();
+        edit.set_cursor(range.start() + TextUnit::of_str("mod ") + TextUnit::from_usize(shift));
+    })
+}
+
+/// Whether the path doesn't depend on the module it is used in.
+fn is_absolute(path: &ast::Path) -> bool {
+    let first = match successors(Some(path.clone()), |it| it.qualifier()).last() {
+        Some(it) => it,
+        None => return false,
+    };
+    match first.segment().and_then(|it| it.name_ref()) {
+        Some(it) => ["std", "core", "alloc"].contains(&it.text().as_str()),
+        None => first.syntax().text() == "crate",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist_range, check_assist_range_not_applicable, check_assist_range_target,
+    };
+
+    #[test]
+    fn group_consts_into_module_qualifies_outer_uses() {
+        check_assist_range(
+            group_consts_into_module,
+            r#"
+mod m {
+    fn f() -> u32 { A + C }
+
+    <|>const A: u32 = 1;
+
+    /// Docs.
+    const B: u64 = A as u64 + std::u64::MAX;<|>
+    const C: u32 = 2;
+
+    mod n {
+        fn g() -> u32 { super::A }
+    }
+}
+"#,
+            r#"
+mod m {
+    fn f() -> u32 { constants::A + C }
+
+    mod <|>constants {
+        pub(super) const A: u32 = 1;
+
+        /// Docs.
+        const B: u64 = A as u64 + std::u64::MAX;
+    }
+    const C: u32 = 2;
+
+    mod n {
+        fn g() -> u32 { super::constants::A }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn group_consts_into_module_target() {
+        check_assist_range_target(
+            group_consts_into_module,
+            "const A: u8 = <|>1;\nconst B: u8 = 2;<|>\nconst C: u8 = 3;",
+            "const A: u8 = 1;\nconst B: u8 = 2;",
+        );
+    }
+
+    #[test]
+    fn group_consts_into_module_not_applicable() {
+        for before in &[
+            // A single constant.
+            "<|>const A: u8 = 1;<|>\nconst B: u8 = 2;",
+            "<|>const A: u8 = 1;\nfn f() {}<|>",
+            "<|>pub const A: u8 = 1;\nconst B: u8 = 2;<|>",
+            // The constant can't be qualified in a pattern or a macro.
+            "<|>const A: u8 = 1;\nconst B: u8 = 2;<|>\nfn f(x: u8) { match x { A => (), _ => () } }",
+            "<|>const A: u8 = 1;\nconst B: u8 = 2;<|>\nfn f() { println!(\"{}\", A); }",
+            // `C` isn't visible from the module.
+            "const C: u8 = 0;\n<|>const A: u8 = C;\nconst B: u8 = 2;<|>",
+        ] {
+            check_assist_range_not_applicable(group_consts_into_module, before);
+        }
+    }
+}
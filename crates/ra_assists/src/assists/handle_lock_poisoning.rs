@@ -0,0 +1,209 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode},
+    TextRange, TextUnit,
+};
+
+use crate::{
+    assist_ctx::{ActionBuilder, ExprPrecedence},
+    utils::path_name,
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: handle_lock_poisoning
+//
+// Handles the poisoning of a lock instead of unwrapping it, recovering the
+// guard, or propagating the error when the function returns a `Result`.
+//
+// ```
+// struct Mutex<T>(T);
+// impl<T> Mutex<T> { fn lock(&self) -> LockResult<T> { loop {} } }
+//
+// fn push(items: &Mutex<Vec<u32>>, item: u32) {
+//     let mut items = items.lock().<|>unwrap();
+//     items.push(item);
+// }
+// ```
+// ->
+// ```
+// struct Mutex<T>(T);
+// impl<T> Mutex<T> { fn lock(&self) -> LockResult<T> { loop {} } }
+//
+// fn push(items: &Mutex<Vec<u32>>, item: u32) {
+//     let mut items = match items.lock() {
+//         Ok(guard) => guard,
+//         Err(poisoned) => poisoned.into_inner(),
+//     };
+//     items.push(item);
+// }
+// ```
+pub(crate) fn handle_lock_poisoning(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let unwrap_call = if is_call_to(&call, "unwrap") {
+        call
+    } else {
+        ast::MethodCallExpr::cast(call.syntax().parent()?).filter(|it| is_call_to(it, "unwrap"))?
+    };
+    let lock_call = match unwrap_call.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let receiver = lock_call.expr()?;
+    let analyzer = ctx.source_analyzer(receiver.syntax(), None);
+    let ty = analyzer.type_of(ctx.db, &receiver)?.display(ctx.db).to_string();
+    let methods: &[&str] = match lock_type(&ty)? {
+        "Mutex" => &["lock"],
+        _ => &["read", "write"],
+    };
+    if !methods.iter().any(|it| is_call_to(&lock_call, it)) {
+        return None;
+    }
+    let returns_result = unwrap_call
+        .syntax()
+        .ancestors()
+        .take_while(|it| !ast::LambdaExpr::can_cast(it.kind()))
+        .find_map(ast::FnDef::cast)
+        .and_then(|it| path_name(&it.ret_type()?.type_ref()?))
+        .map_or(false, |it| it.ends_with("Result"));
+
+    let recover = || {
+        let mut edit = ActionBuilder::default();
+        edit.label("Recover the guard");
+        let mut text = format!(
+            "match {} {{\n    Ok(guard) => guard,\n    Err(poisoned) => poisoned.into_inner(),\n}}",
+            lock_call.syntax()
+        );
+        if ExprPrecedence::Block < ExprPrecedence::required_at(&unwrap_call.clone().into()) {
+            text = format!("({})", text);
+        }
+        edit.target(unwrap_call.syntax().text_range());
+        edit.replace_node_and_indent(unwrap_call.syntax(), text);
+        edit.set_cursor(unwrap_call.syntax().text_range().start());
+        edit
+    };
+    let propagate = || {
+        let mut edit = ActionBuilder::default();
+        edit.label("Propagate the error");
+        edit.target(unwrap_call.syntax().text_range());
+        let range = TextRange::from_to(
+            lock_call.syntax().text_range().end(),
+            unwrap_call.syntax().text_range().end(),
+        );
+        edit.replace(range, "?");
+        edit.set_cursor(range.start() + TextUnit::of_char('?'));
+        edit
+    };
+    let id = AssistId("handle_lock_poisoning");
+    if returns_result {
+        ctx.add_assist_group(id, "Handle lock poisoning", || vec![propagate(), recover()])
+    } else {
+        ctx.add_assist_group(id, "Handle lock poisoning", || vec![recover()])
+    }
+}
+
+fn is_call_to(call: &ast::MethodCallExpr, name: &str) -> bool {
+    call.name_ref().map_or(false, |it| it.text() == name)
+        && call.arg_list().map_or(false, |it| it.args().next().is_none())
+}
+
+/// For `&Arc<Mutex<T>>` returns `Mutex`.
+fn lock_type(ty: &str) -> Option<&'static str> {
+    let mut ty = ty.trim_start_matches('&');
+    while ty.starts_with("Arc<") || ty.starts_with("Rc<") {
+        ty = &ty[ty.find('<')? + 1..];
+    }
+    ["Mutex", "RwLock"].iter().copied().find(|it| ty.starts_with(&format!("{}<", it)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const LOCKS: &str = r#"
+struct Mutex<T>(T);
+impl<T> Mutex<T> { fn lock(&self) -> LockResult<T> { loop {} } }
+struct RwLock<T>(T);
+impl<T> RwLock<T> { fn read(&self) -> LockResult<T> { loop {} } }
+"#;
+
+    #[test]
+    fn handle_lock_poisoning_with_question_mark() {
+        check_assist(
+            handle_lock_poisoning,
+            &format!(
+                "{}{}",
+                LOCKS,
+                "fn f(m: Mutex<u8>) -> io::Result<u8> { let x = *m.lock().unwrap<|>(); Ok(x) }"
+            ),
+            &format!(
+                "{}{}",
+                LOCKS, "fn f(m: Mutex<u8>) -> io::Result<u8> { let x = *m.lock()?<|>; Ok(x) }"
+            ),
+        );
+    }
+
+    #[test]
+    fn handle_lock_poisoning_with_match() {
+        check_assist(
+            handle_lock_poisoning,
+            &format!(
+                "{}{}",
+                LOCKS,
+                r#"
+fn f(cache: RwLock<Vec<u8>>) {
+    if true {
+        cache.<|>read().unwrap().len();
+    }
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                LOCKS,
+                r#"
+fn f(cache: RwLock<Vec<u8>>) {
+    if true {
+        <|>(match cache.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }).len();
+    }
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn handle_lock_poisoning_target() {
+        check_assist_target(
+            handle_lock_poisoning,
+            &format!("{}{}", LOCKS, "fn f(m: &Mutex<u8>) { m.lock().unwrap()<|>; }"),
+            "m.lock().unwrap()",
+        );
+    }
+
+    #[test]
+    fn handle_lock_poisoning_not_applicable() {
+        check_assist_not_applicable(
+            handle_lock_poisoning,
+            &format!("{}{}", LOCKS, "fn f(m: RwLock<u8>) { m.lock().unwrap()<|>; }"),
+        );
+        check_assist_not_applicable(
+            handle_lock_poisoning,
+            &format!("{}{}", LOCKS, "fn f(m: Option<u8>) { m.lock().unwrap()<|>; }"),
+        );
+        check_assist_not_applicable(
+            handle_lock_poisoning,
+            &format!("{}{}", LOCKS, "fn f(m: Mutex<u8>) { m.lock().expect(\"\")<|>; }"),
+        );
+    }
+
+    #[test]
+    fn lock_type_through_pointers() {
+        assert_eq!(lock_type("&Arc<Mutex<u8>>"), Some("Mutex"));
+        assert_eq!(lock_type("Rc<RwLock<Vec<u8>>>"), Some("RwLock"));
+        assert_eq!(lock_type("MutexGuard<u8>"), None);
+    }
+}
@@ -0,0 +1,96 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: hide_return_type
+//
+// Replaces the concrete return type of a function with `impl Trait`, leaving
+// the cursor on the trait name. Callers are not checked.
+//
+// ```
+// fn numbers() -> Vec<u32><|> {
+//     vec![1, 2, 3]
+// }
+// ```
+// ->
+// ```
+// fn numbers() -> impl Trait {
+//     vec![1, 2, 3]
+// }
+// ```
+pub(crate) fn hide_return_type(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let type_ref = ret_type.type_ref()?;
+    match &type_ref {
+        ast::TypeRef::ImplTraitType(_) | ast::TypeRef::NeverType(_) => return None,
+        ast::TypeRef::TupleType(it) if it.fields().next().is_none() => return None,
+        _ => (),
+    }
+    let fn_def = ast::FnDef::cast(ret_type.syntax().parent()?)?;
+    // `impl Trait` is not allowed in trait methods.
+    let container = fn_def.syntax().parent().and_then(|it| it.parent());
+    if let Some(container) = container {
+        if ast::TraitDef::can_cast(container.kind()) {
+            return None;
+        }
+        if ast::ImplBlock::cast(container).and_then(|it| it.target_trait()).is_some() {
+            return None;
+        }
+    }
+
+    ctx.add_assist(AssistId("hide_return_type"), "Hide behind impl Trait", |edit| {
+        let range = type_ref.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, "impl Trait");
+        edit.set_cursor(range.start() + TextUnit::of_str("impl "));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn hide_return_type_in_free_fn() {
+        check_assist(
+            hide_return_type,
+            "fn f() -> <|>Box<dyn Fn()> { todo() }",
+            "fn f() -> impl <|>Trait { todo() }",
+        );
+    }
+
+    #[test]
+    fn hide_return_type_in_inherent_impl() {
+        check_assist(
+            hide_return_type,
+            "impl S { fn f(&self) -> Ve<|>c<u8> { todo() } }",
+            "impl S { fn f(&self) -> impl <|>Trait { todo() } }",
+        );
+    }
+
+    #[test]
+    fn hide_return_type_target() {
+        check_assist_target(hide_return_type, "fn f() -> <|>Vec<u8> { todo() }", "Vec<u8>");
+    }
+
+    #[test]
+    fn hide_return_type_not_applicable_for_impl_trait() {
+        check_assist_not_applicable(hide_return_type, "fn f() -> impl <|>Tr { todo() }");
+    }
+
+    #[test]
+    fn hide_return_type_not_applicable_for_unit() {
+        check_assist_not_applicable(hide_return_type, "fn f() -> <|>() {}");
+    }
+
+    #[test]
+    fn hide_return_type_not_applicable_in_traits() {
+        check_assist_not_applicable(hide_return_type, "trait T { fn f() -> <|>u8; }");
+        check_assist_not_applicable(hide_return_type, "impl T for S { fn f() -> <|>u8 { 92 } }");
+    }
+}
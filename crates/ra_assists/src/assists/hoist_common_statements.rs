@@ -0,0 +1,313 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner},
+    Direction, SyntaxElement,
+    SyntaxKind::{BLOCK, IDENT, WHITESPACE},
+    SyntaxNode, TextRange, TextUnit, T,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{utils::has_side_effects, Assist, AssistCtx, AssistId};
+
+// Assist: hoist_common_statements
+//
+// Moves the statements both branches of an `if` start or end with out of the
+// `if`.
+//
+// ```
+// fn main() {
+//     <|>if fast {
+//         start(x);
+//         quick(x);
+//         finish(x);
+//     } else {
+//         start(x);
+//         slow(x);
+//         finish(x);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     start(x);
+//     if fast {
+//         quick(x);
+//     } else {
+//         slow(x);
+//     }
+//     finish(x);
+// }
+// ```
+pub(crate) fn hoist_common_statements(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_keyword = ctx.find_token_at_offset(T![if])?;
+    let if_expr = ast::IfExpr::cast(if_keyword.parent())?;
+    let if_stmt = match if_expr.syntax().parent()? {
+        it if ast::ExprStmt::can_cast(it.kind()) => it,
+        it if it.kind() == BLOCK => if_expr.syntax().clone(),
+        _ => return None,
+    };
+    let then_block = if_expr.then_branch()?.block()?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => it.block()?,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    let cond = if_expr.condition()?;
+    let then_stmts = then_block.statements().collect::<Vec<_>>();
+    let else_stmts = else_block.statements().collect::<Vec<_>>();
+    let shortest = then_stmts.len().min(else_stmts.len());
+
+    // The leading statements end up in front of the condition, so they must
+    // not touch anything the condition uses or binds.
+    let cond_names = idents(cond.syntax());
+    let leading = if has_side_effects(cond.syntax()) {
+        0
+    } else {
+        then_stmts
+            .iter()
+            .zip(&else_stmts)
+            .take_while(|(a, b)| {
+                is_same(a.syntax(), b.syntax()) && idents(a.syntax()).is_disjoint(&cond_names)
+            })
+            .count()
+    };
+
+    // The trailing statements must not use the bindings of the branches, and
+    // they only end the branches if there are no tail expressions.
+    let mut trailing = 0;
+    if then_block.expr().is_none() && else_block.expr().is_none() {
+        while leading + trailing < shortest {
+            let then_end = then_stmts.len() - trailing - 1;
+            let else_end = else_stmts.len() - trailing - 1;
+            let (a, b) = (&then_stmts[then_end], &else_stmts[else_end]);
+            if !is_same(a.syntax(), b.syntax()) {
+                break;
+            }
+            let mut bound = cond.pat().map(|it| bindings(it.syntax())).unwrap_or_default();
+            for stmt in then_stmts[leading..then_end].iter().chain(&else_stmts[leading..else_end]) {
+                bound.extend(bindings(stmt.syntax()));
+            }
+            if !idents(a.syntax()).is_disjoint(&bound) {
+                break;
+            }
+            trailing += 1;
+        }
+    }
+    if leading == 0 && trailing == 0 {
+        return None;
+    }
+
+    let hoisted_leading = &then_stmts[..leading];
+    let hoisted_trailing = &then_stmts[then_stmts.len() - trailing..];
+    // The hoisted `let`s must not shadow anything used after the `if`.
+    let later_names = if_stmt
+        .siblings(Direction::Next)
+        .skip(1)
+        .flat_map(|it| idents(&it))
+        .collect::<FxHashSet<_>>();
+    let hoisted_bindings = hoisted_leading
+        .iter()
+        .chain(hoisted_trailing)
+        .flat_map(|it| bindings(it.syntax()))
+        .collect::<FxHashSet<_>>();
+    if !hoisted_bindings.is_disjoint(&later_names) {
+        return None;
+    }
+
+    let indent = leading_indent(if_expr.syntax()).unwrap_or_default();
+    let join = |stmts: &[ast::Stmt]| {
+        stmts
+            .iter()
+            .map(|it| IndentLevel(1).decrease_indent(it.clone()).syntax().to_string())
+            .collect::<Vec<_>>()
+            .join(&format!("\n{}", indent))
+    };
+    let before =
+        if leading == 0 { String::new() } else { format!("{}\n{}", join(hoisted_leading), indent) };
+    let after = if trailing == 0 {
+        String::new()
+    } else {
+        format!("\n{}{}", indent, join(hoisted_trailing))
+    };
+    let mut removals = Vec::new();
+    for stmts in [&then_stmts, &else_stmts].iter() {
+        if leading > 0 {
+            removals.push(removal_range(&stmts[0], &stmts[leading - 1])?);
+        }
+        if trailing > 0 {
+            let len = stmts.len();
+            let first = if leading + trailing == len { 0 } else { len - trailing };
+            let range = removal_range(&stmts[first], &stmts[len - 1])?;
+            removals.retain(|it: &TextRange| !it.is_subrange(&range));
+            removals.push(range);
+        }
+    }
+    let if_start = if_expr.syntax().text_range().start();
+
+    ctx.add_assist(AssistId("hoist_common_statements"), "Hoist common statements", |edit| {
+        edit.target(if_keyword.text_range());
+        if !before.is_empty() {
+            edit.insert(if_start, before.clone());
+        }
+        for range in removals {
+            edit.delete(range);
+        }
+        if !after.is_empty() {
+            edit.insert(if_stmt.text_range().end(), after);
+        }
+        edit.set_cursor(if_start + TextUnit::of_str(&before));
+    })
+}
+
+/// Compares the nodes token by token, ignoring whitespace and comments.
+fn is_same(lhs: &SyntaxNode, rhs: &SyntaxNode) -> bool {
+    let tokens = |node: &SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| !it.kind().is_trivia())
+            .map(|it| (it.kind(), it.text().clone()))
+            .collect::<Vec<_>>()
+    };
+    tokens(lhs) == tokens(rhs)
+}
+
+fn idents(node: &SyntaxNode) -> FxHashSet<String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == IDENT || it.kind() == T![self])
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+/// Returns the names bound by a `let` statement or a pattern.
+fn bindings(node: &SyntaxNode) -> FxHashSet<String> {
+    let pat = match ast::LetStmt::cast(node.clone()) {
+        Some(it) => match it.pat() {
+            Some(it) => it.syntax().clone(),
+            None => return FxHashSet::default(),
+        },
+        None if ast::Pat::can_cast(node.kind()) => node.clone(),
+        None => return FxHashSet::default(),
+    };
+    pat.descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|it| Some(it.name()?.text().to_string()))
+        .collect()
+}
+
+/// Returns the range removing the statements from `first` to `last` of a
+/// block along with the whitespace separating them from the rest.
+fn removal_range(first: &ast::Stmt, last: &ast::Stmt) -> Option<TextRange> {
+    let sibling = |node: &SyntaxNode, direction| {
+        let mut siblings = node.siblings_with_tokens(direction).skip(1);
+        siblings.find(|it: &SyntaxElement| it.kind() != WHITESPACE)
+    };
+    let prev = sibling(first.syntax(), Direction::Prev)?;
+    let next = sibling(last.syntax(), Direction::Next)?;
+    let range = match (prev.kind() == T!['{'], next.kind() == T!['}']) {
+        (true, true) => TextRange::from_to(prev.text_range().end(), next.text_range().start()),
+        (true, false) => {
+            TextRange::from_to(first.syntax().text_range().start(), next.text_range().start())
+        }
+        (false, _) => TextRange::from_to(prev.text_range().end(), last.syntax().text_range().end()),
+    };
+    Some(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn hoist_leading_and_trailing_statements() {
+        check_assist(
+            hoist_common_statements,
+            r#"
+fn f(x: u32) {
+    <|>if c() {
+        let y = x * 2;
+        // Small.
+        small(y);
+        done(
+            x,
+        );
+    } else {
+        let y = x  *  2;
+        big(y);
+        done(
+            x,
+        );
+    }
+}
+"#,
+            r#"
+fn f(x: u32) {
+    let y = x * 2;
+    <|>if c() {
+        // Small.
+        small(y);
+    } else {
+        big(y);
+    }
+    done(
+        x,
+    );
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_all_statements() {
+        check_assist(
+            hoist_common_statements,
+            "fn f() {\n    <|>if c() {\n        a();\n    } else {\n        a();\n    }\n}",
+            "fn f() {\n    a();\n    <|>if c() {} else {}\n}",
+        );
+    }
+
+    #[test]
+    fn hoist_common_statements_target() {
+        check_assist_target(
+            hoist_common_statements,
+            "fn f() { <|>if c() { a(); b(); } else { a(); } }",
+            "if",
+        );
+    }
+
+    #[test]
+    fn hoist_common_statements_not_applicable() {
+        // Nothing in common.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f() { <|>if c() { a(); } else { b(); } }",
+        );
+        // Uses a binding of the condition.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f() { <|>if let Some(x) = o { log(x); a(); } else { log(x); b(); } }",
+        );
+        // Changes what the condition sees.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f() { <|>if x > 0 { x += 1; a(); } else { x += 1; b(); } }",
+        );
+        // Uses a binding of the branches.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f() { <|>if c() { let y = 1; log(y); } else { let y = 2; log(y); } }",
+        );
+        // Would shadow a variable used after the `if`.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f(y: u8) { <|>if c() { let y = 1; a(y); } else { let y = 1; b(y); } y; }",
+        );
+        // Not at the end of the branches.
+        check_assist_not_applicable(
+            hoist_common_statements,
+            "fn f() -> u8 { <|>if c() { b(); a(); 1 } else { c(); a(); 2 } }",
+        );
+    }
+}
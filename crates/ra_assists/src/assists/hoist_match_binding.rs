@@ -0,0 +1,236 @@
+use format_buf::format;
+use hir::{db::HirDatabase, ModuleDef, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    SyntaxNode, TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: hoist_match_binding
+//
+// Moves the first field, which every arm of a `match` binds under the same
+// name, into a `let` before the `match`.
+//
+// ```
+// enum Shape { Circle(u32, f64), Square(u32) }
+//
+// fn draw(shape: Shape) {
+//     <|>match shape {
+//         Shape::Circle(id, r) => circle(id, r),
+//         Shape::Square(id) => square(id),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Shape { Circle(u32, f64), Square(u32) }
+//
+// fn draw(shape: Shape) {
+//     let id = match shape {
+//         Shape::Circle(id, ..) | Shape::Square(id) => id,
+//     };
+//     match shape {
+//         Shape::Circle(_, r) => circle(id, r),
+//         Shape::Square(_) => square(id),
+//     }
+// }
+// ```
+pub(crate) fn hoist_match_binding(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+    if ctx.frange.range.start() >= arm_list.syntax().text_range().start() {
+        return None;
+    }
+    // The scrutinee is evaluated twice, so it has to be a place.
+    let scrutinee = match match_expr.expr()? {
+        it @ ast::Expr::PathExpr(_) | it @ ast::Expr::FieldExpr(_) => it,
+        _ => return None,
+    };
+    let parent = match_expr.syntax().parent()?;
+    let stmt = if ast::Block::can_cast(parent.kind()) {
+        match_expr.syntax().clone()
+    } else if ast::ExprStmt::can_cast(parent.kind()) || ast::LetStmt::can_cast(parent.kind()) {
+        parent
+    } else {
+        return None;
+    };
+
+    let analyzer = ctx.source_analyzer(match_expr.syntax(), None);
+    let mut name = None;
+    let mut enum_ = None;
+    let mut hoisted = Vec::new();
+    let mut bindings = Vec::new();
+    for arm in arm_list.arms() {
+        let mut pats = arm.pats();
+        let pat = match (pats.next()?, pats.next()) {
+            (ast::Pat::TupleStructPat(it), None) => it,
+            _ => return None,
+        };
+        let path = pat.path()?;
+        let variant = match analyzer.resolve_path(ctx.db, &path)? {
+            PathResolution::Def(ModuleDef::EnumVariant(it)) => it,
+            _ => return None,
+        };
+        let binding = match pat.args().next()? {
+            ast::Pat::BindPat(it) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => it,
+            _ => return None,
+        };
+        let binding_name = binding.name()?.text().clone();
+        if *name.get_or_insert_with(|| binding_name.clone()) != binding_name {
+            return None;
+        }
+        if *enum_.get_or_insert(variant.parent_enum(ctx.db)) != variant.parent_enum(ctx.db) {
+            return None;
+        }
+        let rest = if variant.fields(ctx.db).len() > 1 { ", .." } else { "" };
+        let hoisted_pat = format!("{}({}{})", path.syntax(), binding_name, rest);
+        if !hoisted.contains(&hoisted_pat) {
+            hoisted.push(hoisted_pat);
+        }
+        bindings.push(binding);
+    }
+    let name = name?;
+
+    // Every variant has to have a first field of the same type.
+    let mut first_fields = enum_?.variants(ctx.db).into_iter().map(|variant| {
+        variant.fields(ctx.db).first().map(|it| it.ty(ctx.db)).filter(|it| !it.contains_unknown())
+    });
+    let ty = first_fields.next()??;
+    if !first_fields.all(|it| it.as_ref() == Some(&ty)) {
+        return None;
+    }
+    // The `let` must not shadow anything the scrutinee or the code after the
+    // `match` refers to.
+    let block = stmt.parent()?;
+    let stmt_end = stmt.text_range().end();
+    let uses_name = |node: &SyntaxNode, from: TextUnit| {
+        node.descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|it| it.text() == &name && it.syntax().text_range().start() >= from)
+    };
+    if uses_name(scrutinee.syntax(), stmt.text_range().start()) || uses_name(&block, stmt_end) {
+        return None;
+    }
+
+    let indent = leading_indent(&stmt).unwrap_or_default();
+    let mut buf = String::new();
+    format!(buf, "let {} = match {} {{\n", name, scrutinee.syntax());
+    format!(buf, "{}    {} => {},\n", indent, hoisted.join(" | "), name);
+    format!(buf, "{}}};\n{}", indent, indent);
+
+    ctx.add_assist(AssistId("hoist_match_binding"), "Hoist shared binding out of match", |edit| {
+        edit.target(match_expr.syntax().text_range());
+        edit.insert(stmt.text_range().start(), buf);
+        for binding in bindings {
+            edit.replace(binding.syntax().text_range(), "_");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn hoist_match_binding_in_tail_expr() {
+        check_assist(
+            hoist_match_binding,
+            r#"
+enum E { A(u8, bool), B(u8), C(u8, u16, u32) }
+fn f(e: E) -> u8 {
+    let y = 1;
+    <|>match e {
+        E::A(x, ..) => x,
+        E::B(x) if x > y => x + 1,
+        E::B(x) => x,
+        E::C(x, _, c) => g(x, c),
+    }
+}
+"#,
+            r#"
+enum E { A(u8, bool), B(u8), C(u8, u16, u32) }
+fn f(e: E) -> u8 {
+    let y = 1;
+    <|>let x = match e {
+        E::A(x, ..) | E::B(x) | E::C(x, ..) => x,
+    };
+    match e {
+        E::A(_, ..) => x,
+        E::B(_) if x > y => x + 1,
+        E::B(_) => x,
+        E::C(_, _, c) => g(x, c),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_match_binding_in_let() {
+        check_assist(
+            hoist_match_binding,
+            r#"
+enum E { A(u8), B(u8) }
+fn f(s: S) {
+    let v = match<|> s.e { E::A(n) => n, E::B(n) => 0 };
+}
+"#,
+            r#"
+enum E { A(u8), B(u8) }
+fn f(s: S) {
+    let n = match s.e {
+        E::A(n) | E::B(n) => n,
+    };
+    let v = match<|> s.e { E::A(_) => n, E::B(_) => 0 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_match_binding_target() {
+        check_assist_target(
+            hoist_match_binding,
+            "enum E { A(u8), B(u8) } fn f(e: E) { <|>match e { E::A(x) => (), E::B(x) => () } }",
+            "match e { E::A(x) => (), E::B(x) => () }",
+        );
+    }
+
+    #[test]
+    fn hoist_match_binding_not_applicable() {
+        // Different field types.
+        check_assist_not_applicable(
+            hoist_match_binding,
+            "enum E { A(u8), B(u16) } fn f(e: E) { <|>match e { E::A(x) => (), E::B(x) => () } }",
+        );
+        // Different names.
+        check_assist_not_applicable(
+            hoist_match_binding,
+            "enum E { A(u8), B(u8) } fn f(e: E) { <|>match e { E::A(x) => (), E::B(y) => () } }",
+        );
+        // A variant without fields.
+        check_assist_not_applicable(
+            hoist_match_binding,
+            "enum E { A(u8), B } fn f(e: E) { <|>match e { E::A(x) => (), _ => () } }",
+        );
+        // The scrutinee isn't a place.
+        check_assist_not_applicable(
+            hoist_match_binding,
+            "enum E { A(u8), B(u8) } fn f() { <|>match g() { E::A(x) => (), E::B(x) => () } }",
+        );
+        // The binding would shadow a later use.
+        check_assist_not_applicable(
+            hoist_match_binding,
+            r#"
+enum E { A(u8), B(u8) }
+fn f(e: E, x: u8) {
+    <|>match e { E::A(x) => (), E::B(x) => () }
+    g(x);
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use either::Either;
+use format_buf::format;
+use hir::{db::HirDatabase, Local, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner},
+    SyntaxNode, TextRange, TextUnit,
+};
+
+use crate::{
+    utils::{is_copy_type, mutated_locals},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: hoist_out_of_loop
+//
+// Moves an expression which is the same on every iteration before the loop.
+//
+// ```
+// struct Scale { num: u32, den: u32 }
+//
+// fn sum(items: &[u32], scale: Scale) -> u32 {
+//     let mut total = 0;
+//     for item in items {
+//         total += <|>scale.num / scale.den<|> * item;
+//     }
+//     total
+// }
+// ```
+// ->
+// ```
+// struct Scale { num: u32, den: u32 }
+//
+// fn sum(items: &[u32], scale: Scale) -> u32 {
+//     let mut total = 0;
+//     let var_name = scale.num / scale.den;
+//     for item in items {
+//         total += var_name * item;
+//     }
+//     total
+// }
+// ```
+pub(crate) fn hoist_out_of_loop(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    if ctx.frange.range.is_empty() {
+        return None;
+    }
+    let expr = ctx.covering_element().ancestors().find_map(ast::Expr::cast)?;
+    match expr {
+        ast::Expr::PathExpr(_) | ast::Expr::Literal(_) => return None,
+        _ => (),
+    }
+    let loop_body = expr
+        .syntax()
+        .ancestors()
+        .skip(1)
+        .take_while(|it| !ast::LambdaExpr::can_cast(it.kind()))
+        .find_map(|it| loop_body(&it))?;
+    let loop_expr = loop_body.syntax().parent()?;
+    // The `let` goes before the statement of the loop.
+    let anchor = match loop_expr.parent() {
+        Some(parent) if ast::ExprStmt::can_cast(parent.kind()) => parent,
+        Some(parent) if ast::Block::can_cast(parent.kind()) => loop_expr.clone(),
+        _ => return None,
+    };
+    let indent = leading_indent(&anchor)?;
+
+    let db = ctx.db;
+    let mutated = mutated_locals(&ctx, &loop_expr);
+    let loop_range = loop_expr.text_range();
+    let is_invariant = |expr: &ast::Expr| {
+        let analyzer = ctx.source_analyzer(expr.syntax(), None);
+        is_side_effect_free(expr)
+            && expr.syntax().descendants().filter_map(ast::PathExpr::cast).all(|it| {
+                let local = match it.path().and_then(|it| analyzer.resolve_path(db, &it)) {
+                    Some(PathResolution::Local(it)) => it,
+                    Some(_) => return true,
+                    None => return false,
+                };
+                let declared_outside = match local.source(db).value {
+                    Either::Left(pat) => !pat.syntax().text_range().is_subrange(&loop_range),
+                    Either::Right(_) => true,
+                };
+                declared_outside && !mutated.contains(&local)
+            })
+    };
+    if !is_invariant(&expr) {
+        return None;
+    }
+    let occurrences = loop_body
+        .syntax()
+        .descendants()
+        .filter_map(ast::Expr::cast)
+        .filter(|it| it.syntax().text() == expr.syntax().text())
+        .filter(|it| it.syntax() == expr.syntax() || is_invariant(it))
+        .collect::<Vec<_>>();
+    // Values which can't be copied would be moved out by the `let`, unless
+    // every occurrence borrows them, so that the reference can be hoisted.
+    let is_copy = ctx
+        .source_analyzer(expr.syntax(), None)
+        .type_of(db, &expr)
+        .map_or(false, |ty| is_copy_type(db, &ty));
+    let (hoisted, occurrences): (String, Vec<TextRange>) = if is_copy {
+        (expr.syntax().to_string(), occurrences.iter().map(|it| it.syntax().text_range()).collect())
+    } else {
+        let refs = occurrences
+            .iter()
+            .map(|it| it.syntax().parent().and_then(ast::RefExpr::cast).filter(|it| !it.is_mut()))
+            .collect::<Option<Vec<_>>>()?;
+        (format!("&{}", expr.syntax()), refs.iter().map(|it| it.syntax().text_range()).collect())
+    };
+
+    ctx.add_assist(AssistId("hoist_out_of_loop"), "Hoist out of loop", |edit| {
+        let mut buf = String::new();
+        format!(buf, "let var_name = {};\n{}", hoisted, indent);
+        let offset = anchor.text_range().start();
+        edit.target(expr.syntax().text_range());
+        edit.insert(offset, buf);
+        for range in occurrences {
+            edit.replace(range, "var_name".to_string());
+        }
+        edit.set_cursor(offset + TextUnit::of_str("let "));
+    })
+}
+
+fn loop_body(node: &SyntaxNode) -> Option<ast::BlockExpr> {
+    if let Some(it) = ast::ForExpr::cast(node.clone()) {
+        return it.loop_body();
+    }
+    if let Some(it) = ast::WhileExpr::cast(node.clone()) {
+        return it.loop_body();
+    }
+    ast::LoopExpr::cast(node.clone())?.loop_body()
+}
+
+/// Whether evaluating the expression once instead of on every iteration
+/// doesn't change what happens. Calls may have side effects, so they aren't.
+fn is_side_effect_free(expr: &ast::Expr) -> bool {
+    expr.syntax().descendants().filter_map(ast::Expr::cast).all(|it| match it {
+        ast::Expr::BinExpr(it) => !it.op_kind().map_or(false, |it| it.is_assignment()),
+        ast::Expr::RefExpr(it) => !it.is_mut(),
+        ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::ReturnExpr(_)
+        | ast::Expr::BreakExpr(_)
+        | ast::Expr::ContinueExpr(_) => false,
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist_range, check_assist_range_not_applicable, check_assist_range_target,
+    };
+
+    #[test]
+    fn hoist_out_of_loop_replaces_occurrences() {
+        check_assist_range(
+            hoist_out_of_loop,
+            r#"
+struct Size { len: usize }
+fn f(size: Size, limit: u8) {
+    let mut i = 0;
+    while i < 10 {
+        if <|>size.len<|> > i {
+            g(size.len, limit);
+        }
+        i += 1;
+    }
+}
+"#,
+            r#"
+struct Size { len: usize }
+fn f(size: Size, limit: u8) {
+    let mut i = 0;
+    let <|>var_name = size.len;
+    while i < 10 {
+        if var_name > i {
+            g(var_name, limit);
+        }
+        i += 1;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_out_of_inner_loop() {
+        check_assist_range(
+            hoist_out_of_loop,
+            r#"
+struct Row { offset: u32 }
+fn f(rows: Vec<Row>, width: u32) {
+    for row in rows {
+        loop {
+            draw(<|>row.offset + width<|>);
+        }
+    }
+}
+"#,
+            r#"
+struct Row { offset: u32 }
+fn f(rows: Vec<Row>, width: u32) {
+    for row in rows {
+        let <|>var_name = row.offset + width;
+        loop {
+            draw(var_name);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_out_of_loop_keeps_shadowed_occurrences() {
+        check_assist_range(
+            hoist_out_of_loop,
+            r#"
+fn f(a: u8) {
+    loop {
+        g(<|>a * 2<|>);
+        let a = 1;
+        g(a * 2);
+    }
+}
+"#,
+            r#"
+fn f(a: u8) {
+    let <|>var_name = a * 2;
+    loop {
+        g(var_name);
+        let a = 1;
+        g(a * 2);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_out_of_loop_borrowed_value() {
+        check_assist_range(
+            hoist_out_of_loop,
+            r#"
+struct S { name: Name }
+fn f(s: S) {
+    loop {
+        g(&<|>s.name<|>);
+        h(&s.name);
+    }
+}
+"#,
+            r#"
+struct S { name: Name }
+fn f(s: S) {
+    let <|>var_name = &s.name;
+    loop {
+        g(var_name);
+        h(var_name);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hoist_out_of_loop_target() {
+        check_assist_range_target(
+            hoist_out_of_loop,
+            "fn f(a: u8) {\n    loop { g(<|>a * 2<|>); }\n}",
+            "a * 2",
+        );
+    }
+
+    #[test]
+    fn hoist_out_of_loop_not_applicable() {
+        // Depends on the loop variable.
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "fn f(v: Vec<u8>) {\n    for x in v { g(<|>x + 1<|>); }\n}",
+        );
+        // Mutated in the loop.
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "fn f(mut a: u8) {\n    loop { g(<|>a * 2<|>); a += 1; }\n}",
+        );
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "struct S { a: u8 }\nfn f(mut s: S) {\n    loop { g(<|>s.a<|>); s.a = 1; }\n}",
+        );
+        // Side effects.
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "fn f(v: Vec<u8>) {\n    loop { g(<|>v.len()<|>); }\n}",
+        );
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "fn f(v: Vec<u8>) {\n    loop { g(<|>v[0] + 1<|>); }\n}",
+        );
+        // Would be moved out.
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "struct S { name: Name }\nfn f(s: S) {\n    loop { g(<|>s.name<|>); }\n}",
+        );
+        // Evaluated on each call of the closure rather than each iteration.
+        check_assist_range_not_applicable(
+            hoist_out_of_loop,
+            "fn f(a: u8) {\n    loop { h(|| <|>a * 2<|>); }\n}",
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use hir::{db::HirDatabase, Adt, AssocItem, Function, HasSource, ModuleDef, PathResolution};
+use ra_syntax::ast::{self, AstNode, AttrsOwner};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: ignore_must_use_result
+//
+// Assigns a `#[must_use]` value discarded by an expression statement to `_`.
+//
+// ```
+// #[must_use]
+// fn checked_add(a: u8, b: u8) -> Option<u8> { None }
+//
+// fn main() {
+//     checked_add(<|>1, 2);
+// }
+// ```
+// ->
+// ```
+// #[must_use]
+// fn checked_add(a: u8, b: u8) -> Option<u8> { None }
+//
+// fn main() {
+//     let _ = checked_add(1, 2);
+// }
+// ```
+pub(crate) fn ignore_must_use_result(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let stmt = ctx.find_node_at_offset::<ast::ExprStmt>()?;
+    let expr = stmt.expr()?;
+    if !stmt.syntax().text().to_string().ends_with(';') {
+        return None;
+    }
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(stmt.syntax(), None);
+    let function = match &expr {
+        ast::Expr::CallExpr(call) => match call.expr() {
+            Some(ast::Expr::PathExpr(path_expr)) => {
+                match analyzer.resolve_path(db, &path_expr.path()?) {
+                    Some(PathResolution::Def(ModuleDef::Function(it)))
+                    | Some(PathResolution::AssocItem(AssocItem::Function(it))) => Some(it),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        ast::Expr::MethodCallExpr(call) => analyzer.resolve_method_call(call),
+        _ => None,
+    };
+    let fn_must_use = function.map_or(false, |it| is_must_use_fn(db, it));
+    let ty_must_use = analyzer
+        .type_of(db, &expr)
+        .and_then(|it| it.as_adt())
+        .map_or(false, |it| is_must_use_adt(db, it));
+    if !fn_must_use && !ty_must_use {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("ignore_must_use_result"), "Explicitly ignore result", |edit| {
+        edit.target(stmt.syntax().text_range());
+        edit.insert(expr.syntax().text_range().start(), "let _ = ");
+    })
+}
+
+fn is_must_use_fn(db: &impl HirDatabase, function: Function) -> bool {
+    has_must_use(&function.source(db).value)
+}
+
+fn is_must_use_adt(db: &impl HirDatabase, adt: Adt) -> bool {
+    match adt {
+        Adt::Struct(it) => has_must_use(&it.source(db).value),
+        Adt::Union(it) => has_must_use(&it.source(db).value),
+        Adt::Enum(it) => has_must_use(&it.source(db).value),
+    }
+}
+
+/// Whether there is `#[must_use]`, with or without a message.
+fn has_must_use(owner: &impl AttrsOwner) -> bool {
+    owner.attrs().any(|it| it.simple_name().map_or(false, |it| it == "must_use"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn ignore_must_use_type() {
+        check_assist(
+            ignore_must_use_result,
+            r#"
+#[must_use = "this `Result` may be an `Err` variant, which should be handled"]
+enum Result<T, E> { Ok(T), Err(E) }
+struct File;
+impl File {
+    fn flush(&mut self) -> Result<(), ()> { loop {} }
+}
+fn close(mut file: File) {
+    file.flush()<|>;
+}
+"#,
+            r#"
+#[must_use = "this `Result` may be an `Err` variant, which should be handled"]
+enum Result<T, E> { Ok(T), Err(E) }
+struct File;
+impl File {
+    fn flush(&mut self) -> Result<(), ()> { loop {} }
+}
+fn close(mut file: File) {
+    let _ = file.flush()<|>;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_must_use_method() {
+        check_assist(
+            ignore_must_use_result,
+            r#"
+struct S;
+impl S {
+    #[must_use]
+    fn len(&self) -> usize { 0 }
+}
+fn f(s: S) {
+    <|>s.len();
+}
+"#,
+            r#"
+struct S;
+impl S {
+    #[must_use]
+    fn len(&self) -> usize { 0 }
+}
+fn f(s: S) {
+    <|>let _ = s.len();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_must_use_result_target() {
+        check_assist_target(
+            ignore_must_use_result,
+            "#[must_use]\nfn g() -> u8 { 0 }\nfn f() { g()<|>; }",
+            "g();",
+        );
+    }
+
+    #[test]
+    fn ignore_must_use_result_not_applicable() {
+        check_assist_not_applicable(
+            ignore_must_use_result,
+            "fn g() -> u8 { 0 }\nfn f() { g()<|>; }",
+        );
+        check_assist_not_applicable(
+            ignore_must_use_result,
+            "#[must_use]\nfn g() -> u8 { 0 }\nfn f() -> u8 { g()<|> }",
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use hir::{db::HirDatabase, ScopeDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, ModuleItemOwner},
+    SmolStr,
+};
+
+use super::add_import::auto_import_text_edit;
+use crate::{utils::is_test_module, Assist, AssistCtx, AssistId};
+
+/// With more names than this, the whole parent module is imported.
+const MAX_SEPARATE_IMPORTS: usize = 2;
+
+// Assist: import_from_super
+//
+// Imports an item of the parent module into a test module.
+//
+// ```
+// fn double(x: u32) -> u32 { x * 2 }
+//
+// #[cfg(test)]
+// mod tests {
+//     #[test]
+//     fn doubles() {
+//         let x = <|>double(2);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn double(x: u32) -> u32 { x * 2 }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::double;
+//
+//     #[test]
+//     fn doubles() {
+//         let x = double(2);
+//     }
+// }
+// ```
+pub(crate) fn import_from_super(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let path = ctx.find_node_at_offset::<ast::Path>()?;
+    let module = path.syntax().ancestors().find_map(ast::Module::cast)?;
+    if !is_test_module(&module) {
+        return None;
+    }
+    let item_list = module.item_list()?;
+    let has_glob_import = item_list
+        .items()
+        .filter_map(|it| match it {
+            ast::ModuleItem::UseItem(it) => it.use_tree(),
+            _ => None,
+        })
+        .any(|it| it.syntax().text() == "super::*");
+    if has_glob_import {
+        return None;
+    }
+
+    let parent_names = {
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        let parent = analyzer.module()?.parent(ctx.db)?;
+        parent
+            .scope(ctx.db)
+            .into_iter()
+            .filter(|(_, def)| match def {
+                ScopeDef::ModuleDef(_) => true,
+                _ => false,
+            })
+            .map(|(name, _)| SmolStr::new(name.to_string()))
+            .collect::<Vec<_>>()
+    };
+    let is_missing = |path: &ast::Path| -> Option<SmolStr> {
+        let first = first_segment(path)?;
+        let name = first.segment()?.name_ref()?.text().clone();
+        if !parent_names.contains(&name) {
+            return None;
+        }
+        let analyzer = ctx.source_analyzer(first.syntax(), None);
+        match analyzer.resolve_path(ctx.db, &first) {
+            Some(_) => None,
+            None => Some(name),
+        }
+    };
+    let name = is_missing(&path)?;
+
+    let mut missing = Vec::new();
+    for path in item_list.syntax().descendants().filter_map(ast::Path::cast) {
+        let is_outermost =
+            path.syntax().parent().map_or(true, |it| !ast::Path::can_cast(it.kind()));
+        let is_in_use = path.syntax().ancestors().any(|it| ast::UseItem::can_cast(it.kind()));
+        if !is_outermost || is_in_use {
+            continue;
+        }
+        if let Some(it) = is_missing(&path) {
+            if !missing.contains(&it) {
+                missing.push(it);
+            }
+        }
+    }
+
+    ctx.add_assist(AssistId("import_from_super"), "Import from super", |edit| {
+        edit.target(path.syntax().text_range());
+        if missing.len() > MAX_SEPARATE_IMPORTS {
+            let first_item = match item_list.items().next() {
+                Some(it) => it,
+                None => return,
+            };
+            let indent = leading_indent(first_item.syntax()).unwrap_or_default();
+            edit.insert(
+                first_item.syntax().text_range().start(),
+                format!("use super::*;\n\n{}", indent),
+            );
+        } else {
+            let target = [SmolStr::new("super"), name];
+            auto_import_text_edit(path.syntax(), path.syntax(), &target, edit.text_edit_builder());
+        }
+    })
+}
+
+/// Returns the first segment of the outermost path containing `path`.
+fn first_segment(path: &ast::Path) -> Option<ast::Path> {
+    let outermost = path.syntax().ancestors().take_while(|it| ast::Path::can_cast(it.kind()));
+    let mut res = ast::Path::cast(outermost.last()?)?;
+    while let Some(qualifier) = res.qualifier() {
+        res = qualifier;
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn import_single_item_from_super() {
+        check_assist(
+            import_from_super,
+            r#"
+struct Point { x: u32 }
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn t() {
+        let p = <|>Point::default();
+    }
+}
+"#,
+            r#"
+struct Point { x: u32 }
+#[cfg(test)]
+mod tests {
+    use super::Point;
+
+    #[test]
+    fn t() {
+        let p = <|>Point::default();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn import_all_items_from_super() {
+        check_assist(
+            import_from_super,
+            r#"
+struct Point;
+fn a() {}
+fn b() {}
+mod tests {
+    fn helper() {}
+    #[test]
+    fn t() {
+        <|>a();
+        b();
+        let p: Point = helper();
+    }
+}
+"#,
+            r#"
+struct Point;
+fn a() {}
+fn b() {}
+mod tests {
+    use super::*;
+
+    fn helper() {}
+    #[test]
+    fn t() {
+        <|>a();
+        b();
+        let p: Point = helper();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn import_from_super_target() {
+        check_assist_target(
+            import_from_super,
+            "fn f() {}\n#[cfg(test)]\nmod tests { fn t() { <|>f(); } }",
+            "f",
+        );
+    }
+
+    #[test]
+    fn import_from_super_not_applicable() {
+        // Not a test module.
+        check_assist_not_applicable(import_from_super, "fn f() {}\nmod m { fn t() { <|>f(); } }");
+        // Already imported.
+        check_assist_not_applicable(
+            import_from_super,
+            "fn f() {}\n#[cfg(test)]\nmod tests { use super::*; fn t() { <|>f(); } }",
+        );
+        // Not in the parent module.
+        check_assist_not_applicable(
+            import_from_super,
+            "#[cfg(test)]\nmod tests { fn t() { <|>f(); } }",
+        );
+        // Defined in the test module itself.
+        check_assist_not_applicable(
+            import_from_super,
+            "fn f() {}\n#[cfg(test)]\nmod tests { fn f() {} fn t() { <|>f(); } }",
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use hir::{db::HirDatabase, Adt, InFile, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxElement, SyntaxNode, TextRange, TextUnit,
+};
+
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: inline_single_variant_enum
+//
+// Replaces an enum with a single variant by the payload of the variant.
+//
+// ```
+// enum <|>Meters { Value(u32) }
+//
+// fn double(m: Meters) -> Meters {
+//     let Meters::Value(n) = m;
+//     Meters::Value(n * 2)
+// }
+// ```
+// ->
+// ```
+// fn double(m: u32) -> u32 {
+//     let n = m;
+//     n * 2
+// }
+// ```
+pub(crate) fn inline_single_variant_enum(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let variant_list = enum_def.variant_list()?;
+    if ctx.frange.range.start() >= variant_list.syntax().text_range().start() {
+        return None;
+    }
+    // Uses in other crates can't be updated, and the type arguments of the
+    // uses would have to be substituted into the payload.
+    if enum_def.visibility().is_some() || enum_def.type_param_list().is_some() {
+        return None;
+    }
+    let mut variants = variant_list.variants();
+    let variant = match (variants.next()?, variants.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    let payload = match variant.kind() {
+        ast::StructKind::Tuple(fields) => {
+            let mut fields = fields.fields();
+            match (fields.next()?, fields.next()) {
+                (it, None) => it.type_ref()?,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    let def = ctx.source_binder().to_def(InFile::new(file_id.into(), enum_def.clone()))?;
+    // All of the uses have to be in this file.
+    let mut modules = vec![def.module(db)];
+    while let Some(it) = modules.pop() {
+        if it.definition_source(db).file_id != file_id.into() {
+            return None;
+        }
+        modules.extend(it.children(db));
+    }
+
+    let names = [enum_def.name()?.text().clone(), variant.name()?.text().clone()];
+    let root = enum_def.syntax().ancestors().last()?;
+    let mut edits = Vec::new();
+    for path in root.descendants().filter_map(ast::Path::cast) {
+        // Only the full paths, not their qualifiers.
+        if path.syntax().parent().and_then(ast::Path::cast).is_some() {
+            continue;
+        }
+        let name_ref = match path.segment().and_then(|it| it.name_ref()) {
+            Some(it) => it,
+            None => continue,
+        };
+        if !names.contains(name_ref.text()) {
+            continue;
+        }
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        let parent = path.syntax().parent()?;
+        match analyzer.resolve_path(db, &path)? {
+            PathResolution::Def(ModuleDef::Adt(Adt::Enum(it))) if it == def => {
+                // The payload may be from another crate, so it can't have the
+                // impls of the enum.
+                let path_type = ast::PathType::cast(parent)?;
+                if ast::ImplBlock::can_cast(path_type.syntax().parent()?.kind()) {
+                    return None;
+                }
+                edits.push((path_type.syntax().text_range(), payload.syntax().to_string()));
+            }
+            PathResolution::Def(ModuleDef::EnumVariant(it)) if it.parent_enum(db) == def => {
+                edits.push(unwrap_variant(parent)?);
+            }
+            _ => continue,
+        }
+    }
+
+    let whitespace = |it: Option<SyntaxElement>| ast::Whitespace::cast(it?.into_token()?);
+    let range = enum_def.syntax().text_range();
+    let range = match whitespace(enum_def.syntax().prev_sibling_or_token()) {
+        Some(it) => TextRange::from_to(it.syntax().text_range().start(), range.end()),
+        None => match whitespace(enum_def.syntax().next_sibling_or_token()) {
+            Some(it) => TextRange::from_to(range.start(), it.syntax().text_range().end()),
+            None => range,
+        },
+    };
+    // The uses in front of the enum move the cursor.
+    let shift = edits
+        .iter()
+        .filter(|(it, _)| it.end() <= range.start())
+        .map(|(it, text)| text.len() as i64 - it.len().to_usize() as i64)
+        .sum::<i64>();
+    let cursor = TextUnit::from_usize((range.start().to_usize() as i64 + shift) as usize);
+
+    ctx.add_assist(AssistId("inline_single_variant_enum"), "Inline single-variant enum", |edit| {
+        edit.target(enum_def.syntax().text_range());
+        edit.delete(range);
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+/// Returns the edit replacing a construction or a pattern of the variant with
+/// its payload, or `None` if the variant is used in some other way.
+fn unwrap_variant(parent: SyntaxNode) -> Option<(TextRange, String)> {
+    if let Some(pat) = ast::TupleStructPat::cast(parent.clone()) {
+        let mut args = pat.args();
+        return match (args.next()?, args.next()) {
+            (it, None) => Some((pat.syntax().text_range(), it.syntax().to_string())),
+            _ => None,
+        };
+    }
+    // As a value, the variant would have to become a closure.
+    let path_expr = ast::PathExpr::cast(parent)?;
+    let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+    let mut args = call.arg_list()?.args();
+    let arg = match (args.next()?, args.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    let text = ExprPrecedence::required_at(&call.clone().into()).parenthesize(&arg);
+    Some((call.syntax().text_range(), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn inline_single_variant_enum_everywhere() {
+        check_assist(
+            inline_single_variant_enum,
+            r#"
+struct Point { x: u8 }
+
+/// A position.
+enum Pos<|> {
+    At(Point),
+}
+
+fn f(p: Pos, ps: Vec<Pos>) -> Option<Pos> {
+    match p {
+        Pos::At(Point { x }) => Some(Pos::At(Point { x: x + 1 })),
+    }
+}
+"#,
+            r#"
+struct Point { x: u8 }<|>
+
+fn f(p: Point, ps: Vec<Point>) -> Option<Point> {
+    match p {
+        Point { x } => Some(Point { x: x + 1 }),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_single_variant_enum_target() {
+        check_assist_target(
+            inline_single_variant_enum,
+            "enum <|>E { A(u8) }\nfn f(e: E) {}",
+            "enum E { A(u8) }",
+        );
+    }
+
+    #[test]
+    fn inline_single_variant_enum_not_applicable() {
+        // Several variants.
+        check_assist_not_applicable(inline_single_variant_enum, "enum <|>E { A(u8), B }");
+        // Public.
+        check_assist_not_applicable(inline_single_variant_enum, "pub enum <|>E { A(u8) }");
+        // The variant as a function.
+        check_assist_not_applicable(
+            inline_single_variant_enum,
+            "enum <|>E { A(u8) }\nfn f(x: Option<u8>) { x.map(E::A); }",
+        );
+        // Methods of the enum.
+        check_assist_not_applicable(
+            inline_single_variant_enum,
+            "enum <|>E { A(u8) }\nimpl E { fn get(&self) {} }",
+        );
+    }
+}
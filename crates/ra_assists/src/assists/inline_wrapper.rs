@@ -0,0 +1,233 @@
+use hir::{db::HirDatabase, InFile, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    TextRange, TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: inline_wrapper
+//
+// Replaces the uses of a private function which only forwards its arguments
+// to another function with that function, and removes the wrapper.
+//
+// ```
+// fn area(w: u32, h: u32) -> u32 { w * h }
+//
+// fn <|>size(w: u32, h: u32) -> u32 {
+//     area(w, h)
+// }
+//
+// fn main() {
+//     let s = size(1, 2);
+// }
+// ```
+// ->
+// ```
+// fn area(w: u32, h: u32) -> u32 { w * h }
+//
+// fn main() {
+//     let s = area(1, 2);
+// }
+// ```
+pub(crate) fn inline_wrapper(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    // Callers outside of the module can't be updated.
+    if fn_def.visibility().is_some() {
+        return None;
+    }
+    let parent = fn_def.syntax().parent()?;
+    let is_module_item = ast::SourceFile::can_cast(parent.kind())
+        || parent.parent().map_or(false, |it| ast::Module::can_cast(it.kind()));
+    if !is_module_item {
+        return None;
+    }
+    let params = fn_def
+        .param_list()?
+        .params()
+        .map(|it| match it.pat()? {
+            ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => {
+                Some(it.name()?.text().clone())
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fn_def.param_list()?.self_param().is_some() {
+        return None;
+    }
+    // Generic arguments of the wrapper can't be mapped onto the callee.
+    if fn_def.type_param_list().is_some() {
+        return None;
+    }
+
+    // The body has to be a single call passing on the parameters in order.
+    let block = body.block()?;
+    if block.statements().next().is_some() {
+        return None;
+    }
+    let call = match block.expr()? {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    let callee = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let args = call
+        .arg_list()?
+        .args()
+        .map(|it| match it {
+            ast::Expr::PathExpr(it) => {
+                let path = it.path()?;
+                if path.qualifier().is_some() {
+                    return None;
+                }
+                Some(path.segment()?.name_ref()?.text().clone())
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if args != params {
+        return None;
+    }
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    let wrapper = ctx.source_binder().to_def(InFile::new(file_id.into(), fn_def.clone()))?;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    match analyzer.resolve_path(db, &callee)? {
+        PathResolution::Def(ModuleDef::Function(it)) if it != wrapper => (),
+        _ => return None,
+    }
+    let module = analyzer.module()?;
+    let mut modules = vec![module];
+    while let Some(it) = modules.pop() {
+        if it.definition_source(db).file_id != file_id.into() {
+            return None;
+        }
+        modules.extend(it.children(db));
+    }
+
+    let root = fn_def.syntax().ancestors().last()?;
+    let mut usages = Vec::new();
+    for path_expr in root.descendants().filter_map(ast::PathExpr::cast) {
+        let path = match path_expr.path() {
+            Some(it) => it,
+            None => continue,
+        };
+        if path.segment().and_then(|it| it.name_ref()).map(|it| it.text().clone())
+            != fn_def.name().map(|it| it.text().clone())
+        {
+            continue;
+        }
+        let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+        match analyzer.resolve_path(db, &path) {
+            Some(PathResolution::Def(ModuleDef::Function(it))) if it == wrapper => (),
+            _ => continue,
+        }
+        // The callee may not be visible under the same path elsewhere.
+        if analyzer.module()? != module {
+            return None;
+        }
+        usages.push(path.syntax().text_range());
+    }
+
+    let start = fn_def
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| ast::Whitespace::cast(it.into_token()?))
+        .map_or(fn_def.syntax().text_range().start(), |it| it.syntax().text_range().start());
+    let callee_text = callee.syntax().to_string();
+    // The usages before the wrapper move the cursor.
+    let shift = usages
+        .iter()
+        .filter(|it| it.end() <= start)
+        .map(|it| callee_text.len() as i64 - it.len().to_usize() as i64)
+        .sum::<i64>();
+    let cursor = TextUnit::from_usize((start.to_usize() as i64 + shift) as usize);
+
+    ctx.add_assist(AssistId("inline_wrapper"), "Inline wrapper everywhere", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        edit.delete(TextRange::from_to(start, fn_def.syntax().text_range().end()));
+        for range in usages {
+            edit.replace(range, callee_text.clone());
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn inline_wrapper_at_all_usages() {
+        check_assist(
+            inline_wrapper,
+            r#"
+mod inner {
+    pub fn run(a: u32, b: bool) {}
+}
+use inner::run;
+
+/// Runs it.
+fn <|>start(a: u32, b: bool) {
+    inner::run(a, b)
+}
+
+fn main() {
+    start(1, true);
+    let f = start;
+    run(2, false);
+}
+"#,
+            r#"
+mod inner {
+    pub fn run(a: u32, b: bool) {}
+}
+use inner::run;<|>
+
+fn main() {
+    inner::run(1, true);
+    let f = inner::run;
+    run(2, false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_wrapper_target() {
+        check_assist_target(inline_wrapper, "fn g() {}\nfn <|>f() { g() }", "fn f() { g() }");
+    }
+
+    #[test]
+    fn inline_wrapper_not_applicable() {
+        // Public wrappers may have callers elsewhere.
+        check_assist_not_applicable(inline_wrapper, "fn g() {}\npub fn <|>f() { g() }");
+        // Reordered arguments.
+        check_assist_not_applicable(
+            inline_wrapper,
+            "fn g(a: u8, b: u8) {}\nfn <|>f(a: u8, b: u8) { g(b, a) }",
+        );
+        // Transformed arguments.
+        check_assist_not_applicable(inline_wrapper, "fn g(a: u8) {}\nfn <|>f(a: u8) { g(a + 1) }");
+        // More than forwarding.
+        check_assist_not_applicable(inline_wrapper, "fn g() {}\nfn <|>f() { h(); g() }");
+        // Methods are called through their type.
+        check_assist_not_applicable(
+            inline_wrapper,
+            "fn g() {}\nstruct S;\nimpl S { fn <|>f() { g() } }",
+        );
+        // A usage in another module.
+        check_assist_not_applicable(
+            inline_wrapper,
+            "fn g() {}\nfn <|>f() { g() }\nmod m { fn h() { super::f() } }",
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    SyntaxKind::{ITEM_LIST, SOURCE_FILE, STRING},
+    TextUnit,
+};
+
+use crate::{
+    utils::{own_descendants, result_error_type, to_pascal_case},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: introduce_error_enum
+//
+// Replaces the string errors of a function with the variants of a new error
+// enum, which displays them.
+//
+// ```
+// fn parse_port(s: &str) -> Result<u16, &'static str> {<|>
+//     if s.is_empty() {
+//         return Err("empty port");
+//     }
+//     Err("not a number")
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug)]
+// enum ParsePortError {
+//     EmptyPort,
+//     NotANumber,
+// }
+//
+// impl std::fmt::Display for ParsePortError {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         let message = match self {
+//             ParsePortError::EmptyPort => "empty port",
+//             ParsePortError::NotANumber => "not a number",
+//         };
+//         f.write_str(message)
+//     }
+// }
+//
+// fn parse_port(s: &str) -> Result<u16, ParsePortError> {
+//     if s.is_empty() {
+//         return Err(ParsePortError::EmptyPort);
+//     }
+//     Err(ParsePortError::NotANumber)
+// }
+// ```
+pub(crate) fn introduce_error_enum(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() > body.syntax().text_range().start() {
+        return None;
+    }
+    let error_type = result_error_type(&fn_def.ret_type()?.type_ref()?)?;
+    if error_type.syntax().text().to_string().split_whitespace().collect::<String>()
+        != "&'staticstr"
+    {
+        return None;
+    }
+
+    // Other errors, like the ones forwarded with `?`, can't be rewritten.
+    let mut errors = Vec::new();
+    for node in own_descendants(body.syntax()) {
+        if ast::TryExpr::can_cast(node.kind()) {
+            return None;
+        }
+        let call = match ast::CallExpr::cast(node) {
+            Some(it) => it,
+            None => continue,
+        };
+        match call.expr() {
+            Some(ast::Expr::PathExpr(it)) if it.syntax().text() == "Err" => (),
+            _ => continue,
+        }
+        let mut args = call.arg_list()?.args();
+        let message = match (args.next()?, args.next()) {
+            (ast::Expr::Literal(it), None) => it.syntax().first_token()?,
+            _ => return None,
+        };
+        if message.kind() != STRING {
+            return None;
+        }
+        errors.push((call, message.text().clone()));
+    }
+    if errors.is_empty() {
+        return None;
+    }
+    // The variants of the distinct messages, in order.
+    let mut variants: Vec<(String, String)> = Vec::new();
+    for (_, message) in &errors {
+        if variants.iter().any(|(_, it)| it == message.as_str()) {
+            continue;
+        }
+        let mut name = to_pascal_case(
+            &message
+                .chars()
+                .map(|it| if it.is_alphanumeric() { it } else { '_' })
+                .collect::<String>(),
+        );
+        if !name.starts_with(|it: char| it.is_alphabetic()) {
+            name = format!("Variant{}", name);
+        }
+        let mut unique = name.clone();
+        for i in 1.. {
+            if !variants.iter().any(|(it, _)| *it == unique) {
+                break;
+            }
+            unique = format!("{}{}", name, i);
+        }
+        variants.push((unique, message.to_string()));
+    }
+
+    let enum_name = format!("{}Error", to_pascal_case(fn_def.name()?.text()));
+    let anchor = fn_def.syntax().ancestors().find(|it| match it.parent() {
+        Some(parent) if parent.kind() == ITEM_LIST => {
+            parent.parent().map_or(false, |it| ast::Module::can_cast(it.kind()))
+        }
+        Some(parent) => parent.kind() == SOURCE_FILE,
+        None => false,
+    })?;
+
+    ctx.add_assist(AssistId("introduce_error_enum"), "Introduce error enum (scaffold)", |edit| {
+        let indent = leading_indent(&anchor).unwrap_or_default();
+        let mut buf = String::new();
+        buf.push_str(&format!("#[derive(Debug)]\n{}enum ", indent));
+        let cursor = anchor.text_range().start() + TextUnit::of_str(&buf);
+        buf.push_str(&format!("{} {{\n", enum_name));
+        for (name, _) in &variants {
+            buf.push_str(&format!("{}    {},\n", indent, name));
+        }
+        buf.push_str(&format!(
+            "{}}}\n\n{}impl std::fmt::Display for {} {{\n",
+            indent, indent, enum_name
+        ));
+        buf.push_str(&format!(
+            "{}    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n",
+            indent
+        ));
+        buf.push_str(&format!("{}        let message = match self {{\n", indent));
+        for (name, message) in &variants {
+            buf.push_str(&format!(
+                "{}            {}::{} => {},\n",
+                indent, enum_name, name, message
+            ));
+        }
+        buf.push_str(&format!("{}        }};\n{}        f.write_str(message)\n", indent, indent));
+        buf.push_str(&format!("{}    }}\n{}}}\n\n{}", indent, indent, indent));
+
+        edit.target(fn_def.syntax().text_range());
+        edit.insert(anchor.text_range().start(), buf);
+        edit.replace(error_type.syntax().text_range(), enum_name.clone());
+        for (call, message) in &errors {
+            let (name, _) = variants.iter().find(|(_, it)| it == message.as_str()).unwrap();
+            edit.replace(call.syntax().text_range(), format!("Err({}::{})", enum_name, name));
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn introduce_error_enum_for_method() {
+        check_assist(
+            introduce_error_enum,
+            r#"
+struct Config;
+impl Config {
+    pub fn load_file<|>(path: &str) -> Result<Config, &'static str> {
+        if path.is_empty() {
+            return Err("no path");
+        }
+        let f = |x: u8| -> Result<u8, &'static str> { Err("closure") };
+        if path == "-" { Err("no path") } else { Err("2 many") }
+    }
+}
+"#,
+            r#"
+struct Config;
+#[derive(Debug)]
+enum <|>LoadFileError {
+    NoPath,
+    Variant2Many,
+}
+
+impl std::fmt::Display for LoadFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            LoadFileError::NoPath => "no path",
+            LoadFileError::Variant2Many => "2 many",
+        };
+        f.write_str(message)
+    }
+}
+
+impl Config {
+    pub fn load_file(path: &str) -> Result<Config, LoadFileError> {
+        if path.is_empty() {
+            return Err(LoadFileError::NoPath);
+        }
+        let f = |x: u8| -> Result<u8, &'static str> { Err("closure") };
+        if path == "-" { Err(LoadFileError::NoPath) } else { Err(LoadFileError::Variant2Many) }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_error_enum_target() {
+        check_assist_target(
+            introduce_error_enum,
+            "fn f<|>() -> Result<(), &'static str> { Err(\"bad\") }",
+            "fn f() -> Result<(), &'static str> { Err(\"bad\") }",
+        );
+    }
+
+    #[test]
+    fn introduce_error_enum_not_applicable() {
+        // Not a static string error.
+        check_assist_not_applicable(
+            introduce_error_enum,
+            "fn f<|>() -> Result<(), String> { Err(\"bad\".to_string()) }",
+        );
+        // Forwarded errors.
+        check_assist_not_applicable(
+            introduce_error_enum,
+            "fn f<|>() -> Result<(), &'static str> { g()?; Err(\"bad\") }",
+        );
+        // Not a literal.
+        check_assist_not_applicable(
+            introduce_error_enum,
+            "fn f<|>(e: &'static str) -> Result<(), &'static str> { Err(e) }",
+        );
+        // In the body.
+        check_assist_not_applicable(
+            introduce_error_enum,
+            "fn f() -> Result<(), &'static str> { <|>Err(\"bad\") }",
+        );
+    }
+}
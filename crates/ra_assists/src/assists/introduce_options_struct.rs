@@ -0,0 +1,579 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxNode, TextRange, TextUnit,
+};
+
+use crate::{
+    assist_ctx::ActionBuilder,
+    utils::{find_calls, local_function, to_pascal_case},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: introduce_options_struct
+//
+// Replaces adjacent `bool` parameters of a function with a struct of named
+// flags, and updates the calls to build it.
+//
+// ```
+// fn draw(size: u32, <|>fill: bool, outline: bool) {
+//     if fill {}
+// }
+//
+// fn main() {
+//     draw(1, true, false);
+// }
+// ```
+// ->
+// ```
+// struct DrawOptions {
+//     fill: bool,
+//     outline: bool,
+// }
+//
+// fn draw(size: u32, options: DrawOptions) {
+//     if options.fill {}
+// }
+//
+// fn main() {
+//     draw(1, DrawOptions { fill: true, outline: false });
+// }
+// ```
+pub(crate) fn introduce_options_struct(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let param = ctx.find_node_at_offset::<ast::Param>()?;
+    let param_list = ast::ParamList::cast(param.syntax().parent()?)?;
+    let fn_def = ast::FnDef::cast(param_list.syntax().parent()?)?;
+    // Callers outside of the module can't be updated.
+    if fn_def.visibility().is_some() {
+        return None;
+    }
+    let item = containing_item(&fn_def)?;
+
+    // The run of `bool` parameters around the cursor.
+    let params = param_list.params().collect::<Vec<_>>();
+    let idx = params.iter().position(|it| *it == param)?;
+    flag_name(&param)?;
+    let first = params[..idx].iter().rev().take_while(|it| flag_name(it).is_some()).count();
+    let first = idx - first;
+    let last = idx + params[idx + 1..].iter().take_while(|it| flag_name(it).is_some()).count();
+    if last == first {
+        return None;
+    }
+    let flags = params[first..=last].iter().map(flag_name).collect::<Option<Vec<_>>>()?;
+
+    let name = format!("{}Options", to_pascal_case(&fn_def.name()?.text()));
+    let root = fn_def.syntax().ancestors().last()?;
+    let is_taken = |name: &str| {
+        root.descendants().filter_map(ast::Name::cast).any(|it| it.text().as_str() == name)
+    };
+    let is_used_in_fn = fn_def
+        .syntax()
+        .descendants_with_tokens()
+        .any(|it| it.as_token().map_or(false, |it| it.text() == "options"));
+    if is_taken(&name) || is_used_in_fn {
+        return None;
+    }
+
+    let (function, module) = local_function(&ctx, &fn_def)?;
+
+    let mut edits = param_uses(&ctx, &params[first..=last], "options")?;
+    let range = TextRange::from_to(
+        params[first].syntax().text_range().start(),
+        params[last].syntax().text_range().end(),
+    );
+    edits.push((range, format!("options: {}", name)));
+    for call in find_calls(&ctx, &root, function)? {
+        let args = call.args;
+        let (first, last) = (first + call.offset, last + call.offset);
+        if args.len() <= last {
+            return None;
+        }
+        // The flags must be named at each call, so they are visible there.
+        if call.module != module {
+            return None;
+        }
+        let mut fields = Vec::new();
+        for (flag, arg) in flags.iter().zip(&args[first..=last]) {
+            if arg.syntax().text() == flag.as_str() {
+                fields.push(flag.clone());
+            } else {
+                fields.push(format!("{}: {}", flag, arg.syntax()));
+            }
+        }
+        let range = TextRange::from_to(
+            args[first].syntax().text_range().start(),
+            args[last].syntax().text_range().end(),
+        );
+        edits.push((range, format!("{} {{ {} }}", name, fields.join(", "))));
+    }
+
+    // A call nested in the flags of another one would need overlapping edits.
+    edits.sort_by_key(|(range, _)| range.start());
+    if edits.windows(2).any(|it| it[0].0.end() > it[1].0.start()) {
+        return None;
+    }
+
+    let indent = leading_indent(&item).unwrap_or_default();
+    let mut struct_def = String::new();
+    format!(struct_def, "struct {} {{\n", name);
+    for flag in &flags {
+        format!(struct_def, "{}    {}: bool,\n", indent, flag);
+    }
+    format!(struct_def, "{}}}\n\n{}", indent, indent);
+    let item_start = item.text_range().start();
+    let cursor = shifted(&edits, item_start) + TextUnit::of_str("struct ");
+
+    ctx.add_assist(AssistId("introduce_options_struct"), "Introduce options struct", |edit| {
+        edit.target(range);
+        edit.insert(item_start, struct_def);
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(cursor);
+    })
+}
+
+// Assist: introduce_config_struct
+//
+// Replaces adjacent `Option` parameters of a function with a config struct
+// with `with_` methods, and updates the calls to build it.
+//
+// ```
+// fn connect(host: &str, <|>timeout: Option<u64>, retries: Option<u8>) {}
+//
+// fn main() {
+//     connect("db", Some(30), None);
+// }
+// ```
+// ->
+// ```
+// #[derive(Default)]
+// struct ConnectConfig {
+//     timeout: Option<u64>,
+//     retries: Option<u8>,
+// }
+//
+// impl ConnectConfig {
+//     fn with_timeout(mut self, timeout: u64) -> Self {
+//         self.timeout = Some(timeout);
+//         self
+//     }
+//
+//     fn with_retries(mut self, retries: u8) -> Self {
+//         self.retries = Some(retries);
+//         self
+//     }
+// }
+//
+// fn connect(host: &str, config: ConnectConfig) {}
+//
+// fn main() {
+//     connect("db", ConnectConfig::default().with_timeout(30));
+// }
+// ```
+pub(crate) fn introduce_config_struct(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let param = ctx.find_node_at_offset::<ast::Param>()?;
+    let param_list = ast::ParamList::cast(param.syntax().parent()?)?;
+    let fn_def = ast::FnDef::cast(param_list.syntax().parent()?)?;
+    // Callers outside of the module can't be updated.
+    if fn_def.visibility().is_some() {
+        return None;
+    }
+    let item = containing_item(&fn_def)?;
+
+    // The run of `Option` parameters around the cursor.
+    let params = param_list.params().collect::<Vec<_>>();
+    let idx = params.iter().position(|it| *it == param)?;
+    optional_param(&param)?;
+    let first = params[..idx].iter().rev().take_while(|it| optional_param(it).is_some()).count();
+    let first = idx - first;
+    let last = idx + params[idx + 1..].iter().take_while(|it| optional_param(it).is_some()).count();
+    if last == first {
+        return None;
+    }
+    let options = params[first..=last].iter().map(optional_param).collect::<Option<Vec<_>>>()?;
+
+    let name = format!("{}Config", to_pascal_case(&fn_def.name()?.text()));
+    let root = fn_def.syntax().ancestors().last()?;
+    let is_taken = |name: &str| {
+        root.descendants().filter_map(ast::Name::cast).any(|it| it.text().as_str() == name)
+    };
+    let is_used_in_fn = fn_def
+        .syntax()
+        .descendants_with_tokens()
+        .any(|it| it.as_token().map_or(false, |it| it.text() == "config"));
+    if is_taken(&name) || is_used_in_fn {
+        return None;
+    }
+    let (function, module) = local_function(&ctx, &fn_def)?;
+
+    let mut edits = param_uses(&ctx, &params[first..=last], "config")?;
+    let range = TextRange::from_to(
+        params[first].syntax().text_range().start(),
+        params[last].syntax().text_range().end(),
+    );
+    edits.push((range, format!("config: {}", name)));
+    for call in find_calls(&ctx, &root, function)? {
+        let args = call.args;
+        let (first, last) = (first + call.offset, last + call.offset);
+        if args.len() <= last {
+            return None;
+        }
+        // The config must be named at each call, so it is visible there.
+        if call.module != module {
+            return None;
+        }
+        let args = &args[first..=last];
+        // Literal options are set with the methods, others with the fields.
+        let values = args.iter().map(some_value).collect::<Option<Vec<_>>>();
+        let text = match values {
+            Some(values) => {
+                let mut text = format!("{}::default()", name);
+                for ((option, _), value) in options.iter().zip(values) {
+                    if let Some(value) = value {
+                        format!(text, ".with_{}({})", option, value.syntax());
+                    }
+                }
+                text
+            }
+            None => {
+                let fields = options
+                    .iter()
+                    .zip(args)
+                    .map(|((option, _), arg)| {
+                        if arg.syntax().text() == option.as_str() {
+                            option.clone()
+                        } else {
+                            format!("{}: {}", option, arg.syntax())
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                format!("{} {{ {} }}", name, fields.join(", "))
+            }
+        };
+        let range = TextRange::from_to(
+            args[0].syntax().text_range().start(),
+            args[args.len() - 1].syntax().text_range().end(),
+        );
+        edits.push((range, text));
+    }
+
+    // A call nested in the options of another one would need overlapping edits.
+    edits.sort_by_key(|(range, _)| range.start());
+    if edits.windows(2).any(|it| it[0].0.end() > it[1].0.start()) {
+        return None;
+    }
+
+    let indent = leading_indent(&item).unwrap_or_default();
+    let mut struct_def = String::new();
+    format!(struct_def, "#[derive(Default)]\n{}struct {} {{\n", indent, name);
+    for (option, ty) in &options {
+        format!(struct_def, "{}    {}: Option<{}>,\n", indent, option, ty);
+    }
+    format!(struct_def, "{}}}\n\n{}impl {} {{\n", indent, indent, name);
+    for (i, (option, ty)) in options.iter().enumerate() {
+        if i > 0 {
+            struct_def.push('\n');
+        }
+        format!(
+            struct_def,
+            "{}    fn with_{}(mut self, {}: {}) -> Self {{\n", indent, option, option, ty
+        );
+        format!(struct_def, "{}        self.{} = Some({});\n", indent, option, option);
+        format!(struct_def, "{}        self\n{}    }}\n", indent, indent);
+    }
+    format!(struct_def, "{}}}\n\n{}", indent, indent);
+    // The struct is generated after the function and its calls are updated,
+    // in front of the item as it is then.
+    let item_start = shifted(&edits, item.text_range().start());
+    let cursor = item_start
+        + TextUnit::of_str("#[derive(Default)]\n")
+        + TextUnit::of_str(&indent)
+        + TextUnit::of_str("struct ");
+
+    let id = AssistId("introduce_config_struct");
+    ctx.add_assist_sequence(id, "Introduce config with defaults", || {
+        let mut update = ActionBuilder::default();
+        update.target(range);
+        for (range, text) in edits {
+            update.replace(range, text);
+        }
+        let mut generate = ActionBuilder::default();
+        generate.insert(item_start, struct_def);
+        generate.set_cursor(cursor);
+        vec![update, generate]
+    })
+}
+
+/// Returns the name of a plain `bool` parameter.
+fn flag_name(param: &ast::Param) -> Option<String> {
+    match param.ascribed_type()? {
+        ast::TypeRef::PathType(it) if it.syntax().text() == "bool" => (),
+        _ => return None,
+    }
+    match param.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => {
+            Some(it.name()?.text().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name and the wrapped type of a plain `Option` parameter.
+fn optional_param(param: &ast::Param) -> Option<(String, String)> {
+    let segment = match param.ascribed_type()? {
+        ast::TypeRef::PathType(it) => it.path()?.segment()?,
+        _ => return None,
+    };
+    if segment.name_ref()?.text() != "Option" {
+        return None;
+    }
+    let mut type_args = segment.type_arg_list()?.type_args();
+    let ty = match (type_args.next(), type_args.next()) {
+        (Some(it), None) => it.type_ref()?,
+        _ => return None,
+    };
+    match param.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => {
+            Some((it.name()?.text().to_string(), ty.syntax().to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// For `None` returns `Some(None)`, for `Some(x)` returns `Some(Some(x))`.
+fn some_value(arg: &ast::Expr) -> Option<Option<ast::Expr>> {
+    match arg {
+        ast::Expr::PathExpr(it) if it.syntax().text() == "None" => Some(None),
+        ast::Expr::CallExpr(call) => {
+            match call.expr()? {
+                ast::Expr::PathExpr(it) if it.syntax().text() == "Some" => (),
+                _ => return None,
+            }
+            let mut args = call.arg_list()?.args();
+            match (args.next(), args.next()) {
+                (Some(it), None) => Some(Some(it)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the module item holding the function, which is the function itself
+/// or its inherent impl block.
+fn containing_item(fn_def: &ast::FnDef) -> Option<SyntaxNode> {
+    let parent = fn_def.syntax().parent()?;
+    if ast::SourceFile::can_cast(parent.kind()) {
+        return Some(fn_def.syntax().clone());
+    }
+    let owner = parent.parent()?;
+    if ast::Module::can_cast(owner.kind()) {
+        return Some(fn_def.syntax().clone());
+    }
+    // Trait methods have to keep the signature of the trait.
+    let impl_block = ast::ImplBlock::cast(owner)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    Some(impl_block.syntax().clone())
+}
+
+/// Replaces the uses of the parameters with fields of `var`.
+fn param_uses(
+    ctx: &AssistCtx<impl HirDatabase>,
+    params: &[ast::Param],
+    var: &str,
+) -> Option<Vec<(TextRange, String)>> {
+    let mut edits = Vec::new();
+    for param in params {
+        let bind_pat = match param.pat()? {
+            ast::Pat::BindPat(it) => it,
+            _ => return None,
+        };
+        let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+        for reference in analyzer.find_all_refs(&bind_pat) {
+            let name_ref = ctx.covering_node_for_range(reference.range);
+            let is_shorthand =
+                name_ref.ancestors().find_map(ast::RecordField::cast).map_or(false, |it| {
+                    it.expr().is_none() && it.syntax().text_range() == reference.range
+                });
+            if is_shorthand {
+                let text = format!("{}: {}.{}", reference.name, var, reference.name);
+                edits.push((reference.range, text));
+            } else {
+                edits.push((reference.range, format!("{}.{}", var, reference.name)));
+            }
+        }
+    }
+    Some(edits)
+}
+
+/// Moves `offset` by the edits in front of it.
+fn shifted(edits: &[(TextRange, String)], offset: TextUnit) -> TextUnit {
+    let shift = edits
+        .iter()
+        .filter(|(range, _)| range.end() <= offset)
+        .map(|(range, text)| text.len() as i64 - range.len().to_usize() as i64)
+        .sum::<i64>();
+    TextUnit::from_usize((offset.to_usize() as i64 + shift) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn introduce_options_struct_for_method() {
+        check_assist(
+            introduce_options_struct,
+            r#"
+struct Printer;
+
+impl Printer {
+    fn print_text(&self, text: &str, bold: bool, <|>italic: bool, n: u8) {
+        let s = Style { bold, italic: !italic };
+    }
+}
+
+fn main(italic: bool) {
+    let p = Printer;
+    p.print_text("a", true, italic, 1);
+    Printer::print_text(&p, "b", false, false, 2);
+}
+"#,
+            r#"
+struct Printer;
+
+struct <|>PrintTextOptions {
+    bold: bool,
+    italic: bool,
+}
+
+impl Printer {
+    fn print_text(&self, text: &str, options: PrintTextOptions, n: u8) {
+        let s = Style { bold: options.bold, italic: !options.italic };
+    }
+}
+
+fn main(italic: bool) {
+    let p = Printer;
+    p.print_text("a", PrintTextOptions { bold: true, italic }, 1);
+    Printer::print_text(&p, "b", PrintTextOptions { bold: false, italic: false }, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_options_struct_target() {
+        check_assist_target(
+            introduce_options_struct,
+            "fn f(a: u8, b: bool, <|>c: bool) {}",
+            "b: bool, c: bool",
+        );
+    }
+
+    #[test]
+    fn introduce_options_struct_not_applicable() {
+        // A single flag.
+        check_assist_not_applicable(introduce_options_struct, "fn f(<|>a: bool, b: u8) {}");
+        // Not adjacent.
+        check_assist_not_applicable(
+            introduce_options_struct,
+            "fn f(<|>a: bool, b: u8, c: bool) {}",
+        );
+        // A trait method.
+        check_assist_not_applicable(
+            introduce_options_struct,
+            "trait T { fn f(&self, <|>a: bool, b: bool); }",
+        );
+        check_assist_not_applicable(
+            introduce_options_struct,
+            "trait T { fn f(&self, a: bool, b: bool); }\nstruct S;\nimpl T for S { fn f(&self, <|>a: bool, b: bool) {} }",
+        );
+        // Used as a value.
+        check_assist_not_applicable(
+            introduce_options_struct,
+            "fn f(<|>a: bool, b: bool) {}\nfn g() { let h = f; }",
+        );
+    }
+
+    #[test]
+    fn introduce_config_struct_with_methods_and_fields() {
+        check_assist(
+            introduce_config_struct,
+            r#"
+mod m {
+    fn fetch(url: &str, <|>retries: Option<u8>, proxy: Option<Proxy>) {
+        if let Some(r) = retries {}
+    }
+
+    fn main(proxy: Option<Proxy>) {
+        fetch("a", None, None);
+        fetch("b", Some(3), proxy);
+    }
+}
+"#,
+            r#"
+mod m {
+    #[derive(Default)]
+    struct <|>FetchConfig {
+        retries: Option<u8>,
+        proxy: Option<Proxy>,
+    }
+
+    impl FetchConfig {
+        fn with_retries(mut self, retries: u8) -> Self {
+            self.retries = Some(retries);
+            self
+        }
+
+        fn with_proxy(mut self, proxy: Proxy) -> Self {
+            self.proxy = Some(proxy);
+            self
+        }
+    }
+
+    fn fetch(url: &str, config: FetchConfig) {
+        if let Some(r) = config.retries {}
+    }
+
+    fn main(proxy: Option<Proxy>) {
+        fetch("a", FetchConfig::default());
+        fetch("b", FetchConfig { retries: Some(3), proxy });
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_config_struct_target() {
+        check_assist_target(
+            introduce_config_struct,
+            "fn f(a: u8, b: Option<u8>, <|>c: Option<u8>) {}",
+            "b: Option<u8>, c: Option<u8>",
+        );
+    }
+
+    #[test]
+    fn introduce_config_struct_not_applicable() {
+        check_assist_not_applicable(introduce_config_struct, "fn f(<|>a: Option<u8>, b: u8) {}");
+        check_assist_not_applicable(
+            introduce_config_struct,
+            "fn f(<|>a: Option<u8>, b: Option<u8>) {}\nfn g() { let h = f; }",
+        );
+        check_assist_not_applicable(
+            introduce_config_struct,
+            "trait T { fn f(&self, <|>a: Option<u8>, b: Option<u8>); }",
+        );
+        check_assist_not_applicable(
+            introduce_config_struct,
+            "pub fn f(<|>a: Option<u8>, b: Option<u8>) {}",
+        );
+    }
+}
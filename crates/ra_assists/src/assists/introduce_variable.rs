@@ -2,15 +2,12 @@ use format_buf::format;
 use hir::db::HirDatabase;
 use ra_syntax::{
     ast::{self, AstNode},
-    SyntaxKind::{
-        BLOCK_EXPR, BREAK_EXPR, COMMENT, LAMBDA_EXPR, LOOP_EXPR, MATCH_ARM, PATH_EXPR, RETURN_EXPR,
-        WHITESPACE,
-    },
+    SyntaxKind::{BLOCK_EXPR, BREAK_EXPR, COMMENT, LOOP_EXPR, PATH_EXPR, RETURN_EXPR, WHITESPACE},
     SyntaxNode, TextUnit,
 };
 use test_utils::tested_by;
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{utils::anchor_stmt, Assist, AssistCtx, AssistId};
 
 // Assist: introduce_variable
 //
@@ -105,34 +102,6 @@ fn valid_target_expr(node: SyntaxNode) -> Option<ast::Expr> {
     }
 }
 
-/// Returns the syntax node which will follow the freshly introduced var
-/// and a boolean indicating whether we have to wrap it within a { } block
-/// to produce correct code.
-/// It can be a statement, the last in a block expression or a wanna be block
-/// expression like a lambda or match arm.
-fn anchor_stmt(expr: ast::Expr) -> Option<(SyntaxNode, bool)> {
-    expr.syntax().ancestors().find_map(|node| {
-        if let Some(expr) = node.parent().and_then(ast::Block::cast).and_then(|it| it.expr()) {
-            if expr.syntax() == &node {
-                tested_by!(test_introduce_var_last_expr);
-                return Some((node, false));
-            }
-        }
-
-        if let Some(parent) = node.parent() {
-            if parent.kind() == MATCH_ARM || parent.kind() == LAMBDA_EXPR {
-                return Some((node, true));
-            }
-        }
-
-        if ast::Stmt::cast(node.clone()).is_some() {
-            return Some((node, false));
-        }
-
-        None
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
@@ -0,0 +1,259 @@
+use either::Either;
+use hir::{db::HirDatabase, HasSource, PathResolution, SourceAnalyzer};
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner, NameOwner},
+    SyntaxKind::IDENT,
+    TextRange, TextUnit, T,
+};
+
+use crate::{utils::is_copy_type, Assist, AssistCtx, AssistId};
+
+// Assist: iterate_by_reference
+//
+// Iterates over a reference to a collection which is still used after the
+// loop, instead of moving it into the loop.
+//
+// ```
+// fn main() {
+//     <|>for name in names {
+//         if name == query {
+//             found(&name);
+//         }
+//     }
+//     report(names);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     for name in &names {
+//         if *name == query {
+//             found(name);
+//         }
+//     }
+//     report(names);
+// }
+// ```
+pub(crate) fn iterate_by_reference(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let for_keyword = ctx.find_token_at_offset(T![for])?;
+    let for_expr = ast::ForExpr::cast(for_keyword.parent())?;
+    let iterable = match for_expr.iterable()? {
+        ast::Expr::PathExpr(it) => it,
+        _ => return None,
+    };
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(for_expr.syntax(), None);
+    let collection = match analyzer.resolve_path(db, &iterable.path()?)? {
+        PathResolution::Local(it) => it,
+        _ => return None,
+    };
+    // Only a collection which is moved by the loop and used afterwards.
+    let ty = analyzer.type_of(db, &ast::Expr::from(iterable.clone()))?;
+    if ty.as_adt().is_none() || is_copy_type(db, &ty) {
+        return None;
+    }
+    let collection_pat = match collection.source(db).value {
+        Either::Left(it) => it,
+        Either::Right(_) => return None,
+    };
+    let loop_end = for_expr.syntax().text_range().end();
+    if !analyzer.find_all_refs(&collection_pat).iter().any(|it| it.range.start() >= loop_end) {
+        return None;
+    }
+
+    let item = match for_expr.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let body = for_expr.loop_body()?;
+    let body_range = body.syntax().text_range();
+    let item_ty = analyzer.type_of_pat(db, &ast::Pat::from(item.clone()));
+    let mut edits = Vec::new();
+    if item_ty.map_or(false, |it| is_copy_type(db, &it)) {
+        // Copying the items out of the references keeps the body as it is.
+        edits.push((TextRange::offset_len(item.syntax().text_range().start(), 0.into()), "&"));
+    } else {
+        let name = item.name()?.text().clone();
+        let refs = analyzer
+            .find_all_refs(&item)
+            .into_iter()
+            .map(|it| it.range)
+            .filter(|it| it.is_subrange(&body_range))
+            .collect::<Vec<_>>();
+        let uses = body
+            .syntax()
+            .descendants()
+            .filter_map(ast::PathExpr::cast)
+            .filter(|it| refs.contains(&it.syntax().text_range()))
+            .collect::<Vec<_>>();
+        if uses.len() != refs.len() {
+            return None;
+        }
+        for path_expr in uses {
+            if let Some(edit) = adjust_use(&ctx, &analyzer, &path_expr)? {
+                edits.push(edit);
+            }
+        }
+        let uses_in_macros =
+            body.syntax().descendants().filter_map(ast::MacroCall::cast).filter(|it| {
+                it.token_tree().map_or(false, |tt| {
+                    tt.syntax()
+                        .descendants_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .any(|it| it.kind() == IDENT && *it.text() == name)
+                })
+            });
+        for macro_call in uses_in_macros {
+            if !is_formatting_macro(&macro_call) {
+                return None;
+            }
+        }
+    }
+
+    ctx.add_assist(AssistId("iterate_by_reference"), "Iterate by reference", |edit| {
+        edit.target(for_expr.syntax().text_range());
+        edit.insert(iterable.syntax().text_range().start(), "&");
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+        edit.set_cursor(for_keyword.text_range().start());
+    })
+}
+
+/// Returns the edit adapting a use of the item to a reference, `Some(None)` if
+/// it works as it is, or `None` if the use needs ownership of the item.
+fn adjust_use(
+    ctx: &AssistCtx<impl HirDatabase>,
+    analyzer: &SourceAnalyzer,
+    path_expr: &ast::PathExpr,
+) -> Option<Option<(TextRange, &'static str)>> {
+    let parent = path_expr.syntax().parent()?;
+    let range = path_expr.syntax().text_range();
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        if ref_expr.is_mut() {
+            return None;
+        }
+        let ref_range = TextRange::from_to(ref_expr.syntax().text_range().start(), range.start());
+        return Some(Some((ref_range, "")));
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        let function = analyzer.resolve_method_call(&call)?;
+        let self_param = function.source(ctx.db).value.param_list()?.self_param()?;
+        return match self_param.kind() {
+            ast::SelfParamKind::Ref => Some(None),
+            _ => None,
+        };
+    }
+    if let Some(field_expr) = ast::FieldExpr::cast(parent.clone()) {
+        // The field can be copied or borrowed out of the reference, but not
+        // moved.
+        let is_borrowed = field_expr
+            .syntax()
+            .parent()
+            .and_then(ast::RefExpr::cast)
+            .map_or(false, |it| !it.is_mut());
+        let is_copy = analyzer
+            .type_of(ctx.db, &ast::Expr::from(field_expr))
+            .map_or(false, |it| is_copy_type(ctx.db, &it));
+        return if is_borrowed || is_copy { Some(None) } else { None };
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        return match bin_expr.op_kind()? {
+            ast::BinOp::EqualityTest
+            | ast::BinOp::NegatedEqualityTest
+            | ast::BinOp::LesserEqualTest
+            | ast::BinOp::GreaterEqualTest
+            | ast::BinOp::LesserTest
+            | ast::BinOp::GreaterTest => {
+                Some(Some((TextRange::offset_len(range.start(), TextUnit::from(0)), "*")))
+            }
+            _ => None,
+        };
+    }
+    None
+}
+
+fn is_formatting_macro(macro_call: &ast::MacroCall) -> bool {
+    let name = match macro_call.path() {
+        Some(it) => it.syntax().to_string(),
+        None => return false,
+    };
+    match name.as_str() {
+        "print" | "println" | "eprint" | "eprintln" | "format" | "write" | "writeln" | "panic"
+        | "assert" | "assert_eq" | "assert_ne" | "debug_assert" | "debug_assert_eq"
+        | "debug_assert_ne" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn iterate_by_reference_adjusting_uses() {
+        check_assist(
+            iterate_by_reference,
+            r#"
+struct Vec<T> { t: T }
+struct Item { id: u32 }
+fn f(items: Vec<Item>, target: Item) {
+    <|>for item in items {
+        if item != target {
+            println!("{}", item);
+            log(&item, &item.id);
+        }
+    }
+    drop(items);
+}
+"#,
+            r#"
+struct Vec<T> { t: T }
+struct Item { id: u32 }
+fn f(items: Vec<Item>, target: Item) {
+    <|>for item in &items {
+        if *item != target {
+            println!("{}", item);
+            log(item, &item.id);
+        }
+    }
+    drop(items);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn iterate_by_reference_target() {
+        check_assist_target(
+            iterate_by_reference,
+            "struct V;\nfn f(v: V) { <|>for x in v {} v; }",
+            "for x in v {}",
+        );
+    }
+
+    #[test]
+    fn iterate_by_reference_not_applicable() {
+        // Not used after the loop.
+        check_assist_not_applicable(
+            iterate_by_reference,
+            "struct V;\nfn f(v: V) { <|>for x in v {} }",
+        );
+        // Already a reference.
+        check_assist_not_applicable(
+            iterate_by_reference,
+            "struct V;\nfn f(v: &V) { <|>for x in v {} v; }",
+        );
+        // The item is moved.
+        check_assist_not_applicable(
+            iterate_by_reference,
+            "struct V;\nfn f(v: V) { <|>for x in v { consume(x); } v; }",
+        );
+        // The item is moved in a macro.
+        check_assist_not_applicable(
+            iterate_by_reference,
+            "struct V;\nfn f(v: V) { <|>for x in v { vec![x]; } v; }",
+        );
+    }
+}
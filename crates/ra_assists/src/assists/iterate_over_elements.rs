@@ -0,0 +1,285 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, LoopBodyOwner},
+    SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: iterate_over_elements
+//
+// Replaces a loop over the indices of a collection with a loop over its
+// elements, when the index is only used to access the collection.
+//
+// ```
+// fn sum(values: &[u32]) -> u32 {
+//     let mut total = 0;
+//     for <|>i in 0..values.len() {
+//         total += values[i];
+//     }
+//     total
+// }
+// ```
+// ->
+// ```
+// fn sum(values: &[u32]) -> u32 {
+//     let mut total = 0;
+//     for value in values {
+//         total += *value;
+//     }
+//     total
+// }
+// ```
+pub(crate) fn iterate_over_elements(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let for_expr = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let body = for_expr.loop_body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let index = match for_expr.pat()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    let range = match for_expr.iterable()? {
+        ast::Expr::RangeExpr(it) => it,
+        _ => return None,
+    };
+    if range.op_kind()? != ast::RangeOp::Exclusive || range.start()?.syntax().text() != "0" {
+        return None;
+    }
+    let collection = match range.end()? {
+        ast::Expr::MethodCallExpr(it) => {
+            let has_args = it.arg_list().map_or(true, |it| it.args().next().is_some());
+            if it.name_ref()?.text().as_str() != "len" || has_args {
+                return None;
+            }
+            it.expr()?
+        }
+        _ => return None,
+    };
+    let collection_text = collection.syntax().text().to_string();
+
+    // Every use of the index has to be an access to the collection.
+    for_expr.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let analyzer = ctx.source_analyzer(index.syntax(), None);
+    let mut accesses = Vec::new();
+    for reference in analyzer.find_all_refs(&index) {
+        let usage = ctx.covering_node_for_range(reference.range);
+        let path_expr = usage.ancestors().find_map(ast::PathExpr::cast)?;
+        let access = ast::IndexExpr::cast(path_expr.syntax().parent()?)?;
+        if access.index()?.syntax() != path_expr.syntax()
+            || access.base()?.syntax().text() != collection_text.as_str()
+        {
+            return None;
+        }
+        accesses.push(access);
+    }
+    if accesses.is_empty() {
+        return None;
+    }
+    // Other uses of the collection would conflict with the borrow.
+    let collection_uses = body
+        .syntax()
+        .descendants()
+        .filter(|it| {
+            it.kind() == collection.syntax().kind() && it.text() == collection_text.as_str()
+        })
+        .count();
+    if collection_uses != accesses.len() {
+        return None;
+    }
+
+    let name = element_name(&collection);
+    let is_taken = body
+        .syntax()
+        .descendants()
+        .filter_map(|it| match ast::NameRef::cast(it.clone()) {
+            Some(it) => Some(it.text().clone()),
+            None => ast::Name::cast(it).map(|it| it.text().clone()),
+        })
+        .any(|it| it.as_str() == name.as_str());
+    if is_taken {
+        return None;
+    }
+    let edits = accesses.iter().map(|it| rewrite_access(it, &name)).collect::<Vec<_>>();
+    let is_mut = edits.iter().any(|(_, _, is_mut)| *is_mut);
+    let collection_ty = ctx
+        .source_analyzer(collection.syntax(), None)
+        .type_of(ctx.db, &collection)
+        .filter(|it| !it.is_unknown())
+        .map(|it| it.display(ctx.db).to_string());
+    let iterable = match collection_ty {
+        _ if is_mut => format!("{}.iter_mut()", collection_text),
+        // A shared reference can be iterated over as is.
+        Some(ty) if ty.starts_with('&') && !ty.starts_with("&mut ") => collection_text,
+        Some(ty) if !ty.starts_with('&') => format!("&{}", collection_text),
+        _ => format!("{}.iter()", collection_text),
+    };
+
+    ctx.add_assist(AssistId("iterate_over_elements"), "Iterate over elements", |edit| {
+        edit.target(for_expr.syntax().text_range());
+        edit.replace(index.syntax().text_range(), name.clone());
+        edit.set_cursor(index.syntax().text_range().start());
+        edit.replace(range.syntax().text_range(), iterable);
+        for (range, text, _) in edits {
+            edit.replace(range, text);
+        }
+    })
+}
+
+/// Names the element after the collection, like `value` for `values`.
+fn element_name(collection: &ast::Expr) -> String {
+    let name = match collection {
+        ast::Expr::PathExpr(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map(|it| it.text().clone()),
+        ast::Expr::FieldExpr(it) => it.name_ref().map(|it| it.text().clone()),
+        _ => None,
+    };
+    match name {
+        Some(name) if name.len() > 1 && name.ends_with('s') && !name.ends_with("ss") => {
+            name[..name.len() - 1].to_string()
+        }
+        _ => "it".to_string(),
+    }
+}
+
+/// Returns the edit for an access, and whether it needs mutable access.
+fn rewrite_access(access: &ast::IndexExpr, name: &str) -> (TextRange, String, bool) {
+    let parent = access.syntax().parent();
+    if let Some(ref_expr) = parent.clone().and_then(ast::RefExpr::cast) {
+        return (ref_expr.syntax().text_range(), name.to_string(), ref_expr.is_mut());
+    }
+    let range = access.syntax().text_range();
+    if parent.as_ref().map_or(false, |it| is_receiver(it, access.syntax())) {
+        let is_mut =
+            parent.and_then(|it| it.parent()).map_or(false, |it| is_assigned(&it, access.syntax()));
+        return (range, name.to_string(), is_mut);
+    }
+    let is_mut = parent.map_or(false, |it| is_assigned(&it, access.syntax()));
+    (range, format!("*{}", name), is_mut)
+}
+
+/// Whether `node` is a field or method receiver in `parent`.
+fn is_receiver(parent: &SyntaxNode, node: &SyntaxNode) -> bool {
+    let receiver = if let Some(it) = ast::FieldExpr::cast(parent.clone()) {
+        it.expr()
+    } else if let Some(it) = ast::MethodCallExpr::cast(parent.clone()) {
+        it.expr()
+    } else {
+        None
+    };
+    receiver.map_or(false, |it| it.syntax() == node)
+}
+
+/// Whether `node` is assigned to in `parent`.
+fn is_assigned(parent: &SyntaxNode, node: &SyntaxNode) -> bool {
+    match ast::BinExpr::cast(parent.clone()) {
+        Some(it) => {
+            it.op_kind().map_or(false, |it| it.is_assignment())
+                && it.lhs().map_or(false, |it| it.syntax() == node)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn iterate_over_borrowed_elements() {
+        check_assist(
+            iterate_over_elements,
+            r#"
+fn f(items: &[Item]) {
+    for <|>i in 0..items.len() {
+        let total = items[i].count + 1;
+        use_item(&items[i]);
+        print(items[i].name());
+    }
+}
+"#,
+            r#"
+fn f(items: &[Item]) {
+    for <|>item in items {
+        let total = item.count + 1;
+        use_item(item);
+        print(item.name());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn iterate_over_elements_of_owned_collection() {
+        check_assist(
+            iterate_over_elements,
+            "struct Items;\nfn f(items: Items) { for <|>i in 0..items.len() { g(items[i]); } }",
+            "struct Items;\nfn f(items: Items) { for <|>item in &items { g(*item); } }",
+        );
+        check_assist(
+            iterate_over_elements,
+            "fn f(items: &mut Items) { for <|>i in 0..items.len() { g(items[i]); } }",
+            "fn f(items: &mut Items) { for <|>item in items.iter() { g(*item); } }",
+        );
+    }
+
+    #[test]
+    fn iterate_over_mutable_elements() {
+        check_assist(
+            iterate_over_elements,
+            r#"
+impl S {
+    fn reset(&mut self) {
+        for i<|> in 0..self.buf.len() {
+            self.buf[i] = 0;
+        }
+    }
+}
+"#,
+            r#"
+impl S {
+    fn reset(&mut self) {
+        for <|>it in self.buf.iter_mut() {
+            *it = 0;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn iterate_over_elements_target() {
+        check_assist_target(
+            iterate_over_elements,
+            "fn f(v: &[u8]) { <|>for i in 0..v.len() { v[i]; } }",
+            "for i in 0..v.len() { v[i]; }",
+        );
+    }
+
+    #[test]
+    fn iterate_over_elements_not_applicable() {
+        check_assist_not_applicable(
+            iterate_over_elements,
+            "fn f(v: &[u8]) { for <|>i in 0..v.len() { g(i, v[i]); } }",
+        );
+        check_assist_not_applicable(
+            iterate_over_elements,
+            "fn f(v: &[u8], w: &[u8]) { for <|>i in 0..v.len() { g(v[i], w[i]); } }",
+        );
+        check_assist_not_applicable(
+            iterate_over_elements,
+            "fn f(v: &mut Vec<u8>) { for <|>i in 0..v.len() { v.push(v[i]); } }",
+        );
+        check_assist_not_applicable(
+            iterate_over_elements,
+            "fn f(v: &[u8]) { for <|>i in 1..v.len() { v[i]; } }",
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt, HirDisplay, InFile, ModuleDef, PathResolution, Type, VariantDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner, VisibilityOwner},
+    SmolStr, TextRange,
+};
+
+use super::add_import::auto_import_text_edit;
+use crate::{
+    assist_ctx::ActionBuilder,
+    utils::{derive_edit, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: make_cloneable
+//
+// Derives `Clone` for a struct with fields which are not `Clone`, by sharing
+// those fields in an `Rc` or `Arc`, or by implementing `Clone` with a
+// `unimplemented!()` for them.
+//
+// ```
+// struct Handle;
+//
+// struct <|>Window {
+//     title: u32,
+//     handle: Handle,
+// }
+//
+// fn open() -> Window {
+//     Window { title: 0, handle: Handle }
+// }
+// ```
+// ->
+// ```
+// use std::rc::Rc;
+//
+// struct Handle;
+//
+// #[derive(Clone)]
+// struct Window {
+//     title: u32,
+//     handle: Rc<Handle>,
+// }
+//
+// fn open() -> Window {
+//     Window { title: 0, handle: Rc::new(Handle) }
+// }
+// ```
+pub(crate) fn make_cloneable(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    // The impl of `Clone` would need bounds for the type parameters.
+    if strukt.type_param_list().is_some() {
+        return None;
+    }
+    let db = ctx.db;
+    let def = ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), strukt.clone()))?;
+    if implements_trait(db, &def.ty(db), "Clone") {
+        return None;
+    }
+
+    let fields = match strukt.kind() {
+        ast::StructKind::Record(it) => it
+            .fields()
+            .map(|it| Some((it.name()?.text().clone(), it.ascribed_type()?)))
+            .collect::<Option<Vec<_>>>()?,
+        ast::StructKind::Tuple(it) => it
+            .fields()
+            .enumerate()
+            .map(|(idx, it)| Some((SmolStr::from(idx.to_string()), it.type_ref()?)))
+            .collect::<Option<Vec<_>>>()?,
+        ast::StructKind::Unit => return None,
+    };
+    let field_tys = def.fields(db).into_iter().map(|it| it.ty(db)).collect::<Vec<_>>();
+    if fields.len() != field_tys.len() {
+        return None;
+    }
+    let not_clone = fields
+        .iter()
+        .zip(&field_tys)
+        .enumerate()
+        .filter(|(_, (_, ty))| is_not_clone(db, ty))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    if not_clone.is_empty() {
+        return None;
+    }
+    let names = not_clone.iter().map(|&idx| format!("`{}`", fields[idx].0)).collect::<Vec<_>>();
+    let names = names.join(", ");
+
+    // Constructors elsewhere can't be updated to share the fields.
+    let constructors = if strukt.visibility().is_none() {
+        Some(find_constructors(&ctx, &strukt, def, &fields, &not_clone)?)
+    } else {
+        None
+    };
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let (derive_offset, derive_text) = derive_edit(&nominal, "Clone")?;
+    let indent = leading_indent(strukt.syntax()).unwrap_or_default();
+
+    ctx.add_assist_group(AssistId("make_cloneable"), "Make cloneable", || {
+        let mut actions = Vec::new();
+        if let Some(constructors) = &constructors {
+            let shared = [("Rc", ["std", "rc", "Rc"]), ("Arc", ["std", "sync", "Arc"])];
+            for (wrapper, path) in shared.iter() {
+                let mut edit = ActionBuilder::default();
+                edit.label(format!("Wrap {} in {}", names, wrapper));
+                edit.target(strukt.syntax().text_range());
+                edit.insert(derive_offset, derive_text.clone());
+                for &idx in &not_clone {
+                    let ty = &fields[idx].1;
+                    let text = format!("{}<{}>", wrapper, ty.syntax());
+                    edit.replace(ty.syntax().text_range(), text);
+                }
+                for (range, text) in constructors {
+                    edit.replace(*range, text.replace("{wrapper}", wrapper));
+                }
+                let path = path.iter().map(|it| SmolStr::new(it)).collect::<Vec<_>>();
+                auto_import_text_edit(
+                    strukt.syntax(),
+                    strukt.syntax(),
+                    &path,
+                    edit.text_edit_builder(),
+                );
+                actions.push(edit);
+            }
+        }
+
+        let mut edit = ActionBuilder::default();
+        edit.label(format!("Implement Clone with unimplemented!() for {}", names));
+        edit.target(strukt.syntax().text_range());
+        let clone_field = |idx: usize, field: &SmolStr| {
+            if not_clone.contains(&idx) {
+                "unimplemented!()".to_string()
+            } else {
+                format!("self.{}.clone()", field)
+            }
+        };
+        let mut buf = String::new();
+        format!(buf, "\n\n{}impl Clone for {} {{\n", indent, name.text());
+        format!(buf, "{}    fn clone(&self) -> Self {{\n", indent);
+        match strukt.kind() {
+            ast::StructKind::Record(_) => {
+                format!(buf, "{}        {} {{\n", indent, name.text());
+                for (idx, (field, _)) in fields.iter().enumerate() {
+                    format!(buf, "{}            {}: {},\n", indent, field, clone_field(idx, field));
+                }
+                format!(buf, "{}        }}\n", indent);
+            }
+            _ => {
+                let args = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, (field, _))| clone_field(idx, field))
+                    .collect::<Vec<_>>();
+                format!(buf, "{}        {}({})\n", indent, name.text(), args.join(", "));
+            }
+        }
+        format!(buf, "{}    }}\n{}}}", indent, indent);
+        edit.insert(strukt.syntax().text_range().end(), buf);
+        actions.push(edit);
+        actions
+    })
+}
+
+/// Whether the type is known not to implement `Clone`. Only the type itself is
+/// checked, not its type arguments.
+fn is_not_clone(db: &impl HirDatabase, ty: &Type) -> bool {
+    if ty.display(db).to_string().starts_with("&mut ") {
+        return true;
+    }
+    ty.as_adt().is_some() && !implements_trait(db, ty, "Clone")
+}
+
+/// Returns the edits sharing the given fields in the constructors of the
+/// struct, with `{wrapper}` in place of the smart pointer.
+fn find_constructors(
+    ctx: &AssistCtx<impl HirDatabase>,
+    strukt: &ast::StructDef,
+    def: hir::Struct,
+    fields: &[(SmolStr, ast::TypeRef)],
+    not_clone: &[usize],
+) -> Option<Vec<(TextRange, String)>> {
+    let db = ctx.db;
+    let root = strukt.syntax().ancestors().last()?;
+    let mut res = Vec::new();
+    for node in root.descendants() {
+        if let Some(record_lit) = ast::RecordLit::cast(node.clone()) {
+            let analyzer = ctx.source_analyzer(&node, None);
+            if analyzer.resolve_record_literal(&record_lit) != Some(VariantDef::Struct(def)) {
+                continue;
+            }
+            for field in record_lit.record_field_list()?.fields() {
+                let name = field.name_ref()?.text().clone();
+                if !not_clone.iter().any(|&idx| fields[idx].0 == name) {
+                    continue;
+                }
+                match field.expr() {
+                    Some(expr) => res.push((
+                        expr.syntax().text_range(),
+                        format!("{{wrapper}}::new({})", expr.syntax()),
+                    )),
+                    None => res.push((
+                        field.syntax().text_range(),
+                        format!("{}: {{wrapper}}::new({})", name, name),
+                    )),
+                }
+            }
+        } else if let Some(call) = ast::CallExpr::cast(node.clone()) {
+            let path = match call.expr() {
+                Some(ast::Expr::PathExpr(it)) => it.path()?,
+                _ => continue,
+            };
+            let analyzer = ctx.source_analyzer(&node, None);
+            match analyzer.resolve_path(db, &path) {
+                Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(it)))) if it == def => (),
+                _ => continue,
+            }
+            for (idx, arg) in call.arg_list()?.args().enumerate() {
+                if not_clone.contains(&idx) {
+                    let text = format!("{{wrapper}}::new({})", arg.syntax());
+                    res.push((arg.syntax().text_range(), text));
+                }
+            }
+        }
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist_group, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_cloneable_tuple_struct() {
+        check_assist_group(
+            make_cloneable,
+            r#"
+struct File;
+#[derive(Debug)]
+struct <|>Log(u32, File);
+fn f(file: File) { let log = Log(1, file); }
+"#,
+            &[
+                r#"
+use std::rc::Rc;
+
+struct File;
+#[derive(Debug, Clone)]
+struct Log(u32, Rc<File>);
+fn f(file: File) { let log = Log(1, Rc::new(file)); }
+"#,
+                r#"
+use std::sync::Arc;
+
+struct File;
+#[derive(Debug, Clone)]
+struct Log(u32, Arc<File>);
+fn f(file: File) { let log = Log(1, Arc::new(file)); }
+"#,
+                r#"
+struct File;
+#[derive(Debug)]
+struct Log(u32, File);
+
+impl Clone for Log {
+    fn clone(&self) -> Self {
+        Log(self.0.clone(), unimplemented!())
+    }
+}
+fn f(file: File) { let log = Log(1, file); }
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn make_cloneable_public_struct() {
+        check_assist_group(
+            make_cloneable,
+            r#"
+mod m {
+    struct Conn;
+    #[derive(Clone)]
+    struct Name;
+    pub struct <|>User { name: Name, conn: Conn, log: &'static mut u8 }
+}
+"#,
+            &[r#"
+mod m {
+    struct Conn;
+    #[derive(Clone)]
+    struct Name;
+    pub struct User { name: Name, conn: Conn, log: &'static mut u8 }
+
+    impl Clone for User {
+        fn clone(&self) -> Self {
+            User {
+                name: self.name.clone(),
+                conn: unimplemented!(),
+                log: unimplemented!(),
+            }
+        }
+    }
+}
+"#],
+        );
+    }
+
+    #[test]
+    fn make_cloneable_target() {
+        check_assist_target(make_cloneable, "struct A;\nstruct <|>B { a: A }", "struct B { a: A }");
+    }
+
+    #[test]
+    fn make_cloneable_not_applicable() {
+        // Every field is `Clone`.
+        check_assist_not_applicable(
+            make_cloneable,
+            "#[derive(Clone)]\nstruct A;\nstruct <|>B { a: A, n: u32 }",
+        );
+        // Already `Clone`.
+        check_assist_not_applicable(
+            make_cloneable,
+            "struct A;\nstruct <|>B { a: A }\nimpl Clone for B { fn clone(&self) -> B { unimplemented!() } }",
+        );
+    }
+}
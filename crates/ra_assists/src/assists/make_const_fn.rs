@@ -0,0 +1,201 @@
+use hir::{
+    db::HirDatabase, AssocItem, Function, HasSource, HirDisplay, ModuleDef, PathResolution,
+    SourceAnalyzer,
+};
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::{ABI, AWAIT_EXPR, FOR_EXPR, LAMBDA_EXPR, TRY_EXPR},
+    SyntaxNode, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: make_const_fn
+//
+// Makes a function `const`, when its body doesn't do anything that is known
+// not to be allowed in constants.
+//
+// ```
+// fn <|>area(width: u32, height: u32) -> u32 {
+//     width * height
+// }
+// ```
+// ->
+// ```
+// const fn area(width: u32, height: u32) -> u32 {
+//     width * height
+// }
+// ```
+pub(crate) fn make_const_fn(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    if fn_def.is_async() || fn_def.syntax().children_with_tokens().any(|it| it.kind() == T![const])
+    {
+        return None;
+    }
+    // Trait methods can't be `const`.
+    let owner = fn_def.syntax().parent().and_then(|it| it.parent());
+    let is_in_trait = owner.map_or(false, |it| {
+        ast::TraitDef::can_cast(it.kind())
+            || ast::ImplBlock::cast(it).map_or(false, |it| it.target_trait().is_some())
+    });
+    if is_in_trait {
+        return None;
+    }
+
+    // Anything which can't be resolved is left for the compiler to check.
+    let analyzer = ctx.source_analyzer(body.syntax(), None);
+    for node in body.syntax().descendants() {
+        match node.kind() {
+            FOR_EXPR | LAMBDA_EXPR | AWAIT_EXPR | TRY_EXPR => return None,
+            _ => (),
+        }
+        if !is_allowed_in_const(&ctx, &analyzer, node) {
+            return None;
+        }
+    }
+
+    let start = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T![unsafe] || it.kind() == ABI || it.kind() == T![fn])?
+        .text_range()
+        .start();
+
+    ctx.add_assist(AssistId("make_const_fn"), "Make const fn", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        edit.insert(start, "const ");
+    })
+}
+
+/// Whether the node is allowed in a `const fn`, as far as we can tell.
+fn is_allowed_in_const(
+    ctx: &AssistCtx<impl HirDatabase>,
+    analyzer: &SourceAnalyzer,
+    node: SyntaxNode,
+) -> bool {
+    if let Some(macro_call) = ast::MacroCall::cast(node.clone()) {
+        return is_const_macro(&macro_call);
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+        let method = analyzer.resolve_method_call(&call);
+        return method.map_or(true, |it| is_const_fn(ctx, it));
+    }
+    if let Some(call) = ast::CallExpr::cast(node.clone()) {
+        let path = match call.expr() {
+            Some(ast::Expr::PathExpr(it)) => it.path(),
+            _ => None,
+        };
+        return match path.and_then(|it| analyzer.resolve_path(ctx.db, &it)) {
+            Some(PathResolution::Def(ModuleDef::Function(it)))
+            | Some(PathResolution::AssocItem(AssocItem::Function(it))) => is_const_fn(ctx, it),
+            _ => true,
+        };
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(node.clone()) {
+        if bin_expr.op_kind() == Some(ast::BinOp::Assignment) {
+            return true;
+        }
+        return !is_overloaded(ctx, analyzer, bin_expr.lhs());
+    }
+    if let Some(prefix_expr) = ast::PrefixExpr::cast(node) {
+        return prefix_expr.op_kind() == Some(ast::PrefixOp::Deref)
+            || !is_overloaded(ctx, analyzer, prefix_expr.expr());
+    }
+    true
+}
+
+fn is_const_fn(ctx: &AssistCtx<impl HirDatabase>, function: Function) -> bool {
+    let fn_def = function.source(ctx.db).value;
+    fn_def.syntax().children_with_tokens().any(|it| it.kind() == T![const])
+}
+
+/// Whether the operand has a type with an operator implemented by a trait,
+/// which can't be called in constants.
+fn is_overloaded(
+    ctx: &AssistCtx<impl HirDatabase>,
+    analyzer: &SourceAnalyzer,
+    operand: Option<ast::Expr>,
+) -> bool {
+    let ty = operand.and_then(|it| analyzer.type_of(ctx.db, &it));
+    ty.map_or(false, |it| {
+        it.as_adt().is_some() || it.display(ctx.db).to_string().starts_with("dyn ")
+    })
+}
+
+fn is_const_macro(macro_call: &ast::MacroCall) -> bool {
+    let name = match macro_call.path() {
+        Some(it) => it.syntax().to_string(),
+        None => return false,
+    };
+    match name.as_str() {
+        "panic" | "assert" | "assert_eq" | "assert_ne" | "debug_assert" | "unreachable"
+        | "todo" | "unimplemented" | "matches" | "concat" | "stringify" | "env" | "include_str"
+        | "include_bytes" | "line" | "column" | "file" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_const_fn_with_calls() {
+        check_assist(
+            make_const_fn,
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+struct S;
+impl S {
+    const fn get(&self) -> u32 { 1 }
+    pub unsafe fn <|>f(&self, n: u32) -> u32 {
+        let mut i = 0;
+        while i < n { i += 1; }
+        unknown(double(i) + self.get())
+    }
+}
+"#,
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+struct S;
+impl S {
+    const fn get(&self) -> u32 { 1 }
+    pub const unsafe fn <|>f(&self, n: u32) -> u32 {
+        let mut i = 0;
+        while i < n { i += 1; }
+        unknown(double(i) + self.get())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_const_fn_target() {
+        check_assist_target(make_const_fn, "fn <|>f() -> u8 { 1 }", "fn f() -> u8 { 1 }");
+    }
+
+    #[test]
+    fn make_const_fn_not_applicable() {
+        // Already `const`.
+        check_assist_not_applicable(make_const_fn, "const fn <|>f() {}");
+        // Calling a function which isn't `const`.
+        check_assist_not_applicable(make_const_fn, "fn g() {}\nfn <|>f() { g() }");
+        // Iterating.
+        check_assist_not_applicable(make_const_fn, "fn <|>f(n: u32) { for i in 0..n {} }");
+        // Formatting.
+        check_assist_not_applicable(make_const_fn, "fn <|>f() { println!(\"hi\") }");
+        // An operator of a trait.
+        check_assist_not_applicable(
+            make_const_fn,
+            "struct V;\nimpl Add for V {}\nfn <|>f(a: V, b: V) { a + b; }",
+        );
+        // A trait method.
+        check_assist_not_applicable(make_const_fn, "trait T { fn <|>f() {} }");
+    }
+}
@@ -0,0 +1,110 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{
+    utils::{adt_fields, derive_edit, implements_trait, is_copy_type},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: make_copy
+//
+// Derives `Copy` for a struct or an enum whose fields are all `Copy`, along
+// with `Clone` if it is missing.
+//
+// ```
+// #[derive(Debug)]
+// struct <|>Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, Clone, Copy)]
+// struct Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+pub(crate) fn make_copy(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let db = ctx.db;
+    let (adt, fields) = adt_fields(&ctx, &nominal)?;
+    let field_tys = fields.into_iter().map(|it| it.ty(db)).collect::<Vec<_>>();
+    let ty = adt.ty(db);
+    if implements_trait(db, &ty, "Copy") {
+        return None;
+    }
+    // A field which isn't known to be `Copy` would make the derive fail.
+    if !field_tys.iter().all(|it| is_copy_type(db, it)) {
+        return None;
+    }
+    let traits = if implements_trait(db, &ty, "Clone") { "Copy" } else { "Clone, Copy" };
+    let (offset, text) = derive_edit(&nominal, traits)?;
+
+    ctx.add_assist(AssistId("make_copy"), "Make Copy", |edit| {
+        edit.target(nominal.syntax().text_range());
+        edit.insert(offset, text);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_struct_copy() {
+        check_assist(
+            make_copy,
+            r#"
+#[derive(Clone, Copy)]
+struct Id(u32);
+
+/// Docs.
+struct <|>Entry {
+    id: Id,
+    pair: (u8, &'static str),
+    bytes: [u8; 4],
+}
+"#,
+            r#"
+#[derive(Clone, Copy)]
+struct Id(u32);
+
+/// Docs.
+#[derive(Clone, Copy)]
+struct <|>Entry {
+    id: Id,
+    pair: (u8, &'static str),
+    bytes: [u8; 4],
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_enum_copy_with_clone() {
+        check_assist(
+            make_copy,
+            "#[derive(Debug, Clone)]\nenum <|>E { A, B(char) }",
+            "#[derive(Debug, Clone, Copy)]\nenum <|>E { A, B(char) }",
+        );
+    }
+
+    #[test]
+    fn make_copy_target() {
+        check_assist_target(make_copy, "struct <|>Unit;", "struct Unit;");
+    }
+
+    #[test]
+    fn make_copy_not_applicable() {
+        // Already `Copy`.
+        check_assist_not_applicable(make_copy, "#[derive(Clone, Copy)]\nstruct <|>S;");
+        // A field which is not `Copy`.
+        check_assist_not_applicable(make_copy, "struct Name;\nstruct <|>S { name: Name }");
+        check_assist_not_applicable(make_copy, "struct <|>S<'a> { r: &'a mut u8 }");
+        // A generic field.
+        check_assist_not_applicable(make_copy, "struct <|>S<T> { t: T }");
+    }
+}
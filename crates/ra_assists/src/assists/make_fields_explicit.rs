@@ -0,0 +1,187 @@
+use hir::{db::HirDatabase, HasSource, VariantDef};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner},
+    SyntaxKind::COMMENT,
+    TextUnit,
+};
+
+use crate::{utils::derives_default, Assist, AssistCtx, AssistId};
+
+// Assist: make_fields_explicit
+//
+// Replaces `..Default::default()` in a struct literal with the default values
+// of the missing fields.
+//
+// ```
+// #[derive(Default)]
+// struct Options { port: u16, verbose: bool, retries: Retries }
+//
+// fn options() -> Options {
+//     Options { port: 8080, ..Default::default()<|> }
+// }
+// ```
+// ->
+// ```
+// #[derive(Default)]
+// struct Options { port: u16, verbose: bool, retries: Retries }
+//
+// fn options() -> Options {
+//     Options { port: 8080, verbose: false, retries: Default::default() }
+// }
+// ```
+pub(crate) fn make_fields_explicit(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let record_lit = ctx.find_node_at_offset::<ast::RecordLit>()?;
+    let field_list = record_lit.record_field_list()?;
+    let spread = field_list.spread()?;
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(record_lit.syntax(), None);
+    let strukt = match analyzer.resolve_record_literal(&record_lit)? {
+        VariantDef::Struct(it) => it,
+        _ => return None,
+    };
+    let struct_def = strukt.source(db).value;
+    // The default of a struct without a derived impl isn't the one of its
+    // fields.
+    let spread_text = spread.syntax().text().to_string().split_whitespace().collect::<String>();
+    let is_default = spread_text == "Default::default()"
+        || spread_text == "Self::default()"
+        || struct_def.name().map_or(false, |it| spread_text == format!("{}::default()", it.text()));
+    if !is_default || !derives_default(&struct_def) {
+        return None;
+    }
+    if field_list.syntax().descendants_with_tokens().any(|it| it.kind() == COMMENT) {
+        return None;
+    }
+
+    let present = field_list
+        .fields()
+        .map(|it| Some(it.name_ref()?.text().to_string()))
+        .collect::<Option<Vec<_>>>()?;
+    let defaulted = strukt
+        .fields(db)
+        .into_iter()
+        .map(|it| (it.name(db).to_string(), default_value(&it.ty(db).display(db).to_string())))
+        .filter(|(name, _)| !present.contains(name))
+        .collect::<Vec<_>>();
+    if defaulted.is_empty() {
+        return None;
+    }
+    let fields = field_list
+        .fields()
+        .map(|it| it.syntax().to_string())
+        .chain(defaulted.iter().map(|(name, value)| format!("{}: {}", name, value)))
+        .collect::<Vec<_>>();
+
+    ctx.add_assist(AssistId("make_fields_explicit"), "Make fields explicit", |edit| {
+        let range = field_list.syntax().text_range();
+        let (text, first_defaulted) = if field_list.syntax().text().contains_char('\n') {
+            let indent = " ".repeat(IndentLevel::from_node(record_lit.syntax()).0 as usize * 4);
+            let text = format!(
+                "{{\n{}    {},\n{}}}",
+                indent,
+                fields.join(&format!(",\n{}    ", indent)),
+                indent
+            );
+            let present_len: usize =
+                fields[..present.len()].iter().map(|it| it.len() + indent.len() + 6).sum();
+            (text, 2 + indent.len() + 4 + present_len)
+        } else {
+            let present_len: usize = fields[..present.len()].iter().map(|it| it.len() + 2).sum();
+            (format!("{{ {} }}", fields.join(", ")), 2 + present_len)
+        };
+        edit.target(spread.syntax().text_range());
+        edit.replace(range, text);
+        // The cursor goes to the first of the fields which were defaulted.
+        edit.set_cursor(range.start() + TextUnit::from_usize(first_defaulted));
+    })
+}
+
+/// The literal for the default value of the type, or `Default::default()`.
+fn default_value(ty: &str) -> String {
+    let value = match ty {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" => "0",
+        "f32" | "f64" => "0.0",
+        "bool" => "false",
+        "char" => "'\\0'",
+        "()" => "()",
+        "String" => "String::new()",
+        _ if ty.starts_with("Vec<") => "Vec::new()",
+        _ if ty.starts_with("Option<") => "None",
+        _ => "Default::default()",
+    };
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_fields_explicit_multiline() {
+        check_assist(
+            make_fields_explicit,
+            r#"
+struct String;
+struct Vec<T>(T);
+enum Option<T> { None, Some(T) }
+#[derive(Debug, Default)]
+struct Config { name: String, ratio: f64, tags: Vec<String>, parent: Option<u8>, c: char }
+fn f() {
+    let config = Config {
+        name: n,
+        ..Config::default()<|>
+    };
+}
+"#,
+            r#"
+struct String;
+struct Vec<T>(T);
+enum Option<T> { None, Some(T) }
+#[derive(Debug, Default)]
+struct Config { name: String, ratio: f64, tags: Vec<String>, parent: Option<u8>, c: char }
+fn f() {
+    let config = Config {
+        name: n,
+        <|>ratio: 0.0,
+        tags: Vec::new(),
+        parent: None,
+        c: '\0',
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_fields_explicit_without_fields() {
+        check_assist(
+            make_fields_explicit,
+            "#[derive(Default)]\nstruct S { a: u8, b: bool }\nfn f() { S { ..S::default()<|> }; }",
+            "#[derive(Default)]\nstruct S { a: u8, b: bool }\nfn f() { S { <|>a: 0, b: false }; }",
+        );
+    }
+
+    #[test]
+    fn make_fields_explicit_target() {
+        check_assist_target(
+            make_fields_explicit,
+            "#[derive(Default)]\nstruct S { a: u8 }\nfn f() { S { <|>..Default::default() }; }",
+            "Default::default()",
+        );
+    }
+
+    #[test]
+    fn make_fields_explicit_not_applicable() {
+        // The default isn't derived.
+        check_assist_not_applicable(
+            make_fields_explicit,
+            "struct S { a: u8 }\nimpl Default for S {}\nfn f() { S { <|>..Default::default() }; }",
+        );
+        check_assist_not_applicable(
+            make_fields_explicit,
+            "#[derive(Default)]\nstruct S { a: u8 }\nfn f(s: S) { S { <|>..s }; }",
+        );
+    }
+}
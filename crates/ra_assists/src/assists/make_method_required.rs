@@ -0,0 +1,172 @@
+use hir::{db::HirDatabase, Crate, HasSource, ImplBlock, InFile};
+use ra_syntax::{
+    ast::{self, edit, AstNode, NameOwner},
+    TextRange,
+};
+
+use crate::{
+    ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: make_method_required
+//
+// Removes the default body of a trait method, copying it into the impls which
+// relied on it.
+//
+// ```
+// trait Shape {
+//     fn name(&self) -> String<|> {
+//         String::new()
+//     }
+// }
+//
+// struct Circle;
+//
+// impl Shape for Circle {}
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn name(&self) -> String;
+// }
+//
+// struct Circle;
+//
+// impl Shape for Circle {
+//     fn name(&self) -> String {
+//         String::new()
+//     }
+// }
+// ```
+pub(crate) fn make_method_required(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let trait_def = fn_def.syntax().ancestors().nth(2).and_then(ast::TraitDef::cast)?;
+    let name = fn_def.name()?;
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let trait_ = ctx.source_binder().to_def(InFile::new(file_id, trait_def))?;
+    let mut impls = Vec::new();
+    for krate in Crate::all(db) {
+        for impl_block in ImplBlock::for_trait(db, krate, trait_) {
+            let source = impl_block.source(db);
+            // Impls in other files or crates can't be updated.
+            if source.file_id != file_id {
+                return None;
+            }
+            impls.push(source.value);
+        }
+    }
+    let impls = impls
+        .into_iter()
+        .filter(|impl_block| match impl_block.item_list() {
+            Some(it) => it.impl_items().all(|it| match it {
+                ast::ImplItem::FnDef(it) => {
+                    it.name().map_or(true, |it| it.text().as_str() != name.text().as_str())
+                }
+                _ => true,
+            }),
+            None => false,
+        })
+        .collect::<Vec<_>>();
+
+    let body_start = body
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| ast::Whitespace::cast(it.into_token()?))
+        .map_or(body.syntax().text_range().start(), |it| it.syntax().text_range().start());
+
+    ctx.add_assist(AssistId("make_method_required"), "Make method required", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        edit.replace(TextRange::from_to(body_start, body.syntax().text_range().end()), ";");
+        for impl_block in impls {
+            let item_list = match impl_block.item_list() {
+                Some(it) => it,
+                None => continue,
+            };
+            let module =
+                hir::SourceAnalyzer::new(db, InFile::new(file_id, impl_block.syntax()), None)
+                    .module();
+            let ast_transform = QualifyPaths::new(db, module)
+                .or(SubstituteTypeParams::for_trait_impl(db, trait_, impl_block));
+            let item = ast::ImplItem::FnDef(fn_def.clone());
+            let item = ast_transform::apply(&*ast_transform, InFile::new(file_id, item));
+            let item = edit::strip_attrs_and_docs(&item);
+            edit.replace_ast(item_list.clone(), item_list.append_items(std::iter::once(item)));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_method_required_copies_default() {
+        check_assist(
+            make_method_required,
+            r#"
+trait Shape<T> {
+    /// Docs.
+    fn <|>scale(&self, by: T) -> T {
+        by
+    }
+    fn name(&self) {}
+}
+struct Circle;
+struct Square;
+impl Shape<u32> for Circle {
+    fn name(&self) {}
+}
+impl Shape<f64> for Square {
+    fn scale(&self, by: f64) -> f64 { by * 2.0 }
+}
+"#,
+            r#"
+trait Shape<T> {
+    /// Docs.
+    fn <|>scale(&self, by: T) -> T;
+    fn name(&self) {}
+}
+struct Circle;
+struct Square;
+impl Shape<u32> for Circle {
+    fn name(&self) {}
+    fn scale(&self, by: u32) -> u32 {
+        by
+    }
+}
+impl Shape<f64> for Square {
+    fn scale(&self, by: f64) -> f64 { by * 2.0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_method_required_without_impls() {
+        check_assist(
+            make_method_required,
+            "trait T { fn f(&self)<|> {} }",
+            "trait T { fn f(&self)<|>; }",
+        );
+    }
+
+    #[test]
+    fn make_method_required_target() {
+        check_assist_target(make_method_required, "trait T { fn <|>f() {} }", "fn f() {}");
+    }
+
+    #[test]
+    fn make_method_required_not_applicable() {
+        check_assist_not_applicable(make_method_required, "trait T { fn <|>f(); }");
+        check_assist_not_applicable(make_method_required, "struct S; impl S { fn <|>f() {} }");
+        check_assist_not_applicable(make_method_required, "trait T { fn f() { <|>() } }");
+    }
+}
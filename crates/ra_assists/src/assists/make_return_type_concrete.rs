@@ -0,0 +1,122 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::{LAMBDA_EXPR, RETURN_EXPR, TRY_EXPR},
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: make_return_type_concrete
+//
+// Replaces an `impl Trait` return type with the concrete type returned by the
+// function.
+//
+// ```
+// struct Meters(u32);
+// trait Unit {}
+// impl Unit for Meters {}
+//
+// fn distance() -> impl Un<|>it {
+//     Meters(92)
+// }
+// ```
+// ->
+// ```
+// struct Meters(u32);
+// trait Unit {}
+// impl Unit for Meters {}
+//
+// fn distance() -> Meters {
+//     Meters(92)
+// }
+// ```
+pub(crate) fn make_return_type_concrete(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let impl_trait = match ret_type.type_ref()? {
+        ast::TypeRef::ImplTraitType(it) => it,
+        _ => return None,
+    };
+    let fn_def = ast::FnDef::cast(ret_type.syntax().parent()?)?;
+    let body = fn_def.body()?;
+    let tail = body.block()?.expr()?;
+
+    // With several return positions, the types might differ.
+    let has_other_returns = body.syntax().descendants().any(|it| {
+        (it.kind() == RETURN_EXPR || it.kind() == TRY_EXPR)
+            && !it
+                .ancestors()
+                .take_while(|it| it != body.syntax())
+                .any(|it| it.kind() == LAMBDA_EXPR)
+    });
+    if has_other_returns {
+        return None;
+    }
+
+    let db = ctx.db;
+    let ty = ctx.source_analyzer(tail.syntax(), None).type_of(db, &tail)?;
+    if ty.contains_unknown() {
+        return None;
+    }
+    let ty = ty.display(db).to_string();
+    // Closures and other opaque types can't be named.
+    if ty.contains('|') || ty.contains("impl ") {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("make_return_type_concrete"), "Make return type concrete", |edit| {
+        edit.target(impl_trait.syntax().text_range());
+        edit.replace(impl_trait.syntax().text_range(), ty);
+        edit.set_cursor(impl_trait.syntax().text_range().start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_return_type_concrete_for_struct() {
+        check_assist(
+            make_return_type_concrete,
+            "struct S<T>(T);\nfn f() -> impl <|>Tr { S(1u8) }",
+            "struct S<T>(T);\nfn f() -> <|>S<u8> { S(1u8) }",
+        );
+    }
+
+    #[test]
+    fn make_return_type_concrete_ignores_closure_returns() {
+        check_assist(
+            make_return_type_concrete,
+            "fn f() -> impl <|>Tr { let g = || { return 1; }; 92u64 }",
+            "fn f() -> <|>u64 { let g = || { return 1; }; 92u64 }",
+        );
+    }
+
+    #[test]
+    fn make_return_type_concrete_target() {
+        check_assist_target(
+            make_return_type_concrete,
+            "fn f() -> impl <|>Tr + Send { 92u64 }",
+            "impl Tr + Send",
+        );
+    }
+
+    #[test]
+    fn make_return_type_concrete_not_applicable_for_closure() {
+        check_assist_not_applicable(make_return_type_concrete, "fn f() -> impl <|>Fn() { || () }");
+    }
+
+    #[test]
+    fn make_return_type_concrete_not_applicable_with_early_return() {
+        check_assist_not_applicable(
+            make_return_type_concrete,
+            "fn f(a: bool) -> impl <|>Tr { if a { return 1u8; } 2u8 }",
+        );
+    }
+
+    #[test]
+    fn make_return_type_concrete_not_applicable_for_concrete_type() {
+        check_assist_not_applicable(make_return_type_concrete, "fn f() -> <|>u8 { 92 }");
+    }
+}
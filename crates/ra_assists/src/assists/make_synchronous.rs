@@ -0,0 +1,188 @@
+use hir::{db::HirDatabase, AssocItem, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, VisibilityOwner},
+    TextRange, T,
+};
+
+use crate::{
+    utils::{local_function, own_descendants},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: make_synchronous
+//
+// Removes `async` from a function which never awaits, and `.await` from
+// the calls to it.
+//
+// ```
+// async fn <|>config() -> Config {
+//     Config::default()
+// }
+//
+// async fn run() {
+//     let config = config().await;
+// }
+// ```
+// ->
+// ```
+// fn config() -> Config {
+//     Config::default()
+// }
+//
+// async fn run() {
+//     let config = config();
+// }
+// ```
+pub(crate) fn make_synchronous(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let async_kw = fn_def.syntax().children_with_tokens().find(|it| it.kind() == T![async])?;
+    let awaits = own_descendants(body.syntax()).any(|it| {
+        ast::AwaitExpr::can_cast(it.kind())
+            || ast::MacroCall::cast(it)
+                .map_or(false, |it| it.syntax().to_string().contains("await"))
+    });
+    if awaits {
+        return None;
+    }
+    // Callers outside of the module, or in child modules of other files,
+    // can't be updated.
+    if fn_def.visibility().is_some() {
+        return None;
+    }
+    let (function, _) = local_function(&ctx, &fn_def)?;
+    let name = fn_def.name()?;
+
+    // Every use of the function has to be a call which is awaited right away.
+    let file = fn_def.syntax().ancestors().last()?;
+    let mut awaited_calls = Vec::new();
+    for node in file.descendants() {
+        let call = if let Some(path_expr) = ast::PathExpr::cast(node.clone()) {
+            let name_ref =
+                path_expr.path().and_then(|it| it.segment()).and_then(|it| it.name_ref());
+            if name_ref.map_or(true, |it| it.text() != name.text()) {
+                continue;
+            }
+            let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+            match path_expr.path().and_then(|it| analyzer.resolve_path(ctx.db, &it)) {
+                Some(PathResolution::Def(ModuleDef::Function(it)))
+                | Some(PathResolution::AssocItem(AssocItem::Function(it)))
+                    if it == function => {}
+                _ => continue,
+            }
+            let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+            if call.expr()?.syntax() != path_expr.syntax() {
+                return None;
+            }
+            call.syntax().clone()
+        } else if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            if call.name_ref().map_or(true, |it| it.text() != name.text()) {
+                continue;
+            }
+            let analyzer = ctx.source_analyzer(call.syntax(), None);
+            if analyzer.resolve_method_call(&call) != Some(function) {
+                continue;
+            }
+            call.syntax().clone()
+        } else {
+            continue;
+        };
+        let await_expr = call.parent().and_then(ast::AwaitExpr::cast)?;
+        awaited_calls.push(TextRange::from_to(
+            call.text_range().end(),
+            await_expr.syntax().text_range().end(),
+        ));
+    }
+
+    ctx.add_assist(AssistId("make_synchronous"), "Make synchronous", |edit| {
+        let async_range = async_kw.text_range();
+        edit.target(async_range);
+        let end = match async_kw.next_sibling_or_token() {
+            Some(it) if it.kind().is_trivia() => it.text_range().end(),
+            _ => async_range.end(),
+        };
+        edit.delete(TextRange::from_to(async_range.start(), end));
+        for range in awaited_calls {
+            edit.delete(range);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn make_synchronous_method() {
+        check_assist(
+            make_synchronous,
+            r#"
+struct Client;
+impl Client {
+    async fn <|>name(&self) -> String { String::new() }
+    async fn greet(&self) -> String {
+        let name = self.name().await;
+        let other = Self::name(self)
+            .await;
+        name
+    }
+}
+"#,
+            r#"
+struct Client;
+impl Client {
+    fn <|>name(&self) -> String { String::new() }
+    async fn greet(&self) -> String {
+        let name = self.name();
+        let other = Self::name(self);
+        name
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_synchronous_target() {
+        check_assist_target(make_synchronous, "async fn <|>f() {}", "async");
+    }
+
+    #[test]
+    fn make_synchronous_not_applicable() {
+        check_assist_not_applicable(make_synchronous, "fn <|>f() {}");
+        check_assist_not_applicable(make_synchronous, "pub async fn <|>f() {}");
+        check_assist_not_applicable(make_synchronous, "async fn <|>f() { g().await; }");
+        check_assist_not_applicable(
+            make_synchronous,
+            "async fn <|>f() { h(async { g().await }); }",
+        );
+        // Not awaited right where it is called.
+        check_assist_not_applicable(
+            make_synchronous,
+            "async fn <|>f() {}\nasync fn g() { let fut = f(); fut.await; }",
+        );
+        check_assist_not_applicable(make_synchronous, "async fn <|>f() {}\nfn g() { spawn(f); }");
+    }
+
+    #[test]
+    fn make_synchronous_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            make_synchronous,
+            r#"
+//- /main.rs
+mod tests;
+async fn <|>config() -> u32 { 0 }
+
+//- /tests.rs
+async fn f() { super::config().await; }
+"#,
+        );
+    }
+}
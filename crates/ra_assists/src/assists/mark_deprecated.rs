@@ -0,0 +1,116 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, VisibilityOwner},
+    TextUnit,
+};
+
+use crate::{assist_ctx::ActionBuilder, utils::attr_insertion_offset, Assist, AssistCtx, AssistId};
+
+// Assist: mark_deprecated
+//
+// Adds a `#[deprecated]` attribute to a public item.
+//
+// ```
+// /// Parses the config.
+// pub fn <|>parse(text: &str) -> Config { todo!() }
+// ```
+// ->
+// ```
+// /// Parses the config.
+// #[deprecated(since = "", note = "")]
+// pub fn parse(text: &str) -> Config { todo!() }
+// ```
+pub(crate) fn mark_deprecated(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let item = ctx.find_node_at_offset::<ast::ModuleItem>()?;
+    // Only offered outside of the body of the item.
+    let in_body = ctx
+        .covering_element()
+        .ancestors()
+        .take_while(|it| it != item.syntax())
+        .any(|it| ast::Block::can_cast(it.kind()) || ast::ItemList::can_cast(it.kind()));
+    if in_body {
+        return None;
+    }
+    if item.visibility()?.syntax().text() != "pub" {
+        return None;
+    }
+    if item.attrs().any(|it| it.simple_name().map_or(false, |it| it == "deprecated")) {
+        return None;
+    }
+    let offset = attr_insertion_offset(item.syntax())?;
+    let indent = leading_indent(item.syntax()).unwrap_or_default();
+
+    // The cursor goes to the first value.
+    let deprecate = |label: &str, args: &str, before_cursor: &str| {
+        let mut edit = ActionBuilder::default();
+        edit.label(label);
+        edit.target(item.syntax().text_range());
+        edit.insert(offset, format!("#[deprecated({})]\n{}", args, indent));
+        edit.set_cursor(
+            offset + TextUnit::of_str("#[deprecated(") + TextUnit::of_str(before_cursor),
+        );
+        edit
+    };
+    let with_since =
+        || deprecate("Deprecate since a version", "since = \"\", note = \"\"", "since = \"");
+    let with_note = || deprecate("Deprecate with a note", "note = \"\"", "note = \"");
+    ctx.add_assist_group(AssistId("mark_deprecated"), "Mark deprecated", || {
+        vec![with_since(), with_note()]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_group, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn mark_deprecated_after_doc_comment() {
+        check_assist(
+            mark_deprecated,
+            r#"
+impl S {
+    /// Docs.
+    #[inline]
+    pub fn <|>f(&self) {}
+}
+"#,
+            r#"
+impl S {
+    /// Docs.
+    #[deprecated(since = "<|>", note = "")]
+    #[inline]
+    pub fn f(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mark_deprecated_with_note() {
+        check_assist_group(
+            mark_deprecated,
+            "pub struct <|>S;",
+            &[
+                "#[deprecated(since = \"\", note = \"\")]\npub struct S;",
+                "#[deprecated(note = \"\")]\npub struct S;",
+            ],
+        );
+    }
+
+    #[test]
+    fn mark_deprecated_target() {
+        check_assist_target(mark_deprecated, "pub fn <|>f() {}\nfn g() {}", "pub fn f() {}");
+    }
+
+    #[test]
+    fn mark_deprecated_not_applicable() {
+        check_assist_not_applicable(mark_deprecated, "fn <|>f() {}");
+        check_assist_not_applicable(mark_deprecated, "pub(crate) fn <|>f() {}");
+        check_assist_not_applicable(mark_deprecated, "#[deprecated]\npub fn <|>f() {}");
+        check_assist_not_applicable(mark_deprecated, "pub fn f() { <|>g(); }");
+    }
+}
@@ -0,0 +1,234 @@
+use hir::{db::HirDatabase, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    Direction, SyntaxNode, TextRange, T,
+};
+
+use crate::{
+    utils::{has_side_effects, mutated_locals},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: merge_duplicate_ifs
+//
+// Merges two adjacent `if` expressions with the same condition.
+//
+// ```
+// fn main() {
+//     <|>if verbose {
+//         print_header();
+//     }
+//     if verbose {
+//         print_rows();
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     if verbose {
+//         print_header();
+//         print_rows();
+//     }
+// }
+// ```
+pub(crate) fn merge_duplicate_ifs(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_keyword = ctx.find_token_at_offset(T![if])?;
+    let first = ast::IfExpr::cast(if_keyword.parent())?;
+    let stmt = first.syntax().parent().filter(|it| ast::ExprStmt::can_cast(it.kind()))?;
+    let second = stmt
+        .siblings(Direction::Next)
+        .nth(1)
+        .and_then(|it| match ast::ExprStmt::cast(it.clone()) {
+            Some(stmt) => stmt.expr(),
+            None => ast::Expr::cast(it),
+        })
+        .and_then(|it| match it {
+            ast::Expr::IfExpr(it) => Some(it),
+            _ => None,
+        })?;
+    if first.else_branch().is_some() || second.else_branch().is_some() {
+        return None;
+    }
+    let cond = first.condition()?;
+    if tokens(cond.syntax()) != tokens(second.condition()?.syntax()) {
+        return None;
+    }
+    if has_side_effects(cond.syntax()) {
+        return None;
+    }
+    let first_block = first.then_branch()?;
+    let second_block = second.then_branch()?;
+
+    // The first body can't change the outcome of the condition, which calls
+    // may depend on anything, like statics or cells.
+    if has_calls(cond.syntax()) && has_calls(first_block.syntax()) {
+        return None;
+    }
+    let mutated = mutated_locals(&ctx, first_block.syntax());
+    for path_expr in cond.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+        let local = match path_expr.path().and_then(|it| analyzer.resolve_path(ctx.db, &it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        if mutated.contains(&local) {
+            return None;
+        }
+        // `&mut self` doesn't have to be `mut` to be mutated by a method.
+        let is_mut_ref = local.ty(ctx.db).display(ctx.db).to_string().starts_with("&mut ");
+        let name = path_expr.syntax().text();
+        let receives_call = first_block
+            .syntax()
+            .descendants()
+            .filter_map(ast::MethodCallExpr::cast)
+            .filter_map(|it| it.expr())
+            .any(|it| it.syntax().text() == name);
+        if is_mut_ref && receives_call {
+            return None;
+        }
+    }
+    // Nor can it shadow something the second body uses.
+    let first_bindings = first_block
+        .block()?
+        .statements()
+        .filter_map(|it| match it {
+            ast::Stmt::LetStmt(it) => it.pat(),
+            _ => None,
+        })
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::BindPat::cast))
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    if second_block
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .any(|it| first_bindings.contains(it.text()))
+    {
+        return None;
+    }
+
+    let first_body = block_body(&first_block)?;
+    let second_body = block_body(&second_block)?;
+    ctx.add_assist(AssistId("merge_duplicate_ifs"), "Merge duplicate ifs", |edit| {
+        let indent = leading_indent(first.syntax()).unwrap_or_default();
+        let range = TextRange::from_to(
+            first.syntax().text_range().start(),
+            second.syntax().text_range().end(),
+        );
+        let mut merged = format!("if {} {{", cond.syntax());
+        for body in [first_body, second_body].iter().filter(|it| !it.is_empty()) {
+            merged.push_str(&format!("\n{}    {}", indent, body));
+        }
+        merged.push_str(&format!("\n{}}}", indent));
+        edit.target(range);
+        edit.replace(range, merged);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn has_calls(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| {
+        ast::CallExpr::can_cast(it.kind())
+            || ast::MethodCallExpr::can_cast(it.kind())
+            || ast::MacroCall::can_cast(it.kind())
+    })
+}
+
+fn tokens(node: &SyntaxNode) -> Vec<String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+/// The text between the braces of the block, with a semicolon after the tail
+/// expression, which isn't the last one anymore.
+fn block_body(block_expr: &ast::BlockExpr) -> Option<String> {
+    let block = block_expr.block()?;
+    let text = block.syntax().text().to_string();
+    let mut body = text.trim_start_matches('{').trim_end_matches('}').trim().to_string();
+    if block.expr().is_some() {
+        body.push(';');
+    }
+    Some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn merge_duplicate_ifs_with_tail_expr() {
+        check_assist(
+            merge_duplicate_ifs,
+            r#"
+fn f(items: &[u8], verbose: bool, mut logged: u8) {
+    if !items.is_empty() {
+        <|>if verbose && items.len() > 1 { logged += 1 }
+        if verbose&&items.len() > 1 {
+            let n = items.len();
+            print(n);
+        };
+    }
+}
+"#,
+            r#"
+fn f(items: &[u8], verbose: bool, mut logged: u8) {
+    if !items.is_empty() {
+        <|>if verbose && items.len() > 1 {
+            logged += 1;
+            let n = items.len();
+            print(n);
+        };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_duplicate_ifs_target() {
+        check_assist_target(
+            merge_duplicate_ifs,
+            "fn f(a: bool) {\n    <|>if a { g(); }\n    if a { h(); }\n}",
+            "if a { g(); }\n    if a { h(); }",
+        );
+    }
+
+    #[test]
+    fn merge_duplicate_ifs_not_applicable() {
+        // Different conditions.
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f(a: bool, b: bool) {\n    <|>if a { g(); }\n    if b { h(); }\n}",
+        );
+        // Not adjacent.
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f(a: bool) {\n    <|>if a { g(); }\n    g();\n    if a { h(); }\n}",
+        );
+        // The condition changes.
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f(mut a: bool) {\n    <|>if a { a = false; }\n    if a { h(); }\n}",
+        );
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f(v: &mut Vec<u8>) {\n    <|>if v.is_empty() { v.push(1); }\n    if v.is_empty() { h(); }\n}",
+        );
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f() {\n    <|>if ready() { a(); }\n    if ready() { b(); }\n}",
+        );
+        // The first body shadows a variable used by the second one.
+        check_assist_not_applicable(
+            merge_duplicate_ifs,
+            "fn f(a: bool, x: u8) {\n    <|>if a { let x = 1; g(x); }\n    if a { h(x); }\n}",
+        );
+    }
+}
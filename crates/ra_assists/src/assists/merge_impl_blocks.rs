@@ -0,0 +1,233 @@
+use hir::{db::HirDatabase, InFile};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, TypeParamsOwner},
+    Direction, SyntaxNode, TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: merge_impl_blocks
+//
+// Moves the members of another inherent `impl` block for the same type into
+// this one.
+//
+// ```
+// struct Counter(u32);
+//
+// impl <|>Counter {
+//     fn get(&self) -> u32 { self.0 }
+// }
+//
+// impl Counter {
+//     fn reset(&mut self) { self.0 = 0 }
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+//
+// impl Counter {
+//     fn get(&self) -> u32 { self.0 }
+//
+//     fn reset(&mut self) { self.0 = 0 }
+// }
+// ```
+pub(crate) fn merge_impl_blocks(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_block = ctx.find_node_at_offset::<ast::ImplBlock>()?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    let item_list = impl_block.item_list()?;
+    // Only offer the assist on the header, not on every member.
+    if ctx.frange.range.start() >= item_list.syntax().text_range().start() {
+        return None;
+    }
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let mut sb = ctx.source_binder();
+    let target_ty = sb.to_def(InFile::new(file_id, impl_block.clone()))?.target_ty(db);
+    let parent = impl_block.syntax().parent()?;
+    let same_impls = parent
+        .children()
+        .filter_map(ast::ImplBlock::cast)
+        .filter(|it| *it != impl_block && it.target_trait().is_none())
+        .filter(|it| {
+            sb.to_def(InFile::new(file_id, it.clone())).map(|it| it.target_ty(db)).as_ref()
+                == Some(&target_ty)
+        })
+        .collect::<Vec<_>>();
+    // Prefer merging the following block into this one.
+    let start = impl_block.syntax().text_range().start();
+    let other = same_impls
+        .iter()
+        .find(|it| it.syntax().text_range().start() > start)
+        .or_else(|| same_impls.last())?
+        .clone();
+    if !has_same_header(&impl_block, &other) {
+        return None;
+    }
+    let (first, second) = if other.syntax().text_range().start() > start {
+        (impl_block.clone(), other)
+    } else {
+        (other, impl_block.clone())
+    };
+
+    let first_items = first.item_list()?;
+    let second_items = second.item_list()?;
+    let members = inner_text(second_items.syntax())?;
+    if members.is_empty() {
+        return None;
+    }
+    let indent = second_items
+        .impl_items()
+        .next()
+        .and_then(|it| leading_indent(it.syntax()))
+        .unwrap_or_default();
+    let (offset, prefix, suffix) = match first_items.impl_items().last() {
+        Some(last) => (last.syntax().text_range().end(), format!("\n\n{}", indent), String::new()),
+        None => {
+            let l_curly = first_items.syntax().first_token()?;
+            let suffix = if first_items.syntax().text().contains_char('\n') {
+                String::new()
+            } else {
+                "\n".to_string()
+            };
+            (l_curly.text_range().end(), format!("\n{}", indent), suffix)
+        }
+    };
+
+    ctx.add_assist(AssistId("merge_impl_blocks"), "Merge impl blocks", |edit| {
+        edit.target(impl_block.syntax().text_range());
+        edit.insert(offset, format!("{}{}{}", prefix, members, suffix));
+        edit.delete(range_with_leading_ws(second.syntax()));
+        edit.set_cursor(offset + TextUnit::of_str(&prefix));
+    })
+}
+
+/// Whether both blocks declare the same generics, bounds and attributes.
+fn has_same_header(a: &ast::ImplBlock, b: &ast::ImplBlock) -> bool {
+    let normalize = |node: Option<&SyntaxNode>| {
+        node.map(|it| it.text().to_string().split_whitespace().collect::<String>())
+    };
+    let attrs =
+        |it: &ast::ImplBlock| it.attrs().map(|it| it.syntax().to_string()).collect::<Vec<_>>();
+    normalize(a.type_param_list().as_ref().map(AstNode::syntax))
+        == normalize(b.type_param_list().as_ref().map(AstNode::syntax))
+        && normalize(a.where_clause().as_ref().map(AstNode::syntax))
+            == normalize(b.where_clause().as_ref().map(AstNode::syntax))
+        && attrs(a) == attrs(b)
+}
+
+/// The text between the curly braces of an item list, without surrounding
+/// whitespace.
+fn inner_text(item_list: &SyntaxNode) -> Option<String> {
+    item_list.first_token().filter(|it| it.kind() == T!['{'])?;
+    item_list.last_token().filter(|it| it.kind() == T!['}'])?;
+    let text = item_list.text().to_string();
+    Some(text[1..text.len() - 1].trim().to_string())
+}
+
+fn range_with_leading_ws(node: &SyntaxNode) -> TextRange {
+    let start = node
+        .siblings_with_tokens(Direction::Prev)
+        .nth(1)
+        .and_then(|it| ast::Whitespace::cast(it.into_token()?))
+        .map_or(node.text_range().start(), |it| it.syntax().text_range().start());
+    TextRange::from_to(start, node.text_range().end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn merge_following_impl_block() {
+        check_assist(
+            merge_impl_blocks,
+            r#"
+struct S;
+impl <|>S {
+    fn a(&self) {}
+}
+trait T {}
+impl T for S {}
+impl S {
+    /// Docs.
+    #[inline]
+    fn b(&self) {}
+
+    // Comment.
+    const C: u32 = 0;
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn a(&self) {}
+
+    <|>/// Docs.
+    #[inline]
+    fn b(&self) {}
+
+    // Comment.
+    const C: u32 = 0;
+}
+trait T {}
+impl T for S {}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_into_preceding_empty_impl_block() {
+        check_assist(
+            merge_impl_blocks,
+            r#"
+struct S<T>(T);
+impl<T: Clone> S<T> {}
+
+impl<T: Clone> <|>S<T> {
+    fn get(&self) -> T { self.0.clone() }
+}
+"#,
+            r#"
+struct S<T>(T);
+impl<T: Clone> S<T> {
+    <|>fn get(&self) -> T { self.0.clone() }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_impl_blocks_target() {
+        check_assist_target(
+            merge_impl_blocks,
+            "struct S; impl <|>S { fn a() {} } impl S { fn b() {} }",
+            "impl S { fn a() {} }",
+        );
+    }
+
+    #[test]
+    fn merge_impl_blocks_not_applicable_for_different_types() {
+        check_assist_not_applicable(
+            merge_impl_blocks,
+            "struct S; struct U; impl <|>S { fn a() {} } impl U { fn b() {} }",
+        );
+    }
+
+    #[test]
+    fn merge_impl_blocks_not_applicable_for_different_generics() {
+        check_assist_not_applicable(
+            merge_impl_blocks,
+            "struct S<T>(T); impl<T> <|>S<T> {} impl<T: Copy> S<T> { fn b() {} }",
+        );
+        check_assist_not_applicable(
+            merge_impl_blocks,
+            "struct S<T>(T); impl <|>S<u32> {} impl S<u64> { fn b() {} }",
+        );
+    }
+}
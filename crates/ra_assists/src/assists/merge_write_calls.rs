@@ -0,0 +1,279 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    Direction, SyntaxElement,
+    SyntaxKind::{COMMENT, IDENT, STRING},
+    SyntaxNode, TextRange, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: merge_write_calls
+//
+// Merges consecutive `write!` and `writeln!` calls to the same target into a
+// single one.
+//
+// ```
+// fn fmt(&self, f: &mut Formatter) -> Result {
+//     <|>write!(f, "({}", self.x)?;
+//     write!(f, ", {})", self.y)
+// }
+// ```
+// ->
+// ```
+// fn fmt(&self, f: &mut Formatter) -> Result {
+//     write!(f, "({}, {})", self.x, self.y)
+// }
+// ```
+pub(crate) fn merge_write_calls(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let first = macro_call
+        .syntax()
+        .ancestors()
+        .find(|it| it.parent().map_or(false, |it| ast::Block::can_cast(it.kind())))?;
+    let mut writes = Vec::new();
+    for stmt in first.siblings(Direction::Next) {
+        match WriteStmt::from_node(&stmt) {
+            Some(it) => writes.push(it),
+            None => break,
+        }
+    }
+    if writes.first()?.macro_call != macro_call {
+        return None;
+    }
+    // Only the last write may ignore the error, by returning it.
+    let count = writes.iter().position(|it| !it.is_try).map_or(writes.len(), |it| it + 1);
+    writes.truncate(count);
+    let last = writes.pop()?;
+    if writes.is_empty() || (!last.is_try && last.has_semi) {
+        return None;
+    }
+    let writes =
+        writes.iter().map(|it| Write::parse(&it.macro_call)).collect::<Option<Vec<_>>>()?;
+    let last_write = Write::parse(&last.macro_call)?;
+    if writes.iter().any(|it| it.target != last_write.target) {
+        return None;
+    }
+    let range =
+        TextRange::from_to(first.text_range().start(), last.macro_call.syntax().text_range().end());
+    if first
+        .parent()?
+        .descendants_with_tokens()
+        .any(|it| it.kind() == COMMENT && it.text_range().is_subrange(&range))
+    {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("merge_write_calls"), "Merge write! calls", |edit| {
+        let mut format_string = String::new();
+        let mut args = Vec::new();
+        for write in writes.iter() {
+            format_string.push_str(&write.format_string);
+            if write.is_writeln {
+                format_string.push_str("\\n");
+            }
+            args.extend(write.args.iter().cloned());
+        }
+        format_string.push_str(&last_write.format_string);
+        args.extend(last_write.args.iter().cloned());
+        let mut buf = format!(
+            "{}!({}, \"{}\"",
+            if last_write.is_writeln { "writeln" } else { "write" },
+            last_write.target.join(""),
+            format_string
+        );
+        for arg in args {
+            buf.push_str(&format!(", {}", arg));
+        }
+        buf.push(')');
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// A statement, or the tail expression, of a block consisting of a macro
+/// call, possibly followed by `?`.
+struct WriteStmt {
+    macro_call: ast::MacroCall,
+    is_try: bool,
+    has_semi: bool,
+}
+
+impl WriteStmt {
+    fn from_node(node: &SyntaxNode) -> Option<WriteStmt> {
+        let (expr, has_semi) = match ast::ExprStmt::cast(node.clone()) {
+            Some(stmt) => (stmt.expr()?, true),
+            None => (ast::Expr::cast(node.clone())?, false),
+        };
+        let (macro_call, is_try) = match expr {
+            ast::Expr::MacroCall(it) => (it, false),
+            ast::Expr::TryExpr(it) => match it.expr()? {
+                ast::Expr::MacroCall(it) => (it, true),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(WriteStmt { macro_call, is_try, has_semi })
+    }
+}
+
+/// The arguments of a `write!` or `writeln!` call.
+struct Write {
+    target: Vec<String>,
+    /// The contents of the format string literal.
+    format_string: String,
+    args: Vec<String>,
+    is_writeln: bool,
+}
+
+impl Write {
+    fn parse(macro_call: &ast::MacroCall) -> Option<Write> {
+        let is_writeln = match macro_call.path()?.syntax().text().to_string().as_str() {
+            "write" => false,
+            "writeln" => true,
+            _ => return None,
+        };
+        let elements = macro_call.token_tree()?.syntax().children_with_tokens().collect::<Vec<_>>();
+        if elements.first()?.kind() != T!['('] {
+            return None;
+        }
+        let inner = elements.get(1..elements.len().checked_sub(1)?)?;
+        let mut parts = inner.split(|it| it.kind() == T![,]).collect::<Vec<_>>();
+        if parts.len() > 1 && non_trivia(parts[parts.len() - 1]).is_empty() {
+            parts.pop();
+        }
+
+        // Evaluating the target again could have side effects.
+        let target = non_trivia(parts.first()?);
+        let is_place = target.iter().all(|it| match it.kind() {
+            IDENT | T![self] | T![.] | T![*] | T![&] | T![mut] => true,
+            _ => false,
+        });
+        if target.is_empty() || !is_place {
+            return None;
+        }
+        let format_string = match parts.get(1).map(|it| non_trivia(it)) {
+            Some(elements) => match elements.as_slice() {
+                [it] if it.kind() == STRING => {
+                    let text = it.to_string();
+                    text[1..text.len() - 1].to_string()
+                }
+                _ => return None,
+            },
+            None if is_writeln => String::new(),
+            None => return None,
+        };
+        if !has_positional_placeholders_only(&format_string) {
+            return None;
+        }
+        let mut args = Vec::new();
+        for part in parts.iter().skip(2) {
+            // Named arguments could clash.
+            if part.iter().any(|it| it.kind() == T![=]) {
+                return None;
+            }
+            args.push(part.iter().map(|it| it.to_string()).collect::<String>().trim().to_string());
+        }
+        let target = target.iter().map(|it| it.to_string()).collect();
+        Some(Write { target, format_string, args, is_writeln })
+    }
+}
+
+fn non_trivia(elements: &[SyntaxElement]) -> Vec<SyntaxElement> {
+    elements.iter().filter(|it| !it.kind().is_trivia()).cloned().collect()
+}
+
+/// Whether the placeholders of the format string only take the next argument,
+/// so that it can be concatenated with another one.
+fn has_positional_placeholders_only(format_string: &str) -> bool {
+    let mut chars = format_string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+            }
+            ('{', Some('}')) | ('{', Some(':')) => (),
+            ('{', _) => return false,
+            _ => (),
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn merge_write_calls_with_writeln() {
+        check_assist(
+            merge_write_calls,
+            r#"
+fn fmt(&self, f: &mut Formatter) -> Result {
+    writeln!(f, "{{")?;
+    <|>writeln!(self.out, "name: {:?}", self.name)?;
+    write!(self.out, "age: {}, ", self.age,)?;
+    writeln!(self.out)?;
+    let x = 1;
+    Ok(())
+}
+"#,
+            r#"
+fn fmt(&self, f: &mut Formatter) -> Result {
+    writeln!(f, "{{")?;
+    <|>writeln!(self.out, "name: {:?}\nage: {}, ", self.name, self.age)?;
+    let x = 1;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_write_calls_until_ignored() {
+        check_assist(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\")?;\n    write!(w, \"b\")\n}",
+            "fn f(w: &mut W) {\n    <|>write!(w, \"ab\")\n}",
+        );
+    }
+
+    #[test]
+    fn merge_write_calls_target() {
+        check_assist_target(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\")?;\n    write!(w, \"b\")?;\n}",
+            "write!(w, \"a\")?;\n    write!(w, \"b\")",
+        );
+    }
+
+    #[test]
+    fn merge_write_calls_not_applicable() {
+        // Different targets.
+        check_assist_not_applicable(
+            merge_write_calls,
+            "fn f(w: &mut W, v: &mut W) {\n    <|>write!(w, \"a\")?;\n    write!(v, \"b\")?;\n}",
+        );
+        // The error of the first one is ignored.
+        check_assist_not_applicable(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\");\n    write!(w, \"b\")?;\n}",
+        );
+        // Positional arguments.
+        check_assist_not_applicable(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\")?;\n    write!(w, \"{0}{0}\", 1)?;\n}",
+        );
+        // Not consecutive.
+        check_assist_not_applicable(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\")?;\n    g();\n    write!(w, \"b\")?;\n}",
+        );
+        check_assist_not_applicable(
+            merge_write_calls,
+            "fn f(w: &mut W) {\n    <|>write!(w, \"a\")?;\n    // b\n    write!(w, \"b\")?;\n}",
+        );
+    }
+}
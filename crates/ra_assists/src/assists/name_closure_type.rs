@@ -0,0 +1,106 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeBoundsOwner, TypeParamsOwner},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: name_closure_type
+//
+// Replaces an `impl Fn` argument type with a named generic parameter, so that
+// the bound can be referred to, for example in a `where` clause.
+//
+// ```
+// fn apply(x: u32, f: impl Fn<|>(u32) -> u32) -> u32 {
+//     f(x)
+// }
+// ```
+// ->
+// ```
+// fn apply<F: Fn(u32) -> u32>(x: u32, f: F) -> u32 {
+//     f(x)
+// }
+// ```
+pub(crate) fn name_closure_type(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_trait = ctx.find_node_at_offset::<ast::ImplTraitType>()?;
+    let param = ast::Param::cast(impl_trait.syntax().parent()?)?;
+    let fn_def = param.syntax().ancestors().nth(2).and_then(ast::FnDef::cast)?;
+    let bounds = impl_trait.type_bound_list()?;
+    let is_fn_bound = bounds.bounds().any(|it| match it.type_ref() {
+        Some(ast::TypeRef::PathType(it)) => {
+            let segment = it.path().and_then(|it| it.segment());
+            let name = segment.as_ref().and_then(|it| it.name_ref());
+            match name.as_ref().map(|it| it.text().as_str()) {
+                Some("Fn") | Some("FnMut") | Some("FnOnce") => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    });
+    if !is_fn_bound {
+        return None;
+    }
+
+    let existing_names = fn_def
+        .type_param_list()
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let name = std::iter::once("F".to_string())
+        .chain((1..).map(|it| format!("F{}", it)))
+        .find(|it| !existing_names.contains(it))?;
+
+    let (offset, prefix, suffix) = match fn_def.type_param_list() {
+        Some(it) => (it.syntax().last_token()?.text_range().start(), ", ", ""),
+        None => (fn_def.name()?.syntax().text_range().end(), "<", ">"),
+    };
+
+    ctx.add_assist(AssistId("name_closure_type"), "Name the closure type", |edit| {
+        edit.target(impl_trait.syntax().text_range());
+        edit.insert(offset, format!("{}{}: {}{}", prefix, name, bounds.syntax(), suffix));
+        edit.replace(impl_trait.syntax().text_range(), name.clone());
+        edit.set_cursor(offset + TextUnit::of_str(prefix));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn name_closure_type_without_generics() {
+        check_assist(
+            name_closure_type,
+            "fn apply(x: u32, f: impl Fn<|>(u32) -> u32) -> u32 { f(x) }",
+            "fn apply<<|>F: Fn(u32) -> u32>(x: u32, f: F) -> u32 { f(x) }",
+        );
+    }
+
+    #[test]
+    fn name_closure_type_with_generics() {
+        check_assist(
+            name_closure_type,
+            "fn apply<F, T>(f: F, g: impl <|>FnMut(T) + Send) {}",
+            "fn apply<F, T, <|>F1: FnMut(T) + Send>(f: F, g: F1) {}",
+        );
+    }
+
+    #[test]
+    fn name_closure_type_target() {
+        check_assist_target(name_closure_type, "fn f(g: impl <|>FnOnce()) {}", "impl FnOnce()");
+    }
+
+    #[test]
+    fn name_closure_type_not_applicable_for_other_traits() {
+        check_assist_not_applicable(name_closure_type, "fn f(x: impl <|>Display) {}");
+    }
+
+    #[test]
+    fn name_closure_type_not_applicable_in_return_type() {
+        check_assist_not_applicable(name_closure_type, "fn f() -> impl <|>Fn() { || () }");
+    }
+}
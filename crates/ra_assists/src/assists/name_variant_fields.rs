@@ -0,0 +1,269 @@
+use hir::{db::HirDatabase, InFile, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, VisibilityOwner},
+    TextRange,
+};
+
+use crate::{utils::is_local_module, Assist, AssistCtx, AssistId};
+
+const ORDINALS: [&str; 10] =
+    ["first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth"];
+
+// Assist: name_variant_fields
+//
+// Gives names to the fields of a tuple variant, and updates the constructions
+// and patterns of the variant in the file.
+//
+// ```
+// enum Shape {
+//     Rect<|>(u32, u32),
+// }
+//
+// fn area(shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Rect(w, h) => w * h,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Shape {
+//     Rect { first: u32, second: u32 },
+// }
+//
+// fn area(shape: Shape) -> u32 {
+//     match shape {
+//         Shape::Rect { first: w, second: h } => w * h,
+//     }
+// }
+// ```
+pub(crate) fn name_variant_fields(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
+    let field_list = match variant.kind() {
+        ast::StructKind::Tuple(it) => it,
+        _ => return None,
+    };
+    let fields = field_list.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let names = (0..fields.len())
+        .map(|idx| ORDINALS.get(idx).map_or_else(|| format!("field{}", idx), |it| it.to_string()))
+        .collect::<Vec<_>>();
+    let variant_name = variant.name()?.text().to_string();
+    let enum_def = variant.syntax().ancestors().find_map(ast::EnumDef::cast)?;
+    let enum_name = enum_def.name()?;
+
+    let file_id = ctx.frange.file_id.into();
+    let def = ctx.source_binder().to_def(InFile::new(file_id, variant.clone()))?;
+    // Uses in other files couldn't be updated.
+    if enum_def.visibility().is_some() || !is_local_module(&ctx, def.module(ctx.db)) {
+        return None;
+    }
+    let root = variant.syntax().ancestors().last()?;
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    for path in root.descendants().filter_map(ast::Path::cast) {
+        if path.syntax().parent().map_or(false, |it| ast::Path::can_cast(it.kind())) {
+            continue;
+        }
+        let mentions_variant =
+            path.segment().map_or(false, |it| it.syntax().text() == &*variant_name)
+                && path.qualifier().and_then(|it| it.segment()).map_or(false, |it| {
+                    it.syntax().text() == "Self" || it.syntax().text() == enum_name.text().as_str()
+                });
+        let analyzer = ctx.source_analyzer(path.syntax(), None);
+        let is_variant = match analyzer.resolve_path(ctx.db, &path) {
+            Some(PathResolution::Def(ModuleDef::EnumVariant(it))) => it == def,
+            Some(_) => false,
+            // `Self::Variant` isn't resolved.
+            None => mentions_variant,
+        };
+        if !is_variant {
+            if mentions_variant {
+                return None;
+            }
+            continue;
+        }
+        let parent = path.syntax().parent()?;
+        if let Some(pat) = ast::TupleStructPat::cast(parent.clone()) {
+            edits.push((pat.syntax().text_range(), rewrite_pattern(&pat, &path, &names)?));
+            continue;
+        }
+        // Only calls can be rewritten, not the constructor used as a function.
+        let call = ast::PathExpr::cast(parent)
+            .and_then(|it| it.syntax().parent())
+            .and_then(ast::CallExpr::cast)?;
+        let args = call.arg_list()?.args().collect::<Vec<_>>();
+        if args.len() != names.len() {
+            return None;
+        }
+        let fields = names
+            .iter()
+            .zip(args.iter())
+            .map(|(name, arg)| field_text(name, &arg.syntax().to_string()))
+            .collect::<Vec<_>>();
+        edits.push((
+            call.syntax().text_range(),
+            format!("{} {{ {} }}", path.syntax(), fields.join(", ")),
+        ));
+    }
+    let is_nested = edits
+        .iter()
+        .any(|(range, _)| edits.iter().any(|(it, _)| it != range && it.is_subrange(range)));
+    if is_nested {
+        return None;
+    }
+    let mut defs = Vec::new();
+    for (field, name) in fields.iter().zip(names.iter()) {
+        let type_ref = field.type_ref()?;
+        // Attributes stay in front of the name.
+        let prefix_len =
+            type_ref.syntax().text_range().start() - field.syntax().text_range().start();
+        let text = field.syntax().text().to_string();
+        defs.push(format!("{}{}: {}", &text[..prefix_len.to_usize()], name, type_ref.syntax()));
+    }
+
+    ctx.add_assist(AssistId("name_variant_fields"), "Name variant fields", |edit| {
+        edit.target(variant.syntax().text_range());
+        edit.replace(field_list.syntax().text_range(), format!(" {{ {} }}", defs.join(", ")));
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+    })
+}
+
+fn rewrite_pattern(
+    pat: &ast::TupleStructPat,
+    path: &ast::Path,
+    names: &[String],
+) -> Option<String> {
+    let args = pat.args().collect::<Vec<_>>();
+    let rest = args.iter().position(|it| match it {
+        ast::Pat::DotDotPat(_) => true,
+        _ => false,
+    });
+    let mut fields = Vec::new();
+    match rest {
+        Some(rest) => {
+            let suffix = args.len() - rest - 1;
+            if rest + suffix > names.len() {
+                return None;
+            }
+            for (name, arg) in names[..rest].iter().zip(&args[..rest]) {
+                fields.push(field_text(name, &arg.syntax().to_string()));
+            }
+            for (name, arg) in names[names.len() - suffix..].iter().zip(&args[rest + 1..]) {
+                fields.push(field_text(name, &arg.syntax().to_string()));
+            }
+            fields.push("..".to_string());
+        }
+        None => {
+            if args.len() != names.len() {
+                return None;
+            }
+            for (name, arg) in names.iter().zip(args.iter()) {
+                fields.push(field_text(name, &arg.syntax().to_string()));
+            }
+        }
+    }
+    Some(format!("{} {{ {} }}", path.syntax(), fields.join(", ")))
+}
+
+fn field_text(name: &str, value: &str) -> String {
+    if name == value {
+        name.to_string()
+    } else {
+        format!("{}: {}", name, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn name_variant_fields_with_constructors() {
+        check_assist(
+            name_variant_fields,
+            r#"
+enum Expr {
+    Lit(i64),
+    <|>Bin(Box<Expr>, char, Box<Expr>),
+}
+
+impl Expr {
+    fn add(first: Expr, rhs: Expr) -> Expr {
+        Self::Bin(Box::new(first), '+', Box::new(rhs))
+    }
+    fn op(&self) -> Option<char> {
+        match self {
+            Expr::Bin(_, op, ..) => Some(*op),
+            Expr::Bin(.., third) => None,
+            Expr::Lit(_) => None,
+        }
+    }
+}
+"#,
+            r#"
+enum Expr {
+    Lit(i64),
+    <|>Bin { first: Box<Expr>, second: char, third: Box<Expr> },
+}
+
+impl Expr {
+    fn add(first: Expr, rhs: Expr) -> Expr {
+        Self::Bin { first: Box::new(first), second: '+', third: Box::new(rhs) }
+    }
+    fn op(&self) -> Option<char> {
+        match self {
+            Expr::Bin { first: _, second: op, .. } => Some(*op),
+            Expr::Bin { third, .. } => None,
+            Expr::Lit(_) => None,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn name_variant_fields_target() {
+        check_assist_target(name_variant_fields, "enum E { <|>A(u8), B }", "A(u8)");
+    }
+
+    #[test]
+    fn name_variant_fields_not_applicable() {
+        check_assist_not_applicable(name_variant_fields, "enum E { <|>A { x: u8 }, B }");
+        // The constructor is used as a function.
+        check_assist_not_applicable(
+            name_variant_fields,
+            "enum E { <|>A(u8), B }\nfn f(v: Vec<u8>) { v.into_iter().map(E::A); }",
+        );
+        // Nested constructions.
+        check_assist_not_applicable(
+            name_variant_fields,
+            "enum E { <|>A(Box<E>), B }\nfn f() { E::A(Box::new(E::A(Box::new(E::B)))); }",
+        );
+        // Visible in other files.
+        check_assist_not_applicable(name_variant_fields, "pub enum E { <|>A(u8), B }");
+    }
+
+    #[test]
+    fn name_variant_fields_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            name_variant_fields,
+            r#"
+//- /main.rs
+mod tests;
+enum E { <|>A(u8), B }
+
+//- /tests.rs
+fn f() -> super::E { super::E::A(0) }
+"#,
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, DocCommentsOwner, ModuleItemOwner, NameOwner},
+    SourceFile,
+    SyntaxKind::TRY_EXPR,
+    SyntaxNode, TextUnit,
+};
+
+use crate::{utils::is_test_module, Assist, AssistCtx, AssistId};
+
+// Assist: promote_doc_example
+//
+// Copies the code of the `# Examples` section of a function's documentation
+// into a unit test.
+//
+// ```
+// /// Adds one.
+// ///
+// /// # Examples
+// ///
+// /// ```
+// /// assert_eq!(add_one(1), 2);
+// /// ```
+// fn <|>add_one(x: u32) -> u32 { x + 1 }
+// ```
+// ->
+// ```
+// /// Adds one.
+// ///
+// /// # Examples
+// ///
+// /// ```
+// /// assert_eq!(add_one(1), 2);
+// /// ```
+// fn add_one(x: u32) -> u32 { x + 1 }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn add_one_example() {
+//         assert_eq!(add_one(1), 2);
+//     }
+// }
+// ```
+pub(crate) fn promote_doc_example(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let example = DocExample::parse(&fn_def.doc_comment_text()?)?;
+    let fn_name = fn_def.name()?;
+
+    // The tests go next to the item containing the function.
+    let (container, items) = fn_def.syntax().ancestors().skip(1).find_map(|it| {
+        if let Some(file) = ast::SourceFile::cast(it.clone()) {
+            return Some((it, file.items().collect::<Vec<_>>()));
+        }
+        let list = ast::ItemList::cast(it.clone())?;
+        ast::Module::cast(list.syntax().parent()?)?;
+        Some((it, list.items().collect::<Vec<_>>()))
+    })?;
+    let tests = items.iter().find_map(|it| match it {
+        ast::ModuleItem::Module(it) if is_test_module(it) => it.item_list(),
+        _ => None,
+    });
+    let existing = tests.as_ref().map_or_else(Vec::new, |it| {
+        it.items()
+            .filter_map(|it| match it {
+                ast::ModuleItem::FnDef(it) => it.name().map(|it| it.text().to_string()),
+                _ => None,
+            })
+            .collect()
+    });
+    let mut test_name = format!("{}_example", fn_name.text());
+    let mut idx = 1;
+    while existing.contains(&test_name) {
+        idx += 1;
+        test_name = format!("{}_example_{}", fn_name.text(), idx);
+    }
+
+    ctx.add_assist(AssistId("promote_doc_example"), "Promote doc example to test", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        let (offset, prefix, test, suffix) = match &tests {
+            Some(list) => {
+                let indent = leading_indent(list.syntax()).unwrap_or_default().to_string();
+                let inner = format!("{}    ", indent);
+                match list.items().last() {
+                    Some(last) => {
+                        let offset = last.syntax().text_range().end();
+                        (
+                            offset,
+                            format!("\n\n{}", inner),
+                            example.to_test(&test_name, &inner),
+                            String::new(),
+                        )
+                    }
+                    None => {
+                        let offset = list.syntax().text_range().start() + TextUnit::of_char('{');
+                        let suffix = format!("\n{}", indent);
+                        (
+                            offset,
+                            format!("\n{}", inner),
+                            example.to_test(&test_name, &inner),
+                            suffix,
+                        )
+                    }
+                }
+            }
+            None => {
+                let indent = match ast::SourceFile::cast(container.clone()) {
+                    Some(_) => String::new(),
+                    None => leading_indent(fn_def.syntax()).unwrap_or_default().to_string(),
+                };
+                let inner = format!("{}    ", indent);
+                let prefix = format!(
+                    "\n\n{}#[cfg(test)]\n{}mod tests {{\n{}use super::*;\n\n{}",
+                    indent, indent, inner, inner
+                );
+                let offset = items.last().map_or(fn_def.syntax(), |it| it.syntax());
+                let offset = offset.text_range().end();
+                (offset, prefix, example.to_test(&test_name, &inner), format!("\n{}}}", indent))
+            }
+        };
+        let (test, name_offset) = test;
+        let cursor = offset + TextUnit::of_str(&prefix) + name_offset;
+        edit.insert(offset, format!("{}{}{}", prefix, test, suffix));
+        edit.set_cursor(cursor);
+    })
+}
+
+/// The code of the first example in a doc comment.
+struct DocExample {
+    lines: Vec<String>,
+    should_panic: bool,
+}
+
+impl DocExample {
+    fn parse(docs: &str) -> Option<DocExample> {
+        let mut lines = docs.lines().skip_while(|it| !is_examples_heading(it));
+        let fence = lines.find(|it| it.trim_start().starts_with("```"))?;
+        let mut should_panic = false;
+        for attr in fence.trim_start()[3..].split(',').map(|it| it.trim()) {
+            match attr {
+                "" | "rust" | "no_run" | "edition2015" | "edition2018" => (),
+                "should_panic" => should_panic = true,
+                _ => return None,
+            }
+        }
+        let mut code = Vec::new();
+        for line in lines {
+            let line = line.trim_start();
+            if line.starts_with("```") {
+                if code.iter().all(|it: &String| it.is_empty()) {
+                    return None;
+                }
+                return Some(DocExample { lines: code, should_panic });
+            }
+            // Hidden lines are part of the test too.
+            let line = if line == "#" {
+                ""
+            } else if line.starts_with("# ") || line.starts_with("##") {
+                &line[2..]
+            } else {
+                line
+            };
+            code.push(line.trim_end().to_string());
+        }
+        None
+    }
+
+    /// Returns the text of the test, with the offset of its name.
+    fn to_test(&self, name: &str, indent: &str) -> (String, TextUnit) {
+        let mut lines = self.lines.clone();
+        while lines.last().map_or(false, |it| it.is_empty()) {
+            lines.pop();
+        }
+        while lines.first().map_or(false, |it| it.is_empty()) {
+            lines.remove(0);
+        }
+        // Examples using `?` have to return a `Result`, which is usually
+        // produced by a hidden last line.
+        let returns_result = parse_block(&lines.join("\n"))
+            .map_or(false, |it| it.descendants().any(|it| it.kind() == TRY_EXPR));
+        if returns_result {
+            if lines.last().map_or(false, |it| it.starts_with("Ok(") || it.starts_with("Ok::<")) {
+                lines.pop();
+            }
+            lines.push("Ok(())".to_string());
+        }
+
+        let mut res = String::new();
+        res.push_str("#[test]\n");
+        if self.should_panic {
+            res.push_str(&format!("{}#[should_panic]\n", indent));
+        }
+        res.push_str(&format!("{}fn ", indent));
+        let name_offset = TextUnit::of_str(&res);
+        res.push_str(name);
+        res.push_str("()");
+        if returns_result {
+            res.push_str(" -> Result<(), Box<dyn std::error::Error>>");
+        }
+        res.push_str(" {\n");
+        for line in lines {
+            if !line.is_empty() {
+                res.push_str(&format!("{}    {}", indent, line));
+            }
+            res.push('\n');
+        }
+        res.push_str(&format!("{}}}", indent));
+        (res, name_offset)
+    }
+}
+
+fn is_examples_heading(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('#') && {
+        let title = line.trim_start_matches('#').trim();
+        title == "Examples" || title == "Example"
+    }
+}
+
+fn parse_block(code: &str) -> Option<SyntaxNode> {
+    let file = SourceFile::parse(&format!("fn f() {{\n{}\n}}", code)).ok().ok()?;
+    Some(file.syntax().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn promote_doc_example_into_existing_tests() {
+        check_assist(
+            promote_doc_example,
+            r#"
+/// # Examples
+///
+/// ```rust
+/// # use crate::parse;
+/// let x = parse("1")?;
+/// assert_eq!(x, 1);
+/// # Ok::<(), Error>(())
+/// ```
+fn <|>parse(s: &str) -> Result<u32, Error> { todo!() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example() {}
+}
+"#,
+            r#"
+/// # Examples
+///
+/// ```rust
+/// # use crate::parse;
+/// let x = parse("1")?;
+/// assert_eq!(x, 1);
+/// # Ok::<(), Error>(())
+/// ```
+fn parse(s: &str) -> Result<u32, Error> { todo!() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example() {}
+
+    #[test]
+    fn <|>parse_example_2() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::parse;
+        let x = parse("1")?;
+        assert_eq!(x, 1);
+        Ok(())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn promote_doc_example_in_module() {
+        check_assist(
+            promote_doc_example,
+            r#"
+mod m {
+    /// Panics on zero.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// check(0);
+    /// ```
+    pub fn check<|>(x: u32) {}
+}
+"#,
+            r#"
+mod m {
+    /// Panics on zero.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// check(0);
+    /// ```
+    pub fn check(x: u32) {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        #[should_panic]
+        fn <|>check_example() {
+            check(0);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn promote_doc_example_target() {
+        check_assist_target(
+            promote_doc_example,
+            "/// # Examples\n/// ```\n/// f();\n/// ```\nfn <|>f() {}",
+            "/// # Examples\n/// ```\n/// f();\n/// ```\nfn f() {}",
+        );
+    }
+
+    #[test]
+    fn promote_doc_example_not_applicable() {
+        check_assist_not_applicable(promote_doc_example, "/// Does nothing.\nfn <|>f() {}");
+        check_assist_not_applicable(
+            promote_doc_example,
+            "/// ```\n/// f();\n/// ```\nfn <|>f() {}",
+        );
+        check_assist_not_applicable(
+            promote_doc_example,
+            "/// # Examples\n/// ```ignore\n/// f();\n/// ```\nfn <|>f() {}",
+        );
+        check_assist_not_applicable(
+            promote_doc_example,
+            "/// # Examples\n/// ```text\n/// f\n/// ```\nfn <|>f() {}",
+        );
+    }
+}
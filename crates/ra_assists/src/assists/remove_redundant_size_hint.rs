@@ -0,0 +1,132 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+
+use crate::{
+    utils::{path_name, single_expr},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: remove_redundant_size_hint
+//
+// Removes an implementation of `Iterator::size_hint` which returns the same
+// bounds as the default one.
+//
+// ```
+// impl Iterator for Lines {
+//     type Item = String;
+//
+//     fn next(&mut self) -> Option<String> { self.read_line() }
+//
+//     fn <|>size_hint(&self) -> (usize, Option<usize>) {
+//         (0, None)
+//     }
+// }
+// ```
+// ->
+// ```
+// impl Iterator for Lines {
+//     type Item = String;
+//
+//     fn next(&mut self) -> Option<String> { self.read_line() }
+// }
+// ```
+pub(crate) fn remove_redundant_size_hint(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if fn_def.name()?.text() != "size_hint" {
+        return None;
+    }
+    let impl_block = ast::ImplBlock::cast(fn_def.syntax().parent()?.parent()?)?;
+    if path_name(&impl_block.target_trait()?)?.as_str() != "Iterator" {
+        return None;
+    }
+    // The default returns `(0, None)`.
+    let bounds = match single_expr(&fn_def.body()?)? {
+        ast::Expr::TupleExpr(it) => it.exprs().collect::<Vec<_>>(),
+        _ => return None,
+    };
+    let is_default = match bounds.as_slice() {
+        [ast::Expr::Literal(lower), ast::Expr::PathExpr(upper)] => {
+            let lower = lower.syntax().text();
+            let upper = upper.syntax().text();
+            (lower == "0" || lower == "0usize") && (upper == "None" || upper == "Option::None")
+        }
+        _ => false,
+    };
+    if !is_default {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("remove_redundant_size_hint"), "Remove redundant size_hint", |edit| {
+        let mut range = fn_def.syntax().text_range();
+        // The first item keeps the whitespace in front of it.
+        let whitespace = if fn_def.syntax().prev_sibling().is_some() {
+            fn_def.syntax().prev_sibling_or_token()
+        } else {
+            fn_def.syntax().next_sibling_or_token()
+        };
+        if let Some(ws) = whitespace.filter(|it| it.kind() == WHITESPACE) {
+            range = TextRange::from_to(
+                range.start().min(ws.text_range().start()),
+                range.end().max(ws.text_range().end()),
+            );
+        }
+        edit.target(fn_def.syntax().text_range());
+        edit.delete(range);
+        edit.set_cursor(range.start());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn remove_redundant_size_hint_first_item() {
+        check_assist(
+            remove_redundant_size_hint,
+            r#"
+impl Iterator for I {
+    /// Docs.
+    fn size_hint(&self) -> (usize, Option<usize>) { (0usize, <|>Option::None) }
+
+    fn next(&mut self) -> Option<u8> { None }
+}
+"#,
+            r#"
+impl Iterator for I {
+    <|>fn next(&mut self) -> Option<u8> { None }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_redundant_size_hint_target() {
+        check_assist_target(
+            remove_redundant_size_hint,
+            "impl Iterator for I { fn <|>size_hint(&self) -> (usize, Option<usize>) { (0, None) } }",
+            "fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }",
+        );
+    }
+
+    #[test]
+    fn remove_redundant_size_hint_not_applicable() {
+        check_assist_not_applicable(
+            remove_redundant_size_hint,
+            "impl Iterator for I { fn <|>size_hint(&self) -> (usize, Option<usize>) { (0, Some(0)) } }",
+        );
+        check_assist_not_applicable(
+            remove_redundant_size_hint,
+            "impl Iterator for I { fn <|>size_hint(&self) -> (usize, Option<usize>) { (self.n, None) } }",
+        );
+        check_assist_not_applicable(
+            remove_redundant_size_hint,
+            "impl Stream for I { fn <|>size_hint(&self) -> (usize, Option<usize>) { (0, None) } }",
+        );
+    }
+}
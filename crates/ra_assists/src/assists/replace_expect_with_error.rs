@@ -0,0 +1,270 @@
+use hir::{db::HirDatabase, Adt, HasSource, ImplBlock, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode},
+    TextRange,
+};
+
+use crate::{utils::result_error_type, Assist, AssistCtx, AssistId};
+
+// Assist: replace_expect_with_error
+//
+// Replaces a panicking `expect` with returning the message as an error from
+// the enclosing function.
+//
+// ```
+// # enum Option<T> { Some(T), None }
+// #
+// fn lookup(key: &str) -> Option<u32> { None }
+//
+// fn get(key: &str) -> Result<u32, String> {
+//     let value = lookup(key).<|>expect("missing key");
+//     Ok(value)
+// }
+// ```
+// ->
+// ```
+// # enum Option<T> { Some(T), None }
+// #
+// fn lookup(key: &str) -> Option<u32> { None }
+//
+// fn get(key: &str) -> Result<u32, String> {
+//     let value = lookup(key).ok_or_else(|| String::from("missing key"))?;
+//     Ok(value)
+// }
+// ```
+pub(crate) fn replace_expect_with_error(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    if name_ref.text().as_str() != "expect" {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let message = match (args.next()?, args.next()) {
+        (ast::Expr::Literal(it), None) => it.syntax().to_string(),
+        _ => return None,
+    };
+    if message.starts_with('b') || !message.ends_with('"') {
+        return None;
+    }
+    let receiver = call.expr()?;
+
+    // Closures have a return type of their own.
+    let fn_def = call.syntax().ancestors().find_map(|it| {
+        if ast::LambdaExpr::can_cast(it.kind()) {
+            Some(None)
+        } else {
+            ast::FnDef::cast(it).map(Some)
+        }
+    })??;
+    let error_type = result_error_type(&fn_def.ret_type()?.type_ref()?)?;
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    let receiver_ty = analyzer.type_of(db, &receiver)?;
+    let conversion = match receiver_ty.as_adt() {
+        Some(Adt::Enum(it)) => match it.name(db).to_string().as_str() {
+            "Option" => "ok_or_else(||",
+            "Result" => "map_err(|_|",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let error = match error_conversion(&ctx, &error_type)? {
+        Conversion::FromStr => format!("{}::from({})", type_prefix(&error_type), message),
+        Conversion::FromString => {
+            format!("{}::from({}.to_string())", type_prefix(&error_type), message)
+        }
+    };
+
+    ctx.add_assist(AssistId("replace_expect_with_error"), "Return error instead", |edit| {
+        let range = TextRange::from_to(
+            name_ref.syntax().text_range().start(),
+            call.syntax().text_range().end(),
+        );
+        edit.target(call.syntax().text_range());
+        edit.replace(range, format!("{} {})?", conversion, error));
+        edit.set_cursor(range.start());
+    })
+}
+
+enum Conversion {
+    FromStr,
+    FromString,
+}
+
+/// Finds out how to build the error type from a message.
+fn error_conversion(
+    ctx: &AssistCtx<impl HirDatabase>,
+    error_type: &ast::TypeRef,
+) -> Option<Conversion> {
+    let text = error_type.syntax().text().to_string();
+    let text = text.split_whitespace().collect::<String>();
+    if text == "String" || (text.starts_with("Box<dyn") && text.contains("Error")) {
+        return Some(Conversion::FromStr);
+    }
+
+    let db = ctx.db;
+    let path = match error_type {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let analyzer = ctx.source_analyzer(error_type.syntax(), None);
+    let adt = match analyzer.resolve_path(db, &path)? {
+        PathResolution::Def(ModuleDef::Adt(it)) => it,
+        _ => return None,
+    };
+    let krate = analyzer.module()?.krate();
+    let mut res = None;
+    for impl_block in ImplBlock::all_in_crate(db, krate) {
+        if impl_block.target_ty(db).as_adt() != Some(adt) {
+            continue;
+        }
+        let trait_ref = match impl_block.source(db).value.target_trait() {
+            Some(it) => it.syntax().text().to_string().split_whitespace().collect::<String>(),
+            None => continue,
+        };
+        let from_arg = match trait_ref.rfind("From<") {
+            Some(idx) if idx == 0 || trait_ref[..idx].ends_with("::") => &trait_ref[idx..],
+            _ => continue,
+        };
+        match from_arg {
+            "From<&str>" | "From<&'staticstr>" => return Some(Conversion::FromStr),
+            "From<String>" => res = Some(Conversion::FromString),
+            _ => (),
+        }
+    }
+    res
+}
+
+/// Returns the type as it can be written before `::from`.
+fn type_prefix(type_ref: &ast::TypeRef) -> String {
+    let has_generics =
+        type_ref.syntax().descendants().any(|it| ast::TypeArgList::can_cast(it.kind()));
+    match type_ref {
+        ast::TypeRef::PathType(_) if !has_generics => type_ref.syntax().to_string(),
+        _ => format!("<{}>", type_ref.syntax()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const PRELUDE: &str = r#"
+enum Option<T> { Some(T), None }
+enum Result<T, E> { Ok(T), Err(E) }
+fn opt() -> Option<u32> { Option::None }
+fn res() -> Result<u32, ()> { Result::Err(()) }
+"#;
+
+    #[test]
+    fn replace_expect_on_option() {
+        check_assist(
+            replace_expect_with_error,
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Error;
+impl std::convert::From<&'static str> for Error {}
+fn f() -> Result<u32, Error> {
+    let x = opt().expect<|>("no value");
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Error;
+impl std::convert::From<&'static str> for Error {}
+fn f() -> Result<u32, Error> {
+    let x = opt().<|>ok_or_else(|| Error::from("no value"))?;
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_expect_on_result() {
+        check_assist(
+            replace_expect_with_error,
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Error<T>(T);
+impl<T> From<String> for Error<T> {}
+fn f() -> Result<u32, Error<u8>> {
+    let x = res().<|>expect(r"failed");
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Error<T>(T);
+impl<T> From<String> for Error<T> {}
+fn f() -> Result<u32, Error<u8>> {
+    let x = res().<|>map_err(|_| <Error<u8>>::from(r"failed".to_string()))?;
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_expect_with_boxed_error() {
+        check_assist(
+            replace_expect_with_error,
+            &format!("{}{}", PRELUDE, "fn f() -> Result<(), Box<dyn Error>> { <|>opt().expect(\"x\"); }"),
+            &format!(
+                "{}{}",
+                PRELUDE,
+                "fn f() -> Result<(), Box<dyn Error>> { opt().<|>ok_or_else(|| <Box<dyn Error>>::from(\"x\"))?; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_expect_with_error_target() {
+        check_assist_target(
+            replace_expect_with_error,
+            &format!("{}{}", PRELUDE, "fn f() -> Result<(), String> { opt().<|>expect(\"x\"); }"),
+            "opt().expect(\"x\")",
+        );
+    }
+
+    #[test]
+    fn replace_expect_with_error_not_applicable() {
+        // No conversion from a message.
+        check_assist_not_applicable(
+            replace_expect_with_error,
+            &format!(
+                "{}{}",
+                PRELUDE, "struct E; fn f() -> Result<(), E> { opt().<|>expect(\"x\"); }"
+            ),
+        );
+        // Not returning a `Result`.
+        check_assist_not_applicable(
+            replace_expect_with_error,
+            &format!("{}{}", PRELUDE, "fn f() -> u32 { opt().<|>expect(\"x\") }"),
+        );
+        // Inside a closure.
+        check_assist_not_applicable(
+            replace_expect_with_error,
+            &format!(
+                "{}{}",
+                PRELUDE, "fn f() -> Result<(), String> { let g = || opt().<|>expect(\"x\"); }"
+            ),
+        );
+        // Not a literal message.
+        check_assist_not_applicable(
+            replace_expect_with_error,
+            &format!("{}{}", PRELUDE, "fn f() -> Result<(), String> { opt().<|>expect(&m); }"),
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use format_buf::format;
 use hir::db::HirDatabase;
-use ra_fmt::extract_trivial_expression;
-use ra_syntax::{ast, AstNode};
+use ra_fmt::{extract_trivial_expression, leading_indent, reindent};
+use ra_syntax::{ast, AstNode, SyntaxKind::IDENT, TextUnit};
 
 use crate::{Assist, AssistCtx, AssistId};
 
@@ -50,6 +50,79 @@ pub(crate) fn replace_if_let_with_match(ctx: AssistCtx<impl HirDatabase>) -> Opt
     })
 }
 
+// Assist: replace_if_let_ok_with_match
+//
+// Replaces `if let Ok(..)` with an else branch with a `match` expression which
+// binds the error in the else branch.
+//
+// ```
+// fn load(path: &str) -> Config {
+//     <|>if let Ok(text) = read_to_string(path) {
+//         parse(&text)
+//     } else {
+//         Config::default()
+//     }
+// }
+// ```
+// ->
+// ```
+// fn load(path: &str) -> Config {
+//     match read_to_string(path) {
+//         Ok(text) => parse(&text),
+//         Err(e) => Config::default(),
+//     }
+// }
+// ```
+pub(crate) fn replace_if_let_ok_with_match(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
+    let cond = if_expr.condition()?;
+    let pat = match cond.pat()? {
+        ast::Pat::TupleStructPat(it) => it,
+        _ => return None,
+    };
+    if pat.path()?.syntax().text() != "Ok" {
+        return None;
+    }
+    let expr = cond.expr()?;
+    let then_block = if_expr.then_branch()?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => it,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(if_expr.syntax(), None);
+    let ty = analyzer.type_of(db, &expr)?.display(db).to_string();
+    if !ty.starts_with("Result<") && ty != "{unknown}" {
+        return None;
+    }
+    // The error may not shadow anything used in the else branch.
+    let err_name = ["e", "err", "error"].iter().find(|name| {
+        !else_block
+            .syntax()
+            .descendants_with_tokens()
+            .any(|it| it.kind() == IDENT && it.to_string() == **name)
+    })?;
+
+    ctx.add_assist(
+        AssistId("replace_if_let_ok_with_match"),
+        "Convert to match to capture error",
+        |edit| {
+            let indent = leading_indent(if_expr.syntax()).unwrap_or_default();
+            let mut ok_arm = String::new();
+            format!(ok_arm, "match {} {{\n", expr.syntax().text());
+            format!(ok_arm, "    {} => {}\n    ", pat.syntax().text(), format_arm(&then_block));
+            let err_arm = format!("Err({}) => {}\n}}", err_name, format_arm(&else_block));
+            edit.target(if_expr.syntax().text_range());
+            edit.replace_node_and_indent(if_expr.syntax(), format!("{}{}", ok_arm, err_arm));
+            // The cursor goes to the `Err` arm.
+            edit.set_cursor(
+                if_expr.syntax().text_range().start()
+                    + TextUnit::of_str(&reindent(&ok_arm, &indent)),
+            );
+        },
+    )
+}
+
 fn build_match_expr(
     expr: ast::Expr,
     pat1: ast::Pat,
@@ -74,7 +147,7 @@ fn format_arm(block: &ast::BlockExpr) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
     #[test]
     fn test_replace_if_let_with_match_unwraps_simple_expressions() {
@@ -151,4 +224,63 @@ impl VariantData {
         }",
         );
     }
+
+    #[test]
+    fn replace_if_let_ok_with_match_binds_error() {
+        check_assist(
+            replace_if_let_ok_with_match,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn parse(s: &str) -> Result<u32, String> { loop {} }
+fn f(e: u32) -> u32 {
+    if let Ok(n) = <|>parse("1") {
+        n
+    } else {
+        log(e);
+        0
+    }
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn parse(s: &str) -> Result<u32, String> { loop {} }
+fn f(e: u32) -> u32 {
+    match parse("1") {
+        Ok(n) => n,
+        <|>Err(err) => {
+            log(e);
+            0
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_if_let_ok_with_match_target() {
+        check_assist_target(
+            replace_if_let_ok_with_match,
+            "fn f() { <|>if let Ok(n) = g() { n } else { 0 }; }",
+            "if let Ok(n) = g() { n } else { 0 }",
+        );
+    }
+
+    #[test]
+    fn replace_if_let_ok_with_match_not_applicable() {
+        check_assist_not_applicable(
+            replace_if_let_ok_with_match,
+            "fn f(x: Option<u32>) { <|>if let Some(n) = x { n } else { 0 }; }",
+        );
+        check_assist_not_applicable(
+            replace_if_let_ok_with_match,
+            "enum E { Ok(u32) }\nuse E::*;\nfn f(x: E) { <|>if let Ok(n) = x { n } else { 0 }; }",
+        );
+        check_assist_not_applicable(
+            replace_if_let_ok_with_match,
+            "fn f() { <|>if let Ok(n) = g() { n }; }",
+        );
+    }
 }
@@ -0,0 +1,120 @@
+use hir::{db::HirDatabase, HasSource, InFile};
+use ra_syntax::{
+    ast::{self, AstNode},
+    SourceFile, T,
+};
+
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: replace_try_macro
+//
+// Replaces the deprecated `try!` macro with the `?` operator.
+//
+// ```
+// fn read() -> Result<u32, ()> {
+//     let x = <|>try!(parse());
+//     Ok(x)
+// }
+// ```
+// ->
+// ```
+// fn read() -> Result<u32, ()> {
+//     let x = parse()?;
+//     Ok(x)
+// }
+// ```
+pub(crate) fn replace_try_macro(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let path = macro_call.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    match path.segment()?.name_ref()?.text().as_str() {
+        "try" | "r#try" => (),
+        _ => return None,
+    }
+    let token_tree = macro_call.token_tree()?;
+    let l_paren = token_tree.syntax().first_token().filter(|it| it.kind() == T!['('])?;
+    token_tree.syntax().last_token().filter(|it| it.kind() == T![')'])?;
+
+    // A `try!` defined in the current crate may do something else entirely.
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    let analyzer = ctx.source_analyzer(macro_call.syntax(), None);
+    if let Some(def) = analyzer.resolve_macro_call(db, InFile::new(file_id.into(), &macro_call)) {
+        let def_file = def.source(db).file_id.original_file(db);
+        let crates = db.relevant_crates(file_id);
+        if db.relevant_crates(def_file).iter().any(|it| crates.contains(it)) {
+            return None;
+        }
+    }
+
+    let tt_text = token_tree.syntax().text().to_string();
+    let arg = tt_text[l_paren.text().len()..tt_text.len() - 1].trim();
+    let expr = parse_expr(arg)?;
+    let replacement = format!("{}?", ExprPrecedence::Postfix.parenthesize(&expr));
+
+    ctx.add_assist(AssistId("replace_try_macro"), "Replace with ? operator", |edit| {
+        let range = macro_call.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, replacement);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Parses the macro argument, which has to be a single expression.
+fn parse_expr(text: &str) -> Option<ast::Expr> {
+    let file = SourceFile::parse(&format!("fn f() {{ {} }}", text)).ok().ok()?;
+    let block = file.syntax().descendants().find_map(ast::Block::cast)?;
+    if block.statements().next().is_some() {
+        return None;
+    }
+    block.expr().filter(|it| it.syntax().text() == text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn replace_try_macro_with_simple_expr() {
+        check_assist(
+            replace_try_macro,
+            "fn f() { let x = tr<|>y!(foo.bar()).baz(); }",
+            "fn f() { let x = <|>foo.bar()?.baz(); }",
+        );
+    }
+
+    #[test]
+    fn replace_try_macro_with_complex_expr() {
+        check_assist(
+            replace_try_macro,
+            "fn f() { <|>try!(*x); r#try!(a as u32); }",
+            "fn f() { <|>(*x)?; r#try!(a as u32); }",
+        );
+        check_assist(
+            replace_try_macro,
+            "fn f() { try!(*x); <|>r#try!( a as u32 ); }",
+            "fn f() { try!(*x); <|>(a as u32)?; }",
+        );
+    }
+
+    #[test]
+    fn replace_try_macro_target() {
+        check_assist_target(replace_try_macro, "fn f() { <|>try!(x); }", "try!(x)");
+    }
+
+    #[test]
+    fn replace_try_macro_not_applicable() {
+        check_assist_not_applicable(replace_try_macro, "fn f() { <|>dbg!(x); }");
+        check_assist_not_applicable(replace_try_macro, "fn f() { <|>try!(x, y); }");
+        check_assist_not_applicable(
+            replace_try_macro,
+            r#"
+macro_rules! r#try { ($e:expr) => { $e } }
+fn f() { <|>r#try!(x); }
+"#,
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use hir::{db::HirDatabase, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, make, AstNode, NameOwner},
+    SyntaxKind::IDENT,
+    T,
+};
+
+use crate::{utils::field_name, Assist, AssistCtx, AssistId};
+
+// Assist: ignore_unused_fields
+//
+// Replaces the fields a struct pattern binds but never uses with `..`.
+//
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+//
+// fn x(point: Point) -> i32 {
+//     let Point { x, y, z }<|> = point;
+//     x
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+//
+// fn x(point: Point) -> i32 {
+//     let Point { x, .. } = point;
+//     x
+// }
+// ```
+pub(crate) fn ignore_unused_fields(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let record_pat = ctx.find_node_at_offset::<ast::RecordPat>()?;
+    let field_list = record_pat.record_field_pat_list()?;
+    if has_rest(&field_list) {
+        return None;
+    }
+    let fn_def = record_pat.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let analyzer = ctx.source_analyzer(record_pat.syntax(), None);
+    let is_unused = |bind_pat: &ast::BindPat| {
+        let name = match bind_pat.name() {
+            Some(it) => it.text().clone(),
+            None => return false,
+        };
+        // `None` or a constant is matched against rather than bound.
+        let path = make::path_from_name_ref(make::name_ref(&name));
+        match analyzer.resolve_path(ctx.db, &path) {
+            Some(PathResolution::Def(ModuleDef::Const(_)))
+            | Some(PathResolution::Def(ModuleDef::Static(_)))
+            | Some(PathResolution::Def(ModuleDef::EnumVariant(_)))
+            | Some(PathResolution::Def(ModuleDef::Adt(_))) => return false,
+            _ => (),
+        }
+        // Uses inside of macro calls aren't resolved, so any token with the
+        // same name counts as one.
+        let in_macro = fn_def
+            .syntax()
+            .descendants()
+            .filter_map(ast::MacroCall::cast)
+            .filter_map(|it| it.token_tree())
+            .flat_map(|it| it.syntax().descendants_with_tokens())
+            .any(|it| it.kind() == IDENT && it.as_token().map_or(false, |it| *it.text() == name));
+        bind_pat.pat().is_none() && !in_macro && analyzer.find_all_refs(bind_pat).is_empty()
+    };
+
+    let mut kept = Vec::new();
+    for field in field_list.syntax().children() {
+        let unused = if let Some(bind_pat) = ast::BindPat::cast(field.clone()) {
+            is_unused(&bind_pat)
+        } else if let Some(field_pat) = ast::RecordFieldPat::cast(field.clone()) {
+            match field_pat.pat() {
+                Some(ast::Pat::PlaceholderPat(_)) => true,
+                Some(ast::Pat::BindPat(it)) => is_unused(&it),
+                _ => false,
+            }
+        } else {
+            false
+        };
+        if !unused {
+            kept.push(field.to_string());
+        }
+    }
+    if kept.len() == field_list.syntax().children().count() {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("ignore_unused_fields"), "Ignore unused fields", |edit| {
+        edit.target(field_list.syntax().text_range());
+        kept.push("..".to_string());
+        edit.replace(field_list.syntax().text_range(), format!("{{ {} }}", kept.join(", ")));
+        edit.set_cursor(field_list.syntax().text_range().start());
+    })
+}
+
+// Assist: expand_rest_pattern
+//
+// Replaces `..` in a struct pattern with the fields it stands for.
+//
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+//
+// fn x(point: Point) -> i32 {
+//     let Point { x, ..<|> } = point;
+//     x
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+//
+// fn x(point: Point) -> i32 {
+//     let Point { x, y, z } = point;
+//     x
+// }
+// ```
+pub(crate) fn expand_rest_pattern(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let record_pat = ctx.find_node_at_offset::<ast::RecordPat>()?;
+    let field_list = record_pat.record_field_pat_list()?;
+    if !has_rest(&field_list) {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(record_pat.syntax(), None);
+    let variant = analyzer.resolve_record_pattern(&record_pat)?;
+    let listed =
+        field_list.syntax().children().filter_map(|it| field_name(&it)).collect::<Vec<_>>();
+
+    let mut fields = field_list.syntax().children().map(|it| it.to_string()).collect::<Vec<_>>();
+    for field in variant.fields(ctx.db) {
+        let name = field.name(ctx.db).to_string();
+        if listed.contains(&name) {
+            continue;
+        }
+        if name.chars().all(|it| it.is_ascii_digit()) {
+            fields.push(format!("{}: _", name));
+        } else {
+            fields.push(name);
+        }
+    }
+
+    ctx.add_assist(AssistId("expand_rest_pattern"), "Name all fields", |edit| {
+        edit.target(field_list.syntax().text_range());
+        let text = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        };
+        edit.replace(field_list.syntax().text_range(), text);
+        edit.set_cursor(field_list.syntax().text_range().start());
+    })
+}
+
+fn has_rest(field_list: &ast::RecordFieldPatList) -> bool {
+    field_list.syntax().children_with_tokens().any(|it| it.kind() == T![..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn ignore_unused_fields_in_match() {
+        check_assist(
+            ignore_unused_fields,
+            r#"
+enum Shape { Circle { r: f64 }, Rect { w: f64, h: f64, name: String } }
+fn area(shape: Shape) -> f64 {
+    match shape {
+        Shape::Circle { r } => r * r,
+        Shape::Rect { w, h: _, <|>name: label, } => { println!("{}", w); 0.0 }
+    }
+}
+"#,
+            r#"
+enum Shape { Circle { r: f64 }, Rect { w: f64, h: f64, name: String } }
+fn area(shape: Shape) -> f64 {
+    match shape {
+        Shape::Circle { r } => r * r,
+        Shape::Rect <|>{ w, .. } => { println!("{}", w); 0.0 }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_all_fields() {
+        check_assist(
+            ignore_unused_fields,
+            "struct S { a: u8, b: u8 }\nfn f(s: S) { let S { <|>a, mut b } = s; }",
+            "struct S { a: u8, b: u8 }\nfn f(s: S) { let S <|>{ .. } = s; }",
+        );
+    }
+
+    #[test]
+    fn ignore_unused_fields_target() {
+        check_assist_target(
+            ignore_unused_fields,
+            "struct S { a: u8, b: u8 }\nfn f(s: S) -> u8 { let S { <|>a, b } = s; a }",
+            "{ a, b }",
+        );
+    }
+
+    #[test]
+    fn ignore_unused_fields_not_applicable() {
+        // All used.
+        check_assist_not_applicable(
+            ignore_unused_fields,
+            "struct S { a: u8, b: u8 }\nfn f(s: S) -> u8 { let S { <|>a, b } = s; a + b }",
+        );
+        // Already has a rest pattern.
+        check_assist_not_applicable(
+            ignore_unused_fields,
+            "struct S { a: u8, b: u8 }\nfn f(s: S) { let S { <|>a, .. } = s; }",
+        );
+        // Nested patterns constrain the match.
+        check_assist_not_applicable(
+            ignore_unused_fields,
+            "const ZERO: u8 = 0;\nstruct S { a: u8, b: u8 }\nfn f(s: S) -> u8 { match s { S { <|>a, b: ZERO } => a, _ => 0 } }",
+        );
+    }
+
+    #[test]
+    fn expand_rest_pattern_with_missing_fields() {
+        check_assist(
+            expand_rest_pattern,
+            "struct S { a: u8, b: u8, c: u8 }\nfn f(s: S) { let S { b: x, <|>.. } = s; }",
+            "struct S { a: u8, b: u8, c: u8 }\nfn f(s: S) { let S <|>{ b: x, a, c } = s; }",
+        );
+    }
+
+    #[test]
+    fn expand_rest_pattern_with_all_fields_listed() {
+        check_assist(
+            expand_rest_pattern,
+            "struct S { a: u8 }\nfn f(s: S) { let S { a, ..<|> } = s; }",
+            "struct S { a: u8 }\nfn f(s: S) { let S <|>{ a } = s; }",
+        );
+    }
+
+    #[test]
+    fn expand_rest_pattern_not_applicable() {
+        check_assist_not_applicable(
+            expand_rest_pattern,
+            "struct S { a: u8 }\nfn f(s: S) { let S { <|>a } = s; }",
+        );
+    }
+}
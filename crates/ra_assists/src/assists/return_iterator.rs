@@ -0,0 +1,229 @@
+use either::Either;
+use hir::{db::HirDatabase, PathResolution};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{
+    utils::{return_positions, single_type_arg},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: return_iterator
+//
+// Returns an iterator from a function which collects one into a `Vec`, to
+// avoid the allocation.
+//
+// ```
+// fn squares(n: u32) -> Vec<u32><|> {
+//     (0..n).map(|x| x * x).collect()
+// }
+// ```
+// ->
+// ```
+// fn squares(n: u32) -> impl Iterator<Item = u32> {
+//     (0..n).map(|x| x * x)
+// }
+// ```
+pub(crate) fn return_iterator(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let fn_def = ast::FnDef::cast(ret_type.syntax().parent()?)?;
+    let type_ref = ret_type.type_ref()?;
+    let item = single_type_arg(&type_ref, "Vec")?;
+    // Borrowed parameters would have to outlive the iterator.
+    let param_list = fn_def.param_list()?;
+    let is_ref = |it: Option<ast::TypeRef>| match it {
+        Some(ast::TypeRef::ReferenceType(_)) => true,
+        _ => false,
+    };
+    if let Some(self_param) = param_list.self_param() {
+        if self_param.kind() != ast::SelfParamKind::Owned || is_ref(self_param.ascribed_type()) {
+            return None;
+        }
+    }
+    if param_list.params().any(|it| is_ref(it.ascribed_type())) {
+        return None;
+    }
+
+    let body = fn_def.body()?;
+    let block = body.block()?;
+    let tail = block.expr()?;
+    let positions = return_positions(&body);
+    if positions.len() != 1 || positions[0] != tail {
+        return None;
+    }
+    // Either `iter.collect()`, or a variable initialized with it.
+    let (replaced, collect_call) = match &tail {
+        ast::Expr::MethodCallExpr(it) => (tail.syntax().text_range(), it.clone()),
+        ast::Expr::PathExpr(path_expr) => {
+            let let_stmt = match block.statements().last()? {
+                ast::Stmt::LetStmt(it) => it,
+                _ => return None,
+            };
+            let bind_pat = match let_stmt.pat()? {
+                ast::Pat::BindPat(it) => it,
+                _ => return None,
+            };
+            if path_expr.syntax().text() != bind_pat.name()?.text().as_str() {
+                return None;
+            }
+            let analyzer = ctx.source_analyzer(block.syntax(), None);
+            if analyzer.find_all_refs(&bind_pat).len() != 1 {
+                return None;
+            }
+            let call = match let_stmt.initializer()? {
+                ast::Expr::MethodCallExpr(it) => it,
+                _ => return None,
+            };
+            let range = TextRange::from_to(
+                let_stmt.syntax().text_range().start(),
+                tail.syntax().text_range().end(),
+            );
+            (range, call)
+        }
+        _ => return None,
+    };
+    if collect_call.name_ref()?.text().as_str() != "collect"
+        || collect_call.arg_list()?.args().next().is_some()
+    {
+        return None;
+    }
+    let iter = collect_call.expr()?;
+    if !is_owned_iterator(&iter) || captures_locals(&ctx, &iter) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("return_iterator"), "Return iterator instead", |edit| {
+        edit.target(type_ref.syntax().text_range());
+        edit.replace(
+            type_ref.syntax().text_range(),
+            format!("impl Iterator<Item = {}>", item.syntax()),
+        );
+        edit.replace(replaced, iter.syntax().to_string());
+    })
+}
+
+/// Whether the iterator owns what it iterates over, starting from a range or
+/// from `into_iter`.
+fn is_owned_iterator(iter: &ast::Expr) -> bool {
+    let mut expr = iter.clone();
+    let mut first_method = None;
+    while let ast::Expr::MethodCallExpr(call) = &expr {
+        first_method = call.name_ref();
+        expr = match call.expr() {
+            Some(it) => it,
+            None => return false,
+        };
+    }
+    while let ast::Expr::ParenExpr(it) = &expr {
+        expr = match it.expr() {
+            Some(it) => it,
+            None => return false,
+        };
+    }
+    match expr {
+        ast::Expr::RangeExpr(_) => true,
+        _ => first_method.map_or(false, |it| it.text().as_str() == "into_iter"),
+    }
+}
+
+/// Whether a closure of the iterator uses a local variable of the function,
+/// which would be borrowed.
+fn captures_locals(ctx: &AssistCtx<impl HirDatabase>, iter: &ast::Expr) -> bool {
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(iter.syntax(), None);
+    iter.syntax().descendants().filter_map(ast::LambdaExpr::cast).any(|lambda| {
+        let range = lambda.syntax().text_range();
+        lambda.syntax().descendants().filter_map(ast::PathExpr::cast).any(|path_expr| {
+            let local = match path_expr.path().and_then(|it| analyzer.resolve_path(db, &it)) {
+                Some(PathResolution::Local(it)) => it,
+                _ => return false,
+            };
+            match local.source(db).value {
+                Either::Left(it) => !it.syntax().text_range().is_subrange(&range),
+                Either::Right(_) => true,
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn return_iterator_from_variable() {
+        check_assist(
+            return_iterator,
+            r#"
+fn names(people: Vec<Person>) -> <|>Vec<String> {
+    let names: Vec<String> = people
+        .into_iter()
+        .filter(|p| p.active)
+        .map(|p| p.name)
+        .collect();
+    names
+}
+"#,
+            r#"
+fn names(people: Vec<Person>) -> <|>impl Iterator<Item = String> {
+    people
+        .into_iter()
+        .filter(|p| p.active)
+        .map(|p| p.name)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn return_iterator_from_range() {
+        check_assist(
+            return_iterator,
+            "fn f() -> Vec<u8><|> { (0..10).filter(|x| x % 2 == 0).collect::<Vec<_>>() }",
+            "fn f() -> impl Iterator<Item = u8><|> { (0..10).filter(|x| x % 2 == 0) }",
+        );
+    }
+
+    #[test]
+    fn return_iterator_target() {
+        check_assist_target(
+            return_iterator,
+            "fn f() -> <|>Vec<u8> { (0..10).collect() }",
+            "Vec<u8>",
+        );
+    }
+
+    #[test]
+    fn return_iterator_not_applicable() {
+        // Borrows from a parameter.
+        check_assist_not_applicable(
+            return_iterator,
+            "fn f(v: &Vec<u8>) -> <|>Vec<u8> { v.into_iter().cloned().collect() }",
+        );
+        check_assist_not_applicable(
+            return_iterator,
+            "fn f(v: Vec<u8>) -> <|>Vec<u8> { v.iter().cloned().collect() }",
+        );
+        check_assist_not_applicable(
+            return_iterator,
+            "impl S { fn f(&self) -> <|>Vec<u8> { (0..self.n).collect() } }",
+        );
+        // Borrows a local.
+        check_assist_not_applicable(
+            return_iterator,
+            "fn f(n: u8) -> <|>Vec<u8> { (0..10).map(|x| x + n).collect() }",
+        );
+        // Used otherwise.
+        check_assist_not_applicable(
+            return_iterator,
+            "fn f() -> <|>Vec<u8> { let mut v: Vec<u8> = (0..1).collect(); v.push(1); v }",
+        );
+        check_assist_not_applicable(
+            return_iterator,
+            "fn f(b: bool) -> <|>Vec<u8> { if b { return Vec::new(); } (0..1).collect() }",
+        );
+    }
+}
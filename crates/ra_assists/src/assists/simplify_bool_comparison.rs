@@ -0,0 +1,121 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode};
+
+use super::invert_if::invert_boolean_expression;
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: simplify_bool_comparison
+//
+// Removes the comparison of a boolean to a literal.
+//
+// ```
+// fn main() {
+//     let done = false;
+//     if done <|>== false {}
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let done = false;
+//     if !done {}
+// }
+// ```
+pub(crate) fn simplify_bool_comparison(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bin_expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
+    let equal = match bin_expr.op_kind()? {
+        ast::BinOp::EqualityTest => true,
+        ast::BinOp::NegatedEqualityTest => false,
+        _ => return None,
+    };
+    let (lhs, rhs) = (bin_expr.lhs()?, bin_expr.rhs()?);
+    let (operand, value) = match (bool_literal(&lhs), bool_literal(&rhs)) {
+        (None, Some(value)) => (lhs, value),
+        (Some(value), None) => (rhs, value),
+        _ => return None,
+    };
+    let analyzer = ctx.source_analyzer(bin_expr.syntax(), None);
+    if !analyzer.type_of(ctx.db, &operand)?.is_bool() {
+        return None;
+    }
+
+    let (text, precedence) = if value == equal {
+        (operand.syntax().to_string(), ExprPrecedence::of(&operand))
+    } else {
+        match invert_boolean_expression(&operand) {
+            Some(it) => (it.syntax().to_string(), ExprPrecedence::of(&it)),
+            None => (
+                format!("!{}", ExprPrecedence::Prefix.parenthesize(&operand)),
+                ExprPrecedence::Prefix,
+            ),
+        }
+    };
+    ctx.add_assist(AssistId("simplify_bool_comparison"), "Simplify boolean comparison", |edit| {
+        let range = bin_expr.syntax().text_range();
+        edit.target(range);
+        edit.replace_expr(&bin_expr.clone().into(), text, precedence);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn bool_literal(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::Literal(it) => match it.syntax().text().to_string().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn simplify_comparison_to_true() {
+        check_assist(
+            simplify_bool_comparison,
+            "fn f(b: bool) { if b <|>== true {} }",
+            "fn f(b: bool) { if <|>b {} }",
+        );
+        check_assist(
+            simplify_bool_comparison,
+            "fn f(b: bool) { if false <|>!= b {} }",
+            "fn f(b: bool) { if <|>b {} }",
+        );
+    }
+
+    #[test]
+    fn simplify_comparison_to_false() {
+        check_assist(
+            simplify_bool_comparison,
+            "fn f(s: S) { let x = true <|>!= s.ready(); }\nstruct S;\nimpl S { fn ready(&self) -> bool { true } }",
+            "fn f(s: S) { let x = <|>!s.ready(); }\nstruct S;\nimpl S { fn ready(&self) -> bool { true } }",
+        );
+        check_assist(
+            simplify_bool_comparison,
+            "fn f(a: u8) { (a < 1) <|>== false; }",
+            "fn f(a: u8) { <|>!(a < 1); }",
+        );
+    }
+
+    #[test]
+    fn simplify_bool_comparison_target() {
+        check_assist_target(
+            simplify_bool_comparison,
+            "fn f(b: bool) { let x = b <|>== false; }",
+            "b == false",
+        );
+    }
+
+    #[test]
+    fn simplify_bool_comparison_not_applicable() {
+        check_assist_not_applicable(simplify_bool_comparison, "fn f(b: bool) { b <|>== b; }");
+        check_assist_not_applicable(simplify_bool_comparison, "fn f() { true <|>== false; }");
+        // Not a `bool`.
+        check_assist_not_applicable(simplify_bool_comparison, "fn f(b: Flag) { b <|>== true; }");
+    }
+}
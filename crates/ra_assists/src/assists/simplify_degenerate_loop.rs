@@ -0,0 +1,184 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner},
+    SyntaxKind::{
+        BREAK_EXPR, CONTINUE_EXPR, FOR_EXPR, LAMBDA_EXPR, LIFETIME, LOOP_EXPR, WHILE_EXPR,
+    },
+    SyntaxNode, T,
+};
+
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: simplify_degenerate_loop
+//
+// Replaces a `loop` which always breaks with a value at the end of its first
+// iteration with that value.
+//
+// ```
+// fn main() {
+//     let total = <|>loop {
+//         let base = price();
+//         break base + tax(base);
+//     };
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let total = {
+//         let base = price();
+//         base + tax(base)
+//     };
+// }
+// ```
+pub(crate) fn simplify_degenerate_loop(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let loop_kw = ctx.find_token_at_offset(T![loop])?;
+    let loop_expr = ast::LoopExpr::cast(loop_kw.parent())?;
+    let body = loop_expr.loop_body()?;
+    let block = body.block()?;
+    let (last, break_expr) = match block.expr() {
+        Some(ast::Expr::BreakExpr(it)) => (it.syntax().clone(), it),
+        Some(_) => return None,
+        None => match block.statements().last()? {
+            ast::Stmt::ExprStmt(stmt) => match stmt.expr()? {
+                ast::Expr::BreakExpr(it) => (stmt.syntax().clone(), it),
+                _ => return None,
+            },
+            ast::Stmt::LetStmt(_) => return None,
+        },
+    };
+    if lifetime(break_expr.syntax()).is_some() {
+        return None;
+    }
+    let value = break_expr.expr()?;
+    // The final `break` has to be the only way out of the loop, and nothing
+    // may start another iteration.
+    let is_labeled = loop_expr.syntax().children().any(|it| lifetime(&it).is_some());
+    let exits_elsewhere = body.syntax().descendants().any(|node| {
+        if node == *break_expr.syntax()
+            || (node.kind() != BREAK_EXPR && node.kind() != CONTINUE_EXPR)
+        {
+            return false;
+        }
+        if lifetime(&node).is_some() {
+            return is_labeled;
+        }
+        !node
+            .ancestors()
+            .take_while(|it| it != body.syntax())
+            .any(|it| [LAMBDA_EXPR, LOOP_EXPR, WHILE_EXPR, FOR_EXPR].contains(&it.kind()))
+    });
+    if exits_elsewhere {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("simplify_degenerate_loop"), "Simplify degenerate loop", |edit| {
+        let range = loop_expr.syntax().text_range();
+        let has_statements = block.statements().count() > 1
+            || (block.expr().is_some() && block.statements().next().is_some());
+        let (text, precedence) = if has_statements {
+            // Keep the block, with the value as its tail.
+            let body_text = body.syntax().to_string();
+            let body_start = body.syntax().text_range().start();
+            let start = (last.text_range().start() - body_start).to_usize();
+            let end = (last.text_range().end() - body_start).to_usize();
+            let text = format!("{}{}{}", &body_text[..start], value.syntax(), &body_text[end..]);
+            (text, ExprPrecedence::Block)
+        } else {
+            (value.syntax().to_string(), ExprPrecedence::of(&value))
+        };
+        edit.target(range);
+        edit.replace_expr(&loop_expr.clone().into(), text, precedence);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The lifetime of a `break`, a `continue` or a label.
+fn lifetime(node: &SyntaxNode) -> Option<String> {
+    node.children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == LIFETIME)
+        .map(|it| it.text().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn simplify_loop_with_statements() {
+        check_assist(
+            simplify_degenerate_loop,
+            r#"
+fn f() -> u8 {
+    <|>loop {
+        let x = g();
+        for i in 0..x {
+            if i > 2 {
+                break;
+            }
+        }
+        break x + 1;
+    }
+}
+"#,
+            r#"
+fn f() -> u8 {
+    <|>{
+        let x = g();
+        for i in 0..x {
+            if i > 2 {
+                break;
+            }
+        }
+        x + 1
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn simplify_loop_with_only_a_value() {
+        check_assist(
+            simplify_degenerate_loop,
+            "fn f() { let x = <|>loop { break 1 }; }",
+            "fn f() { let x = <|>1; }",
+        );
+        check_assist(
+            simplify_degenerate_loop,
+            "fn f() { let x = <|>loop { break a + b; } * 2; }",
+            "fn f() { let x = <|>(a + b) * 2; }",
+        );
+    }
+
+    #[test]
+    fn simplify_degenerate_loop_target() {
+        check_assist_target(
+            simplify_degenerate_loop,
+            "fn f() { let x = <|>loop { break 1; }; }",
+            "loop { break 1; }",
+        );
+    }
+
+    #[test]
+    fn simplify_degenerate_loop_not_applicable() {
+        // Another `break`.
+        check_assist_not_applicable(
+            simplify_degenerate_loop,
+            "fn f() { let x = <|>loop { if c() { break 0; } break 1; }; }",
+        );
+        check_assist_not_applicable(
+            simplify_degenerate_loop,
+            "fn f() { let x = 'a: <|>loop { while c() { break 'a 0; } break 1; }; }",
+        );
+        // A `continue`.
+        check_assist_not_applicable(
+            simplify_degenerate_loop,
+            "fn f() { let x = <|>loop { if c() { continue; } break 1; }; }",
+        );
+        // No value.
+        check_assist_not_applicable(simplify_degenerate_loop, "fn f() { <|>loop { g(); break; } }");
+    }
+}
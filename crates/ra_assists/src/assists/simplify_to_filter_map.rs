@@ -0,0 +1,243 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    TextRange,
+};
+
+use crate::{
+    utils::{is_copy_type, single_expr},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: simplify_to_filter_map
+//
+// Replaces an `and_then` whose closure returns `Some` or `None` depending on a
+// condition with `filter`, followed by `map` if the value changes.
+//
+// ```
+// fn main() {
+//     let port = parse(s).<|>and_then(|n| if n > 1024 { Some(n * 2) } else { None });
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let port = parse(s).filter(|n| *n > 1024).map(|n| n * 2);
+// }
+// ```
+pub(crate) fn simplify_to_filter_map(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    if name_ref.text().as_str() != "and_then" {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let lambda = match (args.next()?, args.next()) {
+        (ast::Expr::LambdaExpr(it), None) => it,
+        _ => return None,
+    };
+    let mut params = lambda.param_list()?.params();
+    let param = match (params.next()?, params.next()) {
+        (it, None) if it.ascribed_type().is_none() => it,
+        _ => return None,
+    };
+    let bind_pat = match param.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && !it.is_mutable() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let name = bind_pat.name()?.text().to_string();
+
+    let if_expr = match lambda.body()? {
+        ast::Expr::IfExpr(it) => it,
+        ast::Expr::BlockExpr(it) => match single_expr(&it)? {
+            ast::Expr::IfExpr(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let pred = cond.expr()?;
+    let value = some_value(&single_expr(&if_expr.then_branch()?)?)?;
+    match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => match single_expr(&it)? {
+            ast::Expr::PathExpr(it) if it.syntax().text() == "None" => (),
+            _ => return None,
+        },
+        ast::ElseBranch::IfExpr(_) => return None,
+    }
+
+    // `filter` passes a reference to the value, which is dereferenced where
+    // the predicate compares it, or copied out if it's needed by value.
+    let pred_range = pred.syntax().text_range();
+    let analyzer = ctx.source_analyzer(lambda.syntax(), None);
+    let uses = analyzer
+        .find_all_refs(&bind_pat)
+        .into_iter()
+        .map(|it| it.range)
+        .filter(|it| it.is_subrange(&pred_range))
+        .collect::<Vec<_>>();
+    let mut derefs = Vec::new();
+    let mut by_ref = true;
+    for path_expr in pred.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let range = path_expr.syntax().text_range();
+        if !uses.contains(&range) || is_receiver(&path_expr) {
+            continue;
+        }
+        if is_compared(&path_expr) {
+            derefs.push(range.start() - pred_range.start());
+        } else {
+            by_ref = false;
+        }
+    }
+    let (filter_param, pred_text) = if by_ref {
+        let mut text = pred.syntax().to_string();
+        for offset in derefs.into_iter().rev() {
+            text.insert(offset.to_usize(), '*');
+        }
+        (name.clone(), text)
+    } else {
+        let ty = analyzer.type_of_pat(ctx.db, &ast::Pat::from(bind_pat.clone()))?;
+        if !is_copy_type(ctx.db, &ty) {
+            return None;
+        }
+        (format!("&{}", name), pred.syntax().to_string())
+    };
+    let is_identity = match &value {
+        ast::Expr::PathExpr(it) => it.syntax().text() == name.as_str(),
+        _ => false,
+    };
+
+    ctx.add_assist(AssistId("simplify_to_filter_map"), "Simplify to filter/map", |edit| {
+        let mut text = format!("filter(|{}| {})", filter_param, pred_text);
+        if !is_identity {
+            text.push_str(&format!(".map(|{}| {})", name, value.syntax()));
+        }
+        let range = TextRange::from_to(
+            name_ref.syntax().text_range().start(),
+            call.syntax().text_range().end(),
+        );
+        edit.target(range);
+        edit.replace(range, text);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// For `Some(x)` returns `x`.
+fn some_value(expr: &ast::Expr) -> Option<ast::Expr> {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    match call.expr()? {
+        ast::Expr::PathExpr(it) if it.syntax().text() == "Some" => (),
+        _ => return None,
+    }
+    let mut args = call.arg_list()?.args();
+    match (args.next()?, args.next()) {
+        (it, None) => Some(it),
+        _ => None,
+    }
+}
+
+fn is_compared(path_expr: &ast::PathExpr) -> bool {
+    let bin_expr = match path_expr.syntax().parent().and_then(ast::BinExpr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    match bin_expr.op_kind() {
+        Some(ast::BinOp::EqualityTest)
+        | Some(ast::BinOp::NegatedEqualityTest)
+        | Some(ast::BinOp::LesserEqualTest)
+        | Some(ast::BinOp::GreaterEqualTest)
+        | Some(ast::BinOp::LesserTest)
+        | Some(ast::BinOp::GreaterTest) => true,
+        _ => false,
+    }
+}
+
+fn is_receiver(path_expr: &ast::PathExpr) -> bool {
+    let parent = match path_expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let receiver = if let Some(it) = ast::MethodCallExpr::cast(parent.clone()) {
+        it.expr()
+    } else if let Some(it) = ast::FieldExpr::cast(parent) {
+        it.expr()
+    } else {
+        None
+    };
+    receiver.map_or(false, |it| it.syntax() == path_expr.syntax())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn simplify_to_filter() {
+        check_assist(
+            simplify_to_filter_map,
+            "fn f(o: Option<u32>) { o.<|>and_then(|x| if x > 1 && 5 != x { Some(x) } else { None }); }",
+            "fn f(o: Option<u32>) { o.<|>filter(|x| *x > 1 && 5 != *x); }",
+        );
+    }
+
+    #[test]
+    fn simplify_to_filter_map_with_receivers() {
+        check_assist(
+            simplify_to_filter_map,
+            r#"
+struct S { n: u32 }
+impl S { fn ok(&self) -> bool { true } }
+fn f(o: Option<S>) {
+    o.<|>and_then(|s| {
+        if s.ok() && s.n > 0 {
+            Some(g(s))
+        } else {
+            None
+        }
+    });
+}
+"#,
+            r#"
+struct S { n: u32 }
+impl S { fn ok(&self) -> bool { true } }
+fn f(o: Option<S>) {
+    o.<|>filter(|s| s.ok() && s.n > 0).map(|s| g(s));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn simplify_to_filter_map_target() {
+        check_assist_target(
+            simplify_to_filter_map,
+            "fn f(o: Option<u8>) { o.<|>and_then(|x| if x > 1 { Some(x) } else { None }); }",
+            "and_then(|x| if x > 1 { Some(x) } else { None })",
+        );
+    }
+
+    #[test]
+    fn simplify_to_filter_map_not_applicable() {
+        // Not a plain condition.
+        check_assist_not_applicable(
+            simplify_to_filter_map,
+            "fn f(o: Option<u8>) { o.<|>and_then(|x| if x > 1 { g(x) } else { None }); }",
+        );
+        // The predicate needs the value itself.
+        check_assist_not_applicable(
+            simplify_to_filter_map,
+            "struct S;\nfn f(o: Option<S>) { o.<|>and_then(|s| if check(s) { Some(s) } else { None }); }",
+        );
+        check_assist_not_applicable(
+            simplify_to_filter_map,
+            "fn f(o: Option<u8>) { o.<|>and_then(|x| if x > 1 { None } else { Some(x) }); }",
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
+    SyntaxKind::{IDENT, LAMBDA_EXPR, MACRO_CALL, RETURN_EXPR, TRY_EXPR, WHITESPACE},
+    TextRange, TextUnit, T,
+};
+
+use crate::{
+    utils::{outer_locals, params_and_args},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: split_function
+//
+// Moves everything after the blank line under the cursor into a new function,
+// passing the locals it uses as parameters.
+//
+// ```
+// fn main() {
+//     let x = 1;
+// <|>
+//     let y = x * 2;
+//     foo(y);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = 1;
+//
+//     fun_name(x);
+// }
+//
+// fn fun_name(x: i32) {
+//     let y = x * 2;
+//     foo(y);
+// }
+// ```
+pub(crate) fn split_function(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let offset = ctx.frange.range.start();
+    let ws = ctx.token_at_offset().find(|it| it.kind() == WHITESPACE)?;
+    let (before, after) = ws.text().split_at((offset - ws.text_range().start()).to_usize());
+    if !before.contains('\n') || !after.contains('\n') {
+        return None;
+    }
+    let fn_def = ws.parent().ancestors().find_map(ast::FnDef::cast)?;
+    let block = fn_def.body()?.block()?;
+    if ws.parent() != *block.syntax() {
+        return None;
+    }
+    // Generic, async and unsafe functions would need the same treatment for
+    // the new function, which is not supported yet.
+    if fn_def.type_param_list().is_some()
+        || fn_def.is_async()
+        || fn_def.syntax().children_with_tokens().any(|it| it.kind() == T![unsafe])
+    {
+        return None;
+    }
+
+    let split_at = ws.text_range().end();
+    let has_statements_before =
+        block.statements().any(|it| it.syntax().text_range().end() <= ws.text_range().start());
+    let mut rest = block
+        .statements()
+        .map(|it| it.syntax().clone())
+        .filter(|it| it.text_range().start() >= split_at)
+        .collect::<Vec<_>>();
+    let tail = block.expr();
+    rest.extend(tail.as_ref().map(|it| it.syntax().clone()));
+    if !has_statements_before || rest.is_empty() {
+        return None;
+    }
+    let rest_range =
+        TextRange::from_to(rest.first()?.text_range().start(), rest.last()?.text_range().end());
+    // Without a tail expression the new function can't produce the return value.
+    let ret_type = match (&tail, fn_def.ret_type()) {
+        (Some(_), ret_type) => ret_type,
+        (None, Some(_)) => return None,
+        (None, None) => None,
+    };
+
+    // `return` and `?` would exit the new function instead of the original one.
+    let exits_early = rest.iter().flat_map(|it| it.descendants()).any(|it| {
+        (it.kind() == RETURN_EXPR || it.kind() == TRY_EXPR)
+            && !it.ancestors().any(|it| it.kind() == LAMBDA_EXPR)
+    });
+    if exits_early {
+        return None;
+    }
+
+    let locals = outer_locals(&ctx, &rest, rest_range)?;
+
+    // Uses of locals inside macro calls are not resolved, so we can't tell
+    // which ones have to be passed along.
+    let names_before = fn_def
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| it.syntax().text_range().end() <= split_at)
+        .filter_map(|it| it.name())
+        .map(|it| it.text().clone())
+        .collect::<Vec<_>>();
+    let uses_local_in_macro = rest
+        .iter()
+        .flat_map(|it| it.descendants())
+        .filter(|it| it.kind() == MACRO_CALL)
+        .flat_map(|it| it.descendants_with_tokens())
+        .filter_map(|it| it.into_token())
+        .any(|it| it.kind() == IDENT && names_before.contains(it.text()));
+    if uses_local_in_macro {
+        return None;
+    }
+
+    let (params, args) = params_and_args(ctx.db, locals)?;
+
+    let is_method = fn_def.syntax().parent().and_then(|it| it.parent()).map_or(false, |it| {
+        ast::ImplBlock::can_cast(it.kind()) || ast::TraitDef::can_cast(it.kind())
+    });
+    let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+    let body_indent = leading_indent(&rest[0]).unwrap_or_default();
+    let rest_text = {
+        let block_text = block.syntax().text().to_string();
+        let block_start = block.syntax().text_range().start();
+        let start = (rest_range.start() - block_start).to_usize();
+        let end = (rest_range.end() - block_start).to_usize();
+        block_text[start..end].to_string()
+    };
+
+    ctx.add_assist(AssistId("split_function"), "Split function here", |edit| {
+        let mut call = String::new();
+        if is_method {
+            call.push_str("Self::");
+        }
+        format!(call, "fun_name({})", args.join(", "));
+        if tail.is_none() {
+            call.push(';');
+        }
+
+        let mut buf = String::new();
+        format!(buf, "\n\n{}fn ", indent);
+        let name_offset = TextUnit::of_str(&buf);
+        format!(buf, "fun_name({})", params.join(", "));
+        if let Some(ret_type) = &ret_type {
+            format!(buf, " {}", ret_type.syntax());
+        }
+        format!(buf, " {{\n{}{}\n{}}}", body_indent, rest_text, indent);
+
+        let fn_end = fn_def.syntax().text_range().end();
+        edit.target(rest_range);
+        edit.replace(rest_range, call.clone());
+        edit.insert(fn_end, buf);
+        edit.set_cursor(fn_end - rest_range.len() + TextUnit::of_str(&call) + name_offset);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn split_function_with_tail_expr() {
+        check_assist(
+            split_function,
+            r#"
+fn foo(x: i32) -> i32 {
+    let y = x + 1;
+<|>
+    let z = y * 2;
+    z + x
+}
+"#,
+            r#"
+fn foo(x: i32) -> i32 {
+    let y = x + 1;
+
+    fun_name(y, x)
+}
+
+fn <|>fun_name(y: i32, x: i32) -> i32 {
+    let z = y * 2;
+    z + x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_function_passes_mutable_locals() {
+        check_assist(
+            split_function,
+            r#"
+struct S;
+impl S { fn touch(&mut self) {} }
+
+fn foo() {
+    let mut s = S;
+<|>
+    s.touch();
+}
+"#,
+            r#"
+struct S;
+impl S { fn touch(&mut self) {} }
+
+fn foo() {
+    let mut s = S;
+
+    fun_name(s);
+}
+
+fn <|>fun_name(mut s: S) {
+    s.touch();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_function_in_impl() {
+        check_assist(
+            split_function,
+            r#"
+struct S;
+impl S {
+    fn foo() -> u32 {
+        let a = 1u32;
+<|>
+        a
+    }
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo() -> u32 {
+        let a = 1u32;
+
+        Self::fun_name(a)
+    }
+
+    fn <|>fun_name(a: u32) -> u32 {
+        a
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_function_target() {
+        check_assist_target(
+            split_function,
+            "fn foo() {\n    bar();\n<|>\n    baz();\n    quux();\n}",
+            "baz();\n    quux();",
+        );
+    }
+
+    #[test]
+    fn split_function_not_applicable_outside_blank_line() {
+        check_assist_not_applicable(split_function, "fn foo() {\n    bar();<|>\n    baz();\n}");
+    }
+
+    #[test]
+    fn split_function_not_applicable_with_self() {
+        check_assist_not_applicable(
+            split_function,
+            "struct S;\nimpl S {\n    fn foo(&self) {\n        bar();\n<|>\n        self.baz();\n    }\n}",
+        );
+    }
+
+    #[test]
+    fn split_function_not_applicable_with_return() {
+        check_assist_not_applicable(
+            split_function,
+            "fn foo() {\n    bar();\n<|>\n    if baz() { return; }\n    quux();\n}",
+        );
+    }
+}
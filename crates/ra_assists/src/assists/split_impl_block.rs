@@ -0,0 +1,193 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: split_impl_block
+//
+// Moves the selected members of an inherent `impl` block into a new block for
+// the same type, placed right after it.
+//
+// ```
+// struct Counter(u32);
+//
+// impl Counter {
+//     fn get(&self) -> u32 { self.0 }
+//     <|>fn reset(&mut self) { self.0 = 0 }<|>
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+//
+// impl Counter {
+//     fn get(&self) -> u32 { self.0 }
+// }
+//
+// impl Counter {
+//     fn reset(&mut self) { self.0 = 0 }
+// }
+// ```
+pub(crate) fn split_impl_block(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let item_list = ctx.covering_element().ancestors().find_map(ast::ItemList::cast)?;
+    let impl_block = ast::ImplBlock::cast(item_list.syntax().parent()?)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+
+    let items = item_list.impl_items().collect::<Vec<_>>();
+    let is_selected =
+        |it: &ast::ImplItem| it.syntax().text_range().intersection(&selection).is_some();
+    let first = items.iter().position(|it| is_selected(it))?;
+    let last = items.iter().rposition(|it| is_selected(it))?;
+    // Moving all of the members would leave an empty block behind.
+    if first == 0 && last == items.len() - 1 {
+        return None;
+    }
+
+    let moved = TextRange::from_to(
+        items[first].syntax().text_range().start(),
+        items[last].syntax().text_range().end(),
+    );
+    let removed = if first == 0 {
+        TextRange::from_to(moved.start(), items[last + 1].syntax().text_range().start())
+    } else {
+        TextRange::from_to(items[first - 1].syntax().text_range().end(), moved.end())
+    };
+    let header_start = impl_block
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T![impl])?
+        .text_range()
+        .start();
+    let header = TextRange::from_to(header_start, item_list.syntax().text_range().start());
+
+    let text = impl_block.syntax().ancestors().last()?.text().to_string();
+    let slice = |range: TextRange| &text[range.start().to_usize()..range.end().to_usize()];
+    let indent = leading_indent(impl_block.syntax()).unwrap_or_default();
+    let old_indent = leading_indent(items[first].syntax()).unwrap_or_default();
+    let new_indent = format!("{}    ", indent);
+    let members = change_indent(slice(moved), &old_indent, &new_indent);
+
+    let mut buf = String::new();
+    format!(buf, "\n\n{}", indent);
+    let cursor_in_buf = TextUnit::of_str(&buf);
+    format!(buf, "{}{{\n{}", slice(header), new_indent);
+    buf.push_str(&members);
+    format!(buf, "\n{}}}", indent);
+
+    ctx.add_assist(AssistId("split_impl_block"), "Split impl block", |edit| {
+        edit.target(moved);
+        edit.delete(removed);
+        let impl_end = impl_block.syntax().text_range().end();
+        edit.insert(impl_end, buf);
+        edit.set_cursor(impl_end - removed.len() + cursor_in_buf);
+    })
+}
+
+/// Replaces the indentation of all but the first line, which is already
+/// indented.
+fn change_indent(text: &str, old_indent: &str, new_indent: &str) -> String {
+    let mut lines = text.lines();
+    let mut res = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        res.push('\n');
+        if !line.trim().is_empty() {
+            res.push_str(new_indent);
+            res.push_str(if line.starts_with(old_indent) {
+                &line[old_indent.len()..]
+            } else {
+                line
+            });
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist_range, check_assist_range_not_applicable};
+
+    #[test]
+    fn split_impl_block_with_header() {
+        check_assist_range(
+            split_impl_block,
+            r#"
+struct S<T>(T);
+mod m {
+    impl<T> super::S<T>
+    where
+        T: Clone,
+    {
+        fn a(&self) {}
+
+        <|>#[cfg(test)]
+        fn b(&self) {
+            let _ = 1;
+        }<|>
+
+        fn c(&self) {}
+    }
+}
+"#,
+            r#"
+struct S<T>(T);
+mod m {
+    impl<T> super::S<T>
+    where
+        T: Clone,
+    {
+        fn a(&self) {}
+
+        fn c(&self) {}
+    }
+
+    <|>impl<T> super::S<T>
+    where
+        T: Clone,
+    {
+        #[cfg(test)]
+        fn b(&self) {
+            let _ = 1;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_impl_block_from_start() {
+        check_assist_range(
+            split_impl_block,
+            "struct S;\nimpl S { fn <|>a() {} const B: u8 = 0;<|> fn c() {} }",
+            "struct S;\nimpl S { fn c() {} }\n\n<|>impl S {\n    fn a() {} const B: u8 = 0;\n}",
+        );
+    }
+
+    #[test]
+    fn split_impl_block_not_applicable_for_all_members() {
+        check_assist_range_not_applicable(
+            split_impl_block,
+            "struct S;\nimpl S { <|>fn a() {} fn b() {}<|> }",
+        );
+    }
+
+    #[test]
+    fn split_impl_block_not_applicable_for_trait_impls() {
+        check_assist_range_not_applicable(
+            split_impl_block,
+            "struct S;\ntrait T { fn a(); fn b(); }\nimpl T for S { <|>fn a() {}<|> fn b() {} }",
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, TypeBoundsOwner},
+    T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: split_where_bounds
+//
+// Splits a predicate of a where clause into a predicate per bound, or merges
+// the predicates about the same type.
+//
+// ```
+// fn print<T>(x: T) where <|>T: Debug + Clone {}
+// ```
+// ->
+// ```
+// fn print<T>(x: T) where T: Debug, T: Clone {}
+// ```
+pub(crate) fn split_where_bounds(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let pred = ctx.find_node_at_offset::<ast::WherePred>()?;
+    let where_clause = ast::WhereClause::cast(pred.syntax().parent()?)?;
+    let preds = where_clause.predicates().collect::<Vec<_>>();
+    let subjects = preds.iter().map(subject).collect::<Option<Vec<_>>>()?;
+    let subject = subject(&pred)?;
+    let same_subject = subjects.iter().filter(|it| **it == subject).count();
+
+    let mut new_preds = Vec::new();
+    let label = if same_subject > 1 {
+        // Merged where the first of the predicates is.
+        let mut merged = false;
+        for (it, it_subject) in preds.iter().zip(&subjects) {
+            if *it_subject != subject {
+                new_preds.push(it.syntax().to_string());
+            } else if !merged {
+                merged = true;
+                let bounds = preds
+                    .iter()
+                    .zip(&subjects)
+                    .filter(|(_, it)| **it == subject)
+                    .flat_map(|(it, _)| bounds(it))
+                    .collect::<Vec<_>>();
+                new_preds.push(format!("{} {}", subject, bounds.join(" + ")));
+            }
+        }
+        "Merge bounds"
+    } else {
+        if pred.type_bound_list()?.bounds().count() < 2 {
+            return None;
+        }
+        for it in &preds {
+            if it == &pred {
+                new_preds.extend(bounds(it).into_iter().map(|it| format!("{} {}", subject, it)));
+            } else {
+                new_preds.push(it.syntax().to_string());
+            }
+        }
+        "Split bounds"
+    };
+
+    let text = rebuild(&where_clause, &preds, &new_preds);
+    ctx.add_assist(AssistId("split_where_bounds"), label, |edit| {
+        let range = where_clause.syntax().text_range();
+        edit.target(pred.syntax().text_range());
+        edit.replace(range, text);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The text of the predicate before the bounds, like `T:` or `for<'a> F:`.
+fn subject(pred: &ast::WherePred) -> Option<String> {
+    let bound_list = pred.type_bound_list()?;
+    let len = bound_list.syntax().text_range().start() - pred.syntax().text_range().start();
+    let text = pred.syntax().to_string();
+    let subject = text[..len.to_usize()].trim_end();
+    if !subject.ends_with(':') {
+        return None;
+    }
+    Some(subject.to_string())
+}
+
+fn bounds(pred: &ast::WherePred) -> Vec<String> {
+    pred.type_bound_list()
+        .map(|it| it.bounds().map(|it| it.syntax().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Builds the where clause with `new_preds`, in the layout of the original.
+fn rebuild(
+    where_clause: &ast::WhereClause,
+    preds: &[ast::WherePred],
+    new_preds: &[String],
+) -> String {
+    let trailing_comma = where_clause.syntax().last_token().map_or(false, |it| it.kind() == T![,]);
+    let indent = preds.first().and_then(|it| leading_indent(it.syntax()));
+    let mut res = String::from("where");
+    match indent {
+        // One predicate per line.
+        Some(indent) if where_clause.syntax().text().contains_char('\n') => {
+            for pred in new_preds {
+                res.push_str(&format!("\n{}{},", indent, pred));
+            }
+            if !trailing_comma {
+                res.pop();
+            }
+        }
+        _ => {
+            res.push(' ');
+            res.push_str(&new_preds.join(", "));
+            if trailing_comma {
+                res.push(',');
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn split_where_bounds_keeps_lifetimes_and_assoc_types() {
+        check_assist(
+            split_where_bounds,
+            "fn f<'a, I>(i: I) where 'a: 'static, I: Iterator<Item = &'a u8><|> + 'a + Clone, {}",
+            "fn f<'a, I>(i: I) <|>where 'a: 'static, I: Iterator<Item = &'a u8>, I: 'a, I: Clone, {}",
+        );
+        check_assist(
+            split_where_bounds,
+            "fn f<'a, 'b, 'c>() where <|>'a: 'b + 'c {}",
+            "fn f<'a, 'b, 'c>() <|>where 'a: 'b, 'a: 'c {}",
+        );
+    }
+
+    #[test]
+    fn split_where_bounds_multiline() {
+        check_assist(
+            split_where_bounds,
+            r#"
+impl<T> S<T>
+where
+    <|>T: Clone + Debug,
+    u8: From<T>,
+{
+}
+"#,
+            r#"
+impl<T> S<T>
+<|>where
+    T: Clone,
+    T: Debug,
+    u8: From<T>,
+{
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_where_bounds() {
+        check_assist(
+            split_where_bounds,
+            "struct S<T, U>(T, U) where T: Clone, U: Copy, <|>T: Debug + Eq;",
+            "struct S<T, U>(T, U) <|>where T: Clone + Debug + Eq, U: Copy;",
+        );
+        check_assist(
+            split_where_bounds,
+            "fn f<F>() where for<'a> F: Fn(&'a u8), <|>for<'a> F: Send {}",
+            "fn f<F>() <|>where for<'a> F: Fn(&'a u8) + Send {}",
+        );
+    }
+
+    #[test]
+    fn split_where_bounds_target() {
+        check_assist_target(
+            split_where_bounds,
+            "fn f<T>() where T: Copy, <|>T: Clone {}",
+            "T: Clone",
+        );
+    }
+
+    #[test]
+    fn split_where_bounds_not_applicable() {
+        check_assist_not_applicable(split_where_bounds, "fn f<T>() where <|>T: Clone, U: Eq {}");
+        check_assist_not_applicable(split_where_bounds, "fn f<T: Clone + <|>Copy>() {}");
+    }
+}
@@ -0,0 +1,145 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    T,
+};
+
+use super::invert_if::invert_boolean_expression;
+use crate::{assist_ctx::ExprPrecedence, Assist, AssistCtx, AssistId};
+
+// Assist: swap_if_branches
+//
+// Negates the condition of an `if/else` and swaps its branches.
+//
+// ```
+// fn main() {
+//     if<|> x > 0 { foo() } else { bar() }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     if !(x > 0) { bar() } else { foo() }
+// }
+// ```
+pub(crate) fn swap_if_branches(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_keyword = ctx.find_token_at_offset(T![if])?;
+    let expr = ast::IfExpr::cast(if_keyword.parent())?;
+    let cond = expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond = cond.expr()?;
+    let then_block = expr.then_branch()?;
+    let else_block = match expr.else_branch()? {
+        ast::ElseBranch::Block(it) => it,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+
+    ctx.add_assist(AssistId("swap_if_branches"), "Swap branches", |edit| {
+        let flip_cond = match invert_boolean_expression(&cond) {
+            Some(it) => it.syntax().to_string(),
+            None => format!("!{}", ExprPrecedence::Prefix.parenthesize(&cond)),
+        };
+        edit.target(if_keyword.text_range());
+        edit.replace(cond.syntax().text_range(), flip_cond);
+        edit.replace(then_block.syntax().text_range(), else_block.syntax().text());
+        edit.replace(else_block.syntax().text_range(), then_block.syntax().text());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn swap_if_branches_negates_simple_condition() {
+        check_assist(
+            swap_if_branches,
+            "fn f() { i<|>f cond { 1 } else { 2 } }",
+            "fn f() { i<|>f !cond { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn swap_if_branches_parenthesizes_complex_condition() {
+        check_assist(
+            swap_if_branches,
+            "fn f() { <|>if a && b { 1 } else { 2 } }",
+            "fn f() { <|>if !(a && b) { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn swap_if_branches_simplifies_negation() {
+        check_assist(
+            swap_if_branches,
+            "fn f() { <|>if !cond { 1 } else { 2 } }",
+            "fn f() { <|>if cond { 2 } else { 1 } }",
+        );
+        check_assist(
+            swap_if_branches,
+            "fn f() { <|>if x != 3 { 1 } else { 2 } }",
+            "fn f() { <|>if x == 3 { 2 } else { 1 } }",
+        );
+    }
+
+    #[test]
+    fn swap_if_branches_multiline() {
+        check_assist(
+            swap_if_branches,
+            r#"
+            fn f() {
+                <|>if is_ready() {
+                    foo();
+                } else {
+                    bar();
+                    baz();
+                }
+            }
+            "#,
+            r#"
+            fn f() {
+                <|>if !is_ready() {
+                    bar();
+                    baz();
+                } else {
+                    foo();
+                }
+            }
+            "#,
+        )
+    }
+
+    #[test]
+    fn swap_if_branches_target() {
+        check_assist_target(swap_if_branches, "fn f() { <|>if a { 1 } else { 2 } }", "if")
+    }
+
+    #[test]
+    fn swap_if_branches_not_applicable_for_else_if() {
+        check_assist_not_applicable(
+            swap_if_branches,
+            "fn f() { <|>if a { 1 } else if b { 2 } else { 3 } }",
+        )
+    }
+
+    #[test]
+    fn swap_if_branches_not_applicable_without_else() {
+        check_assist_not_applicable(swap_if_branches, "fn f() { <|>if a { 1 } }")
+    }
+
+    #[test]
+    fn swap_if_branches_not_applicable_for_if_let() {
+        check_assist_not_applicable(
+            swap_if_branches,
+            "fn f() { <|>if let Some(x) = a { x } else { 2 } }",
+        )
+    }
+
+    #[test]
+    fn swap_if_branches_not_applicable_with_cursor_not_on_if() {
+        check_assist_not_applicable(swap_if_branches, "fn f() { if a { <|>1 } else { 2 } }")
+    }
+}
@@ -0,0 +1,289 @@
+use hir::{db::HirDatabase, HasSource, SourceAnalyzer};
+use ra_syntax::{
+    ast::{self, AstNode, TypeAscriptionOwner, VisibilityOwner},
+    T,
+};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{find_calls, is_copy_type, local_function},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: take_self_by_ref
+//
+// Changes a method taking `self` by value to take it by reference, when the
+// method doesn't need to own `self`.
+//
+// ```
+// struct User { name: String, age: u32 }
+//
+// impl User {
+//     fn <|>is_adult(self) -> bool {
+//         self.age >= 18
+//     }
+// }
+// ```
+// ->
+// ```
+// struct User { name: String, age: u32 }
+//
+// impl User {
+//     fn is_adult(&self) -> bool {
+//         self.age >= 18
+//     }
+// }
+// ```
+pub(crate) fn take_self_by_ref(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    // Callers outside of the module can't be updated.
+    if fn_def.visibility().is_some() {
+        return None;
+    }
+    let self_param = fn_def.param_list()?.self_param()?;
+    if self_param.kind() != ast::SelfParamKind::Owned
+        || self_param.ascribed_type().is_some()
+        || self_param.syntax().children_with_tokens().any(|it| it.kind() == T![mut])
+    {
+        return None;
+    }
+    // Trait methods have to keep the signature of the trait.
+    let impl_block = fn_def.syntax().ancestors().find_map(ast::ImplBlock::cast)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+
+    // `&self` in the body is already a reference afterwards.
+    let mut edits = Vec::new();
+    let analyzer = ctx.source_analyzer(body.syntax(), None);
+    for token in body.syntax().descendants_with_tokens().filter(|it| it.kind() == T![self]) {
+        let path_expr = match token.ancestors().find_map(ast::PathExpr::cast) {
+            Some(it) => it,
+            None => {
+                // Format macros only take references to their arguments.
+                let macro_call = token.ancestors().find_map(ast::MacroCall::cast)?;
+                if !is_format_macro(&macro_call) {
+                    return None;
+                }
+                continue;
+            }
+        };
+        if path_expr.syntax().text() != "self" {
+            continue;
+        }
+        let parent = path_expr.syntax().parent()?;
+        match ast::RefExpr::cast(parent.clone()) {
+            Some(ref_expr) if !ref_expr.is_mut() => {
+                edits.push((ref_expr.syntax().text_range(), "self".to_string()));
+                continue;
+            }
+            _ => (),
+        }
+        if !is_borrowed(&ctx, &analyzer, &ast::Expr::PathExpr(path_expr))? {
+            return None;
+        }
+    }
+
+    // Calls in child modules of other files couldn't be updated.
+    let (function, _) = local_function(&ctx, &fn_def)?;
+    let root = fn_def.syntax().ancestors().last()?;
+    // Method calls borrow the receiver on their own, only calls through the
+    // path of the method need a reference.
+    for call in find_calls(&ctx, &root, function)? {
+        if call.offset == 0 {
+            continue;
+        }
+        let receiver = call.args.first()?;
+        let text = format!("&{}", ExprPrecedence::Prefix.parenthesize(receiver));
+        edits.push((receiver.syntax().text_range(), text));
+    }
+
+    ctx.add_assist(AssistId("take_self_by_ref"), "Take self by reference", |edit| {
+        edit.target(self_param.syntax().text_range());
+        edit.insert(self_param.syntax().text_range().start(), "&");
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+    })
+}
+
+/// Whether the value of `expr` is only borrowed, rather than moved, mutated or
+/// copied out of `self`.
+fn is_borrowed(
+    ctx: &AssistCtx<impl HirDatabase>,
+    analyzer: &SourceAnalyzer,
+    expr: &ast::Expr,
+) -> Option<bool> {
+    let parent = expr.syntax().parent()?;
+    if let Some(field_expr) = ast::FieldExpr::cast(parent.clone()) {
+        let field_expr = ast::Expr::FieldExpr(field_expr);
+        if is_borrowed(ctx, analyzer, &field_expr)? {
+            return Some(true);
+        }
+        // Fields of `Copy` types are copied instead.
+        let ty = analyzer.type_of(ctx.db, &field_expr)?;
+        return Some(is_copy_type(ctx.db, &ty) && is_read(&field_expr));
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        if call.expr()?.syntax() != expr.syntax() {
+            return Some(false);
+        }
+        let method = analyzer.resolve_method_call(&call)?;
+        let self_param = method.source(ctx.db).value.param_list()?.self_param()?;
+        return Some(self_param.kind() == ast::SelfParamKind::Ref);
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        return Some(!ref_expr.is_mut());
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        use ast::BinOp::*;
+        let is_comparison = match bin_expr.op_kind()? {
+            EqualityTest | NegatedEqualityTest | LesserTest | LesserEqualTest | GreaterTest
+            | GreaterEqualTest => true,
+            _ => false,
+        };
+        return Some(is_comparison);
+    }
+    Some(false)
+}
+
+/// Whether the value of `expr` is only read, so that a copy of it is used.
+fn is_read(expr: &ast::Expr) -> bool {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        return !bin_expr.op_kind().map_or(false, |op| op.is_assignment())
+            || bin_expr.lhs().as_ref().map(|it| it.syntax()) != Some(expr.syntax());
+    }
+    // Borrows and calls would be of the field itself.
+    !ast::RefExpr::can_cast(parent.kind())
+        && !ast::MethodCallExpr::can_cast(parent.kind())
+        && !ast::FieldExpr::can_cast(parent.kind())
+}
+
+fn is_format_macro(macro_call: &ast::MacroCall) -> bool {
+    let name = match macro_call.path() {
+        Some(it) => it.syntax().to_string(),
+        None => return false,
+    };
+    match name.as_str() {
+        "format" | "print" | "println" | "eprint" | "eprintln" | "write" | "writeln" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn take_self_by_ref_with_calls() {
+        check_assist(
+            take_self_by_ref,
+            r#"
+struct Name;
+impl Name { fn len(&self) -> usize { 0 } }
+struct S { name: Name, n: u32 }
+impl S {
+    fn <|>describe(self) -> String {
+        let s = &self;
+        println!("{}", self.name);
+        if self.n == 0 && self.name.len() > 1 {}
+        format!("{}", self.n + 1)
+    }
+}
+fn main(s: S) {
+    s.describe();
+    S::describe(s);
+    S::describe(make());
+}
+"#,
+            r#"
+struct Name;
+impl Name { fn len(&self) -> usize { 0 } }
+struct S { name: Name, n: u32 }
+impl S {
+    fn <|>describe(&self) -> String {
+        let s = self;
+        println!("{}", self.name);
+        if self.n == 0 && self.name.len() > 1 {}
+        format!("{}", self.n + 1)
+    }
+}
+fn main(s: S) {
+    s.describe();
+    S::describe(&s);
+    S::describe(&make());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn take_self_by_ref_copying_fields() {
+        check_assist(
+            take_self_by_ref,
+            "#[derive(Clone, Copy)]\nstruct Id(u32);\nstruct S { id: Id }\nimpl S { fn <|>id(self) -> Id { self.id } }",
+            "#[derive(Clone, Copy)]\nstruct Id(u32);\nstruct S { id: Id }\nimpl S { fn <|>id(&self) -> Id { self.id } }",
+        );
+    }
+
+    #[test]
+    fn take_self_by_ref_target() {
+        check_assist_target(take_self_by_ref, "struct S; impl S { fn <|>f(self) {} }", "self");
+    }
+
+    #[test]
+    fn take_self_by_ref_not_applicable() {
+        // Moving a field out.
+        check_assist_not_applicable(
+            take_self_by_ref,
+            "struct S { name: String } impl S { fn <|>f(self) -> String { self.name } }",
+        );
+        // Returning `self`.
+        check_assist_not_applicable(
+            take_self_by_ref,
+            "struct S; impl S { fn <|>f(self) -> S { self } }",
+        );
+        // Calling a method which consumes `self`.
+        check_assist_not_applicable(
+            take_self_by_ref,
+            "struct S; impl S { fn g(self) {} fn <|>f(self) { self.g() } }",
+        );
+        // Already a reference.
+        check_assist_not_applicable(take_self_by_ref, "struct S; impl S { fn <|>f(&self) {} }");
+        // A trait method.
+        check_assist_not_applicable(
+            take_self_by_ref,
+            "trait T { fn f(self); } struct S; impl T for S { fn <|>f(self) {} }",
+        );
+    }
+
+    #[test]
+    fn take_self_by_ref_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            take_self_by_ref,
+            r#"
+//- /main.rs
+mod tests;
+struct User;
+impl User {
+    fn <|>consume(self) {}
+}
+
+//- /tests.rs
+fn f(x: super::User) { super::User::consume(x); }
+"#,
+        );
+    }
+}
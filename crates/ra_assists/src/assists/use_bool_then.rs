@@ -0,0 +1,161 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode},
+    SyntaxKind::{BREAK_EXPR, CONTINUE_EXPR, RETURN_EXPR, TRY_EXPR},
+    T,
+};
+
+use crate::{assist_ctx::ExprPrecedence, utils::single_expr, Assist, AssistCtx, AssistId};
+
+// Assist: use_bool_then
+//
+// Replaces an `if` producing `Some` or `None` with `bool::then_some`, or with
+// `bool::then` if the value is not cheap to compute.
+//
+// ```
+// fn first(v: &[u8]) -> Option<u8> {
+//     <|>if v.len() > 0 { Some(v[0]) } else { None }
+// }
+// ```
+// ->
+// ```
+// fn first(v: &[u8]) -> Option<u8> {
+//     (v.len() > 0).then(|| v[0])
+// }
+// ```
+pub(crate) fn use_bool_then(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_keyword = ctx.find_token_at_offset(T![if])?;
+    let if_expr = ast::IfExpr::cast(if_keyword.parent())?;
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond_expr = cond.expr()?;
+    let then_expr = single_expr(&if_expr.then_branch()?)?;
+    let else_expr = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => single_expr(&it)?,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    match else_expr {
+        ast::Expr::PathExpr(it) if it.syntax().text() == "None" => (),
+        _ => return None,
+    }
+    let call = match then_expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    match call.expr()? {
+        ast::Expr::PathExpr(it) if it.syntax().text() == "Some" => (),
+        _ => return None,
+    }
+    let mut args = call.arg_list()?.args();
+    let value = match (args.next()?, args.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    // The value can't leave the function from inside of a closure.
+    if value.syntax().descendants().any(|it| match it.kind() {
+        RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR => true,
+        _ => false,
+    }) {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(if_expr.syntax(), None);
+    let cond_ty = analyzer.type_of(ctx.db, &cond_expr)?;
+    if cond_ty.display(ctx.db).to_string() != "bool" {
+        return None;
+    }
+
+    // Building anything more than a plain value only happens when needed.
+    let receiver = ExprPrecedence::Postfix.parenthesize(&cond_expr);
+    let (label, text) = if is_cheap(&value) {
+        ("Use bool::then_some", format!("{}.then_some({})", receiver, value.syntax()))
+    } else {
+        ("Use bool::then", format!("{}.then(|| {})", receiver, value.syntax()))
+    };
+
+    ctx.add_assist(AssistId("use_bool_then"), label, |edit| {
+        let range = if_expr.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, text);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn is_cheap(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) | ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_cheap(&it)),
+        ast::Expr::RefExpr(it) => it.expr().map_or(false, |it| is_cheap(&it)),
+        ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| is_cheap(&it)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_bool_then_some() {
+        check_assist(
+            use_bool_then,
+            "fn f(b: bool, x: S) -> Option<&u8> { <|>if b { Some(&x.y) } else { None } }",
+            "fn f(b: bool, x: S) -> Option<&u8> { <|>b.then_some(&x.y) }",
+        );
+    }
+
+    #[test]
+    fn use_bool_then_with_closure() {
+        check_assist(
+            use_bool_then,
+            r#"
+fn f(n: u32) -> Option<u32> {
+    let x = <|>if n > 1 {
+        Some(g(n))
+    } else {
+        None
+    };
+    x
+}
+"#,
+            r#"
+fn f(n: u32) -> Option<u32> {
+    let x = <|>(n > 1).then(|| g(n));
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_bool_then_target() {
+        check_assist_target(
+            use_bool_then,
+            "fn f(b: bool) { <|>if b { Some(1) } else { None }; }",
+            "if b { Some(1) } else { None }",
+        );
+    }
+
+    #[test]
+    fn use_bool_then_not_applicable() {
+        check_assist_not_applicable(
+            use_bool_then,
+            "fn f(b: bool) { <|>if b { Some(1) } else { Some(2) }; }",
+        );
+        check_assist_not_applicable(
+            use_bool_then,
+            "fn f(b: bool) { <|>if let Some(x) = y { Some(x) } else { None }; }",
+        );
+        check_assist_not_applicable(
+            use_bool_then,
+            "fn f(b: bool) -> Option<u8> { <|>if b { Some(g()?) } else { None } }",
+        );
+        // Not a `bool` condition.
+        check_assist_not_applicable(
+            use_bool_then,
+            "fn f(b: Foo) { <|>if b { Some(1) } else { None }; }",
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use hir::{db::HirDatabase, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    SmolStr,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_destructured_bindings
+//
+// Replaces the accesses to the fields of a tuple after it is destructured with
+// the bindings of its fields.
+//
+// ```
+// fn main() {
+//     let pair = (1, 2);
+//     <|>let (first, second) = pair;
+//     report(first, pair.1);
+//     let sum = pair.0 + second;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let pair = (1, 2);
+//     let (first, second) = pair;
+//     report(first, second);
+//     let sum = first + second;
+// }
+// ```
+pub(crate) fn use_destructured_bindings(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let tuple_pat = match let_stmt.pat()? {
+        ast::Pat::TuplePat(it) => it,
+        _ => return None,
+    };
+    // Bindings by position, `None` for the fields which aren't bound.
+    let mut bindings: Vec<Option<SmolStr>> = Vec::new();
+    for pat in tuple_pat.args() {
+        match pat {
+            ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => {
+                bindings.push(Some(it.name()?.text().clone()))
+            }
+            ast::Pat::DotDotPat(_) => return None,
+            _ => bindings.push(None),
+        }
+    }
+    let initializer = match let_stmt.initializer()? {
+        ast::Expr::PathExpr(it) => it,
+        _ => return None,
+    };
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    let tuple = match analyzer.resolve_path(db, &initializer.path()?)? {
+        PathResolution::Local(it) => it,
+        _ => return None,
+    };
+    let tuple_pat = tuple.source(db).value.left()?;
+    let block = let_stmt.syntax().parent()?;
+    let stmt_end = let_stmt.syntax().text_range().end();
+    let mut edits = Vec::new();
+    for reference in analyzer.find_all_refs(&tuple_pat) {
+        // The accesses before the bindings are left as they are.
+        if reference.range.start() < stmt_end {
+            continue;
+        }
+        let path_expr = block
+            .descendants()
+            .filter_map(ast::PathExpr::cast)
+            .find(|it| it.syntax().text_range() == reference.range);
+        // The tuple can't be used whole, or from a macro.
+        let field_expr = path_expr
+            .and_then(|it| it.syntax().parent())
+            .and_then(ast::FieldExpr::cast)
+            .filter(|it| it.syntax().text_range().start() == reference.range.start());
+        let field_expr = field_expr?;
+        let index = match field_expr.field_access()? {
+            ast::FieldKind::Index(it) => it.text().parse::<usize>().ok()?,
+            ast::FieldKind::Name(_) => return None,
+        };
+        if is_mutated(&field_expr) {
+            return None;
+        }
+        let name = bindings.get(index)?.clone()?;
+        edits.push((field_expr.syntax().text_range(), name));
+    }
+    if edits.is_empty() {
+        return None;
+    }
+    // The bindings mustn't be shadowed where they replace the fields.
+    let last_use = edits.iter().map(|(range, _)| range.end()).max()?;
+    let shadowed = block
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| it.syntax().text_range().start() >= stmt_end)
+        .filter(|it| it.syntax().text_range().start() < last_use)
+        .filter_map(|it| it.name())
+        .any(|name| edits.iter().any(|(_, it)| *it == *name.text()));
+    if shadowed {
+        return None;
+    }
+
+    ctx.add_assist(
+        AssistId("use_destructured_bindings"),
+        "Replace tuple fields with bindings",
+        |edit| {
+            edit.target(let_stmt.syntax().text_range());
+            for (range, name) in edits {
+                edit.replace(range, name);
+            }
+        },
+    )
+}
+
+fn is_mutated(field_expr: &ast::FieldExpr) -> bool {
+    match field_expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::RefExpr(it)) => it.is_mut(),
+        Some(ast::Expr::BinExpr(it)) => {
+            it.op_kind().map_or(false, |it| it.is_assignment())
+                && it.lhs().map_or(false, |lhs| lhs.syntax() == field_expr.syntax())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_destructured_bindings_after_let() {
+        check_assist(
+            use_destructured_bindings,
+            r#"
+fn f(t: (u8, u8, u8)) {
+    let x = t.0;
+    <|>let (a, _, mut c) = t;
+    g(t.0, t.2);
+    c += 1;
+}
+"#,
+            r#"
+fn f(t: (u8, u8, u8)) {
+    let x = t.0;
+    <|>let (a, _, mut c) = t;
+    g(a, c);
+    c += 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_destructured_bindings_target() {
+        check_assist_target(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, b) = t; g(t.1); h(); }",
+            "let (a, b) = t;",
+        );
+    }
+
+    #[test]
+    fn use_destructured_bindings_not_applicable() {
+        // Passed whole.
+        check_assist_not_applicable(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, b) = t; g(t.1); h(t); }",
+        );
+        // Not bound.
+        check_assist_not_applicable(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, _) = t; g(t.1); }",
+        );
+        check_assist_not_applicable(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, b) = t; t.0 = 1; }",
+        );
+        // `a` means something else there.
+        check_assist_not_applicable(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, b) = t; let a = 2; g(t.0); }",
+        );
+        check_assist_not_applicable(
+            use_destructured_bindings,
+            "fn f(t: (u8, u8)) { <|>let (a, b) = t; g(a, b); }",
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{utils::implements_trait, Assist, AssistCtx, AssistId};
+
+// Assist: use_direct_indexing
+//
+// Indexes a slice directly, panicking when out of bounds, instead of
+// unwrapping the result of `get`.
+//
+// ```
+// fn first(words: &[String]) -> usize {
+//     words.get(0).unwrap<|>().len()
+// }
+// ```
+// ->
+// ```
+// fn first(words: &[String]) -> usize {
+//     words[0].len()
+// }
+// ```
+pub(crate) fn use_direct_indexing(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (unwrap, get) = ctx
+        .covering_element()
+        .ancestors()
+        .filter_map(ast::MethodCallExpr::cast)
+        .find_map(|it| Some((it.clone(), get_call(&it)?)))?;
+    let receiver = get.expr()?;
+    let mut args = get.arg_list()?.args();
+    let index = match (args.next(), args.next()) {
+        (Some(it), None) => it,
+        _ => return None,
+    };
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(receiver.syntax(), None);
+    let ty = analyzer.type_of(db, &receiver)?;
+    let is_indexable = ty.autoderef(db).any(|it| {
+        it.display(db).to_string().starts_with('[') || implements_trait(db, &it, "Index")
+    });
+    if !is_indexable {
+        return None;
+    }
+
+    // `get` returns a reference, which isn't needed for derefs and receivers.
+    let parent = unwrap.syntax().parent()?;
+    let (range, is_ref) = match ast::Expr::cast(parent.clone()) {
+        Some(ast::Expr::PrefixExpr(it)) if it.op_kind() == Some(ast::PrefixOp::Deref) => {
+            (it.syntax().text_range(), false)
+        }
+        Some(ast::Expr::MethodCallExpr(it))
+            if it.expr().as_ref().map(|it| it.syntax()) == Some(unwrap.syntax()) =>
+        {
+            (unwrap.syntax().text_range(), false)
+        }
+        Some(ast::Expr::FieldExpr(_)) => (unwrap.syntax().text_range(), false),
+        _ => (unwrap.syntax().text_range(), true),
+    };
+
+    ctx.add_assist(AssistId("use_direct_indexing"), "Use direct indexing", |edit| {
+        edit.target(unwrap.syntax().text_range());
+        edit.replace(
+            range,
+            format!("{}{}[{}]", if is_ref { "&" } else { "" }, receiver.syntax(), index.syntax()),
+        );
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The `get` call of a `get(i).unwrap()` chain.
+fn get_call(unwrap: &ast::MethodCallExpr) -> Option<ast::MethodCallExpr> {
+    if unwrap.name_ref()?.text() != "unwrap" || unwrap.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let get = match unwrap.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if get.name_ref()?.text() != "get" {
+        return None;
+    }
+    Some(get)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_direct_indexing_as_reference() {
+        check_assist(
+            use_direct_indexing,
+            "fn f(xs: &[u32], i: usize) { let x = xs.get(i + 1)<|>.unwrap(); }",
+            "fn f(xs: &[u32], i: usize) { let x = <|>&xs[i + 1]; }",
+        );
+    }
+
+    #[test]
+    fn use_direct_indexing_with_deref() {
+        check_assist(
+            use_direct_indexing,
+            "struct S { xs: [u32; 4] }\nfn f(s: &S) -> u32 { *s.xs.<|>get(1).unwrap() + 1 }",
+            "struct S { xs: [u32; 4] }\nfn f(s: &S) -> u32 { <|>s.xs[1] + 1 }",
+        );
+    }
+
+    #[test]
+    fn use_direct_indexing_with_index_impl() {
+        check_assist(
+            use_direct_indexing,
+            r#"
+struct Grid;
+impl Index<usize> for Grid {}
+impl Grid { fn get(&self, i: usize) -> Option<&u8> { None } }
+fn f(g: Grid) { g.get(0).<|>unwrap().count_ones(); }
+"#,
+            r#"
+struct Grid;
+impl Index<usize> for Grid {}
+impl Grid { fn get(&self, i: usize) -> Option<&u8> { None } }
+fn f(g: Grid) { <|>g[0].count_ones(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn use_direct_indexing_target() {
+        check_assist_target(
+            use_direct_indexing,
+            "fn f(xs: &[u32]) { xs.get(0).unwrap<|>(); }",
+            "xs.get(0).unwrap()",
+        );
+    }
+
+    #[test]
+    fn use_direct_indexing_not_applicable() {
+        check_assist_not_applicable(
+            use_direct_indexing,
+            "fn f(xs: &[u32]) { xs.get(0).<|>unwrap_or(&0); }",
+        );
+        check_assist_not_applicable(
+            use_direct_indexing,
+            "fn f(xs: &[u32]) { xs.get(0).<|>expect(\"first\"); }",
+        );
+        // Not indexable.
+        check_assist_not_applicable(
+            use_direct_indexing,
+            "struct C;\nimpl C { fn get(&self, i: usize) -> Option<u8> { None } }\nfn f(c: C) { c.get(0).<|>unwrap(); }",
+        );
+    }
+}
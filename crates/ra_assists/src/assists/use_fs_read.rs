@@ -0,0 +1,244 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, make, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::COMMENT,
+    TextRange,
+};
+
+use crate::{utils::is_mutated_after, Assist, AssistCtx, AssistId};
+
+// Assist: use_fs_read
+//
+// Reads a whole file with `fs::read_to_string` or `fs::read` instead of
+// opening it and reading it into a buffer.
+//
+// ```
+// mod std { pub mod fs { pub struct File; } }
+// use std::fs::File;
+// struct String;
+// impl String { fn new() -> String { String } }
+//
+// fn load(path: &str) -> io::Result<String> {
+//     let mut <|>file = File::open(path)?;
+//     let mut text = String::new();
+//     file.read_to_string(&mut text)?;
+//     Ok(text)
+// }
+// ```
+// ->
+// ```
+// mod std { pub mod fs { pub struct File; } }
+// use std::fs::File;
+// struct String;
+// impl String { fn new() -> String { String } }
+//
+// fn load(path: &str) -> io::Result<String> {
+//     let text = std::fs::read_to_string(path)?;
+//     Ok(text)
+// }
+// ```
+pub(crate) fn use_fs_read(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let open_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let file_pat = match open_stmt.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let open_call = match try_operand(open_stmt.initializer()?)? {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    let open_path = match open_call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let mut args = open_call.arg_list()?.args();
+    let path_arg = match (args.next(), args.next()) {
+        (Some(it), None) => it,
+        _ => return None,
+    };
+    if open_path.segment()?.syntax().text() != "open" {
+        return None;
+    }
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(open_stmt.syntax(), None);
+    let file =
+        ["fs", "File"].iter().fold(make::path_from_name_ref(make::name_ref("std")), |qual, it| {
+            make::path_qualified(qual, make::name_ref(it))
+        });
+    let is_file = analyzer
+        .resolve_path(db, &open_path.qualifier()?)
+        .map_or(false, |it| analyzer.resolve_path(db, &file).map_or(false, |file| file == it));
+    if !is_file {
+        return None;
+    }
+
+    // The buffer is declared right before or after opening the file.
+    let next = open_stmt.syntax().next_sibling()?;
+    let (buf_stmt, read_stmt) = match ast::LetStmt::cast(next.clone()) {
+        Some(it) => (it, next.next_sibling().and_then(ast::ExprStmt::cast)?),
+        None => (
+            open_stmt.syntax().prev_sibling().and_then(ast::LetStmt::cast)?,
+            ast::ExprStmt::cast(next)?,
+        ),
+    };
+    let buf_pat = match buf_stmt.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let buf_ty =
+        analyzer.type_of_pat(db, &ast::Pat::from(buf_pat.clone()))?.display(db).to_string();
+    let (constructor, method, function) = match buf_ty.as_str() {
+        "String" => ("String::new()", "read_to_string", "read_to_string"),
+        it if it.starts_with("Vec<") => ("Vec::new()", "read_to_end", "read"),
+        _ => return None,
+    };
+    if buf_stmt.initializer()?.syntax().text() != constructor {
+        return None;
+    }
+
+    // Nothing else is done with the file, nor with the result of the read.
+    let read_call = match try_operand(read_stmt.expr()?)? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let mut args = read_call.arg_list()?.args();
+    let buf_arg = match (args.next(), args.next()) {
+        (Some(ast::Expr::RefExpr(it)), None) if it.is_mut() => it.expr()?,
+        _ => return None,
+    };
+    let file_name = file_pat.name()?.text().clone();
+    let buf_name = buf_pat.name()?.text().clone();
+    if read_call.name_ref()?.text() != method
+        || read_call.expr()?.syntax().text() != file_name.as_str()
+        || buf_arg.syntax().text() != buf_name.as_str()
+        || analyzer.find_all_refs(&file_pat).len() != 1
+    {
+        return None;
+    }
+    let read_start = read_stmt.syntax().text_range().start();
+    let buf_refs = analyzer.find_all_refs(&buf_pat);
+    if buf_refs.iter().any(|it| it.range.start() < read_start) {
+        return None;
+    }
+    let start = buf_stmt.syntax().text_range().start().min(open_stmt.syntax().text_range().start());
+    let range = TextRange::from_to(start, read_stmt.syntax().text_range().end());
+    let block = open_stmt.syntax().parent()?;
+    if block
+        .descendants_with_tokens()
+        .any(|it| it.kind() == COMMENT && it.text_range().is_subrange(&range))
+    {
+        return None;
+    }
+    // The buffer is only mutable while it's read into.
+    let refs = buf_refs.iter().map(|it| it.range).collect::<Vec<_>>();
+    let is_mut =
+        buf_pat.is_mut() && is_mutated_after(&block, &refs, read_stmt.syntax().text_range());
+    let binding = format!(
+        "{}{}{}",
+        if is_mut { "mut " } else { "" },
+        buf_name,
+        buf_stmt.ascribed_type().map_or(String::new(), |it| format!(": {}", it.syntax()))
+    );
+
+    ctx.add_assist(AssistId("use_fs_read"), format!("Use fs::{}", function), |edit| {
+        edit.target(range);
+        edit.replace(
+            range,
+            format!("let {} = std::fs::{}({})?;", binding, function, path_arg.syntax()),
+        );
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The operand of a `?` expression.
+fn try_operand(expr: ast::Expr) -> Option<ast::Expr> {
+    match expr {
+        ast::Expr::TryExpr(it) => it.expr(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const PRELUDE: &str = r#"mod std { pub mod fs { pub struct File; } }
+use std::fs::File;
+struct String;
+impl String { fn new() -> String { String } }
+struct Vec<T>(T);
+impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } }
+"#;
+
+    fn with_prelude(text: &str) -> String {
+        format!("{}{}", PRELUDE, text)
+    }
+
+    #[test]
+    fn use_fs_read_with_buffer_first() {
+        check_assist(
+            use_fs_read,
+            &with_prelude(
+                r#"
+fn load(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut <|>file = File::open(path.join("data"))?;
+    file.read_to_end(&mut bytes)?;
+    bytes.push(0);
+    Ok(bytes)
+}
+"#,
+            ),
+            &with_prelude(
+                r#"
+fn load(path: &Path) -> io::Result<Vec<u8>> {
+    <|>let mut bytes: Vec<u8> = std::fs::read(path.join("data"))?;
+    bytes.push(0);
+    Ok(bytes)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn use_fs_read_to_string() {
+        check_assist(
+            use_fs_read,
+            &with_prelude(
+                "fn f() -> io::Result<String> {\n    let mut <|>f = File::open(\"a\")?;\n    let mut s = String::new();\n    f.read_to_string(&mut s)?;\n    Ok(s)\n}",
+            ),
+            &with_prelude(
+                "fn f() -> io::Result<String> {\n    <|>let s = std::fs::read_to_string(\"a\")?;\n    Ok(s)\n}",
+            ),
+        );
+    }
+
+    #[test]
+    fn use_fs_read_target() {
+        check_assist_target(
+            use_fs_read,
+            &with_prelude(
+                "fn f() -> io::Result<()> { let mut <|>f = File::open(\"a\")?; let mut s = String::new(); f.read_to_string(&mut s)?; Ok(()) }",
+            ),
+            "let mut f = File::open(\"a\")?; let mut s = String::new(); f.read_to_string(&mut s)?;",
+        );
+    }
+
+    #[test]
+    fn use_fs_read_not_applicable() {
+        for before in &[
+            // The number of bytes is used.
+            "fn f() { let mut <|>f = File::open(\"a\")?; let mut s = String::new(); let n = f.read_to_string(&mut s)?; }",
+            // The file is read again.
+            "fn f() { let mut <|>f = File::open(\"a\")?; let mut s = String::new(); f.read_to_string(&mut s)?; f.seek(0); }",
+            // The buffer isn't empty.
+            "fn f() { let mut <|>f = File::open(\"a\")?; let mut s = String::from(\"a\"); f.read_to_string(&mut s)?; }",
+            "fn f() { let mut <|>f = File::open(\"a\").unwrap(); let mut s = String::new(); f.read_to_string(&mut s).unwrap(); }",
+            "fn f() { let mut <|>f = Db::open(\"a\")?; let mut s = String::new(); f.read_to_string(&mut s)?; }",
+        ] {
+            check_assist_not_applicable(use_fs_read, &with_prelude(before));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_implicit_return
+//
+// Replaces a `return Ok(());` ending a function which returns `Result<(), E>`
+// with a tail `Ok(())`.
+//
+// ```
+// fn save(path: &str) -> Result<(), Error> {
+//     write(path)?;
+//     <|>return Ok(());
+// }
+// ```
+// ->
+// ```
+// fn save(path: &str) -> Result<(), Error> {
+//     write(path)?;
+//     Ok(())
+// }
+// ```
+pub(crate) fn use_implicit_return(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let return_expr = ctx.find_node_at_offset::<ast::ReturnExpr>()?;
+    let value = return_expr.expr()?;
+    if value.syntax().text().to_string().split_whitespace().collect::<String>() != "Ok(())" {
+        return None;
+    }
+    // The `return` has to be the last thing in the body of the function.
+    let (node, block) = match return_expr.syntax().parent()? {
+        it if ast::ExprStmt::can_cast(it.kind()) => (it.clone(), ast::Block::cast(it.parent()?)?),
+        it => (return_expr.syntax().clone(), ast::Block::cast(it)?),
+    };
+    let is_last = match block.expr() {
+        Some(it) => it.syntax() == &node,
+        None => block.statements().last().map_or(false, |it| it.syntax() == &node),
+    };
+    if !is_last {
+        return None;
+    }
+    let fn_def = ast::FnDef::cast(block.syntax().parent()?.parent()?)?;
+    if !returns_unit_result(&fn_def.ret_type()?.type_ref()?) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("use_implicit_return"), "Use implicit return", |edit| {
+        let range = node.text_range();
+        edit.target(return_expr.syntax().text_range());
+        edit.replace(range, value.syntax().text().to_string());
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Whether the type is `Result<(), E>`, or an alias like `io::Result<()>`.
+fn returns_unit_result(ty: &ast::TypeRef) -> bool {
+    let segment = match ty {
+        ast::TypeRef::PathType(it) => it.path().and_then(|it| it.segment()),
+        _ => None,
+    };
+    let segment = match segment {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_result = segment.name_ref().map_or(false, |it| it.text().as_str() == "Result");
+    let ok_type =
+        segment.type_arg_list().and_then(|it| it.type_args().next()).and_then(|it| it.type_ref());
+    match ok_type {
+        Some(ast::TypeRef::TupleType(it)) => is_result && it.fields().next().is_none(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_implicit_return_for_statement() {
+        check_assist(
+            use_implicit_return,
+            r#"
+fn f() -> Result<(), String> {
+    if g() {
+        return Err(String::new());
+    }
+    ret<|>urn Ok( () );
+}
+"#,
+            r#"
+fn f() -> Result<(), String> {
+    if g() {
+        return Err(String::new());
+    }
+    <|>Ok( () )
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_implicit_return_for_tail() {
+        check_assist(
+            use_implicit_return,
+            "fn f() -> io::Result<()> { g(); <|>return Ok(()) }",
+            "fn f() -> io::Result<()> { g(); <|>Ok(()) }",
+        );
+    }
+
+    #[test]
+    fn use_implicit_return_target() {
+        check_assist_target(
+            use_implicit_return,
+            "fn f() -> Result<(), ()> { <|>return Ok(()); }",
+            "return Ok(())",
+        );
+    }
+
+    #[test]
+    fn use_implicit_return_not_applicable() {
+        // Not the last statement.
+        check_assist_not_applicable(
+            use_implicit_return,
+            "fn f() -> Result<(), ()> { <|>return Ok(()); g(); }",
+        );
+        // Not in the function body itself.
+        check_assist_not_applicable(
+            use_implicit_return,
+            "fn f() -> Result<(), ()> { loop { <|>return Ok(()); } }",
+        );
+        check_assist_not_applicable(
+            use_implicit_return,
+            "fn f() -> Result<(), ()> { if c() { <|>return Ok(()); } g() }",
+        );
+        // Not a unit result.
+        check_assist_not_applicable(
+            use_implicit_return,
+            "fn f() -> Result<u8, ()> { <|>return Ok(1); }",
+        );
+        check_assist_not_applicable(
+            use_implicit_return,
+            "fn f() -> Option<()> { <|>return Some(()); }",
+        );
+    }
+}
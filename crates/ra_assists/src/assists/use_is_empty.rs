@@ -0,0 +1,162 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_is_empty
+//
+// Replaces a comparison of the length of a collection to zero with a call to
+// `is_empty`.
+//
+// ```
+// struct Stack;
+// impl Stack {
+//     fn len(&self) -> usize { 0 }
+//     fn is_empty(&self) -> bool { true }
+// }
+//
+// fn has_items(s: &Stack) -> bool {
+//     s.len() <|>!= 0
+// }
+// ```
+// ->
+// ```
+// struct Stack;
+// impl Stack {
+//     fn len(&self) -> usize { 0 }
+//     fn is_empty(&self) -> bool { true }
+// }
+//
+// fn has_items(s: &Stack) -> bool {
+//     !s.is_empty()
+// }
+// ```
+pub(crate) fn use_is_empty(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bin_expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
+    let (lhs, rhs) = (bin_expr.lhs()?, bin_expr.rhs()?);
+    // With the length on the left, `len() > 0` means it's not empty.
+    let (len_call, len_on_left) = match (len_call(&lhs), len_call(&rhs)) {
+        (Some(it), None) if is_zero(&rhs) => (it, true),
+        (None, Some(it)) if is_zero(&lhs) => (it, false),
+        _ => return None,
+    };
+    let negated = match bin_expr.op_kind()? {
+        ast::BinOp::EqualityTest => false,
+        ast::BinOp::NegatedEqualityTest => true,
+        ast::BinOp::GreaterTest if len_on_left => true,
+        ast::BinOp::LesserTest if !len_on_left => true,
+        _ => return None,
+    };
+    let receiver = len_call.expr()?;
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(bin_expr.syntax(), None);
+    let receiver_ty = analyzer.type_of(db, &receiver)?;
+    let krate = analyzer.module()?.krate();
+    let traits_in_scope = analyzer.traits_in_scope(db);
+    receiver_ty.iterate_method_candidates(db, krate, &traits_in_scope, None, |_, func| {
+        if func.has_self_param(db) && func.name(db).to_string() == "is_empty" {
+            Some(())
+        } else {
+            None
+        }
+    })?;
+
+    let (label, text) = if negated {
+        ("Use !is_empty()", format!("!{}.is_empty()", receiver.syntax()))
+    } else {
+        ("Use is_empty()", format!("{}.is_empty()", receiver.syntax()))
+    };
+    ctx.add_assist(AssistId("use_is_empty"), label, |edit| {
+        let range = bin_expr.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, text);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn len_call(expr: &ast::Expr) -> Option<ast::MethodCallExpr> {
+    match expr {
+        ast::Expr::MethodCallExpr(it)
+            if it.name_ref()?.text().as_str() == "len"
+                && it.arg_list()?.args().next().is_none() =>
+        {
+            Some(it.clone())
+        }
+        _ => None,
+    }
+}
+
+fn is_zero(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(it) => it.syntax().text() == "0",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const STACK: &str = r#"
+struct Stack;
+impl Stack {
+    fn len(&self) -> usize { 0 }
+    fn is_empty(&self) -> bool { true }
+}
+"#;
+
+    #[test]
+    fn use_is_empty_for_equality() {
+        check_assist(
+            use_is_empty,
+            &format!("{}fn f(s: Stack) -> bool {{ s.len() ==<|> 0 }}", STACK),
+            &format!("{}fn f(s: Stack) -> bool {{ <|>s.is_empty() }}", STACK),
+        );
+    }
+
+    #[test]
+    fn use_is_empty_negated() {
+        check_assist(
+            use_is_empty,
+            &format!("{}fn f(s: &Stack, b: bool) -> bool {{ b && 0 <|>< s.len() }}", STACK),
+            &format!("{}fn f(s: &Stack, b: bool) -> bool {{ b && <|>!s.is_empty() }}", STACK),
+        );
+        check_assist(
+            use_is_empty,
+            &format!("{}fn f(s: Stack) -> bool {{ s.len() <|>> 0 }}", STACK),
+            &format!("{}fn f(s: Stack) -> bool {{ <|>!s.is_empty() }}", STACK),
+        );
+    }
+
+    #[test]
+    fn use_is_empty_target() {
+        check_assist_target(
+            use_is_empty,
+            &format!("{}fn f(s: Stack) {{ if s.len() <|>!= 0 {{}} }}", STACK),
+            "s.len() != 0",
+        );
+    }
+
+    #[test]
+    fn use_is_empty_not_applicable() {
+        check_assist_not_applicable(
+            use_is_empty,
+            &format!("{}fn f(s: Stack) -> bool {{ s.len() <|>== 1 }}", STACK),
+        );
+        check_assist_not_applicable(
+            use_is_empty,
+            &format!("{}fn f(s: Stack) -> bool {{ s.len() <|>< 0 }}", STACK),
+        );
+        // There is no `is_empty` method.
+        check_assist_not_applicable(
+            use_is_empty,
+            r#"
+struct Stack;
+impl Stack { fn len(&self) -> usize { 0 } }
+fn f(s: Stack) -> bool { s.len() <|>== 0 }
+"#,
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use format_buf::format;
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_matches_macro
+//
+// Replaces a `match` which only tells whether a pattern matches with the
+// `matches!` macro.
+//
+// ```
+// fn is_vowel(c: char) -> bool {
+//     <|>match c {
+//         'a' | 'e' | 'i' | 'o' | 'u' => true,
+//         _ => false,
+//     }
+// }
+// ```
+// ->
+// ```
+// fn is_vowel(c: char) -> bool {
+//     matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+// }
+// ```
+pub(crate) fn use_matches_macro(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let scrutinee = match_expr.expr()?;
+    let mut arms = match_expr.match_arm_list()?.arms();
+    let (arm, catch_all) = match (arms.next()?, arms.next()?, arms.next()) {
+        (first, second, None) => (first, second),
+        _ => return None,
+    };
+    let mut catch_all_pats = catch_all.pats();
+    match (catch_all_pats.next()?, catch_all_pats.next(), catch_all.guard()) {
+        (ast::Pat::PlaceholderPat(_), None, None) => (),
+        _ => return None,
+    }
+    let (value, other) = (bool_value(&arm.expr()?)?, bool_value(&catch_all.expr()?)?);
+    if value == other {
+        return None;
+    }
+
+    let mut buf = String::new();
+    if !value {
+        buf.push('!');
+    }
+    format!(buf, "matches!({}, ", scrutinee.syntax());
+    let pats = arm.pats().map(|it| it.syntax().to_string()).collect::<Vec<_>>();
+    buf.push_str(&pats.join(" | "));
+    if let Some(guard) = arm.guard() {
+        format!(buf, " if {}", guard.expr()?.syntax());
+    }
+    buf.push(')');
+    // `!` binds less tightly than a method call on the result.
+    let is_receiver = match match_expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::MethodCallExpr(_))
+        | Some(ast::Expr::FieldExpr(_))
+        | Some(ast::Expr::TryExpr(_))
+        | Some(ast::Expr::IndexExpr(_)) => true,
+        _ => false,
+    };
+    if !value && is_receiver {
+        buf = format!("({})", buf);
+    }
+
+    ctx.add_assist(AssistId("use_matches_macro"), "Use matches! macro", |edit| {
+        let range = match_expr.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+fn bool_value(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::Literal(it) => match it.syntax().text().to_string().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_matches_macro_with_guard() {
+        check_assist(
+            use_matches_macro,
+            "fn f() -> bool { <|>match x { Some(n) if n > 0 => true, _ => false } }",
+            "fn f() -> bool { <|>matches!(x, Some(n) if n > 0) }",
+        );
+    }
+
+    #[test]
+    fn use_matches_macro_inverted() {
+        check_assist(
+            use_matches_macro,
+            "fn f() { let b = <|>match e { E::A | E::B => false, _ => true }; }",
+            "fn f() { let b = <|>!matches!(e, E::A | E::B); }",
+        );
+    }
+
+    #[test]
+    fn use_matches_macro_inverted_receiver() {
+        check_assist(
+            use_matches_macro,
+            "fn f() { let b = <|>match e { E::A => false, _ => true }.then(g); }",
+            "fn f() { let b = <|>(!matches!(e, E::A)).then(g); }",
+        );
+    }
+
+    #[test]
+    fn use_matches_macro_target() {
+        check_assist_target(
+            use_matches_macro,
+            "fn f() { <|>match e { E::A => true, _ => false }; }",
+            "match e { E::A => true, _ => false }",
+        );
+    }
+
+    #[test]
+    fn use_matches_macro_not_applicable() {
+        check_assist_not_applicable(
+            use_matches_macro,
+            "fn f() { <|>match e { E::A => true, _ => true }; }",
+        );
+        check_assist_not_applicable(
+            use_matches_macro,
+            "fn f() { <|>match e { E::A => true, E::B => false }; }",
+        );
+        check_assist_not_applicable(
+            use_matches_macro,
+            "fn f() { <|>match e { E::A => true, _ if c => false }; }",
+        );
+        check_assist_not_applicable(
+            use_matches_macro,
+            "fn f() { <|>match e { E::A => true, E::B => true, _ => false }; }",
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use hir::{db::HirDatabase, ModuleDef, PathResolution};
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_mem_take
+//
+// Replaces `mem::replace` with a default value by `mem::take`.
+//
+// ```
+// struct Buffer { lines: Lines }
+// struct Lines;
+//
+// fn flush(buffer: &mut Buffer) -> Lines {
+//     mem::replace<|>(&mut buffer.lines, Lines::default())
+// }
+// ```
+// ->
+// ```
+// struct Buffer { lines: Lines }
+// struct Lines;
+//
+// fn flush(buffer: &mut Buffer) -> Lines {
+//     mem::take(&mut buffer.lines)
+// }
+// ```
+pub(crate) fn use_mem_take(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::CallExpr>()?;
+    let path = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if path.segment()?.syntax().text() != "replace" {
+        return None;
+    }
+    let prefix = match path.qualifier() {
+        Some(it) => match it.syntax().to_string().as_str() {
+            "mem" | "std::mem" | "core::mem" | "::std::mem" | "::core::mem" => format!("{}::", it),
+            _ => return None,
+        },
+        None => "std::mem::".to_string(),
+    };
+    let mut args = call.arg_list()?.args();
+    let (dest, value) = match (args.next()?, args.next()?, args.next()) {
+        (ast::Expr::RefExpr(dest), ast::Expr::CallExpr(value), None) if dest.is_mut() => {
+            (dest, value)
+        }
+        _ => return None,
+    };
+    if value.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let default_path = match value.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if default_path.segment()?.syntax().text() != "default" {
+        return None;
+    }
+    let ty = default_path.qualifier()?;
+
+    // `T::default()` has to be the default of the type of the destination.
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    let is_default = match ty.syntax().to_string().as_str() {
+        "Default" | "std::default::Default" | "core::default::Default" => true,
+        ty_text => {
+            let dest_ty = analyzer.type_of(db, &dest.expr()?)?;
+            match analyzer.resolve_path(db, &ty) {
+                Some(PathResolution::Def(ModuleDef::Adt(adt))) => dest_ty.as_adt() == Some(adt),
+                Some(PathResolution::SelfType(impl_block)) => {
+                    let adt = impl_block.target_ty(db).as_adt();
+                    adt.is_some() && dest_ty.as_adt() == adt
+                }
+                _ => dest_ty.display(db).to_string() == ty_text,
+            }
+        }
+    };
+    if !is_default {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("use_mem_take"), "Use mem::take", |edit| {
+        edit.target(call.syntax().text_range());
+        edit.replace(call.syntax().text_range(), format!("{}take({})", prefix, dest.syntax()));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_mem_take_with_default_trait() {
+        check_assist(
+            use_mem_take,
+            "fn f(v: &mut Vec<u8>) { let old = <|>std::mem::replace(&mut *v, Default::default()); }",
+            "fn f(v: &mut Vec<u8>) { let old = <|>std::mem::take(&mut *v); }",
+        );
+        check_assist(
+            use_mem_take,
+            "fn f(mut x: u32) { replace(&mut x, u32::default())<|>; }",
+            "fn f(mut x: u32) { std::mem::take(&mut x)<|>; }",
+        );
+    }
+
+    #[test]
+    fn use_mem_take_with_self() {
+        check_assist(
+            use_mem_take,
+            r#"
+struct Items(Vec<u8>);
+impl Items {
+    fn reset(&mut self) -> Items {
+        <|>mem::replace(&mut *self, Self::default())
+    }
+}
+"#,
+            r#"
+struct Items(Vec<u8>);
+impl Items {
+    fn reset(&mut self) -> Items {
+        <|>mem::take(&mut *self)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_mem_take_target() {
+        check_assist_target(
+            use_mem_take,
+            "fn f(mut x: u8) { <|>mem::replace(&mut x, Default::default()); }",
+            "mem::replace(&mut x, Default::default())",
+        );
+    }
+
+    #[test]
+    fn use_mem_take_not_applicable() {
+        check_assist_not_applicable(
+            use_mem_take,
+            "fn f(mut x: u8) { <|>mem::replace(&mut x, 1); }",
+        );
+        check_assist_not_applicable(
+            use_mem_take,
+            "struct A; struct B; fn f(mut a: A) { <|>mem::replace(&mut a, B::default()); }",
+        );
+        check_assist_not_applicable(
+            use_mem_take,
+            "fn f(mut x: u8) { <|>ptr::replace(&mut x, Default::default()); }",
+        );
+    }
+}
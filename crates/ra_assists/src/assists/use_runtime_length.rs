@@ -0,0 +1,298 @@
+use hir::{db::HirDatabase, InFile};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner, VisibilityOwner},
+    Direction, SyntaxElement,
+    SyntaxKind::{ARRAY_EXPR, ARRAY_TYPE, CONST_DEF, IDENT, STATIC_DEF, TYPE_ARG_LIST},
+    SyntaxNode, TextRange, T,
+};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{find_calls, local_function},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: use_runtime_length
+//
+// Replaces a const generic array length with the length of a slice.
+//
+// ```
+// fn sum<const N: usize>(<|>values: [u32; N]) -> u32 {
+//     let mut total = 0;
+//     for i in 0..N {
+//         total += values[i];
+//     }
+//     total
+// }
+// ```
+// ->
+// ```
+// fn sum(values: &[u32]) -> u32 {
+//     let mut total = 0;
+//     for i in 0..values.len() {
+//         total += values[i];
+//     }
+//     total
+// }
+// ```
+pub(crate) fn use_runtime_length(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let type_param_list = fn_def.type_param_list()?;
+    let mut const_params = type_param_list.syntax().children().filter_map(ast::ConstParam::cast);
+    let const_param = match (const_params.next()?, const_params.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    match const_param.ascribed_type()? {
+        ast::TypeRef::PathType(it) if it.syntax().text() == "usize" => (),
+        _ => return None,
+    }
+    let len_name = const_param.name()?.text().clone();
+
+    // The only parameter with the length in its type has to be an array, or a
+    // shared reference to one.
+    let params = fn_def.param_list()?.params().collect::<Vec<_>>();
+    let mut arrays = params.iter().enumerate().filter_map(|(idx, param)| {
+        let (array, by_ref) = match param.ascribed_type()? {
+            ast::TypeRef::ArrayType(it) => (it, false),
+            ast::TypeRef::ReferenceType(it) if !it.is_mut() => match it.type_ref()? {
+                ast::TypeRef::ArrayType(it) => (it, true),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if array.expr()?.syntax().text() != len_name.as_str() {
+            return None;
+        }
+        Some((idx, param, array, by_ref))
+    });
+    let (idx, param, array, by_ref) = match (arrays.next()?, arrays.next()) {
+        (it, None) => it,
+        _ => return None,
+    };
+    let bind_pat = match param.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    // Callers outside of the module can't be updated to pass a reference.
+    if !by_ref && fn_def.visibility().is_some() {
+        return None;
+    }
+    let array_name = bind_pat.name()?.text().clone();
+    let elem_ty = array.type_ref()?;
+
+    let mut edits = Vec::new();
+    let array_len = array.expr()?.syntax().text_range();
+    let db = ctx.db;
+    for token in fn_def.syntax().descendants_with_tokens().filter_map(|it| it.into_token()) {
+        if token.kind() != IDENT || token.text() != &len_name {
+            continue;
+        }
+        let range = token.text_range();
+        if range.is_subrange(&const_param.syntax().text_range()) || range.is_subrange(&array_len) {
+            continue;
+        }
+        // Only the value of the length is known at runtime, it can't be used
+        // in types, array repeats or constants.
+        let path_expr = token.parent().ancestors().find_map(ast::PathExpr::cast)?;
+        if path_expr.syntax().text() != len_name.as_str()
+            || !path_expr.syntax().text_range().is_subrange(&body.syntax().text_range())
+        {
+            return None;
+        }
+        let needs_constant = path_expr
+            .syntax()
+            .ancestors()
+            .take_while(|it| it != body.syntax())
+            .any(|it| [ARRAY_EXPR, ARRAY_TYPE, CONST_DEF, STATIC_DEF].contains(&it.kind()));
+        if needs_constant {
+            return None;
+        }
+        // Anything the name resolves to is not the const parameter.
+        let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+        if analyzer.resolve_path(db, &path_expr.path()?).is_some() {
+            return None;
+        }
+        edits.push((path_expr.syntax().text_range(), format!("{}.len()", array_name)));
+    }
+
+    // By-value callers in child modules of other files couldn't be updated.
+    let function = if by_ref {
+        ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), fn_def.clone()))?
+    } else {
+        local_function(&ctx, &fn_def)?.0
+    };
+    let root = fn_def.syntax().ancestors().last()?;
+    for call in find_calls(&ctx, &root, function)? {
+        // The length can't be passed explicitly anymore.
+        let call_expr = call.args.first()?.syntax().parent()?.parent()?;
+        let has_type_args = match ast::CallExpr::cast(call_expr.clone()) {
+            Some(it) => it.expr()?.syntax().descendants().any(|it| it.kind() == TYPE_ARG_LIST),
+            None => ast::MethodCallExpr::cast(call_expr)?.type_arg_list().is_some(),
+        };
+        if has_type_args {
+            return None;
+        }
+        if by_ref {
+            continue;
+        }
+        let arg = call.args.get(call.offset + idx)?;
+        let text = format!("&{}", ExprPrecedence::Prefix.parenthesize(arg));
+        edits.push((arg.syntax().text_range(), text));
+    }
+    if !by_ref {
+        let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+        for reference in analyzer.find_all_refs(&bind_pat) {
+            let path_expr = ctx
+                .covering_node_for_range(reference.range)
+                .ancestors()
+                .find_map(ast::PathExpr::cast)?;
+            if !is_borrowed(&path_expr) {
+                return None;
+            }
+        }
+    }
+
+    ctx.add_assist(AssistId("use_runtime_length"), "Use runtime length", |edit| {
+        edit.target(const_param.syntax().text_range());
+        edit.delete(removal_range(&type_param_list, const_param.syntax()));
+        let slice = format!("[{}]", elem_ty.syntax());
+        if by_ref {
+            edit.replace(array.syntax().text_range(), slice);
+        } else {
+            edit.replace(array.syntax().text_range(), format!("&{}", slice));
+        }
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+    })
+}
+
+/// Whether a use of the array is fine with a slice in its place.
+fn is_borrowed(path_expr: &ast::PathExpr) -> bool {
+    let parent = match path_expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(index_expr) = ast::IndexExpr::cast(parent.clone()) {
+        return index_expr.base().map_or(false, |it| it.syntax() == path_expr.syntax());
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        return call.expr().map_or(false, |it| it.syntax() == path_expr.syntax());
+    }
+    false
+}
+
+/// Returns the range to delete for the parameter, with its comma, or the whole
+/// list if it is the only parameter.
+fn removal_range(type_param_list: &ast::TypeParamList, param: &SyntaxNode) -> TextRange {
+    if type_param_list.syntax().children().count() == 1 {
+        return type_param_list.syntax().text_range();
+    }
+    let is_separator = |it: &SyntaxElement| it.kind().is_trivia() || it.kind() == T![,];
+    let range = param.text_range();
+    if param.next_sibling().is_some() {
+        let after = param.siblings_with_tokens(Direction::Next).skip(1).take_while(is_separator);
+        let end = after.last().map_or(range.end(), |it| it.text_range().end());
+        TextRange::from_to(range.start(), end)
+    } else {
+        let before = param.siblings_with_tokens(Direction::Prev).skip(1).take_while(is_separator);
+        let start = before.last().map_or(range.start(), |it| it.text_range().start());
+        TextRange::from_to(start, range.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn use_runtime_length_updates_calls() {
+        check_assist(
+            use_runtime_length,
+            r#"
+fn first<T: Copy, const N: usize>(<|>items: [T; N]) -> Option<T> {
+    if N > 0 { Some(items[0]) } else { None }
+}
+fn main() {
+    first([1, 2]);
+    first(make());
+}
+"#,
+            r#"
+fn first<T: Copy>(<|>items: &[T]) -> Option<T> {
+    if items.len() > 0 { Some(items[0]) } else { None }
+}
+fn main() {
+    first(&[1, 2]);
+    first(&make());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_runtime_length_for_reference() {
+        check_assist(
+            use_runtime_length,
+            "pub fn <|>f<const N: usize>(a: &[u8; N], n: u8) -> usize { a.len() + N }",
+            "pub fn <|>f(a: &[u8], n: u8) -> usize { a.len() + a.len() }",
+        );
+    }
+
+    #[test]
+    fn use_runtime_length_target() {
+        check_assist_target(
+            use_runtime_length,
+            "fn <|>f<const N: usize>(a: &[u8; N]) {}",
+            "const N: usize",
+        );
+    }
+
+    #[test]
+    fn use_runtime_length_not_applicable() {
+        // Building an array of the same length.
+        check_assist_not_applicable(
+            use_runtime_length,
+            "fn <|>f<const N: usize>(a: &[u8; N]) { let b = [0u8; N]; }",
+        );
+        // The length is used in a type.
+        check_assist_not_applicable(
+            use_runtime_length,
+            "fn <|>f<const N: usize>(a: &[u8; N]) -> [u8; N] { *a }",
+        );
+        // The array is moved.
+        check_assist_not_applicable(
+            use_runtime_length,
+            "fn <|>f<const N: usize>(a: [u8; N]) { g(a); }",
+        );
+        // Callers in other crates can't be updated.
+        check_assist_not_applicable(
+            use_runtime_length,
+            "pub fn <|>f<const N: usize>(a: [u8; N]) {}\nfn g() { f([1]); }",
+        );
+    }
+
+    #[test]
+    fn use_runtime_length_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            use_runtime_length,
+            r#"
+//- /main.rs
+mod tests;
+fn <|>sum<const N: usize>(a: [u8; N]) -> usize { N }
+
+//- /tests.rs
+fn f() { super::sum([1, 2]); }
+"#,
+        );
+    }
+}
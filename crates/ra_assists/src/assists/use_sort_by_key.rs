@@ -0,0 +1,189 @@
+use hir::{db::HirDatabase, HirDisplay};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    SyntaxKind::{COMMENT, IDENT, WHITESPACE},
+    SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_sort_by_key
+//
+// Replaces sorting with a closure comparing the same key of both elements
+// with sorting by that key.
+//
+// ```
+// struct Person { age: u32 }
+//
+// fn sort(people: &mut [Person]) {
+//     people.<|>sort_by(|a: &Person, b: &Person| a.age.cmp(&b.age));
+// }
+// ```
+// ->
+// ```
+// struct Person { age: u32 }
+//
+// fn sort(people: &mut [Person]) {
+//     people.sort_by_key(|x| x.age);
+// }
+// ```
+pub(crate) fn use_sort_by_key(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    let method = match name_ref.text().as_str() {
+        "sort_by" => "sort_by_key",
+        "sort_unstable_by" => "sort_unstable_by_key",
+        _ => return None,
+    };
+    let mut args = call.arg_list()?.args();
+    let lambda = match (args.next()?, args.next()) {
+        (ast::Expr::LambdaExpr(it), None) => it,
+        _ => return None,
+    };
+    let mut params = lambda.param_list()?.params();
+    let (a, b) = match (params.next()?.pat()?, params.next()?.pat()?, params.next()) {
+        (ast::Pat::BindPat(a), ast::Pat::BindPat(b), None) => (a.name()?, b.name()?),
+        _ => return None,
+    };
+
+    // `a.key.cmp(&b.key)`, with the same key on both sides. Comparing `b` to
+    // `a` would sort in reverse.
+    let cmp_call = match lambda.body()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if cmp_call.name_ref()?.text().as_str() != "cmp" {
+        return None;
+    }
+    let lhs = cmp_call.expr()?;
+    let mut cmp_args = cmp_call.arg_list()?.args();
+    let rhs = match (cmp_args.next()?, cmp_args.next()) {
+        (ast::Expr::RefExpr(it), None) if !it.is_mut() => it.expr()?,
+        _ => return None,
+    };
+    let (a, b) = (a.text().as_str(), b.text().as_str());
+    let lhs_key = key_tokens(lhs.syntax(), a, b)?;
+    if lhs_key != key_tokens(rhs.syntax(), b, a)? || !lhs_key.iter().any(|it| it.is_none()) {
+        return None;
+    }
+
+    // The key is returned by value, so it has to be a copy which can be
+    // ordered.
+    let analyzer = ctx.source_analyzer(lhs.syntax(), None);
+    let key_ty = analyzer.type_of(ctx.db, &lhs)?.display(ctx.db).to_string();
+    if !is_copy_ord(&key_ty) {
+        return None;
+    }
+    let param =
+        if lhs_key.iter().any(|it| it.as_ref().map(String::as_str) == Some("x")) { a } else { "x" };
+    let mut key = String::new();
+    for token in lhs.syntax().descendants_with_tokens().filter_map(|it| it.into_token()) {
+        if token.kind() == IDENT && token.text() == a {
+            key.push_str(param);
+        } else {
+            key.push_str(token.text());
+        }
+    }
+
+    ctx.add_assist(AssistId("use_sort_by_key"), "Use sort_by_key", |edit| {
+        let range = TextRange::from_to(
+            name_ref.syntax().text_range().start(),
+            call.syntax().text_range().end(),
+        );
+        edit.target(call.syntax().text_range());
+        edit.replace(range, format!("{}(|{}| {})", method, param, key));
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Returns the tokens of the key expression, with `None` for the element
+/// itself. Returns `None` if the expression uses the other element.
+fn key_tokens(node: &SyntaxNode, this: &str, other: &str) -> Option<Vec<Option<String>>> {
+    let mut res = Vec::new();
+    for token in node.descendants_with_tokens().filter_map(|it| it.into_token()) {
+        match token.kind() {
+            WHITESPACE | COMMENT => continue,
+            IDENT if token.text() == this => res.push(None),
+            IDENT if token.text() == other => return None,
+            _ => res.push(Some(token.text().to_string())),
+        }
+    }
+    Some(res)
+}
+
+fn is_copy_ord(ty: &str) -> bool {
+    match ty {
+        "bool" | "char" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "u128" | "usize" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_sort_by_key_with_method() {
+        check_assist(
+            use_sort_by_key,
+            r#"
+struct P { x: u8 }
+impl P { fn id(&self) -> i64 { 0 } }
+fn f(v: &mut [P]) {
+    v.<|>sort_unstable_by(|l: &P, r: &P| l.id().cmp(&r.id()));
+}
+"#,
+            r#"
+struct P { x: u8 }
+impl P { fn id(&self) -> i64 { 0 } }
+fn f(v: &mut [P]) {
+    v.<|>sort_unstable_by_key(|x| x.id());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_sort_by_key_keeps_name() {
+        check_assist(
+            use_sort_by_key,
+            "struct P { x: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| a.x.cmp(&b.x)); }",
+            "struct P { x: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by_key(|a| a.x); }",
+        );
+    }
+
+    #[test]
+    fn use_sort_by_key_target() {
+        check_assist_target(
+            use_sort_by_key,
+            "struct P { k: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| a.k.cmp(&b.k)); }",
+            "v.sort_by(|a: &P, b: &P| a.k.cmp(&b.k))",
+        );
+    }
+
+    #[test]
+    fn use_sort_by_key_not_applicable() {
+        // Reversed.
+        check_assist_not_applicable(
+            use_sort_by_key,
+            "struct P { k: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| b.k.cmp(&a.k)); }",
+        );
+        // Different keys.
+        check_assist_not_applicable(
+            use_sort_by_key,
+            "struct P { k: u8, j: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| a.k.cmp(&b.j)); }",
+        );
+        // Several keys.
+        check_assist_not_applicable(
+            use_sort_by_key,
+            "struct P { k: u8, j: u8 }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| a.k.cmp(&b.k).then(a.j.cmp(&b.j))); }",
+        );
+        // A key which isn't `Copy`.
+        check_assist_not_applicable(
+            use_sort_by_key,
+            "struct N; struct P { k: N }\nfn f(v: &mut [P]) { v.<|>sort_by(|a: &P, b: &P| a.k.cmp(&b.k)); }",
+        );
+    }
+}
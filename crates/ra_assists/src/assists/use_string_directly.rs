@@ -0,0 +1,264 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::{BYTE, BYTE_STRING, IDENT},
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: use_string_directly
+//
+// Builds a string with a `String` rather than with the bytes of a `Vec<u8>`
+// converted at the end.
+//
+// ```
+// struct Vec<T>(T);
+//
+// fn greeting(name: &str) -> String {
+//     let mut <|>buf: Vec<u8> = Vec::new();
+//     buf.extend_from_slice(b"Hello, ");
+//     buf.extend_from_slice(name.as_bytes());
+//     buf.push(b'!');
+//     String::from_utf8(buf).unwrap()
+// }
+// ```
+// ->
+// ```
+// struct Vec<T>(T);
+//
+// fn greeting(name: &str) -> String {
+//     let mut buf: String = String::new();
+//     buf.push_str("Hello, ");
+//     buf.push_str(name);
+//     buf.push('!');
+//     buf
+// }
+// ```
+pub(crate) fn use_string_directly(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let bind_pat = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let init = let_stmt.initializer()?;
+    if ctx.frange.range.start() >= init.syntax().text_range().start() {
+        return None;
+    }
+    let new_init = string_init(&init)?;
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    let ty = analyzer.type_of_pat(db, &ast::Pat::from(bind_pat.clone()))?;
+    if ty.display(db).to_string() != "Vec<u8>" {
+        return None;
+    }
+    // Uses inside of macro calls, like `write!`, aren't found.
+    let name = bind_pat.name()?.text().clone();
+    let fn_def = let_stmt.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let in_macro = fn_def
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .filter_map(|it| it.token_tree())
+        .flat_map(|it| it.syntax().descendants_with_tokens())
+        .any(|it| it.kind() == IDENT && it.as_token().map_or(false, |it| *it.text() == name));
+    if in_macro {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let mut is_converted = false;
+    for reference in analyzer.find_all_refs(&bind_pat) {
+        let usage = ctx.covering_node_for_range(reference.range);
+        let path_expr = usage.ancestors().find_map(ast::PathExpr::cast)?;
+        let parent = path_expr.syntax().parent()?;
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            edits.extend(rewrite_method_call(&call)?);
+        } else {
+            // `String::from_utf8(buf).unwrap()` is the string itself.
+            let call = ast::ArgList::cast(parent)
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::CallExpr::cast)?;
+            match call.expr()? {
+                ast::Expr::PathExpr(it) if it.syntax().text() == "String::from_utf8" => (),
+                _ => return None,
+            }
+            let unwrap = call.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+            match unwrap.name_ref()?.text().as_str() {
+                "unwrap" | "expect" => (),
+                _ => return None,
+            }
+            edits.push((unwrap.syntax().text_range(), path_expr.syntax().to_string()));
+            is_converted = true;
+        }
+    }
+    if !is_converted {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("use_string_directly"), "Use String directly", |edit| {
+        edit.target(let_stmt.syntax().text_range());
+        if let Some(type_ref) = let_stmt.ascribed_type() {
+            edit.replace(type_ref.syntax().text_range(), "String");
+        }
+        edit.replace(init.syntax().text_range(), new_init);
+        for (range, text) in edits {
+            edit.replace(range, text);
+        }
+    })
+}
+
+/// The `String` constructor for an empty `Vec`.
+fn string_init(init: &ast::Expr) -> Option<String> {
+    match init {
+        ast::Expr::CallExpr(call) => {
+            let mut args = call.arg_list()?.args();
+            let path = call.expr()?.syntax().to_string();
+            match (path.as_str(), args.next(), args.next()) {
+                ("Vec::new", None, None) => Some("String::new()".to_string()),
+                ("Vec::with_capacity", Some(capacity), None) => {
+                    Some(format!("String::with_capacity({})", capacity.syntax()))
+                }
+                _ => None,
+            }
+        }
+        ast::Expr::MacroCall(call) => {
+            let tokens = call.token_tree()?.syntax().text().to_string();
+            let is_empty = call.path()?.syntax().text() == "vec"
+                && tokens.split_whitespace().collect::<String>() == "[]";
+            if is_empty {
+                Some("String::new()".to_string())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The edits of a method call on the vector, if the bytes it pushes are
+/// characters or strings.
+fn rewrite_method_call(call: &ast::MethodCallExpr) -> Option<Vec<(TextRange, String)>> {
+    let name_ref = call.name_ref()?;
+    let mut args = call.arg_list()?.args();
+    let arg = match (args.next(), args.next()) {
+        (arg, None) => arg,
+        _ => return None,
+    };
+    let edits = match (name_ref.text().as_str(), arg) {
+        ("len", None) | ("is_empty", None) | ("clear", None) | ("reserve", Some(_)) => vec![],
+        ("push", Some(ast::Expr::Literal(literal))) => {
+            let token = literal.syntax().first_token()?;
+            if token.kind() != BYTE || token.text().contains("\\x") {
+                return None;
+            }
+            vec![(token.text_range(), token.text()[1..].to_string())]
+        }
+        ("extend_from_slice", Some(arg)) => {
+            let text = match arg {
+                ast::Expr::Literal(literal) => {
+                    let token = literal.syntax().first_token()?;
+                    if token.kind() != BYTE_STRING || token.text().contains("\\x") {
+                        return None;
+                    }
+                    token.text()[1..].to_string()
+                }
+                ast::Expr::MethodCallExpr(as_bytes) => {
+                    let no_args = as_bytes.arg_list()?.args().next().is_none();
+                    if as_bytes.name_ref()?.text() != "as_bytes" || !no_args {
+                        return None;
+                    }
+                    as_bytes.expr()?.syntax().to_string()
+                }
+                _ => return None,
+            };
+            let arg_range = call.arg_list()?.syntax().text_range();
+            vec![
+                (name_ref.syntax().text_range(), "push_str".to_string()),
+                (arg_range, format!("({})", text)),
+            ]
+        }
+        _ => return None,
+    };
+    Some(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_string_directly_with_capacity() {
+        check_assist(
+            use_string_directly,
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> { fn with_capacity(n: usize) -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+fn line(words: &[&str]) -> String {
+    let mut <|>out = Vec::with_capacity(words.len());
+    for word in words {
+        if !out.is_empty() {
+            out.push(b' ');
+        }
+        out.extend_from_slice(word.as_bytes());
+    }
+    out.push(b'\n');
+    String::from_utf8(out).expect("ascii")
+}
+"#,
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> { fn with_capacity(n: usize) -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+fn line(words: &[&str]) -> String {
+    let mut <|>out = String::with_capacity(words.len());
+    for word in words {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out.push('\n');
+    out
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_string_directly_target() {
+        check_assist_target(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f() -> String { let <|>v: Vec<u8> = vec![]; String::from_utf8(v).unwrap() }",
+            "let v: Vec<u8> = vec![];",
+        );
+    }
+
+    #[test]
+    fn use_string_directly_not_applicable() {
+        // Raw bytes.
+        check_assist_not_applicable(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f(b: u8) -> String { let mut <|>v: Vec<u8> = Vec::new(); v.push(b); String::from_utf8(v).unwrap() }",
+        );
+        check_assist_not_applicable(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f() -> String { let mut <|>v: Vec<u8> = Vec::new(); v.push(b'\\xff'); String::from_utf8(v).unwrap() }",
+        );
+        // Written to.
+        check_assist_not_applicable(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f() -> String { let mut <|>v: Vec<u8> = Vec::new(); write!(v, \"a\"); String::from_utf8(v).unwrap() }",
+        );
+        // Not converted.
+        check_assist_not_applicable(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f() -> Vec<u8> { let mut <|>v: Vec<u8> = Vec::new(); v.push(b'a'); v }",
+        );
+        // The conversion is fallible.
+        check_assist_not_applicable(
+            use_string_directly,
+            "struct Vec<T>(T);\nfn f() -> Result<String, E> { let <|>v: Vec<u8> = Vec::new(); Ok(String::from_utf8(v)?) }",
+        );
+    }
+}
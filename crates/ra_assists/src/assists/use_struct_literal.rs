@@ -0,0 +1,227 @@
+use format_buf::format;
+use hir::{db::HirDatabase, Adt};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{
+    utils::{implements_trait, is_mutated_after},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: use_struct_literal
+//
+// Replaces a struct created with `default()` and then filled in field by field
+// with a struct literal.
+//
+// ```
+// #[derive(Default)]
+// struct Config { verbose: bool, level: u8 }
+//
+// fn main() {
+//     let mut <|>config: Config = Default::default();
+//     config.verbose = true;
+//     config.level = 3;
+//     run(config);
+// }
+// ```
+// ->
+// ```
+// #[derive(Default)]
+// struct Config { verbose: bool, level: u8 }
+//
+// fn main() {
+//     let config = Config { verbose: true, level: 3, ..Default::default() };
+//     run(config);
+// }
+// ```
+pub(crate) fn use_struct_literal(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let var = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) if it.is_mutable() && !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let init = match let_stmt.initializer()? {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    let callee = match init.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if callee.segment()?.name_ref()?.text().as_str() != "default"
+        || init.arg_list()?.args().next().is_some()
+    {
+        return None;
+    }
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    let ty = analyzer.type_of_pat(db, &ast::Pat::from(var.clone()))?;
+    match ty.as_adt() {
+        Some(Adt::Struct(_)) if implements_trait(db, &ty, "Default") => (),
+        _ => return None,
+    }
+    // The path of the literal, from `Foo::default()` or from `let a: Foo`.
+    let struct_path = match callee.qualifier() {
+        Some(it) if it.syntax().text() != "Default" => it.syntax().to_string(),
+        _ => match let_stmt.ascribed_type()? {
+            ast::TypeRef::PathType(it) => it.path()?.syntax().to_string(),
+            _ => return None,
+        },
+    };
+    if struct_path.contains('<') {
+        return None;
+    }
+
+    let refs = analyzer.find_all_refs(&var).into_iter().map(|it| it.range).collect::<Vec<_>>();
+    let mut fields = Vec::<(String, ast::Expr)>::new();
+    let mut end = let_stmt.syntax().text_range().end();
+    let mut next = let_stmt.syntax().next_sibling();
+    while let Some(stmt) = next.clone().and_then(ast::ExprStmt::cast) {
+        let (name, value) = match field_assignment(&stmt.expr()?, &refs) {
+            Some(it) => it,
+            None => break,
+        };
+        // The struct can't be read before it is complete.
+        let value_range = value.syntax().text_range();
+        if refs.iter().any(|it| it.is_subrange(&value_range))
+            || fields.iter().any(|(it, _)| *it == name)
+        {
+            break;
+        }
+        fields.push((name, value));
+        end = stmt.syntax().text_range().end();
+        next = stmt.syntax().next_sibling();
+    }
+    if fields.is_empty() {
+        return None;
+    }
+    let range = TextRange::from_to(let_stmt.syntax().text_range().start(), end);
+    let block = let_stmt.syntax().parent()?;
+
+    ctx.add_assist(AssistId("use_struct_literal"), "Use struct literal", |edit| {
+        let mut buf = String::new();
+        let mut_kw = if is_mutated_after(&block, &refs, range) { "mut " } else { "" };
+        let name = var.name().map(|it| it.text().to_string()).unwrap_or_default();
+        format!(buf, "let {}{} = {} {{ ", mut_kw, name, struct_path);
+        for (field, value) in &fields {
+            if value.syntax().text() == field.as_str() {
+                format!(buf, "{}, ", field);
+            } else {
+                format!(buf, "{}: {}, ", field, value.syntax());
+            }
+        }
+        buf.push_str("..Default::default() };");
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Matches `var.field = value`, returning the field and the value.
+fn field_assignment(expr: &ast::Expr, refs: &[TextRange]) -> Option<(String, ast::Expr)> {
+    let bin_expr = match expr {
+        ast::Expr::BinExpr(it) if it.op_kind()? == ast::BinOp::Assignment => it,
+        _ => return None,
+    };
+    let field_expr = match bin_expr.lhs()? {
+        ast::Expr::FieldExpr(it) => it,
+        _ => return None,
+    };
+    match field_expr.expr()? {
+        ast::Expr::PathExpr(it) if refs.contains(&it.syntax().text_range()) => (),
+        _ => return None,
+    }
+    let name = match field_expr.field_access()? {
+        ast::FieldKind::Name(it) => it.text().to_string(),
+        ast::FieldKind::Index(_) => return None,
+    };
+    Some((name, bin_expr.rhs()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_struct_literal_with_default_trait() {
+        check_assist(
+            use_struct_literal,
+            r#"
+trait Default { fn default() -> Self; }
+struct Foo { a: u8, b: u8, c: u8 }
+impl Default for Foo { fn default() -> Self { loop {} } }
+fn f(b: u8) {
+    let mut <|>foo = Foo::default();
+    foo.a = 1;
+    foo.b = b;
+    if b > 1 {
+        foo.c = 2;
+    }
+    g(foo);
+}
+"#,
+            r#"
+trait Default { fn default() -> Self; }
+struct Foo { a: u8, b: u8, c: u8 }
+impl Default for Foo { fn default() -> Self { loop {} } }
+fn f(b: u8) {
+    <|>let mut foo = Foo { a: 1, b, ..Default::default() };
+    if b > 1 {
+        foo.c = 2;
+    }
+    g(foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_struct_literal_with_ascription() {
+        check_assist(
+            use_struct_literal,
+            r#"
+#[derive(Default)]
+struct Foo { a: u8, b: u8 }
+fn f() {
+    let mut <|>foo: Foo = Default::default();
+    foo.a = 1;
+    foo.b = foo.a;
+}
+"#,
+            r#"
+#[derive(Default)]
+struct Foo { a: u8, b: u8 }
+fn f() {
+    <|>let mut foo = Foo { a: 1, ..Default::default() };
+    foo.b = foo.a;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_struct_literal_target() {
+        check_assist_target(
+            use_struct_literal,
+            "#[derive(Default)]\nstruct Foo { a: u8 }\nfn f() { let mut <|>x: Foo = Default::default(); x.a = 1; }",
+            "let mut x: Foo = Default::default(); x.a = 1;",
+        );
+    }
+
+    #[test]
+    fn use_struct_literal_not_applicable() {
+        // No `Default` impl.
+        check_assist_not_applicable(
+            use_struct_literal,
+            "struct Foo { a: u8 }\nfn f() { let mut <|>x: Foo = Default::default(); x.a = 1; }",
+        );
+        // No assignments.
+        check_assist_not_applicable(
+            use_struct_literal,
+            "#[derive(Default)]\nstruct Foo { a: u8 }\nfn f() { let mut <|>x: Foo = Default::default(); g(&x); x.a = 1; }",
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{utils::path_name, Assist, AssistCtx, AssistId};
+
+// Assist: use_to_vec
+//
+// Replaces collecting the cloned elements of a slice into a `Vec` with
+// `to_vec`, or with `clone` for a `Vec`.
+//
+// ```
+// struct Vec<T>(T);
+//
+// fn keys(keys: &[u32]) -> Vec<u32> {
+//     keys.iter().cloned().<|>collect::<Vec<_>>()
+// }
+// ```
+// ->
+// ```
+// struct Vec<T>(T);
+//
+// fn keys(keys: &[u32]) -> Vec<u32> {
+//     keys.to_vec()
+// }
+// ```
+pub(crate) fn use_to_vec(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let collect_call = ctx
+        .find_node_at_offset::<ast::MethodCallExpr>()?
+        .syntax()
+        .ancestors()
+        .take_while(|it| ast::MethodCallExpr::can_cast(it.kind()))
+        .filter_map(ast::MethodCallExpr::cast)
+        .find(|it| is_call_to(it, &["collect"]))?;
+    let cloned_call = receiver_call(&collect_call, &["cloned", "copied"])?;
+    let iter_call = receiver_call(&cloned_call, &["iter"])?;
+    let receiver = iter_call.expr()?;
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(collect_call.syntax(), None);
+    let into_vec = match collect_call.type_arg_list() {
+        Some(type_args) => type_args
+            .type_args()
+            .next()
+            .and_then(|it| path_name(&it.type_ref()?))
+            .map_or(false, |it| it == "Vec"),
+        None => analyzer
+            .type_of(db, &ast::Expr::from(collect_call.clone()))
+            .map_or(false, |it| it.display(db).to_string().starts_with("Vec<")),
+    };
+    if !into_vec {
+        return None;
+    }
+    let receiver_ty = analyzer.type_of(db, &receiver)?.display(db).to_string();
+    let (label, method) = match receiver_ty.trim_start_matches('&').trim_start_matches("mut ") {
+        it if it.starts_with('[') => ("Use to_vec()", "to_vec"),
+        it if it.starts_with("Vec<") => ("Use clone()", "clone"),
+        _ => return None,
+    };
+
+    ctx.add_assist(AssistId("use_to_vec"), label, |edit| {
+        let range = collect_call.syntax().text_range();
+        edit.target(range);
+        edit.replace(range, format!("{}.{}()", receiver.syntax(), method));
+        edit.set_cursor(range.start());
+    })
+}
+
+fn is_call_to(call: &ast::MethodCallExpr, names: &[&str]) -> bool {
+    call.name_ref().map_or(false, |it| names.contains(&it.text().as_str()))
+        && call.arg_list().map_or(false, |it| it.args().next().is_none())
+}
+
+fn receiver_call(call: &ast::MethodCallExpr, names: &[&str]) -> Option<ast::MethodCallExpr> {
+    match call.expr()? {
+        ast::Expr::MethodCallExpr(it) if is_call_to(&it, names) => Some(it),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn use_clone_for_vec() {
+        check_assist(
+            use_to_vec,
+            r#"
+struct Vec<T>(T);
+fn f(v: &Vec<u8>) {
+    let w = v.<|>iter().copied().collect::<Vec<_>>();
+}
+"#,
+            r#"
+struct Vec<T>(T);
+fn f(v: &Vec<u8>) {
+    let w = <|>v.clone();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn use_to_vec_for_array() {
+        check_assist(
+            use_to_vec,
+            "struct Vec<T>(T);\nfn f(a: [u8; 4]) { g(a.iter().cloned().collect::<Vec<u8>>()<|>) }",
+            "struct Vec<T>(T);\nfn f(a: [u8; 4]) { g(<|>a.to_vec()) }",
+        );
+    }
+
+    #[test]
+    fn use_to_vec_target() {
+        check_assist_target(
+            use_to_vec,
+            "struct Vec<T>(T);\nfn f(s: &[u8]) { g(s.iter().cloned().<|>collect::<Vec<_>>()) }",
+            "s.iter().cloned().collect::<Vec<_>>()",
+        );
+    }
+
+    #[test]
+    fn use_to_vec_not_applicable() {
+        // Not collected into a `Vec`.
+        check_assist_not_applicable(
+            use_to_vec,
+            "struct HashSet<T>(T);\nfn f(s: &[u8]) { s.iter().cloned().<|>collect::<HashSet<_>>(); }",
+        );
+        // Not cloned.
+        check_assist_not_applicable(
+            use_to_vec,
+            "struct Vec<T>(T);\nfn f(s: &[u8]) { s.iter().<|>collect::<Vec<_>>(); }",
+        );
+        check_assist_not_applicable(
+            use_to_vec,
+            "struct Vec<T>(T);\nfn f(s: &[u8]) { s.iter().map(|it| *it).cloned().<|>collect::<Vec<_>>(); }",
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use hir::{db::HirDatabase, Adt, HasSource, Type};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode},
+    TextRange,
+};
+
+use crate::{
+    utils::{derives_default, implements_trait},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: use_unwrap_or_default
+//
+// Falls back to the default value instead of panicking in `expect`.
+//
+// ```
+// # enum Option<T> { Some(T), None }
+// #
+// #[derive(Default)]
+// struct Settings { verbose: bool }
+//
+// fn load() -> Option<Settings> { None }
+//
+// fn main() {
+//     let settings = load().<|>expect("no settings");
+// }
+// ```
+// ->
+// ```
+// # enum Option<T> { Some(T), None }
+// #
+// #[derive(Default)]
+// struct Settings { verbose: bool }
+//
+// fn load() -> Option<Settings> { None }
+//
+// fn main() {
+//     let settings = load().unwrap_or_default();
+// }
+// ```
+pub(crate) fn use_unwrap_or_default(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    if name_ref.text().as_str() != "expect" {
+        return None;
+    }
+    // The message is dropped, so it may not do anything else.
+    let mut args = call.arg_list()?.args();
+    match (args.next()?, args.next()) {
+        (ast::Expr::Literal(_), None) => (),
+        _ => return None,
+    }
+    let receiver = call.expr()?;
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    let receiver_ty = analyzer.type_of(db, &receiver)?;
+    let enum_ = match receiver_ty.as_adt() {
+        Some(Adt::Enum(it)) => it,
+        _ => return None,
+    };
+    let value_variant = match enum_.name(db).to_string().as_str() {
+        "Option" => "Some",
+        "Result" => "Ok",
+        _ => return None,
+    };
+    let variant =
+        enum_.variants(db).into_iter().find(|it| it.name(db).to_string() == value_variant)?;
+    let (_, value_ty) = receiver_ty.variant_fields(db, variant.into()).into_iter().next()?;
+    if !has_default(db, &value_ty) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("use_unwrap_or_default"), "Use unwrap_or_default", |edit| {
+        let range = TextRange::from_to(
+            name_ref.syntax().text_range().start(),
+            call.syntax().text_range().end(),
+        );
+        edit.target(range);
+        edit.replace(range, "unwrap_or_default()");
+        edit.set_cursor(range.start());
+    })
+}
+
+/// Whether the type is known to implement `Default`.
+fn has_default(db: &impl HirDatabase, ty: &Type) -> bool {
+    match ty.as_adt() {
+        Some(Adt::Struct(it)) if derives_default(&it.source(db).value) => true,
+        _ => implements_trait(db, ty, "Default"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    const TYPES: &str = r#"
+enum Option<T> { Some(T), None }
+enum Result<T, E> { Ok(T), Err(E) }
+struct Config;
+impl Default for Config { fn default() -> Config { Config } }
+struct Conn;
+"#;
+
+    #[test]
+    fn use_unwrap_or_default_for_result() {
+        check_assist(
+            use_unwrap_or_default,
+            &format!("{}{}", TYPES, "fn f(r: Result<Config, ()>) { let c = r.exp<|>ect(\"c\"); }"),
+            &format!(
+                "{}{}",
+                TYPES, "fn f(r: Result<Config, ()>) { let c = r.<|>unwrap_or_default(); }"
+            ),
+        );
+    }
+
+    #[test]
+    fn use_unwrap_or_default_for_impl() {
+        check_assist(
+            use_unwrap_or_default,
+            &format!("{}{}", TYPES, "fn f(c: Option<Config>) { c.<|>expect(\"config\"); }"),
+            &format!("{}{}", TYPES, "fn f(c: Option<Config>) { c.<|>unwrap_or_default(); }"),
+        );
+    }
+
+    #[test]
+    fn use_unwrap_or_default_target() {
+        check_assist_target(
+            use_unwrap_or_default,
+            &format!("{}{}", TYPES, "fn f(c: Option<Config>) { c.<|>expect(\"c\"); }"),
+            "expect(\"c\")",
+        );
+    }
+
+    #[test]
+    fn use_unwrap_or_default_not_applicable() {
+        for code in &[
+            // No default.
+            "fn f(c: Option<Conn>) { c.<|>expect(\"conn\"); }",
+            "fn f(c: Option<Config>) { c.<|>expect(&message()); }",
+            "fn f(c: Option<Config>) { c.<|>unwrap(); }",
+        ] {
+            check_assist_not_applicable(use_unwrap_or_default, &format!("{}{}", TYPES, code));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use format_buf::format;
+use hir::{db::HirDatabase, InFile, VariantDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, VisibilityOwner},
+    SyntaxKind::{ITEM_LIST, MODULE, SOURCE_FILE},
+    SyntaxNode, TextUnit,
+};
+
+use crate::{
+    assist_ctx::ActionBuilder,
+    utils::{find_calls, local_function, to_pascal_case, FieldRef, FieldUsages},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: wrap_in_newtype
+//
+// Wraps the primitive type of a field or parameter in a new tuple struct,
+// named after the field or parameter. Uses of the value, and the arguments of
+// calls for a parameter, are updated to go through the wrapper.
+//
+// ```
+// struct User {
+//     id: <|>u64,
+// }
+// ```
+// ->
+// ```
+// struct Id(u64);
+//
+// struct User {
+//     id: Id,
+// }
+// ```
+pub(crate) fn wrap_in_newtype(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let path_type = ctx.find_node_at_offset::<ast::PathType>()?;
+    let path = path_type.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let segment = path.segment()?;
+    let primitive = segment.name_ref()?.text().clone();
+    if !is_primitive(primitive.as_str()) || segment.type_arg_list().is_some() {
+        return None;
+    }
+
+    let parent = path_type.syntax().parent()?;
+    let (binding_name, usages) = if let Some(field) = ast::RecordFieldDef::cast(parent.clone()) {
+        field_usages(&ctx, &field)?
+    } else if let Some(param) = ast::Param::cast(parent) {
+        param_usages(&ctx, &param)?
+    } else {
+        return None;
+    };
+    let name = to_pascal_case(&binding_name);
+
+    // The new struct is placed in front of the item containing the type.
+    let item = path_type.syntax().ancestors().find(|it| is_module_item(it))?;
+    let root = item.ancestors().last()?;
+    let is_taken = root
+        .descendants()
+        .filter_map(ast::Name::cast)
+        .any(|it| it.text().as_str() == name.as_str());
+    if is_taken {
+        return None;
+    }
+    // The field is as visible as the struct, to be usable wherever it is.
+    let visibility = item
+        .children()
+        .find_map(ast::Visibility::cast)
+        .map(|it| format!("{} ", it.syntax()))
+        .unwrap_or_default();
+    let indent = leading_indent(&item).unwrap_or_default();
+
+    ctx.add_assist(AssistId("wrap_in_newtype"), "Wrap in newtype", |edit| {
+        edit.target(path_type.syntax().text_range());
+        let item_start = item.text_range().start();
+        let mut buf = String::new();
+        format!(buf, "{}struct {}({}{});\n\n{}", visibility, name, visibility, primitive, indent);
+        edit.insert(item_start, buf);
+        edit.replace(path_type.syntax().text_range(), name.clone());
+        usages.apply(edit, &name);
+        edit.set_cursor(item_start + TextUnit::of_str(&visibility) + TextUnit::of_str("struct "));
+    })
+}
+
+/// The expressions which have to be updated to go through the newtype.
+enum Usages {
+    Field(FieldUsages),
+    Param { uses: Vec<ast::PathExpr>, args: Vec<ast::Expr> },
+}
+
+impl Usages {
+    fn apply(self, edit: &mut ActionBuilder, name: &str) {
+        match self {
+            Usages::Field(usages) => {
+                for expr in usages.initializers {
+                    edit.replace(
+                        expr.syntax().text_range(),
+                        format!("{}({})", name, expr.syntax()),
+                    );
+                }
+                for name_ref in usages.shorthands {
+                    let text = format!(": {}({})", name, name_ref.text());
+                    edit.insert(name_ref.syntax().text_range().end(), text);
+                }
+                for access in usages.reads.into_iter().chain(usages.receivers) {
+                    edit.insert(access.syntax().text_range().end(), ".0");
+                }
+            }
+            Usages::Param { uses, args } => {
+                for path_expr in uses {
+                    edit.insert(path_expr.syntax().text_range().end(), ".0");
+                }
+                // An argument may itself be a use, which is unwrapped first.
+                for arg in args {
+                    edit.insert(arg.syntax().text_range().start(), format!("{}(", name));
+                    edit.insert(arg.syntax().text_range().end(), ")");
+                }
+            }
+        }
+    }
+}
+
+fn field_usages(
+    ctx: &AssistCtx<impl HirDatabase>,
+    field: &ast::RecordFieldDef,
+) -> Option<(String, Usages)> {
+    let name = field.name()?.text().to_string();
+    let strukt = field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let variant: VariantDef =
+        ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), strukt.clone()))?.into();
+    let usages = FieldUsages::find(ctx, &FieldRef::Named(name.clone()), field.syntax(), variant)?;
+    // Reading the field inside of its own initializer would need overlapping
+    // edits.
+    let is_nested = usages.receivers.iter().any(|receiver| {
+        let range = receiver.syntax().text_range();
+        usages.initializers.iter().any(|it| range.is_subrange(&it.syntax().text_range()))
+    });
+    if is_nested {
+        return None;
+    }
+    Some((name, Usages::Field(usages)))
+}
+
+fn param_usages(ctx: &AssistCtx<impl HirDatabase>, param: &ast::Param) -> Option<(String, Usages)> {
+    let bind_pat = match param.pat()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    let name = bind_pat.name()?.text().to_string();
+    let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+    let mut uses = Vec::new();
+    for reference in analyzer.find_all_refs(&bind_pat) {
+        let usage = ctx.covering_node_for_range(reference.range);
+        uses.push(usage.ancestors().find_map(ast::PathExpr::cast)?);
+    }
+
+    // Callers in other files, or through a trait, couldn't be updated.
+    let param_list = param.syntax().parent().and_then(ast::ParamList::cast)?;
+    let fn_def = param_list.syntax().parent().and_then(ast::FnDef::cast)?;
+    let is_trait_item =
+        fn_def.syntax().ancestors().any(|it| match ast::ImplBlock::cast(it.clone()) {
+            Some(impl_block) => impl_block.target_trait().is_some(),
+            None => ast::TraitDef::can_cast(it.kind()),
+        });
+    if fn_def.visibility().is_some() || is_trait_item {
+        return None;
+    }
+    let (function, _) = local_function(ctx, &fn_def)?;
+    let idx = param_list.params().position(|it| it.syntax() == param.syntax())?;
+    let root = fn_def.syntax().ancestors().last()?;
+    let args = find_calls(ctx, &root, function)?
+        .into_iter()
+        .map(|call| call.args.get(idx + call.offset).cloned())
+        .collect::<Option<Vec<_>>>()?;
+    Some((name, Usages::Param { uses, args }))
+}
+
+fn is_module_item(node: &SyntaxNode) -> bool {
+    match node.parent() {
+        Some(parent) if parent.kind() == SOURCE_FILE => true,
+        Some(parent) if parent.kind() == ITEM_LIST => {
+            parent.parent().map_or(false, |it| it.kind() == MODULE)
+        }
+        _ => false,
+    }
+}
+
+fn is_primitive(name: &str) -> bool {
+    match name {
+        "bool" | "char" | "str" | "f32" | "f64" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn wrap_field_in_newtype() {
+        check_assist(
+            wrap_in_newtype,
+            r#"
+struct User { user_id: <|>u64, age: u8 }
+fn make(user_id: u64) -> User { User { user_id, age: 0 } }
+fn next(u: &User) -> u64 { u.user_id + 1 }
+fn id(u: &User) -> String { u.user_id.to_string() }
+"#,
+            r#"
+struct <|>UserId(u64);
+
+struct User { user_id: UserId, age: u8 }
+fn make(user_id: u64) -> User { User { user_id: UserId(user_id), age: 0 } }
+fn next(u: &User) -> u64 { u.user_id.0 + 1 }
+fn id(u: &User) -> String { u.user_id.0.to_string() }
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_param_in_newtype() {
+        check_assist(
+            wrap_in_newtype,
+            r#"
+mod ops {
+    fn scale(factor: f6<|>4, x: f64) -> f64 {
+        if x > 1.0 { scale(factor, x / 2.0) } else { x * factor }
+    }
+    fn double(x: f64) -> f64 { scale(2.0, x) }
+}
+"#,
+            r#"
+mod ops {
+    struct <|>Factor(f64);
+
+    fn scale(factor: Factor, x: f64) -> f64 {
+        if x > 1.0 { scale(Factor(factor.0), x / 2.0) } else { x * factor.0 }
+    }
+    fn double(x: f64) -> f64 { scale(Factor(2.0), x) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_field_of_public_struct_in_newtype() {
+        check_assist(
+            wrap_in_newtype,
+            "pub(crate) struct User { id: <|>u64 }",
+            "pub(crate) struct <|>Id(pub(crate) u64);\n\npub(crate) struct User { id: Id }",
+        );
+    }
+
+    #[test]
+    fn wrap_in_newtype_target() {
+        check_assist_target(wrap_in_newtype, "struct S { id: u3<|>2 }", "u32");
+    }
+
+    #[test]
+    fn wrap_in_newtype_not_applicable_for_taken_names() {
+        check_assist_not_applicable(wrap_in_newtype, "struct Id; struct User { id: <|>u64 }");
+    }
+
+    #[test]
+    fn wrap_in_newtype_not_applicable_for_other_types() {
+        check_assist_not_applicable(wrap_in_newtype, "struct User { name: <|>String }");
+        check_assist_not_applicable(wrap_in_newtype, "fn f() -> <|>u32 { 0 }");
+    }
+
+    #[test]
+    fn wrap_in_newtype_not_applicable_for_params_of_public_functions() {
+        check_assist_not_applicable(wrap_in_newtype, "pub fn f(x: <|>u32) {}");
+        check_assist_not_applicable(wrap_in_newtype, "fn f(x: <|>u32) {}\nfn g() { h(f); }");
+    }
+}
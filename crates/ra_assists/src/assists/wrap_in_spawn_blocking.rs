@@ -0,0 +1,197 @@
+use either::Either;
+use hir::{db::HirDatabase, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, make, AstNode},
+    SyntaxKind::{BLOCK_EXPR, FN_DEF, LAMBDA_EXPR},
+    SyntaxNode, TextUnit, T,
+};
+
+use crate::{
+    utils::{is_copy_type, own_descendants, path_name},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: wrap_in_spawn_blocking
+//
+// Runs a blocking call in an async function on tokio's thread pool for
+// blocking code.
+//
+// ```
+// mod tokio { pub mod task { pub fn spawn_blocking() {} } }
+//
+// async fn load(path: String) -> Result<String, Error> {
+//     let text = <|>read_to_string(path);
+//     Ok(text)
+// }
+// ```
+// ->
+// ```
+// mod tokio { pub mod task { pub fn spawn_blocking() {} } }
+//
+// async fn load(path: String) -> Result<String, Error> {
+//     let text = tokio::task::spawn_blocking(move || read_to_string(path)).await?;
+//     Ok(text)
+// }
+// ```
+pub(crate) fn wrap_in_spawn_blocking(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx
+        .covering_element()
+        .ancestors()
+        .find(|it| ast::CallExpr::can_cast(it.kind()) || ast::MethodCallExpr::can_cast(it.kind()))
+        .and_then(ast::Expr::cast)?;
+    // Awaited calls don't block.
+    if call.syntax().parent().map_or(false, |it| ast::AwaitExpr::can_cast(it.kind())) {
+        return None;
+    }
+    let scope = call.syntax().ancestors().skip(1).find(|it| match it.kind() {
+        FN_DEF | LAMBDA_EXPR => true,
+        BLOCK_EXPR => is_async_block(it),
+        _ => false,
+    })?;
+    let returns_result = match ast::FnDef::cast(scope.clone()) {
+        Some(fn_def) if fn_def.is_async() => fn_def
+            .ret_type()
+            .and_then(|it| path_name(&it.type_ref()?))
+            .map_or(false, |it| it.ends_with("Result")),
+        _ if is_async_block(&scope) => false,
+        _ => return None,
+    };
+    // The closure can't return from the function or await.
+    let escapes = own_descendants(call.syntax()).any(|it| {
+        ast::AwaitExpr::can_cast(it.kind())
+            || ast::TryExpr::can_cast(it.kind())
+            || ast::ReturnExpr::can_cast(it.kind())
+    });
+    if escapes {
+        return None;
+    }
+
+    let db = ctx.db;
+    let analyzer = ctx.source_analyzer(call.syntax(), None);
+    let spawn_blocking = ["task", "spawn_blocking"]
+        .iter()
+        .fold(make::path_from_name_ref(make::name_ref("tokio")), |qual, it| {
+            make::path_qualified(qual, make::name_ref(it))
+        });
+    match analyzer.resolve_path(db, &spawn_blocking) {
+        Some(PathResolution::Def(ModuleDef::Function(_))) => (),
+        _ => return None,
+    }
+    // The locals are moved into the closure, which has to be `'static`.
+    let call_end = call.syntax().text_range().end();
+    for path_expr in call.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|it| analyzer.resolve_path(db, &it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let ty = local.ty(db);
+        if ty.display(db).to_string().starts_with('&') {
+            return None;
+        }
+        let used_later = match local.source(db).value {
+            Either::Left(pat) => {
+                analyzer.find_all_refs(&pat).iter().any(|it| it.range.start() >= call_end)
+            }
+            Either::Right(self_param) => {
+                if self_param.kind() != ast::SelfParamKind::Owned {
+                    return None;
+                }
+                is_used_after(&scope, "self", call_end)
+            }
+        };
+        if used_later && !is_copy_type(db, &ty) {
+            return None;
+        }
+    }
+
+    ctx.add_assist(AssistId("wrap_in_spawn_blocking"), "Wrap in spawn_blocking", |edit| {
+        let range = call.syntax().text_range();
+        edit.target(range);
+        edit.replace(
+            range,
+            format!(
+                "{}(move || {}).await{}",
+                spawn_blocking.syntax(),
+                call.syntax(),
+                if returns_result { "?" } else { ".unwrap()" }
+            ),
+        );
+    })
+}
+
+fn is_async_block(node: &SyntaxNode) -> bool {
+    node.kind() == BLOCK_EXPR && node.children_with_tokens().any(|it| it.kind() == T![async])
+}
+
+fn is_used_after(scope: &SyntaxNode, name: &str, offset: TextUnit) -> bool {
+    scope
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| it.syntax().text_range().start() >= offset)
+        .any(|it| it.syntax().text() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_in_fixture, check_assist_not_applicable};
+
+    const TOKIO: &str = "mod tokio { pub mod task { pub fn spawn_blocking() {} } }\n";
+
+    #[test]
+    fn wrap_in_spawn_blocking_with_dependency() {
+        check_assist_in_fixture(
+            wrap_in_spawn_blocking,
+            r#"
+//- /main.rs crate:main deps:tokio
+async fn f(data: Vec<u8>) {
+    <|>compress(data);
+}
+//- /tokio.rs crate:tokio
+pub mod task {
+    pub fn spawn_blocking() {}
+}
+"#,
+            "async fn f(data: Vec<u8>) {\n    <|>tokio::task::spawn_blocking(move || compress(data)).await.unwrap();\n}\n",
+        );
+    }
+
+    #[test]
+    fn wrap_method_call_in_spawn_blocking() {
+        check_assist(
+            wrap_in_spawn_blocking,
+            &format!(
+                "{}{}",
+                TOKIO,
+                "fn f(n: u64) { let x = async move { let db = Db; db.query(n)<|>; n }; }"
+            ),
+            &format!(
+                "{}{}",
+                TOKIO,
+                "fn f(n: u64) { let x = async move { let db = Db; tokio::task::spawn_blocking(move || db.query(n)).await.unwrap()<|>; n }; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn wrap_in_spawn_blocking_not_applicable() {
+        // No tokio.
+        check_assist_not_applicable(
+            wrap_in_spawn_blocking,
+            "async fn f(p: String) { <|>read(p); }",
+        );
+        for before in &[
+            // Not async.
+            "fn f(p: String) { <|>read(p); }",
+            "async fn f() { <|>fetch().await; }",
+            // Borrowed.
+            "async fn f(p: &str) { <|>read(p); }",
+            "struct S; impl S { async fn f(&self) { <|>read(self); } }",
+            // Used after being moved.
+            "async fn f(p: String) { <|>read(p); print(p); }",
+            "async fn f() -> Result<(), E> { <|>read(open()?); Ok(()) }",
+        ] {
+            check_assist_not_applicable(wrap_in_spawn_blocking, &format!("{}{}", TOKIO, before));
+        }
+    }
+}
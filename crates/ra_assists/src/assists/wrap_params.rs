@@ -0,0 +1,177 @@
+use hir::db::HirDatabase;
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::{COMMA, L_PAREN, PARAM, R_PAREN, SELF_PARAM, WHITESPACE},
+    SyntaxNode,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+/// The width of the lines formatted by rustfmt by default.
+const MAX_WIDTH: usize = 100;
+
+// Assist: wrap_params
+//
+// Puts each parameter of a function with a long signature on a line of its
+// own.
+//
+// ```
+// fn <|>render(template: &Template, context: &Context, output: &mut dyn Write) -> Result<(), RenderError> {}
+// ```
+// ->
+// ```
+// fn render(
+//     template: &Template,
+//     context: &Context,
+//     output: &mut dyn Write,
+// ) -> Result<(), RenderError> {}
+// ```
+pub(crate) fn wrap_params(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (fn_def, param_list) = param_list_at_head(&ctx)?;
+    let range = param_list.syntax().text_range();
+    let params = params(&param_list)?;
+    if params.is_empty() || param_list.syntax().text().contains_char('\n') {
+        return None;
+    }
+    let text = fn_def.syntax().ancestors().last()?.text().to_string();
+    let (start, end) = (range.start().to_usize(), range.end().to_usize());
+    let line_start = text[..start].rfind('\n').map_or(0, |it| it + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |it| end + it);
+    if text[line_start..line_end].chars().count() <= MAX_WIDTH {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("wrap_params"), "Wrap parameters", |edit| {
+        let indent = leading_indent(fn_def.syntax()).unwrap_or_default();
+        let mut buf = String::from("(\n");
+        for param in params {
+            buf.push_str(&format!("{}    {},\n", indent, param));
+        }
+        buf.push_str(&format!("{})", indent));
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+// Assist: join_params
+//
+// Puts the parameters of a function on a single line.
+//
+// ```
+// fn <|>area(
+//     width: u32,
+//     height: u32,
+// ) -> u32 {}
+// ```
+// ->
+// ```
+// fn area(width: u32, height: u32) -> u32 {}
+// ```
+pub(crate) fn join_params(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (_, param_list) = param_list_at_head(&ctx)?;
+    let range = param_list.syntax().text_range();
+    let params = params(&param_list)?;
+    if !param_list.syntax().text().contains_char('\n') || params.iter().any(|it| it.contains('\n'))
+    {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("join_params"), "Join parameters", |edit| {
+        edit.target(range);
+        edit.replace(range, format!("({})", params.join(", ")));
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The parameter list of the function under the cursor, when it's not in its
+/// body.
+fn param_list_at_head(ctx: &AssistCtx<impl HirDatabase>) -> Option<(ast::FnDef, ast::ParamList)> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let param_list = fn_def.param_list()?;
+    if ctx.frange.range.start() > param_list.syntax().text_range().end() {
+        return None;
+    }
+    Some((fn_def, param_list))
+}
+
+/// The text of the parameters, including `self`, unless the list contains
+/// anything else, like comments or `...`.
+fn params(param_list: &ast::ParamList) -> Option<Vec<String>> {
+    let mut params = Vec::new();
+    for element in param_list.syntax().children_with_tokens() {
+        match element.kind() {
+            L_PAREN | R_PAREN | COMMA | WHITESPACE => (),
+            PARAM | SELF_PARAM => params.push(element.as_node().map(SyntaxNode::to_string)?),
+            _ => return None,
+        }
+    }
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn wrap_params_in_impl() {
+        check_assist(
+            wrap_params,
+            r#"
+impl Server {
+    pub fn <|>listen(&mut self, addr: SocketAddr, backlog: u32, handler: impl Fn(Request) -> Response) {}
+}
+"#,
+            r#"
+impl Server {
+    pub fn <|>listen(
+        &mut self,
+        addr: SocketAddr,
+        backlog: u32,
+        handler: impl Fn(Request) -> Response,
+    ) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_params_target() {
+        check_assist_target(
+            wrap_params,
+            "fn f(<|>aaaaaaaaaaaaaaaaaaaa: u32, bbbbbbbbbbbbbbbbbbbb: u32, cccccccccccccccccccc: u32, dddddddddddd: u32) {}",
+            "(aaaaaaaaaaaaaaaaaaaa: u32, bbbbbbbbbbbbbbbbbbbb: u32, cccccccccccccccccccc: u32, dddddddddddd: u32)",
+        );
+    }
+
+    #[test]
+    fn wrap_params_not_applicable() {
+        // Short enough.
+        check_assist_not_applicable(wrap_params, "fn <|>f(a: u32, b: u32) {}");
+        check_assist_not_applicable(
+            wrap_params,
+            "fn f(aaaaaaaaaaaaaaaaaaaa: u32, bbbbbbbbbbbbbbbbbbbb: u32, cccccccccccccccccccc: u32, dddddddddddd: u32) { <|> }",
+        );
+        check_assist_not_applicable(
+            wrap_params,
+            "fn <|>f(aaaaaaaaaaaaaaaaaaaa: u32, bbbbbbbbbbbbbbbbbbbb: u32, /* c */ cccccccccccccccccccc: u32, d: u32) {}",
+        );
+    }
+
+    #[test]
+    fn join_params_with_self() {
+        check_assist(
+            join_params,
+            "impl S {\n    fn f<|>(\n        self,\n        #[cfg(test)] n: u32\n    ) {}\n}",
+            "impl S {\n    fn f<|>(self, #[cfg(test)] n: u32) {}\n}",
+        );
+    }
+
+    #[test]
+    fn join_params_not_applicable() {
+        check_assist_not_applicable(join_params, "fn <|>f(a: u32, b: u32) {}");
+        check_assist_not_applicable(join_params, "fn <|>f(\n    a: u32, // a\n    b: u32,\n) {}");
+    }
+}
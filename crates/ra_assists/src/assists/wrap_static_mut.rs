@@ -0,0 +1,297 @@
+use format_buf::format;
+use hir::{db::HirDatabase, InFile, ModuleDef, PathResolution};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxKind::WHITESPACE,
+    SyntaxNode, TextRange, T,
+};
+
+use crate::{utils::is_local_module, Assist, AssistCtx, AssistId};
+
+// Assist: wrap_static_mut
+//
+// Wraps a `static mut` into a `Mutex` and routes all accesses in the current
+// file through an accessor function.
+//
+// This is a migration scaffold: the `unsafe` blocks around the former accesses
+// become unnecessary and should be removed by hand, and the wrapped type has to
+// be `Send`.
+//
+// ```
+// static mut COUNTER<|>: u32 = 0;
+//
+// fn inc() {
+//     unsafe { COUNTER += 1; }
+// }
+// ```
+// ->
+// ```
+// static COUNTER: std::sync::Mutex<u32> = std::sync::Mutex::new(0);
+//
+// fn counter() -> std::sync::MutexGuard<'static, u32> {
+//     COUNTER.lock().unwrap()
+// }
+//
+// fn inc() {
+//     unsafe { *counter() += 1; }
+// }
+// ```
+pub(crate) fn wrap_static_mut(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let static_def = ctx.find_node_at_offset::<ast::StaticDef>()?;
+    let mut_token = static_def
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == T![mut])?;
+    // Accesses from other files can't be rewritten, so only private statics
+    // are supported.
+    if static_def.visibility().is_some() {
+        return None;
+    }
+    let name = static_def.name()?;
+    let ty = static_def.ascribed_type()?;
+    let init = static_def.body()?;
+
+    let file = static_def.syntax().ancestors().last()?;
+    let accessor = name.text().to_lowercase();
+    let is_taken = file.descendants().any(|it| match ast::Name::cast(it.clone()) {
+        Some(it) => it.text().as_str() == accessor,
+        None => ast::NameRef::cast(it).map_or(false, |it| it.text().as_str() == accessor),
+    });
+    if is_taken {
+        return None;
+    }
+
+    let db = ctx.db;
+    let def =
+        ctx.source_binder().to_def(InFile::new(ctx.frange.file_id.into(), static_def.clone()))?;
+    // Child modules in other files can access the static too.
+    if !is_local_module(&ctx, def.module(db)) {
+        return None;
+    }
+    let accesses = file
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|path_expr| {
+            let path = match path_expr.path() {
+                Some(it) => it,
+                None => return false,
+            };
+            let name_ref = path.segment().and_then(|it| it.name_ref());
+            if name_ref.map_or(true, |it| it.text() != name.text()) {
+                return false;
+            }
+            let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+            match analyzer.resolve_path(db, &path) {
+                Some(PathResolution::Def(ModuleDef::Static(it))) => it == def,
+                _ => false,
+            }
+        })
+        .collect::<Vec<_>>();
+    // Locking again while a guard is alive would deadlock.
+    let mut scopes =
+        accesses.iter().map(|it| guard_scope(it.syntax())).collect::<Option<Vec<_>>>()?;
+    scopes.sort_by_key(|it| it.start());
+    if scopes.windows(2).any(|it| it[0].end() > it[1].start()) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("wrap_static_mut"), "Wrap in safe abstraction (scaffold)", |edit| {
+        let indent = leading_indent(static_def.syntax()).unwrap_or_default();
+
+        let mut_range = match mut_token.next_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => {
+                TextRange::from_to(mut_token.text_range().start(), ws.text_range().end())
+            }
+            _ => mut_token.text_range(),
+        };
+        edit.target(static_def.syntax().text_range());
+        edit.delete(mut_range);
+        edit.replace(ty.syntax().text_range(), format!("std::sync::Mutex<{}>", ty.syntax()));
+        edit.replace(
+            init.syntax().text_range(),
+            format!("std::sync::Mutex::new({})", init.syntax()),
+        );
+
+        let mut buf = String::new();
+        format!(
+            buf,
+            "\n\n{}fn {}() -> std::sync::MutexGuard<'static, {}> {{\n",
+            indent,
+            accessor,
+            ty.syntax()
+        );
+        format!(buf, "{}    {}.lock().unwrap()\n", indent, name.syntax());
+        format!(buf, "{}}}", indent);
+        edit.insert(static_def.syntax().text_range().end(), buf);
+
+        for access in accesses {
+            // Method calls, field accesses and indexing auto-deref the guard.
+            let is_receiver = access
+                .syntax()
+                .parent()
+                .filter(|parent| {
+                    ast::MethodCallExpr::can_cast(parent.kind())
+                        || ast::FieldExpr::can_cast(parent.kind())
+                        || ast::IndexExpr::can_cast(parent.kind())
+                })
+                .and_then(|parent| parent.first_child())
+                .map_or(false, |receiver| &receiver == access.syntax());
+            let replacement =
+                if is_receiver { format!("{}()", accessor) } else { format!("*{}()", accessor) };
+            edit.replace(access.syntax().text_range(), replacement);
+        }
+    })
+}
+
+/// Returns the range in which a guard locked at `node` is alive. That's the
+/// statement, or the tail expression of a block, containing it, or all of a
+/// `match`, `if let` or `while let` with the lock in its scrutinee.
+fn guard_scope(node: &SyntaxNode) -> Option<TextRange> {
+    let mut child = node.clone();
+    for it in node.ancestors() {
+        if it != *node && is_scrutinee(&child, &it) {
+            return guard_scope(&it);
+        }
+        if ast::ExprStmt::can_cast(it.kind())
+            || ast::LetStmt::can_cast(it.kind())
+            || it.parent().map_or(false, |parent| ast::Block::can_cast(parent.kind()))
+        {
+            return Some(it.text_range());
+        }
+        child = it;
+    }
+    None
+}
+
+fn is_scrutinee(child: &SyntaxNode, parent: &SyntaxNode) -> bool {
+    if let Some(match_expr) = ast::MatchExpr::cast(parent.clone()) {
+        return match_expr.expr().map_or(false, |it| it.syntax() == child);
+    }
+    ast::Condition::cast(parent.clone()).map_or(false, |it| it.pat().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn wrap_static_mut_rewrites_accesses() {
+        check_assist(
+            wrap_static_mut,
+            r#"
+/// Number of calls.
+static mut CALLS<|>: usize = 0;
+
+fn bump() -> usize {
+    unsafe {
+        CALLS += 1;
+        CALLS.count_ones();
+        foo(CALLS)
+    }
+}
+"#,
+            r#"
+/// Number of calls.
+static CALLS<|>: std::sync::Mutex<usize> = std::sync::Mutex::new(0);
+
+fn calls() -> std::sync::MutexGuard<'static, usize> {
+    CALLS.lock().unwrap()
+}
+
+fn bump() -> usize {
+    unsafe {
+        *calls() += 1;
+        calls().count_ones();
+        foo(*calls())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_static_mut_ignores_other_statics() {
+        check_assist(
+            wrap_static_mut,
+            r#"
+mod other { pub static X: u32 = 0; }
+
+static mut <|>X: u32 = 1;
+
+fn f() {
+    g(other::X, X);
+}
+"#,
+            r#"
+mod other { pub static X: u32 = 0; }
+
+static <|>X: std::sync::Mutex<u32> = std::sync::Mutex::new(1);
+
+fn x() -> std::sync::MutexGuard<'static, u32> {
+    X.lock().unwrap()
+}
+
+fn f() {
+    g(other::X, *x());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_static_mut_target() {
+        check_assist_target(wrap_static_mut, "static mut <|>X: u32 = 1;", "static mut X: u32 = 1;");
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_for_immutable_static() {
+        check_assist_not_applicable(wrap_static_mut, "static <|>X: u32 = 1;");
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_for_public_static() {
+        check_assist_not_applicable(wrap_static_mut, "pub static mut <|>X: u32 = 1;");
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_when_locking_twice() {
+        check_assist_not_applicable(
+            wrap_static_mut,
+            "static mut <|>X: u32 = 1;\nfn f() { unsafe { X = X + 1; } }",
+        );
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_when_locking_in_match_scrutinee() {
+        check_assist_not_applicable(
+            wrap_static_mut,
+            "static mut <|>X: u32 = 1;\nfn f() { unsafe { match X { _ => { X = 0; } } } }",
+        );
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_with_child_module_in_other_file() {
+        check_assist_not_applicable_in_fixture(
+            wrap_static_mut,
+            r#"
+//- /main.rs
+mod tests;
+static mut <|>X: u32 = 1;
+
+//- /tests.rs
+fn f() { unsafe { super::X = 0; } }
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_static_mut_not_applicable_when_accessor_is_taken() {
+        check_assist_not_applicable(wrap_static_mut, "static mut <|>X: u32 = 1;\nfn x() {}");
+    }
+}
@@ -8,7 +8,7 @@ mod generated;
 use ra_db::{fixture::WithFixture, FileRange};
 use test_utils::{assert_eq_text, extract_range_or_offset};
 
-use crate::test_db::TestDB;
+use crate::{helpers::apply_assist, test_db::TestDB};
 
 fn check(assist_id: &str, before: &str, after: &str) {
     let (selection, before) = extract_range_or_offset(before);
@@ -30,6 +30,6 @@ fn check(assist_id: &str, before: &str, after: &str) {
             )
         });
 
-    let actual = assist.get_first_action().edit.apply(&before);
+    let (actual, _) = apply_assist(&assist, &before, frange.range.start());
     assert_eq_text!(after, &actual);
 }
@@ -2,6 +2,62 @@
 
 use super::check;
 
+#[test]
+fn doctest_add_as_ref_impls() {
+    check(
+        "add_as_ref_impls",
+        r#####"
+struct Name(String);<|>
+"#####,
+        r#####"
+struct Name(String);
+
+impl AsRef<String> for Name {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_block_to_closure() {
+    check(
+        "add_block_to_closure",
+        r#####"
+fn main() {
+    let double = <|>|x: u32| x * 2;
+}
+"#####,
+        r#####"
+fn main() {
+    let double = |x: u32| {
+        x * 2
+    };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_bounds_check() {
+    check(
+        "add_bounds_check",
+        r#####"
+unsafe fn get(ptr: *const u8, i: usize) -> u8 {
+    *ptr.<|>add(i)
+}
+"#####,
+        r#####"
+unsafe fn get(ptr: *const u8, i: usize) -> u8 {
+    debug_assert!(i <= len, "`ptr` offset out of bounds");
+    *ptr.add(i)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_custom_impl() {
     check(
@@ -21,6 +77,25 @@ impl Debug for S {
     )
 }
 
+#[test]
+fn doctest_add_default_implementation() {
+    check(
+        "add_default_implementation",
+        r#####"
+trait Shape {
+    fn name(&self) -> String<|>;
+}
+"#####,
+        r#####"
+trait Shape {
+    fn name(&self) -> String {
+        unimplemented!()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_derive() {
     check(
@@ -41,6 +116,120 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_add_doc_alias() {
+    check(
+        "add_doc_alias",
+        r#####"
+/// Counts the elements.
+pub fn <|>len(&self) -> usize {
+    self.len
+}
+"#####,
+        r#####"
+/// Counts the elements.
+#[doc(alias = "")]
+pub fn len(&self) -> usize {
+    self.len
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_drop_impl() {
+    check(
+        "add_drop_impl",
+        r#####"
+struct Guard<|> {
+    id: u32,
+}
+"#####,
+        r#####"
+struct Guard {
+    id: u32,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        unimplemented!()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_enumerate() {
+    check(
+        "add_enumerate",
+        r#####"
+fn print(names: &[String]) {
+    for <|>name in &names {
+        println!("{}", name);
+    }
+}
+"#####,
+        r#####"
+fn print(names: &[String]) {
+    for (i, name) in names.iter().enumerate() {
+        println!("{}", name);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_error_from() {
+    check(
+        "add_error_from",
+        r#####"
+struct IoError;
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("io error")]
+    <|>Io(IoError),
+}
+"#####,
+        r#####"
+struct IoError;
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("io error")]
+    Io(#[from] IoError),
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_explicit_drop() {
+    check(
+        "add_explicit_drop",
+        r#####"
+struct Guard;
+
+fn main() {
+    let guard = Guard;
+    guard<|>;
+    work();
+}
+"#####,
+        r#####"
+struct Guard;
+
+fn main() {
+    let guard = Guard;
+    drop(guard);
+    work();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check(
@@ -58,6 +247,31 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_add_from_str_impl() {
+    check(
+        "add_from_str_impl",
+        r#####"
+struct Guard<|> {
+    id: u32,
+}
+"#####,
+        r#####"
+struct Guard {
+    id: u32,
+}
+
+impl std::str::FromStr for Guard {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        unimplemented!()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_hash() {
     check(
@@ -175,6 +389,32 @@ fn process(map: HashMap<String, String>) {}
     )
 }
 
+#[test]
+fn doctest_add_into_iterator_impl() {
+    check(
+        "add_into_iterator_impl",
+        r#####"
+struct Vec<T>(T);
+
+struct Ids<|>(Vec<u32>);
+"#####,
+        r#####"
+struct Vec<T>(T);
+
+struct Ids(Vec<u32>);
+
+impl IntoIterator for Ids {
+    type Item = u32;
+    type IntoIter = std::vec::IntoIter<u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_new() {
     check(
@@ -198,239 +438,358 @@ impl<T: Clone> Ctx<T> {
 }
 
 #[test]
-fn doctest_apply_demorgan() {
+fn doctest_add_partial_cmp() {
     check(
-        "apply_demorgan",
+        "add_partial_cmp",
         r#####"
-fn main() {
-    if x != 4 ||<|> !y {}
-}
+struct Version { major: u32, minor: u32 }
+
+impl PartialOrd for Version {<|>}
 "#####,
         r#####"
-fn main() {
-    if !(x == 4 && y) {}
+struct Version { major: u32, minor: u32 }
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.major.partial_cmp(&other.major) {
+            Some(std::cmp::Ordering::Equal) => {}
+            ord => return ord,
+        }
+        self.minor.partial_cmp(&other.minor)
+    }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_change_visibility() {
+fn doctest_add_phantom_data() {
     check(
-        "change_visibility",
+        "add_phantom_data",
         r#####"
-<|>fn frobnicate() {}
+struct Id<T> { <|>raw: u64 }
+
+fn id<T>(raw: u64) -> Id<T> {
+    Id { raw }
+}
 "#####,
         r#####"
-pub(crate) fn frobnicate() {}
+struct Id<T> { raw: u64, _marker: std::marker::PhantomData<T> }
+
+fn id<T>(raw: u64) -> Id<T> {
+    Id { raw, _marker: std::marker::PhantomData }
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_convert_to_guarded_return() {
+fn doctest_add_tracing_span() {
     check(
-        "convert_to_guarded_return",
+        "add_tracing_span",
         r#####"
-fn main() {
-    <|>if cond {
-        foo();
-        bar();
-    }
+mod tracing {}
+
+fn <|>handle(request: Request) {
+    respond(request);
 }
 "#####,
         r#####"
-fn main() {
-    if !cond {
-        return;
-    }
-    foo();
-    bar();
+mod tracing {}
+
+fn handle(request: Request) {
+    let _span = tracing::info_span!("handle").entered();
+    respond(request);
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_fill_match_arms() {
+fn doctest_add_track_caller() {
     check(
-        "fill_match_arms",
+        "add_track_caller",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
-
-fn handle(action: Action) {
-    match action {
-        <|>
-    }
+/// Returns the first byte.
+fn <|>first(bytes: &[u8]) -> u8 {
+    assert!(!bytes.is_empty());
+    bytes[0]
 }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
-
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } => (),
-        Action::Stop => (),
-    }
+/// Returns the first byte.
+#[track_caller]
+fn first(bytes: &[u8]) -> u8 {
+    assert!(!bytes.is_empty());
+    bytes[0]
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_binexpr() {
+fn doctest_apply_demorgan() {
     check(
-        "flip_binexpr",
+        "apply_demorgan",
         r#####"
 fn main() {
-    let _ = 90 +<|> 2;
+    if x != 4 ||<|> !y {}
 }
 "#####,
         r#####"
 fn main() {
-    let _ = 2 + 90;
+    if !(x == 4 && y) {}
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_comma() {
+fn doctest_box_recursive_field() {
     check(
-        "flip_comma",
+        "box_recursive_field",
         r#####"
-fn main() {
-    ((1, 2),<|> (3, 4));
+struct Node {
+    value: u32,
+    next: <|>Node,
+}
+
+fn make(value: u32, next: Node) -> Node {
+    Node { value, next }
 }
 "#####,
         r#####"
-fn main() {
-    ((3, 4), (1, 2));
+struct Node {
+    value: u32,
+    next: Box<Node>,
+}
+
+fn make(value: u32, next: Node) -> Node {
+    Node { value, next: Box::new(next) }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_trait_bound() {
+fn doctest_box_recursive_variant() {
     check(
-        "flip_trait_bound",
+        "box_recursive_variant",
         r#####"
-fn foo<T: Clone +<|> Copy>() { }
+enum Expr {
+    Lit(i64),
+    <|>Neg(Expr),
+}
+
+fn eval(expr: Expr) -> i64 {
+    match expr {
+        Expr::Lit(n) => n,
+        Expr::Neg(inner) => -eval(inner),
+    }
+}
 "#####,
         r#####"
-fn foo<T: Copy + Clone>() { }
+enum Expr {
+    Lit(i64),
+    Neg(Box<Expr>),
+}
+
+fn eval(expr: Expr) -> i64 {
+    match expr {
+        Expr::Lit(n) => n,
+        Expr::Neg(inner) => -eval(*inner),
+    }
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_inline_local_variable() {
+fn doctest_build_with_iterator() {
     check(
-        "inline_local_variable",
+        "build_with_iterator",
         r#####"
-fn main() {
-    let x<|> = 1 + 2;
-    x * 4;
+struct Vec<T>(T);
+impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+
+fn args(verbose: bool) -> Vec<&'static str> {
+    let mut <|>args = Vec::new();
+    args.push("run");
+    if verbose {
+        args.push("-v");
+    }
+    args
 }
 "#####,
         r#####"
-fn main() {
-    (1 + 2) * 4;
+struct Vec<T>(T);
+impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } fn push(&mut self, x: T) {} }
+
+fn args(verbose: bool) -> Vec<&'static str> {
+    let args = std::iter::once("run")
+        .chain(if verbose { Some("-v") } else { None })
+        .collect::<Vec<_>>();
+    args
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_introduce_variable() {
+fn doctest_change_closure_capture() {
     check(
-        "introduce_variable",
+        "change_closure_capture",
         r#####"
 fn main() {
-    <|>(1 + 2)<|> * 4;
+    let name = String::new();
+    spawn(<|>|| greet(&name));
 }
 "#####,
         r#####"
 fn main() {
-    let var_name = (1 + 2);
-    var_name * 4;
+    let name = String::new();
+    spawn(move || greet(&name));
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_invert_if() {
+fn doctest_change_option_to_result() {
     check(
-        "invert_if",
+        "change_option_to_result",
         r#####"
-fn main() {
-    if<|> !y { A } else { B }
+fn parse(s: &str) -> Option<|><u32> {
+    if s.is_empty() {
+        return None;
+    }
+    Some(92)
 }
 "#####,
         r#####"
-fn main() {
-    if y { B } else { A }
+fn parse(s: &str) -> Result<u32, ()> {
+    if s.is_empty() {
+        return Err(());
+    }
+    Ok(92)
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_make_raw_string() {
+fn doctest_change_result_to_option() {
     check(
-        "make_raw_string",
+        "change_result_to_option",
         r#####"
-fn main() {
-    "Hello,<|> World!";
+fn parse(s: &str) -> Result<|><u32, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    Ok(92)
 }
 "#####,
         r#####"
-fn main() {
-    r#"Hello, World!"#;
+fn parse(s: &str) -> Option<u32> {
+    if s.is_empty() {
+        return None;
+    }
+    Some(92)
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_make_usual_string() {
+fn doctest_change_visibility() {
     check(
-        "make_usual_string",
+        "change_visibility",
+        r#####"
+<|>fn frobnicate() {}
+"#####,
+        r#####"
+pub(crate) fn frobnicate() {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_collapse_map_projections() {
+    check(
+        "collapse_map_projections",
         r#####"
 fn main() {
-    r#"Hello,<|> "World!""#;
+    let city = user.map(|u| u.address).<|>map(|a| a.city);
 }
 "#####,
         r#####"
 fn main() {
-    "Hello, \"World!\"";
+    let city = user.map(|u| u.address.city);
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_merge_match_arms() {
+fn doctest_collect_into_map() {
     check(
-        "merge_match_arms",
+        "collect_into_map",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
-
-fn handle(action: Action) {
-    match action {
-        <|>Action::Move(..) => foo(),
-        Action::Stop => foo(),
+fn main() {
+    let mut ages = HashMap::new();
+    <|>for (name, age) in people {
+        ages.insert(name, age);
     }
 }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+fn main() {
+    let ages: HashMap<_, _> = people.into_iter().collect();
+}
+"#####,
+    )
+}
 
-fn handle(action: Action) {
-    match action {
-        Action::Move(..) | Action::Stop => foo(),
+#[test]
+fn doctest_collect_into_result() {
+    check(
+        "collect_into_result",
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+
+fn parse_all(xs: Vec<Result<u32, String>>) -> Result<Vec<u32>, String> {
+    let values = xs.into_iter().<|>map(|x| x.unwrap()).collect::<Vec<_>>();
+    Result::Ok(values)
+}
+"#####,
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+
+fn parse_all(xs: Vec<Result<u32, String>>) -> Result<Vec<u32>, String> {
+    let values = xs.into_iter().collect::<Result<Vec<_>, _>>()?;
+    Result::Ok(values)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_combine_if_let() {
+    check(
+        "combine_if_let",
+        r#####"
+fn main(a: &Option<u32>, b: &Option<u32>) {
+    <|>if let Some(x) = a {
+        if let Some(y) = b {
+            foo(x, y);
+        }
+    }
+}
+"#####,
+        r#####"
+fn main(a: &Option<u32>, b: &Option<u32>) {
+    if let (Some(x), Some(y)) = (a, b) {
+        foo(x, y);
     }
 }
 "#####,
@@ -438,26 +797,76 @@ fn handle(action: Action) {
 }
 
 #[test]
-fn doctest_move_arm_cond_to_match_guard() {
+fn doctest_combine_into_let_else() {
     check(
-        "move_arm_cond_to_match_guard",
+        "combine_into_let_else",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+fn parse(s: &str) -> Result<u32, Error> {
+    let digits = find_digits(s);
+    let <|>n = match digits {
+        Some(n) => n,
+        None => return Err(Error::Empty),
+    };
+    Ok(n)
+}
+"#####,
+        r#####"
+fn parse(s: &str) -> Result<u32, Error> {
+    let Some(n) = find_digits(s) else { return Err(Error::Empty); };
+    Ok(n)
+}
+"#####,
+    )
+}
 
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } => <|>if distance > 10 { foo() },
-        _ => (),
+#[test]
+fn doctest_convert_boxed_str_field() {
+    check(
+        "convert_boxed_str_field",
+        r#####"
+struct Person {
+    name: <|>Box<str>,
+}
+
+fn person(name: Box<str>) -> Person {
+    Person { name }
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+fn person(name: Box<str>) -> Person {
+    Person { name: name.to_string() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_field_to_cow() {
+    check(
+        "convert_field_to_cow",
+        r#####"
+struct Person {
+    name: <|>String,
+}
+
+impl Person {
+    fn new(name: String) -> Self {
+        Person { name }
     }
 }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+struct Person<'a> {
+    name: std::borrow::Cow<'a, str>,
+}
 
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } if distance > 10 => foo(),
-        _ => (),
+impl Person<'_> {
+    fn new(name: String) -> Self {
+        Person { name: name.into() }
     }
 }
 "#####,
@@ -465,105 +874,221 @@ fn handle(action: Action) {
 }
 
 #[test]
-fn doctest_move_bounds_to_where_clause() {
+fn doctest_convert_if_chain_to_match() {
     check(
-        "move_bounds_to_where_clause",
+        "convert_if_chain_to_match",
         r#####"
-fn apply<T, U, <|>F: FnOnce(T) -> U>(f: F, x: T) -> U {
-    f(x)
+fn grade(score: u32) -> char {
+    <|>if score < 50 {
+        'F'
+    } else if score >= 50 && score < 80 {
+        'B'
+    } else if score == 100 {
+        'S'
+    } else {
+        'A'
+    }
 }
 "#####,
         r#####"
-fn apply<T, U, F>(f: F, x: T) -> U where F: FnOnce(T) -> U {
-    f(x)
+fn grade(score: u32) -> char {
+    match score {
+        0..=49 => 'F',
+        50..=79 => 'B',
+        100 => 'S',
+        _ => 'A',
+    }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_move_guard_to_arm_body() {
+fn doctest_convert_match_to_if_chain() {
     check(
-        "move_guard_to_arm_body",
+        "convert_match_to_if_chain",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+fn size(n: u32) -> &'static str {
+    <|>match n {
+        0 => "none",
+        1..=9 => "small",
+        _ => "large",
+    }
+}
+"#####,
+        r#####"
+fn size(n: u32) -> &'static str {
+    if n == 0 {
+        "none"
+    } else if n >= 1 && n <= 9 {
+        "small"
+    } else {
+        "large"
+    }
+}
+"#####,
+    )
+}
 
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } <|>if distance > 10 => foo(),
-        _ => (),
+#[test]
+fn doctest_convert_to_any_or_find() {
+    check(
+        "convert_to_any_or_find",
+        r#####"
+fn main() {
+    let mut has_admin = false;
+    <|>for user in users {
+        if user.is_admin() {
+            has_admin = true;
+            break;
+        }
     }
 }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+fn main() {
+    let has_admin = users.into_iter().any(|user| user.is_admin());
+}
+"#####,
+    )
+}
 
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } => if distance > 10 { foo() },
-        _ => (),
+#[test]
+fn doctest_convert_to_count_or_sum() {
+    check(
+        "convert_to_count_or_sum",
+        r#####"
+fn main() {
+    let mut total: u32 = 0;
+    <|>for item in items {
+        if item.is_valid() {
+            total += item.size;
+        }
     }
 }
+"#####,
+        r#####"
+fn main() {
+    let total: u32 = items.into_iter().filter(|item| item.is_valid()).map(|item| item.size).sum();
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_remove_dbg() {
+fn doctest_convert_to_fold() {
     check(
-        "remove_dbg",
+        "convert_to_fold",
         r#####"
 fn main() {
-    <|>dbg!(92);
+    let mut sum = 0;
+    <|>for x in &xs {
+        sum = sum + x;
+    }
 }
 "#####,
         r#####"
 fn main() {
-    92;
+    let sum = xs.iter().fold(0, |sum, x| sum + x);
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_remove_hash() {
+fn doctest_convert_to_guarded_return() {
     check(
-        "remove_hash",
+        "convert_to_guarded_return",
         r#####"
 fn main() {
-    r#"Hello,<|> World!"#;
+    <|>if cond {
+        foo();
+        bar();
+    }
 }
 "#####,
         r#####"
 fn main() {
-    r"Hello, World!";
+    if !cond {
+        return;
+    }
+    foo();
+    bar();
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_replace_if_let_with_match() {
+fn doctest_convert_to_min_max() {
     check(
-        "replace_if_let_with_match",
+        "convert_to_min_max",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+fn main() {
+    let mut smallest: i32 = i32::MAX;
+    <|>for x in xs {
+        if x < smallest {
+            smallest = x;
+        }
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let smallest: i32 = xs.into_iter().min().unwrap_or(i32::MAX);
+}
+"#####,
+    )
+}
 
-fn handle(action: Action) {
-    <|>if let Action::Move { distance } = action {
-        foo(distance)
-    } else {
-        bar()
+#[test]
+fn doctest_convert_vec_to_map() {
+    check(
+        "convert_vec_to_map",
+        r#####"
+fn main() {
+    let mut <|>ages = Vec::new();
+    ages.push((1u32, 20u8));
+    let known = ages.iter().any(|(id, _)| *id == 1);
+}
+"#####,
+        r#####"
+use std::collections::HashMap;
+
+fn main() {
+    let mut ages = HashMap::new();
+    ages.insert(1u32, 20u8);
+    let known = ages.contains_key(&1);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_default_from_new() {
+    check(
+        "default_from_new",
+        r#####"
+struct Registry { count: u32 }
+
+impl Registry {
+    pub fn <|>new() -> Self {
+        Registry { count: 0 }
     }
 }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+struct Registry { count: u32 }
 
-fn handle(action: Action) {
-    match action {
-        Action::Move { distance } => foo(distance),
-        _ => bar(),
+impl Registry {
+    pub fn new() -> Self {
+        Registry { count: 0 }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
     }
 }
 "#####,
@@ -571,14 +1096,2502 @@ fn handle(action: Action) {
 }
 
 #[test]
-fn doctest_split_import() {
+fn doctest_derive_default() {
     check(
-        "split_import",
+        "derive_default",
         r#####"
-use std::<|>collections::HashMap;
+#[derive(Debug)]
+struct <|>Options {
+    verbose: bool,
+    retries: u32,
+}
 "#####,
         r#####"
-use std::{collections::HashMap};
+#[derive(Debug, Default)]
+struct Options {
+    verbose: bool,
+    retries: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_derive_equality() {
+    check(
+        "derive_equality",
+        r#####"
+#[derive(Debug)]
+struct <|>Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+        r#####"
+#[derive(Debug, PartialEq, Eq)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_derive_ord() {
+    check(
+        "derive_ord",
+        r#####"
+#[derive(PartialEq, Eq)]
+struct Version { major: u32, minor: u32 }
+
+impl <|>Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major.cmp(&other.major).then(self.minor.cmp(&other.minor))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+"#####,
+        r#####"
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Version { major: u32, minor: u32 }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_derive_trivial_clone() {
+    check(
+        "derive_trivial_clone",
+        r#####"
+struct Point { x: u32, y: u32 }
+
+impl <|>Clone for Point {
+    fn clone(&self) -> Self {
+        Point { x: self.x.clone(), y: self.y.clone() }
+    }
+}
+"#####,
+        r#####"
+#[derive(Clone)]
+struct Point { x: u32, y: u32 }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_elide_lifetimes() {
+    check(
+        "elide_lifetimes",
+        r#####"
+fn first_word<'a><|>(s: &'a str) -> &'a str {
+    s
+}
+"#####,
+        r#####"
+fn first_word(s: &str) -> &str {
+    s
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_expand_field_shorthand() {
+    check(
+        "expand_field_shorthand",
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn point(x: i32, y: i32) -> Point {
+    Point { x, <|>y }
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn point(x: i32, y: i32) -> Point {
+    Point { x: x, y: y }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_expand_matches_macro() {
+    check(
+        "expand_matches_macro",
+        r#####"
+fn is_vowel(c: char) -> bool {
+    <|>matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+"#####,
+        r#####"
+fn is_vowel(c: char) -> bool {
+    match c {
+        'a' | 'e' | 'i' | 'o' | 'u' => true,
+        _ => false,
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_expand_nested_match() {
+    check(
+        "expand_nested_match",
+        r#####"
+enum Shape { Circle, Square }
+enum Item { Shape(Shape), Empty }
+
+fn draw(item: Item) {
+    match item {
+        Item::Shape(shape) => <|>unimplemented!(),
+        Item::Empty => (),
+    }
+}
+"#####,
+        r#####"
+enum Shape { Circle, Square }
+enum Item { Shape(Shape), Empty }
+
+fn draw(item: Item) {
+    match item {
+        Item::Shape(shape) => match shape {
+            Shape::Circle => unimplemented!(),
+            Shape::Square => unimplemented!(),
+        },
+        Item::Empty => (),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_expand_rest_pattern() {
+    check(
+        "expand_rest_pattern",
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+
+fn x(point: Point) -> i32 {
+    let Point { x, ..<|> } = point;
+    x
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+
+fn x(point: Point) -> i32 {
+    let Point { x, y, z } = point;
+    x
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_accessor_method() {
+    check(
+        "extract_accessor_method",
+        r#####"
+struct Theme;
+struct Config { theme: Theme }
+struct App { config: Config }
+
+impl App {
+    fn greet(&self) {
+        draw(&self.config.<|>theme);
+    }
+}
+"#####,
+        r#####"
+struct Theme;
+struct Config { theme: Theme }
+struct App { config: Config }
+
+impl App {
+    fn greet(&self) {
+        draw(self.theme());
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.config.theme
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_assertion_helper() {
+    check(
+        "extract_assertion_helper",
+        r#####"
+#[test]
+fn parses_numbers() {
+    <|>assert_eq!(parse("1"), Some(1));
+    assert_eq!(parse("42"), Some(42));
+}
+"#####,
+        r#####"
+#[test]
+fn parses_numbers() {
+    assert_helper("1", 1);
+    assert_helper("42", 42);
+}
+
+fn assert_helper(arg1: &str, arg2: i32) {
+    assert_eq!(parse(arg1), Some(arg2));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_common_field() {
+    check(
+        "extract_common_field",
+        r#####"
+enum Token<|> {
+    Ident { span: u32, name: u32 },
+    Comma { span: u32 },
+}
+
+fn span(token: &Token) -> u32 {
+    match token {
+        Token::Ident { span, .. } => *span,
+        Token::Comma { span } => *span,
+    }
+}
+"#####,
+        r#####"
+enum TokenKind {
+    Ident { name: u32 },
+    Comma,
+}
+
+struct Token {
+    span: u32,
+    kind: TokenKind,
+}
+
+fn span(token: &Token) -> u32 {
+    match token {
+        Token { span, kind: TokenKind::Ident { .. } } => *span,
+        Token { span, kind: TokenKind::Comma } => *span,
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_if_condition() {
+    check(
+        "extract_if_condition",
+        r#####"
+fn main() {
+    if x > 0 &&<|> y < 10 {
+        foo();
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let is_valid = x > 0 && y < 10;
+    if is_valid {
+        foo();
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_match_arm() {
+    check(
+        "extract_match_arm",
+        r#####"
+enum Shape { Circle(u32), Square(u32) }
+
+fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(r) => <|>{
+            let d = 2 * r;
+            d * d * 3 / 4
+        }
+        Shape::Square(a) => a * a,
+    }
+}
+"#####,
+        r#####"
+enum Shape { Circle(u32), Square(u32) }
+
+fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(r) => fun_name(r),
+        Shape::Square(a) => a * a,
+    }
+}
+
+fn fun_name(r: u32) -> u32 {
+    let d = 2 * r;
+    d * d * 3 / 4
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_reverse_helper() {
+    check(
+        "extract_reverse_helper",
+        r#####"
+fn main() {
+    let same = name == name.chars().rev()<|>.collect::<String>();
+}
+"#####,
+        r#####"
+fn main() {
+    let same = name == reverse(&name);
+}
+
+fn reverse(s: &str) -> String {
+    s.chars().rev().collect()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_type_alias() {
+    check(
+        "extract_type_alias",
+        r#####"
+struct Index {
+    by_name: HashMap<String, Vec<(u32, String)>>,
+}
+
+fn build(entries: &[String]) -> HashMap<<|>String, Vec<(u32, String)>> {
+    todo!()
+}
+"#####,
+        r#####"
+type Alias = HashMap<String, Vec<(u32, String)>>;
+
+struct Index {
+    by_name: Alias,
+}
+
+fn build(entries: &[String]) -> Alias {
+    todo!()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_visitor_trait() {
+    check(
+        "extract_visitor_trait",
+        r#####"
+enum Shape { Circle(u32), Square { side: u32 } }
+
+fn area(shape: Shape) -> u32 {
+    <|>match shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Square { side } => side * side,
+    }
+}
+"#####,
+        r#####"
+enum Shape { Circle(u32), Square { side: u32 } }
+
+fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Square { side } => side * side,
+    }
+}
+
+trait ShapeVisitor {
+    fn visit_circle(&mut self, r: u32) -> u32 {
+        3 * r * r
+    }
+
+    fn visit_square(&mut self, side: u32) -> u32 {
+        side * side
+    }
+}
+
+fn walk_shape(visitor: &mut impl ShapeVisitor, shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(r) => visitor.visit_circle(r),
+        Shape::Square { side } => visitor.visit_square(side),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_fill_match_arms() {
+    check(
+        "fill_match_arms",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        <|>
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } => (),
+        Action::Stop => (),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flatten_nested_result() {
+    check(
+        "flatten_nested_result",
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+struct Error;
+
+fn parse(text: &str) -> Result<Result<u32, Error>, Error> { todo!() }
+
+fn main() {
+    let n = <|>parse("1");
+}
+"#####,
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+struct Error;
+
+fn parse(text: &str) -> Result<Result<u32, Error>, Error> { todo!() }
+
+fn main() {
+    let n = parse("1").and_then(|it| it);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_binexpr() {
+    check(
+        "flip_binexpr",
+        r#####"
+fn main() {
+    let _ = 90 +<|> 2;
+}
+"#####,
+        r#####"
+fn main() {
+    let _ = 2 + 90;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_comma() {
+    check(
+        "flip_comma",
+        r#####"
+fn main() {
+    ((1, 2),<|> (3, 4));
+}
+"#####,
+        r#####"
+fn main() {
+    ((3, 4), (1, 2));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_to_early_return() {
+    check(
+        "flip_to_early_return",
+        r#####"
+fn first(s: Option<&str>) -> Option<char> {
+    <|>if let Some(s) = s {
+        let c = s.chars().next();
+        c
+    } else {
+        return None
+    }
+}
+"#####,
+        r#####"
+fn first(s: Option<&str>) -> Option<char> {
+    let s = match s {
+        Some(it) => it,
+        _ => return None,
+    };
+    let c = s.chars().next();
+    c
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_trait_bound() {
+    check(
+        "flip_trait_bound",
+        r#####"
+fn foo<T: Clone +<|> Copy>() { }
+"#####,
+        r#####"
+fn foo<T: Copy + Clone>() { }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_gate_behind_feature() {
+    check(
+        "gate_behind_feature",
+        r#####"
+/// Serializes the config.
+pub fn <|>to_json(config: &Config) -> String { todo!() }
+"#####,
+        r#####"
+/// Serializes the config.
+#[cfg(feature = "")]
+pub fn to_json(config: &Config) -> String { todo!() }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_gate_imports_with_cfg() {
+    check(
+        "gate_imports_with_cfg",
+        r#####"
+<|>use std::fs::File;
+use std::io::Read;<|>
+"#####,
+        r#####"
+#[cfg(feature = "")]
+use std::fs::File;
+#[cfg(feature = "")]
+use std::io::Read;
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generalize_to_blanket_impl() {
+    check(
+        "generalize_to_blanket_impl",
+        r#####"
+trait Describe { fn describe(&self) -> String; }
+struct Cat;
+struct Dog;
+
+impl <|>Describe for Cat {
+    fn describe(&self) -> String { self.to_string() }
+}
+
+impl Describe for Dog {
+    fn describe(&self) -> String { self.to_string() }
+}
+"#####,
+        r#####"
+trait Describe { fn describe(&self) -> String; }
+struct Cat;
+struct Dog;
+
+impl<T: Sized> Describe for T {
+    fn describe(&self) -> String { self.to_string() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_chained_setters() {
+    check(
+        "generate_chained_setters",
+        r#####"
+pub struct Request { timeout: u64, retries: u8 }
+
+impl<|> Request {
+    pub fn send(&self) {}
+}
+"#####,
+        r#####"
+pub struct Request { timeout: u64, retries: u8 }
+
+impl Request {
+    pub fn send(&self) {}
+
+    pub fn timeout(&mut self, timeout: u64) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retries(&mut self, retries: u8) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_group_consts_into_module() {
+    check(
+        "group_consts_into_module",
+        r#####"
+<|>const MAX_RETRIES: u32 = 3;
+const BACKOFF_MS: u64 = 500;<|>
+
+fn backoff(attempt: u32) -> u64 {
+    if attempt < MAX_RETRIES { BACKOFF_MS } else { 0 }
+}
+"#####,
+        r#####"
+mod constants {
+    pub(super) const MAX_RETRIES: u32 = 3;
+    pub(super) const BACKOFF_MS: u64 = 500;
+}
+
+fn backoff(attempt: u32) -> u64 {
+    if attempt < constants::MAX_RETRIES { constants::BACKOFF_MS } else { 0 }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_handle_lock_poisoning() {
+    check(
+        "handle_lock_poisoning",
+        r#####"
+struct Mutex<T>(T);
+impl<T> Mutex<T> { fn lock(&self) -> LockResult<T> { loop {} } }
+
+fn push(items: &Mutex<Vec<u32>>, item: u32) {
+    let mut items = items.lock().<|>unwrap();
+    items.push(item);
+}
+"#####,
+        r#####"
+struct Mutex<T>(T);
+impl<T> Mutex<T> { fn lock(&self) -> LockResult<T> { loop {} } }
+
+fn push(items: &Mutex<Vec<u32>>, item: u32) {
+    let mut items = match items.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    items.push(item);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_hide_return_type() {
+    check(
+        "hide_return_type",
+        r#####"
+fn numbers() -> Vec<u32><|> {
+    vec![1, 2, 3]
+}
+"#####,
+        r#####"
+fn numbers() -> impl Trait {
+    vec![1, 2, 3]
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_hoist_common_statements() {
+    check(
+        "hoist_common_statements",
+        r#####"
+fn main() {
+    <|>if fast {
+        start(x);
+        quick(x);
+        finish(x);
+    } else {
+        start(x);
+        slow(x);
+        finish(x);
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    start(x);
+    if fast {
+        quick(x);
+    } else {
+        slow(x);
+    }
+    finish(x);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_hoist_match_binding() {
+    check(
+        "hoist_match_binding",
+        r#####"
+enum Shape { Circle(u32, f64), Square(u32) }
+
+fn draw(shape: Shape) {
+    <|>match shape {
+        Shape::Circle(id, r) => circle(id, r),
+        Shape::Square(id) => square(id),
+    }
+}
+"#####,
+        r#####"
+enum Shape { Circle(u32, f64), Square(u32) }
+
+fn draw(shape: Shape) {
+    let id = match shape {
+        Shape::Circle(id, ..) | Shape::Square(id) => id,
+    };
+    match shape {
+        Shape::Circle(_, r) => circle(id, r),
+        Shape::Square(_) => square(id),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_hoist_out_of_loop() {
+    check(
+        "hoist_out_of_loop",
+        r#####"
+struct Scale { num: u32, den: u32 }
+
+fn sum(items: &[u32], scale: Scale) -> u32 {
+    let mut total = 0;
+    for item in items {
+        total += <|>scale.num / scale.den<|> * item;
+    }
+    total
+}
+"#####,
+        r#####"
+struct Scale { num: u32, den: u32 }
+
+fn sum(items: &[u32], scale: Scale) -> u32 {
+    let mut total = 0;
+    let var_name = scale.num / scale.den;
+    for item in items {
+        total += var_name * item;
+    }
+    total
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_ignore_must_use_result() {
+    check(
+        "ignore_must_use_result",
+        r#####"
+#[must_use]
+fn checked_add(a: u8, b: u8) -> Option<u8> { None }
+
+fn main() {
+    checked_add(<|>1, 2);
+}
+"#####,
+        r#####"
+#[must_use]
+fn checked_add(a: u8, b: u8) -> Option<u8> { None }
+
+fn main() {
+    let _ = checked_add(1, 2);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_ignore_unused_fields() {
+    check(
+        "ignore_unused_fields",
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+
+fn x(point: Point) -> i32 {
+    let Point { x, y, z }<|> = point;
+    x
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+
+fn x(point: Point) -> i32 {
+    let Point { x, .. } = point;
+    x
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_import_from_super() {
+    check(
+        "import_from_super",
+        r#####"
+fn double(x: u32) -> u32 { x * 2 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn doubles() {
+        let x = <|>double(2);
+    }
+}
+"#####,
+        r#####"
+fn double(x: u32) -> u32 { x * 2 }
+
+#[cfg(test)]
+mod tests {
+    use super::double;
+
+    #[test]
+    fn doubles() {
+        let x = double(2);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_local_variable() {
+    check(
+        "inline_local_variable",
+        r#####"
+fn main() {
+    let x<|> = 1 + 2;
+    x * 4;
+}
+"#####,
+        r#####"
+fn main() {
+    (1 + 2) * 4;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_single_variant_enum() {
+    check(
+        "inline_single_variant_enum",
+        r#####"
+enum <|>Meters { Value(u32) }
+
+fn double(m: Meters) -> Meters {
+    let Meters::Value(n) = m;
+    Meters::Value(n * 2)
+}
+"#####,
+        r#####"
+fn double(m: u32) -> u32 {
+    let n = m;
+    n * 2
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_wrapper() {
+    check(
+        "inline_wrapper",
+        r#####"
+fn area(w: u32, h: u32) -> u32 { w * h }
+
+fn <|>size(w: u32, h: u32) -> u32 {
+    area(w, h)
+}
+
+fn main() {
+    let s = size(1, 2);
+}
+"#####,
+        r#####"
+fn area(w: u32, h: u32) -> u32 { w * h }
+
+fn main() {
+    let s = area(1, 2);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_introduce_config_struct() {
+    check(
+        "introduce_config_struct",
+        r#####"
+fn connect(host: &str, <|>timeout: Option<u64>, retries: Option<u8>) {}
+
+fn main() {
+    connect("db", Some(30), None);
+}
+"#####,
+        r#####"
+#[derive(Default)]
+struct ConnectConfig {
+    timeout: Option<u64>,
+    retries: Option<u8>,
+}
+
+impl ConnectConfig {
+    fn with_timeout(mut self, timeout: u64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn with_retries(mut self, retries: u8) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+}
+
+fn connect(host: &str, config: ConnectConfig) {}
+
+fn main() {
+    connect("db", ConnectConfig::default().with_timeout(30));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_introduce_error_enum() {
+    check(
+        "introduce_error_enum",
+        r#####"
+fn parse_port(s: &str) -> Result<u16, &'static str> {<|>
+    if s.is_empty() {
+        return Err("empty port");
+    }
+    Err("not a number")
+}
+"#####,
+        r#####"
+#[derive(Debug)]
+enum ParsePortError {
+    EmptyPort,
+    NotANumber,
+}
+
+impl std::fmt::Display for ParsePortError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ParsePortError::EmptyPort => "empty port",
+            ParsePortError::NotANumber => "not a number",
+        };
+        f.write_str(message)
+    }
+}
+
+fn parse_port(s: &str) -> Result<u16, ParsePortError> {
+    if s.is_empty() {
+        return Err(ParsePortError::EmptyPort);
+    }
+    Err(ParsePortError::NotANumber)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_introduce_options_struct() {
+    check(
+        "introduce_options_struct",
+        r#####"
+fn draw(size: u32, <|>fill: bool, outline: bool) {
+    if fill {}
+}
+
+fn main() {
+    draw(1, true, false);
+}
+"#####,
+        r#####"
+struct DrawOptions {
+    fill: bool,
+    outline: bool,
+}
+
+fn draw(size: u32, options: DrawOptions) {
+    if options.fill {}
+}
+
+fn main() {
+    draw(1, DrawOptions { fill: true, outline: false });
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_introduce_variable() {
+    check(
+        "introduce_variable",
+        r#####"
+fn main() {
+    <|>(1 + 2)<|> * 4;
+}
+"#####,
+        r#####"
+fn main() {
+    let var_name = (1 + 2);
+    var_name * 4;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_invert_if() {
+    check(
+        "invert_if",
+        r#####"
+fn main() {
+    if<|> !y { A } else { B }
+}
+"#####,
+        r#####"
+fn main() {
+    if y { B } else { A }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_iterate_by_reference() {
+    check(
+        "iterate_by_reference",
+        r#####"
+fn main() {
+    <|>for name in names {
+        if name == query {
+            found(&name);
+        }
+    }
+    report(names);
+}
+"#####,
+        r#####"
+fn main() {
+    for name in &names {
+        if *name == query {
+            found(name);
+        }
+    }
+    report(names);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_iterate_over_elements() {
+    check(
+        "iterate_over_elements",
+        r#####"
+fn sum(values: &[u32]) -> u32 {
+    let mut total = 0;
+    for <|>i in 0..values.len() {
+        total += values[i];
+    }
+    total
+}
+"#####,
+        r#####"
+fn sum(values: &[u32]) -> u32 {
+    let mut total = 0;
+    for value in values {
+        total += *value;
+    }
+    total
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_join_params() {
+    check(
+        "join_params",
+        r#####"
+fn <|>area(
+    width: u32,
+    height: u32,
+) -> u32 {}
+"#####,
+        r#####"
+fn area(width: u32, height: u32) -> u32 {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_cloneable() {
+    check(
+        "make_cloneable",
+        r#####"
+struct Handle;
+
+struct <|>Window {
+    title: u32,
+    handle: Handle,
+}
+
+fn open() -> Window {
+    Window { title: 0, handle: Handle }
+}
+"#####,
+        r#####"
+use std::rc::Rc;
+
+struct Handle;
+
+#[derive(Clone)]
+struct Window {
+    title: u32,
+    handle: Rc<Handle>,
+}
+
+fn open() -> Window {
+    Window { title: 0, handle: Rc::new(Handle) }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_const_fn() {
+    check(
+        "make_const_fn",
+        r#####"
+fn <|>area(width: u32, height: u32) -> u32 {
+    width * height
+}
+"#####,
+        r#####"
+const fn area(width: u32, height: u32) -> u32 {
+    width * height
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_copy() {
+    check(
+        "make_copy",
+        r#####"
+#[derive(Debug)]
+struct <|>Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+        r#####"
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_fields_explicit() {
+    check(
+        "make_fields_explicit",
+        r#####"
+#[derive(Default)]
+struct Options { port: u16, verbose: bool, retries: Retries }
+
+fn options() -> Options {
+    Options { port: 8080, ..Default::default()<|> }
+}
+"#####,
+        r#####"
+#[derive(Default)]
+struct Options { port: u16, verbose: bool, retries: Retries }
+
+fn options() -> Options {
+    Options { port: 8080, verbose: false, retries: Default::default() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_method_required() {
+    check(
+        "make_method_required",
+        r#####"
+trait Shape {
+    fn name(&self) -> String<|> {
+        String::new()
+    }
+}
+
+struct Circle;
+
+impl Shape for Circle {}
+"#####,
+        r#####"
+trait Shape {
+    fn name(&self) -> String;
+}
+
+struct Circle;
+
+impl Shape for Circle {
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_raw_string() {
+    check(
+        "make_raw_string",
+        r#####"
+fn main() {
+    "Hello,<|> World!";
+}
+"#####,
+        r#####"
+fn main() {
+    r#"Hello, World!"#;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_return_type_concrete() {
+    check(
+        "make_return_type_concrete",
+        r#####"
+struct Meters(u32);
+trait Unit {}
+impl Unit for Meters {}
+
+fn distance() -> impl Un<|>it {
+    Meters(92)
+}
+"#####,
+        r#####"
+struct Meters(u32);
+trait Unit {}
+impl Unit for Meters {}
+
+fn distance() -> Meters {
+    Meters(92)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_synchronous() {
+    check(
+        "make_synchronous",
+        r#####"
+async fn <|>config() -> Config {
+    Config::default()
+}
+
+async fn run() {
+    let config = config().await;
+}
+"#####,
+        r#####"
+fn config() -> Config {
+    Config::default()
+}
+
+async fn run() {
+    let config = config();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_usual_string() {
+    check(
+        "make_usual_string",
+        r#####"
+fn main() {
+    r#"Hello,<|> "World!""#;
+}
+"#####,
+        r#####"
+fn main() {
+    "Hello, \"World!\"";
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_mark_deprecated() {
+    check(
+        "mark_deprecated",
+        r#####"
+/// Parses the config.
+pub fn <|>parse(text: &str) -> Config { todo!() }
+"#####,
+        r#####"
+/// Parses the config.
+#[deprecated(since = "", note = "")]
+pub fn parse(text: &str) -> Config { todo!() }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_merge_duplicate_ifs() {
+    check(
+        "merge_duplicate_ifs",
+        r#####"
+fn main() {
+    <|>if verbose {
+        print_header();
+    }
+    if verbose {
+        print_rows();
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    if verbose {
+        print_header();
+        print_rows();
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_merge_impl_blocks() {
+    check(
+        "merge_impl_blocks",
+        r#####"
+struct Counter(u32);
+
+impl <|>Counter {
+    fn get(&self) -> u32 { self.0 }
+}
+
+impl Counter {
+    fn reset(&mut self) { self.0 = 0 }
+}
+"#####,
+        r#####"
+struct Counter(u32);
+
+impl Counter {
+    fn get(&self) -> u32 { self.0 }
+
+    fn reset(&mut self) { self.0 = 0 }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_merge_match_arms() {
+    check(
+        "merge_match_arms",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        <|>Action::Move(..) => foo(),
+        Action::Stop => foo(),
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move(..) | Action::Stop => foo(),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_merge_write_calls() {
+    check(
+        "merge_write_calls",
+        r#####"
+fn fmt(&self, f: &mut Formatter) -> Result {
+    <|>write!(f, "({}", self.x)?;
+    write!(f, ", {})", self.y)
+}
+"#####,
+        r#####"
+fn fmt(&self, f: &mut Formatter) -> Result {
+    write!(f, "({}, {})", self.x, self.y)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_move_arm_cond_to_match_guard() {
+    check(
+        "move_arm_cond_to_match_guard",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } => <|>if distance > 10 { foo() },
+        _ => (),
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } if distance > 10 => foo(),
+        _ => (),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_move_binding_type_to_collect() {
+    check(
+        "move_binding_type_to_collect",
+        r#####"
+fn main() {
+    let <|>squares: Vec<u32> = (1..4).map(|it| it * it).collect();
+}
+"#####,
+        r#####"
+fn main() {
+    let squares = (1..4).map(|it| it * it).collect::<Vec<u32>>();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_move_bounds_to_where_clause() {
+    check(
+        "move_bounds_to_where_clause",
+        r#####"
+fn apply<T, U, <|>F: FnOnce(T) -> U>(f: F, x: T) -> U {
+    f(x)
+}
+"#####,
+        r#####"
+fn apply<T, U, F>(f: F, x: T) -> U where F: FnOnce(T) -> U {
+    f(x)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_move_collect_type_to_binding() {
+    check(
+        "move_collect_type_to_binding",
+        r#####"
+fn main() {
+    let <|>squares = (1..4).map(|it| it * it).collect::<Vec<u32>>();
+}
+"#####,
+        r#####"
+fn main() {
+    let squares: Vec<u32> = (1..4).map(|it| it * it).collect();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_move_guard_to_arm_body() {
+    check(
+        "move_guard_to_arm_body",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } <|>if distance > 10 => foo(),
+        _ => (),
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } => if distance > 10 { foo() },
+        _ => (),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_name_closure_type() {
+    check(
+        "name_closure_type",
+        r#####"
+fn apply(x: u32, f: impl Fn<|>(u32) -> u32) -> u32 {
+    f(x)
+}
+"#####,
+        r#####"
+fn apply<F: Fn(u32) -> u32>(x: u32, f: F) -> u32 {
+    f(x)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_name_variant_fields() {
+    check(
+        "name_variant_fields",
+        r#####"
+enum Shape {
+    Rect<|>(u32, u32),
+}
+
+fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Rect(w, h) => w * h,
+    }
+}
+"#####,
+        r#####"
+enum Shape {
+    Rect { first: u32, second: u32 },
+}
+
+fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Rect { first: w, second: h } => w * h,
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_promote_doc_example() {
+    check(
+        "promote_doc_example",
+        r#####"
+/// Adds one.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(add_one(1), 2);
+/// ```
+fn <|>add_one(x: u32) -> u32 { x + 1 }
+"#####,
+        r#####"
+/// Adds one.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(add_one(1), 2);
+/// ```
+fn add_one(x: u32) -> u32 { x + 1 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_one_example() {
+        assert_eq!(add_one(1), 2);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_dbg() {
+    check(
+        "remove_dbg",
+        r#####"
+fn main() {
+    <|>dbg!(92);
+}
+"#####,
+        r#####"
+fn main() {
+    92;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_hash() {
+    check(
+        "remove_hash",
+        r#####"
+fn main() {
+    r#"Hello,<|> World!"#;
+}
+"#####,
+        r#####"
+fn main() {
+    r"Hello, World!";
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_redundant_size_hint() {
+    check(
+        "remove_redundant_size_hint",
+        r#####"
+impl Iterator for Lines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> { self.read_line() }
+
+    fn <|>size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+"#####,
+        r#####"
+impl Iterator for Lines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> { self.read_line() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_expect_with_error() {
+    check(
+        "replace_expect_with_error",
+        r#####"
+enum Option<T> { Some(T), None }
+
+fn lookup(key: &str) -> Option<u32> { None }
+
+fn get(key: &str) -> Result<u32, String> {
+    let value = lookup(key).<|>expect("missing key");
+    Ok(value)
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+
+fn lookup(key: &str) -> Option<u32> { None }
+
+fn get(key: &str) -> Result<u32, String> {
+    let value = lookup(key).ok_or_else(|| String::from("missing key"))?;
+    Ok(value)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_if_let_ok_with_match() {
+    check(
+        "replace_if_let_ok_with_match",
+        r#####"
+fn load(path: &str) -> Config {
+    <|>if let Ok(text) = read_to_string(path) {
+        parse(&text)
+    } else {
+        Config::default()
+    }
+}
+"#####,
+        r#####"
+fn load(path: &str) -> Config {
+    match read_to_string(path) {
+        Ok(text) => parse(&text),
+        Err(e) => Config::default(),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_if_let_with_match() {
+    check(
+        "replace_if_let_with_match",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    <|>if let Action::Move { distance } = action {
+        foo(distance)
+    } else {
+        bar()
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { distance } => foo(distance),
+        _ => bar(),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_try_macro() {
+    check(
+        "replace_try_macro",
+        r#####"
+fn read() -> Result<u32, ()> {
+    let x = <|>try!(parse());
+    Ok(x)
+}
+"#####,
+        r#####"
+fn read() -> Result<u32, ()> {
+    let x = parse()?;
+    Ok(x)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_return_iterator() {
+    check(
+        "return_iterator",
+        r#####"
+fn squares(n: u32) -> Vec<u32><|> {
+    (0..n).map(|x| x * x).collect()
+}
+"#####,
+        r#####"
+fn squares(n: u32) -> impl Iterator<Item = u32> {
+    (0..n).map(|x| x * x)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_simplify_bool_comparison() {
+    check(
+        "simplify_bool_comparison",
+        r#####"
+fn main() {
+    let done = false;
+    if done <|>== false {}
+}
+"#####,
+        r#####"
+fn main() {
+    let done = false;
+    if !done {}
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_simplify_closure_body() {
+    check(
+        "simplify_closure_body",
+        r#####"
+fn main() {
+    let double = <|>|x: u32| {
+        x * 2
+    };
+}
+"#####,
+        r#####"
+fn main() {
+    let double = |x: u32| x * 2;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_simplify_degenerate_loop() {
+    check(
+        "simplify_degenerate_loop",
+        r#####"
+fn main() {
+    let total = <|>loop {
+        let base = price();
+        break base + tax(base);
+    };
+}
+"#####,
+        r#####"
+fn main() {
+    let total = {
+        let base = price();
+        base + tax(base)
+    };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_simplify_to_filter_map() {
+    check(
+        "simplify_to_filter_map",
+        r#####"
+fn main() {
+    let port = parse(s).<|>and_then(|n| if n > 1024 { Some(n * 2) } else { None });
+}
+"#####,
+        r#####"
+fn main() {
+    let port = parse(s).filter(|n| *n > 1024).map(|n| n * 2);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_split_function() {
+    check(
+        "split_function",
+        r#####"
+fn main() {
+    let x = 1;
+<|>
+    let y = x * 2;
+    foo(y);
+}
+"#####,
+        r#####"
+fn main() {
+    let x = 1;
+
+    fun_name(x);
+}
+
+fn fun_name(x: i32) {
+    let y = x * 2;
+    foo(y);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_split_impl_block() {
+    check(
+        "split_impl_block",
+        r#####"
+struct Counter(u32);
+
+impl Counter {
+    fn get(&self) -> u32 { self.0 }
+    <|>fn reset(&mut self) { self.0 = 0 }<|>
+}
+"#####,
+        r#####"
+struct Counter(u32);
+
+impl Counter {
+    fn get(&self) -> u32 { self.0 }
+}
+
+impl Counter {
+    fn reset(&mut self) { self.0 = 0 }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_split_import() {
+    check(
+        "split_import",
+        r#####"
+use std::<|>collections::HashMap;
+"#####,
+        r#####"
+use std::{collections::HashMap};
+"#####,
+    )
+}
+
+#[test]
+fn doctest_split_where_bounds() {
+    check(
+        "split_where_bounds",
+        r#####"
+fn print<T>(x: T) where <|>T: Debug + Clone {}
+"#####,
+        r#####"
+fn print<T>(x: T) where T: Debug, T: Clone {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_swap_if_branches() {
+    check(
+        "swap_if_branches",
+        r#####"
+fn main() {
+    if<|> x > 0 { foo() } else { bar() }
+}
+"#####,
+        r#####"
+fn main() {
+    if !(x > 0) { bar() } else { foo() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_take_self_by_ref() {
+    check(
+        "take_self_by_ref",
+        r#####"
+struct User { name: String, age: u32 }
+
+impl User {
+    fn <|>is_adult(self) -> bool {
+        self.age >= 18
+    }
+}
+"#####,
+        r#####"
+struct User { name: String, age: u32 }
+
+impl User {
+    fn is_adult(&self) -> bool {
+        self.age >= 18
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_bool_then() {
+    check(
+        "use_bool_then",
+        r#####"
+fn first(v: &[u8]) -> Option<u8> {
+    <|>if v.len() > 0 { Some(v[0]) } else { None }
+}
+"#####,
+        r#####"
+fn first(v: &[u8]) -> Option<u8> {
+    (v.len() > 0).then(|| v[0])
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_destructured_bindings() {
+    check(
+        "use_destructured_bindings",
+        r#####"
+fn main() {
+    let pair = (1, 2);
+    <|>let (first, second) = pair;
+    report(first, pair.1);
+    let sum = pair.0 + second;
+}
+"#####,
+        r#####"
+fn main() {
+    let pair = (1, 2);
+    let (first, second) = pair;
+    report(first, second);
+    let sum = first + second;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_direct_indexing() {
+    check(
+        "use_direct_indexing",
+        r#####"
+fn first(words: &[String]) -> usize {
+    words.get(0).unwrap<|>().len()
+}
+"#####,
+        r#####"
+fn first(words: &[String]) -> usize {
+    words[0].len()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_field_shorthand() {
+    check(
+        "use_field_shorthand",
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn point(x: i32, y: i32) -> Point {
+    Point { x: x, <|>y: y }
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn point(x: i32, y: i32) -> Point {
+    Point { x, y }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_fs_read() {
+    check(
+        "use_fs_read",
+        r#####"
+mod std { pub mod fs { pub struct File; } }
+use std::fs::File;
+struct String;
+impl String { fn new() -> String { String } }
+
+fn load(path: &str) -> io::Result<String> {
+    let mut <|>file = File::open(path)?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(text)
+}
+"#####,
+        r#####"
+mod std { pub mod fs { pub struct File; } }
+use std::fs::File;
+struct String;
+impl String { fn new() -> String { String } }
+
+fn load(path: &str) -> io::Result<String> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(text)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_implicit_return() {
+    check(
+        "use_implicit_return",
+        r#####"
+fn save(path: &str) -> Result<(), Error> {
+    write(path)?;
+    <|>return Ok(());
+}
+"#####,
+        r#####"
+fn save(path: &str) -> Result<(), Error> {
+    write(path)?;
+    Ok(())
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_is_empty() {
+    check(
+        "use_is_empty",
+        r#####"
+struct Stack;
+impl Stack {
+    fn len(&self) -> usize { 0 }
+    fn is_empty(&self) -> bool { true }
+}
+
+fn has_items(s: &Stack) -> bool {
+    s.len() <|>!= 0
+}
+"#####,
+        r#####"
+struct Stack;
+impl Stack {
+    fn len(&self) -> usize { 0 }
+    fn is_empty(&self) -> bool { true }
+}
+
+fn has_items(s: &Stack) -> bool {
+    !s.is_empty()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_matches_macro() {
+    check(
+        "use_matches_macro",
+        r#####"
+fn is_vowel(c: char) -> bool {
+    <|>match c {
+        'a' | 'e' | 'i' | 'o' | 'u' => true,
+        _ => false,
+    }
+}
+"#####,
+        r#####"
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_mem_take() {
+    check(
+        "use_mem_take",
+        r#####"
+struct Buffer { lines: Lines }
+struct Lines;
+
+fn flush(buffer: &mut Buffer) -> Lines {
+    mem::replace<|>(&mut buffer.lines, Lines::default())
+}
+"#####,
+        r#####"
+struct Buffer { lines: Lines }
+struct Lines;
+
+fn flush(buffer: &mut Buffer) -> Lines {
+    mem::take(&mut buffer.lines)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_runtime_length() {
+    check(
+        "use_runtime_length",
+        r#####"
+fn sum<const N: usize>(<|>values: [u32; N]) -> u32 {
+    let mut total = 0;
+    for i in 0..N {
+        total += values[i];
+    }
+    total
+}
+"#####,
+        r#####"
+fn sum(values: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..values.len() {
+        total += values[i];
+    }
+    total
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_sort_by_key() {
+    check(
+        "use_sort_by_key",
+        r#####"
+struct Person { age: u32 }
+
+fn sort(people: &mut [Person]) {
+    people.<|>sort_by(|a: &Person, b: &Person| a.age.cmp(&b.age));
+}
+"#####,
+        r#####"
+struct Person { age: u32 }
+
+fn sort(people: &mut [Person]) {
+    people.sort_by_key(|x| x.age);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_string_directly() {
+    check(
+        "use_string_directly",
+        r#####"
+struct Vec<T>(T);
+
+fn greeting(name: &str) -> String {
+    let mut <|>buf: Vec<u8> = Vec::new();
+    buf.extend_from_slice(b"Hello, ");
+    buf.extend_from_slice(name.as_bytes());
+    buf.push(b'!');
+    String::from_utf8(buf).unwrap()
+}
+"#####,
+        r#####"
+struct Vec<T>(T);
+
+fn greeting(name: &str) -> String {
+    let mut buf: String = String::new();
+    buf.push_str("Hello, ");
+    buf.push_str(name);
+    buf.push('!');
+    buf
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_struct_literal() {
+    check(
+        "use_struct_literal",
+        r#####"
+#[derive(Default)]
+struct Config { verbose: bool, level: u8 }
+
+fn main() {
+    let mut <|>config: Config = Default::default();
+    config.verbose = true;
+    config.level = 3;
+    run(config);
+}
+"#####,
+        r#####"
+#[derive(Default)]
+struct Config { verbose: bool, level: u8 }
+
+fn main() {
+    let config = Config { verbose: true, level: 3, ..Default::default() };
+    run(config);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_to_vec() {
+    check(
+        "use_to_vec",
+        r#####"
+struct Vec<T>(T);
+
+fn keys(keys: &[u32]) -> Vec<u32> {
+    keys.iter().cloned().<|>collect::<Vec<_>>()
+}
+"#####,
+        r#####"
+struct Vec<T>(T);
+
+fn keys(keys: &[u32]) -> Vec<u32> {
+    keys.to_vec()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_use_unwrap_or_default() {
+    check(
+        "use_unwrap_or_default",
+        r#####"
+enum Option<T> { Some(T), None }
+
+#[derive(Default)]
+struct Settings { verbose: bool }
+
+fn load() -> Option<Settings> { None }
+
+fn main() {
+    let settings = load().<|>expect("no settings");
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+
+#[derive(Default)]
+struct Settings { verbose: bool }
+
+fn load() -> Option<Settings> { None }
+
+fn main() {
+    let settings = load().unwrap_or_default();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_in_newtype() {
+    check(
+        "wrap_in_newtype",
+        r#####"
+struct User {
+    id: <|>u64,
+}
+"#####,
+        r#####"
+struct Id(u64);
+
+struct User {
+    id: Id,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_in_spawn_blocking() {
+    check(
+        "wrap_in_spawn_blocking",
+        r#####"
+mod tokio { pub mod task { pub fn spawn_blocking() {} } }
+
+async fn load(path: String) -> Result<String, Error> {
+    let text = <|>read_to_string(path);
+    Ok(text)
+}
+"#####,
+        r#####"
+mod tokio { pub mod task { pub fn spawn_blocking() {} } }
+
+async fn load(path: String) -> Result<String, Error> {
+    let text = tokio::task::spawn_blocking(move || read_to_string(path)).await?;
+    Ok(text)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_params() {
+    check(
+        "wrap_params",
+        r#####"
+fn <|>render(template: &Template, context: &Context, output: &mut dyn Write) -> Result<(), RenderError> {}
+"#####,
+        r#####"
+fn render(
+    template: &Template,
+    context: &Context,
+    output: &mut dyn Write,
+) -> Result<(), RenderError> {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_static_mut() {
+    check(
+        "wrap_static_mut",
+        r#####"
+static mut COUNTER<|>: u32 = 0;
+
+fn inc() {
+    unsafe { COUNTER += 1; }
+}
+"#####,
+        r#####"
+static COUNTER: std::sync::Mutex<u32> = std::sync::Mutex::new(0);
+
+fn counter() -> std::sync::MutexGuard<'static, u32> {
+    COUNTER.lock().unwrap()
+}
+
+fn inc() {
+    unsafe { *counter() += 1; }
+}
 "#####,
     )
 }
@@ -7,13 +7,13 @@
 
 mod assist_ctx;
 mod marks;
+mod utils;
 #[cfg(test)]
 mod doc_tests;
 #[cfg(test)]
 mod test_db;
 pub mod ast_transform;
 
-use either::Either;
 use hir::db::HirDatabase;
 use ra_db::FileRange;
 use ra_syntax::{TextRange, TextUnit};
@@ -24,7 +24,10 @@ pub use crate::assists::add_import::auto_import_text_edit;
 
 /// Unique identifier of the assist, should not be shown to the user
 /// directly.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Also used as a tie breaker when sorting assists, so that the final list is
+/// deterministic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct AssistId(pub &'static str);
 
 #[derive(Debug, Clone)]
@@ -42,17 +45,29 @@ pub struct AssistAction {
     pub target: Option<TextRange>,
 }
 
+/// The edits of a resolved assist.
+#[derive(Debug, Clone)]
+pub enum ActionData {
+    Single(AssistAction),
+    /// Mutually exclusive alternatives, the user picks one of them.
+    Group(Vec<AssistAction>),
+    /// Steps which build on each other, like generating a struct and then an
+    /// impl for it. All of them are applied in order, each one to the text
+    /// produced by the previous steps.
+    Sequence(Vec<AssistAction>),
+}
+
 #[derive(Debug, Clone)]
 pub struct ResolvedAssist {
     pub label: AssistLabel,
-    pub action_data: Either<AssistAction, Vec<AssistAction>>,
+    pub action_data: ActionData,
 }
 
 impl ResolvedAssist {
     pub fn get_first_action(&self) -> AssistAction {
         match &self.action_data {
-            Either::Left(action) => action.clone(),
-            Either::Right(actions) => actions[0].clone(),
+            ActionData::Single(action) => action.clone(),
+            ActionData::Group(actions) | ActionData::Sequence(actions) => actions[0].clone(),
         }
     }
 }
@@ -80,7 +95,8 @@ where
 /// Return all the assists applicable at the given position.
 ///
 /// Assists are returned in the "resolved" state, that is with edit fully
-/// computed.
+/// computed. More specific assists (with smaller target ranges) come first,
+/// assists with equal targets are ordered by their `AssistId`.
 pub fn assists<H>(db: &H, range: FileRange) -> Vec<ResolvedAssist>
 where
     H: HirDatabase + 'static,
@@ -96,11 +112,14 @@ where
                 Assist::Unresolved { .. } => unreachable!(),
             })
             .collect::<Vec<_>>();
-        a.sort_by(|a, b| match (a.get_first_action().target, b.get_first_action().target) {
-            (Some(a), Some(b)) => a.len().cmp(&b.len()),
-            (Some(_), None) => Ordering::Less,
-            (None, Some(_)) => Ordering::Greater,
-            (None, None) => Ordering::Equal,
+        a.sort_by(|a, b| {
+            let by_target = match (a.get_first_action().target, b.get_first_action().target) {
+                (Some(a), Some(b)) => a.len().cmp(&b.len()),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            by_target.then_with(|| a.label.id.cmp(&b.label.id))
         });
         a
     })
@@ -134,6 +153,123 @@ mod assists {
     mod move_guard;
     mod move_bounds;
     mod early_return;
+    mod combine_if_let;
+    mod extract_if_condition;
+    mod swap_if_branches;
+    mod wrap_static_mut;
+    mod elide_lifetimes;
+    mod split_function;
+    mod convert_to_fold;
+    mod convert_to_count_or_sum;
+    mod convert_to_min_max;
+    mod make_return_type_concrete;
+    mod hide_return_type;
+    mod add_as_ref_impls;
+    mod change_option_to_result;
+    mod change_result_to_option;
+    mod add_partial_cmp;
+    mod box_recursive_field;
+    mod convert_field_to_cow;
+    mod name_closure_type;
+    mod convert_vec_to_map;
+    mod wrap_in_newtype;
+    mod merge_impl_blocks;
+    mod split_impl_block;
+    mod make_method_required;
+    mod add_default_implementation;
+    mod replace_try_macro;
+    mod iterate_over_elements;
+    mod add_enumerate;
+    mod hoist_match_binding;
+    mod import_from_super;
+    mod replace_expect_with_error;
+    mod inline_wrapper;
+    mod convert_if_chain_to_match;
+    mod convert_match_to_if_chain;
+    mod flip_to_early_return;
+    mod build_with_iterator;
+    mod collect_into_result;
+    mod expand_nested_match;
+    mod introduce_options_struct;
+    mod take_self_by_ref;
+    mod add_track_caller;
+    mod use_sort_by_key;
+    mod extract_match_arm;
+    mod use_runtime_length;
+    mod make_cloneable;
+    mod derive_trivial_clone;
+    mod make_const_fn;
+    mod inline_single_variant_enum;
+    mod collect_into_map;
+    mod hoist_common_statements;
+    mod extract_reverse_helper;
+    mod make_copy;
+    mod iterate_by_reference;
+    mod derive_equality;
+    mod use_implicit_return;
+    mod use_struct_literal;
+    mod combine_into_let_else;
+    mod change_closure_capture;
+    mod add_drop_impl;
+    mod simplify_degenerate_loop;
+    mod extract_accessor_method;
+    mod collapse_map_projections;
+    mod derive_default;
+    mod convert_boxed_str_field;
+    mod use_matches_macro;
+    mod expand_matches_macro;
+    mod use_bool_then;
+    mod simplify_to_filter_map;
+    mod add_error_from;
+    mod promote_doc_example;
+    mod use_is_empty;
+    mod simplify_bool_comparison;
+    mod gate_imports_with_cfg;
+    mod gate_behind_feature;
+    mod default_from_new;
+    mod use_destructured_bindings;
+    mod derive_ord;
+    mod split_where_bounds;
+    mod return_iterator;
+    mod add_explicit_drop;
+    mod use_mem_take;
+    mod hoist_out_of_loop;
+    mod add_phantom_data;
+    mod make_synchronous;
+    mod wrap_in_spawn_blocking;
+    mod handle_lock_poisoning;
+    mod extract_assertion_helper;
+    mod rest_pattern;
+    mod add_into_iterator_impl;
+    mod ignore_must_use_result;
+    mod merge_duplicate_ifs;
+    mod use_to_vec;
+    mod extract_type_alias;
+    mod extract_visitor_trait;
+    mod introduce_error_enum;
+    mod extract_common_field;
+    mod closure_body;
+    mod add_bounds_check;
+    mod use_string_directly;
+    mod add_from_str_impl;
+    mod merge_write_calls;
+    mod name_variant_fields;
+    mod add_doc_alias;
+    mod collect_type;
+    mod add_tracing_span;
+    mod use_fs_read;
+    mod wrap_params;
+    mod use_direct_indexing;
+    mod generate_chained_setters;
+    mod generalize_to_blanket_impl;
+    mod make_fields_explicit;
+    mod convert_to_any_or_find;
+    mod mark_deprecated;
+    mod field_shorthand;
+    mod group_consts_into_module;
+    mod use_unwrap_or_default;
+    mod remove_redundant_size_hint;
+    mod flatten_nested_result;
 
     pub(crate) fn all<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assist>] {
         &[
@@ -152,6 +288,7 @@ mod assists {
             flip_trait_bound::flip_trait_bound,
             introduce_variable::introduce_variable,
             replace_if_let_with_match::replace_if_let_with_match,
+            replace_if_let_with_match::replace_if_let_ok_with_match,
             split_import::split_import,
             remove_dbg::remove_dbg,
             add_import::add_import,
@@ -166,17 +303,141 @@ mod assists {
             raw_string::make_usual_string,
             raw_string::remove_hash,
             early_return::convert_to_guarded_return,
+            combine_if_let::combine_if_let,
+            extract_if_condition::extract_if_condition,
+            swap_if_branches::swap_if_branches,
+            wrap_static_mut::wrap_static_mut,
+            elide_lifetimes::elide_lifetimes,
+            split_function::split_function,
+            convert_to_fold::convert_to_fold,
+            convert_to_count_or_sum::convert_to_count_or_sum,
+            convert_to_min_max::convert_to_min_max,
+            make_return_type_concrete::make_return_type_concrete,
+            hide_return_type::hide_return_type,
+            add_as_ref_impls::add_as_ref_impls,
+            change_option_to_result::change_option_to_result,
+            change_result_to_option::change_result_to_option,
+            add_partial_cmp::add_partial_cmp,
+            box_recursive_field::box_recursive_field,
+            box_recursive_field::box_recursive_variant,
+            convert_field_to_cow::convert_field_to_cow,
+            name_closure_type::name_closure_type,
+            convert_vec_to_map::convert_vec_to_map,
+            wrap_in_newtype::wrap_in_newtype,
+            merge_impl_blocks::merge_impl_blocks,
+            split_impl_block::split_impl_block,
+            make_method_required::make_method_required,
+            add_default_implementation::add_default_implementation,
+            replace_try_macro::replace_try_macro,
+            iterate_over_elements::iterate_over_elements,
+            add_enumerate::add_enumerate,
+            hoist_match_binding::hoist_match_binding,
+            import_from_super::import_from_super,
+            replace_expect_with_error::replace_expect_with_error,
+            inline_wrapper::inline_wrapper,
+            convert_if_chain_to_match::convert_if_chain_to_match,
+            convert_match_to_if_chain::convert_match_to_if_chain,
+            flip_to_early_return::flip_to_early_return,
+            build_with_iterator::build_with_iterator,
+            collect_into_result::collect_into_result,
+            expand_nested_match::expand_nested_match,
+            introduce_options_struct::introduce_options_struct,
+            introduce_options_struct::introduce_config_struct,
+            take_self_by_ref::take_self_by_ref,
+            add_track_caller::add_track_caller,
+            use_sort_by_key::use_sort_by_key,
+            extract_match_arm::extract_match_arm,
+            use_runtime_length::use_runtime_length,
+            make_cloneable::make_cloneable,
+            derive_trivial_clone::derive_trivial_clone,
+            make_const_fn::make_const_fn,
+            inline_single_variant_enum::inline_single_variant_enum,
+            collect_into_map::collect_into_map,
+            hoist_common_statements::hoist_common_statements,
+            extract_reverse_helper::extract_reverse_helper,
+            make_copy::make_copy,
+            iterate_by_reference::iterate_by_reference,
+            derive_equality::derive_equality,
+            use_implicit_return::use_implicit_return,
+            use_struct_literal::use_struct_literal,
+            combine_into_let_else::combine_into_let_else,
+            change_closure_capture::change_closure_capture,
+            add_drop_impl::add_drop_impl,
+            simplify_degenerate_loop::simplify_degenerate_loop,
+            extract_accessor_method::extract_accessor_method,
+            collapse_map_projections::collapse_map_projections,
+            derive_default::derive_default,
+            convert_boxed_str_field::convert_boxed_str_field,
+            use_matches_macro::use_matches_macro,
+            expand_matches_macro::expand_matches_macro,
+            use_bool_then::use_bool_then,
+            simplify_to_filter_map::simplify_to_filter_map,
+            add_error_from::add_error_from,
+            promote_doc_example::promote_doc_example,
+            use_is_empty::use_is_empty,
+            simplify_bool_comparison::simplify_bool_comparison,
+            gate_imports_with_cfg::gate_imports_with_cfg,
+            gate_behind_feature::gate_behind_feature,
+            default_from_new::default_from_new,
+            use_destructured_bindings::use_destructured_bindings,
+            derive_ord::derive_ord,
+            split_where_bounds::split_where_bounds,
+            return_iterator::return_iterator,
+            add_explicit_drop::add_explicit_drop,
+            use_mem_take::use_mem_take,
+            hoist_out_of_loop::hoist_out_of_loop,
+            add_phantom_data::add_phantom_data,
+            make_synchronous::make_synchronous,
+            wrap_in_spawn_blocking::wrap_in_spawn_blocking,
+            handle_lock_poisoning::handle_lock_poisoning,
+            extract_assertion_helper::extract_assertion_helper,
+            rest_pattern::ignore_unused_fields,
+            rest_pattern::expand_rest_pattern,
+            add_into_iterator_impl::add_into_iterator_impl,
+            ignore_must_use_result::ignore_must_use_result,
+            merge_duplicate_ifs::merge_duplicate_ifs,
+            use_to_vec::use_to_vec,
+            extract_type_alias::extract_type_alias,
+            extract_visitor_trait::extract_visitor_trait,
+            introduce_error_enum::introduce_error_enum,
+            extract_common_field::extract_common_field,
+            closure_body::simplify_closure_body,
+            closure_body::add_block_to_closure,
+            add_bounds_check::add_bounds_check,
+            use_string_directly::use_string_directly,
+            add_from_str_impl::add_from_str_impl,
+            merge_write_calls::merge_write_calls,
+            name_variant_fields::name_variant_fields,
+            add_doc_alias::add_doc_alias,
+            collect_type::move_collect_type_to_binding,
+            collect_type::move_binding_type_to_collect,
+            add_tracing_span::add_tracing_span,
+            use_fs_read::use_fs_read,
+            wrap_params::wrap_params,
+            wrap_params::join_params,
+            use_direct_indexing::use_direct_indexing,
+            generate_chained_setters::generate_chained_setters,
+            generalize_to_blanket_impl::generalize_to_blanket_impl,
+            make_fields_explicit::make_fields_explicit,
+            convert_to_any_or_find::convert_to_any_or_find,
+            mark_deprecated::mark_deprecated,
+            field_shorthand::use_field_shorthand,
+            field_shorthand::expand_field_shorthand,
+            group_consts_into_module::group_consts_into_module,
+            use_unwrap_or_default::use_unwrap_or_default,
+            remove_redundant_size_hint::remove_redundant_size_hint,
+            flatten_nested_result::flatten_nested_result,
         ]
     }
 }
 
 #[cfg(test)]
 mod helpers {
-    use ra_db::{fixture::WithFixture, FileRange};
-    use ra_syntax::TextRange;
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
+    use ra_syntax::{TextRange, TextUnit};
     use test_utils::{add_cursor, assert_eq_text, extract_offset, extract_range};
 
-    use crate::{test_db::TestDB, Assist, AssistCtx};
+    use crate::{test_db::TestDB, ActionData, Assist, AssistCtx, ResolvedAssist};
 
     pub(crate) fn check_assist(
         assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
@@ -187,6 +448,54 @@ mod helpers {
         let (db, file_id) = TestDB::with_single_file(&before);
         let frange =
             FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
+        let assist =
+            AssistCtx::with_ctx(&db, frange, true, assist).expect("code action is not applicable");
+        let assist = match assist {
+            Assist::Unresolved { .. } => unreachable!(),
+            Assist::Resolved { assist } => assist,
+        };
+
+        let (actual, actual_cursor_pos) = apply_assist(&assist, &before, before_cursor_pos);
+        let actual_cursor_pos = actual_cursor_pos.expect("cursor position is affected by the edit");
+        let actual = add_cursor(&actual, actual_cursor_pos);
+        assert_eq_text!(after, &actual);
+    }
+
+    /// Applies the first action of the assist, or all of the steps of a
+    /// sequence in order. Returns the new text and cursor position.
+    pub(crate) fn apply_assist(
+        assist: &ResolvedAssist,
+        before: &str,
+        cursor_pos: TextUnit,
+    ) -> (String, Option<TextUnit>) {
+        let actions = match &assist.action_data {
+            ActionData::Sequence(actions) => actions.clone(),
+            _ => vec![assist.get_first_action()],
+        };
+        let mut text = before.to_string();
+        let mut cursor_pos = Some(cursor_pos);
+        for action in actions {
+            text = action.edit.apply(&text);
+            cursor_pos = match action.cursor_position {
+                None => cursor_pos.and_then(|it| action.edit.apply_to_offset(it)),
+                Some(off) => Some(off),
+            };
+        }
+        (text, cursor_pos)
+    }
+
+    /// Like `check_assist`, for a fixture with several files or crates. Only
+    /// the file with the cursor is compared.
+    pub(crate) fn check_assist_in_fixture(
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+        before: &str,
+        after: &str,
+    ) {
+        let (db, position) = TestDB::with_position(before);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
         let assist =
             AssistCtx::with_ctx(&db, frange, true, assist).expect("code action is not applicable");
         let action = match assist {
@@ -194,11 +503,11 @@ mod helpers {
             Assist::Resolved { assist } => assist.get_first_action(),
         };
 
-        let actual = action.edit.apply(&before);
+        let actual = action.edit.apply(&db.file_text(position.file_id));
         let actual_cursor_pos = match action.cursor_position {
             None => action
                 .edit
-                .apply_to_offset(before_cursor_pos)
+                .apply_to_offset(position.offset)
                 .expect("cursor position is affected by the edit"),
             Some(off) => off,
         };
@@ -206,6 +515,32 @@ mod helpers {
         assert_eq_text!(after, &actual);
     }
 
+    /// Checks each of the alternative actions of an assist offering a group.
+    pub(crate) fn check_assist_group(
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+        before: &str,
+        after: &[&str],
+    ) {
+        let (before_cursor_pos, before) = extract_offset(before);
+        let (db, file_id) = TestDB::with_single_file(&before);
+        let frange =
+            FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
+        let assist =
+            AssistCtx::with_ctx(&db, frange, true, assist).expect("code action is not applicable");
+        let actions = match assist {
+            Assist::Unresolved { .. } => unreachable!(),
+            Assist::Resolved { assist } => match assist.action_data {
+                ActionData::Group(actions) => actions,
+                _ => panic!("expected a group of actions"),
+            },
+        };
+
+        assert_eq!(actions.len(), after.len());
+        for (action, after) in actions.iter().zip(after) {
+            assert_eq_text!(after, &action.edit.apply(&before));
+        }
+    }
+
     pub(crate) fn check_assist_range(
         assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
         before: &str,
@@ -275,8 +610,21 @@ mod helpers {
         let (db, file_id) = TestDB::with_single_file(&before);
         let frange =
             FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
-        let assist = AssistCtx::with_ctx(&db, frange, true, assist);
-        assert!(assist.is_none());
+        assert_not_applicable(&db, frange, assist);
+    }
+
+    /// Like `check_assist_not_applicable`, for a fixture with several files
+    /// or crates.
+    pub(crate) fn check_assist_not_applicable_in_fixture(
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+        before: &str,
+    ) {
+        let (db, position) = TestDB::with_position(before);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        assert_not_applicable(&db, frange, assist);
     }
 
     pub(crate) fn check_assist_range_not_applicable(
@@ -286,18 +634,33 @@ mod helpers {
         let (range, before) = extract_range(before);
         let (db, file_id) = TestDB::with_single_file(&before);
         let frange = FileRange { file_id, range };
-        let assist = AssistCtx::with_ctx(&db, frange, true, assist);
-        assert!(assist.is_none());
+        assert_not_applicable(&db, frange, assist);
+    }
+
+    /// Checks applicability the way it is checked before the assist is shown,
+    /// without computing the edit.
+    fn assert_not_applicable(
+        db: &TestDB,
+        frange: FileRange,
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+    ) {
+        let label = match AssistCtx::with_ctx(db, frange, false, assist) {
+            None => return,
+            Some(Assist::Unresolved { label }) => label,
+            Some(Assist::Resolved { assist }) => assist.label,
+        };
+        panic!("assist `{}` is applicable: {}", label.id.0, label.label);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use ra_db::{fixture::WithFixture, FileRange};
+    use hir::HirDisplay;
+    use ra_db::{fixture::WithFixture, Edition, FileRange};
     use ra_syntax::TextRange;
     use test_utils::{extract_offset, extract_range};
 
-    use crate::test_db::TestDB;
+    use crate::{test_db::TestDB, AssistCtx};
 
     #[test]
     fn assist_order_field_struct() {
@@ -335,4 +698,70 @@ mod tests {
         assert_eq!(assists.next().expect("expected assist").label.label, "Extract into variable");
         assert_eq!(assists.next().expect("expected assist").label.label, "Replace with match");
     }
+
+    #[test]
+    fn assist_order_equal_targets_by_id() {
+        let before = "struct Foo {<|>}";
+        let (before_cursor_pos, before) = extract_offset(before);
+        let (db, file_id) = TestDB::with_single_file(&before);
+        let frange =
+            FileRange { file_id, range: TextRange::offset_len(before_cursor_pos, 0.into()) };
+        let ids = super::assists(&db, frange).iter().map(|it| it.label.id.0).collect::<Vec<_>>();
+
+        let position = |id: &str| ids.iter().position(|&it| it == id).expect("expected assist");
+        assert!(position("add_derive") < position("add_impl"));
+        assert!(position("add_impl") < position("add_new"));
+
+        let ids_again =
+            super::assists(&db, frange).iter().map(|it| it.label.id.0).collect::<Vec<_>>();
+        assert_eq!(ids, ids_again);
+    }
+
+    #[test]
+    fn edition_of_the_containing_crate() {
+        let (db, position) = TestDB::with_position(
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {}
+
+//- /dep.rs crate:dep edition:2015
+fn dep() {<|>}
+"#,
+        );
+        let range = TextRange::offset_len(position.offset, 0.into());
+        let frange = FileRange { file_id: position.file_id, range };
+        let edition = AssistCtx::with_ctx(&db, frange, false, |ctx| ctx.edition());
+        assert_eq!(edition, Edition::Edition2015);
+    }
+
+    #[test]
+    fn type_of_expr_at_cursor() {
+        let check = |before: &str, expected: Option<&str>| {
+            let (db, position) = TestDB::with_position(before);
+            let range = TextRange::offset_len(position.offset, 0.into());
+            let frange = FileRange { file_id: position.file_id, range };
+            let ty = AssistCtx::with_ctx(&db, frange, false, |ctx| {
+                ctx.type_of_expr_at_cursor().map(|it| it.display(&db).to_string())
+            });
+            assert_eq!(ty.as_ref().map(String::as_str), expected);
+        };
+        check("struct S; fn f(s: S) { let x = (s<|>, 1u8); }", Some("S"));
+        check("fn f() { let x = (1u8, <|>2u32); }", Some("u32"));
+        check("fn f() { let x = <|>g(); }", None);
+        check("fn f<|>() {}", None);
+    }
+
+    #[test]
+    fn syntax_errors_in_range() {
+        let before = "fn f() { <|>g(1, 2);<|> }\n)\nfn h() {}";
+        let (range, before) = extract_range(before);
+        let (db, file_id) = TestDB::with_single_file(&before);
+        let frange = FileRange { file_id, range };
+        let (in_call, in_file) = AssistCtx::with_ctx(&db, frange, false, |ctx| {
+            let file_range = TextRange::offset_len(0.into(), TextUnit::of_str(&before));
+            (ctx.has_syntax_errors_in(range), ctx.has_syntax_errors_in(file_range))
+        });
+        assert!(!in_call);
+        assert!(in_file);
+    }
 }
@@ -0,0 +1,736 @@
+//! Assorted functions shared by several assists.
+
+mod accumulator_loop;
+mod field_usages;
+
+use std::iter::{self, successors};
+
+use either::Either;
+use hir::{
+    db::HirDatabase, Adt, AssocItem, Function, HasSource, HirDisplay, HirFileId, ImplBlock, InFile,
+    Local, ModuleDef, PathResolution, StructField, Type,
+};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{
+        self, make, ArgListOwner, AstNode, AttrsOwner, ModuleItemOwner, NameOwner, TypeParamsOwner,
+    },
+    SmolStr,
+    SyntaxKind::{
+        BREAK_EXPR, COMMENT, CONTINUE_EXPR, FN_DEF, INT_NUMBER, LAMBDA_EXPR, MACRO_CALL, MATCH_ARM,
+        RETURN_EXPR, TRY_EXPR, WHITESPACE,
+    },
+    SyntaxNode, TextRange, TextUnit, T,
+};
+use test_utils::tested_by;
+
+use crate::{
+    assist_ctx::{ActionBuilder, ExprPrecedence},
+    AssistCtx,
+};
+
+pub(crate) use self::{
+    accumulator_loop::AccumulatorLoop,
+    field_usages::{field_at_offset, FieldRef, FieldUsages},
+};
+
+/// Descendants of the function body which don't belong to closures or nested
+/// items.
+pub(crate) fn own_descendants(body: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> + '_ {
+    body.descendants().filter(move |it| {
+        !it.ancestors()
+            .skip(1)
+            .take_while(|it| it != body)
+            .any(|it| it.kind() == LAMBDA_EXPR || it.kind() == FN_DEF)
+    })
+}
+
+/// Returns the expression of a block consisting of just one expression, with
+/// or without a trailing semicolon.
+pub(crate) fn single_expr(block_expr: &ast::BlockExpr) -> Option<ast::Expr> {
+    let block = block_expr.block()?;
+    let mut statements = block.statements();
+    let expr = match (statements.next(), block.expr()) {
+        (Some(ast::Stmt::ExprStmt(it)), None) => it.expr()?,
+        (None, Some(it)) => it,
+        _ => return None,
+    };
+    if statements.next().is_some() {
+        return None;
+    }
+    Some(expr)
+}
+
+/// Whether evaluating the node can do anything besides computing its value,
+/// like assigning or leaving the function.
+pub(crate) fn has_side_effects(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| match it.kind() {
+        RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR | MACRO_CALL => true,
+        _ => ast::BinExpr::cast(it)
+            .and_then(|it| it.op_kind())
+            .map_or(false, |op| op.is_assignment()),
+    })
+}
+
+/// Whether the expression, or the end of the block, never completes, so that
+/// the value it would produce doesn't matter.
+pub(crate) fn diverges(ctx: &AssistCtx<impl HirDatabase>, expr: &ast::Expr) -> bool {
+    let analyzer = ctx.source_analyzer(expr.syntax(), None);
+    if let Some(ty) = analyzer.type_of(ctx.db, expr) {
+        if ty.display(ctx.db).to_string() == "!" {
+            return true;
+        }
+    }
+    let last = match expr {
+        ast::Expr::BlockExpr(it) => it.block().and_then(|it| match it.expr() {
+            Some(it) => Some(it),
+            None => match it.statements().last()? {
+                ast::Stmt::ExprStmt(it) => it.expr(),
+                ast::Stmt::LetStmt(_) => None,
+            },
+        }),
+        it => Some(it.clone()),
+    };
+    match last {
+        Some(ast::Expr::ReturnExpr(_))
+        | Some(ast::Expr::BreakExpr(_))
+        | Some(ast::Expr::ContinueExpr(_)) => true,
+        _ => false,
+    }
+}
+
+/// The name of the last segment of a path type.
+pub(crate) fn path_name(ty: &ast::TypeRef) -> Option<SmolStr> {
+    match ty {
+        ast::TypeRef::PathType(it) => Some(it.path()?.segment()?.name_ref()?.text().clone()),
+        _ => None,
+    }
+}
+
+/// For `Name<T>`, returns `T`.
+pub(crate) fn single_type_arg(type_ref: &ast::TypeRef, name: &str) -> Option<ast::TypeRef> {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text().as_str() != name {
+        return None;
+    }
+    let mut args = segment.type_arg_list()?.type_args();
+    let arg = args.next()?.type_ref()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+/// For `Result<T, E>`, returns `E`.
+pub(crate) fn result_error_type(type_ref: &ast::TypeRef) -> Option<ast::TypeRef> {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text().as_str() != "Result" {
+        return None;
+    }
+    let mut args = segment.type_arg_list()?.type_args();
+    args.next()?;
+    let error = args.next()?.type_ref()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(error)
+}
+
+/// Collects the expressions whose values are returned from the function: the
+/// tail expression, looking through blocks, `if`s and `match`es, and the
+/// operands of `return`s outside of closures.
+pub(crate) fn return_positions(body: &ast::BlockExpr) -> Vec<ast::Expr> {
+    let mut res = Vec::new();
+    if let Some(tail) = body.block().and_then(|it| it.expr()) {
+        collect_tails(tail, &mut res);
+    }
+    for ret in own_descendants(body.syntax()).filter_map(ast::ReturnExpr::cast) {
+        if let Some(expr) = ret.expr() {
+            collect_tails(expr, &mut res);
+        }
+    }
+    res
+}
+
+fn collect_tails(expr: ast::Expr, acc: &mut Vec<ast::Expr>) {
+    match expr {
+        ast::Expr::BlockExpr(it) => {
+            if let Some(tail) = it.block().and_then(|it| it.expr()) {
+                collect_tails(tail, acc);
+            }
+        }
+        ast::Expr::IfExpr(it) => {
+            if let Some(then_branch) = it.then_branch() {
+                collect_tails(then_branch.into(), acc);
+            }
+            match it.else_branch() {
+                Some(ast::ElseBranch::Block(it)) => collect_tails(it.into(), acc),
+                Some(ast::ElseBranch::IfExpr(it)) => collect_tails(it.into(), acc),
+                None => (),
+            }
+        }
+        ast::Expr::MatchExpr(it) => {
+            let arms = it.match_arm_list().into_iter().flat_map(|it| it.arms());
+            for arm in arms {
+                if let Some(expr) = arm.expr() {
+                    collect_tails(expr, acc);
+                }
+            }
+        }
+        // Diverges, the operand is collected on its own.
+        ast::Expr::ReturnExpr(_) => (),
+        it => acc.push(it),
+    }
+}
+
+/// For `Some(x)` returns the callee and `"Some"`, for `None` the path and
+/// `"None"`.
+pub(crate) fn constructor_call(expr: &ast::Expr) -> Option<(ast::Expr, &'static str)> {
+    let (callee, name) = match expr {
+        ast::Expr::CallExpr(call) => {
+            let callee = call.expr()?;
+            let name = callee.syntax().text().to_string();
+            (callee, name)
+        }
+        ast::Expr::PathExpr(path) => (expr.clone(), path.syntax().text().to_string()),
+        _ => return None,
+    };
+    let is_call = ast::CallExpr::can_cast(expr.syntax().kind());
+    let name = match (name.as_str(), is_call) {
+        ("Some", true) => "Some",
+        ("None", false) => "None",
+        ("Ok", true) => "Ok",
+        ("Err", true) => "Err",
+        _ => return None,
+    };
+    Some((callee, name))
+}
+
+/// Appends a conversion method call to an arbitrary return value.
+pub(crate) fn wrap_in_conversion(edit: &mut ActionBuilder, expr: &ast::Expr, conversion: &str) {
+    let range = expr.syntax().text_range();
+    if ExprPrecedence::of(expr) < ExprPrecedence::Postfix {
+        edit.insert(range.start(), "(");
+        edit.insert(range.end(), format!("){}", conversion));
+    } else {
+        edit.insert(range.end(), conversion);
+    }
+}
+
+/// Returns the syntax node which will follow the freshly introduced var
+/// and a boolean indicating whether we have to wrap it within a { } block
+/// to produce correct code.
+/// It can be a statement, the last in a block expression or a wanna be block
+/// expression like a lambda or match arm.
+pub(crate) fn anchor_stmt(expr: ast::Expr) -> Option<(SyntaxNode, bool)> {
+    expr.syntax().ancestors().find_map(|node| {
+        if let Some(expr) = node.parent().and_then(ast::Block::cast).and_then(|it| it.expr()) {
+            if expr.syntax() == &node {
+                tested_by!(test_introduce_var_last_expr);
+                return Some((node, false));
+            }
+        }
+
+        if let Some(parent) = node.parent() {
+            if parent.kind() == MATCH_ARM || parent.kind() == LAMBDA_EXPR {
+                return Some((node, true));
+            }
+        }
+
+        if ast::Stmt::cast(node.clone()).is_some() {
+            return Some((node, false));
+        }
+
+        None
+    })
+}
+
+/// The name of the field matched by a field pattern or a shorthand binding.
+pub(crate) fn field_name(field: &SyntaxNode) -> Option<String> {
+    if let Some(bind_pat) = ast::BindPat::cast(field.clone()) {
+        return Some(bind_pat.name()?.text().to_string());
+    }
+    let field_pat = ast::RecordFieldPat::cast(field.clone())?;
+    match field_pat.name() {
+        Some(it) => Some(it.text().to_string()),
+        // Tuple fields like `0: x`.
+        None => field_pat
+            .syntax()
+            .first_token()
+            .filter(|it| it.kind() == INT_NUMBER)
+            .map(|it| it.text().to_string()),
+    }
+}
+
+/// Turns a `snake_case` name into `PascalCase`.
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Whether the module is `#[cfg(test)]` or contains `#[test]` functions.
+pub(crate) fn is_test_module(module: &ast::Module) -> bool {
+    let is_cfg_test = module.attrs().any(|it| match it.as_simple_call() {
+        Some((name, tt)) => name == "cfg" && tt.syntax().text() == "(test)",
+        None => false,
+    });
+    let has_tests = || {
+        module.item_list().map_or(false, |it| {
+            it.items().any(|it| match it {
+                ast::ModuleItem::FnDef(it) => it.has_atom_attr("test"),
+                _ => false,
+            })
+        })
+    };
+    is_cfg_test || has_tests()
+}
+
+/// Whether `Default` is in one of the `#[derive]`s of the item.
+pub(crate) fn derives_default(item: &impl AttrsOwner) -> bool {
+    let mut derives =
+        item.attrs().filter_map(|it| it.as_simple_call()).filter(|(name, _)| name == "derive");
+    derives.any(|(_, tt)| {
+        tt.syntax().children_with_tokens().any(|it| match it.into_token() {
+            Some(token) => token.text() == "Default",
+            None => false,
+        })
+    })
+}
+
+/// Returns the insertion adding `trait_name` to the `#[derive]` of the item,
+/// creating the attribute if there is none.
+pub(crate) fn derive_edit(
+    nominal: &ast::NominalDef,
+    trait_name: &str,
+) -> Option<(TextUnit, String)> {
+    let derive_attr = nominal
+        .attrs()
+        .filter_map(|x| x.as_simple_call())
+        .filter(|(name, _arg)| name == "derive")
+        .map(|(_name, arg)| arg)
+        .next();
+    let tt = match derive_attr {
+        Some(it) => it,
+        None => {
+            let indent = leading_indent(nominal.syntax()).unwrap_or_default();
+            let text = format!("#[derive({})]\n{}", trait_name, indent);
+            return Some((attr_insertion_offset(nominal.syntax())?, text));
+        }
+    };
+    let r_paren = tt.syntax().last_token()?;
+    let last =
+        successors(r_paren.prev_token(), |it| it.prev_token()).find(|it| !it.kind().is_trivia())?;
+    let text = if last.kind() == T!['('] || last.kind() == T![,] {
+        trait_name.to_string()
+    } else {
+        format!(", {}", trait_name)
+    };
+    Some((last.text_range().end(), text))
+}
+
+/// The offset in front of the item, after its doc comments, where a new
+/// attribute goes.
+pub(crate) fn attr_insertion_offset(node: &SyntaxNode) -> Option<TextUnit> {
+    let non_ws_child =
+        node.children_with_tokens().find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?;
+    Some(non_ws_child.text_range().start())
+}
+
+/// Returns the struct an impl is for, if a derive would be equivalent to it
+/// with respect to generics.
+pub(crate) fn derived_struct(impl_block: &ast::ImplBlock) -> Option<ast::StructDef> {
+    // The derive would add bounds on the type parameters, which may differ
+    // from the ones of the impl.
+    let target_type = match impl_block.target_type()? {
+        ast::TypeRef::PathType(it) => it,
+        _ => return None,
+    };
+    if impl_block.type_param_list().is_some()
+        || target_type.path()?.segment()?.type_arg_list().is_some()
+    {
+        return None;
+    }
+    let name = path_name(&ast::TypeRef::PathType(target_type))?;
+    let mut structs = impl_block
+        .syntax()
+        .ancestors()
+        .last()?
+        .descendants()
+        .filter_map(ast::StructDef::cast)
+        .filter(|it| it.name().map_or(false, |it| *it.text() == name));
+    match (structs.next()?, structs.next()) {
+        (it, None) => Some(it),
+        _ => None,
+    }
+}
+
+/// The range of an impl with the whitespace before it.
+pub(crate) fn removal_range(impl_block: &ast::ImplBlock) -> TextRange {
+    let start = impl_block
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| ast::Whitespace::cast(it.into_token()?))
+        .map_or(impl_block.syntax().text_range().start(), |it| it.syntax().text_range().start());
+    TextRange::from_to(start, impl_block.syntax().text_range().end())
+}
+
+/// Whether there is an impl of the trait named `trait_name` for the ADT, which
+/// has to be in the crate defining it.
+pub(crate) fn implements_trait(db: &impl HirDatabase, ty: &Type, trait_name: &str) -> bool {
+    let adt = match ty.as_adt() {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = match adt.krate(db) {
+        Some(it) => it,
+        None => return false,
+    };
+    ImplBlock::all_in_crate(db, krate).into_iter().any(|impl_block| {
+        impl_block.target_ty(db).as_adt() == Some(adt)
+            && impl_block.source(db).value.target_trait().and_then(|it| path_name(&it)).as_ref()
+                == Some(&SmolStr::new(trait_name))
+    })
+}
+
+/// Whether values of the type are copied rather than moved.
+pub(crate) fn is_copy_type(db: &impl HirDatabase, ty: &Type) -> bool {
+    if ty.as_adt().is_some() {
+        return implements_trait(db, ty, "Copy");
+    }
+    let tuple_fields = ty.tuple_fields(db);
+    if !tuple_fields.is_empty() {
+        return tuple_fields.iter().all(|it| is_copy_type(db, it));
+    }
+    let text = ty.display(db).to_string();
+    let array_item = if text.starts_with('[') && text.ends_with(";_]") {
+        Some(&text[1..text.len() - 3])
+    } else {
+        None
+    };
+    is_scalar(&text)
+        || array_item.map_or(false, is_scalar)
+        || (text.starts_with('&') && !text.starts_with("&mut "))
+        || text == "()"
+        || text == "!"
+        || text.starts_with("*const ")
+        || text.starts_with("*mut ")
+        || text.starts_with("fn(")
+}
+
+/// Whether the type, which must not be an ADT, is a number, `bool` or `char`.
+pub(crate) fn is_scalar(ty: &str) -> bool {
+    match ty {
+        "bool" | "char" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" => true,
+        _ => false,
+    }
+}
+
+/// Returns the definition of the struct or enum along with its fields, or the
+/// fields of all of its variants.
+pub(crate) fn adt_fields(
+    ctx: &AssistCtx<impl HirDatabase>,
+    nominal: &ast::NominalDef,
+) -> Option<(Adt, Vec<StructField>)> {
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id.into();
+    let res = match nominal {
+        ast::NominalDef::StructDef(it) => {
+            let def = ctx.source_binder().to_def(InFile::new(file_id, it.clone()))?;
+            (Adt::from(def), def.fields(db))
+        }
+        ast::NominalDef::EnumDef(it) => {
+            let def = ctx.source_binder().to_def(InFile::new(file_id, it.clone()))?;
+            let fields = def.variants(db).into_iter().flat_map(|it| it.fields(db)).collect();
+            (Adt::from(def), fields)
+        }
+        ast::NominalDef::UnionDef(_) => return None,
+    };
+    Some(res)
+}
+
+pub(crate) fn resolve_enum_def(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    expr: &ast::Expr,
+) -> Option<hir::Enum> {
+    let expr_ty = analyzer.type_of(db, &expr)?;
+
+    let result = expr_ty.autoderef(db).find_map(|ty| match ty.as_adt() {
+        Some(Adt::Enum(e)) => Some(e),
+        _ => None,
+    });
+    result
+}
+
+pub(crate) fn build_pat(
+    db: &impl HirDatabase,
+    module: hir::Module,
+    var: hir::EnumVariant,
+) -> Option<ast::Pat> {
+    let path = crate::ast_transform::path_to_ast(module.find_use_path(db, var.into())?);
+
+    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
+    let pat: ast::Pat = match var.source(db).value.kind() {
+        ast::StructKind::Tuple(field_list) => {
+            let pats =
+                iter::repeat(make::placeholder_pat().into()).take(field_list.fields().count());
+            make::tuple_struct_pat(path, pats).into()
+        }
+        ast::StructKind::Record(field_list) => {
+            let pats = field_list.fields().map(|f| make::bind_pat(f.name().unwrap()).into());
+            make::record_pat(path, pats).into()
+        }
+        ast::StructKind::Unit => make::path_pat(path),
+    };
+
+    Some(pat)
+}
+
+/// Returns the locals used in `nodes` which are defined outside of `range`, or
+/// `None` if `self` is used.
+pub(crate) fn outer_locals(
+    ctx: &AssistCtx<impl HirDatabase>,
+    nodes: &[SyntaxNode],
+    range: TextRange,
+) -> Option<Vec<Local>> {
+    let db = ctx.db;
+    let mut locals: Vec<Local> = Vec::new();
+    for node in nodes {
+        for path_expr in node.descendants().filter_map(ast::PathExpr::cast) {
+            let path = match path_expr.path() {
+                Some(it) => it,
+                None => continue,
+            };
+            let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+            let local = match analyzer.resolve_path(db, &path) {
+                Some(PathResolution::Local(it)) => it,
+                _ => continue,
+            };
+            let is_defined_inside = match local.source(db).value {
+                Either::Left(bind_pat) => bind_pat.syntax().text_range().is_subrange(&range),
+                Either::Right(_) => return None,
+            };
+            if !is_defined_inside && !locals.contains(&local) {
+                locals.push(local);
+            }
+        }
+    }
+    Some(locals)
+}
+
+/// Returns the parameters of a new function taking `locals`, and the arguments
+/// to pass them.
+pub(crate) fn params_and_args(
+    db: &impl HirDatabase,
+    locals: Vec<Local>,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let mut args = Vec::new();
+    let mut params = Vec::new();
+    for local in locals {
+        let name = local.name(db)?;
+        let ty = local.ty(db);
+        let ty_text = ty.display(db).to_string();
+        // Closures and unresolved types can't be named in a signature.
+        if ty.contains_unknown() || ty_text.contains('|') {
+            return None;
+        }
+        let mut_prefix = if local.is_mut(db) { "mut " } else { "" };
+        params.push(format!("{}{}: {}", mut_prefix, name, ty_text));
+        args.push(name.to_string());
+    }
+    Some((params, args))
+}
+
+/// The locals which are assigned, mutably borrowed, or receive a method call
+/// while mutable, in the node.
+pub(crate) fn mutated_locals(ctx: &AssistCtx<impl HirDatabase>, node: &SyntaxNode) -> Vec<Local> {
+    let db = ctx.db;
+    let mut res = Vec::new();
+    for path_expr in node.descendants().filter_map(ast::PathExpr::cast) {
+        let analyzer = ctx.source_analyzer(path_expr.syntax(), None);
+        let local = match path_expr.path().and_then(|it| analyzer.resolve_path(db, &it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        // The place is the local, or one of its fields or elements.
+        let mut place = path_expr.syntax().clone();
+        while let Some(parent) = place.parent() {
+            match ast::Expr::cast(parent.clone()) {
+                Some(ast::Expr::FieldExpr(_)) => place = parent,
+                Some(ast::Expr::IndexExpr(it))
+                    if it.base().map_or(false, |it| it.syntax() == &place) =>
+                {
+                    place = parent
+                }
+                _ => break,
+            }
+        }
+        let is_mutated = match place.parent().and_then(ast::Expr::cast) {
+            Some(ast::Expr::BinExpr(it)) => {
+                it.op_kind().map_or(false, |it| it.is_assignment())
+                    && it.lhs().map_or(false, |it| it.syntax() == &place)
+            }
+            Some(ast::Expr::RefExpr(it)) => it.is_mut(),
+            Some(ast::Expr::MethodCallExpr(it)) => {
+                it.expr().map_or(false, |it| it.syntax() == &place) && local.is_mut(db)
+            }
+            _ => false,
+        };
+        if is_mutated && !res.contains(&local) {
+            res.push(local);
+        }
+    }
+    res
+}
+
+/// Whether any of the usages after `range` may modify the variable, or one of
+/// its fields or elements.
+pub(crate) fn is_mutated_after(block: &SyntaxNode, refs: &[TextRange], range: TextRange) -> bool {
+    block
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| it.syntax().text_range().start() >= range.end())
+        .filter(|it| refs.contains(&it.syntax().text_range()))
+        .any(|it| {
+            let mut place = it.syntax().clone();
+            while let Some(parent) = place.parent() {
+                let base = match ast::Expr::cast(parent.clone()) {
+                    Some(ast::Expr::FieldExpr(it)) => it.expr(),
+                    Some(ast::Expr::IndexExpr(it)) => it.base(),
+                    _ => None,
+                };
+                if base.as_ref().map(|it| it.syntax()) != Some(&place) {
+                    break;
+                }
+                place = parent;
+            }
+            let parent = match place.parent() {
+                Some(it) => it,
+                None => return false,
+            };
+            if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+                return bin_expr.op_kind().map_or(false, |op| op.is_assignment())
+                    && bin_expr.lhs().as_ref().map(|it| it.syntax()) == Some(&place);
+            }
+            if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+                return ref_expr.is_mut();
+            }
+            // Methods might take `&mut self`.
+            ast::MethodCallExpr::can_cast(parent.kind())
+        })
+}
+
+/// Returns the function, and its module, if all of the code which can call it
+/// is in this file.
+pub(crate) fn local_function(
+    ctx: &AssistCtx<impl HirDatabase>,
+    fn_def: &ast::FnDef,
+) -> Option<(Function, hir::Module)> {
+    let file_id = ctx.frange.file_id;
+    let function = ctx.source_binder().to_def(InFile::new(file_id.into(), fn_def.clone()))?;
+    let module = function.module(ctx.db);
+    if !is_local_module(ctx, module) {
+        return None;
+    }
+    Some((function, module))
+}
+
+/// Whether the module and all of its descendants are defined in this file, so
+/// that all of the code which can see its private items is here.
+pub(crate) fn is_local_module(ctx: &AssistCtx<impl HirDatabase>, module: hir::Module) -> bool {
+    let db = ctx.db;
+    let file_id = HirFileId::from(ctx.frange.file_id);
+    let mut modules = vec![module];
+    while let Some(it) = modules.pop() {
+        if it.definition_source(db).file_id != file_id {
+            return false;
+        }
+        modules.extend(it.children(db));
+    }
+    true
+}
+
+/// A call of the function, where `offset` is the number of arguments in front
+/// of the declared parameters.
+pub(crate) struct Call {
+    pub(crate) args: Vec<ast::Expr>,
+    pub(crate) offset: usize,
+    pub(crate) module: hir::Module,
+}
+
+/// Finds the calls of the function under `root`, or returns `None` if it is
+/// also used in other ways.
+pub(crate) fn find_calls(
+    ctx: &AssistCtx<impl HirDatabase>,
+    root: &SyntaxNode,
+    function: Function,
+) -> Option<Vec<Call>> {
+    let db = ctx.db;
+    let has_self = function.has_self_param(db);
+    let mut res = Vec::new();
+    for node in root.descendants() {
+        if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            let analyzer = ctx.source_analyzer(&node, None);
+            if analyzer.resolve_method_call(&call) != Some(function) {
+                continue;
+            }
+            let args = call.arg_list()?.args().collect();
+            res.push(Call { args, offset: 0, module: analyzer.module()? });
+        } else if let Some(call) = ast::CallExpr::cast(node.clone()) {
+            let path = match call.expr() {
+                Some(ast::Expr::PathExpr(it)) => it.path()?,
+                _ => continue,
+            };
+            let analyzer = ctx.source_analyzer(&node, None);
+            let callee = match analyzer.resolve_path(db, &path) {
+                Some(PathResolution::Def(ModuleDef::Function(it))) => it,
+                Some(PathResolution::AssocItem(AssocItem::Function(it))) => it,
+                _ => continue,
+            };
+            if callee != function {
+                continue;
+            }
+            let args = call.arg_list()?.args().collect();
+            res.push(Call { args, offset: has_self as usize, module: analyzer.module()? });
+        } else if let Some(path_expr) = ast::PathExpr::cast(node.clone()) {
+            // Uses other than calls would need a closure to adapt the arguments.
+            let is_callee = path_expr
+                .syntax()
+                .parent()
+                .and_then(ast::CallExpr::cast)
+                .and_then(|it| it.expr())
+                .map_or(false, |it| it.syntax() == path_expr.syntax());
+            if is_callee {
+                continue;
+            }
+            let analyzer = ctx.source_analyzer(&node, None);
+            match analyzer.resolve_path(db, &path_expr.path()?) {
+                Some(PathResolution::Def(ModuleDef::Function(it)))
+                | Some(PathResolution::AssocItem(AssocItem::Function(it)))
+                    if it == function =>
+                {
+                    return None
+                }
+                _ => (),
+            }
+        }
+    }
+    Some(res)
+}
@@ -0,0 +1,112 @@
+//! Support for assists rewriting a loop which accumulates a value.
+
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::BLOCK,
+    TextRange, T,
+};
+
+use crate::{
+    assist_ctx::ExprPrecedence,
+    utils::{is_mutated_after, single_expr},
+    AssistCtx,
+};
+
+/// A `let mut acc = init;` statement immediately followed by a `for` loop.
+pub(crate) struct AccumulatorLoop {
+    pub(crate) let_stmt: ast::LetStmt,
+    pub(crate) acc: ast::BindPat,
+    pub(crate) init: ast::Expr,
+    pub(crate) for_expr: ast::ForExpr,
+    /// Ranges of all the usages of the accumulator.
+    pub(crate) refs: Vec<TextRange>,
+    /// Range covering both the `let` and the loop.
+    pub(crate) range: TextRange,
+}
+
+impl AccumulatorLoop {
+    /// Finds the loop under the `for` keyword at the cursor.
+    pub(crate) fn find(ctx: &AssistCtx<impl HirDatabase>) -> Option<AccumulatorLoop> {
+        let for_keyword = ctx.find_token_at_offset(T![for])?;
+        let for_expr = ast::ForExpr::cast(for_keyword.parent())?;
+        let loop_stmt = match for_expr.syntax().parent()? {
+            it if ast::ExprStmt::can_cast(it.kind()) => it,
+            it if it.kind() == BLOCK => for_expr.syntax().clone(),
+            _ => return None,
+        };
+        let let_stmt = loop_stmt.prev_sibling().and_then(ast::LetStmt::cast)?;
+        let acc = match let_stmt.pat()? {
+            ast::Pat::BindPat(it) if it.is_mutable() && !it.is_ref() => it,
+            _ => return None,
+        };
+        let init = let_stmt.initializer()?;
+        let refs = ctx
+            .source_analyzer(acc.syntax(), None)
+            .find_all_refs(&acc)
+            .into_iter()
+            .map(|it| it.range)
+            .collect::<Vec<_>>();
+        // The iterable is evaluated only once, before the first iteration.
+        let iterable_range = for_expr.iterable()?.syntax().text_range();
+        if refs.iter().any(|it| it.is_subrange(&iterable_range)) {
+            return None;
+        }
+        let range = TextRange::from_to(
+            let_stmt.syntax().text_range().start(),
+            loop_stmt.text_range().end(),
+        );
+        Some(AccumulatorLoop { let_stmt, acc, init, for_expr, refs, range })
+    }
+
+    /// Returns the only expression evaluated by the loop body.
+    pub(crate) fn body_expr(&self) -> Option<ast::Expr> {
+        single_expr(&self.for_expr.loop_body()?)
+    }
+
+    pub(crate) fn is_acc(&self, expr: &ast::Expr) -> bool {
+        match expr {
+            ast::Expr::PathExpr(it) => self.refs.contains(&it.syntax().text_range()),
+            _ => false,
+        }
+    }
+
+    /// Turns the iterable of the loop into an iterator expression.
+    pub(crate) fn iterator(&self) -> Option<String> {
+        let iterable = self.for_expr.iterable()?;
+        let res = match &iterable {
+            ast::Expr::RefExpr(it) => {
+                let method = if it.is_mut() { "iter_mut" } else { "iter" };
+                format!("{}.{}()", ExprPrecedence::Postfix.parenthesize(&it.expr()?), method)
+            }
+            ast::Expr::RangeExpr(_) => format!("({})", iterable.syntax()),
+            _ => format!("{}.into_iter()", ExprPrecedence::Postfix.parenthesize(&iterable)),
+        };
+        Some(res)
+    }
+
+    /// Builds the `let` replacing the accumulator, keeping it `mut` only if it
+    /// is still modified after the loop.
+    pub(crate) fn let_with_value(&self, value: String) -> String {
+        self.let_with_typed_value(None, value)
+    }
+
+    /// Like `let_with_value`, ascribing `default_ty` if the `let` has no type.
+    pub(crate) fn let_with_typed_value(&self, default_ty: Option<&str>, value: String) -> String {
+        let mut_kw = if self.is_mutated_later() { "mut " } else { "" };
+        let name = self.acc.name().map(|it| it.text().to_string()).unwrap_or_default();
+        let ty = match (self.let_stmt.ascribed_type(), default_ty) {
+            (Some(it), _) => format!(": {}", it.syntax()),
+            (None, Some(it)) => format!(": {}", it),
+            (None, None) => String::new(),
+        };
+        format!("let {}{}{} = {};", mut_kw, name, ty, value)
+    }
+
+    fn is_mutated_later(&self) -> bool {
+        match self.for_expr.syntax().ancestors().find_map(ast::FnDef::cast) {
+            Some(it) => is_mutated_after(it.syntax(), &self.refs, self.range),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Finds the places where a field is initialized, read or matched.
+
+use hir::{db::HirDatabase, Adt, ModuleDef, PathResolution, StructField, VariantDef};
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxNode,
+};
+
+use crate::AssistCtx;
+
+/// A named or positional field of a struct or variant.
+pub(crate) enum FieldRef {
+    Named(String),
+    Positional(usize),
+}
+
+/// Returns the field definition under the cursor with its type.
+pub(crate) fn field_at_offset(
+    ctx: &AssistCtx<impl HirDatabase>,
+) -> Option<(FieldRef, ast::TypeRef, SyntaxNode)> {
+    match ctx.find_node_at_offset::<ast::RecordFieldDef>() {
+        Some(it) => Some((
+            FieldRef::Named(it.name()?.text().to_string()),
+            it.ascribed_type()?,
+            it.syntax().clone(),
+        )),
+        None => {
+            let field = ctx.find_node_at_offset::<ast::TupleFieldDef>()?;
+            let list = ast::TupleFieldDefList::cast(field.syntax().parent()?)?;
+            let idx = list.fields().position(|it| it == field)?;
+            Some((FieldRef::Positional(idx), field.type_ref()?, field.syntax().clone()))
+        }
+    }
+}
+
+/// The places in the current file where a field is initialized or read by
+/// value.
+#[derive(Default)]
+pub(crate) struct FieldUsages {
+    pub(crate) initializers: Vec<ast::Expr>,
+    pub(crate) shorthands: Vec<ast::NameRef>,
+    /// Accesses which are not auto-dereferenced receivers.
+    pub(crate) reads: Vec<ast::FieldExpr>,
+    /// Accesses which are receivers of a field access or method call.
+    pub(crate) receivers: Vec<ast::FieldExpr>,
+    /// The subpatterns matching the field.
+    pub(crate) patterns: Vec<ast::Pat>,
+}
+
+impl FieldUsages {
+    /// Returns `None` if some usage, like a pattern binding the field, can't
+    /// be updated.
+    pub(crate) fn find(
+        ctx: &AssistCtx<impl HirDatabase>,
+        field: &FieldRef,
+        field_syntax: &SyntaxNode,
+        variant: VariantDef,
+    ) -> Option<FieldUsages> {
+        let usages = FieldUsages::find_with_patterns(ctx, field, field_syntax, variant)?;
+        // Patterns binding the field are not updated.
+        if !usages.patterns.is_empty() {
+            return None;
+        }
+        Some(usages)
+    }
+
+    /// Like `find`, but leaves the patterns matching the field to the caller.
+    pub(crate) fn find_with_patterns(
+        ctx: &AssistCtx<impl HirDatabase>,
+        field: &FieldRef,
+        field_syntax: &SyntaxNode,
+        variant: VariantDef,
+    ) -> Option<FieldUsages> {
+        let db = ctx.db;
+        let fields = variant.fields(db);
+        let hir_field = match field {
+            FieldRef::Named(name) => *fields.iter().find(|it| it.name(db).to_string() == *name)?,
+            FieldRef::Positional(idx) => *fields.get(*idx)?,
+        };
+
+        let mut usages = FieldUsages::default();
+        let root = field_syntax.ancestors().last()?;
+        for node in root.descendants() {
+            usages.collect(ctx, &node, field, hir_field, variant)?;
+        }
+        if usages.is_nested() {
+            return None;
+        }
+        Some(usages)
+    }
+
+    fn collect(
+        &mut self,
+        ctx: &AssistCtx<impl HirDatabase>,
+        node: &SyntaxNode,
+        field: &FieldRef,
+        hir_field: StructField,
+        variant: VariantDef,
+    ) -> Option<()> {
+        if let Some(record_field) = ast::RecordField::cast(node.clone()) {
+            let analyzer = ctx.source_analyzer(node, None);
+            if analyzer.resolve_record_field(&record_field) == Some(hir_field) {
+                match record_field.expr() {
+                    Some(expr) => self.initializers.push(expr),
+                    None => self.shorthands.push(record_field.name_ref()?),
+                }
+            }
+        } else if let Some(field_expr) = ast::FieldExpr::cast(node.clone()) {
+            let analyzer = ctx.source_analyzer(node, None);
+            if analyzer.resolve_field(&field_expr) == Some(hir_field) {
+                if is_receiver(&field_expr) {
+                    self.receivers.push(field_expr);
+                } else {
+                    self.reads.push(field_expr);
+                }
+            }
+        } else if let FieldRef::Positional(idx) = field {
+            if let Some(call) = ast::CallExpr::cast(node.clone()) {
+                let path = match call.expr() {
+                    Some(ast::Expr::PathExpr(it)) => it.path(),
+                    _ => None,
+                };
+                if path.map_or(false, |it| resolves_to_variant(ctx, &it, variant)) {
+                    self.initializers.push(call.arg_list()?.args().nth(*idx)?);
+                }
+            } else if let Some(pat) = ast::TupleStructPat::cast(node.clone()) {
+                if pat.path().map_or(false, |it| resolves_to_variant(ctx, &it, variant)) {
+                    let field_count = variant.fields(ctx.db).len();
+                    self.patterns.extend(positional_subpattern(&pat, *idx, field_count)?);
+                }
+            }
+        }
+        if let (Some(pat), FieldRef::Named(name)) = (ast::RecordPat::cast(node.clone()), field) {
+            let analyzer = ctx.source_analyzer(node, None);
+            if analyzer.resolve_record_pattern(&pat) == Some(variant) {
+                let list = pat.record_field_pat_list()?;
+                let field_pat = list
+                    .record_field_pats()
+                    .find(|it| it.name().map_or(false, |it| it.text().as_str() == name.as_str()))
+                    .and_then(|it| it.pat());
+                let shorthand = list
+                    .bind_pats()
+                    .find(|it| it.name().map_or(false, |it| it.text().as_str() == name.as_str()))
+                    .map(ast::Pat::from);
+                self.patterns.extend(field_pat.or(shorthand));
+            }
+        }
+        Some(())
+    }
+
+    /// Whether some usage is inside of an initializer, which would need
+    /// overlapping edits.
+    fn is_nested(&self) -> bool {
+        let ranges = self
+            .initializers
+            .iter()
+            .map(|it| it.syntax().text_range())
+            .chain(self.shorthands.iter().map(|it| it.syntax().text_range()))
+            .chain(self.reads.iter().map(|it| it.syntax().text_range()))
+            .collect::<Vec<_>>();
+        self.initializers.iter().any(|init| {
+            let init = init.syntax().text_range();
+            ranges.iter().filter(|it| it.is_subrange(&init)).count() > 1
+        })
+    }
+}
+
+/// The subpattern of a tuple struct pattern matching the field at `idx`, if
+/// it's not left out with `..`.
+fn positional_subpattern(
+    pat: &ast::TupleStructPat,
+    idx: usize,
+    field_count: usize,
+) -> Option<Option<ast::Pat>> {
+    let args = pat.args().collect::<Vec<_>>();
+    let rest = args.iter().position(|it| match it {
+        ast::Pat::DotDotPat(_) => true,
+        _ => false,
+    });
+    let res = match rest {
+        Some(rest) => {
+            let suffix = args.len() - rest - 1;
+            if idx < rest {
+                Some(args[idx].clone())
+            } else if idx + suffix >= field_count {
+                Some(args.get(rest + 1 + idx + suffix - field_count)?.clone())
+            } else {
+                None
+            }
+        }
+        None if args.len() == field_count => Some(args[idx].clone()),
+        None => return None,
+    };
+    Some(res)
+}
+
+fn resolves_to_variant(
+    ctx: &AssistCtx<impl HirDatabase>,
+    path: &ast::Path,
+    variant: VariantDef,
+) -> bool {
+    let analyzer = ctx.source_analyzer(path.syntax(), None);
+    match analyzer.resolve_path(ctx.db, path) {
+        Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(it)))) => {
+            VariantDef::Struct(it) == variant
+        }
+        Some(PathResolution::Def(ModuleDef::EnumVariant(it))) => {
+            VariantDef::EnumVariant(it) == variant
+        }
+        _ => false,
+    }
+}
+
+/// Whether the access is the receiver of a field access or method call, which
+/// is dereferenced automatically.
+fn is_receiver(field_expr: &ast::FieldExpr) -> bool {
+    let parent = match field_expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let receiver = if let Some(it) = ast::FieldExpr::cast(parent.clone()) {
+        it.expr()
+    } else if let Some(it) = ast::MethodCallExpr::cast(parent) {
+        it.expr()
+    } else {
+        None
+    };
+    receiver.map_or(false, |it| it.syntax() == field_expr.syntax())
+}
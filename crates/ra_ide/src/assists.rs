@@ -1,12 +1,14 @@
 //! FIXME: write short doc here
 
-use ra_db::{FilePosition, FileRange};
+use ra_db::{FilePosition, FileRange, SourceDatabaseExt};
+use ra_syntax::{TextRange, TextUnit};
+use ra_text_edit::TextEditBuilder;
 
 use crate::{db::RootDatabase, FileId, SourceChange, SourceFileEdit};
 
 use either::Either;
 pub use ra_assists::AssistId;
-use ra_assists::{AssistAction, AssistLabel};
+use ra_assists::{ActionData, AssistAction, AssistLabel};
 
 #[derive(Debug)]
 pub struct Assist {
@@ -25,15 +27,19 @@ pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<Assist> {
                 id: assist_label.id,
                 label: assist_label.label.clone(),
                 change_data: match assist.action_data {
-                    Either::Left(action) => {
+                    ActionData::Single(action) => {
                         Either::Left(action_to_edit(action, file_id, assist_label))
                     }
-                    Either::Right(actions) => Either::Right(
+                    ActionData::Group(actions) => Either::Right(
                         actions
                             .into_iter()
                             .map(|action| action_to_edit(action, file_id, assist_label))
                             .collect(),
                     ),
+                    ActionData::Sequence(actions) => {
+                        let action = squash_actions(&db.file_text(file_id), actions);
+                        Either::Left(action_to_edit(action, file_id, assist_label))
+                    }
                 },
             }
         })
@@ -52,3 +58,71 @@ fn action_to_edit(
     )
     .with_cursor_opt(action.cursor_position.map(|offset| FilePosition { offset, file_id }))
 }
+
+/// Combines the steps of a sequence into a single action, because clients
+/// apply all edits of a change to the original text.
+fn squash_actions(before: &str, actions: Vec<AssistAction>) -> AssistAction {
+    let mut after = before.to_string();
+    let mut cursor_position = None;
+    let mut target = None;
+    for action in actions {
+        after = action.edit.apply(&after);
+        cursor_position = match action.cursor_position {
+            Some(it) => Some(it),
+            None => cursor_position.and_then(|it| action.edit.apply_to_offset(it)),
+        };
+        target = target.or(action.target);
+    }
+
+    let common_prefix: usize = before
+        .chars()
+        .zip(after.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(it, _)| it.len_utf8())
+        .sum();
+    let common_suffix: usize = before[common_prefix..]
+        .chars()
+        .rev()
+        .zip(after[common_prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(it, _)| it.len_utf8())
+        .sum();
+    let mut edit = TextEditBuilder::default();
+    edit.replace(
+        TextRange::from_to(
+            TextUnit::from_usize(common_prefix),
+            TextUnit::from_usize(before.len() - common_suffix),
+        ),
+        after[common_prefix..after.len() - common_suffix].to_string(),
+    );
+    AssistAction { label: None, edit: edit.finish(), cursor_position, target }
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_text_edit::TextEdit;
+
+    use super::*;
+
+    fn insert(offset: usize, text: &str, cursor: Option<usize>) -> AssistAction {
+        AssistAction {
+            label: None,
+            edit: TextEdit::insert(TextUnit::from_usize(offset), text.to_string()),
+            cursor_position: cursor.map(TextUnit::from_usize),
+            target: None,
+        }
+    }
+
+    #[test]
+    fn squash_actions_applies_steps_in_order() {
+        let before = "struct S;\n";
+        let actions = vec![
+            insert(10, "\nimpl S {}\n", Some(11)),
+            insert(19, "fn new() {}", None),
+            insert(0, "// generated\n", None),
+        ];
+        let action = squash_actions(before, actions);
+        assert_eq!(action.edit.apply(before), "// generated\nstruct S;\n\nimpl S {fn new() {}}\n");
+        assert_eq!(action.cursor_position, Some(TextUnit::from_usize(24)));
+    }
+}
@@ -180,6 +180,12 @@ pub(super) fn stmt(p: &mut Parser, with_semi: StmtWithSemi) {
         if p.eat(T![=]) {
             expressions::expr(p);
         }
+        // test let_else_stmt
+        // fn foo() { let Some(x) = opt else { return; }; }
+        if p.at(T![else]) {
+            p.bump(T![else]);
+            block(p);
+        }
 
         match with_semi {
             StmtWithSemi::Yes => {
@@ -62,6 +62,12 @@ pub fn expr_return() -> ast::Expr {
 pub fn expr_match(expr: ast::Expr, match_arm_list: ast::MatchArmList) -> ast::Expr {
     expr_from_text(&format!("match {} {}", expr.syntax(), match_arm_list.syntax()))
 }
+pub fn expr_not(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("!{}", expr.syntax()))
+}
+pub fn expr_paren(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("({})", expr.syntax()))
+}
 fn expr_from_text(text: &str) -> ast::Expr {
     ast_from_text(&format!("const C: () = {};", text))
 }
@@ -153,7 +159,7 @@ pub fn where_clause(preds: impl IntoIterator<Item = ast::WherePred>) -> ast::Whe
 
 pub fn if_expression(condition: &ast::Expr, statement: &str) -> ast::IfExpr {
     ast_from_text(&format!(
-        "fn f() {{ if !{} {{\n    {}\n}}\n}}",
+        "fn f() {{ if {} {{\n    {}\n}}\n}}",
         condition.syntax().text(),
         statement
     ))
@@ -0,0 +1 @@
+fn foo() { try!(Ok(())); }
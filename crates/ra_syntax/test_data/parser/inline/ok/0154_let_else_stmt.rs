@@ -0,0 +1 @@
+fn foo() { let Some(x) = opt else { return; }; }
@@ -97,7 +97,10 @@ r#####"
 "#####)
 }}
 "######,
-            assist.id, assist.id, assist.before, assist.after
+            assist.id,
+            assist.id,
+            unhide_lines(&assist.before),
+            unhide_lines(&assist.after)
         );
 
         buf.push_str(&test)
@@ -112,8 +115,8 @@ fn generate_docs(assists: &[Assist], mode: Mode) -> Result<()> {
     );
 
     for assist in assists {
-        let before = assist.before.replace("<|>", "┃"); // Unicode pseudo-graphics bar
-        let after = assist.after.replace("<|>", "┃");
+        let before = hide_lines(&assist.before).replace("<|>", "┃"); // Unicode pseudo-graphics bar
+        let after = hide_lines(&assist.after).replace("<|>", "┃");
         let docs = format!(
             "
 ## `{}`
@@ -135,3 +138,22 @@ fn generate_docs(assists: &[Assist], mode: Mode) -> Result<()> {
 
     codegen::update(&project_root().join(codegen::ASSISTS_DOCS), &buf, mode)
 }
+
+/// Lines starting with `# ` are only needed by the test, like in rustdoc.
+fn hidden_line(line: &str) -> Option<&str> {
+    if line == "#" {
+        Some("")
+    } else if line.starts_with("# ") {
+        Some(&line["# ".len()..])
+    } else {
+        None
+    }
+}
+
+fn unhide_lines(text: &str) -> String {
+    text.split('\n').map(|line| hidden_line(line).unwrap_or(line)).collect::<Vec<_>>().join("\n")
+}
+
+fn hide_lines(text: &str) -> String {
+    text.split('\n').filter(|line| hidden_line(line).is_none()).collect::<Vec<_>>().join("\n")
+}